@@ -0,0 +1,7 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- timestamp of the last checkpoint made during the current client session. 0 if none has been made
+ALTER TABLE status ADD COLUMN last_checkpoint_timestamp INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- the reply surbs are now removed one by one as they're being used
+CREATE INDEX reply_surb_index ON reply_surb (reply_surb);
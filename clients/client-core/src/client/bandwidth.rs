@@ -0,0 +1,69 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::ClientCoreError;
+use futures::channel::{mpsc, oneshot};
+use gateway_client::error::GatewayClientError;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+pub(crate) fn new_request_channels() -> (BandwidthRequestSender, BandwidthRequestReceiver) {
+    let (tx, rx) = mpsc::unbounded();
+    (BandwidthRequestSender(tx), rx)
+}
+
+/// Amount of bandwidth, in bytes, that is still available to this client at its current gateway.
+///
+/// It's updated whenever the gateway tells us about it, i.e. upon authentication or after claiming
+/// more bandwidth, and, if the automatic top up is enabled, decreased locally as we send packets
+/// through the gateway.
+#[derive(Clone, Debug, Default)]
+pub struct RemainingBandwidth {
+    inner: Arc<AtomicI64>,
+}
+
+impl RemainingBandwidth {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn get(&self) -> i64 {
+        self.inner.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set(&self, bandwidth: i64) {
+        self.inner.store(bandwidth, Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BandwidthRequestSender(mpsc::UnboundedSender<BandwidthRequest>);
+
+impl BandwidthRequestSender {
+    /// Attempts to claim more bandwidth at the current gateway, by spending the next unused
+    /// credential from the credential storage or, in the disabled credentials mode, by requesting
+    /// free testnet bandwidth.
+    /// Returns the total amount of bandwidth available afterwards.
+    pub async fn claim_bandwidth(&self) -> Result<i64, ClientCoreError> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .unbounded_send(BandwidthRequest::Claim {
+                response_channel: response_tx,
+            })
+            .map_err(|_| ClientCoreError::MixTrafficControllerNotRunning)?;
+
+        response_rx
+            .await
+            .map_err(|_| ClientCoreError::MixTrafficControllerNotRunning)?
+            .map_err(Into::into)
+    }
+}
+
+pub(crate) type BandwidthRequestReceiver = mpsc::UnboundedReceiver<BandwidthRequest>;
+
+#[derive(Debug)]
+pub(crate) enum BandwidthRequest {
+    Claim {
+        response_channel: oneshot::Sender<Result<i64, GatewayClientError>>,
+    },
+}
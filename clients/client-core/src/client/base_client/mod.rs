@@ -1,7 +1,12 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::bandwidth;
+use crate::client::bandwidth::{
+    BandwidthRequestReceiver, BandwidthRequestSender, RemainingBandwidth,
+};
 use crate::client::cover_traffic_stream::LoopCoverTrafficStream;
+use crate::client::gateway_failover::{GatewayClientConnector, GatewayFailover};
 use crate::client::inbound_messages::{InputMessage, InputMessageReceiver, InputMessageSender};
 use crate::client::key_manager::KeyManager;
 use crate::client::mix_traffic::{BatchMixMessageSender, MixTrafficController};
@@ -15,29 +20,32 @@ use crate::client::replies::reply_controller::{ReplyControllerReceiver, ReplyCon
 use crate::client::replies::reply_storage::{
     CombinedReplyStorage, PersistentReplyStorage, ReplyStorageBackend, SentReplyKeys,
 };
+use crate::client::self_address::SelfAddress;
 use crate::client::topology_control::{
-    TopologyAccessor, TopologyRefresher, TopologyRefresherConfig,
+    NymApiTopologyProvider, TopologyAccessor, TopologyProvider, TopologyRefresher,
+    TopologyRefresherConfig,
 };
+use crate::config::persistence::key_pathfinder::ClientKeyPathfinder;
 use crate::config::{Config, DebugConfig, GatewayEndpointConfig};
 use crate::error::ClientCoreError;
 use crate::spawn_future;
 use client_connections::{ConnectionCommandReceiver, ConnectionCommandSender, LaneQueueLengths};
-use crypto::asymmetric::{encryption, identity};
+use crypto::asymmetric::encryption;
 use futures::channel::mpsc;
 use gateway_client::bandwidth::BandwidthController;
 use gateway_client::{
     AcknowledgementReceiver, AcknowledgementSender, GatewayClient, MixnetMessageReceiver,
     MixnetMessageSender,
 };
-use log::{debug, info};
+use log::{debug, error, info};
 use nymsphinx::acknowledgements::AckKey;
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::addressing::nodes::NodeIdentity;
 use nymsphinx::receiver::ReconstructedMessage;
 use std::sync::Arc;
 use std::time::Duration;
-use tap::TapFallible;
 use task::{TaskClient, TaskManager};
+use topology::route_selection::RouteSelectionPolicy;
 use url::Url;
 
 use super::received_buffer::ReceivedBufferMessage;
@@ -73,6 +81,9 @@ impl ClientOutput {
 pub struct ClientState {
     pub shared_lane_queue_lengths: LaneQueueLengths,
     pub reply_controller_sender: ReplyControllerSender,
+    pub self_address: SelfAddress,
+    pub remaining_bandwidth: RemainingBandwidth,
+    pub bandwidth_request_sender: BandwidthRequestSender,
 }
 
 pub enum ClientInputStatus {
@@ -132,13 +143,18 @@ impl From<bool> for CredentialsToggle {
 pub struct BaseClientBuilder<'a, B> {
     // due to wasm limitations I had to split it like this : (
     gateway_config: &'a GatewayEndpointConfig,
+    standby_gateways: Vec<GatewayEndpointConfig>,
     debug_config: &'a DebugConfig,
     disabled_credentials: bool,
     nym_api_endpoints: Vec<Url>,
     reply_storage_backend: B,
+    custom_topology_provider: Option<Box<dyn TopologyProvider>>,
+    route_selection_policy: RouteSelectionPolicy,
 
     bandwidth_controller: Option<BandwidthController>,
+    automatic_bandwidth_top_up: bool,
     key_manager: KeyManager,
+    key_pathfinder: Option<ClientKeyPathfinder>,
 }
 
 impl<'a, B> BaseClientBuilder<'a, B>
@@ -153,12 +169,20 @@ where
     ) -> BaseClientBuilder<'a, B> {
         BaseClientBuilder {
             gateway_config: base_config.get_gateway_endpoint_config(),
+            standby_gateways: base_config.get_standby_gateway_endpoints().to_vec(),
             debug_config: base_config.get_debug_config(),
             disabled_credentials: base_config.get_disabled_credentials_mode(),
             nym_api_endpoints: base_config.get_nym_api_endpoints(),
             bandwidth_controller,
+            automatic_bandwidth_top_up: false,
             reply_storage_backend,
+            custom_topology_provider: None,
+            route_selection_policy: RouteSelectionPolicy {
+                denied_nodes: Self::parse_denied_mixnodes(base_config.get_denied_mixnodes()),
+                ..Default::default()
+            },
             key_manager,
+            key_pathfinder: Some(ClientKeyPathfinder::new_from_config(base_config)),
         }
     }
 
@@ -173,15 +197,71 @@ where
     ) -> BaseClientBuilder<'a, B> {
         BaseClientBuilder {
             gateway_config,
+            standby_gateways: Vec::new(),
             debug_config,
             disabled_credentials: credentials_toggle.is_disabled(),
             nym_api_endpoints,
             reply_storage_backend,
+            custom_topology_provider: None,
+            route_selection_policy: Default::default(),
             bandwidth_controller,
+            automatic_bandwidth_top_up: false,
             key_manager,
+            key_pathfinder: None,
         }
     }
 
+    fn parse_denied_mixnodes(denied_mixnodes: &[String]) -> Vec<NodeIdentity> {
+        denied_mixnodes
+            .iter()
+            .filter_map(|identity| match NodeIdentity::from_base58_string(identity) {
+                Ok(identity) => Some(identity),
+                Err(err) => {
+                    error!("'{identity}' is not a valid mixnode identity to deny - {err}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Specifies where the keys of the client are stored, so that the keys derived with the standby
+    /// gateways could be persisted alongside them.
+    pub fn with_key_pathfinder(mut self, key_pathfinder: ClientKeyPathfinder) -> Self {
+        self.key_pathfinder = Some(key_pathfinder);
+        self
+    }
+
+    /// Makes the client keep track of the bandwidth it uses at the gateway and claim more of it,
+    /// with the bandwidth controller, as soon as it runs out. Otherwise, the bandwidth is only
+    /// claimed upon explicit requests.
+    pub fn with_automatic_bandwidth_top_up(mut self, automatic_bandwidth_top_up: bool) -> Self {
+        self.automatic_bandwidth_top_up = automatic_bandwidth_top_up;
+        self
+    }
+
+    /// Specifies gateways the client is going to fail over to, in the provided order,
+    /// if the primary one becomes unavailable.
+    pub fn with_standby_gateways(mut self, standby_gateways: Vec<GatewayEndpointConfig>) -> Self {
+        self.standby_gateways = standby_gateways;
+        self
+    }
+
+    /// Specifies the source of the network topology to use instead of querying the nym-api.
+    pub fn with_topology_provider(mut self, topology_provider: Box<dyn TopologyProvider>) -> Self {
+        self.custom_topology_provider = Some(topology_provider);
+        self
+    }
+
+    /// Specifies the rules for choosing the mixnodes of all the routes, of both the real and
+    /// the cover traffic. It replaces any policy set on the topology by the topology provider.
+    pub fn with_route_selection_policy(
+        mut self,
+        route_selection_policy: RouteSelectionPolicy,
+    ) -> Self {
+        self.route_selection_policy = route_selection_policy;
+        self
+    }
+
     pub fn as_mix_recipient(&self) -> Recipient {
         Recipient::new(
             *self.key_manager.identity_keypair().public_key(),
@@ -197,7 +277,7 @@ where
     fn start_cover_traffic_stream(
         debug_config: &DebugConfig,
         ack_key: Arc<AckKey>,
-        self_address: Recipient,
+        self_address: SelfAddress,
         topology_accessor: TopologyAccessor,
         mix_tx: BatchMixMessageSender,
         shutdown: TaskClient,
@@ -278,70 +358,62 @@ where
         &mut self,
         mixnet_message_sender: MixnetMessageSender,
         ack_sender: AcknowledgementSender,
+        self_address: SelfAddress,
+        reply_controller_sender: ReplyControllerSender,
+        topology_accessor: TopologyAccessor,
         shutdown: TaskClient,
-    ) -> Result<GatewayClient, ClientCoreError> {
-        let gateway_id = self.gateway_config.gateway_id.clone();
-        if gateway_id.is_empty() {
-            return Err(ClientCoreError::GatewayIdUnknown);
-        }
-        let gateway_owner = self.gateway_config.gateway_owner.clone();
-        if gateway_owner.is_empty() {
-            return Err(ClientCoreError::GatewayOwnerUnknown);
-        }
-        let gateway_address = self.gateway_config.gateway_listener.clone();
-        if gateway_address.is_empty() {
-            return Err(ClientCoreError::GatwayAddressUnknown);
-        }
-
-        let gateway_identity = identity::PublicKey::from_base58_string(gateway_id)
-            .map_err(ClientCoreError::UnableToCreatePublicKeyFromGatewayId)?;
-
+    ) -> Result<(GatewayClient, GatewayFailover), ClientCoreError> {
         // disgusting wasm workaround since there's no key persistence there (nor `client init`)
-        let shared_key = if self.key_manager.is_gateway_key_set() {
-            Some(self.key_manager.gateway_shared_key())
-        } else {
-            None
-        };
+        let mut shared_keys = self.key_manager.standby_gateway_shared_keys();
+        if self.key_manager.is_gateway_key_set() {
+            shared_keys.insert(
+                self.gateway_config.gateway_id.clone(),
+                self.key_manager.gateway_shared_key(),
+            );
+        }
 
-        let mut gateway_client = GatewayClient::new(
-            gateway_address,
+        let connector = GatewayClientConnector::new(
+            shared_keys,
+            self.gateway_config.gateway_id.clone(),
+            self.key_pathfinder.take(),
             self.key_manager.identity_keypair(),
-            gateway_identity,
-            gateway_owner,
-            shared_key,
             mixnet_message_sender,
             ack_sender,
             self.debug_config.gateway_response_timeout,
+            self.disabled_credentials,
             self.bandwidth_controller.take(),
+            self.automatic_bandwidth_top_up,
             shutdown,
         );
+        let mut gateway_failover = GatewayFailover::new(
+            self.gateway_config.clone(),
+            std::mem::take(&mut self.standby_gateways),
+            connector,
+            self_address,
+            reply_controller_sender,
+            topology_accessor,
+        );
 
-        gateway_client.set_disabled_credentials_mode(self.disabled_credentials);
-
-        gateway_client
-            .authenticate_and_start()
-            .await
-            .tap_err(|err| {
-                log::error!("Could not authenticate and start up the gateway connection - {err}")
-            })?;
-        Ok(gateway_client)
+        let gateway_client = gateway_failover.connect_initial().await?;
+        Ok((gateway_client, gateway_failover))
     }
 
     // future responsible for periodically polling directory server and updating
     // the current global view of topology
     async fn start_topology_refresher(
-        nym_api_urls: Vec<Url>,
+        topology_provider: Box<dyn TopologyProvider>,
         refresh_rate: Duration,
+        route_selection_policy: RouteSelectionPolicy,
         topology_accessor: TopologyAccessor,
         shutdown: TaskClient,
     ) -> Result<(), ClientCoreError> {
-        let topology_refresher_config = TopologyRefresherConfig::new(
-            nym_api_urls,
-            refresh_rate,
-            env!("CARGO_PKG_VERSION").to_string(),
+        let topology_refresher_config =
+            TopologyRefresherConfig::new(refresh_rate, route_selection_policy);
+        let mut topology_refresher = TopologyRefresher::new(
+            topology_refresher_config,
+            topology_accessor,
+            topology_provider,
         );
-        let mut topology_refresher =
-            TopologyRefresher::new(topology_refresher_config, topology_accessor);
         // before returning, block entire runtime to refresh the current network view so that any
         // components depending on topology would see a non-empty view
         info!("Obtaining initial network topology");
@@ -366,22 +438,34 @@ where
     // requests?
     fn start_mix_traffic_controller(
         gateway_client: GatewayClient,
+        gateway_failover: GatewayFailover,
+        bandwidth_request_receiver: BandwidthRequestReceiver,
+        remaining_bandwidth: RemainingBandwidth,
+        automatic_bandwidth_top_up: bool,
         shutdown: TaskClient,
     ) -> BatchMixMessageSender {
         info!("Starting mix traffic controller...");
-        let (mix_traffic_controller, mix_tx) = MixTrafficController::new(gateway_client);
+        let (mix_traffic_controller, mix_tx) = MixTrafficController::new(
+            gateway_client,
+            gateway_failover,
+            bandwidth_request_receiver,
+            remaining_bandwidth,
+            automatic_bandwidth_top_up,
+        );
         mix_traffic_controller.start_with_shutdown(shutdown);
         mix_tx
     }
 
     async fn setup_persistent_reply_storage(
         backend: B,
+        checkpoint_interval: Duration,
         shutdown: TaskClient,
     ) -> Result<CombinedReplyStorage, ClientCoreError>
     where
         <B as ReplyStorageBackend>::StorageError: Sync + Send,
     {
-        let persistent_storage = PersistentReplyStorage::new(backend);
+        let persistent_storage =
+            PersistentReplyStorage::new(backend).with_checkpoint_interval(checkpoint_interval);
         let mem_store = persistent_storage
             .load_state_from_backend()
             .await
@@ -430,23 +514,47 @@ where
         let (reply_controller_sender, reply_controller_receiver) =
             reply_controller::requests::new_control_channels();
 
-        let self_address = self.as_mix_recipient();
+        // note: the gateway part of our address might change if we fail over to a standby gateway
+        let self_address = SelfAddress::new(self.as_mix_recipient());
+
+        // channels for checking and topping up the bandwidth we have at the gateway
+        let (bandwidth_request_sender, bandwidth_request_receiver) =
+            bandwidth::new_request_channels();
+        let remaining_bandwidth = RemainingBandwidth::new();
 
         // the components are started in very specific order. Unless you know what you are doing,
         // do not change that.
-        let gateway_client = self
-            .start_gateway_client(mixnet_messages_sender, ack_sender, task_manager.subscribe())
+        let (gateway_client, gateway_failover) = self
+            .start_gateway_client(
+                mixnet_messages_sender,
+                ack_sender,
+                self_address.clone(),
+                reply_controller_sender.clone(),
+                shared_topology_accessor.clone(),
+                task_manager.subscribe(),
+            )
             .await?;
 
         let reply_storage = Self::setup_persistent_reply_storage(
             self.reply_storage_backend,
+            self.debug_config.reply_surb_storage_checkpoint_interval,
             task_manager.subscribe(),
         )
         .await?;
 
+        let topology_provider = self.custom_topology_provider.take().unwrap_or_else(|| {
+            let provider = NymApiTopologyProvider::new(
+                self.nym_api_endpoints.clone(),
+                env!("CARGO_PKG_VERSION").to_string(),
+            )
+            .with_node_annotations(self.route_selection_policy.needs_node_annotations())
+            .with_rotated_sphinx_keys(self.debug_config.use_rotated_sphinx_keys);
+            Box::new(provider)
+        });
         Self::start_topology_refresher(
-            self.nym_api_endpoints.clone(),
+            topology_provider,
             self.debug_config.topology_refresh_rate,
+            self.route_selection_policy.clone(),
             shared_topology_accessor.clone(),
             task_manager.subscribe(),
         )
@@ -465,8 +573,14 @@ where
         // that are to be sent to the mixnet. They are used by cover traffic stream and real
         // traffic stream.
         // The MixTrafficController then sends the actual traffic
-        let sphinx_message_sender =
-            Self::start_mix_traffic_controller(gateway_client, task_manager.subscribe());
+        let sphinx_message_sender = Self::start_mix_traffic_controller(
+            gateway_client,
+            gateway_failover,
+            bandwidth_request_receiver,
+            remaining_bandwidth.clone(),
+            self.automatic_bandwidth_top_up,
+            task_manager.subscribe(),
+        );
 
         // Channels that the websocket listener can use to signal downstream to the real traffic
         // controller that connections are closed.
@@ -479,7 +593,7 @@ where
         let mut controller_config = real_messages_control::Config::new(
             self.debug_config,
             self.key_manager.ack_key(),
-            self_address,
+            self_address.clone(),
         );
 
         if let Some(size) = self.debug_config.use_extended_packet_size {
@@ -505,7 +619,7 @@ where
             Self::start_cover_traffic_stream(
                 self.debug_config,
                 self.key_manager.ack_key(),
-                self_address,
+                self_address.clone(),
                 shared_topology_accessor,
                 sphinx_message_sender,
                 task_manager.subscribe(),
@@ -513,7 +627,7 @@ where
         }
 
         debug!("Core client startup finished!");
-        debug!("The address of this client is: {self_address}");
+        debug!("The address of this client is: {}", self_address.get());
 
         Ok(BaseClient {
             client_input: ClientInputStatus::AwaitingProducer {
@@ -530,6 +644,9 @@ where
             client_state: ClientState {
                 shared_lane_queue_lengths,
                 reply_controller_sender,
+                self_address,
+                remaining_bandwidth,
+                bandwidth_request_sender,
             },
             task_manager,
         })
@@ -0,0 +1,595 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::key_manager::KeyManager;
+use crate::client::replies::reply_controller::ReplyControllerSender;
+use crate::client::self_address::SelfAddress;
+use crate::client::topology_control::TopologyAccessor;
+use crate::config::persistence::key_pathfinder::ClientKeyPathfinder;
+use crate::config::GatewayEndpointConfig;
+use crate::error::ClientCoreError;
+use async_trait::async_trait;
+use crypto::asymmetric::identity;
+use gateway_client::bandwidth::BandwidthController;
+use gateway_client::{AcknowledgementSender, GatewayClient, MixnetMessageSender};
+use gateway_requests::registration::handshake::SharedKeys;
+use log::*;
+use nymsphinx::addressing::clients::Recipient;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use task::TaskClient;
+
+fn parse_gateway_identity(
+    gateway_config: &GatewayEndpointConfig,
+) -> Result<identity::PublicKey, ClientCoreError> {
+    if gateway_config.gateway_id.is_empty() {
+        return Err(ClientCoreError::GatewayIdUnknown);
+    }
+    if gateway_config.gateway_owner.is_empty() {
+        return Err(ClientCoreError::GatewayOwnerUnknown);
+    }
+    if gateway_config.gateway_listener.is_empty() {
+        return Err(ClientCoreError::GatwayAddressUnknown);
+    }
+
+    identity::PublicKey::from_base58_string(&gateway_config.gateway_id)
+        .map_err(ClientCoreError::UnableToCreatePublicKeyFromGatewayId)
+}
+
+/// Establishes the actual connection with the gateway chosen by the [`GatewayFailover`].
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait GatewayConnector {
+    type Connection;
+
+    async fn connect(
+        &mut self,
+        gateway_config: &GatewayEndpointConfig,
+        gateway_identity: identity::PublicKey,
+    ) -> Result<Self::Connection, ClientCoreError>;
+}
+
+/// Connects to the gateways by authenticating (and, if required, registering) with them.
+pub struct GatewayClientConnector {
+    /// Shared keys derived with the gateways we have already registered with.
+    shared_keys: HashMap<String, Arc<SharedKeys>>,
+
+    /// Identity of the primary gateway whose key is managed by the client initialisation.
+    primary_gateway_id: String,
+
+    /// If specified, keys derived with the standby gateways are persisted alongside the key of
+    /// the primary gateway, so that the client would not have to register with them again.
+    key_pathfinder: Option<ClientKeyPathfinder>,
+
+    local_identity: Arc<identity::KeyPair>,
+    mixnet_message_sender: MixnetMessageSender,
+    ack_sender: AcknowledgementSender,
+    response_timeout: Duration,
+    disabled_credentials: bool,
+    bandwidth_controller: Option<BandwidthController>,
+    track_bandwidth_locally: bool,
+    shutdown: TaskClient,
+}
+
+impl GatewayClientConnector {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        shared_keys: HashMap<String, Arc<SharedKeys>>,
+        primary_gateway_id: String,
+        key_pathfinder: Option<ClientKeyPathfinder>,
+        local_identity: Arc<identity::KeyPair>,
+        mixnet_message_sender: MixnetMessageSender,
+        ack_sender: AcknowledgementSender,
+        response_timeout: Duration,
+        disabled_credentials: bool,
+        bandwidth_controller: Option<BandwidthController>,
+        track_bandwidth_locally: bool,
+        shutdown: TaskClient,
+    ) -> Self {
+        GatewayClientConnector {
+            shared_keys,
+            primary_gateway_id,
+            key_pathfinder,
+            local_identity,
+            mixnet_message_sender,
+            ack_sender,
+            response_timeout,
+            disabled_credentials,
+            bandwidth_controller,
+            track_bandwidth_locally,
+            shutdown,
+        }
+    }
+
+    fn persist_standby_key(&self, gateway_id: &str, shared_key: &SharedKeys) {
+        if gateway_id == self.primary_gateway_id {
+            return;
+        }
+        let Some(key_pathfinder) = &self.key_pathfinder else {
+            return;
+        };
+        if let Err(err) =
+            KeyManager::store_standby_gateway_key(key_pathfinder, gateway_id, shared_key)
+        {
+            warn!("Failed to store the shared key of standby gateway {gateway_id} - {err}. We will have to register with it again after restart")
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GatewayConnector for GatewayClientConnector {
+    type Connection = GatewayClient;
+
+    async fn connect(
+        &mut self,
+        gateway_config: &GatewayEndpointConfig,
+        gateway_identity: identity::PublicKey,
+    ) -> Result<GatewayClient, ClientCoreError> {
+        let mut gateway_client = GatewayClient::new(
+            gateway_config.gateway_listener.clone(),
+            Arc::clone(&self.local_identity),
+            gateway_identity,
+            gateway_config.gateway_owner.clone(),
+            self.shared_keys.get(&gateway_config.gateway_id).cloned(),
+            self.mixnet_message_sender.clone(),
+            self.ack_sender.clone(),
+            self.response_timeout,
+            self.bandwidth_controller.clone(),
+            self.shutdown.clone(),
+        );
+
+        gateway_client.set_disabled_credentials_mode(self.disabled_credentials);
+        gateway_client.with_local_bandwidth_tracking(self.track_bandwidth_locally);
+
+        // if we have never registered with this gateway, this is going to happen now
+        let shared_key = gateway_client.authenticate_and_start().await?;
+        let previous = self
+            .shared_keys
+            .insert(gateway_config.gateway_id.clone(), Arc::clone(&shared_key));
+        if previous.is_none() {
+            self.persist_standby_key(&gateway_config.gateway_id, &shared_key);
+        }
+
+        Ok(gateway_client)
+    }
+}
+
+/// Keeps track of the primary and standby gateways of the client and is responsible for
+/// (re)establishing the connection with one of them.
+///
+/// The client registers with a standby gateway the first time it fails over to it. Once the
+/// connection is established, the gateway part of our address is updated and all the peers that
+/// hold our reply SURBs are sent replacement ones, so that they could keep on replying to us.
+pub struct GatewayFailover<C = GatewayClientConnector> {
+    /// All known gateways, with the one we're currently connected to (or attempting to connect to)
+    /// always being at the front.
+    gateways: VecDeque<GatewayEndpointConfig>,
+
+    connector: C,
+
+    self_address: SelfAddress,
+    reply_controller_sender: ReplyControllerSender,
+    topology_accessor: TopologyAccessor,
+}
+
+impl<C: GatewayConnector> GatewayFailover<C> {
+    pub(crate) fn new(
+        primary_gateway: GatewayEndpointConfig,
+        standby_gateways: Vec<GatewayEndpointConfig>,
+        connector: C,
+        self_address: SelfAddress,
+        reply_controller_sender: ReplyControllerSender,
+        topology_accessor: TopologyAccessor,
+    ) -> Self {
+        let mut gateways = VecDeque::with_capacity(standby_gateways.len() + 1);
+        gateways.push_back(primary_gateway);
+        gateways.extend(standby_gateways);
+
+        GatewayFailover {
+            gateways,
+            connector,
+            self_address,
+            reply_controller_sender,
+            topology_accessor,
+        }
+    }
+
+    pub(crate) fn has_standby_gateways(&self) -> bool {
+        self.gateways.len() > 1
+    }
+
+    /// Identity of the gateway we're currently using.
+    pub(crate) fn current_gateway(&self) -> identity::PublicKey {
+        *self.self_address.get().gateway()
+    }
+
+    async fn connect(
+        &mut self,
+        gateway_config: &GatewayEndpointConfig,
+    ) -> Result<(C::Connection, identity::PublicKey), ClientCoreError> {
+        let gateway_identity = parse_gateway_identity(gateway_config)?;
+        let connection = self
+            .connector
+            .connect(gateway_config, gateway_identity)
+            .await?;
+        Ok((connection, gateway_identity))
+    }
+
+    /// Attempts to connect to the primary gateway and, if that fails, to each standby gateway
+    /// in the order they were specified.
+    /// If the primary gateway could not be used, our address gets updated accordingly.
+    pub(crate) async fn connect_initial(&mut self) -> Result<C::Connection, ClientCoreError> {
+        let mut primary_err = None;
+
+        for _ in 0..self.gateways.len() {
+            let candidate = self.gateways[0].clone();
+            match self.connect(&candidate).await {
+                Ok((connection, gateway_identity)) => {
+                    if primary_err.is_some() {
+                        warn!(
+                            "Using standby gateway {} instead of the primary one",
+                            candidate.gateway_id
+                        );
+                        self.update_address(gateway_identity);
+                    }
+                    return Ok(connection);
+                }
+                Err(err) => {
+                    error!(
+                        "Could not authenticate and start up the gateway connection with {} - {err}",
+                        candidate.gateway_id
+                    );
+                    primary_err.get_or_insert(err);
+                    self.gateways.rotate_left(1);
+                }
+            }
+        }
+
+        // we have tried all of them, so the primary gateway is at the front again
+        Err(primary_err.unwrap_or(ClientCoreError::GatewayIdUnknown))
+    }
+
+    /// Abandons the current gateway and attempts to connect to any other one, preferring those
+    /// that are present in the current network topology.
+    /// On success our address gets updated and announced to all peers holding our reply SURBs.
+    pub(crate) async fn fail_over(&mut self) -> Option<C::Connection> {
+        let failed = self.gateways[0].gateway_id.clone();
+        self.gateways.rotate_left(1);
+
+        let candidates = self.gateways.len() - 1;
+        for attempt in 0..candidates {
+            let candidate = self.gateways[0].clone();
+
+            // skip gateways that are not in the topology, unless it's our last option
+            if attempt + 1 < candidates && !self.is_in_topology(&candidate).await {
+                debug!(
+                    "Standby gateway {} is not present in the network topology",
+                    candidate.gateway_id
+                );
+                self.gateways.rotate_left(1);
+                continue;
+            }
+
+            info!(
+                "Attempting to fail over from gateway {failed} to {}",
+                candidate.gateway_id
+            );
+            match self.connect(&candidate).await {
+                Ok((connection, gateway_identity)) => {
+                    info!("Failed over to gateway {}", candidate.gateway_id);
+                    self.update_address(gateway_identity);
+                    self.announce_address_change();
+                    return Some(connection);
+                }
+                Err(err) => {
+                    warn!(
+                        "Could not fail over to gateway {} - {err}",
+                        candidate.gateway_id
+                    );
+                    self.gateways.rotate_left(1);
+                }
+            }
+        }
+
+        // none of the standbys worked and we went through the whole queue,
+        // so the failed gateway is at the front again
+        error!("Could not fail over to any of the standby gateways");
+        None
+    }
+
+    async fn is_in_topology(&self, gateway_config: &GatewayEndpointConfig) -> bool {
+        let Ok(gateway_identity) = parse_gateway_identity(gateway_config) else {
+            return false;
+        };
+        match self.topology_accessor.get_read_permit().await.as_ref() {
+            Some(topology) => topology.gateway_exists(&gateway_identity),
+            // if we don't know anything about the network, don't treat it as missing
+            None => true,
+        }
+    }
+
+    /// Checks whether our current gateway has disappeared from the network topology.
+    /// If we don't have a valid topology or it doesn't contain any gateways, we can't tell,
+    /// so it's assumed to still be there.
+    pub(crate) async fn is_current_gateway_gone(&self) -> bool {
+        let current = self.current_gateway();
+        match self.topology_accessor.get_read_permit().await.as_ref() {
+            Some(topology) if !topology.gateways().is_empty() => !topology.gateway_exists(&current),
+            _ => false,
+        }
+    }
+
+    fn update_address(&self, gateway_identity: identity::PublicKey) {
+        let old_address = self.self_address.get();
+        let new_address = Recipient::new(
+            *old_address.identity(),
+            *old_address.encryption_key(),
+            gateway_identity,
+        );
+        info!("The address of this client is now: {new_address}");
+        self.self_address.set(new_address);
+    }
+
+    fn announce_address_change(&self) {
+        self.reply_controller_sender
+            .send_address_change_announcement();
+    }
+}
+
+impl GatewayFailover {
+    /// Closes the connection with the gateway we're abandoning. Any failures are irrelevant
+    /// since we're not going to use it anymore.
+    pub(crate) async fn close_connection(gateway_client: &mut GatewayClient) {
+        if let Err(err) = gateway_client.close_connection().await {
+            debug!("Failed to cleanly close the old gateway connection - {err}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::replies::reply_controller::requests::new_control_channels;
+    use crate::client::replies::reply_controller::{
+        ReplyControllerMessage, ReplyControllerReceiver,
+    };
+    use crypto::asymmetric::encryption;
+    use topology::{gateway, NymTopology};
+
+    // pretends to connect to the gateways, failing for all the unreachable ones
+    #[derive(Default)]
+    struct MockConnector {
+        unreachable: Vec<String>,
+        attempts: Vec<String>,
+    }
+
+    #[async_trait]
+    impl GatewayConnector for MockConnector {
+        type Connection = identity::PublicKey;
+
+        async fn connect(
+            &mut self,
+            gateway_config: &GatewayEndpointConfig,
+            gateway_identity: identity::PublicKey,
+        ) -> Result<identity::PublicKey, ClientCoreError> {
+            self.attempts.push(gateway_config.gateway_id.clone());
+            if self.unreachable.contains(&gateway_config.gateway_id) {
+                Err(ClientCoreError::FailedToSetupGateway)
+            } else {
+                Ok(gateway_identity)
+            }
+        }
+    }
+
+    fn gateway_fixture() -> (GatewayEndpointConfig, identity::PublicKey) {
+        let identity = *identity::KeyPair::new(&mut rand::rngs::OsRng).public_key();
+        let config = GatewayEndpointConfig {
+            gateway_id: identity.to_base58_string(),
+            gateway_owner: "n1owner".to_string(),
+            gateway_listener: "ws://1.2.3.4:9000".to_string(),
+        };
+        (config, identity)
+    }
+
+    fn topology_node(identity_key: identity::PublicKey) -> gateway::Node {
+        gateway::Node {
+            owner: "n1owner".to_string(),
+            stake: 123,
+            location: "unknown".to_string(),
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            clients_port: 9000,
+            identity_key,
+            sphinx_key: *encryption::KeyPair::new(&mut rand::rngs::OsRng).public_key(),
+            sphinx_key_epoch: None,
+            version: "1.1.0".to_string(),
+        }
+    }
+
+    struct TestSetup {
+        gateway_failover: GatewayFailover<MockConnector>,
+        gateways: Vec<identity::PublicKey>,
+        self_address: SelfAddress,
+        topology_accessor: TopologyAccessor,
+        reply_controller_receiver: ReplyControllerReceiver,
+    }
+
+    fn setup(num_gateways: usize, unreachable: &[usize]) -> TestSetup {
+        let (configs, gateways): (Vec<_>, Vec<_>) =
+            (0..num_gateways).map(|_| gateway_fixture()).unzip();
+        let connector = MockConnector {
+            unreachable: unreachable
+                .iter()
+                .map(|&i| configs[i].gateway_id.clone())
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut rng = rand::rngs::OsRng;
+        let self_address = SelfAddress::new(Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            gateways[0],
+        ));
+        let topology_accessor = TopologyAccessor::new();
+        let (reply_controller_sender, reply_controller_receiver) = new_control_channels();
+
+        let mut configs = configs.into_iter();
+        let gateway_failover = GatewayFailover::new(
+            configs.next().unwrap(),
+            configs.collect(),
+            connector,
+            self_address.clone(),
+            reply_controller_sender,
+            topology_accessor.clone(),
+        );
+
+        TestSetup {
+            gateway_failover,
+            gateways,
+            self_address,
+            topology_accessor,
+            reply_controller_receiver,
+        }
+    }
+
+    fn attempted(setup: &TestSetup) -> Vec<identity::PublicKey> {
+        setup
+            .gateway_failover
+            .connector
+            .attempts
+            .iter()
+            .map(|id| identity::PublicKey::from_base58_string(id).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn initial_connection_uses_primary_gateway() {
+        let mut setup = setup(3, &[]);
+        let connected = setup.gateway_failover.connect_initial().await.unwrap();
+
+        assert_eq!(connected, setup.gateways[0]);
+        assert_eq!(attempted(&setup), vec![setup.gateways[0]]);
+        assert_eq!(*setup.self_address.get().gateway(), setup.gateways[0]);
+        assert!(setup.reply_controller_receiver.try_next().is_err());
+    }
+
+    #[tokio::test]
+    async fn initial_connection_falls_back_to_standby_gateways_in_order() {
+        let mut setup = setup(4, &[0, 1]);
+        let connected = setup.gateway_failover.connect_initial().await.unwrap();
+
+        assert_eq!(connected, setup.gateways[2]);
+        assert_eq!(
+            attempted(&setup),
+            vec![setup.gateways[0], setup.gateways[1], setup.gateways[2]]
+        );
+        assert_eq!(*setup.self_address.get().gateway(), setup.gateways[2]);
+        assert_eq!(setup.gateway_failover.current_gateway(), setup.gateways[2]);
+        // nobody holds our reply surbs yet, so there's nothing to announce
+        assert!(setup.reply_controller_receiver.try_next().is_err());
+    }
+
+    #[tokio::test]
+    async fn initial_connection_returns_primary_error_if_all_gateways_fail() {
+        let mut setup = setup(3, &[0, 1, 2]);
+        let err = setup.gateway_failover.connect_initial().await.unwrap_err();
+
+        assert!(matches!(err, ClientCoreError::FailedToSetupGateway));
+        assert_eq!(attempted(&setup), setup.gateways);
+        assert_eq!(*setup.self_address.get().gateway(), setup.gateways[0]);
+        // the primary gateway is back at the front
+        assert_eq!(
+            setup.gateway_failover.gateways[0].gateway_id,
+            setup.gateways[0].to_base58_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_over_updates_and_announces_the_address() {
+        let mut setup = setup(3, &[]);
+        setup.gateway_failover.connect_initial().await.unwrap();
+
+        let connected = setup.gateway_failover.fail_over().await.unwrap();
+        assert_eq!(connected, setup.gateways[1]);
+        assert_eq!(*setup.self_address.get().gateway(), setup.gateways[1]);
+        assert!(matches!(
+            setup.reply_controller_receiver.try_next(),
+            Ok(Some(ReplyControllerMessage::AnnounceAddressChange))
+        ));
+    }
+
+    #[tokio::test]
+    async fn failing_over_skips_unreachable_gateways() {
+        let mut setup = setup(4, &[1]);
+        setup.gateway_failover.connect_initial().await.unwrap();
+
+        let connected = setup.gateway_failover.fail_over().await.unwrap();
+        assert_eq!(connected, setup.gateways[2]);
+        assert_eq!(
+            attempted(&setup),
+            vec![setup.gateways[0], setup.gateways[1], setup.gateways[2]]
+        );
+        assert_eq!(*setup.self_address.get().gateway(), setup.gateways[2]);
+    }
+
+    #[tokio::test]
+    async fn failing_over_prefers_gateways_present_in_topology() {
+        let mut setup = setup(4, &[]);
+        setup
+            .topology_accessor
+            .update_global_topology(Some(NymTopology::new(
+                Default::default(),
+                vec![
+                    topology_node(setup.gateways[0]),
+                    topology_node(setup.gateways[2]),
+                ],
+            )))
+            .await;
+        setup.gateway_failover.connect_initial().await.unwrap();
+
+        let connected = setup.gateway_failover.fail_over().await.unwrap();
+        assert_eq!(connected, setup.gateways[2]);
+        assert_eq!(
+            attempted(&setup),
+            vec![setup.gateways[0], setup.gateways[2]]
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_over_uses_last_gateway_even_if_not_in_topology() {
+        let mut setup = setup(3, &[]);
+        setup
+            .topology_accessor
+            .update_global_topology(Some(NymTopology::new(
+                Default::default(),
+                vec![topology_node(setup.gateways[0])],
+            )))
+            .await;
+        setup.gateway_failover.connect_initial().await.unwrap();
+
+        let connected = setup.gateway_failover.fail_over().await.unwrap();
+        assert_eq!(connected, setup.gateways[2]);
+        assert_eq!(
+            attempted(&setup),
+            vec![setup.gateways[0], setup.gateways[2]]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_fail_over_keeps_the_current_address() {
+        let mut setup = setup(3, &[1, 2]);
+        setup.gateway_failover.connect_initial().await.unwrap();
+
+        assert!(setup.gateway_failover.fail_over().await.is_none());
+        assert_eq!(*setup.self_address.get().gateway(), setup.gateways[0]);
+        assert!(setup.reply_controller_receiver.try_next().is_err());
+        // and the failed gateway is at the front again
+        assert_eq!(
+            setup.gateway_failover.gateways[0].gateway_id,
+            setup.gateways[0].to_base58_string()
+        );
+    }
+}
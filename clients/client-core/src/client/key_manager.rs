@@ -7,6 +7,7 @@ use gateway_requests::registration::handshake::SharedKeys;
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
 use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
 use std::io;
 use std::sync::Arc;
 
@@ -28,6 +29,10 @@ pub struct KeyManager {
     /// shared key derived with the gateway during "registration handshake"
     gateway_shared_key: Option<Arc<SharedKeys>>,
 
+    /// shared keys derived with the standby gateways the client has failed over to,
+    /// keyed by the identities of the gateways.
+    standby_gateway_shared_keys: HashMap<String, Arc<SharedKeys>>,
+
     /// key used for producing and processing acknowledgement packets.
     ack_key: Arc<AckKey>,
 }
@@ -51,6 +56,7 @@ impl KeyManager {
             identity_keypair: Arc::new(identity::KeyPair::new(rng)),
             encryption_keypair: Arc::new(encryption::KeyPair::new(rng)),
             gateway_shared_key: None,
+            standby_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(AckKey::new(rng)),
         }
     }
@@ -65,6 +71,7 @@ impl KeyManager {
             identity_keypair: Arc::new(id_keypair),
             encryption_keypair: Arc::new(enc_keypair),
             gateway_shared_key: Some(Arc::new(gateway_shared_key)),
+            standby_gateway_shared_keys: HashMap::new(),
             ack_key: Arc::new(ack_key),
         }
     }
@@ -88,10 +95,44 @@ impl KeyManager {
             identity_keypair: Arc::new(identity_keypair),
             encryption_keypair: Arc::new(encryption_keypair),
             gateway_shared_key: None,
+            standby_gateway_shared_keys: Self::load_standby_gateway_keys(client_pathfinder)?,
             ack_key: Arc::new(ack_key),
         })
     }
 
+    /// Loads the shared keys of all the standby gateways we have previously registered with.
+    fn load_standby_gateway_keys(
+        client_pathfinder: &ClientKeyPathfinder,
+    ) -> io::Result<HashMap<String, Arc<SharedKeys>>> {
+        let entries = match std::fs::read_dir(client_pathfinder.standby_gateway_shared_keys_dir()) {
+            Ok(entries) => entries,
+            // we have never failed over to any standby gateway
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(err) => return Err(err),
+        };
+
+        let mut keys = HashMap::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+                continue;
+            }
+            let Some(gateway_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            match pemstore::load_key::<SharedKeys>(&path) {
+                Ok(key) => {
+                    keys.insert(gateway_id.to_owned(), Arc::new(key));
+                }
+                // we'll just have to register with that gateway again
+                Err(err) => {
+                    warn!("failed to load the shared key of standby gateway {gateway_id} - {err}")
+                }
+            }
+        }
+        Ok(keys)
+    }
+
     /// Loads previously stored keys from the disk. Fails if not all, including the shared gateway
     /// key, is available.
     pub fn load_keys(client_pathfinder: &ClientKeyPathfinder) -> io::Result<Self> {
@@ -156,9 +197,25 @@ impl KeyManager {
             }
         }
 
+        for (gateway_id, gate_key) in &self.standby_gateway_shared_keys {
+            Self::store_standby_gateway_key(client_pathfinder, gateway_id, gate_key)?
+        }
+
         Ok(())
     }
 
+    /// Stores the key derived with the standby gateway alongside the shared key of the primary one.
+    pub fn store_standby_gateway_key(
+        client_pathfinder: &ClientKeyPathfinder,
+        gateway_id: &str,
+        gateway_shared_key: &SharedKeys,
+    ) -> io::Result<()> {
+        pemstore::store_key(
+            gateway_shared_key,
+            &client_pathfinder.standby_gateway_shared_key(gateway_id),
+        )
+    }
+
     pub fn store_gateway_key(&self, client_pathfinder: &ClientKeyPathfinder) -> io::Result<()> {
         match self.gateway_shared_key.as_ref() {
             None => {
@@ -224,4 +281,86 @@ impl KeyManager {
     pub fn is_gateway_key_set(&self) -> bool {
         self.gateway_shared_key.is_some()
     }
+
+    /// Puts the shared key derived with one of the standby gateways to this instance of a [`KeyManager`].
+    pub fn insert_standby_gateway_shared_key(
+        &mut self,
+        gateway_id: String,
+        gateway_shared_key: Arc<SharedKeys>,
+    ) {
+        self.standby_gateway_shared_keys
+            .insert(gateway_id, gateway_shared_key);
+    }
+
+    /// Gets the shared keys of all the standby gateways we have registered with.
+    pub fn standby_gateway_shared_keys(&self) -> HashMap<String, Arc<SharedKeys>> {
+        self.standby_gateway_shared_keys.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::generic_array::typenum::Unsigned;
+    use gateway_requests::registration::handshake::SharedKeySize;
+
+    fn shared_keys_fixture(byte: u8) -> Arc<SharedKeys> {
+        Arc::new(SharedKeys::try_from_bytes(&vec![byte; SharedKeySize::to_usize()]).unwrap())
+    }
+
+    fn pathfinder_fixture(dir: &std::path::Path) -> ClientKeyPathfinder {
+        ClientKeyPathfinder {
+            identity_private_key: dir.join("private_identity.pem"),
+            identity_public_key: dir.join("public_identity.pem"),
+            encryption_private_key: dir.join("private_encryption.pem"),
+            encryption_public_key: dir.join("public_encryption.pem"),
+            gateway_shared_key: dir.join("gateway_shared.pem"),
+            ack_key: dir.join("ack_key.pem"),
+        }
+    }
+
+    #[test]
+    fn standby_gateway_keys_are_stored_alongside_the_primary_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let pathfinder = pathfinder_fixture(dir.path());
+
+        let mut key_manager = KeyManager::new(&mut rand::rngs::OsRng);
+        key_manager.insert_gateway_shared_key(shared_keys_fixture(1));
+        key_manager
+            .insert_standby_gateway_shared_key("standby1".to_string(), shared_keys_fixture(2));
+        key_manager.store_keys(&pathfinder).unwrap();
+
+        // and another one derived after failing over while the client was running
+        KeyManager::store_standby_gateway_key(&pathfinder, "standby2", &shared_keys_fixture(3))
+            .unwrap();
+
+        let loaded = KeyManager::load_keys(&pathfinder).unwrap();
+        assert_eq!(
+            loaded.gateway_shared_key().to_bytes(),
+            shared_keys_fixture(1).to_bytes()
+        );
+        let standby_keys = loaded.standby_gateway_shared_keys();
+        assert_eq!(standby_keys.len(), 2);
+        assert_eq!(
+            standby_keys["standby1"].to_bytes(),
+            shared_keys_fixture(2).to_bytes()
+        );
+        assert_eq!(
+            standby_keys["standby2"].to_bytes(),
+            shared_keys_fixture(3).to_bytes()
+        );
+    }
+
+    #[test]
+    fn standby_gateway_keys_are_optional() {
+        let dir = tempfile::tempdir().unwrap();
+        let pathfinder = pathfinder_fixture(dir.path());
+
+        let mut key_manager = KeyManager::new(&mut rand::rngs::OsRng);
+        key_manager.insert_gateway_shared_key(shared_keys_fixture(1));
+        key_manager.store_keys(&pathfinder).unwrap();
+
+        let loaded = KeyManager::load_keys(&pathfinder).unwrap();
+        assert!(loaded.standby_gateway_shared_keys().is_empty());
+    }
 }
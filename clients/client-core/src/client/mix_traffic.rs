@@ -1,10 +1,16 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::bandwidth::{BandwidthRequest, BandwidthRequestReceiver, RemainingBandwidth};
+use crate::client::gateway_failover::GatewayFailover;
+use crate::client::helpers::new_interval_stream;
 use crate::spawn_future;
+use futures::StreamExt;
+use gateway_client::error::GatewayClientError;
 use gateway_client::GatewayClient;
 use log::*;
 use nymsphinx::forwarding::packet::MixPacket;
+use std::time::Duration;
 
 pub type BatchMixMessageSender = tokio::sync::mpsc::Sender<Vec<MixPacket>>;
 pub type BatchMixMessageReceiver = tokio::sync::mpsc::Receiver<Vec<MixPacket>>;
@@ -13,11 +19,21 @@ pub type BatchMixMessageReceiver = tokio::sync::mpsc::Receiver<Vec<MixPacket>>;
 pub const MIX_MESSAGE_RECEIVER_BUFFER_SIZE: usize = 32;
 const MAX_FAILURE_COUNT: usize = 100;
 
+// note that each of those failures already includes all reconnection attempts of the gateway client
+const FAILOVER_FAILURE_COUNT: usize = 3;
+const GATEWAY_PRESENCE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 pub struct MixTrafficController {
     // TODO: most likely to be replaced by some higher level construct as
     // later on gateway_client will need to be accessible by other entities
     gateway_client: GatewayClient,
+    gateway_failover: GatewayFailover,
     mix_rx: BatchMixMessageReceiver,
+    bandwidth_request_receiver: BandwidthRequestReceiver,
+    remaining_bandwidth: RemainingBandwidth,
+
+    /// Specifies whether more bandwidth should be claimed as soon as we run out of it.
+    automatic_bandwidth_top_up: bool,
 
     // TODO: this is temporary work-around.
     // in long run `gateway_client` will be moved away from `MixTrafficController` anyway.
@@ -25,13 +41,24 @@ pub struct MixTrafficController {
 }
 
 impl MixTrafficController {
-    pub fn new(gateway_client: GatewayClient) -> (MixTrafficController, BatchMixMessageSender) {
+    pub fn new(
+        gateway_client: GatewayClient,
+        gateway_failover: GatewayFailover,
+        bandwidth_request_receiver: BandwidthRequestReceiver,
+        remaining_bandwidth: RemainingBandwidth,
+        automatic_bandwidth_top_up: bool,
+    ) -> (MixTrafficController, BatchMixMessageSender) {
         let (sphinx_message_sender, sphinx_message_receiver) =
             tokio::sync::mpsc::channel(MIX_MESSAGE_RECEIVER_BUFFER_SIZE);
+        remaining_bandwidth.set(gateway_client.remaining_bandwidth());
         (
             MixTrafficController {
                 gateway_client,
+                gateway_failover,
                 mix_rx: sphinx_message_receiver,
+                bandwidth_request_receiver,
+                remaining_bandwidth,
+                automatic_bandwidth_top_up,
                 consecutive_gateway_failure_count: 0,
             },
             sphinx_message_sender,
@@ -50,13 +77,30 @@ impl MixTrafficController {
                 .await
         };
 
+        self.remaining_bandwidth
+            .set(self.gateway_client.remaining_bandwidth());
+
         match result {
+            Err(GatewayClientError::NotEnoughBandwidth(required, remaining))
+                if self.automatic_bandwidth_top_up =>
+            {
+                // it's not the gateway's fault, so don't count it towards the failures
+                warn!("Could not send sphinx packet(s) to the gateway, we need {required} bandwidth, but only have {remaining} left - attempting to claim more");
+                if let Err(err) = self.claim_bandwidth().await {
+                    error!("Failed to claim more bandwidth - {err}");
+                }
+            }
             Err(err) => {
                 error!("Failed to send sphinx packet(s) to the gateway! - {err}");
                 self.consecutive_gateway_failure_count += 1;
+                if self.gateway_failover.has_standby_gateways()
+                    && self.consecutive_gateway_failure_count % FAILOVER_FAILURE_COUNT == 0
+                {
+                    warn!("Failed to send sphinx packets to the gateway {} times in a row - attempting to fail over to a standby gateway", self.consecutive_gateway_failure_count);
+                    self.fail_over().await;
+                }
                 if self.consecutive_gateway_failure_count == MAX_FAILURE_COUNT {
-                    // todo: in the future this should initiate a 'graceful' shutdown or try
-                    // to reconnect?
+                    // todo: in the future this should initiate a 'graceful' shutdown
                     panic!("failed to send sphinx packet to the gateway {MAX_FAILURE_COUNT} times in a row - assuming the gateway is dead. Can't do anything about it yet :(")
                 }
             }
@@ -67,10 +111,53 @@ impl MixTrafficController {
         }
     }
 
+    async fn claim_bandwidth(&mut self) -> Result<i64, GatewayClientError> {
+        let result = self.gateway_client.claim_bandwidth().await;
+        let remaining = self.gateway_client.remaining_bandwidth();
+        self.remaining_bandwidth.set(remaining);
+        result.map(|_| remaining)
+    }
+
+    async fn on_bandwidth_request(&mut self, request: BandwidthRequest) {
+        match request {
+            BandwidthRequest::Claim { response_channel } => {
+                let result = self.claim_bandwidth().await;
+                if response_channel.send(result).is_err() {
+                    debug!("The requester of the bandwidth claim has gone away");
+                }
+            }
+        }
+    }
+
+    async fn fail_over(&mut self) {
+        if let Some(gateway_client) = self.gateway_failover.fail_over().await {
+            let mut old_client = std::mem::replace(&mut self.gateway_client, gateway_client);
+            GatewayFailover::close_connection(&mut old_client).await;
+            self.consecutive_gateway_failure_count = 0;
+            self.remaining_bandwidth
+                .set(self.gateway_client.remaining_bandwidth());
+        }
+    }
+
+    async fn check_gateway_presence(&mut self) {
+        if !self.gateway_failover.has_standby_gateways() {
+            return;
+        }
+        if self.gateway_failover.is_current_gateway_gone().await {
+            warn!(
+                "Our gateway {} is no longer present in the network topology - attempting to fail over to a standby gateway",
+                self.gateway_failover.current_gateway()
+            );
+            self.fail_over().await;
+        }
+    }
+
     pub fn start_with_shutdown(mut self, mut shutdown: task::TaskClient) {
         spawn_future(async move {
             debug!("Started MixTrafficController with graceful shutdown support");
 
+            let mut presence_check = new_interval_stream(GATEWAY_PRESENCE_CHECK_INTERVAL);
+
             loop {
                 tokio::select! {
                     mix_packets = self.mix_rx.recv() => match mix_packets {
@@ -82,6 +169,12 @@ impl MixTrafficController {
                             break;
                         }
                     },
+                    Some(request) = self.bandwidth_request_receiver.next() => {
+                        self.on_bandwidth_request(request).await;
+                    },
+                    _ = presence_check.next() => {
+                        self.check_gateway_presence().await;
+                    },
                     _ = shutdown.recv_with_delay() => {
                         log::trace!("MixTrafficController: Received shutdown");
                         break;
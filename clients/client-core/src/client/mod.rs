@@ -1,8 +1,10 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod bandwidth;
 pub mod base_client;
 pub mod cover_traffic_stream;
+pub mod gateway_failover;
 pub(crate) mod helpers;
 pub mod inbound_messages;
 pub mod key_manager;
@@ -10,5 +12,6 @@ pub mod mix_traffic;
 pub mod real_messages_control;
 pub mod received_buffer;
 pub mod replies;
+pub mod self_address;
 pub mod topology_control;
 pub(crate) mod transmission_buffer;
@@ -7,6 +7,7 @@ use crate::client::real_messages_control::real_traffic_stream::{
 };
 use crate::client::real_messages_control::{AckActionSender, Action};
 use crate::client::replies::reply_storage::{ReceivedReplySurbsMap, SentReplyKeys, UsedSenderTags};
+use crate::client::self_address::SelfAddress;
 use crate::client::topology_control::{TopologyAccessor, TopologyReadPermit};
 use client_connections::TransmissionLane;
 use log::{debug, error, info, trace, warn};
@@ -16,7 +17,7 @@ use nymsphinx::anonymous_replies::requests::{AnonymousSenderTag, RepliableMessag
 use nymsphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
 use nymsphinx::chunking::fragment::{Fragment, FragmentIdentifier};
 use nymsphinx::message::NymMessage;
-use nymsphinx::params::{PacketSize, DEFAULT_NUM_MIX_HOPS};
+use nymsphinx::params::{PacketSize, PacketType, DEFAULT_NUM_MIX_HOPS};
 use nymsphinx::preparer::{MessagePreparer, PreparedFragment};
 use nymsphinx::Delay;
 use rand::{CryptoRng, Rng};
@@ -70,13 +71,13 @@ where
 }
 
 impl SurbWrappedPreparationError {
-    pub(crate) fn return_unused_surbs(
+    pub(crate) async fn return_unused_surbs(
         self,
         surb_storage: &ReceivedReplySurbsMap,
         target: &AnonymousSenderTag,
     ) -> PreparationError {
         if let Some(reply_surbs) = self.returned_surbs {
-            surb_storage.insert_surbs(target, reply_surbs)
+            surb_storage.insert_surbs(target, reply_surbs).await
         }
         self.source
     }
@@ -89,7 +90,7 @@ pub(crate) struct Config {
 
     /// Address of this client which also represent an address to which all acknowledgements
     /// and surb-based are going to be sent.
-    sender_address: Recipient,
+    sender_address: SelfAddress,
 
     /// Average delay a data packet is going to get delay at a single mixnode.
     average_packet_delay: Duration,
@@ -103,12 +104,15 @@ pub(crate) struct Config {
 
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
+
+    /// Format of the packets used for the encapsulated messages.
+    packet_type: PacketType,
 }
 
 impl Config {
     pub fn new(
         ack_key: Arc<AckKey>,
-        sender_address: Recipient,
+        sender_address: SelfAddress,
         average_packet_delay: Duration,
         average_ack_delay: Duration,
     ) -> Self {
@@ -119,6 +123,7 @@ impl Config {
             average_ack_delay,
             num_mix_hops: DEFAULT_NUM_MIX_HOPS,
             packet_size: PacketSize::default(),
+            packet_type: PacketType::default(),
         }
     }
 
@@ -134,6 +139,12 @@ impl Config {
         self.packet_size = packet_size;
         self
     }
+
+    /// Allows setting non-default format of the packets sent out.
+    pub fn with_packet_type(mut self, packet_type: PacketType) -> Self {
+        self.packet_type = packet_type;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -166,11 +177,12 @@ where
     {
         let message_preparer = MessagePreparer::new(
             rng,
-            config.sender_address,
+            config.sender_address.get(),
             config.average_packet_delay,
             config.average_ack_delay,
         )
         .with_custom_real_message_packet_size(config.packet_size)
+        .with_packet_type(config.packet_type)
         .with_mix_hops(config.num_mix_hops);
 
         MessageHandler {
@@ -198,11 +210,18 @@ where
         }
     }
 
+    // the preparer keeps its own copy of our address, so make sure it's up to date in case
+    // our gateway has changed in the meantime
+    fn sync_sender_address(&mut self) {
+        self.message_preparer
+            .set_sender_address(self.config.sender_address.get());
+    }
+
     fn get_topology<'a>(
         &self,
         permit: &'a TopologyReadPermit<'a>,
     ) -> Result<&'a NymTopology, PreparationError> {
-        match permit.try_get_valid_topology_ref(&self.config.sender_address, None) {
+        match permit.try_get_valid_topology_ref(&self.config.sender_address.get(), None) {
             Ok(topology_ref) => Ok(topology_ref),
             Err(err) => {
                 warn!("Could not process the packet - the network topology is invalid - {err}");
@@ -215,6 +234,7 @@ where
         &mut self,
         amount: usize,
     ) -> Result<(Vec<ReplySurb>, Vec<SurbEncryptionKey>), PreparationError> {
+        self.sync_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -282,7 +302,7 @@ where
         debug!("requesting {amount} reply SURBs from {from}");
 
         let surbs_request =
-            ReplyMessage::new_surb_request_message(self.config.sender_address, amount);
+            ReplyMessage::new_surb_request_message(self.config.sender_address.get(), amount);
         self.try_send_single_surb_message(from, surbs_request, reply_surb, true)
             .await
     }
@@ -385,6 +405,7 @@ where
         debug_assert!(!matches!(message, NymMessage::Reply(_)));
 
         // TODO2: it's really annoying we have to get topology permit again here due to borrow-checker
+        self.sync_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -445,6 +466,35 @@ where
         Ok(())
     }
 
+    /// Sends fresh reply surbs to the recipient asking it to discard all the ones it got before,
+    /// for example because our gateway has changed.
+    pub(crate) async fn try_send_replacement_reply_surbs(
+        &mut self,
+        recipient: Recipient,
+        amount: u32,
+    ) -> Result<(), PreparationError> {
+        let sender_tag = self.get_or_create_sender_tag(&recipient);
+        let (reply_surbs, reply_keys) =
+            self.generate_reply_surbs_with_keys(amount as usize).await?;
+
+        let message = NymMessage::new_repliable(RepliableMessage::new_replacement_surbs(
+            sender_tag,
+            reply_surbs,
+        ));
+
+        self.try_split_and_send_non_reply_message(
+            message,
+            recipient,
+            TransmissionLane::AdditionalReplySurbs,
+        )
+        .await?;
+
+        log::trace!("storing {} reply keys", reply_keys.len());
+        self.reply_key_storage.insert_multiple(reply_keys);
+
+        Ok(())
+    }
+
     pub(crate) async fn try_send_message_with_reply_surbs(
         &mut self,
         recipient: Recipient,
@@ -474,6 +524,7 @@ where
         recipient: Recipient,
         chunk: Fragment,
     ) -> Result<PreparedFragment, PreparationError> {
+        self.sync_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = self.get_topology(&topology_permit)?;
 
@@ -498,6 +549,7 @@ where
             reply_surbs.len()
         );
 
+        self.sync_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = match self.get_topology(&topology_permit) {
             Ok(topology) => topology,
@@ -526,6 +578,7 @@ where
         reply_surb: ReplySurb,
         chunk: Fragment,
     ) -> Result<PreparedFragment, SurbWrappedPreparationError> {
+        self.sync_sender_address();
         let topology_permit = self.topology_access.get_read_permit().await;
         let topology = match self.get_topology(&topology_permit) {
             Ok(topology) => topology,
@@ -17,7 +17,7 @@ use crate::{
     client::{
         inbound_messages::InputMessageReceiver, mix_traffic::BatchMixMessageSender,
         real_messages_control::acknowledgement_control::AcknowledgementControllerConnectors,
-        topology_control::TopologyAccessor,
+        self_address::SelfAddress, topology_control::TopologyAccessor,
     },
     spawn_future,
 };
@@ -26,8 +26,7 @@ use futures::channel::mpsc;
 use gateway_client::AcknowledgementReceiver;
 use log::*;
 use nymsphinx::acknowledgements::AckKey;
-use nymsphinx::addressing::clients::Recipient;
-use nymsphinx::params::PacketSize;
+use nymsphinx::params::{PacketSize, PacketType};
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::sync::Arc;
 use std::time::Duration;
@@ -52,7 +51,7 @@ pub struct Config {
     ack_wait_multiplier: f64,
 
     /// Address of `this` client.
-    self_recipient: Recipient,
+    self_recipient: SelfAddress,
 
     /// Average delay between sending subsequent packets from this client.
     average_message_sending_delay: Duration,
@@ -70,6 +69,9 @@ pub struct Config {
     /// Predefined packet size used for the encapsulated messages.
     packet_size: PacketSize,
 
+    /// Format of the packets used for the encapsulated messages.
+    packet_type: PacketType,
+
     /// Defines the minimum number of reply surbs the client would request.
     minimum_reply_surb_request_size: u32,
 
@@ -103,7 +105,7 @@ impl<'a> From<&'a Config> for real_traffic_stream::Config {
     fn from(cfg: &'a Config) -> Self {
         real_traffic_stream::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_recipient.clone(),
             cfg.average_ack_delay_duration,
             cfg.average_packet_delay_duration,
             cfg.average_message_sending_delay,
@@ -130,11 +132,12 @@ impl<'a> From<&'a Config> for message_handler::Config {
     fn from(cfg: &'a Config) -> Self {
         message_handler::Config::new(
             Arc::clone(&cfg.ack_key),
-            cfg.self_recipient,
+            cfg.self_recipient.clone(),
             cfg.average_packet_delay_duration,
             cfg.average_ack_delay_duration,
         )
         .with_custom_packet_size(cfg.packet_size)
+        .with_packet_type(cfg.packet_type)
     }
 }
 
@@ -142,12 +145,13 @@ impl Config {
     pub fn new(
         base_client_debug_config: &config::DebugConfig,
         ack_key: Arc<AckKey>,
-        self_recipient: Recipient,
+        self_recipient: SelfAddress,
     ) -> Self {
         Config {
             ack_key,
             self_recipient,
             packet_size: Default::default(),
+            packet_type: base_client_debug_config.packet_type,
             ack_wait_addition: base_client_debug_config.ack_wait_addition,
             ack_wait_multiplier: base_client_debug_config.ack_wait_multiplier,
             average_message_sending_delay: base_client_debug_config.message_sending_average_delay,
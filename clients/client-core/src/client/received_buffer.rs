@@ -235,6 +235,16 @@ impl ReceivedMessagesBuffer {
                     error!("received a repliable heartbeat message - we don't know how to handle it yet (and we won't know until future PRs)");
                     (additional_reply_surbs, false)
                 }
+                RepliableMessageContent::ReplacementSurbs { reply_surbs } => {
+                    debug!(
+                        "received {} replacement reply surbs from {:?}!",
+                        reply_surbs.len(),
+                        msg.sender_tag
+                    );
+                    self.reply_controller_sender
+                        .send_replacement_surbs(msg.sender_tag, reply_surbs);
+                    continue;
+                }
             };
 
             self.reply_controller_sender.send_additional_surbs(
@@ -233,7 +233,8 @@ where
             let (surbs, _surbs_left) = self
                 .full_reply_storage
                 .surbs_storage_ref()
-                .get_reply_surbs(&recipient_tag, max_to_send);
+                .get_reply_surbs(&recipient_tag, max_to_send)
+                .await;
 
             if let Some(reply_surbs) = surbs {
                 let to_send = fragments.drain(..max_to_send).collect::<Vec<_>>();
@@ -247,10 +248,12 @@ where
                     )
                     .await
                 {
-                    let err = err.return_unused_surbs(
-                        self.full_reply_storage.surbs_storage_ref(),
-                        &recipient_tag,
-                    );
+                    let err = err
+                        .return_unused_surbs(
+                            self.full_reply_storage.surbs_storage_ref(),
+                            &recipient_tag,
+                        )
+                        .await;
                     warn!("failed to send reply to {recipient_tag}: {err}");
                     self.insert_pending_replies(&recipient_tag, to_send, lane);
                 }
@@ -277,6 +280,7 @@ where
             .full_reply_storage
             .surbs_storage_ref()
             .get_reply_surb_ignoring_threshold(&target)
+            .await
             .and_then(|(reply_surb, _)| reply_surb)
             .ok_or(PreparationError::NotEnoughSurbs {
                 available: 0,
@@ -288,7 +292,9 @@ where
             .try_request_additional_reply_surbs(target, reply_surb, amount)
             .await
         {
-            let err = err.return_unused_surbs(self.full_reply_storage.surbs_storage_ref(), &target);
+            let err = err
+                .return_unused_surbs(self.full_reply_storage.surbs_storage_ref(), &target)
+                .await;
             warn!(
                 "failed to request additional surbs from {:?} - {err}",
                 target
@@ -350,7 +356,8 @@ where
         let (surbs_for_reply, _) = self
             .full_reply_storage
             .surbs_storage_ref()
-            .get_reply_surbs(&target, to_take.len());
+            .get_reply_surbs(&target, to_take.len())
+            .await;
 
         let Some(surbs_for_reply) = surbs_for_reply else {
             error!("somehow different task has stolen our reply surbs! - this should have been impossible");
@@ -367,8 +374,9 @@ where
         {
             Ok(prepared) => prepared,
             Err(err) => {
-                let err =
-                    err.return_unused_surbs(self.full_reply_storage.surbs_storage_ref(), &target);
+                let err = err
+                    .return_unused_surbs(self.full_reply_storage.surbs_storage_ref(), &target)
+                    .await;
                 self.re_insert_pending_retransmission(&target, to_take);
 
                 warn!(
@@ -435,7 +443,8 @@ where
             let (surbs_for_reply, _) = self
                 .full_reply_storage
                 .surbs_storage_ref()
-                .get_reply_surbs(&target, to_send_clone.len());
+                .get_reply_surbs(&target, to_send_clone.len())
+                .await;
 
             let Some(surbs_for_reply) = surbs_for_reply else {
                 error!("somehow different task has stolen our reply surbs! - this should have been impossible");
@@ -448,8 +457,9 @@ where
                 .try_send_reply_chunks(target, to_send_clone, surbs_for_reply)
                 .await
             {
-                let err =
-                    err.return_unused_surbs(self.full_reply_storage.surbs_storage_ref(), &target);
+                let err = err
+                    .return_unused_surbs(self.full_reply_storage.surbs_storage_ref(), &target)
+                    .await;
                 self.re_insert_pending_replies(&target, to_send);
                 warn!("failed to clear pending queue for {:?} - {err}", target);
             }
@@ -479,7 +489,8 @@ where
         // store received surbs
         self.full_reply_storage
             .surbs_storage_ref()
-            .insert_surbs(&from, reply_surbs);
+            .insert_surbs(&from, reply_surbs)
+            .await;
 
         // use as many as we can for clearing pending retransmission queue
         self.try_clear_pending_retransmission(from).await;
@@ -493,6 +504,45 @@ where
         }
     }
 
+    async fn handle_replacement_surbs(
+        &mut self,
+        from: AnonymousSenderTag,
+        reply_surbs: Vec<ReplySurb>,
+    ) {
+        debug!("{from} has changed its address - replacing all of its reply surbs");
+
+        // whatever we had before is not going to reach them anymore
+        self.full_reply_storage
+            .surbs_storage_ref()
+            .replace_surbs(&from, reply_surbs)
+            .await;
+
+        self.try_clear_pending_retransmission(from).await;
+        self.try_clear_pending_queue(from).await;
+
+        if self.should_request_more_surbs(&from) {
+            self.request_reply_surbs_for_queue_clearing(from).await;
+        }
+    }
+
+    async fn handle_address_change_announcement(&mut self) {
+        let recipients = self.full_reply_storage.tags_storage_ref().recipients();
+        info!(
+            "announcing our new address to {} recipient(s) holding our reply surbs",
+            recipients.len()
+        );
+
+        for recipient in recipients {
+            if let Err(err) = self
+                .message_handler
+                .try_send_replacement_reply_surbs(recipient, self.config.min_surb_request_size)
+                .await
+            {
+                warn!("failed to announce our new address to {recipient}: {err}")
+            }
+        }
+    }
+
     async fn handle_surb_request(&mut self, recipient: Recipient, mut amount: u32) {
         // 1. check whether we sent any surbs in the past to this recipient, otherwise
         // they have no business in asking for more
@@ -571,10 +621,12 @@ where
             self.full_reply_storage
                 .surbs_storage_ref()
                 .get_reply_surb_ignoring_threshold(&recipient_tag)
+                .await
         } else {
             self.full_reply_storage
                 .surbs_storage_ref()
                 .get_reply_surb(&recipient_tag)
+                .await
         }
         .expect("attempted to retransmit a packet to an unknown recipient - we shouldn't have sent the original packet in the first place!");
 
@@ -597,10 +649,12 @@ where
                         .await;
                 }
                 Err(err) => {
-                    let err = err.return_unused_surbs(
-                        self.full_reply_storage.surbs_storage_ref(),
-                        &recipient_tag,
-                    );
+                    let err = err
+                        .return_unused_surbs(
+                            self.full_reply_storage.surbs_storage_ref(),
+                            &recipient_tag,
+                        )
+                        .await;
                     warn!("failed to prepare message for retransmission - {err}");
                     // we buffer that packet and to try another day
                     self.buffer_pending_ack(recipient_tag, ack_ref, timed_out_ack);
@@ -668,6 +722,13 @@ where
                 self.handle_received_surbs(sender_tag, reply_surbs, from_surb_request)
                     .await
             }
+            ReplyControllerMessage::ReplacementSurbs {
+                sender_tag,
+                reply_surbs,
+            } => self.handle_replacement_surbs(sender_tag, reply_surbs).await,
+            ReplyControllerMessage::AnnounceAddressChange => {
+                self.handle_address_change_announcement().await
+            }
             ReplyControllerMessage::LaneQueueLength {
                 connection_id,
                 response_channel,
@@ -815,7 +876,8 @@ where
         for to_remove in to_remove_surbs {
             self.full_reply_storage
                 .surbs_storage_ref()
-                .remove(&to_remove);
+                .remove(&to_remove)
+                .await;
         }
 
         for to_remove in to_remove_keys {
@@ -70,6 +70,25 @@ impl ReplyControllerSender {
             .expect("ReplyControllerReceiver has died!")
     }
 
+    pub(crate) fn send_replacement_surbs(
+        &self,
+        sender_tag: AnonymousSenderTag,
+        reply_surbs: Vec<ReplySurb>,
+    ) {
+        self.0
+            .unbounded_send(ReplyControllerMessage::ReplacementSurbs {
+                sender_tag,
+                reply_surbs,
+            })
+            .expect("ReplyControllerReceiver has died!")
+    }
+
+    pub(crate) fn send_address_change_announcement(&self) {
+        self.0
+            .unbounded_send(ReplyControllerMessage::AnnounceAddressChange)
+            .expect("ReplyControllerReceiver has died!")
+    }
+
     pub(crate) fn send_additional_surbs_request(&self, recipient: Recipient, amount: u32) {
         self.0
             .unbounded_send(ReplyControllerMessage::AdditionalSurbsRequest {
@@ -139,6 +158,15 @@ pub(crate) enum ReplyControllerMessage {
         from_surb_request: bool,
     },
 
+    ReplacementSurbs {
+        sender_tag: AnonymousSenderTag,
+        reply_surbs: Vec<ReplySurb>,
+    },
+
+    // our address has changed (i.e. we're now using a different gateway), so all the reply surbs
+    // we have sent so far have become useless
+    AnnounceAddressChange,
+
     // this one doesn't belong here either...
     LaneQueueLength {
         connection_id: ConnectionId,
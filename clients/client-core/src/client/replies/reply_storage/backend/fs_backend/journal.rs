@@ -0,0 +1,77 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::client::replies::reply_storage::backend::fs_backend::manager::StorageManager;
+use crate::client::replies::reply_storage::backend::fs_backend::models::StoredSurbSender;
+use crate::client::replies::reply_storage::surb_storage::{JournalError, ReplySurbsJournal};
+use async_trait::async_trait;
+use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
+use nymsphinx::anonymous_replies::ReplySurb;
+
+/// Writes the received reply surbs to the database as they arrive and removes them
+/// just before they get used.
+#[derive(Debug)]
+pub(crate) struct SurbJournal {
+    manager: StorageManager,
+}
+
+impl SurbJournal {
+    pub(crate) fn new(manager: StorageManager) -> Self {
+        SurbJournal { manager }
+    }
+
+    async fn store(
+        &self,
+        target: &AnonymousSenderTag,
+        surbs: &[ReplySurb],
+        received_at_timestamp: i64,
+        replace_existing: bool,
+    ) -> Result<(), JournalError> {
+        let raw_surbs = surbs.iter().map(|surb| surb.to_bytes()).collect();
+        self.manager
+            .store_received_reply_surbs(
+                StoredSurbSender::new(*target, received_at_timestamp),
+                raw_surbs,
+                replace_existing,
+            )
+            .await
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl ReplySurbsJournal for SurbJournal {
+    async fn store_surbs(
+        &self,
+        target: &AnonymousSenderTag,
+        surbs: &[ReplySurb],
+        received_at_timestamp: i64,
+    ) -> Result<(), JournalError> {
+        self.store(target, surbs, received_at_timestamp, false)
+            .await
+    }
+
+    async fn replace_surbs(
+        &self,
+        target: &AnonymousSenderTag,
+        surbs: &[ReplySurb],
+        received_at_timestamp: i64,
+    ) -> Result<(), JournalError> {
+        self.store(target, surbs, received_at_timestamp, true).await
+    }
+
+    async fn remove_used_surbs(&self, surbs: &[ReplySurb]) -> Result<(), JournalError> {
+        let raw_surbs = surbs.iter().map(|surb| surb.to_bytes()).collect();
+        self.manager
+            .delete_reply_surbs(raw_surbs)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn remove_sender(&self, target: &AnonymousSenderTag) -> Result<(), JournalError> {
+        self.manager
+            .delete_surb_sender(target.to_bytes().to_vec())
+            .await
+            .map_err(Into::into)
+    }
+}
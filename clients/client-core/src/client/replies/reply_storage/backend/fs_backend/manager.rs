@@ -7,6 +7,7 @@ use crate::client::replies::reply_storage::backend::fs_backend::models::{
 };
 use log::{error, info};
 use sqlx::ConnectOptions;
+use std::collections::HashSet;
 use std::path::Path;
 
 #[derive(Debug, Clone)]
@@ -108,6 +109,108 @@ impl StorageManager {
         Ok(())
     }
 
+    pub(crate) async fn get_last_checkpoint_timestamp(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query!("SELECT last_checkpoint_timestamp FROM status;")
+            .fetch_one(&self.connection_pool)
+            .await
+            .map(|r| r.last_checkpoint_timestamp)
+    }
+
+    pub(crate) async fn set_last_checkpoint_timestamp(
+        &self,
+        timestamp: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!("UPDATE status SET last_checkpoint_timestamp = ?", timestamp)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Atomically brings the stored tags and reply keys up to date with the provided ones.
+    /// Only the entries that have changed since the previous checkpoint are written.
+    /// If the process goes down in the middle of it, the previous data remains intact.
+    ///
+    /// Note that the reply surbs are not part of it as they're persisted as soon as they're
+    /// received or used.
+    pub(crate) async fn store_checkpoint(
+        &self,
+        tags: Vec<StoredSenderTag>,
+        reply_keys: Vec<StoredReplyKey>,
+        timestamp: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+
+        let stored_tags = sqlx::query!("SELECT recipient, tag FROM sender_tag;")
+            .fetch_all(&mut tx)
+            .await?
+            .into_iter()
+            .map(|r| (r.recipient, r.tag))
+            .collect::<HashSet<_>>();
+        let current_tags = tags
+            .into_iter()
+            .map(|stored_tag| (stored_tag.recipient, stored_tag.tag))
+            .collect::<HashSet<_>>();
+
+        for (recipient, tag) in stored_tags.difference(&current_tags) {
+            sqlx::query!(
+                "DELETE FROM sender_tag WHERE recipient = ? AND tag = ?;",
+                recipient,
+                tag
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        for (recipient, tag) in current_tags.difference(&stored_tags) {
+            sqlx::query!(
+                "INSERT OR REPLACE INTO sender_tag(recipient, tag) VALUES (?, ?);",
+                recipient,
+                tag
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        let stored_digests = sqlx::query!("SELECT key_digest FROM reply_key;")
+            .fetch_all(&mut tx)
+            .await?
+            .into_iter()
+            .map(|r| r.key_digest)
+            .collect::<HashSet<_>>();
+        let current_digests = reply_keys
+            .iter()
+            .map(|stored_reply_key| stored_reply_key.key_digest.clone())
+            .collect::<HashSet<_>>();
+
+        for key_digest in stored_digests.difference(&current_digests) {
+            sqlx::query!("DELETE FROM reply_key WHERE key_digest = ?;", key_digest)
+                .execute(&mut tx)
+                .await?;
+        }
+        for stored_reply_key in reply_keys
+            .into_iter()
+            .filter(|stored_reply_key| !stored_digests.contains(&stored_reply_key.key_digest))
+        {
+            sqlx::query!(
+                "INSERT INTO reply_key(key_digest, reply_key, sent_at_timestamp) VALUES (?, ?, ?);",
+                stored_reply_key.key_digest,
+                stored_reply_key.reply_key,
+                stored_reply_key.sent_at_timestamp
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            "UPDATE status SET previous_flush_timestamp = ?, last_checkpoint_timestamp = ?",
+            timestamp,
+            timestamp
+        )
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await
+    }
+
     pub(crate) async fn delete_all_tags(&self) -> Result<(), sqlx::Error> {
         sqlx::query!("DELETE FROM sender_tag;")
             .execute(&self.connection_pool)
@@ -212,6 +315,89 @@ impl StorageManager {
         Ok(())
     }
 
+    /// Atomically stores the reply surbs received from the particular sender alongside the time
+    /// of their reception, optionally replacing all the surbs we have had from them before.
+    pub(crate) async fn store_received_reply_surbs(
+        &self,
+        stored_surb_sender: StoredSurbSender,
+        reply_surbs: Vec<Vec<u8>>,
+        replace_existing: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO reply_surb_sender(tag, last_sent_timestamp) VALUES (?, ?)
+                ON CONFLICT(tag) DO UPDATE SET last_sent_timestamp = excluded.last_sent_timestamp;
+            "#,
+            stored_surb_sender.tag,
+            stored_surb_sender.last_sent_timestamp
+        )
+        .execute(&mut tx)
+        .await?;
+
+        let sender_id = sqlx::query!(
+            "SELECT id FROM reply_surb_sender WHERE tag = ?;",
+            stored_surb_sender.tag
+        )
+        .fetch_one(&mut tx)
+        .await?
+        .id;
+
+        if replace_existing {
+            sqlx::query!(
+                "DELETE FROM reply_surb WHERE reply_surb_sender_id = ?;",
+                sender_id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        for reply_surb in reply_surbs {
+            sqlx::query!(
+                "INSERT INTO reply_surb(reply_surb_sender_id, reply_surb) VALUES (?, ?);",
+                sender_id,
+                reply_surb
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    pub(crate) async fn delete_reply_surbs(
+        &self,
+        reply_surbs: Vec<Vec<u8>>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+
+        for reply_surb in reply_surbs {
+            sqlx::query!("DELETE FROM reply_surb WHERE reply_surb = ?;", reply_surb)
+                .execute(&mut tx)
+                .await?;
+        }
+
+        tx.commit().await
+    }
+
+    pub(crate) async fn delete_surb_sender(&self, tag: Vec<u8>) -> Result<(), sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM reply_surb WHERE reply_surb_sender_id IN (SELECT id FROM reply_surb_sender WHERE tag = ?);",
+            tag
+        )
+        .execute(&mut tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM reply_surb_sender WHERE tag = ?;", tag)
+            .execute(&mut tx)
+            .await?;
+
+        tx.commit().await
+    }
+
     pub(crate) async fn insert_reply_surb(
         &self,
         stored_reply_surb: StoredReplySurb,
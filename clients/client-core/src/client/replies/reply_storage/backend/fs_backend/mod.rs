@@ -1,6 +1,7 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::client::replies::reply_storage::backend::fs_backend::journal::SurbJournal;
 use crate::client::replies::reply_storage::backend::fs_backend::manager::StorageManager;
 use crate::client::replies::reply_storage::backend::fs_backend::models::{
     ReplySurbStorageMetadata, StoredReplyKey, StoredReplySurb, StoredSenderTag, StoredSurbSender,
@@ -14,11 +15,13 @@ use log::{error, info, warn};
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use time::OffsetDateTime;
 
 pub use self::error::StorageError;
 
 mod error;
+mod journal;
 mod manager;
 mod models;
 
@@ -73,13 +76,17 @@ impl Backend {
             return Err(StorageError::IncompleteDataFlush);
         }
 
-        // the process has gone down without full graceful shutdown,
-        // meaning the database doesn't contain valid data anymore
-        // so we have to purge it
+        // the process has gone down without full graceful shutdown.
+        // the reply surbs are persisted as soon as they're received or used, so they're still valid.
+        // if we managed to checkpoint the data in the meantime, we can recover the rest from that point,
+        // otherwise the stored encryption keys can't be trusted anymore so we have to purge them
         if manager.get_client_in_use_status().await? {
-            error!("the client hasn't undergone through graceful shutdown the last time it's gone down - we can't trust its reply surbs or stored encryption keys. They shall get purged");
-            manager.delete_all_reply_surb_data().await?;
-            manager.delete_all_reply_keys().await?;
+            if manager.get_last_checkpoint_timestamp().await? > 0 {
+                warn!("the client hasn't undergone through graceful shutdown the last time it's gone down - we're going to recover its reply surbs as well as the stored encryption keys and sender tags from the last checkpoint. Any changes made to the keys and tags afterwards are lost");
+            } else {
+                error!("the client hasn't undergone through graceful shutdown the last time it's gone down - we can't trust its stored encryption keys. They shall get purged");
+                manager.delete_all_reply_keys().await?;
+            }
         }
 
         if let Err(err) = manager.get_reply_surb_storage_metadata().await {
@@ -172,6 +179,8 @@ impl Backend {
     }
 
     async fn start_client_use(&self) -> Result<(), StorageError> {
+        // any previous checkpoint refers to the data from the previous session
+        self.manager.set_last_checkpoint_timestamp(0).await?;
         Ok(self.manager.set_client_in_use_status(true).await?)
     }
 
@@ -252,6 +261,7 @@ impl Backend {
             metadata.min_reply_surb_threshold as usize,
             metadata.max_reply_surb_threshold as usize,
             received_surbs,
+            Arc::new(SurbJournal::new(self.manager.clone())),
         ))
     }
 
@@ -278,6 +288,32 @@ impl Backend {
         Ok(())
     }
 
+    // note: the reply surbs are not included as they're journaled as soon as they're received or used
+    async fn checkpoint(&self, storage: &CombinedReplyStorage) -> Result<(), StorageError> {
+        let tags = storage
+            .tags_storage_ref()
+            .as_raw_iter()
+            .map(|map_ref| {
+                let (recipient, tag) = map_ref.pair();
+                StoredSenderTag::new(*recipient, *tag)
+            })
+            .collect();
+
+        let reply_keys = storage
+            .key_storage_ref()
+            .as_raw_iter()
+            .map(|map_ref| {
+                let (digest, key) = map_ref.pair();
+                StoredReplyKey::new(*digest, *key)
+            })
+            .collect();
+
+        self.manager
+            .store_checkpoint(tags, reply_keys, OffsetDateTime::now_utc().unix_timestamp())
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_reply_surb_storage_metadata(
         &self,
     ) -> Result<ReplySurbStorageMetadata, StorageError> {
@@ -328,6 +364,13 @@ impl ReplyStorageBackend for Backend {
         self.end_storage_flush().await
     }
 
+    async fn checkpoint_surb_storage(
+        &mut self,
+        storage: &CombinedReplyStorage,
+    ) -> Result<(), Self::StorageError> {
+        self.checkpoint(storage).await
+    }
+
     async fn init_fresh(&mut self, fresh: &CombinedReplyStorage) -> Result<(), Self::StorageError> {
         // for now nothing more to do apart from dumping the metadata
         self.dump_reply_surb_storage_metadata(fresh.surbs_storage_ref())
@@ -346,3 +389,140 @@ impl ReplyStorageBackend for Backend {
         self.stop_client_use().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::replies::reply_storage::key_storage::UsedReplyKey;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+    use nymsphinx::addressing::clients::Recipient;
+    use nymsphinx::anonymous_replies::{ReplySurb, SurbEncryptionKey};
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use topology::{gateway, mix, NymTopology};
+
+    fn topology_fixture() -> NymTopology {
+        let mut rng = rand::rngs::OsRng;
+        let mut mixes = HashMap::new();
+        for (layer, mix_layer) in [(1, Layer::One), (2, Layer::Two), (3, Layer::Three)] {
+            mixes.insert(
+                layer,
+                vec![mix::Node {
+                    mix_id: layer as u32,
+                    owner: format!("owner{layer}"),
+                    host: "10.20.30.40".parse().unwrap(),
+                    mix_host: format!("10.20.30.4{layer}:1789").parse().unwrap(),
+                    http_api_port: 8000,
+                    identity_key: *identity::KeyPair::new(&mut rng).public_key(),
+                    sphinx_key: *encryption::KeyPair::new(&mut rng).public_key(),
+                    sphinx_key_epoch: None,
+                    layer: mix_layer,
+                    version: "1.1.0".to_string(),
+                    family: None,
+                    performance: None,
+                }],
+            );
+        }
+
+        let gateway = gateway::Node {
+            owner: "owner4".to_string(),
+            stake: 123,
+            location: "unknown".to_string(),
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            clients_port: 9000,
+            identity_key: *identity::KeyPair::new(&mut rng).public_key(),
+            sphinx_key: *encryption::KeyPair::new(&mut rng).public_key(),
+            sphinx_key_epoch: None,
+            version: "1.1.0".to_string(),
+        };
+
+        NymTopology::new(mixes, vec![gateway])
+    }
+
+    fn recipient_fixture(topology: &NymTopology) -> Recipient {
+        let mut rng = rand::rngs::OsRng;
+        Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            topology.gateways()[0].identity_key,
+        )
+    }
+
+    #[tokio::test]
+    async fn unused_reply_surbs_are_restored_after_unclean_shutdown() {
+        let mut rng = rand::rngs::OsRng;
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("persistent_reply_store.sqlite");
+        let topology = topology_fixture();
+        let recipient = recipient_fixture(&topology);
+        let sender_tag = AnonymousSenderTag::new_random(&mut rng);
+
+        // the previous session has gone down gracefully with some reply surbs stored
+        let mut backend = Backend::init(&db_path).await.unwrap();
+        let storage = CombinedReplyStorage::new(0, 100);
+        backend.init_fresh(&storage).await.unwrap();
+        let surbs = (0..3)
+            .map(|_| {
+                ReplySurb::construct(&mut rng, &recipient, Duration::from_millis(10), &topology)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        storage
+            .surbs_storage_ref()
+            .insert_surbs(&sender_tag, surbs)
+            .await;
+        backend.flush_surb_storage(&storage).await.unwrap();
+        backend.stop_storage_session().await.unwrap();
+
+        // the next session uses one of them, receives a new one, makes a checkpoint and crashes
+        let mut backend = Backend::try_load(&db_path).await.unwrap();
+        let storage = backend.load_surb_storage().await.unwrap();
+        backend.start_storage_session().await.unwrap();
+        assert_eq!(storage.surbs_storage_ref().available_surbs(&sender_tag), 3);
+        let (used_surb, _) = storage
+            .surbs_storage_ref()
+            .get_reply_surb_ignoring_threshold(&sender_tag)
+            .await
+            .unwrap();
+        let used_surb = used_surb.unwrap().to_bytes();
+
+        let new_surb =
+            ReplySurb::construct(&mut rng, &recipient, Duration::from_millis(10), &topology)
+                .unwrap();
+        storage
+            .surbs_storage_ref()
+            .insert_surbs(&sender_tag, vec![new_surb])
+            .await;
+
+        let reply_key = SurbEncryptionKey::new(&mut rng);
+        storage.key_storage_ref().insert(UsedReplyKey::new(
+            reply_key,
+            OffsetDateTime::now_utc().unix_timestamp(),
+        ));
+        backend.checkpoint_surb_storage(&storage).await.unwrap();
+        backend.close_pool().await;
+        drop(backend);
+
+        // all the unused surbs are restored, but the used one is gone for good
+        let backend = Backend::try_load(&db_path).await.unwrap();
+        let storage = backend.load_surb_storage().await.unwrap();
+        assert_eq!(storage.surbs_storage_ref().available_surbs(&sender_tag), 3);
+        let restored = storage
+            .surbs_storage_ref()
+            .get_reply_surbs(&sender_tag, 3)
+            .await
+            .0
+            .unwrap();
+        assert!(restored
+            .iter()
+            .all(|restored_surb| restored_surb.to_bytes() != used_surb));
+
+        // but the checkpointed reply keys are
+        assert!(storage
+            .key_storage_ref()
+            .try_pop(reply_key.compute_digest())
+            .is_some());
+    }
+}
@@ -67,6 +67,16 @@ pub trait ReplyStorageBackend: Sized {
         storage: &CombinedReplyStorage,
     ) -> Result<(), Self::StorageError>;
 
+    /// Persists the current state of the storage while the client is still running,
+    /// so that it could be recovered if the process goes down without a graceful shutdown.
+    /// By default this is a no-op.
+    async fn checkpoint_surb_storage(
+        &mut self,
+        _storage: &CombinedReplyStorage,
+    ) -> Result<(), Self::StorageError> {
+        Ok(())
+    }
+
     /// The purpose of this call is to save any metadata that might be present.
     /// (such as surb thresholds)
     async fn init_fresh(&mut self, fresh: &CombinedReplyStorage) -> Result<(), Self::StorageError>;
@@ -6,6 +6,7 @@ pub use crate::client::replies::reply_storage::key_storage::SentReplyKeys;
 pub use crate::client::replies::reply_storage::surb_storage::ReceivedReplySurbsMap;
 pub use crate::client::replies::reply_storage::tag_storage::UsedSenderTags;
 pub use backend::*;
+use std::time::Duration;
 
 mod backend;
 mod combined;
@@ -19,6 +20,10 @@ where
     T: ReplyStorageBackend,
 {
     backend: T,
+
+    /// How often the in-memory state is checkpointed to the backend.
+    /// Zero disables the checkpointing, in which case the data is only saved on shutdown.
+    checkpoint_interval: Duration,
 }
 
 impl<T> PersistentReplyStorage<T>
@@ -26,13 +31,48 @@ where
     T: ReplyStorageBackend + Send + Sync,
 {
     pub fn new(backend: T) -> Self {
-        PersistentReplyStorage { backend }
+        PersistentReplyStorage {
+            backend,
+            checkpoint_interval: Duration::ZERO,
+        }
+    }
+
+    #[must_use]
+    pub fn with_checkpoint_interval(mut self, checkpoint_interval: Duration) -> Self {
+        self.checkpoint_interval = checkpoint_interval;
+        self
     }
 
     pub async fn load_state_from_backend(&self) -> Result<CombinedReplyStorage, T::StorageError> {
         self.backend.load_surb_storage().await
     }
 
+    async fn checkpoint_until_shutdown(
+        &mut self,
+        mem_state: &CombinedReplyStorage,
+        shutdown: &mut task::TaskClient,
+    ) {
+        use futures::StreamExt;
+        use log::{debug, error, trace};
+
+        let mut checkpoint_timer =
+            crate::client::helpers::new_interval_stream(self.checkpoint_interval);
+
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                _ = checkpoint_timer.next() => {
+                    debug!("checkpointing reply-related data to underlying storage");
+                    if let Err(err) = self.backend.checkpoint_surb_storage(mem_state).await {
+                        error!("failed to checkpoint our reply-related data to the persistent storage: {err}")
+                    }
+                }
+                _ = shutdown.recv() => {
+                    trace!("PersistentReplyStorage: Received shutdown");
+                }
+            }
+        }
+    }
+
     // this will have to get enabled after merging develop
     pub async fn flush_on_shutdown(
         mut self,
@@ -47,7 +87,11 @@ where
             return;
         }
 
-        shutdown.recv().await;
+        if self.checkpoint_interval.is_zero() {
+            shutdown.recv().await;
+        } else {
+            self.checkpoint_until_shutdown(&mem_state, &mut shutdown).await;
+        }
 
         info!("PersistentReplyStorage is flushing all reply-related data to underlying storage");
         warn!("you MUST NOT forcefully shutdown now or you risk data corruption!");
@@ -1,16 +1,44 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use async_trait::async_trait;
 use dashmap::iter::Iter;
 use dashmap::DashMap;
-use log::trace;
+use log::{error, trace};
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
 use nymsphinx::anonymous_replies::ReplySurb;
 use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::Debug;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use time::OffsetDateTime;
 
+pub(crate) type JournalError = Box<dyn Error + Send + Sync>;
+
+/// Persists the changes made to the received reply surbs as soon as they happen,
+/// so that the surbs could be recovered after an unclean shutdown without any risk of reusing them.
+#[async_trait]
+pub(crate) trait ReplySurbsJournal: Debug + Send + Sync {
+    async fn store_surbs(
+        &self,
+        target: &AnonymousSenderTag,
+        surbs: &[ReplySurb],
+        received_at_timestamp: i64,
+    ) -> Result<(), JournalError>;
+
+    async fn replace_surbs(
+        &self,
+        target: &AnonymousSenderTag,
+        surbs: &[ReplySurb],
+        received_at_timestamp: i64,
+    ) -> Result<(), JournalError>;
+
+    async fn remove_used_surbs(&self, surbs: &[ReplySurb]) -> Result<(), JournalError>;
+
+    async fn remove_sender(&self, target: &AnonymousSenderTag) -> Result<(), JournalError>;
+}
+
 #[derive(Debug, Clone)]
 pub struct ReceivedReplySurbsMap {
     inner: Arc<ReceivedReplySurbsMapInner>,
@@ -25,6 +53,9 @@ struct ReceivedReplySurbsMapInner {
 
     // the maximum amount of surbs that we want to keep in storage so that we don't over-request them
     max_surb_threshold: AtomicUsize,
+
+    // if present, every received and used surb is recorded there before being handed out
+    journal: Option<Arc<dyn ReplySurbsJournal>>,
 }
 
 impl ReceivedReplySurbsMap {
@@ -37,6 +68,7 @@ impl ReceivedReplySurbsMap {
                 data: DashMap::new(),
                 min_surb_threshold: AtomicUsize::new(min_surb_threshold),
                 max_surb_threshold: AtomicUsize::new(max_surb_threshold),
+                journal: None,
             }),
         }
     }
@@ -46,22 +78,51 @@ impl ReceivedReplySurbsMap {
         min_surb_threshold: usize,
         max_surb_threshold: usize,
         raw: Vec<(AnonymousSenderTag, ReceivedReplySurbs)>,
+        journal: Arc<dyn ReplySurbsJournal>,
     ) -> ReceivedReplySurbsMap {
         ReceivedReplySurbsMap {
             inner: Arc::new(ReceivedReplySurbsMapInner {
                 data: raw.into_iter().collect(),
                 min_surb_threshold: AtomicUsize::new(min_surb_threshold),
                 max_surb_threshold: AtomicUsize::new(max_surb_threshold),
+                journal: Some(journal),
             }),
         }
     }
 
+    // makes sure the surbs are not going to get restored after an unclean shutdown
+    // before we let anyone use them. if we fail to do so, they're put back instead
+    async fn journal_used_surbs(
+        &self,
+        target: &AnonymousSenderTag,
+        surbs: Vec<ReplySurb>,
+    ) -> Option<Vec<ReplySurb>> {
+        let Some(journal) = &self.inner.journal else {
+            return Some(surbs);
+        };
+        match journal.remove_used_surbs(&surbs).await {
+            Ok(_) => Some(surbs),
+            Err(err) => {
+                error!("failed to persist the usage of {} reply surbs from {target} - {err}. They are not going to be used", surbs.len());
+                if let Some(mut entry) = self.inner.data.get_mut(target) {
+                    entry.restore_unused_reply_surbs(surbs)
+                }
+                None
+            }
+        }
+    }
+
     pub(crate) fn as_raw_iter(&self) -> Iter<'_, AnonymousSenderTag, ReceivedReplySurbs> {
         self.inner.data.iter()
     }
 
-    pub(crate) fn remove(&self, target: &AnonymousSenderTag) {
+    pub(crate) async fn remove(&self, target: &AnonymousSenderTag) {
         self.inner.data.remove(target);
+        if let Some(journal) = &self.inner.journal {
+            if let Err(err) = journal.remove_sender(target).await {
+                error!("failed to remove the persisted reply surbs from {target} - {err}")
+            }
+        }
     }
 
     pub(crate) fn reset_surbs_last_received_at(&self, target: &AnonymousSenderTag) {
@@ -133,12 +194,12 @@ impl ReceivedReplySurbsMap {
         self.inner.data.contains_key(target)
     }
 
-    pub(crate) fn get_reply_surbs(
+    pub(crate) async fn get_reply_surbs(
         &self,
         target: &AnonymousSenderTag,
         amount: usize,
     ) -> (Option<Vec<ReplySurb>>, usize) {
-        if let Some(mut entry) = self.inner.data.get_mut(target) {
+        let (surbs, surbs_left) = if let Some(mut entry) = self.inner.data.get_mut(target) {
             let surbs_left = entry.items_left();
             if surbs_left < self.min_surb_threshold() + amount {
                 (None, surbs_left)
@@ -147,42 +208,93 @@ impl ReceivedReplySurbsMap {
             }
         } else {
             (None, 0)
+        };
+
+        match surbs {
+            Some(surbs) => {
+                let amount = surbs.len();
+                match self.journal_used_surbs(target, surbs).await {
+                    Some(surbs) => (Some(surbs), surbs_left),
+                    None => (None, surbs_left + amount),
+                }
+            }
+            None => (None, surbs_left),
         }
     }
 
-    pub(crate) fn get_reply_surb_ignoring_threshold(
+    pub(crate) async fn get_reply_surb_ignoring_threshold(
         &self,
         target: &AnonymousSenderTag,
     ) -> Option<(Option<ReplySurb>, usize)> {
-        self.inner
+        let retrieved = self
+            .inner
             .data
             .get_mut(target)
-            .map(|mut s| s.get_reply_surb())
+            .map(|mut s| s.get_reply_surb());
+
+        self.journal_used_surb(target, retrieved).await
     }
 
-    pub(crate) fn get_reply_surb(
+    pub(crate) async fn get_reply_surb(
         &self,
         target: &AnonymousSenderTag,
     ) -> Option<(Option<ReplySurb>, usize)> {
-        self.inner.data.get_mut(target).map(|mut entry| {
+        let retrieved = self.inner.data.get_mut(target).map(|mut entry| {
             let surbs_left = entry.items_left();
             if surbs_left < self.min_surb_threshold() {
                 (None, surbs_left)
             } else {
                 entry.get_reply_surb()
             }
-        })
+        });
+
+        self.journal_used_surb(target, retrieved).await
     }
 
-    pub(crate) fn insert_surbs<I: IntoIterator<Item = ReplySurb>>(
+    async fn journal_used_surb(
         &self,
         target: &AnonymousSenderTag,
-        surbs: I,
-    ) {
+        retrieved: Option<(Option<ReplySurb>, usize)>,
+    ) -> Option<(Option<ReplySurb>, usize)> {
+        match retrieved {
+            Some((Some(surb), surbs_left)) => {
+                match self.journal_used_surbs(target, vec![surb]).await {
+                    Some(mut surbs) => Some((surbs.pop(), surbs_left)),
+                    None => Some((None, surbs_left + 1)),
+                }
+            }
+            retrieved => retrieved,
+        }
+    }
+
+    pub(crate) async fn insert_surbs(&self, target: &AnonymousSenderTag, surbs: Vec<ReplySurb>) {
+        if let Some(journal) = &self.inner.journal {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            if let Err(err) = journal.store_surbs(target, &surbs, now).await {
+                error!("failed to persist {} reply surbs received from {target} - {err}. They won't be recovered after an unclean shutdown", surbs.len())
+            }
+        }
+
         if let Some(mut existing_data) = self.inner.data.get_mut(target) {
             existing_data.insert_reply_surbs(surbs)
         } else {
-            let new_entry = ReceivedReplySurbs::new(surbs.into_iter().collect());
+            let new_entry = ReceivedReplySurbs::new(surbs.into());
+            self.inner.data.insert(*target, new_entry);
+        }
+    }
+
+    pub(crate) async fn replace_surbs(&self, target: &AnonymousSenderTag, surbs: Vec<ReplySurb>) {
+        if let Some(journal) = &self.inner.journal {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            if let Err(err) = journal.replace_surbs(target, &surbs, now).await {
+                error!("failed to persist {} replacement reply surbs received from {target} - {err}. They won't be recovered after an unclean shutdown", surbs.len())
+            }
+        }
+
+        if let Some(mut existing_data) = self.inner.data.get_mut(target) {
+            existing_data.replace_reply_surbs(surbs)
+        } else {
+            let new_entry = ReceivedReplySurbs::new(surbs.into());
             self.inner.data.insert(*target, new_entry);
         }
     }
@@ -275,4 +387,17 @@ impl ReceivedReplySurbs {
         self.surbs_last_received_at_timestamp = OffsetDateTime::now_utc().unix_timestamp();
         trace!("we now have {} surbs!", self.data.len());
     }
+
+    // puts back the surbs we have taken out, but couldn't use, so that they'd be the next ones to go
+    fn restore_unused_reply_surbs<I: IntoIterator<Item = ReplySurb>>(&mut self, surbs: I) {
+        let mut restored = surbs.into_iter().collect::<VecDeque<_>>();
+        restored.append(&mut self.data);
+        self.data = restored;
+    }
+
+    pub(crate) fn replace_reply_surbs<I: IntoIterator<Item = ReplySurb>>(&mut self, surbs: I) {
+        self.data = surbs.into_iter().collect();
+        trace!("replaced our surbs, we now have {} surbs!", self.data.len());
+        self.surbs_last_received_at_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+    }
 }
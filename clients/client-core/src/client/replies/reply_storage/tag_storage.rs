@@ -56,4 +56,13 @@ impl UsedSenderTags {
     pub(crate) fn exists(&self, recipient: &Recipient) -> bool {
         self.inner.data.contains_key(&recipient.to_bytes())
     }
+
+    /// Returns all recipients we have ever sent our reply surbs to.
+    pub(crate) fn recipients(&self) -> Vec<Recipient> {
+        self.inner
+            .data
+            .iter()
+            .filter_map(|entry| Recipient::try_from_bytes(*entry.key()).ok())
+            .collect()
+    }
 }
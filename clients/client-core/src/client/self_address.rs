@@ -0,0 +1,39 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nymsphinx::addressing::clients::Recipient;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Full address of this client shared between all the components that need to know it.
+///
+/// Unlike the identity and encryption keys, the gateway part of the address might change
+/// while the client is running, i.e. after failing over to one of the standby gateways.
+#[derive(Clone, Debug)]
+pub struct SelfAddress {
+    inner: Arc<watch::Sender<Recipient>>,
+}
+
+impl SelfAddress {
+    pub fn new(address: Recipient) -> Self {
+        let (sender, _) = watch::channel(address);
+        SelfAddress {
+            inner: Arc::new(sender),
+        }
+    }
+
+    /// Returns the current address of this client.
+    pub fn get(&self) -> Recipient {
+        *self.inner.borrow()
+    }
+
+    /// Returns a receiver notified whenever the address of this client changes,
+    /// so that it could be announced to anyone relying on the previous one.
+    pub fn subscribe(&self) -> watch::Receiver<Recipient> {
+        self.inner.subscribe()
+    }
+
+    pub(crate) fn set(&self, address: Recipient) {
+        self.inner.send_replace(address);
+    }
+}
@@ -0,0 +1,522 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use log::*;
+use mixnet_contract_common::{GatewayBond, MixNodeBond};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use topology::{nym_topology_from_bonds, nym_topology_from_detailed, NymTopology};
+use url::Url;
+
+use super::sphinx_keys::RotatedSphinxKeys;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// Source of the network topology used by the [`TopologyRefresher`](super::TopologyRefresher).
+///
+/// Any closure returning an `Option<NymTopology>` can be used as a provider as well.
+///
+/// Note: on wasm the returned futures are not required to be `Send`, as the `NymApiClient`
+/// futures are not.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait TopologyProvider: Send {
+    /// Retrieve the current network topology, or `None` if it's not available at the moment,
+    /// in which case the previous topology might be kept in use for a bit longer.
+    async fn get_new_topology(&mut self) -> Option<NymTopology>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<F> TopologyProvider for F
+where
+    F: FnMut() -> Option<NymTopology> + Send,
+{
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        self()
+    }
+}
+
+/// Retrieves the active topology from the nym-api, falling back to a different nym-api
+/// whenever the query fails.
+pub struct NymApiTopologyProvider {
+    validator_client: validator_client::client::NymApiClient,
+    client_version: String,
+
+    nym_api_urls: Vec<Url>,
+    currently_used_api: usize,
+
+    /// Whether the families and the performance of the mixnodes should be retrieved as well.
+    node_annotations: bool,
+
+    /// Sphinx keys announced by the mixnodes. If not set, the bonded keys are always used.
+    rotated_sphinx_keys: Option<RotatedSphinxKeys>,
+}
+
+impl NymApiTopologyProvider {
+    pub fn new(mut nym_api_urls: Vec<Url>, client_version: String) -> Self {
+        nym_api_urls.shuffle(&mut thread_rng());
+
+        NymApiTopologyProvider {
+            validator_client: validator_client::client::NymApiClient::new(nym_api_urls[0].clone()),
+            client_version,
+            nym_api_urls,
+            currently_used_api: 0,
+            node_annotations: false,
+            rotated_sphinx_keys: None,
+        }
+    }
+
+    /// Makes the provider retrieve the rotated sphinx keys of the mixnodes, as collected by the nym-api,
+    /// and use them instead of the bonded keys, whenever they're available.
+    pub fn with_rotated_sphinx_keys(mut self, enabled: bool) -> Self {
+        self.rotated_sphinx_keys = enabled.then(RotatedSphinxKeys::new);
+        self
+    }
+
+    /// Makes the provider retrieve the families and the performance of the mixnodes, from the
+    /// more expensive detailed nym-api endpoint, so that they could be used for choosing the routes.
+    pub fn with_node_annotations(mut self, enabled: bool) -> Self {
+        self.node_annotations = enabled;
+        self
+    }
+
+    fn use_next_nym_api(&mut self) {
+        if self.nym_api_urls.len() == 1 {
+            warn!("There's only a single nym API available - it won't be possible to use a different one");
+            return;
+        }
+
+        self.currently_used_api = (self.currently_used_api + 1) % self.nym_api_urls.len();
+        self.validator_client
+            .change_nym_api(self.nym_api_urls[self.currently_used_api].clone())
+    }
+
+    /// Verifies whether nodes a reasonably distributed among all mix layers.
+    ///
+    /// In ideal world we would have 33% nodes on layer 1, 33% on layer 2 and 33% on layer 3.
+    /// However, this is a rather unrealistic expectation, instead we check whether there exists
+    /// a layer with more than 66% of nodes or with fewer than 15% and if so, we trigger a failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `topology`: active topology constructed from validator api data
+    fn check_layer_distribution(&self, active_topology: &NymTopology) -> bool {
+        let mixes = active_topology.mixes();
+        let mixnodes_count = active_topology.num_mixnodes();
+
+        if active_topology.gateways().is_empty() {
+            return false;
+        }
+
+        // trivial check to see if have at least a single node on each layer (regardless of active set size)
+        if mixes.get(&1).is_none() || mixes.get(&2).is_none() || mixes.get(&3).is_none() {
+            return false;
+        }
+
+        let upper_bound = (mixnodes_count as f32 * 0.66) as usize;
+        let lower_bound = (mixnodes_count as f32 * 0.15) as usize;
+
+        let layer1 = mixes.get(&1).unwrap().len();
+        let layer2 = mixes.get(&2).unwrap().len();
+        let layer3 = mixes.get(&3).unwrap().len();
+
+        if layer1 < lower_bound || layer1 > upper_bound {
+            warn!(
+                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
+                mixnodes_count, layer1, layer2, layer3
+            );
+            return false;
+        }
+
+        if layer2 < lower_bound || layer2 > upper_bound {
+            warn!(
+                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
+                mixnodes_count, layer1, layer2, layer3
+            );
+            return false;
+        }
+
+        if layer3 < lower_bound || layer3 > upper_bound {
+            warn!(
+                "nodes: {}, layer1: {}, layer2: {}, layer3: {}",
+                mixnodes_count, layer1, layer2, layer3
+            );
+            return false;
+        }
+
+        true
+    }
+
+    async fn use_rotated_sphinx_keys(&mut self, mut topology: NymTopology) -> NymTopology {
+        if let Some(rotated_sphinx_keys) = &mut self.rotated_sphinx_keys {
+            rotated_sphinx_keys
+                .update_topology(&self.validator_client, &mut topology)
+                .await;
+        }
+        topology
+    }
+
+    async fn get_current_compatible_topology(&self) -> Option<NymTopology> {
+        // TODO: optimization for the future:
+        // only refresh mixnodes on timer and refresh gateways only when
+        // we have to send to a new, unknown, gateway
+
+        let (mixnodes, mut annotations) = if self.node_annotations {
+            let mixnodes = match self
+                .validator_client
+                .get_cached_active_mixnodes_detailed()
+                .await
+            {
+                Err(err) => {
+                    error!("failed to get network mixnodes - {err}");
+                    return None;
+                }
+                Ok(mixes) => mixes,
+            };
+
+            // families and performance of the nodes are used for choosing the routes
+            let annotations = mixnodes
+                .iter()
+                .map(|mix| (mix.mix_id(), (mix.family.clone(), mix.performance)))
+                .collect::<HashMap<_, _>>();
+            let mixnodes = mixnodes
+                .into_iter()
+                .map(|mix| mix.mixnode_details)
+                .collect();
+            (mixnodes, annotations)
+        } else {
+            match self.validator_client.get_cached_active_mixnodes().await {
+                Err(err) => {
+                    error!("failed to get network mixnodes - {err}");
+                    return None;
+                }
+                Ok(mixes) => (mixes, HashMap::new()),
+            }
+        };
+
+        let gateways = match self.validator_client.get_cached_gateways().await {
+            Err(err) => {
+                error!("failed to get network gateways - {err}");
+                return None;
+            }
+            Ok(gateways) => gateways,
+        };
+
+        let mut topology = nym_topology_from_detailed(mixnodes, gateways)
+            .filter_system_version(&self.client_version);
+        for mix in topology.mixnodes_mut() {
+            if let Some((family, performance)) = annotations.remove(&mix.mix_id) {
+                mix.family = family;
+                mix.performance = Some(performance);
+            }
+        }
+
+        if !self.check_layer_distribution(&topology) {
+            warn!("The current filtered active topology has extremely skewed layer distribution. It cannot be used.");
+            None
+        } else {
+            Some(topology)
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TopologyProvider for NymApiTopologyProvider {
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        match self.get_current_compatible_topology().await {
+            Some(topology) => Some(self.use_rotated_sphinx_keys(topology).await),
+            None => {
+                self.use_next_nym_api();
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StaticTopologyError {
+    #[error("failed to read the topology file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("the topology is not valid json: {0}")]
+    MalformedJson(#[from] serde_json::Error),
+
+    #[error("the topology is not valid toml: {0}")]
+    MalformedToml(#[from] toml::de::Error),
+}
+
+/// Hand-written description of the network, using the same bonds as the mixnet contract.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StaticTopologyDescription {
+    #[serde(default)]
+    pub mixnodes: Vec<MixNodeBond>,
+
+    #[serde(default)]
+    pub gateways: Vec<GatewayBond>,
+}
+
+/// Always provides the same, predefined, topology. Useful for local testnets and simulations,
+/// where there's no nym-api to query.
+#[derive(Debug, Clone)]
+pub struct StaticTopologyProvider {
+    topology: NymTopology,
+}
+
+impl StaticTopologyProvider {
+    pub fn new(topology: NymTopology) -> Self {
+        StaticTopologyProvider { topology }
+    }
+
+    pub fn from_description(description: StaticTopologyDescription) -> Self {
+        Self::new(nym_topology_from_bonds(
+            description.mixnodes,
+            description.gateways,
+        ))
+    }
+
+    pub fn from_json_str(raw: &str) -> Result<Self, StaticTopologyError> {
+        Ok(Self::from_description(serde_json::from_str(raw)?))
+    }
+
+    pub fn from_toml_str(raw: &str) -> Result<Self, StaticTopologyError> {
+        Ok(Self::from_description(toml::from_str(raw)?))
+    }
+
+    /// Loads the topology from the file at the provided path. Files with the `.toml` extension
+    /// are parsed as toml, anything else is expected to be json.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, StaticTopologyError> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&raw),
+            _ => Self::from_json_str(&raw),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TopologyProvider for StaticTopologyProvider {
+    async fn get_new_topology(&mut self) -> Option<NymTopology> {
+        Some(self.topology.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::{Addr, Coin, Gateway, Layer, MixNode};
+
+    fn random_identity() -> String {
+        identity::KeyPair::new(&mut rand::rngs::OsRng)
+            .public_key()
+            .to_base58_string()
+    }
+
+    fn random_sphinx_key() -> String {
+        encryption::KeyPair::new(&mut rand::rngs::OsRng)
+            .public_key()
+            .to_base58_string()
+    }
+
+    fn mixnode(mix_id: u32, layer: Layer) -> MixNodeBond {
+        MixNodeBond::new(
+            mix_id,
+            Addr::unchecked(format!("owner{mix_id}")),
+            Coin::new(100_000_000, "unym"),
+            layer,
+            MixNode {
+                host: "127.0.0.1".to_string(),
+                mix_port: 1789,
+                verloc_port: 1790,
+                http_api_port: 8000,
+                sphinx_key: random_sphinx_key(),
+                identity_key: random_identity(),
+                version: "1.1.0".to_string(),
+            },
+            None,
+            1,
+        )
+    }
+
+    fn gateway() -> GatewayBond {
+        GatewayBond::new(
+            Coin::new(100_000_000, "unym"),
+            Addr::unchecked("gateway-owner"),
+            1,
+            Gateway {
+                host: "127.0.0.1".to_string(),
+                mix_port: 1789,
+                clients_port: 9000,
+                location: "localhost".to_string(),
+                sphinx_key: random_sphinx_key(),
+                identity_key: random_identity(),
+                version: "1.1.0".to_string(),
+            },
+            None,
+        )
+    }
+
+    fn description() -> StaticTopologyDescription {
+        StaticTopologyDescription {
+            mixnodes: vec![
+                mixnode(1, Layer::One),
+                mixnode(2, Layer::Two),
+                mixnode(3, Layer::Three),
+            ],
+            gateways: vec![gateway()],
+        }
+    }
+
+    fn toml_description() -> String {
+        let mut raw = String::new();
+        for (mix_id, layer) in [(1, 1), (2, 2), (3, 3)] {
+            raw += &format!(
+                r#"
+[[mixnodes]]
+mix_id = {mix_id}
+owner = "owner{mix_id}"
+original_pledge = {{ denom = "unym", amount = "100000000" }}
+layer = {layer}
+bonding_height = 1
+is_unbonding = false
+
+[mixnodes.mix_node]
+host = "127.0.0.1"
+mix_port = 1789
+verloc_port = 1790
+http_api_port = 8000
+sphinx_key = "{}"
+identity_key = "{}"
+version = "1.1.0"
+"#,
+                random_sphinx_key(),
+                random_identity()
+            );
+        }
+        raw += &format!(
+            r#"
+[[gateways]]
+pledge_amount = {{ denom = "unym", amount = "100000000" }}
+owner = "gateway-owner"
+block_height = 1
+
+[gateways.gateway]
+host = "127.0.0.1"
+mix_port = 1789
+clients_port = 9000
+location = "localhost"
+sphinx_key = "{}"
+identity_key = "{}"
+version = "1.1.0"
+"#,
+            random_sphinx_key(),
+            random_identity()
+        );
+        raw
+    }
+
+    async fn assert_full_topology(mut provider: StaticTopologyProvider) {
+        let topology = provider.get_new_topology().await.unwrap();
+        for layer in 1..=3 {
+            assert_eq!(topology.mixes()[&layer].len(), 1);
+            assert_eq!(topology.mixes()[&layer][0].mix_id, layer as u32);
+        }
+        assert_eq!(topology.gateways().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn topology_can_be_provided_in_json() {
+        let raw = serde_json::to_string(&description()).unwrap();
+        assert_full_topology(StaticTopologyProvider::from_json_str(&raw).unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn topology_can_be_provided_in_toml() {
+        let raw = toml_description();
+        assert_full_topology(StaticTopologyProvider::from_toml_str(&raw).unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn topology_file_format_is_chosen_by_its_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let json_path = dir.path().join("topology.json");
+        std::fs::write(&json_path, serde_json::to_string(&description()).unwrap()).unwrap();
+        assert_full_topology(StaticTopologyProvider::from_file(&json_path).unwrap()).await;
+
+        let toml_path = dir.path().join("topology.toml");
+        std::fs::write(&toml_path, toml_description()).unwrap();
+        assert_full_topology(StaticTopologyProvider::from_file(&toml_path).unwrap()).await;
+
+        // anything that's not explicitly toml is treated as json
+        let other_path = dir.path().join("topology");
+        std::fs::write(&other_path, toml_description()).unwrap();
+        assert!(matches!(
+            StaticTopologyProvider::from_file(&other_path),
+            Err(StaticTopologyError::MalformedJson(_))
+        ));
+
+        assert!(matches!(
+            StaticTopologyProvider::from_file(dir.path().join("missing.json")),
+            Err(StaticTopologyError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn malformed_topology_is_rejected() {
+        assert!(matches!(
+            StaticTopologyProvider::from_json_str(r#"{"mixnodes": ["#),
+            Err(StaticTopologyError::MalformedJson(_))
+        ));
+        assert!(matches!(
+            StaticTopologyProvider::from_toml_str("[[mixnodes]\nmix_id = 1"),
+            Err(StaticTopologyError::MalformedToml(_))
+        ));
+
+        // the nodes have to be complete
+        assert!(matches!(
+            StaticTopologyProvider::from_json_str(r#"{"mixnodes": [{"mix_id": 1}]}"#),
+            Err(StaticTopologyError::MalformedJson(_))
+        ));
+
+        // and assigned to one of the existing layers
+        let raw = serde_json::to_string(&description())
+            .unwrap()
+            .replace(r#""layer":3"#, r#""layer":4"#);
+        assert!(matches!(
+            StaticTopologyProvider::from_json_str(&raw),
+            Err(StaticTopologyError::MalformedJson(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn nodes_with_invalid_keys_are_skipped() {
+        let mut description = description();
+        description.mixnodes[0].mix_node.sphinx_key = "not a key".to_string();
+        description.gateways[0].gateway.identity_key = "not a key either".to_string();
+
+        let mut provider = StaticTopologyProvider::from_description(description);
+        let topology = provider.get_new_topology().await.unwrap();
+        assert!(topology.mixes().get(&1).map_or(true, Vec::is_empty));
+        assert_eq!(topology.num_mixnodes(), 2);
+        assert!(topology.gateways().is_empty());
+    }
+
+    #[tokio::test]
+    async fn empty_description_gives_an_empty_topology() {
+        let mut provider = StaticTopologyProvider::from_json_str("{}").unwrap();
+        let topology = provider.get_new_topology().await.unwrap();
+        assert_eq!(topology.num_mixnodes(), 0);
+        assert!(topology.gateways().is_empty());
+    }
+}
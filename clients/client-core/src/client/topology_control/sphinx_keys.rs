@@ -0,0 +1,191 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use log::*;
+use std::collections::HashMap;
+use topology::sphinx_key::SignedSphinxKey;
+use topology::NymTopology;
+use validator_client::models::MixNodeSphinxKeys;
+use validator_client::NymApiClient;
+
+fn current_unix_timestamp() -> u64 {
+    time::OffsetDateTime::now_utc().unix_timestamp() as u64
+}
+
+/// Keeps track of the sphinx keys announced by the mixnodes, as collected by the nym-api,
+/// so that the packets could be constructed with the rotated keys rather than the keys
+/// the nodes have bonded with.
+pub(super) struct RotatedSphinxKeys {
+    announced: HashMap<String, Vec<SignedSphinxKey>>,
+}
+
+impl RotatedSphinxKeys {
+    pub(super) fn new() -> Self {
+        RotatedSphinxKeys {
+            announced: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, announced: Vec<MixNodeSphinxKeys>) {
+        self.announced = announced
+            .into_iter()
+            .map(|node| (node.identity_key, node.keys))
+            .collect();
+    }
+
+    /// Switches the mixnodes in the topology to their current announced keys, assuming they have
+    /// been signed with the nodes' identities. Any node that doesn't announce any valid keys
+    /// keeps on using its bonded key.
+    fn apply(&self, topology: &mut NymTopology, unix_timestamp: u64) {
+        for node in topology.mixnodes_mut() {
+            let Some(announced) = self.announced.get(&node.identity_key.to_base58_string()) else {
+                continue;
+            };
+            if let Err(err) = node.apply_announced_sphinx_keys(announced, unix_timestamp) {
+                warn!(
+                    "mixnode {} has announced an invalid sphinx key - {err}",
+                    node.identity_key.to_base58_string()
+                )
+            }
+        }
+    }
+
+    pub(super) async fn update_topology(
+        &mut self,
+        nym_api_client: &NymApiClient,
+        topology: &mut NymTopology,
+    ) {
+        match nym_api_client.get_cached_mixnodes_sphinx_keys().await {
+            Ok(announced) => self.update(announced),
+            // keep on using whatever we knew before, the keys are announced well in advance anyway
+            Err(err) => warn!("failed to get the announced sphinx keys of the mixnodes - {err}"),
+        }
+        self.apply(topology, current_unix_timestamp());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+    use nymsphinx::params::SphinxKeyRotation;
+    use nymsphinx::Node as SphinxNode;
+    use topology::mix;
+
+    fn mixnode(identity_key: identity::PublicKey) -> mix::Node {
+        let mut rng = rand::rngs::OsRng;
+        mix::Node {
+            mix_id: 1,
+            owner: "owner".to_string(),
+            host: "127.0.0.1".parse().unwrap(),
+            mix_host: "127.0.0.1:1789".parse().unwrap(),
+            http_api_port: 8000,
+            identity_key,
+            sphinx_key: *encryption::KeyPair::new(&mut rng).public_key(),
+            sphinx_key_epoch: None,
+            layer: Layer::One,
+            version: "1.1.0".to_string(),
+            family: None,
+            performance: None,
+        }
+    }
+
+    fn announced(
+        identity_key: &identity::PublicKey,
+        keys: Vec<SignedSphinxKey>,
+    ) -> MixNodeSphinxKeys {
+        MixNodeSphinxKeys {
+            mix_id: 1,
+            identity_key: identity_key.to_base58_string(),
+            keys,
+        }
+    }
+
+    #[test]
+    fn topology_uses_the_announced_keys() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        let rotated_key = encryption::KeyPair::new(&mut rng);
+        let now = current_unix_timestamp();
+        let keys = vec![SignedSphinxKey::new(
+            7,
+            now - 10,
+            now + 3600 * 24,
+            rotated_key.public_key(),
+            identity_keys.private_key(),
+        )];
+
+        let mut topology = NymTopology::new(
+            [(1, vec![mixnode(*identity_keys.public_key())])].into(),
+            Vec::new(),
+        );
+        let mut rotated_keys = RotatedSphinxKeys::new();
+        rotated_keys.update(vec![announced(identity_keys.public_key(), keys)]);
+        rotated_keys.apply(&mut topology, now);
+
+        let node = &topology.mixes()[&1][0];
+        assert_eq!(node.sphinx_key_epoch, Some(7));
+        assert_eq!(&node.sphinx_key, rotated_key.public_key());
+
+        // and the packets constructed through it let the node know which key has been used
+        let sphinx_node = SphinxNode::from(node);
+        assert_eq!(
+            SphinxKeyRotation::from_node_address(&sphinx_node.address),
+            SphinxKeyRotation::from_key_epoch(Some(7))
+        );
+    }
+
+    #[test]
+    fn keys_not_signed_by_the_node_are_ignored() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        let impostor = identity::KeyPair::new(&mut rng);
+        let now = current_unix_timestamp();
+        let keys = vec![SignedSphinxKey::new(
+            7,
+            now - 10,
+            now + 3600 * 24,
+            encryption::KeyPair::new(&mut rng).public_key(),
+            impostor.private_key(),
+        )];
+
+        let node = mixnode(*identity_keys.public_key());
+        let bonded_key = node.sphinx_key;
+        let mut topology = NymTopology::new([(1, vec![node])].into(), Vec::new());
+        let mut rotated_keys = RotatedSphinxKeys::new();
+        rotated_keys.update(vec![announced(identity_keys.public_key(), keys)]);
+        rotated_keys.apply(&mut topology, now);
+
+        let node = &topology.mixes()[&1][0];
+        assert_eq!(node.sphinx_key_epoch, None);
+        assert_eq!(node.sphinx_key, bonded_key);
+        assert!(SphinxKeyRotation::from_node_address(&SphinxNode::from(node).address).is_unknown());
+    }
+
+    #[test]
+    fn nodes_without_announced_keys_keep_their_bonded_keys() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        let other_identity = identity::KeyPair::new(&mut rng);
+        let now = current_unix_timestamp();
+        let keys = vec![SignedSphinxKey::new(
+            7,
+            now - 10,
+            now + 3600 * 24,
+            encryption::KeyPair::new(&mut rng).public_key(),
+            other_identity.private_key(),
+        )];
+
+        let node = mixnode(*identity_keys.public_key());
+        let bonded_key = node.sphinx_key;
+        let mut topology = NymTopology::new([(1, vec![node])].into(), Vec::new());
+        let mut rotated_keys = RotatedSphinxKeys::new();
+        rotated_keys.update(vec![announced(other_identity.public_key(), keys)]);
+        rotated_keys.apply(&mut topology, now);
+
+        let node = &topology.mixes()[&1][0];
+        assert_eq!(node.sphinx_key_epoch, None);
+        assert_eq!(node.sphinx_key, bonded_key);
+    }
+}
@@ -3,7 +3,7 @@
 
 use config::defaults::NymNetworkDetails;
 use config::{NymConfig, OptionalSet, DB_FILE_NAME};
-use nymsphinx::params::PacketSize;
+use nymsphinx::params::{PacketSize, PacketType};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -53,6 +53,9 @@ const DEFAULT_MAXIMUM_REPLY_SURB_AGE: Duration = Duration::from_secs(12 * 60 * 6
 // 24 hours
 const DEFAULT_MAXIMUM_REPLY_KEY_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
+// clients/client-core/src/client/replies/reply_storage/mod.rs
+const DEFAULT_REPLY_SURB_STORAGE_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
 }
@@ -61,6 +64,13 @@ pub trait ClientCoreConfigTrait {
     fn get_gateway_endpoint(&self) -> &GatewayEndpointConfig;
 }
 
+/// Configuration of a client implementation wrapping the common base [`Config`].
+pub trait ClientImplementationConfig: NymConfig + Clone {
+    fn base(&self) -> &Config<Self>;
+
+    fn base_mut(&mut self) -> &mut Config<Self>;
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config<T> {
@@ -178,6 +188,14 @@ impl<T> Config<T> {
         self.client.gateway_endpoint.gateway_id = id.into();
     }
 
+    pub fn with_standby_gateway_endpoints(&mut self, standby_gateways: Vec<GatewayEndpointConfig>) {
+        self.client.standby_gateways = standby_gateways;
+    }
+
+    pub fn with_denied_mixnodes(&mut self, denied_mixnodes: Vec<String>) {
+        self.client.denied_mixnodes = denied_mixnodes;
+    }
+
     pub fn with_custom_nyxd(mut self, urls: Vec<Url>) -> Self {
         self.client.nyxd_urls = urls;
         self
@@ -287,6 +305,14 @@ impl<T> Config<T> {
         &self.client.gateway_endpoint
     }
 
+    pub fn get_standby_gateway_endpoints(&self) -> &[GatewayEndpointConfig] {
+        &self.client.standby_gateways
+    }
+
+    pub fn get_denied_mixnodes(&self) -> &[String] {
+        &self.client.denied_mixnodes
+    }
+
     pub fn get_database_path(&self) -> PathBuf {
         self.client.database_path.clone()
     }
@@ -352,6 +378,10 @@ impl<T> Config<T> {
         self.debug.use_extended_packet_size
     }
 
+    pub fn get_packet_type(&self) -> PacketType {
+        self.debug.packet_type
+    }
+
     pub fn get_minimum_reply_surb_storage_threshold(&self) -> usize {
         self.debug.minimum_reply_surb_storage_threshold
     }
@@ -481,6 +511,15 @@ pub struct Client<T> {
     /// Information regarding how the client should send data to gateway.
     gateway_endpoint: GatewayEndpointConfig,
 
+    /// Additional gateways the client is going to register with and fail over to, in the specified
+    /// order, if its primary gateway becomes unreachable or disappears from the network topology.
+    #[serde(default)]
+    standby_gateways: Vec<GatewayEndpointConfig>,
+
+    /// Identities of the mixnodes that are never going to be used in any of the routes.
+    #[serde(default)]
+    denied_mixnodes: Vec<String>,
+
     /// Path to the database containing bandwidth credentials of this client.
     database_path: PathBuf,
 
@@ -531,6 +570,8 @@ impl<T: NymConfig> Default for Client<T> {
             gateway_shared_key_file: Default::default(),
             ack_key_file: Default::default(),
             gateway_endpoint: Default::default(),
+            standby_gateways: Vec::new(),
+            denied_mixnodes: Vec::new(),
             database_path: Default::default(),
             reply_surb_database_path: Default::default(),
             nym_root_directory: T::default_root_directory(),
@@ -633,6 +674,10 @@ pub struct DebugConfig {
     #[serde(with = "humantime_serde")]
     pub topology_resolution_timeout: Duration,
 
+    /// Controls whether the client retrieves the rotated sphinx keys of the mixnodes from the nym-api
+    /// and constructs the packets with them, rather than with the keys the nodes have bonded with.
+    pub use_rotated_sphinx_keys: bool,
+
     /// Controls whether the dedicated loop cover traffic stream should be enabled.
     /// (and sending packets, on average, every [Self::loop_cover_traffic_average_delay])
     pub disable_loop_cover_traffic_stream: bool,
@@ -644,6 +689,10 @@ pub struct DebugConfig {
     /// Controls whether the sent sphinx packet use a NON-DEFAULT bigger size.
     pub use_extended_packet_size: Option<ExtendedPacketSize>,
 
+    /// Controls the format of the packets carrying the actual messages, i.e. sphinx or outfox.
+    /// Note that acknowledgements, replies and cover traffic are always sent as sphinx packets.
+    pub packet_type: PacketType,
+
     /// Defines the minimum number of reply surbs the client wants to keep in its storage at all times.
     /// It can only allow to go below that value if its to request additional reply surbs.
     pub minimum_reply_surb_storage_threshold: usize,
@@ -674,6 +723,12 @@ pub struct DebugConfig {
     /// This is going to be superseded by key rotation once implemented.
     #[serde(with = "humantime_serde")]
     pub maximum_reply_key_age: Duration,
+
+    /// Defines how often the reply surbs, reply keys and sender tags are persisted while the client
+    /// is running, so that they could be recovered if it goes down without a graceful shutdown.
+    /// Setting it to zero disables the checkpointing, so the data is only saved on shutdown.
+    #[serde(with = "humantime_serde")]
+    pub reply_surb_storage_checkpoint_interval: Duration,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -696,9 +751,11 @@ impl Default for DebugConfig {
             gateway_response_timeout: DEFAULT_GATEWAY_RESPONSE_TIMEOUT,
             topology_refresh_rate: DEFAULT_TOPOLOGY_REFRESH_RATE,
             topology_resolution_timeout: DEFAULT_TOPOLOGY_RESOLUTION_TIMEOUT,
+            use_rotated_sphinx_keys: true,
             disable_loop_cover_traffic_stream: false,
             disable_main_poisson_packet_distribution: false,
             use_extended_packet_size: None,
+            packet_type: PacketType::Sphinx,
             minimum_reply_surb_storage_threshold: DEFAULT_MINIMUM_REPLY_SURB_STORAGE_THRESHOLD,
             maximum_reply_surb_storage_threshold: DEFAULT_MAXIMUM_REPLY_SURB_STORAGE_THRESHOLD,
             minimum_reply_surb_request_size: DEFAULT_MINIMUM_REPLY_SURB_REQUEST_SIZE,
@@ -707,6 +764,7 @@ impl Default for DebugConfig {
             maximum_reply_surb_waiting_period: DEFAULT_MAXIMUM_REPLY_SURB_WAITING_PERIOD,
             maximum_reply_surb_age: DEFAULT_MAXIMUM_REPLY_SURB_AGE,
             maximum_reply_key_age: DEFAULT_MAXIMUM_REPLY_KEY_AGE,
+            reply_surb_storage_checkpoint_interval: DEFAULT_REPLY_SURB_STORAGE_CHECKPOINT_INTERVAL,
         }
     }
 }
@@ -2,10 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Config;
-use config::NymConfig;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientKeyPathfinder {
     pub identity_private_key: PathBuf,
     pub identity_public_key: PathBuf,
@@ -29,7 +28,7 @@ impl ClientKeyPathfinder {
         }
     }
 
-    pub fn new_from_config<T: NymConfig>(config: &Config<T>) -> Self {
+    pub fn new_from_config<T>(config: &Config<T>) -> Self {
         ClientKeyPathfinder {
             identity_private_key: config.get_private_identity_key_file(),
             identity_public_key: config.get_public_identity_key_file(),
@@ -85,6 +84,17 @@ impl ClientKeyPathfinder {
     pub fn ack_key(&self) -> &Path {
         &self.ack_key
     }
+
+    /// Directory, next to the shared key of the primary gateway, holding the keys derived with
+    /// the standby gateways.
+    pub fn standby_gateway_shared_keys_dir(&self) -> PathBuf {
+        self.gateway_shared_key.with_file_name("standby_gateways")
+    }
+
+    pub fn standby_gateway_shared_key(&self, gateway_id: &str) -> PathBuf {
+        self.standby_gateway_shared_keys_dir()
+            .join(format!("{gateway_id}.pem"))
+    }
 }
 
 fn file_exists(path: &Path) -> Option<PathBuf> {
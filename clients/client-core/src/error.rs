@@ -55,6 +55,17 @@ pub enum ClientCoreError {
     #[error("The address of the gateway is unknown - did you run init?")]
     GatwayAddressUnknown,
 
+    #[error("The client is already using gateway {0}")]
+    AlreadyUsingGateway(String),
+
+    #[error(
+        "Failed to persist the new gateway configuration, the previous one has been kept - {0}"
+    )]
+    FailedToPersistGatewaySwitch(std::io::Error),
+
+    #[error("The mix traffic controller is not running")]
+    MixTrafficControllerNotRunning,
+
     #[error("failed to register receiver for reconstructed mixnet messages")]
     FailedToRegisterReceiver,
 
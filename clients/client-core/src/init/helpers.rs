@@ -11,15 +11,19 @@ use crypto::asymmetric::identity;
 use gateway_client::GatewayClient;
 use gateway_requests::registration::handshake::SharedKeys;
 use rand::{seq::SliceRandom, thread_rng};
-use std::{sync::Arc, time::Duration};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tap::TapFallible;
 use topology::{filter::VersionFilterable, gateway};
 use url::Url;
 
-pub(super) async fn query_gateway_details(
+pub(super) async fn query_gateways(
     validator_servers: Vec<Url>,
-    chosen_gateway_id: Option<identity::PublicKey>,
-) -> Result<gateway::Node, ClientCoreError> {
+) -> Result<Vec<gateway::Node>, ClientCoreError> {
     let nym_api = validator_servers
         .choose(&mut thread_rng())
         .ok_or(ClientCoreError::ListOfNymApisIsEmpty)?;
@@ -32,19 +36,32 @@ pub(super) async fn query_gateway_details(
         .filter_map(|gateway| gateway.try_into().ok())
         .collect::<Vec<gateway::Node>>();
 
-    let filtered_gateways = valid_gateways.filter_by_version(env!("CARGO_PKG_VERSION"));
+    Ok(valid_gateways.filter_by_version(env!("CARGO_PKG_VERSION")))
+}
+
+pub(super) async fn query_gateway_details(
+    validator_servers: Vec<Url>,
+    chosen_gateway_id: Option<identity::PublicKey>,
+) -> Result<gateway::Node, ClientCoreError> {
+    let filtered_gateways = query_gateways(validator_servers).await?;
+    choose_gateway(&filtered_gateways, chosen_gateway_id)
+}
 
+pub(super) fn choose_gateway(
+    gateways: &[gateway::Node],
+    chosen_gateway_id: Option<identity::PublicKey>,
+) -> Result<gateway::Node, ClientCoreError> {
     // if we have chosen particular gateway - use it, otherwise choose a random one.
     // (remember that in active topology all gateways have at least 100 reputation so should
     // be working correctly)
     if let Some(gateway_id) = chosen_gateway_id {
-        filtered_gateways
+        gateways
             .iter()
             .find(|gateway| gateway.identity_key == gateway_id)
             .ok_or_else(|| ClientCoreError::NoGatewayWithId(gateway_id.to_string()))
             .cloned()
     } else {
-        filtered_gateways
+        gateways
             .choose(&mut rand::thread_rng())
             .ok_or(ClientCoreError::NoGatewaysOnNetwork)
             .cloned()
@@ -86,3 +103,152 @@ where
         .store_keys(&pathfinder)
         .tap_err(|err| log::error!("Failed to generate keys: {err}"))?)
 }
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(suffix);
+    path.into()
+}
+
+pub(super) fn temporary_path(path: &Path) -> PathBuf {
+    with_suffix(path, "tmp")
+}
+
+// moves the temporary file into its destination, returning the path to the backup of the
+// replaced file, if there was any
+fn replace_file(temporary: &Path, destination: &Path) -> io::Result<Option<PathBuf>> {
+    let backup = if destination.exists() {
+        let backup = with_suffix(destination, "bak");
+        fs::copy(destination, &backup)?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    if let Err(err) = fs::rename(temporary, destination) {
+        if let Some(backup) = backup {
+            let _ = fs::remove_file(backup);
+        }
+        return Err(err);
+    }
+    Ok(backup)
+}
+
+/// Moves each of the temporary files into its destination. If any of the moves fails,
+/// the already replaced files are restored, so that either all or none of them end up changed.
+pub(super) fn replace_files(files: &[(&Path, &Path)]) -> io::Result<()> {
+    let mut replaced = Vec::with_capacity(files.len());
+    let mut result = Ok(());
+    for (temporary, destination) in files {
+        match replace_file(temporary, destination) {
+            Ok(backup) => replaced.push((destination, backup)),
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        }
+    }
+
+    if result.is_err() {
+        for (destination, backup) in replaced.into_iter().rev() {
+            let restored = match backup {
+                Some(backup) => fs::rename(backup, destination),
+                None => fs::remove_file(destination),
+            };
+            if let Err(err) = restored {
+                log::error!("Failed to restore {destination:?} - {err}");
+            }
+        }
+        for (temporary, _) in files {
+            let _ = fs::remove_file(temporary);
+        }
+    } else {
+        for (_, backup) in replaced {
+            if let Some(backup) = backup {
+                let _ = fs::remove_file(backup);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::write(path, content).unwrap()
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn all_files_get_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        write(&first, "old first");
+        write(&temporary_path(&first), "new first");
+        write(&temporary_path(&second), "new second");
+
+        replace_files(&[
+            (&temporary_path(&first), &first),
+            (&temporary_path(&second), &second),
+        ])
+        .unwrap();
+
+        assert_eq!(read(&first), "new first");
+        assert_eq!(read(&second), "new second");
+        assert!(!temporary_path(&first).exists());
+        assert!(!temporary_path(&second).exists());
+        assert!(!with_suffix(&first, "bak").exists());
+    }
+
+    #[test]
+    fn already_replaced_files_are_restored_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        let third = dir.path().join("third");
+        write(&first, "old first");
+        write(&second, "old second");
+        write(&temporary_path(&first), "new first");
+        write(&temporary_path(&third), "new third");
+        // the temporary file for the second destination was never created, so moving it fails
+
+        let res = replace_files(&[
+            (&temporary_path(&first), &first),
+            (&temporary_path(&second), &second),
+            (&temporary_path(&third), &third),
+        ]);
+        assert!(res.is_err());
+
+        assert_eq!(read(&first), "old first");
+        assert_eq!(read(&second), "old second");
+        assert!(!third.exists());
+        assert!(!with_suffix(&first, "bak").exists());
+        assert!(!with_suffix(&second, "bak").exists());
+        assert!(!temporary_path(&first).exists());
+        assert!(!temporary_path(&third).exists());
+    }
+
+    #[test]
+    fn newly_created_files_are_removed_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        write(&temporary_path(&first), "new first");
+
+        let res = replace_files(&[
+            (&temporary_path(&first), &first),
+            (&temporary_path(&second), &second),
+        ]);
+        assert!(res.is_err());
+        assert!(!first.exists());
+        assert!(!second.exists());
+    }
+}
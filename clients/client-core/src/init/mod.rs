@@ -4,21 +4,27 @@
 //! Collection of initialization steps used by client implementations
 
 use std::fmt::Display;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
 
+use gateway_requests::registration::handshake::SharedKeys;
 use nymsphinx::addressing::{clients::Recipient, nodes::NodeIdentity};
 use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
 use serde::Serialize;
 use tap::TapFallible;
 
 use config::NymConfig;
 use crypto::asymmetric::{encryption, identity};
+use topology::{gateway, NymTopology};
 use url::Url;
 
 use crate::client::key_manager::KeyManager;
 use crate::{
     config::{
-        persistence::key_pathfinder::ClientKeyPathfinder, ClientCoreConfigTrait, Config,
-        GatewayEndpointConfig,
+        persistence::key_pathfinder::ClientKeyPathfinder, ClientCoreConfigTrait,
+        ClientImplementationConfig, Config, GatewayEndpointConfig,
     },
     error::ClientCoreError,
 };
@@ -82,6 +88,28 @@ pub async fn register_with_gateway(
     let gateway = helpers::query_gateway_details(nym_api_endpoints, chosen_gateway_id).await?;
     log::debug!("Querying gateway gives: {}", gateway);
 
+    register_with_chosen_gateway(key_manager, gateway).await
+}
+
+/// Authenticate and register with a gateway from the provided topology instead of the gateways
+/// announced by the nym-api, such as when running against a hand-written topology.
+/// Either pick one at random or use the chosen one if it's in the topology.
+/// The shared key is added to the supplied `KeyManager` and the endpoint details are returned.
+pub async fn register_with_gateway_from_topology(
+    key_manager: &mut KeyManager,
+    topology: &NymTopology,
+    chosen_gateway_id: Option<identity::PublicKey>,
+) -> Result<GatewayEndpointConfig, ClientCoreError> {
+    let gateway = helpers::choose_gateway(topology.gateways(), chosen_gateway_id)?;
+    log::debug!("Chosen gateway from the topology: {}", gateway);
+
+    register_with_chosen_gateway(key_manager, gateway).await
+}
+
+async fn register_with_chosen_gateway(
+    key_manager: &mut KeyManager,
+    gateway: gateway::Node,
+) -> Result<GatewayEndpointConfig, ClientCoreError> {
     let our_identity = key_manager.identity_keypair();
 
     // Establish connection, authenticate and generate keys for talking with the gateway
@@ -144,6 +172,146 @@ where
     Ok(gateway.into())
 }
 
+/// Perform a fresh handshake with a different gateway using the existing keys of the client.
+/// Either use the chosen gateway, or pick a random one, other than the current one, from the
+/// gateways available in the nym-api. Nothing is persisted and the supplied `KeyManager` is not
+/// modified, so that the switch could be simply abandoned on any failure.
+pub async fn register_with_new_gateway(
+    key_manager: &KeyManager,
+    nym_api_endpoints: Vec<Url>,
+    current_gateway_id: &str,
+    new_gateway_id: Option<identity::PublicKey>,
+) -> Result<(GatewayEndpointConfig, Arc<SharedKeys>), ClientCoreError> {
+    if let Some(new_gateway_id) = new_gateway_id {
+        if new_gateway_id.to_base58_string() == current_gateway_id {
+            return Err(ClientCoreError::AlreadyUsingGateway(
+                current_gateway_id.to_string(),
+            ));
+        }
+    }
+
+    let gateway = match new_gateway_id {
+        Some(gateway_id) => {
+            helpers::query_gateway_details(nym_api_endpoints, Some(gateway_id)).await?
+        }
+        None => {
+            let gateways = helpers::query_gateways(nym_api_endpoints).await?;
+            gateways
+                .iter()
+                .filter(|gateway| gateway.identity_key.to_base58_string() != current_gateway_id)
+                .collect::<Vec<_>>()
+                .choose(&mut rand::thread_rng())
+                .map(|gateway| (*gateway).clone())
+                .ok_or(ClientCoreError::NoGatewaysOnNetwork)?
+        }
+    };
+    log::debug!("Switching to gateway: {}", gateway);
+
+    // Establish connection, authenticate and generate keys for talking with the new gateway
+    let shared_keys =
+        helpers::register_with_gateway(&gateway, key_manager.identity_keypair()).await?;
+
+    Ok((gateway.into(), shared_keys))
+}
+
+/// Store the shared key derived with the new gateway alongside the configuration pointing to it,
+/// so that either both or neither of them get replaced. The updated configuration is expected
+/// to be written by `write_config` to the provided (temporary) path.
+pub fn persist_gateway_switch<F>(
+    shared_key: &SharedKeys,
+    shared_key_file: &Path,
+    config_file: &Path,
+    write_config: F,
+) -> Result<(), ClientCoreError>
+where
+    F: FnOnce(&Path) -> io::Result<()>,
+{
+    let temporary_key_file = helpers::temporary_path(shared_key_file);
+    let temporary_config_file = helpers::temporary_path(config_file);
+
+    let written = pemstore::store_key(shared_key, &temporary_key_file)
+        .and_then(|_| write_config(&temporary_config_file));
+    if let Err(err) = written {
+        let _ = std::fs::remove_file(&temporary_key_file);
+        let _ = std::fs::remove_file(&temporary_config_file);
+        return Err(ClientCoreError::FailedToPersistGatewaySwitch(err));
+    }
+
+    helpers::replace_files(&[
+        (&temporary_key_file, shared_key_file),
+        (&temporary_config_file, config_file),
+    ])
+    .map_err(ClientCoreError::FailedToPersistGatewaySwitch)
+}
+
+/// Move an already initialised client to a different gateway while keeping its identity,
+/// encryption and ack keys. A fresh handshake is performed with the new gateway and, only if it
+/// succeeds, the new shared key and the configuration are replaced together. On any failure the
+/// client is left with its previous gateway.
+///
+/// Note that the gateway is part of the client address, so the correspondents of the client
+/// have to learn its new address.
+pub async fn switch_gateway_from_config<T, F>(
+    new_gateway_id: Option<identity::PublicKey>,
+    config: &Config<T>,
+    config_file: &Path,
+    write_config: F,
+) -> Result<GatewayEndpointConfig, ClientCoreError>
+where
+    T: NymConfig,
+    F: FnOnce(&GatewayEndpointConfig, &Path) -> io::Result<()>,
+{
+    let pathfinder = ClientKeyPathfinder::new_from_config(config);
+    let key_manager = KeyManager::load_keys(&pathfinder)
+        .tap_err(|err| log::error!("Failed to load the existing client keys: {err}"))?;
+
+    let (gateway, shared_key) = register_with_new_gateway(
+        &key_manager,
+        config.get_nym_api_endpoints(),
+        &config.get_gateway_id(),
+        new_gateway_id,
+    )
+    .await?;
+
+    persist_gateway_switch(
+        &shared_key,
+        &config.get_gateway_shared_key_file(),
+        config_file,
+        |path| write_config(&gateway, path),
+    )?;
+
+    Ok(gateway)
+}
+
+/// Move the client described by the provided configuration to a different gateway, as described
+/// in [`switch_gateway_from_config`], using its default configuration file. On success the
+/// provided configuration is updated to use the new gateway.
+pub async fn switch_gateway<C>(
+    config: &mut C,
+    new_gateway_id: Option<identity::PublicKey>,
+) -> Result<(), ClientCoreError>
+where
+    C: ClientImplementationConfig,
+{
+    // the identity, encryption and ack keys are kept, only the gateway shared key and the
+    // gateway configuration are replaced, and only if the whole process succeeds
+    let config_file = C::default_config_file_path(Some(&config.base().get_id()));
+    let gateway = switch_gateway_from_config(
+        new_gateway_id,
+        config.base(),
+        &config_file,
+        |gateway, path| {
+            let mut updated = config.clone();
+            updated.base_mut().with_gateway_endpoint(gateway.clone());
+            updated.save_to_file(Some(path.to_path_buf()))
+        },
+    )
+    .await?;
+
+    config.base_mut().with_gateway_endpoint(gateway);
+    Ok(())
+}
+
 /// Read and reuse the existing gateway configuration from a file that was generate earlier.
 pub fn load_existing_gateway_config<T>(id: &str) -> Result<GatewayEndpointConfig, ClientCoreError>
 where
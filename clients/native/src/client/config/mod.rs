@@ -4,7 +4,7 @@
 use crate::client::config::template::config_template;
 pub use client_core::config::Config as BaseConfig;
 pub use client_core::config::MISSING_VALUE;
-use client_core::config::{ClientCoreConfigTrait, DebugConfig};
+use client_core::config::{ClientCoreConfigTrait, ClientImplementationConfig, DebugConfig};
 use config::defaults::DEFAULT_WEBSOCKET_LISTENING_PORT;
 use config::{NymConfig, OptionalSet};
 use serde::{Deserialize, Serialize};
@@ -36,7 +36,7 @@ impl SocketType {
     }
 }
 
-#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Default, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(flatten)]
@@ -82,6 +82,16 @@ impl ClientCoreConfigTrait for Config {
     }
 }
 
+impl ClientImplementationConfig for Config {
+    fn base(&self) -> &BaseConfig<Self> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseConfig<Self> {
+        &mut self.base
+    }
+}
+
 impl Config {
     pub fn new<S: Into<String>>(id: S) -> Self {
         Config {
@@ -179,7 +189,7 @@ impl Config {
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Socket {
     socket_type: SocketType,
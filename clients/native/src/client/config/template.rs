@@ -55,6 +55,13 @@ database_path = '{{ client.database_path }}'
 # Path to the persistent store for received reply surbs, unused encryption keys and used sender tags.
 reply_surb_database_path = '{{ client.reply_surb_database_path }}'
 
+# Identities of the mixnodes that are never going to be used in any of the routes.
+denied_mixnodes = [
+    {{#each client.denied_mixnodes }}
+        '{{this}}',
+    {{/each}}
+]
+
 ##### additional client config options #####
 
 # A gateway specific, optional, base58 stringified shared key used for
@@ -80,6 +87,15 @@ gateway_owner = '{{ client.gateway_endpoint.gateway_owner }}'
 # Address of the gateway listener to which all client requests should be sent.
 gateway_listener = '{{ client.gateway_endpoint.gateway_listener }}'
 
+{{#each client.standby_gateways }}
+# Gateway the client is going to fail over to if its primary gateway becomes unavailable.
+[[client.standby_gateways]]
+gateway_id = '{{ this.gateway_id }}'
+gateway_owner = '{{ this.gateway_owner }}'
+gateway_listener = '{{ this.gateway_listener }}'
+
+{{/each}}
+
 
 
 ##### socket config options #####
@@ -87,7 +87,6 @@ impl SocketClient {
         client_input: ClientInput,
         client_output: ClientOutput,
         client_state: ClientState,
-        self_address: &Recipient,
         shutdown: task::TaskClient,
     ) {
         info!("Starting websocket listener...");
@@ -104,6 +103,9 @@ impl SocketClient {
         let ClientState {
             shared_lane_queue_lengths,
             reply_controller_sender,
+            self_address,
+            remaining_bandwidth: _,
+            bandwidth_request_sender: _,
         } = client_state;
 
         let websocket_handler = websocket::HandlerBuilder::new(
@@ -143,18 +145,17 @@ impl SocketClient {
             .await?,
         );
 
-        let self_address = base_builder.as_mix_recipient();
         let mut started_client = base_builder.start_base().await?;
         let client_input = started_client.client_input.register_producer();
         let client_output = started_client.client_output.register_consumer();
         let client_state = started_client.client_state;
+        let self_address = client_state.self_address.get();
 
         Self::start_websocket_listener(
             &self.config,
             client_input,
             client_output,
             client_state,
-            &self_address,
             started_client.task_manager.subscribe(),
         );
 
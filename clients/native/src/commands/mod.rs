@@ -12,6 +12,7 @@ use std::error::Error;
 
 pub(crate) mod init;
 pub(crate) mod run;
+pub(crate) mod switch_gateway;
 pub(crate) mod upgrade;
 
 lazy_static! {
@@ -44,6 +45,9 @@ pub(crate) enum Commands {
     /// Try to upgrade the client
     Upgrade(upgrade::Upgrade),
 
+    /// Move the client to a different gateway, keeping its identity
+    SwitchGateway(switch_gateway::SwitchGateway),
+
     /// Generate shell completions
     Completions(ArgShell),
 
@@ -72,6 +76,7 @@ pub(crate) async fn execute(args: &Cli) -> Result<(), Box<dyn Error + Send + Syn
         Commands::Init(m) => init::execute(m).await?,
         Commands::Run(m) => run::execute(m).await?,
         Commands::Upgrade(m) => upgrade::execute(m),
+        Commands::SwitchGateway(m) => switch_gateway::execute(m).await?,
         Commands::Completions(s) => s.generate(&mut Cli::command(), bin_name),
         Commands::GenerateFigSpec => fig_generate(&mut Cli::command(), bin_name),
     }
@@ -0,0 +1,46 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{client::config::Config, error::ClientError};
+use clap::Args;
+use config::NymConfig;
+use crypto::asymmetric::identity;
+use tap::TapFallible;
+
+#[derive(Args, Clone)]
+pub(crate) struct SwitchGateway {
+    /// Id of the nym-mixnet-client we want to move to a different gateway.
+    #[clap(long)]
+    id: String,
+
+    /// Id of the gateway we are going to switch to. If omitted, a random gateway, other than
+    /// the current one, is chosen.
+    #[clap(long)]
+    gateway: Option<identity::PublicKey>,
+}
+
+pub(crate) async fn execute(args: &SwitchGateway) -> Result<(), ClientError> {
+    let id = &args.id;
+
+    let mut config = Config::load_from_file(Some(id)).map_err(|err| {
+        log::error!("Failed to load config for {id}. Are you sure you have run `init` before? (Error was: {err})");
+        ClientError::FailedToLoadConfig(id.to_string())
+    })?;
+
+    println!(
+        "Switching client \"{id}\" away from gateway {}",
+        config.get_base().get_gateway_id()
+    );
+
+    client_core::init::switch_gateway(&mut config, args.gateway)
+        .await
+        .tap_err(|err| {
+            eprintln!("Failed to switch gateway, keeping the previous one\nError: {err}")
+        })?;
+
+    println!("Using gateway: {}", config.get_base().get_gateway_id());
+
+    let address = client_core::init::get_client_address_from_stored_keys(config.get_base())?;
+    println!("\nThe new address of this client is: {address}\n");
+    Ok(())
+}
@@ -10,6 +10,7 @@ use client_core::client::{
     received_buffer::{
         ReceivedBufferMessage, ReceivedBufferRequestSender, ReconstructedMessagesReceiver,
     },
+    self_address::SelfAddress,
 };
 use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
@@ -38,7 +39,7 @@ pub(crate) struct HandlerBuilder {
     msg_input: InputMessageSender,
     client_connection_tx: ConnectionCommandSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: SelfAddress,
     lane_queue_lengths: LaneQueueLengths,
     reply_controller_sender: ReplyControllerSender,
 }
@@ -48,7 +49,7 @@ impl HandlerBuilder {
         msg_input: InputMessageSender,
         client_connection_tx: ConnectionCommandSender,
         buffer_requester: ReceivedBufferRequestSender,
-        self_full_address: &Recipient,
+        self_full_address: SelfAddress,
         lane_queue_lengths: LaneQueueLengths,
         reply_controller_sender: ReplyControllerSender,
     ) -> Self {
@@ -56,7 +57,7 @@ impl HandlerBuilder {
             msg_input,
             client_connection_tx,
             buffer_requester,
-            self_full_address: *self_full_address,
+            self_full_address,
             lane_queue_lengths,
             reply_controller_sender,
         }
@@ -68,7 +69,7 @@ impl HandlerBuilder {
             msg_input: self.msg_input.clone(),
             client_connection_tx: self.client_connection_tx.clone(),
             buffer_requester: self.buffer_requester.clone(),
-            self_full_address: self.self_full_address,
+            self_full_address: self.self_full_address.clone(),
             socket: None,
             received_response_type: Default::default(),
             lane_queue_lengths: self.lane_queue_lengths.clone(),
@@ -81,7 +82,7 @@ pub(crate) struct Handler {
     msg_input: InputMessageSender,
     client_connection_tx: ConnectionCommandSender,
     buffer_requester: ReceivedBufferRequestSender,
-    self_full_address: Recipient,
+    self_full_address: SelfAddress,
     socket: Option<WebSocketStream<TcpStream>>,
     received_response_type: ReceivedResponseType,
     lane_queue_lengths: LaneQueueLengths,
@@ -233,7 +234,7 @@ impl Handler {
     }
 
     fn handle_self_address(&self) -> ServerResponse {
-        ServerResponse::SelfAddress(Box::new(self.self_full_address))
+        ServerResponse::SelfAddress(Box::new(self.self_full_address.get()))
     }
 
     fn handle_closed_connection(&self, connection_id: u64) -> Option<ServerResponse> {
@@ -4,7 +4,7 @@
 use crate::client::config::template::config_template;
 pub use client_core::config::Config as BaseConfig;
 pub use client_core::config::MISSING_VALUE;
-use client_core::config::{ClientCoreConfigTrait, DebugConfig};
+use client_core::config::{ClientCoreConfigTrait, ClientImplementationConfig, DebugConfig};
 use config::defaults::DEFAULT_SOCKS5_LISTENING_PORT;
 use config::{NymConfig, OptionalSet};
 use nymsphinx::addressing::clients::Recipient;
@@ -15,10 +15,13 @@ use std::str::FromStr;
 
 mod template;
 
-const DEFAULT_CONNECTION_START_SURBS: u32 = 20;
-const DEFAULT_PER_REQUEST_SURBS: u32 = 3;
+/// Default number of reply SURBs sent along the request opening a new connection.
+pub const DEFAULT_CONNECTION_START_SURBS: u32 = 20;
 
-#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+/// Default number of reply SURBs sent along each subsequent request of a connection.
+pub const DEFAULT_PER_REQUEST_SURBS: u32 = 3;
+
+#[derive(Debug, Default, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(flatten)]
@@ -67,6 +70,16 @@ impl ClientCoreConfigTrait for Config {
     }
 }
 
+impl ClientImplementationConfig for Config {
+    fn base(&self) -> &BaseConfig<Self> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseConfig<Self> {
+        &mut self.base
+    }
+}
+
 impl Config {
     pub fn new<S: Into<String>>(id: S, provider_mix_address: S) -> Self {
         Config {
@@ -176,7 +189,7 @@ impl Config {
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Socks5 {
     /// The port on which the client will be listening for incoming requests
@@ -214,7 +227,7 @@ impl Default for Socks5 {
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Socks5Debug {
     /// Number of reply SURBs attached to each `Request::Connect` message.
@@ -5,7 +5,7 @@ use crate::client::config::Config;
 use crate::error::Socks5ClientError;
 use crate::socks;
 use crate::socks::{
-    authentication::{AuthenticationMethods, Authenticator, User},
+    authentication::{AuthenticationMethods, Authenticator},
     server::SphinxSocksServer,
 };
 use client_core::client::base_client::{
@@ -19,6 +19,7 @@ use gateway_client::bandwidth::BandwidthController;
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
 use std::error::Error;
+use std::net::SocketAddr;
 use task::{TaskClient, TaskManager};
 
 pub mod config;
@@ -96,51 +97,26 @@ impl NymClient {
         client_input: ClientInput,
         client_output: ClientOutput,
         client_status: ClientState,
-        self_address: Recipient,
         shutdown: TaskClient,
     ) {
-        info!("Starting socks5 listener...");
-        let auth_methods = vec![AuthenticationMethods::NoAuth as u8];
-        let allowed_users: Vec<User> = Vec::new();
-
-        let ClientInput {
-            connection_command_sender,
-            input_sender,
-        } = client_input;
-
-        let ClientOutput {
-            received_buffer_request_sender,
-        } = client_output;
-
-        let ClientState {
-            shared_lane_queue_lengths,
-            reply_controller_sender: _,
-        } = client_status;
-
-        let authenticator = Authenticator::new(auth_methods, allowed_users);
-        let mut sphinx_socks = SphinxSocksServer::new(
-            config.get_listening_port(),
+        // hardcode ip as we (presumably) ONLY want to listen locally. If we change it, we can
+        // just modify the config
+        let listening_address = SocketAddr::from(([127, 0, 0, 1], config.get_listening_port()));
+        let authenticator =
+            Authenticator::new(vec![AuthenticationMethods::NoAuth as u8], Vec::new());
+
+        start_socks5_server(
+            listening_address,
             authenticator,
             config.get_provider_mix_address(),
-            self_address,
-            shared_lane_queue_lengths,
             socks::client::Config::new(
                 config.get_send_anonymously(),
                 config.get_connection_start_surbs(),
                 config.get_per_request_surbs(),
             ),
-            shutdown.clone(),
-        );
-        task::spawn_with_report_error(
-            async move {
-                sphinx_socks
-                    .serve(
-                        input_sender,
-                        received_buffer_request_sender,
-                        connection_command_sender,
-                    )
-                    .await
-            },
+            client_input,
+            client_output,
+            client_status,
             shutdown,
         );
     }
@@ -212,18 +188,17 @@ impl NymClient {
             .await?,
         );
 
-        let self_address = base_builder.as_mix_recipient();
         let mut started_client = base_builder.start_base().await?;
         let client_input = started_client.client_input.register_producer();
         let client_output = started_client.client_output.register_consumer();
         let client_state = started_client.client_state;
+        let self_address = client_state.self_address.get();
 
         Self::start_socks5_listener(
             &self.config,
             client_input,
             client_output,
             client_state,
-            self_address,
             started_client.task_manager.subscribe(),
         );
 
@@ -233,3 +208,58 @@ impl NymClient {
         Ok(started_client.task_manager)
     }
 }
+
+/// Start the socks5 server on top of an already running base client. The server keeps running
+/// until the shutdown is signalled.
+#[allow(clippy::too_many_arguments)]
+pub fn start_socks5_server(
+    listening_address: SocketAddr,
+    authenticator: Authenticator,
+    service_provider: Recipient,
+    client_config: socks::client::Config,
+    client_input: ClientInput,
+    client_output: ClientOutput,
+    client_status: ClientState,
+    shutdown: TaskClient,
+) {
+    info!("Starting socks5 listener...");
+
+    let ClientInput {
+        connection_command_sender,
+        input_sender,
+    } = client_input;
+
+    let ClientOutput {
+        received_buffer_request_sender,
+    } = client_output;
+
+    let ClientState {
+        shared_lane_queue_lengths,
+        reply_controller_sender: _,
+        self_address,
+        remaining_bandwidth: _,
+        bandwidth_request_sender: _,
+    } = client_status;
+
+    let mut sphinx_socks = SphinxSocksServer::new(
+        listening_address,
+        authenticator,
+        service_provider,
+        self_address,
+        shared_lane_queue_lengths,
+        client_config,
+        shutdown.clone(),
+    );
+    task::spawn_with_report_error(
+        async move {
+            sphinx_socks
+                .serve(
+                    input_sender,
+                    received_buffer_request_sender,
+                    connection_command_sender,
+                )
+                .await
+        },
+        shutdown,
+    );
+}
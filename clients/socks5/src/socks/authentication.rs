@@ -1,5 +1,5 @@
 /// Client Authentication Methods
-pub(crate) enum AuthenticationMethods {
+pub enum AuthenticationMethods {
     /// No Authentication
     NoAuth = 0x00,
     // GssApi = 0x01, // question to DH: why is this commented?
@@ -19,13 +19,13 @@ pub struct User {
 #[derive(Clone, Debug)]
 /// Allows configuration of access methods (no auth required, username/pass, reject all)
 /// and keeps a list of users who have access if that method is enabled.
-pub(crate) struct Authenticator {
+pub struct Authenticator {
     allowed_users: Vec<User>,
     pub(crate) auth_methods: Vec<u8>,
 }
 
 impl Authenticator {
-    pub(crate) fn new(auth_methods: Vec<u8>, allowed_users: Vec<User>) -> Authenticator {
+    pub fn new(auth_methods: Vec<u8>, allowed_users: Vec<User>) -> Authenticator {
         Authenticator {
             allowed_users,
             auth_methods,
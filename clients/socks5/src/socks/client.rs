@@ -2,28 +2,35 @@
 
 use super::authentication::{AuthenticationMethods, Authenticator, User};
 use super::request::{SocksCommand, SocksRequest};
-use super::types::{ResponseCodeV4, ResponseCodeV5, SocksProxyError};
+use super::types::{AddrType, ResponseCodeV4, ResponseCodeV5, SocksProxyError};
+use super::udp::UdpRelay;
 use super::{SocksVersion, RESERVED, SOCKS4_VERSION, SOCKS5_VERSION};
 use client_connections::{LaneQueueLengths, TransmissionLane};
 use client_core::client::inbound_messages::{InputMessage, InputMessageSender};
+use client_core::client::self_address::SelfAddress;
 use futures::channel::mpsc;
 use futures::task::{Context, Poll};
+use futures::StreamExt;
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
 use pin_project::pin_project;
 use proxy_helpers::connection_controller::{
-    ConnectionReceiver, ControllerCommand, ControllerSender,
+    BindReceiver, ConnectionReceiver, ControllerCommand, ControllerSender, DatagramReceiver,
 };
 use proxy_helpers::proxy_runner::ProxyRunner;
 use rand::RngCore;
-use socks5_requests::{ConnectionId, Message, RemoteAddress, Request};
+use socks5_requests::{BindStatus, ConnectionId, Message, RemoteAddress, Request};
 use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::time::Duration;
 use task::TaskClient;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
 use tokio::{self, net::TcpStream};
 
+/// How long we're willing to wait for the network requester to report progress of the BIND request.
+const BIND_TIMEOUT: Duration = Duration::from_secs(180);
+
 #[pin_project(project = StateProject)]
 enum StreamState {
     Available(TcpStream),
@@ -52,6 +59,17 @@ impl StreamState {
         stream.unwrap()
     }
 
+    /// Returns the local address that this stream is bound to.
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            StreamState::RunningProxy => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "stream is being used to run the proxy",
+            )),
+            StreamState::Available(ref stream) => stream.local_addr(),
+        }
+    }
+
     /// Returns the remote address that this stream is connected to.
     fn peer_addr(&self) -> io::Result<SocketAddr> {
         match self {
@@ -126,15 +144,16 @@ impl AsyncWrite for StreamState {
     }
 }
 
+/// Configuration of how the requests of the socks clients are sent through the mixnet.
 #[derive(Debug, Copy, Clone)]
-pub(crate) struct Config {
+pub struct Config {
     use_surbs_for_responses: bool,
     connection_start_surbs: u32,
     per_request_surbs: u32,
 }
 
 impl Config {
-    pub(crate) fn new(
+    pub fn new(
         use_surbs_for_responses: bool,
         connection_start_surbs: u32,
         per_request_surbs: u32,
@@ -161,7 +180,7 @@ pub(crate) struct SocksClient {
     input_sender: InputMessageSender,
     connection_id: ConnectionId,
     service_provider: Recipient,
-    self_address: Recipient,
+    self_address: SelfAddress,
     started_proxy: bool,
     lane_queue_lengths: LaneQueueLengths,
     shutdown_listener: TaskClient,
@@ -188,7 +207,7 @@ impl SocksClient {
         input_sender: InputMessageSender,
         service_provider: &Recipient,
         controller_sender: ControllerSender,
-        self_address: &Recipient,
+        self_address: SelfAddress,
         lane_queue_lengths: LaneQueueLengths,
         mut shutdown_listener: TaskClient,
     ) -> Self {
@@ -207,7 +226,7 @@ impl SocksClient {
             authenticator,
             input_sender,
             service_provider: *service_provider,
-            self_address: *self_address,
+            self_address,
             started_proxy: false,
             lane_queue_lengths,
             shutdown_listener,
@@ -309,7 +328,11 @@ impl SocksClient {
     }
 
     async fn send_connect_to_mixnet_with_return_address(&mut self, remote_address: RemoteAddress) {
-        let req = Request::new_connect(self.connection_id, remote_address, Some(self.self_address));
+        let req = Request::new_connect(
+            self.connection_id,
+            remote_address,
+            Some(self.self_address.get()),
+        );
         let msg = Message::Request(req);
 
         let input_message = InputMessage::new_regular(
@@ -332,10 +355,78 @@ impl SocksClient {
         }
     }
 
-    async fn run_proxy(&mut self, conn_receiver: ConnectionReceiver, remote_proxy_target: String) {
-        self.send_connect_to_mixnet(remote_proxy_target.clone())
-            .await;
+    async fn send_return_address_update(&mut self, return_address: Recipient) {
+        debug!(
+            "Informing the service provider about our new address for connection {}",
+            self.connection_id
+        );
+        let req = Request::new_return_address_update(self.connection_id, return_address);
+        let msg = Message::Request(req);
+
+        let input_message = InputMessage::new_regular(
+            self.service_provider,
+            msg.into_bytes(),
+            TransmissionLane::ConnectionId(self.connection_id),
+        );
+        self.input_sender
+            .send(input_message)
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+    }
+
+    async fn send_bind_to_mixnet(&mut self, remote_address: RemoteAddress) {
+        let req = if self.config.use_surbs_for_responses {
+            Request::new_bind(self.connection_id, remote_address, None)
+        } else {
+            Request::new_bind(
+                self.connection_id,
+                remote_address,
+                Some(self.self_address.get()),
+            )
+        };
+        let msg = Message::Request(req);
+        let lane = TransmissionLane::ConnectionId(self.connection_id);
+
+        let input_message = if self.config.use_surbs_for_responses {
+            InputMessage::new_anonymous(
+                self.service_provider,
+                msg.into_bytes(),
+                self.config.connection_start_surbs,
+                lane,
+            )
+        } else {
+            InputMessage::new_regular(self.service_provider, msg.into_bytes(), lane)
+        };
+        self.input_sender
+            .send(input_message)
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+    }
+
+    /// Waits for the next progress update of the `Bind` request and returns the address
+    /// it has reported, i.e. either the bound address or the address of the accepted peer.
+    async fn next_bind_address(
+        &mut self,
+        bind_receiver: &mut BindReceiver,
+    ) -> Result<SocketAddr, SocksProxyError> {
+        let status = tokio::time::timeout(BIND_TIMEOUT, bind_receiver.next())
+            .await
+            .map_err(|_| ResponseCodeV5::TtlExpired)?
+            .ok_or(ResponseCodeV5::Failure)?;
+
+        match status {
+            BindStatus::Listening(address) | BindStatus::Accepted(address) => Ok(address.parse()?),
+            BindStatus::Failed(reason) => {
+                warn!(
+                    "Network requester failed to bind the socket for connection {}: {reason}",
+                    self.connection_id
+                );
+                Err(ResponseCodeV5::Failure.into())
+            }
+        }
+    }
 
+    async fn run_proxy(&mut self, conn_receiver: ConnectionReceiver, remote_proxy_target: String) {
         let stream = self.stream.run_proxy();
         let peer_addr = match stream.peer_addr() {
             Ok(peer_addr) => peer_addr,
@@ -352,7 +443,7 @@ impl SocksClient {
         let per_request_surbs = self.config.per_request_surbs;
 
         let recipient = self.service_provider;
-        let (stream, _) = ProxyRunner::new(
+        let proxy = ProxyRunner::new(
             stream,
             local_stream_remote,
             remote_proxy_target,
@@ -376,13 +467,38 @@ impl SocksClient {
             } else {
                 InputMessage::new_regular(recipient, provider_message.into_bytes(), lane)
             }
-        })
-        .await
-        .into_inner();
+        });
+        tokio::pin!(proxy);
+
+        // if we have switched our gateway in the meantime, the service provider has to be told
+        // where to send the remaining responses (with surbs it's handled by the reply controller)
+        let mut address_changes = self.self_address.subscribe();
+        let (stream, _) = loop {
+            tokio::select! {
+                proxy = &mut proxy => break proxy.into_inner(),
+                Ok(_) = address_changes.changed(), if !anonymous => {
+                    let new_address = *address_changes.borrow_and_update();
+                    self.send_return_address_update(new_address).await;
+                }
+            }
+        };
         // recover stream from the proxy
         self.stream.finish_proxy(stream)
     }
 
+    async fn run_udp_relay(&mut self, relay: UdpRelay, datagram_receiver: DatagramReceiver) {
+        let mut stream = self.stream.run_proxy();
+        relay
+            .run(
+                &mut stream,
+                datagram_receiver,
+                self.shutdown_listener.clone(),
+            )
+            .await;
+        // recover stream from the relay
+        self.stream.finish_proxy(stream)
+    }
+
     /// Handles a client request.
     async fn handle_request(&mut self) -> Result<(), SocksProxyError> {
         debug!("Handling CONNECT Command");
@@ -421,6 +537,7 @@ impl SocksClient {
                     remote_address.clone(),
                     self.connection_id
                 );
+                self.send_connect_to_mixnet(remote_address.clone()).await;
                 self.run_proxy(mix_receiver, remote_address.clone()).await;
                 info!(
                     "Proxy for {} is finished (id: {})",
@@ -428,8 +545,85 @@ impl SocksClient {
                 );
             }
 
-            SocksCommand::Bind => unimplemented!(), // not handled
-            SocksCommand::UdpAssociate => unimplemented!(), // not handled
+            SocksCommand::UdpAssociate => {
+                // UDP ASSOCIATE is not part of SOCKS4
+                if *version != SocksVersion::V5 {
+                    return Err(ResponseCodeV5::CommandNotSupported.into());
+                }
+
+                // the relay listens on the same interface the client has connected to
+                let return_address =
+                    (!self.config.use_surbs_for_responses).then(|| self.self_address.clone());
+                let relay = UdpRelay::bind(
+                    self.stream.local_addr()?.ip(),
+                    self.connection_id,
+                    self.input_sender.clone(),
+                    self.service_provider,
+                    return_address,
+                    self.config.per_request_surbs,
+                )
+                .await?;
+                let relay_address = relay.local_addr()?;
+                self.acknowledge_socks5_with_address(relay_address).await?;
+
+                let (datagram_sender, datagram_receiver) = mpsc::unbounded();
+                self.started_proxy = true;
+                self.controller_sender
+                    .unbounded_send(ControllerCommand::InsertAssociation(
+                        self.connection_id,
+                        datagram_sender,
+                    ))
+                    .unwrap();
+
+                info!(
+                    "Starting UDP relay on {} (id: {})",
+                    relay_address, self.connection_id
+                );
+                self.run_udp_relay(relay, datagram_receiver).await;
+                info!(
+                    "UDP relay on {} is finished (id: {})",
+                    relay_address, self.connection_id
+                );
+            }
+
+            SocksCommand::Bind => {
+                // we only support BIND for SOCKS5
+                if *version != SocksVersion::V5 {
+                    return Err(ResponseCodeV5::CommandNotSupported.into());
+                }
+
+                let (bind_sender, mut bind_receiver) = mpsc::unbounded();
+                self.started_proxy = true;
+                self.controller_sender
+                    .unbounded_send(ControllerCommand::InsertBind(
+                        self.connection_id,
+                        bind_sender,
+                    ))
+                    .unwrap();
+                self.controller_sender
+                    .unbounded_send(ControllerCommand::Insert(self.connection_id, mix_sender))
+                    .unwrap();
+
+                trace!("Binding for: {:?}", remote_address.clone());
+                self.send_bind_to_mixnet(remote_address.clone()).await;
+
+                // as per the RFC, the first reply contains the address the remote should connect to
+                // and the second one is sent once the connection has been accepted
+                let bound_address = self.next_bind_address(&mut bind_receiver).await?;
+                self.acknowledge_socks5_with_address(bound_address).await?;
+                let peer_address = self.next_bind_address(&mut bind_receiver).await?;
+                self.acknowledge_socks5_with_address(peer_address).await?;
+
+                info!(
+                    "Starting proxy for inbound connection from {} (id: {})",
+                    peer_address, self.connection_id
+                );
+                self.run_proxy(mix_receiver, peer_address.to_string()).await;
+                info!(
+                    "Proxy for inbound connection from {} is finished (id: {})",
+                    peer_address, self.connection_id
+                );
+            }
         };
 
         Ok(())
@@ -455,6 +649,28 @@ impl SocksClient {
             .unwrap();
     }
 
+    /// Writes a Socks5 header back to the requesting client's TCP stream including the
+    /// address the client is supposed to use for further communication (BND.ADDR and BND.PORT)
+    async fn acknowledge_socks5_with_address(
+        &mut self,
+        address: SocketAddr,
+    ) -> Result<(), SocksProxyError> {
+        let mut response = vec![SOCKS5_VERSION, ResponseCodeV5::Success as u8, RESERVED];
+        match address {
+            SocketAddr::V4(address) => {
+                response.push(AddrType::V4 as u8);
+                response.extend_from_slice(&address.ip().octets());
+            }
+            SocketAddr::V6(address) => {
+                response.push(AddrType::V6 as u8);
+                response.extend_from_slice(&address.ip().octets());
+            }
+        }
+        response.extend_from_slice(&address.port().to_be_bytes());
+        self.stream.write_all(&response).await?;
+        Ok(())
+    }
+
     /// Writes a Socks4 header back to the requesting client's TCP stream,
     async fn acknowledge_socks4(&mut self) {
         self.stream
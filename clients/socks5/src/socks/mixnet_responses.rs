@@ -71,6 +71,25 @@ impl MixnetResponseListener {
                 return Ok(());
             }
             Ok(Message::Response(data)) => data,
+            Ok(Message::BindResponse(bind_response)) => {
+                self.controller_sender
+                    .unbounded_send(ControllerCommand::SendBindStatus(
+                        bind_response.connection_id,
+                        bind_response.status,
+                    ))
+                    .unwrap();
+                return Ok(());
+            }
+            Ok(Message::DatagramResponse(datagram)) => {
+                self.controller_sender
+                    .unbounded_send(ControllerCommand::SendDatagram(
+                        datagram.connection_id,
+                        datagram.source_addr,
+                        datagram.data,
+                    ))
+                    .unwrap();
+                return Ok(());
+            }
             Ok(Message::NetworkRequesterResponse(r)) => {
                 error!(
                     "Network requester failed on connection id {} with error: {}",
@@ -5,11 +5,12 @@ use std::convert::TryFrom;
 use self::types::SocksProxyError;
 
 pub mod authentication;
-pub(crate) mod client;
+pub mod client;
 pub(crate) mod mixnet_responses;
 mod request;
 pub mod server;
 pub mod types;
+pub(crate) mod udp;
 pub mod utils;
 
 /// Version of socks
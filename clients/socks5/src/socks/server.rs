@@ -7,6 +7,7 @@ use crate::socks::client;
 use client_connections::{ConnectionCommandSender, LaneQueueLengths};
 use client_core::client::{
     inbound_messages::InputMessageSender, received_buffer::ReceivedBufferRequestSender,
+    self_address::SelfAddress,
 };
 use log::*;
 use nymsphinx::addressing::clients::Recipient;
@@ -21,7 +22,7 @@ pub struct SphinxSocksServer {
     authenticator: Authenticator,
     listening_address: SocketAddr,
     service_provider: Recipient,
-    self_address: Recipient,
+    self_address: SelfAddress,
     client_config: client::Config,
     lane_queue_lengths: LaneQueueLengths,
     shutdown: TaskClient,
@@ -29,22 +30,19 @@ pub struct SphinxSocksServer {
 
 impl SphinxSocksServer {
     /// Create a new SphinxSocks instance
-    pub(crate) fn new(
-        port: u16,
+    pub fn new(
+        listening_address: SocketAddr,
         authenticator: Authenticator,
         service_provider: Recipient,
-        self_address: Recipient,
+        self_address: SelfAddress,
         lane_queue_lengths: LaneQueueLengths,
         client_config: client::Config,
         shutdown: TaskClient,
     ) -> Self {
-        // hardcode ip as we (presumably) ONLY want to listen locally. If we change it, we can
-        // just modify the config
-        let ip = "127.0.0.1";
-        info!("Listening on {}:{}", ip, port);
+        info!("Listening on {}", listening_address);
         SphinxSocksServer {
             authenticator,
-            listening_address: format!("{}:{}", ip, port).parse().unwrap(),
+            listening_address,
             service_provider,
             self_address,
             client_config,
@@ -55,7 +53,7 @@ impl SphinxSocksServer {
 
     /// Set up the listener and initiate connection handling when something
     /// connects to the server.
-    pub(crate) async fn serve(
+    pub async fn serve(
         &mut self,
         input_sender: InputMessageSender,
         buffer_requester: ReceivedBufferRequestSender,
@@ -96,7 +94,7 @@ impl SphinxSocksServer {
                         input_sender.clone(),
                         &self.service_provider,
                         controller_sender.clone(),
-                        &self.self_address,
+                        self.self_address.clone(),
                         self.lane_queue_lengths.clone(),
                         self.shutdown.clone(),
                     );
@@ -0,0 +1,304 @@
+use super::types::{AddrType, SocksProxyError};
+use super::utils as socks_utils;
+use client_connections::TransmissionLane;
+use client_core::client::inbound_messages::{InputMessage, InputMessageSender};
+use client_core::client::self_address::SelfAddress;
+use futures::StreamExt;
+use log::*;
+use nymsphinx::addressing::clients::Recipient;
+use proxy_helpers::connection_controller::DatagramReceiver;
+use socks5_requests::{ConnectionId, Message, RemoteAddress, Request};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use task::TaskClient;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Maximum size of a datagram we're willing to receive from the local application.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Parses the header prepended to every UDP datagram sent to the relay, as described in
+/// https://www.rfc-editor.org/rfc/rfc1928#section-7
+///
+/// +----+------+------+----------+----------+----------+
+/// |RSV | FRAG | ATYP | DST.ADDR | DST.PORT |   DATA   |
+/// +----+------+------+----------+----------+----------+
+/// | 2  |  1   |  1   | Variable |    2     | Variable |
+/// +----+------+------+----------+----------+----------+
+///
+/// Returns the destination address alongside the actual datagram payload.
+pub(crate) fn parse_udp_header(datagram: &[u8]) -> Result<(RemoteAddress, &[u8]), SocksProxyError> {
+    let malformed = |reason: &str| {
+        SocksProxyError::from(io::Error::new(
+            io::ErrorKind::InvalidData,
+            reason.to_string(),
+        ))
+    };
+
+    if datagram.len() < 4 {
+        return Err(malformed("datagram too short to contain the header"));
+    }
+
+    // we don't support fragmentation - as per the RFC, any fragment must be dropped
+    if datagram[2] != 0 {
+        return Err(malformed("fragmented datagrams are not supported"));
+    }
+
+    let Some(addr_type) = AddrType::from(datagram[3] as usize) else {
+        return Err(malformed("unknown address type"));
+    };
+
+    let (addr, rest) = match addr_type {
+        AddrType::V4 => split_checked(&datagram[4..], 4),
+        AddrType::V6 => split_checked(&datagram[4..], 16),
+        AddrType::Domain => match datagram.get(4) {
+            Some(len) => split_checked(&datagram[5..], *len as usize),
+            None => None,
+        },
+    }
+    .ok_or_else(|| malformed("datagram too short to contain the address"))?;
+
+    let (port, data) = split_checked(rest, 2)
+        .ok_or_else(|| malformed("datagram too short to contain the port"))?;
+    let port = u16::from_be_bytes([port[0], port[1]]);
+
+    let address = socks_utils::pretty_print_addr(&addr_type, addr);
+    let remote = match addr_type {
+        AddrType::V6 => format!("[{address}]:{port}"),
+        _ => format!("{address}:{port}"),
+    };
+
+    Ok((remote, data))
+}
+
+/// Prepends the header described in [`parse_udp_header`] to the datagram received from the
+/// specified source.
+pub(crate) fn encode_udp_datagram(source: &str, data: &[u8]) -> Vec<u8> {
+    let mut datagram = vec![0, 0, 0];
+    match source.parse::<SocketAddr>() {
+        Ok(SocketAddr::V4(addr)) => {
+            datagram.push(AddrType::V4 as u8);
+            datagram.extend_from_slice(&addr.ip().octets());
+            datagram.extend_from_slice(&addr.port().to_be_bytes());
+        }
+        Ok(SocketAddr::V6(addr)) => {
+            datagram.push(AddrType::V6 as u8);
+            datagram.extend_from_slice(&addr.ip().octets());
+            datagram.extend_from_slice(&addr.port().to_be_bytes());
+        }
+        Err(_) => {
+            // this should never happen as the service provider always reports resolved addresses,
+            // but in case it did not, treat it as a domain
+            let (host, port) = source.rsplit_once(':').unwrap_or((source, "0"));
+            let port = port.parse::<u16>().unwrap_or_default();
+            let host = &host.as_bytes()[..host.len().min(u8::MAX as usize)];
+            datagram.push(AddrType::Domain as u8);
+            datagram.push(host.len() as u8);
+            datagram.extend_from_slice(host);
+            datagram.extend_from_slice(&port.to_be_bytes());
+        }
+    }
+    datagram.extend_from_slice(data);
+    datagram
+}
+
+fn split_checked(data: &[u8], at: usize) -> Option<(&[u8], &[u8])> {
+    if data.len() < at {
+        None
+    } else {
+        Some(data.split_at(at))
+    }
+}
+
+/// Local UDP relay created as a result of the UDP ASSOCIATE command. It receives datagrams from
+/// the local application, strips their SOCKS header and sends them through the mix network.
+/// Datagrams are not ordered nor retransmitted - each of them is sent as an independent message.
+pub(crate) struct UdpRelay {
+    socket: UdpSocket,
+    connection_id: ConnectionId,
+    input_sender: InputMessageSender,
+    service_provider: Recipient,
+    // our own address is read for every datagram, so that the responses would always
+    // be sent to the gateway we're currently using
+    return_address: Option<SelfAddress>,
+    per_request_surbs: u32,
+
+    // address of the local application that's using the association. It's learned from the
+    // first received datagram and any datagrams from other sources are dropped afterwards
+    client_address: Option<SocketAddr>,
+}
+
+impl UdpRelay {
+    pub(crate) async fn bind(
+        ip: IpAddr,
+        connection_id: ConnectionId,
+        input_sender: InputMessageSender,
+        service_provider: Recipient,
+        return_address: Option<SelfAddress>,
+        per_request_surbs: u32,
+    ) -> io::Result<Self> {
+        let socket = UdpSocket::bind(SocketAddr::new(ip, 0)).await?;
+
+        Ok(UdpRelay {
+            socket,
+            connection_id,
+            input_sender,
+            service_provider,
+            return_address,
+            per_request_surbs,
+            client_address: None,
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    async fn send_to_mixnet(&self, remote: RemoteAddress, data: Vec<u8>) {
+        let return_address = self.return_address.as_ref().map(SelfAddress::get);
+        let req = Request::new_datagram(self.connection_id, remote, return_address, data);
+        let msg = Message::Request(req);
+        let lane = TransmissionLane::ConnectionId(self.connection_id);
+
+        // if we haven't specified our address, the service provider has to reply using surbs
+        let input_message = if return_address.is_none() {
+            InputMessage::new_anonymous(
+                self.service_provider,
+                msg.into_bytes(),
+                self.per_request_surbs,
+                lane,
+            )
+        } else {
+            InputMessage::new_regular(self.service_provider, msg.into_bytes(), lane)
+        };
+        self.input_sender
+            .send(input_message)
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+    }
+
+    fn on_local_datagram(
+        &mut self,
+        source: SocketAddr,
+        datagram: &[u8],
+    ) -> Option<(RemoteAddress, Vec<u8>)> {
+        match self.client_address {
+            Some(client_address) if client_address != source => {
+                debug!("Dropping UDP datagram from unexpected source {source}");
+                return None;
+            }
+            Some(_) => (),
+            None => self.client_address = Some(source),
+        }
+
+        match parse_udp_header(datagram) {
+            Ok((remote, data)) => Some((remote, data.to_vec())),
+            Err(err) => {
+                debug!("Dropping malformed UDP datagram: {err}");
+                None
+            }
+        }
+    }
+
+    /// Relays the datagrams until the TCP connection the association was requested on
+    /// gets closed, as required by the RFC.
+    pub(crate) async fn run(
+        mut self,
+        control_stream: &mut TcpStream,
+        mut mix_receiver: DatagramReceiver,
+        mut shutdown: TaskClient,
+    ) {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let mut control_buf = [0u8; 64];
+
+        loop {
+            tokio::select! {
+                local = self.socket.recv_from(&mut buf) => {
+                    let (n, source) = match local {
+                        Ok(received) => received,
+                        Err(err) => {
+                            debug!("Failed to receive local UDP datagram: {err}");
+                            continue;
+                        }
+                    };
+                    if let Some((remote, data)) = self.on_local_datagram(source, &buf[..n]) {
+                        self.send_to_mixnet(remote, data).await
+                    }
+                }
+                mix_datagram = mix_receiver.next() => {
+                    let Some(mix_datagram) = mix_datagram else {
+                        trace!("UdpRelay: Stopping since channel closed");
+                        break;
+                    };
+                    let Some(client_address) = self.client_address else {
+                        debug!("Received a datagram before the local application sent anything");
+                        continue;
+                    };
+                    let datagram =
+                        encode_udp_datagram(&mix_datagram.source_addr, &mix_datagram.payload);
+                    if let Err(err) = self.socket.send_to(&datagram, client_address).await {
+                        debug!("Failed to send UDP datagram to the local application: {err}");
+                    }
+                }
+                read = control_stream.read(&mut control_buf) => {
+                    // the client is not supposed to send anything on the control connection,
+                    // so we only care about it getting closed
+                    if matches!(read, Ok(0) | Err(_)) {
+                        debug!(
+                            "The control connection for UDP association {} got closed",
+                            self.connection_id
+                        );
+                        break;
+                    }
+                }
+                _ = shutdown.recv() => {
+                    trace!("UdpRelay: Received shutdown");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_ipv4_header() {
+        let datagram = [0, 0, 0, 1, 1, 1, 1, 1, 0, 53, 42, 43];
+        let (remote, data) = parse_udp_header(&datagram).unwrap();
+        assert_eq!("1.1.1.1:53", remote);
+        assert_eq!(&[42, 43], data);
+    }
+
+    #[test]
+    fn parsing_domain_header() {
+        let mut datagram = vec![0, 0, 0, 3, 7];
+        datagram.extend_from_slice(b"foo.com");
+        datagram.extend_from_slice(&[1, 187, 42]);
+        let (remote, data) = parse_udp_header(&datagram).unwrap();
+        assert_eq!("foo.com:443", remote);
+        assert_eq!(&[42], data);
+    }
+
+    #[test]
+    fn parsing_rejects_fragments_and_truncated_datagrams() {
+        assert!(parse_udp_header(&[0, 0, 1, 1, 1, 1, 1, 1, 0, 53]).is_err());
+        assert!(parse_udp_header(&[0, 0, 0, 1, 1, 1]).is_err());
+        assert!(parse_udp_header(&[0, 0, 0, 3]).is_err());
+        assert!(parse_udp_header(&[0, 0, 0, 9, 1, 1, 1, 1, 0, 53]).is_err());
+    }
+
+    #[test]
+    fn encoded_datagram_can_be_parsed_back() {
+        let encoded = encode_udp_datagram("1.2.3.4:5353", &[1, 2, 3]);
+        let (remote, data) = parse_udp_header(&encoded).unwrap();
+        assert_eq!("1.2.3.4:5353", remote);
+        assert_eq!(&[1, 2, 3], data);
+
+        let encoded = encode_udp_datagram("[::1]:53", &[4, 5]);
+        assert_eq!(encoded[3], AddrType::V6 as u8);
+        assert_eq!(&[4, 5], &encoded[encoded.len() - 2..]);
+    }
+}
@@ -5,6 +5,7 @@
 #![allow(clippy::drop_non_drop)]
 
 use client_core::config::{DebugConfig as ConfigDebug, ExtendedPacketSize, GatewayEndpointConfig};
+use nymsphinx::params::PacketType;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use url::Url;
@@ -96,6 +97,10 @@ pub struct Debug {
     /// did not reach its destination.
     pub topology_resolution_timeout_ms: u64,
 
+    /// Controls whether the client retrieves the rotated sphinx keys of the mixnodes from the nym-api
+    /// and constructs the packets with them, rather than with the keys the nodes have bonded with.
+    pub use_rotated_sphinx_keys: bool,
+
     /// Controls whether the dedicated loop cover traffic stream should be enabled.
     /// (and sending packets, on average, every [Self::loop_cover_traffic_average_delay_ms])
     pub disable_loop_cover_traffic_stream: bool,
@@ -107,6 +112,9 @@ pub struct Debug {
     /// Controls whether the sent sphinx packet use the NON-DEFAULT bigger size.
     pub use_extended_packet_size: bool,
 
+    /// Controls whether the messages should be sent using the outfox rather than the sphinx packets.
+    pub use_outfox: bool,
+
     /// Defines the minimum number of reply surbs the client wants to keep in its storage at all times.
     /// It can only allow to go below that value if its to request additional reply surbs.
     pub minimum_reply_surb_storage_threshold: usize,
@@ -159,10 +167,16 @@ impl From<Debug> for ConfigDebug {
             topology_resolution_timeout: Duration::from_millis(
                 debug.topology_resolution_timeout_ms,
             ),
+            use_rotated_sphinx_keys: debug.use_rotated_sphinx_keys,
             disable_loop_cover_traffic_stream: debug.disable_loop_cover_traffic_stream,
             disable_main_poisson_packet_distribution: debug
                 .disable_main_poisson_packet_distribution,
             use_extended_packet_size,
+            packet_type: if debug.use_outfox {
+                PacketType::Outfox
+            } else {
+                PacketType::Sphinx
+            },
             minimum_reply_surb_storage_threshold: debug.minimum_reply_surb_storage_threshold,
             maximum_reply_surb_storage_threshold: debug.maximum_reply_surb_storage_threshold,
             minimum_reply_surb_request_size: debug.minimum_reply_surb_request_size,
@@ -173,6 +187,9 @@ impl From<Debug> for ConfigDebug {
             ),
             maximum_reply_surb_age: Duration::from_millis(debug.maximum_reply_surb_age_ms),
             maximum_reply_key_age: Duration::from_millis(debug.maximum_reply_key_age_ms),
+            // browser clients don't persist their reply surbs, so there's nothing to checkpoint
+            reply_surb_storage_checkpoint_interval: ConfigDebug::default()
+                .reply_surb_storage_checkpoint_interval,
         }
     }
 }
@@ -191,10 +208,12 @@ impl From<ConfigDebug> for Debug {
             gateway_response_timeout_ms: debug.gateway_response_timeout.as_millis() as u64,
             topology_refresh_rate_ms: debug.topology_refresh_rate.as_millis() as u64,
             topology_resolution_timeout_ms: debug.topology_resolution_timeout.as_millis() as u64,
+            use_rotated_sphinx_keys: debug.use_rotated_sphinx_keys,
             disable_loop_cover_traffic_stream: debug.disable_loop_cover_traffic_stream,
             disable_main_poisson_packet_distribution: debug
                 .disable_main_poisson_packet_distribution,
             use_extended_packet_size: debug.use_extended_packet_size.is_some(),
+            use_outfox: debug.packet_type.is_outfox(),
             minimum_reply_surb_storage_threshold: debug.minimum_reply_surb_storage_threshold,
             maximum_reply_surb_storage_threshold: debug.maximum_reply_surb_storage_threshold,
             minimum_reply_surb_request_size: debug.minimum_reply_surb_request_size,
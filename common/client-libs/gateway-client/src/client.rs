@@ -56,6 +56,9 @@ pub struct GatewayClient {
     packet_router: PacketRouter,
     response_timeout_duration: Duration,
     bandwidth_controller: Option<BandwidthController<PersistentStorage>>,
+    /// Specifies whether the bandwidth used by the forwarded packets should be deducted from the
+    /// remaining bandwidth locally, rather than only learning about it from the gateway.
+    track_bandwidth_locally: bool,
 
     // reconnection related variables
     /// Specifies whether client should try to reconnect to gateway on connection failure.
@@ -98,6 +101,7 @@ impl GatewayClient {
             packet_router: PacketRouter::new(ack_sender, mixnet_message_sender, shutdown.clone()),
             response_timeout_duration,
             bandwidth_controller,
+            track_bandwidth_locally: false,
             should_reconnect_on_failure: true,
             reconnection_attempts: DEFAULT_RECONNECTION_ATTEMPTS,
             reconnection_backoff: DEFAULT_RECONNECTION_BACKOFF,
@@ -122,6 +126,10 @@ impl GatewayClient {
         self.reconnection_backoff = backoff
     }
 
+    pub fn with_local_bandwidth_tracking(&mut self, track_bandwidth_locally: bool) {
+        self.track_bandwidth_locally = track_bandwidth_locally
+    }
+
     pub fn new_init(
         gateway_address: String,
         gateway_identity: identity::PublicKey,
@@ -151,6 +159,7 @@ impl GatewayClient {
             packet_router,
             response_timeout_duration,
             bandwidth_controller: None,
+            track_bandwidth_locally: false,
             should_reconnect_on_failure: false,
             reconnection_attempts: DEFAULT_RECONNECTION_ATTEMPTS,
             reconnection_backoff: DEFAULT_RECONNECTION_BACKOFF,
@@ -611,7 +620,7 @@ impl GatewayClient {
     fn estimate_required_bandwidth(&self, packets: &[MixPacket]) -> i64 {
         packets
             .iter()
-            .map(|packet| packet.sphinx_packet().len())
+            .map(|packet| packet.packet().len())
             .sum::<usize>() as i64
     }
 
@@ -622,9 +631,10 @@ impl GatewayClient {
         if !self.authenticated {
             return Err(GatewayClientError::NotAuthenticated);
         }
-        if self.estimate_required_bandwidth(&packets) > self.bandwidth_remaining {
+        let required_bandwidth = self.estimate_required_bandwidth(&packets);
+        if required_bandwidth > self.bandwidth_remaining {
             return Err(GatewayClientError::NotEnoughBandwidth(
-                self.estimate_required_bandwidth(&packets),
+                required_bandwidth,
                 self.bandwidth_remaining,
             ));
         }
@@ -653,10 +663,19 @@ impl GatewayClient {
                 Err(err)
             }
         } else {
+            self.consume_bandwidth(required_bandwidth);
             Ok(())
         }
     }
 
+    // the gateway charges us for every packet it forwards, so if requested, keep track of it
+    // locally rather than waiting for it to start rejecting our packets
+    fn consume_bandwidth(&mut self, amount: i64) {
+        if self.track_bandwidth_locally {
+            self.bandwidth_remaining = self.bandwidth_remaining.saturating_sub(amount);
+        }
+    }
+
     async fn send_with_reconnection_on_failure(
         &mut self,
         msg: Message,
@@ -692,9 +711,10 @@ impl GatewayClient {
         if !self.authenticated {
             return Err(GatewayClientError::NotAuthenticated);
         }
-        if (mix_packet.sphinx_packet().len() as i64) > self.bandwidth_remaining {
+        let required_bandwidth = mix_packet.packet().len() as i64;
+        if required_bandwidth > self.bandwidth_remaining {
             return Err(GatewayClientError::NotEnoughBandwidth(
-                mix_packet.sphinx_packet().len() as i64,
+                required_bandwidth,
                 self.bandwidth_remaining,
             ));
         }
@@ -708,7 +728,9 @@ impl GatewayClient {
                 .as_ref()
                 .expect("no shared key present even though we're authenticated!"),
         );
-        self.send_with_reconnection_on_failure(msg).await
+        self.send_with_reconnection_on_failure(msg).await?;
+        self.consume_bandwidth(required_bandwidth);
+        Ok(())
     }
 
     async fn recover_socket_connection(&mut self) -> Result<(), GatewayClientError> {
@@ -6,8 +6,8 @@ use futures::StreamExt;
 use log::*;
 use nymsphinx::framing::codec::SphinxCodec;
 use nymsphinx::framing::packet::FramedSphinxPacket;
-use nymsphinx::params::PacketMode;
-use nymsphinx::{addressing::nodes::NymNodeRoutingAddress, SphinxPacket};
+use nymsphinx::params::{PacketMode, SphinxKeyRotation};
+use nymsphinx::{addressing::nodes::NymNodeRoutingAddress, NymPacket};
 use std::collections::HashMap;
 use std::io;
 use std::net::SocketAddr;
@@ -50,8 +50,9 @@ pub trait SendWithoutResponse {
     fn send_without_response(
         &mut self,
         address: NymNodeRoutingAddress,
-        packet: SphinxPacket,
+        packet: NymPacket,
         packet_mode: PacketMode,
+        key_rotation: SphinxKeyRotation,
     ) -> io::Result<()>;
 }
 
@@ -200,12 +201,14 @@ impl SendWithoutResponse for Client {
     fn send_without_response(
         &mut self,
         address: NymNodeRoutingAddress,
-        packet: SphinxPacket,
+        packet: NymPacket,
         packet_mode: PacketMode,
+        key_rotation: SphinxKeyRotation,
     ) -> io::Result<()> {
         trace!("Sending packet to {:?}", address);
         let framed_packet =
-            FramedSphinxPacket::new(packet, packet_mode, self.config.use_legacy_version);
+            FramedSphinxPacket::new(packet, packet_mode, self.config.use_legacy_version)
+                .with_key_rotation(key_rotation);
 
         if let Some(sender) = self.conn_new.get_mut(&address) {
             if let Err(err) = sender.channel.try_send(framed_packet) {
@@ -60,13 +60,14 @@ impl PacketForwarder {
 
                     let next_hop = mix_packet.next_hop();
                     let packet_mode = mix_packet.packet_mode();
-                    let sphinx_packet = mix_packet.into_sphinx_packet();
+                    let key_rotation = mix_packet.key_rotation();
+                    let packet = mix_packet.into_packet();
                     // we don't care about responses, we just want to fire packets
                     // as quickly as possible
 
                     if let Err(err) =
                         self.mixnet_client
-                            .send_without_response(next_hop, sphinx_packet, packet_mode)
+                            .send_without_response(next_hop, packet, packet_mode, key_rotation)
                     {
                         debug!("failed to forward the packet - {err}")
                     }
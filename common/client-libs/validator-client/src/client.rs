@@ -11,8 +11,8 @@ use nym_api_requests::coconut::{
     BlindSignRequestBody, BlindedSignatureResponse, VerifyCredentialBody, VerifyCredentialResponse,
 };
 use nym_api_requests::models::{
-    GatewayCoreStatusResponse, MixnodeCoreStatusResponse, MixnodeStatusResponse,
-    RewardEstimationResponse, StakeSaturationResponse,
+    GatewayCoreStatusResponse, MixNodeBondAnnotated, MixNodeSphinxKeys, MixnodeCoreStatusResponse,
+    MixnodeStatusResponse, RewardEstimationResponse, StakeSaturationResponse,
 };
 
 #[cfg(feature = "nyxd-client")]
@@ -37,8 +37,6 @@ use mixnet_contract_common::{
 #[cfg(feature = "nyxd-client")]
 use network_defaults::NymNetworkDetails;
 #[cfg(feature = "nyxd-client")]
-use nym_api_requests::models::MixNodeBondAnnotated;
-#[cfg(feature = "nyxd-client")]
 use std::str::FromStr;
 use url::Url;
 
@@ -849,6 +847,12 @@ impl NymApiClient {
         Ok(self.nym_api_client.get_active_mixnodes().await?)
     }
 
+    pub async fn get_cached_active_mixnodes_detailed(
+        &self,
+    ) -> Result<Vec<MixNodeBondAnnotated>, ValidatorClientError> {
+        Ok(self.nym_api_client.get_active_mixnodes_detailed().await?)
+    }
+
     pub async fn get_cached_rewarded_mixnodes(
         &self,
     ) -> Result<Vec<MixNodeDetails>, ValidatorClientError> {
@@ -859,6 +863,12 @@ impl NymApiClient {
         Ok(self.nym_api_client.get_mixnodes().await?)
     }
 
+    pub async fn get_cached_mixnodes_sphinx_keys(
+        &self,
+    ) -> Result<Vec<MixNodeSphinxKeys>, ValidatorClientError> {
+        Ok(self.nym_api_client.get_mixnodes_sphinx_keys().await?)
+    }
+
     pub async fn get_cached_gateways(&self) -> Result<Vec<GatewayBond>, ValidatorClientError> {
         Ok(self.nym_api_client.get_gateways().await?)
     }
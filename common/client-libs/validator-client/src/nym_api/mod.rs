@@ -11,9 +11,9 @@ use nym_api_requests::coconut::{
 use nym_api_requests::models::{
     ComputeRewardEstParam, GatewayCoreStatusResponse, GatewayStatusReportResponse,
     GatewayUptimeHistoryResponse, InclusionProbabilityResponse, MixNodeBondAnnotated,
-    MixnodeCoreStatusResponse, MixnodeStatusReportResponse, MixnodeStatusResponse,
-    MixnodeUptimeHistoryResponse, RequestError, RewardEstimationResponse, StakeSaturationResponse,
-    UptimeResponse,
+    MixNodeSphinxKeys, MixnodeCoreStatusResponse, MixnodeStatusReportResponse,
+    MixnodeStatusResponse, MixnodeUptimeHistoryResponse, RequestError, RewardEstimationResponse,
+    StakeSaturationResponse, UptimeResponse,
 };
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
@@ -144,6 +144,14 @@ impl Client {
         .await
     }
 
+    pub async fn get_mixnodes_sphinx_keys(&self) -> Result<Vec<MixNodeSphinxKeys>, NymAPIError> {
+        self.query_nym_api(
+            &[routes::API_VERSION, routes::MIXNODES, routes::SPHINX_KEYS],
+            NO_PARAMS,
+        )
+        .await
+    }
+
     pub async fn get_gateways(&self) -> Result<Vec<GatewayBond>, NymAPIError> {
         self.query_nym_api(&[routes::API_VERSION, routes::GATEWAYS], NO_PARAMS)
             .await
@@ -23,7 +23,9 @@ use mixnet_contract_common::{
     MixnodeDetailsResponse, PagedAllDelegationsResponse, PagedDelegatorDelegationsResponse,
     PagedFamiliesResponse, PagedGatewayResponse, PagedMembersResponse,
     PagedMixNodeDelegationsResponse, PagedMixnodeBondsResponse, PagedRewardedSetResponse,
-    PendingEpochEventsResponse, PendingIntervalEventsResponse, QueryMsg as MixnetQueryMsg,
+    PendingEpochEventsResponse, PendingGatewayOwnershipTransferResponse,
+    PendingIntervalEventsResponse, PendingMixnodeOwnershipTransferResponse,
+    QueryMsg as MixnetQueryMsg,
 };
 use serde::Deserialize;
 
@@ -196,6 +198,14 @@ pub trait MixnetQueryClient {
             .await
     }
 
+    async fn get_pending_mixnode_ownership_transfer(
+        &self,
+        mix_id: MixId,
+    ) -> Result<PendingMixnodeOwnershipTransferResponse, NyxdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetPendingMixnodeOwnershipTransfer { mix_id })
+            .await
+    }
+
     // gateway-related:
 
     async fn get_gateways_paged(
@@ -227,6 +237,14 @@ pub trait MixnetQueryClient {
         .await
     }
 
+    async fn get_pending_gateway_ownership_transfer(
+        &self,
+        identity: IdentityKey,
+    ) -> Result<PendingGatewayOwnershipTransferResponse, NyxdError> {
+        self.query_mixnet_contract(MixnetQueryMsg::GetPendingGatewayOwnershipTransfer { identity })
+            .await
+    }
+
     // delegation-related:
 
     /// Gets list of all delegations towards particular mixnode on particular page.
@@ -11,7 +11,8 @@ use cosmrs::AccountId;
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
 use mixnet_contract_common::reward_params::{IntervalRewardingParamsUpdate, Performance};
 use mixnet_contract_common::{
-    ContractStateParams, ExecuteMsg as MixnetExecuteMsg, Gateway, LayerAssignment, MixId, MixNode,
+    ContractStateParams, ExecuteMsg as MixnetExecuteMsg, Gateway, GatewayConfigUpdate, IdentityKey,
+    LayerAssignment, MixId, MixNode, SphinxKey,
 };
 
 #[async_trait]
@@ -350,6 +351,38 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn decrease_pledge(
+        &self,
+        decrease_by: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::DecreasePledge {
+                amount: decrease_by.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn decrease_pledge_on_behalf(
+        &self,
+        owner: AccountId,
+        decrease_by: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::DecreasePledgeOnBehalf {
+                owner: owner.to_string(),
+                amount: decrease_by.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn unbond_mixnode(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError> {
         self.execute_mixnet_contract(fee, MixnetExecuteMsg::UnbondMixnode {}, vec![])
             .await
@@ -430,6 +463,135 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn propose_mixnode_ownership_transfer(
+        &self,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::ProposeMixnodeOwnershipTransfer {
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn cancel_mixnode_ownership_transfer(
+        &self,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::CancelMixnodeOwnershipTransfer {},
+            vec![],
+        )
+        .await
+    }
+
+    async fn accept_mixnode_ownership_transfer(
+        &self,
+        mix_id: MixId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::AcceptMixnodeOwnershipTransfer { mix_id },
+            vec![],
+        )
+        .await
+    }
+
+    async fn propose_mixnode_ownership_transfer_on_behalf(
+        &self,
+        owner: AccountId,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::ProposeMixnodeOwnershipTransferOnBehalf {
+                owner: owner.to_string(),
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn cancel_mixnode_ownership_transfer_on_behalf(
+        &self,
+        owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::CancelMixnodeOwnershipTransferOnBehalf {
+                owner: owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn accept_mixnode_ownership_transfer_on_behalf(
+        &self,
+        mix_id: MixId,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::AcceptMixnodeOwnershipTransferOnBehalf {
+                mix_id,
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_mixnode_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateMixnodeKeys {
+                new_identity_key,
+                new_sphinx_key,
+                owner_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_mixnode_keys_on_behalf(
+        &self,
+        owner: AccountId,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateMixnodeKeysOnBehalf {
+                new_identity_key,
+                new_sphinx_key,
+                owner: owner.to_string(),
+                owner_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
     // gateway-related:
 
     async fn bond_gateway(
@@ -490,6 +652,165 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGatewayConfig { new_config },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_gateway_config_on_behalf(
+        &self,
+        owner: AccountId,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGatewayConfigOnBehalf {
+                new_config,
+                owner: owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn propose_gateway_ownership_transfer(
+        &self,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::ProposeGatewayOwnershipTransfer {
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn cancel_gateway_ownership_transfer(
+        &self,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::CancelGatewayOwnershipTransfer {},
+            vec![],
+        )
+        .await
+    }
+
+    async fn accept_gateway_ownership_transfer(
+        &self,
+        identity: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::AcceptGatewayOwnershipTransfer { identity },
+            vec![],
+        )
+        .await
+    }
+
+    async fn propose_gateway_ownership_transfer_on_behalf(
+        &self,
+        owner: AccountId,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::ProposeGatewayOwnershipTransferOnBehalf {
+                owner: owner.to_string(),
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn cancel_gateway_ownership_transfer_on_behalf(
+        &self,
+        owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::CancelGatewayOwnershipTransferOnBehalf {
+                owner: owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn accept_gateway_ownership_transfer_on_behalf(
+        &self,
+        identity: IdentityKey,
+        new_owner: AccountId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::AcceptGatewayOwnershipTransferOnBehalf {
+                identity,
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_gateway_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGatewayKeys {
+                new_identity_key,
+                new_sphinx_key,
+                owner_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn update_gateway_keys_on_behalf(
+        &self,
+        owner: AccountId,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::UpdateGatewayKeysOnBehalf {
+                new_identity_key,
+                new_sphinx_key,
+                owner: owner.to_string(),
+                owner_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
     // delegation-related:
 
     async fn delegate_to_mixnode(
@@ -554,6 +875,46 @@ pub trait MixnetSigningClient {
         .await
     }
 
+    async fn redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RedelegateMixnode {
+                from_mix_id,
+                to_mix_id,
+                amount: amount.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn redelegate_mixnode_on_behalf(
+        &self,
+        delegate: AccountId,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_mixnet_contract(
+            fee,
+            MixnetExecuteMsg::RedelegateMixnodeOnBehalf {
+                from_mix_id,
+                to_mix_id,
+                amount: amount.into(),
+                delegate: delegate.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
     // reward-related
 
     async fn reward_mixnode(
@@ -7,7 +7,9 @@ use crate::nyxd::error::NyxdError;
 use crate::nyxd::{Coin, Fee, NyxdClient};
 use async_trait::async_trait;
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
-use mixnet_contract_common::{Gateway, MixId, MixNode};
+use mixnet_contract_common::{
+    Gateway, GatewayConfigUpdate, IdentityKey, MixId, MixNode, SphinxKey,
+};
 use vesting_contract_common::messages::{ExecuteMsg as VestingExecuteMsg, VestingSpecification};
 use vesting_contract_common::PledgeCap;
 
@@ -32,6 +34,65 @@ pub trait VestingSigningClient {
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError>;
 
+    async fn vesting_update_mixnode_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::UpdateMixnodeKeys {
+                new_identity_key,
+                new_sphinx_key,
+                owner_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn vesting_propose_mixnode_ownership_transfer(
+        &self,
+        new_owner: &str,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::ProposeMixnodeOwnershipTransfer {
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn vesting_cancel_mixnode_ownership_transfer(
+        &self,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::CancelMixnodeOwnershipTransfer {},
+            vec![],
+        )
+        .await
+    }
+
+    async fn vesting_accept_mixnode_ownership_transfer(
+        &self,
+        mix_id: MixId,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::AcceptMixnodeOwnershipTransfer { mix_id },
+            vec![],
+        )
+        .await
+    }
+
     async fn update_mixnet_address(
         &self,
         address: &str,
@@ -48,6 +109,71 @@ pub trait VestingSigningClient {
 
     async fn vesting_unbond_gateway(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError>;
 
+    async fn vesting_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError>;
+
+    async fn vesting_update_gateway_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::UpdateGatewayKeys {
+                new_identity_key,
+                new_sphinx_key,
+                owner_signature,
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn vesting_propose_gateway_ownership_transfer(
+        &self,
+        new_owner: &str,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::ProposeGatewayOwnershipTransfer {
+                new_owner: new_owner.to_string(),
+            },
+            vec![],
+        )
+        .await
+    }
+
+    async fn vesting_cancel_gateway_ownership_transfer(
+        &self,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::CancelGatewayOwnershipTransfer {},
+            vec![],
+        )
+        .await
+    }
+
+    async fn vesting_accept_gateway_ownership_transfer(
+        &self,
+        identity: IdentityKey,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::AcceptGatewayOwnershipTransfer { identity },
+            vec![],
+        )
+        .await
+    }
+
     async fn vesting_track_unbond_gateway(
         &self,
         owner: &str,
@@ -79,6 +205,21 @@ pub trait VestingSigningClient {
         .await
     }
 
+    async fn vesting_decrease_pledge(
+        &self,
+        decrease_by: Coin,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::DecreasePledge {
+                amount: decrease_by.into(),
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn vesting_unbond_mixnode(&self, fee: Option<Fee>) -> Result<ExecuteResult, NyxdError>;
 
     async fn vesting_track_unbond_mixnode(
@@ -117,6 +258,15 @@ pub trait VestingSigningClient {
         fee: Option<Fee>,
     ) -> Result<ExecuteResult, NyxdError>;
 
+    async fn vesting_redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        on_behalf_of: Option<String>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError>;
+
     async fn create_periodic_vesting_account(
         &self,
         owner_address: &str,
@@ -243,6 +393,25 @@ impl<C: SigningCosmWasmClient + Sync + Send> VestingSigningClient for NyxdClient
             .await
     }
 
+    async fn vesting_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        let fee = fee.unwrap_or(Fee::Auto(Some(self.simulated_gas_multiplier)));
+        let req = VestingExecuteMsg::UpdateGatewayConfig { new_config };
+        self.client
+            .execute(
+                self.address(),
+                self.vesting_contract_address(),
+                &req,
+                fee,
+                "VestingContract::UpdateGatewayConfig",
+                vec![],
+            )
+            .await
+    }
+
     async fn vesting_track_unbond_gateway(
         &self,
         owner: &str,
@@ -401,6 +570,27 @@ impl<C: SigningCosmWasmClient + Sync + Send> VestingSigningClient for NyxdClient
         .await
     }
 
+    async fn vesting_redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        on_behalf_of: Option<String>,
+        fee: Option<Fee>,
+    ) -> Result<ExecuteResult, NyxdError> {
+        self.execute_vesting_contract(
+            fee,
+            VestingExecuteMsg::RedelegateMixnode {
+                from_mix_id,
+                to_mix_id,
+                amount: amount.into(),
+                on_behalf_of,
+            },
+            vec![],
+        )
+        .await
+    }
+
     async fn create_periodic_vesting_account(
         &self,
         owner_address: &str,
@@ -7,8 +7,10 @@ pub mod rewards;
 
 pub mod delegate_to_mixnode;
 pub mod query_for_delegations;
+pub mod redelegate_mixnode;
 pub mod undelegate_from_mixnode;
 pub mod vesting_delegate_to_mixnode;
+pub mod vesting_redelegate_mixnode;
 pub mod vesting_undelegate_from_mixnode;
 
 #[derive(Debug, Args)]
@@ -28,8 +30,12 @@ pub enum MixnetDelegatorsCommands {
     Delegate(delegate_to_mixnode::Args),
     /// Undelegate from a mixnode
     Undelegate(undelegate_from_mixnode::Args),
+    /// Move delegation to a different mixnode without undelegating
+    Redelegate(redelegate_mixnode::Args),
     /// Delegate to a mixnode with locked tokens
     DelegateVesting(vesting_delegate_to_mixnode::Args),
     /// Undelegate from a mixnode (when originally using locked tokens)
     UndelegateVesting(vesting_undelegate_from_mixnode::Args),
+    /// Move delegation made with locked tokens to a different mixnode without undelegating
+    RedelegateVesting(vesting_redelegate_mixnode::Args),
 }
@@ -0,0 +1,52 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use log::info;
+
+use mixnet_contract_common::{Coin, MixId};
+use validator_client::nyxd::VestingSigningClient;
+
+use crate::context::SigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Id of the mixnode the tokens are currently delegated to
+    #[clap(long)]
+    pub from_mix_id: MixId,
+
+    /// Id of the mixnode the tokens are going to be delegated to
+    #[clap(long)]
+    pub to_mix_id: MixId,
+
+    #[clap(long)]
+    pub on_behalf_of: Option<String>,
+
+    /// Amount to move, it can include any rewards the delegation has accumulated
+    #[clap(long)]
+    pub amount: u128,
+}
+
+pub async fn vesting_redelegate_mixnode(args: Args, client: SigningClient) {
+    let denom = client.current_chain_details().mix_denom.base.as_str();
+
+    info!(
+        "Starting vesting redelegation from mixnode {} to mixnode {}",
+        args.from_mix_id, args.to_mix_id
+    );
+
+    let coin = Coin::new(args.amount, denom);
+
+    let res = client
+        .vesting_redelegate_mixnode(
+            args.from_mix_id,
+            args.to_mix_id,
+            coin.into(),
+            args.on_behalf_of,
+            None,
+        )
+        .await
+        .expect("failed to redelegate!");
+
+    info!("vesting redelegating: {:?}", res);
+}
@@ -0,0 +1,33 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use validator_client::nyxd::VestingSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub identity_key: String,
+
+    #[clap(long)]
+    pub sphinx_key: String,
+
+    #[clap(
+        long,
+        help = "signature of the owner address made with the new identity key"
+    )]
+    pub signature: String,
+}
+
+pub async fn vesting_update_keys(args: Args, client: SigningClient) {
+    info!("Update vesting gateway keys!");
+
+    let res = client
+        .vesting_update_gateway_keys(args.identity_key, args.sphinx_key, args.signature, None)
+        .await
+        .expect("updating vesting gateway keys");
+
+    info!("gateway keys update scheduled: {:?}", res)
+}
@@ -4,7 +4,9 @@
 use clap::{Args, Subcommand};
 
 pub mod update_config;
+pub mod update_keys;
 pub mod vesting_update_config;
+pub mod vesting_update_keys;
 
 #[derive(Debug, Args)]
 #[clap(args_conflicts_with_subcommands = true, subcommand_required = true)]
@@ -19,6 +21,10 @@ pub enum MixnetOperatorsMixnodeSettingsCommands {
     UpdateConfig(update_config::Args),
     /// Update mixnode configuration for a mixnode bonded with locked tokens
     VestingUpdateConfig(vesting_update_config::Args),
+    /// Replace the mixnode identity and sphinx keys at the end of the current epoch
+    UpdateKeys(update_keys::Args),
+    /// Replace the mixnode identity and sphinx keys for a mixnode bonded with locked tokens
+    VestingUpdateKeys(vesting_update_keys::Args),
     /// Update mixnode cost parameters
     UpdateCostParameters,
     /// Update mixnode cost parameters for a mixnode bonded with locked tokens
@@ -0,0 +1,33 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::SigningClient;
+use clap::Parser;
+use log::info;
+use validator_client::nyxd::traits::MixnetSigningClient;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(long)]
+    pub identity_key: String,
+
+    #[clap(long)]
+    pub sphinx_key: String,
+
+    #[clap(
+        long,
+        help = "signature of the owner address made with the new identity key"
+    )]
+    pub signature: String,
+}
+
+pub async fn update_keys(args: Args, client: SigningClient) {
+    info!("Update mixnode keys!");
+
+    let res = client
+        .update_mixnode_keys(args.identity_key, args.sphinx_key, args.signature, None)
+        .await
+        .expect("updating mixnode keys");
+
+    info!("mixnode keys update scheduled: {:?}", res)
+}
@@ -25,12 +25,25 @@ pub enum MixnetContractError {
     #[error("Not enough funds sent for node pledge. (received {received}, minimum {minimum})")]
     InsufficientPledge { received: Coin, minimum: Coin },
 
+    #[error("Attempted to reduce the mixnode pledge ({current}) by {decrease_by}, which would have resulted in it falling below the minimum pledge of {minimum}")]
+    InvalidPledgeReduction {
+        current: Coin,
+        decrease_by: Coin,
+        minimum: Coin,
+    },
+
     #[error("Not enough funds sent for node delegation. (received {received}, minimum {minimum})")]
     InsufficientDelegation { received: Coin, minimum: Coin },
 
     #[error("Mixnode ({mix_id}) does not exist")]
     MixNodeBondNotFound { mix_id: MixId },
 
+    #[error("Attempted to redelegate tokens from mixnode {mix_id} to itself")]
+    RedelegationToSameMixnode { mix_id: MixId },
+
+    #[error("Attempted to redelegate {requested}, but the delegation (including its rewards) is only worth {available}")]
+    RedelegationExceedsDelegation { requested: Coin, available: Coin },
+
     #[error("{owner} does not seem to own any mixnodes")]
     NoAssociatedMixNodeBond { owner: Addr },
 
@@ -64,6 +77,27 @@ pub enum MixnetContractError {
     #[error("Proxy address mismatch, expected {existing}, got {incoming}")]
     ProxyMismatch { existing: String, incoming: String },
 
+    #[error("Attempted to transfer the ownership of the node to its current owner ({owner})")]
+    OwnershipTransferToSelf { owner: Addr },
+
+    #[error("Mixnode {mix_id} does not have any pending ownership transfers")]
+    NoPendingMixnodeOwnershipTransfer { mix_id: MixId },
+
+    #[error("Gateway {identity} does not have any pending ownership transfers")]
+    NoPendingGatewayOwnershipTransfer { identity: IdentityKey },
+
+    #[error("{address} is not the proposed new owner of the node (expected {proposed})")]
+    NotProposedOwner { address: Addr, proposed: Addr },
+
+    #[error("Mixnode {mix_id} has a pending pledge change, so its ownership can't be transferred until the end of the current epoch")]
+    PendingPledgeChange { mix_id: MixId },
+
+    #[error("The provided keys are identical to the ones currently used by the node")]
+    UnchangedNodeKeys,
+
+    #[error("Key {key} is already used by mixnode {mix_id}")]
+    DuplicateMixnodeKey { key: String, mix_id: MixId },
+
     #[error("Failed to recover ed25519 public key from its base58 representation - {0}")]
     MalformedEd25519IdentityKey(String),
 
@@ -1,10 +1,13 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::gateway::GatewayConfigUpdate;
 use crate::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
 use crate::reward_params::{IntervalRewardParams, IntervalRewardingParamsUpdate};
 use crate::rewarding::RewardDistribution;
-use crate::{BlockHeight, ContractStateParams, IdentityKeyRef, Interval, Layer, MixId};
+use crate::{
+    BlockHeight, ContractStateParams, IdentityKeyRef, Interval, Layer, MixId, SphinxKeyRef,
+};
 pub use contracts_common::events::*;
 use cosmwasm_std::{Addr, Coin, Decimal, Event};
 
@@ -14,13 +17,29 @@ pub enum MixnetEventType {
     MixnodeBonding,
     PendingPledgeIncrease,
     PledgeIncrease,
+    PendingPledgeDecrease,
+    PledgeDecrease,
+    PledgeDecreaseRejected,
     GatewayBonding,
     GatewayUnbonding,
+    GatewayConfigUpdate,
     PendingMixnodeUnbonding,
     MixnodeUnbonding,
     MixnodeConfigUpdate,
     PendingMixnodeCostParamsUpdate,
     MixnodeCostParamsUpdate,
+    MixnodeOwnershipTransferProposal,
+    MixnodeOwnershipTransferCancellation,
+    MixnodeOwnershipTransfer,
+    GatewayOwnershipTransferProposal,
+    GatewayOwnershipTransferCancellation,
+    GatewayOwnershipTransfer,
+    PendingMixnodeKeysUpdate,
+    MixnodeKeysUpdate,
+    MixnodeKeysUpdateRejected,
+    PendingGatewayKeysUpdate,
+    GatewayKeysUpdate,
+    GatewayKeysUpdateRejected,
     MixnodeRewarding,
     WithdrawDelegatorReward,
     WithdrawOperatorReward,
@@ -30,9 +49,11 @@ pub enum MixnetEventType {
     IntervalRewardingParamsUpdate,
     PendingDelegation,
     PendingUndelegation,
+    PendingRedelegation,
     Delegation,
     DelegationOnUnbonding,
     Undelegation,
+    Redelegation,
     ContractSettingsUpdate,
     RewardingValidatorUpdate,
     AdvanceEpoch,
@@ -55,13 +76,37 @@ impl ToString for MixnetEventType {
             MixnetEventType::MixnodeBonding => "mixnode_bonding",
             MixnetEventType::PendingPledgeIncrease => "pending_pledge_increase",
             MixnetEventType::PledgeIncrease => "pledge_increase",
+            MixnetEventType::PendingPledgeDecrease => "pending_pledge_decrease",
+            MixnetEventType::PledgeDecrease => "pledge_decrease",
+            MixnetEventType::PledgeDecreaseRejected => "pledge_decrease_rejected",
             MixnetEventType::GatewayBonding => "gateway_bonding",
             MixnetEventType::GatewayUnbonding => "gateway_unbonding",
+            MixnetEventType::GatewayConfigUpdate => "gateway_config_update",
             MixnetEventType::PendingMixnodeUnbonding => "pending_mixnode_unbonding",
             MixnetEventType::MixnodeConfigUpdate => "mixnode_config_update",
             MixnetEventType::MixnodeUnbonding => "mixnode_unbonding",
             MixnetEventType::PendingMixnodeCostParamsUpdate => "pending_mixnode_cost_params_update",
             MixnetEventType::MixnodeCostParamsUpdate => "mixnode_cost_params_update",
+            MixnetEventType::MixnodeOwnershipTransferProposal => {
+                "mixnode_ownership_transfer_proposal"
+            }
+            MixnetEventType::MixnodeOwnershipTransferCancellation => {
+                "mixnode_ownership_transfer_cancellation"
+            }
+            MixnetEventType::MixnodeOwnershipTransfer => "mixnode_ownership_transfer",
+            MixnetEventType::GatewayOwnershipTransferProposal => {
+                "gateway_ownership_transfer_proposal"
+            }
+            MixnetEventType::GatewayOwnershipTransferCancellation => {
+                "gateway_ownership_transfer_cancellation"
+            }
+            MixnetEventType::GatewayOwnershipTransfer => "gateway_ownership_transfer",
+            MixnetEventType::PendingMixnodeKeysUpdate => "pending_mixnode_keys_update",
+            MixnetEventType::MixnodeKeysUpdate => "mixnode_keys_update",
+            MixnetEventType::MixnodeKeysUpdateRejected => "mixnode_keys_update_rejected",
+            MixnetEventType::PendingGatewayKeysUpdate => "pending_gateway_keys_update",
+            MixnetEventType::GatewayKeysUpdate => "gateway_keys_update",
+            MixnetEventType::GatewayKeysUpdateRejected => "gateway_keys_update_rejected",
             MixnetEventType::MixnodeRewarding => "mix_rewarding",
             MixnetEventType::WithdrawDelegatorReward => "withdraw_delegator_reward",
             MixnetEventType::WithdrawOperatorReward => "withdraw_operator_reward",
@@ -73,8 +118,10 @@ impl ToString for MixnetEventType {
             MixnetEventType::IntervalRewardingParamsUpdate => "interval_rewarding_params_update",
             MixnetEventType::PendingDelegation => "pending_delegation",
             MixnetEventType::PendingUndelegation => "pending_undelegation",
+            MixnetEventType::PendingRedelegation => "pending_redelegation",
             MixnetEventType::Delegation => "delegation",
             MixnetEventType::Undelegation => "undelegation",
+            MixnetEventType::Redelegation => "redelegation",
             MixnetEventType::ContractSettingsUpdate => "settings_update",
             MixnetEventType::RewardingValidatorUpdate => "rewarding_validator_address_update",
             MixnetEventType::AdvanceEpoch => "advance_epoch",
@@ -100,6 +147,7 @@ pub const PROXY_KEY: &str = "proxy";
 // delegation/undelegation
 pub const DELEGATOR_KEY: &str = "delegator";
 pub const DELEGATION_TARGET_KEY: &str = "delegation_target";
+pub const REDELEGATION_SOURCE_KEY: &str = "redelegation_source";
 pub const UNIT_REWARD_KEY: &str = "unit_reward";
 
 // bonding/unbonding
@@ -121,6 +169,14 @@ pub const NEW_REWARDING_VALIDATOR_ADDRESS_KEY: &str = "new_rewarding_validator_a
 
 pub const UPDATED_MIXNODE_CONFIG_KEY: &str = "updated_mixnode_config";
 pub const UPDATED_MIXNODE_COST_PARAMS_KEY: &str = "updated_mixnode_cost_params";
+pub const UPDATED_GATEWAY_CONFIG_KEY: &str = "updated_gateway_config";
+
+// ownership transfer
+pub const NEW_OWNER_KEY: &str = "new_owner";
+
+// key rotation
+pub const NEW_NODE_IDENTITY_KEY: &str = "new_identity";
+pub const NEW_SPHINX_KEY_KEY: &str = "new_sphinx_key";
 
 // rewarding
 pub const INTERVAL_KEY: &str = "interval_details";
@@ -290,6 +346,53 @@ pub fn new_pending_undelegation_event(
         .add_attribute(MIX_ID_KEY, mix_id.to_string())
 }
 
+pub fn new_redelegation_event(
+    created_at: BlockHeight,
+    delegator: &Addr,
+    proxy: &Option<Addr>,
+    amount: &Coin,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    unit_reward: Decimal,
+) -> Event {
+    Event::new(MixnetEventType::Redelegation)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(REDELEGATION_SOURCE_KEY, from_mix_id.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, to_mix_id.to_string())
+        .add_attribute(UNIT_REWARD_KEY, unit_reward.to_string())
+}
+
+pub fn new_redelegation_to_unbonded_node_event(
+    delegator: &Addr,
+    proxy: &Option<Addr>,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+) -> Event {
+    Event::new(MixnetEventType::Redelegation)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(REDELEGATION_SOURCE_KEY, from_mix_id.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, to_mix_id.to_string())
+}
+
+pub fn new_pending_redelegation_event(
+    delegator: &Addr,
+    proxy: &Option<Addr>,
+    amount: &Coin,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+) -> Event {
+    Event::new(MixnetEventType::PendingRedelegation)
+        .add_attribute(DELEGATOR_KEY, delegator)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+        .add_attribute(REDELEGATION_SOURCE_KEY, from_mix_id.to_string())
+        .add_attribute(DELEGATION_TARGET_KEY, to_mix_id.to_string())
+}
+
 pub fn new_gateway_bonding_event(
     owner: &Addr,
     proxy: &Option<Addr>,
@@ -316,6 +419,19 @@ pub fn new_gateway_unbonding_event(
         .add_attribute(AMOUNT_KEY, amount.to_string())
 }
 
+pub fn new_gateway_config_update_event(
+    owner: &Addr,
+    proxy: &Option<Addr>,
+    identity: IdentityKeyRef<'_>,
+    update: &GatewayConfigUpdate,
+) -> Event {
+    Event::new(MixnetEventType::GatewayConfigUpdate)
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(UPDATED_GATEWAY_CONFIG_KEY, update.to_inline_json())
+}
+
 pub fn new_mixnode_bonding_event(
     owner: &Addr,
     proxy: &Option<Addr>,
@@ -347,6 +463,27 @@ pub fn new_pledge_increase_event(created_at: BlockHeight, mix_id: MixId, amount:
         .add_attribute(AMOUNT_KEY, amount.to_string())
 }
 
+pub fn new_pending_pledge_decrease_event(mix_id: MixId, amount: &Coin) -> Event {
+    Event::new(MixnetEventType::PendingPledgeDecrease)
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+}
+
+pub fn new_pledge_decrease_event(created_at: BlockHeight, mix_id: MixId, amount: &Coin) -> Event {
+    Event::new(MixnetEventType::PledgeDecrease)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(AMOUNT_KEY, amount.to_string())
+}
+
+// the pledge could have been changed (or the minimum pledge increased) between the request
+// getting created and executed, so that the decrease would no longer be valid
+pub fn new_rejected_pledge_decrease_event(created_at: BlockHeight, mix_id: MixId) -> Event {
+    Event::new(MixnetEventType::PledgeDecreaseRejected)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+}
+
 pub fn new_mixnode_unbonding_event(created_at: BlockHeight, mix_id: MixId) -> Event {
     Event::new(MixnetEventType::MixnodeUnbonding)
         .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
@@ -403,6 +540,147 @@ pub fn new_mixnode_cost_params_update_event(
         .add_attribute(UPDATED_MIXNODE_COST_PARAMS_KEY, new_costs.to_inline_json())
 }
 
+pub fn new_mixnode_ownership_transfer_proposal_event(
+    mix_id: MixId,
+    owner: &Addr,
+    new_owner: &Addr,
+) -> Event {
+    Event::new(MixnetEventType::MixnodeOwnershipTransferProposal)
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NEW_OWNER_KEY, new_owner)
+}
+
+pub fn new_mixnode_ownership_transfer_cancellation_event(
+    mix_id: MixId,
+    owner: &Addr,
+    new_owner: &Addr,
+) -> Event {
+    Event::new(MixnetEventType::MixnodeOwnershipTransferCancellation)
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NEW_OWNER_KEY, new_owner)
+}
+
+pub fn new_mixnode_ownership_transfer_event(
+    mix_id: MixId,
+    previous_owner: &Addr,
+    new_owner: &Addr,
+) -> Event {
+    Event::new(MixnetEventType::MixnodeOwnershipTransfer)
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(OWNER_KEY, previous_owner)
+        .add_attribute(NEW_OWNER_KEY, new_owner)
+}
+
+pub fn new_gateway_ownership_transfer_proposal_event(
+    identity: IdentityKeyRef<'_>,
+    owner: &Addr,
+    new_owner: &Addr,
+) -> Event {
+    Event::new(MixnetEventType::GatewayOwnershipTransferProposal)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NEW_OWNER_KEY, new_owner)
+}
+
+pub fn new_gateway_ownership_transfer_cancellation_event(
+    identity: IdentityKeyRef<'_>,
+    owner: &Addr,
+    new_owner: &Addr,
+) -> Event {
+    Event::new(MixnetEventType::GatewayOwnershipTransferCancellation)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(OWNER_KEY, owner)
+        .add_attribute(NEW_OWNER_KEY, new_owner)
+}
+
+pub fn new_gateway_ownership_transfer_event(
+    identity: IdentityKeyRef<'_>,
+    previous_owner: &Addr,
+    new_owner: &Addr,
+) -> Event {
+    Event::new(MixnetEventType::GatewayOwnershipTransfer)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(OWNER_KEY, previous_owner)
+        .add_attribute(NEW_OWNER_KEY, new_owner)
+}
+
+pub fn new_pending_mixnode_keys_update_event(
+    mix_id: MixId,
+    owner: &Addr,
+    proxy: &Option<Addr>,
+    new_identity: IdentityKeyRef<'_>,
+    new_sphinx_key: SphinxKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::PendingMixnodeKeysUpdate)
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(OWNER_KEY, owner)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(NEW_NODE_IDENTITY_KEY, new_identity)
+        .add_attribute(NEW_SPHINX_KEY_KEY, new_sphinx_key)
+}
+
+pub fn new_mixnode_keys_update_event(
+    created_at: BlockHeight,
+    mix_id: MixId,
+    new_identity: IdentityKeyRef<'_>,
+    new_sphinx_key: SphinxKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::MixnodeKeysUpdate)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+        .add_attribute(NEW_NODE_IDENTITY_KEY, new_identity)
+        .add_attribute(NEW_SPHINX_KEY_KEY, new_sphinx_key)
+}
+
+// the node could have unbonded, changed its owner or the keys could have been taken by another node
+// between the request getting created and executed, so that the update would no longer be valid
+pub fn new_rejected_mixnode_keys_update_event(created_at: BlockHeight, mix_id: MixId) -> Event {
+    Event::new(MixnetEventType::MixnodeKeysUpdateRejected)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(MIX_ID_KEY, mix_id.to_string())
+}
+
+pub fn new_pending_gateway_keys_update_event(
+    identity: IdentityKeyRef<'_>,
+    owner: &Addr,
+    proxy: &Option<Addr>,
+    new_identity: IdentityKeyRef<'_>,
+    new_sphinx_key: SphinxKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::PendingGatewayKeysUpdate)
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(OWNER_KEY, owner)
+        .add_optional_attribute(PROXY_KEY, proxy.as_ref())
+        .add_attribute(NEW_NODE_IDENTITY_KEY, new_identity)
+        .add_attribute(NEW_SPHINX_KEY_KEY, new_sphinx_key)
+}
+
+pub fn new_gateway_keys_update_event(
+    created_at: BlockHeight,
+    identity: IdentityKeyRef<'_>,
+    new_identity: IdentityKeyRef<'_>,
+    new_sphinx_key: SphinxKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayKeysUpdate)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+        .add_attribute(NEW_NODE_IDENTITY_KEY, new_identity)
+        .add_attribute(NEW_SPHINX_KEY_KEY, new_sphinx_key)
+}
+
+// the gateway could have unbonded, changed its owner or the new identity could have been taken
+// by another gateway between the request getting created and executed
+pub fn new_rejected_gateway_keys_update_event(
+    created_at: BlockHeight,
+    identity: IdentityKeyRef<'_>,
+) -> Event {
+    Event::new(MixnetEventType::GatewayKeysUpdateRejected)
+        .add_attribute(EVENT_CREATION_HEIGHT_KEY, created_at.to_string())
+        .add_attribute(NODE_IDENTITY_KEY, identity)
+}
+
 pub fn new_rewarding_validator_address_update_event(old: Addr, new: Addr) -> Event {
     Event::new(MixnetEventType::RewardingValidatorUpdate)
         .add_attribute(OLD_REWARDING_VALIDATOR_ADDRESS_KEY, old)
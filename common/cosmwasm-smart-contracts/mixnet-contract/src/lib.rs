@@ -26,7 +26,8 @@ pub use delegation::{
     PagedMixNodeDelegationsResponse,
 };
 pub use gateway::{
-    Gateway, GatewayBond, GatewayBondResponse, GatewayOwnershipResponse, PagedGatewayResponse,
+    Gateway, GatewayBond, GatewayBondResponse, GatewayConfigUpdate, GatewayOwnershipResponse,
+    PagedGatewayResponse, PendingGatewayOwnershipTransferResponse,
 };
 pub use interval::{
     CurrentIntervalResponse, Interval, PendingEpochEventsResponse, PendingIntervalEventsResponse,
@@ -34,7 +35,7 @@ pub use interval::{
 pub use mixnode::{
     Layer, MixNode, MixNodeBond, MixNodeConfigUpdate, MixNodeCostParams, MixNodeDetails,
     MixNodeRewarding, MixOwnershipResponse, MixnodeDetailsResponse, PagedMixnodeBondsResponse,
-    RewardedSetNodeStatus, UnbondedMixnode,
+    PendingMixnodeOwnershipTransferResponse, RewardedSetNodeStatus, UnbondedMixnode,
 };
 pub use msg::*;
 pub use pending_events::{
@@ -332,6 +332,13 @@ impl MixNodeRewarding {
         Ok(())
     }
 
+    pub fn decrease_operator_uint128(
+        &mut self,
+        amount: Uint128,
+    ) -> Result<(), MixnetContractError> {
+        self.decrease_operator_decimal(amount.into_base_decimal()?)
+    }
+
     pub fn increase_delegates_uint128(
         &mut self,
         amount: Uint128,
@@ -723,6 +730,12 @@ pub struct UnbondedMixnodeResponse {
     pub unbonded_info: Option<UnbondedMixnode>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct PendingMixnodeOwnershipTransferResponse {
+    pub mix_id: MixId,
+    pub new_owner: Option<Addr>,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, JsonSchema)]
 pub struct StakeSaturationResponse {
     pub mix_id: MixId,
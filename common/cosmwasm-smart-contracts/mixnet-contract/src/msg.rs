@@ -9,8 +9,8 @@ use crate::reward_params::{
     IntervalRewardParams, IntervalRewardingParamsUpdate, Performance, RewardingParams,
 };
 use crate::{delegation, ContractStateParams, Layer, LayerAssignment, MixId, Percent};
-use crate::{Gateway, IdentityKey, MixNode};
-use cosmwasm_std::Decimal;
+use crate::{Gateway, GatewayConfigUpdate, IdentityKey, MixNode, SphinxKey};
+use cosmwasm_std::{Coin, Decimal};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -163,6 +163,13 @@ pub enum ExecuteMsg {
     PledgeMoreOnBehalf {
         owner: String,
     },
+    DecreasePledge {
+        amount: Coin,
+    },
+    DecreasePledgeOnBehalf {
+        owner: String,
+        amount: Coin,
+    },
     UnbondMixnode {},
     UnbondMixnodeOnBehalf {
         owner: String,
@@ -181,6 +188,35 @@ pub enum ExecuteMsg {
         new_config: MixNodeConfigUpdate,
         owner: String,
     },
+    ProposeMixnodeOwnershipTransfer {
+        new_owner: String,
+    },
+    ProposeMixnodeOwnershipTransferOnBehalf {
+        owner: String,
+        new_owner: String,
+    },
+    CancelMixnodeOwnershipTransfer {},
+    CancelMixnodeOwnershipTransferOnBehalf {
+        owner: String,
+    },
+    AcceptMixnodeOwnershipTransfer {
+        mix_id: MixId,
+    },
+    AcceptMixnodeOwnershipTransferOnBehalf {
+        mix_id: MixId,
+        new_owner: String,
+    },
+    UpdateMixnodeKeys {
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+    },
+    UpdateMixnodeKeysOnBehalf {
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner: String,
+        owner_signature: String,
+    },
 
     // gateway-related:
     BondGateway {
@@ -196,6 +232,42 @@ pub enum ExecuteMsg {
     UnbondGatewayOnBehalf {
         owner: String,
     },
+    UpdateGatewayConfig {
+        new_config: GatewayConfigUpdate,
+    },
+    UpdateGatewayConfigOnBehalf {
+        new_config: GatewayConfigUpdate,
+        owner: String,
+    },
+    ProposeGatewayOwnershipTransfer {
+        new_owner: String,
+    },
+    ProposeGatewayOwnershipTransferOnBehalf {
+        owner: String,
+        new_owner: String,
+    },
+    CancelGatewayOwnershipTransfer {},
+    CancelGatewayOwnershipTransferOnBehalf {
+        owner: String,
+    },
+    AcceptGatewayOwnershipTransfer {
+        identity: IdentityKey,
+    },
+    AcceptGatewayOwnershipTransferOnBehalf {
+        identity: IdentityKey,
+        new_owner: String,
+    },
+    UpdateGatewayKeys {
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+    },
+    UpdateGatewayKeysOnBehalf {
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner: String,
+        owner_signature: String,
+    },
 
     // delegation-related:
     DelegateToMixnode {
@@ -212,6 +284,17 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         delegate: String,
     },
+    RedelegateMixnode {
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+    },
+    RedelegateMixnodeOnBehalf {
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        delegate: String,
+    },
 
     // reward-related
     RewardMixnode {
@@ -291,6 +374,12 @@ impl ExecuteMsg {
             }
             ExecuteMsg::PledgeMore {} => "pledging additional tokens".into(),
             ExecuteMsg::PledgeMoreOnBehalf { .. } => "pledging additional tokens on behalf".into(),
+            ExecuteMsg::DecreasePledge { amount } => {
+                format!("decreasing mixnode pledge by {amount}")
+            }
+            ExecuteMsg::DecreasePledgeOnBehalf { amount, .. } => {
+                format!("decreasing mixnode pledge by {amount} on behalf")
+            }
             ExecuteMsg::UnbondMixnode { .. } => "unbonding mixnode".into(),
             ExecuteMsg::UnbondMixnodeOnBehalf { .. } => "unbonding mixnode on behalf".into(),
             ExecuteMsg::UpdateMixnodeCostParams { .. } => "updating mixnode cost parameters".into(),
@@ -301,6 +390,30 @@ impl ExecuteMsg {
             ExecuteMsg::UpdateMixnodeConfigOnBehalf { .. } => {
                 "updating mixnode configuration on behalf".into()
             }
+            ExecuteMsg::ProposeMixnodeOwnershipTransfer { new_owner } => {
+                format!("proposing mixnode ownership transfer to {new_owner}")
+            }
+            ExecuteMsg::ProposeMixnodeOwnershipTransferOnBehalf { new_owner, .. } => {
+                format!("proposing mixnode ownership transfer to {new_owner} on behalf")
+            }
+            ExecuteMsg::CancelMixnodeOwnershipTransfer { .. } => {
+                "cancelling mixnode ownership transfer".into()
+            }
+            ExecuteMsg::CancelMixnodeOwnershipTransferOnBehalf { .. } => {
+                "cancelling mixnode ownership transfer on behalf".into()
+            }
+            ExecuteMsg::AcceptMixnodeOwnershipTransfer { mix_id } => {
+                format!("accepting ownership of mixnode {mix_id}")
+            }
+            ExecuteMsg::AcceptMixnodeOwnershipTransferOnBehalf { mix_id, .. } => {
+                format!("accepting ownership of mixnode {mix_id} on behalf")
+            }
+            ExecuteMsg::UpdateMixnodeKeys {
+                new_identity_key, ..
+            } => format!("updating mixnode keys to {new_identity_key}"),
+            ExecuteMsg::UpdateMixnodeKeysOnBehalf {
+                new_identity_key, ..
+            } => format!("updating mixnode keys to {new_identity_key} on behalf"),
             ExecuteMsg::BondGateway { gateway, .. } => {
                 format!("bonding gateway {}", gateway.identity_key)
             }
@@ -309,6 +422,34 @@ impl ExecuteMsg {
             }
             ExecuteMsg::UnbondGateway { .. } => "unbonding gateway".into(),
             ExecuteMsg::UnbondGatewayOnBehalf { .. } => "unbonding gateway on behalf".into(),
+            ExecuteMsg::UpdateGatewayConfig { .. } => "updating gateway configuration".into(),
+            ExecuteMsg::UpdateGatewayConfigOnBehalf { .. } => {
+                "updating gateway configuration on behalf".into()
+            }
+            ExecuteMsg::ProposeGatewayOwnershipTransfer { new_owner } => {
+                format!("proposing gateway ownership transfer to {new_owner}")
+            }
+            ExecuteMsg::ProposeGatewayOwnershipTransferOnBehalf { new_owner, .. } => {
+                format!("proposing gateway ownership transfer to {new_owner} on behalf")
+            }
+            ExecuteMsg::CancelGatewayOwnershipTransfer { .. } => {
+                "cancelling gateway ownership transfer".into()
+            }
+            ExecuteMsg::CancelGatewayOwnershipTransferOnBehalf { .. } => {
+                "cancelling gateway ownership transfer on behalf".into()
+            }
+            ExecuteMsg::AcceptGatewayOwnershipTransfer { identity } => {
+                format!("accepting ownership of gateway {identity}")
+            }
+            ExecuteMsg::AcceptGatewayOwnershipTransferOnBehalf { identity, .. } => {
+                format!("accepting ownership of gateway {identity} on behalf")
+            }
+            ExecuteMsg::UpdateGatewayKeys {
+                new_identity_key, ..
+            } => format!("updating gateway keys to {new_identity_key}"),
+            ExecuteMsg::UpdateGatewayKeysOnBehalf {
+                new_identity_key, ..
+            } => format!("updating gateway keys to {new_identity_key} on behalf"),
             ExecuteMsg::DelegateToMixnode { mix_id } => format!("delegating to mixnode {mix_id}"),
             ExecuteMsg::DelegateToMixnodeOnBehalf { mix_id, .. } => {
                 format!("delegating to mixnode {mix_id} on behalf")
@@ -319,6 +460,19 @@ impl ExecuteMsg {
             ExecuteMsg::UndelegateFromMixnodeOnBehalf { mix_id, .. } => {
                 format!("removing delegation from mixnode {mix_id} on behalf")
             }
+            ExecuteMsg::RedelegateMixnode {
+                from_mix_id,
+                to_mix_id,
+                amount,
+            } => format!("redelegating {amount} from mixnode {from_mix_id} to mixnode {to_mix_id}"),
+            ExecuteMsg::RedelegateMixnodeOnBehalf {
+                from_mix_id,
+                to_mix_id,
+                amount,
+                ..
+            } => format!(
+                "redelegating {amount} from mixnode {from_mix_id} to mixnode {to_mix_id} on behalf"
+            ),
             ExecuteMsg::RewardMixnode {
                 mix_id,
                 performance,
@@ -419,6 +573,9 @@ pub enum QueryMsg {
         mix_identity: IdentityKey,
     },
     GetLayerDistribution {},
+    GetPendingMixnodeOwnershipTransfer {
+        mix_id: MixId,
+    },
     // gateway-related:
     GetGateways {
         start_after: Option<IdentityKey>,
@@ -430,6 +587,9 @@ pub enum QueryMsg {
     GetOwnedGateway {
         address: String,
     },
+    GetPendingGatewayOwnershipTransfer {
+        identity: IdentityKey,
+    },
 
     // delegation-related:
     // gets all [paged] delegations associated with particular mixnode
@@ -3,7 +3,7 @@
 
 use crate::mixnode::MixNodeCostParams;
 use crate::reward_params::IntervalRewardingParamsUpdate;
-use crate::{BlockHeight, EpochEventId, IntervalEventId, MixId};
+use crate::{BlockHeight, EpochEventId, IdentityKey, IntervalEventId, MixId, SphinxKey};
 use cosmwasm_std::{Addr, Coin};
 use serde::{Deserialize, Serialize};
 
@@ -34,13 +34,36 @@ pub enum PendingEpochEventKind {
         mix_id: MixId,
         proxy: Option<Addr>,
     },
+    Redelegate {
+        owner: Addr,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        proxy: Option<Addr>,
+    },
     PledgeMore {
         mix_id: MixId,
         amount: Coin,
     },
+    DecreasePledge {
+        mix_id: MixId,
+        decrease_by: Coin,
+    },
     UnbondMixnode {
         mix_id: MixId,
     },
+    UpdateMixnodeKeys {
+        mix_id: MixId,
+        owner: Addr,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+    },
+    UpdateGatewayKeys {
+        identity: IdentityKey,
+        owner: Addr,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+    },
     UpdateActiveSetSize {
         new_size: u32,
     },
@@ -11,18 +11,39 @@ pub const NEW_PERIODIC_VESTING_ACCOUNT_EVENT_TYPE: &str = "new_periodic_vesting_
 
 pub const VESTING_DELEGATION_EVENT_TYPE: &str = "vesting_delegation";
 pub const VESTING_UNDELEGATION_EVENT_TYPE: &str = "vesting_undelegation";
+pub const VESTING_REDELEGATION_EVENT_TYPE: &str = "vesting_redelegation";
 pub const VESTING_GATEWAY_BONDING_EVENT_TYPE: &str = "vesting_gateway_bonding";
 pub const VESTING_GATEWAY_UNBONDING_EVENT_TYPE: &str = "vesting_gateway_unbonding";
+pub const VESTING_UPDATE_GATEWAY_CONFIG_EVENT_TYPE: &str = "vesting_update_gateway_config";
+pub const VESTING_UPDATE_GATEWAY_KEYS_EVENT_TYPE: &str = "vesting_update_gateway_keys";
+pub const VESTING_GATEWAY_OWNERSHIP_TRANSFER_PROPOSAL_EVENT_TYPE: &str =
+    "vesting_gateway_ownership_transfer_proposal";
+pub const VESTING_GATEWAY_OWNERSHIP_TRANSFER_CANCELLATION_EVENT_TYPE: &str =
+    "vesting_gateway_ownership_transfer_cancellation";
+pub const VESTING_GATEWAY_OWNERSHIP_TRANSFER_EVENT_TYPE: &str =
+    "vesting_gateway_ownership_transfer";
 pub const VESTING_MIXNODE_BONDING_EVENT_TYPE: &str = "vesting_mixnode_bonding";
 pub const VESTING_PLEDGE_MORE_EVENT_TYPE: &str = "vesting_pledge_more";
+pub const VESTING_DECREASE_PLEDGE_EVENT_TYPE: &str = "vesting_decrease_pledge";
 pub const VESTING_MIXNODE_UNBONDING_EVENT_TYPE: &str = "vesting_mixnode_unbonding";
 pub const VESTING_UPDATE_MIXNODE_CONFIG_EVENT_TYPE: &str = "vesting_update_mixnode_config";
+pub const VESTING_UPDATE_MIXNODE_KEYS_EVENT_TYPE: &str = "vesting_update_mixnode_keys";
 pub const VESTING_UPDATE_MIXNODE_COST_PARAMS_EVENT_TYPE: &str =
     "vesting_update_mixnode_cost_params";
+pub const VESTING_MIXNODE_OWNERSHIP_TRANSFER_PROPOSAL_EVENT_TYPE: &str =
+    "vesting_mixnode_ownership_transfer_proposal";
+pub const VESTING_MIXNODE_OWNERSHIP_TRANSFER_CANCELLATION_EVENT_TYPE: &str =
+    "vesting_mixnode_ownership_transfer_cancellation";
+pub const VESTING_MIXNODE_OWNERSHIP_TRANSFER_EVENT_TYPE: &str =
+    "vesting_mixnode_ownership_transfer";
 
 pub const TRACK_MIXNODE_UNBOND_EVENT_TYPE: &str = "track_mixnode_unbond";
+pub const TRACK_DECREASE_PLEDGE_EVENT_TYPE: &str = "track_decrease_pledge";
 pub const TRACK_GATEWAY_UNBOND_EVENT_TYPE: &str = "track_gateway_unbond";
+pub const TRACK_MIXNODE_OWNERSHIP_TRANSFER_EVENT_TYPE: &str = "track_mixnode_ownership_transfer";
+pub const TRACK_GATEWAY_OWNERSHIP_TRANSFER_EVENT_TYPE: &str = "track_gateway_ownership_transfer";
 pub const TRACK_UNDELEGATION_EVENT_TYPE: &str = "track_undelegation";
+pub const TRACK_REDELEGATION_EVENT_TYPE: &str = "track_redelegation";
 pub const TRACK_REWARD_EVENT_TYPE: &str = "track_reaward";
 
 // attributes that are used in multiple places
@@ -117,14 +138,54 @@ pub fn new_vesting_pledge_more_event() -> Event {
     Event::new(VESTING_PLEDGE_MORE_EVENT_TYPE)
 }
 
+pub fn new_vesting_decrease_pledge_event() -> Event {
+    Event::new(VESTING_DECREASE_PLEDGE_EVENT_TYPE)
+}
+
+pub fn new_vesting_update_gateway_config_event() -> Event {
+    Event::new(VESTING_UPDATE_GATEWAY_CONFIG_EVENT_TYPE)
+}
+
+pub fn new_vesting_update_gateway_keys_event() -> Event {
+    Event::new(VESTING_UPDATE_GATEWAY_KEYS_EVENT_TYPE)
+}
+
+pub fn new_vesting_gateway_ownership_transfer_proposal_event() -> Event {
+    Event::new(VESTING_GATEWAY_OWNERSHIP_TRANSFER_PROPOSAL_EVENT_TYPE)
+}
+
+pub fn new_vesting_gateway_ownership_transfer_cancellation_event() -> Event {
+    Event::new(VESTING_GATEWAY_OWNERSHIP_TRANSFER_CANCELLATION_EVENT_TYPE)
+}
+
+pub fn new_vesting_gateway_ownership_transfer_event() -> Event {
+    Event::new(VESTING_GATEWAY_OWNERSHIP_TRANSFER_EVENT_TYPE)
+}
+
 pub fn new_vesting_update_mixnode_config_event() -> Event {
     Event::new(VESTING_UPDATE_MIXNODE_CONFIG_EVENT_TYPE)
 }
 
+pub fn new_vesting_update_mixnode_keys_event() -> Event {
+    Event::new(VESTING_UPDATE_MIXNODE_KEYS_EVENT_TYPE)
+}
+
 pub fn new_vesting_update_mixnode_cost_params_event() -> Event {
     Event::new(VESTING_UPDATE_MIXNODE_COST_PARAMS_EVENT_TYPE)
 }
 
+pub fn new_vesting_mixnode_ownership_transfer_proposal_event() -> Event {
+    Event::new(VESTING_MIXNODE_OWNERSHIP_TRANSFER_PROPOSAL_EVENT_TYPE)
+}
+
+pub fn new_vesting_mixnode_ownership_transfer_cancellation_event() -> Event {
+    Event::new(VESTING_MIXNODE_OWNERSHIP_TRANSFER_CANCELLATION_EVENT_TYPE)
+}
+
+pub fn new_vesting_mixnode_ownership_transfer_event() -> Event {
+    Event::new(VESTING_MIXNODE_OWNERSHIP_TRANSFER_EVENT_TYPE)
+}
+
 pub fn new_vesting_mixnode_unbonding_event() -> Event {
     Event::new(VESTING_MIXNODE_UNBONDING_EVENT_TYPE)
 }
@@ -137,18 +198,38 @@ pub fn new_vesting_undelegation_event() -> Event {
     Event::new(VESTING_UNDELEGATION_EVENT_TYPE)
 }
 
+pub fn new_vesting_redelegation_event() -> Event {
+    Event::new(VESTING_REDELEGATION_EVENT_TYPE)
+}
+
 pub fn new_track_mixnode_unbond_event() -> Event {
     Event::new(TRACK_MIXNODE_UNBOND_EVENT_TYPE)
 }
 
+pub fn new_track_decrease_pledge_event() -> Event {
+    Event::new(TRACK_DECREASE_PLEDGE_EVENT_TYPE)
+}
+
 pub fn new_track_gateway_unbond_event() -> Event {
     Event::new(TRACK_GATEWAY_UNBOND_EVENT_TYPE)
 }
 
+pub fn new_track_mixnode_ownership_transfer_event() -> Event {
+    Event::new(TRACK_MIXNODE_OWNERSHIP_TRANSFER_EVENT_TYPE)
+}
+
+pub fn new_track_gateway_ownership_transfer_event() -> Event {
+    Event::new(TRACK_GATEWAY_OWNERSHIP_TRANSFER_EVENT_TYPE)
+}
+
 pub fn new_track_undelegation_event() -> Event {
     Event::new(TRACK_UNDELEGATION_EVENT_TYPE)
 }
 
+pub fn new_track_redelegation_event() -> Event {
+    Event::new(TRACK_REDELEGATION_EVENT_TYPE)
+}
+
 pub fn new_track_reward_event() -> Event {
     Event::new(TRACK_REWARD_EVENT_TYPE)
 }
@@ -1,7 +1,7 @@
 use cosmwasm_std::{Coin, Timestamp};
 use mixnet_contract_common::{
     mixnode::{MixNodeConfigUpdate, MixNodeCostParams},
-    Gateway, IdentityKey, MixId, MixNode,
+    Gateway, GatewayConfigUpdate, IdentityKey, MixId, MixNode, SphinxKey,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -91,6 +91,22 @@ pub enum ExecuteMsg {
     UpdateMixnodeConfig {
         new_config: MixNodeConfigUpdate,
     },
+    UpdateMixnodeKeys {
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+    },
+    ProposeMixnodeOwnershipTransfer {
+        new_owner: String,
+    },
+    CancelMixnodeOwnershipTransfer {},
+    AcceptMixnodeOwnershipTransfer {
+        mix_id: MixId,
+    },
+    TrackMixnodeOwnershipTransfer {
+        owner: String,
+        new_owner: String,
+    },
     UpdateMixnetAddress {
         address: String,
     },
@@ -103,6 +119,12 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         on_behalf_of: Option<String>,
     },
+    RedelegateMixnode {
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        on_behalf_of: Option<String>,
+    },
     CreateAccount {
         owner_address: String,
         staking_address: Option<String>,
@@ -117,6 +139,13 @@ pub enum ExecuteMsg {
         mix_id: MixId,
         amount: Coin,
     },
+    TrackRedelegation {
+        owner: String,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        remaining: Coin,
+    },
     BondMixnode {
         mix_node: MixNode,
         cost_params: MixNodeCostParams,
@@ -126,21 +155,47 @@ pub enum ExecuteMsg {
     PledgeMore {
         amount: Coin,
     },
+    DecreasePledge {
+        amount: Coin,
+    },
     UnbondMixnode {},
     TrackUnbondMixnode {
         owner: String,
         amount: Coin,
     },
+    TrackDecreasePledge {
+        owner: String,
+        amount: Coin,
+    },
     BondGateway {
         gateway: Gateway,
         owner_signature: String,
         amount: Coin,
     },
     UnbondGateway {},
+    UpdateGatewayConfig {
+        new_config: GatewayConfigUpdate,
+    },
+    UpdateGatewayKeys {
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+    },
     TrackUnbondGateway {
         owner: String,
         amount: Coin,
     },
+    ProposeGatewayOwnershipTransfer {
+        new_owner: String,
+    },
+    CancelGatewayOwnershipTransfer {},
+    AcceptGatewayOwnershipTransfer {
+        identity: IdentityKey,
+    },
+    TrackGatewayOwnershipTransfer {
+        owner: String,
+        new_owner: String,
+    },
     TransferOwnership {
         to_address: String,
     },
@@ -164,22 +219,53 @@ impl ExecuteMsg {
             ExecuteMsg::ClaimOperatorReward { .. } => "VestingExecuteMsg::ClaimOperatorReward",
             ExecuteMsg::ClaimDelegatorReward { .. } => "VestingExecuteMsg::ClaimDelegatorReward",
             ExecuteMsg::UpdateMixnodeConfig { .. } => "VestingExecuteMsg::UpdateMixnodeConfig",
+            ExecuteMsg::UpdateMixnodeKeys { .. } => "VestingExecuteMsg::UpdateMixnodeKeys",
+            ExecuteMsg::ProposeMixnodeOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::ProposeMixnodeOwnershipTransfer"
+            }
+            ExecuteMsg::CancelMixnodeOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::CancelMixnodeOwnershipTransfer"
+            }
+            ExecuteMsg::AcceptMixnodeOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::AcceptMixnodeOwnershipTransfer"
+            }
+            ExecuteMsg::TrackMixnodeOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::TrackMixnodeOwnershipTransfer"
+            }
             ExecuteMsg::UpdateMixnodeCostParams { .. } => {
                 "VestingExecuteMsg::UpdateMixnodeCostParams"
             }
             ExecuteMsg::UpdateMixnetAddress { .. } => "VestingExecuteMsg::UpdateMixnetAddress",
             ExecuteMsg::DelegateToMixnode { .. } => "VestingExecuteMsg::DelegateToMixnode",
             ExecuteMsg::UndelegateFromMixnode { .. } => "VestingExecuteMsg::UndelegateFromMixnode",
+            ExecuteMsg::RedelegateMixnode { .. } => "VestingExecuteMsg::RedelegateMixnode",
             ExecuteMsg::CreateAccount { .. } => "VestingExecuteMsg::CreateAccount",
             ExecuteMsg::WithdrawVestedCoins { .. } => "VestingExecuteMsg::WithdrawVestedCoins",
             ExecuteMsg::TrackUndelegation { .. } => "VestingExecuteMsg::TrackUndelegation",
+            ExecuteMsg::TrackRedelegation { .. } => "VestingExecuteMsg::TrackRedelegation",
             ExecuteMsg::BondMixnode { .. } => "VestingExecuteMsg::BondMixnode",
             ExecuteMsg::PledgeMore { .. } => "VestingExecuteMsg::PledgeMore",
+            ExecuteMsg::DecreasePledge { .. } => "VestingExecuteMsg::DecreasePledge",
             ExecuteMsg::UnbondMixnode { .. } => "VestingExecuteMsg::UnbondMixnode",
             ExecuteMsg::TrackUnbondMixnode { .. } => "VestingExecuteMsg::TrackUnbondMixnode",
+            ExecuteMsg::TrackDecreasePledge { .. } => "VestingExecuteMsg::TrackDecreasePledge",
             ExecuteMsg::BondGateway { .. } => "VestingExecuteMsg::BondGateway",
             ExecuteMsg::UnbondGateway { .. } => "VestingExecuteMsg::UnbondGateway",
+            ExecuteMsg::UpdateGatewayConfig { .. } => "VestingExecuteMsg::UpdateGatewayConfig",
+            ExecuteMsg::UpdateGatewayKeys { .. } => "VestingExecuteMsg::UpdateGatewayKeys",
             ExecuteMsg::TrackUnbondGateway { .. } => "VestingExecuteMsg::TrackUnbondGateway",
+            ExecuteMsg::ProposeGatewayOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::ProposeGatewayOwnershipTransfer"
+            }
+            ExecuteMsg::CancelGatewayOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::CancelGatewayOwnershipTransfer"
+            }
+            ExecuteMsg::AcceptGatewayOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::AcceptGatewayOwnershipTransfer"
+            }
+            ExecuteMsg::TrackGatewayOwnershipTransfer { .. } => {
+                "VestingExecuteMsg::TrackGatewayOwnershipTransfer"
+            }
             ExecuteMsg::TransferOwnership { .. } => "VestingExecuteMsg::TransferOwnership",
             ExecuteMsg::UpdateStakingAddress { .. } => "VestingExecuteMsg::UpdateStakingAddress",
             ExecuteMsg::UpdateLockedPledgeCap { .. } => "VestingExecuteMsg::UpdateLockedPledgeCap",
@@ -0,0 +1,109 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use mixnode_common::packet_processor::processor::SphinxPacketProcessor;
+use mixnode_common::packet_processor::replay::{ReplayFilter, ReplayProtectionConfig};
+use nymsphinx_addressing::nodes::NymNodeRoutingAddress;
+use nymsphinx_framing::packet::FramedSphinxPacket;
+use nymsphinx_params::{PacketSize, PacketType};
+use nymsphinx_types::{
+    crypto, Delay, Destination, DestinationAddressBytes, Node, NymPacket, PrivateKey,
+    DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH,
+};
+use std::convert::TryInto;
+use std::net::SocketAddr;
+
+fn make_packet(
+    packet_type: PacketType,
+    packet_size: PacketSize,
+) -> (PrivateKey, PacketType, Vec<u8>) {
+    let (first_hop_key, first_hop_public) = crypto::keygen();
+
+    // all the addresses have to be valid as the processor is going to parse them
+    let mut route = vec![Node::new(
+        NymNodeRoutingAddress::from(SocketAddr::from(([1, 1, 1, 1], 1789)))
+            .try_into()
+            .unwrap(),
+        first_hop_public,
+    )];
+    for i in 2..=3 {
+        route.push(Node::new(
+            NymNodeRoutingAddress::from(SocketAddr::from(([i, i, i, i], 1789)))
+                .try_into()
+                .unwrap(),
+            crypto::keygen().1,
+        ));
+    }
+    let destination = Destination::new(
+        DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+        [4u8; IDENTIFIER_LENGTH],
+    );
+    let delays = vec![Delay::new_from_nanos(42); route.len()];
+    let message = vec![42u8; 1024];
+
+    let packet = match packet_type {
+        PacketType::Sphinx => NymPacket::sphinx_build(
+            packet_size.payload_size(),
+            &message,
+            &route,
+            &destination,
+            &delays,
+        )
+        .unwrap(),
+        PacketType::Outfox => NymPacket::outfox_build(
+            packet_size.payload_size(),
+            &message,
+            &route,
+            &destination,
+            &delays,
+        )
+        .unwrap(),
+    };
+
+    (first_hop_key, packet_type, packet.to_bytes())
+}
+
+fn framed(packet_type: PacketType, bytes: &[u8]) -> FramedSphinxPacket {
+    let packet = match packet_type {
+        PacketType::Sphinx => NymPacket::sphinx_from_bytes(bytes).unwrap(),
+        PacketType::Outfox => NymPacket::outfox_from_bytes(bytes).unwrap(),
+    };
+    FramedSphinxPacket::new(packet, Default::default(), false)
+}
+
+fn bench_forward_hop_processing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("forward_hop_processing");
+
+    for packet_size in [PacketSize::RegularPacket, PacketSize::ExtendedPacket32] {
+        for packet_type in [PacketType::Sphinx, PacketType::Outfox] {
+            let (key, packet_type, bytes) = make_packet(packet_type, packet_size);
+
+            // we're processing the very same packet over and over again
+            let processor = SphinxPacketProcessor::new(
+                key,
+                ReplayFilter::new(ReplayProtectionConfig {
+                    enabled: false,
+                    ..Default::default()
+                }),
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{packet_type:?}"), format!("{packet_size:?}")),
+                &bytes,
+                |b, bytes| {
+                    b.iter_batched(
+                        || framed(packet_type, bytes),
+                        |packet| processor.process_received(packet).unwrap(),
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_forward_hop_processing);
+criterion_main!(benches);
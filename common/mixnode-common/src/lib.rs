@@ -2,4 +2,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod packet_processor;
+pub mod sphinx_keys;
 pub mod verloc;
@@ -3,7 +3,8 @@
 
 use nymsphinx_acknowledgements::surb_ack::SurbAckRecoveryError;
 use nymsphinx_addressing::nodes::NymNodeRoutingAddressError;
-use nymsphinx_types::Error as SphinxError;
+use nymsphinx_params::SphinxKeyRotation;
+use nymsphinx_types::{Error as SphinxError, OutFoxError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +12,9 @@ pub enum MixProcessingError {
     #[error("failed to process received packet: {0}")]
     SphinxProcessingError(#[from] SphinxError),
 
+    #[error("failed to process received outfox packet: {0}")]
+    OutfoxProcessingError(#[from] OutFoxError),
+
     #[error("the forward hop address was malformed: {0}")]
     InvalidForwardHopAddress(#[from] NymNodeRoutingAddressError),
 
@@ -22,4 +26,13 @@ pub enum MixProcessingError {
 
     #[error("the received packet was set to use the very old and very much deprecated 'VPN' mode")]
     ReceivedOldTypeVpnPacket,
+
+    #[error("the received packet has already been processed before")]
+    ReplayedPacket,
+
+    #[error("the node does not currently accept any sphinx keys")]
+    NoSphinxKeys,
+
+    #[error("the packet was created with a sphinx key ({key_rotation:?}) that the node does not currently accept")]
+    NoMatchingSphinxKey { key_rotation: SphinxKeyRotation },
 }
@@ -2,18 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::packet_processor::error::MixProcessingError;
+use crate::packet_processor::replay::{ReplayFilter, ReplayProtectionStats};
+use crate::sphinx_keys::ActiveSphinxKeys;
 use log::*;
 use nymsphinx_acknowledgements::surb_ack::SurbAck;
 use nymsphinx_addressing::nodes::NymNodeRoutingAddress;
 use nymsphinx_forwarding::packet::MixPacket;
 use nymsphinx_framing::packet::FramedSphinxPacket;
-use nymsphinx_params::{PacketMode, PacketSize};
+use nymsphinx_params::{PacketMode, PacketSize, SphinxKeyRotation};
 use nymsphinx_types::{
-    Delay as SphinxDelay, DestinationAddressBytes, NodeAddressBytes, Payload, PrivateKey,
-    ProcessedPacket, SphinxPacket,
+    Delay as SphinxDelay, DestinationAddressBytes, NodeAddressBytes, NymPacket, OutfoxPacket,
+    OutfoxProcessedPacket, PrivateKey, ProcessedPacket, SphinxPacket,
 };
 use std::convert::TryFrom;
-use std::sync::Arc;
 
 type ForwardAck = MixPacket;
 
@@ -31,58 +32,129 @@ pub enum MixProcessingResult {
     FinalHop(ProcessedFinalHop),
 }
 
+/// Result of unwrapping a single layer of encryption of either of the supported packet formats.
+enum UnwrappedPacket {
+    Sphinx(ProcessedPacket),
+    Outfox(OutfoxProcessedPacket),
+}
+
 #[derive(Clone)]
 pub struct SphinxPacketProcessor {
-    /// Private sphinx key of this node required to unwrap received sphinx packet.
-    sphinx_key: Arc<PrivateKey>,
+    /// Private sphinx keys of this node required to unwrap received sphinx packets alongside
+    /// filters of already processed packets used for rejecting any replays.
+    sphinx_keys: ActiveSphinxKeys,
 }
 
 impl SphinxPacketProcessor {
     /// Creates new instance of `CachedPacketProcessor`
-    pub fn new(sphinx_key: PrivateKey) -> Self {
+    pub fn new(sphinx_key: PrivateKey, replay_filter: ReplayFilter) -> Self {
         SphinxPacketProcessor {
-            sphinx_key: Arc::new(sphinx_key),
+            sphinx_keys: ActiveSphinxKeys::new_static(sphinx_key, replay_filter),
         }
     }
 
+    /// Creates new instance of `CachedPacketProcessor` using the provided, possibly rotating, set of keys.
+    pub fn new_with_keys(sphinx_keys: ActiveSphinxKeys) -> Self {
+        SphinxPacketProcessor { sphinx_keys }
+    }
+
+    pub fn replay_stats(&self) -> &ReplayProtectionStats {
+        self.sphinx_keys.replay_stats()
+    }
+
     /// Performs a fresh sphinx unwrapping using no cache.
     fn perform_initial_sphinx_packet_processing(
         &self,
         packet: SphinxPacket,
+        sphinx_key: &PrivateKey,
     ) -> Result<ProcessedPacket, MixProcessingError> {
-        packet.process(&self.sphinx_key).map_err(|err| {
+        packet.process(sphinx_key).map_err(|err| {
             debug!("Failed to unwrap Sphinx packet: {err}");
             MixProcessingError::SphinxProcessingError(err)
         })
     }
 
-    /// Takes the received framed packet and tries to unwrap it from the sphinx encryption.
+    /// Performs unwrapping of the outfox packet using the same key as for the sphinx packets.
+    fn perform_initial_outfox_packet_processing(
+        &self,
+        packet: OutfoxPacket,
+        sphinx_key: &PrivateKey,
+    ) -> Result<OutfoxProcessedPacket, MixProcessingError> {
+        packet.process(&sphinx_key.to_bytes()).map_err(|err| {
+            debug!("Failed to unwrap Outfox packet: {err}");
+            MixProcessingError::OutfoxProcessingError(err)
+        })
+    }
+
+    fn unwrap_with_key(
+        &self,
+        packet: NymPacket,
+        sphinx_key: &PrivateKey,
+    ) -> Result<UnwrappedPacket, MixProcessingError> {
+        match packet {
+            NymPacket::Sphinx(sphinx_packet) => self
+                .perform_initial_sphinx_packet_processing(sphinx_packet, sphinx_key)
+                .map(UnwrappedPacket::Sphinx),
+            NymPacket::Outfox(outfox_packet) => self
+                .perform_initial_outfox_packet_processing(outfox_packet, sphinx_key)
+                .map(UnwrappedPacket::Outfox),
+        }
+    }
+
+    /// Takes the received framed packet and tries to unwrap it from its layer of encryption.
     fn perform_initial_unwrapping(
         &self,
         received: FramedSphinxPacket,
-    ) -> Result<ProcessedPacket, MixProcessingError> {
+    ) -> Result<UnwrappedPacket, MixProcessingError> {
         let packet_mode = received.packet_mode();
-        let sphinx_packet = received.into_inner();
+        let key_rotation = received.key_rotation();
+        let packet = received.into_inner();
 
         if packet_mode.is_old_vpn() {
             return Err(MixProcessingError::ReceivedOldTypeVpnPacket);
         }
 
-        self.perform_initial_sphinx_packet_processing(sphinx_packet)
+        let replay_tag = match &packet {
+            NymPacket::Sphinx(sphinx_packet) => *sphinx_packet.header.shared_secret.as_bytes(),
+            NymPacket::Outfox(outfox_packet) => outfox_packet.replay_tag(),
+        };
+
+        // the packet tells us which of our keys it has been created with,
+        // so that we would not have to try all of them
+        let keys = self.sphinx_keys.read();
+        if keys.is_empty() {
+            return Err(MixProcessingError::NoSphinxKeys);
+        }
+        let active = keys
+            .iter()
+            .find(|active| key_rotation.matches(active.epoch))
+            .ok_or(MixProcessingError::NoMatchingSphinxKey { key_rotation })?;
+
+        let processed = self.unwrap_with_key(packet, &active.key)?;
+
+        // only remember packets that we managed to unwrap so that garbage could not be used
+        // for exhausting the memory of the filter
+        if !active.replay_filter.insert(replay_tag) {
+            debug!("Received a replayed packet");
+            return Err(MixProcessingError::ReplayedPacket);
+        }
+        Ok(processed)
     }
 
     /// Processed received forward hop packet - tries to extract next hop address, sets delay
     /// and packs all the data in a way that can be easily sent to the next hop.
     fn process_forward_hop(
         &self,
-        packet: SphinxPacket,
+        packet: NymPacket,
         forward_address: NodeAddressBytes,
         delay: SphinxDelay,
         packet_mode: PacketMode,
     ) -> Result<MixProcessingResult, MixProcessingError> {
+        let key_rotation = SphinxKeyRotation::from_node_address(&forward_address);
         let next_hop_address = NymNodeRoutingAddress::try_from(forward_address)?;
 
-        let mix_packet = MixPacket::new(next_hop_address, packet, packet_mode);
+        let mix_packet =
+            MixPacket::new(next_hop_address, packet, packet_mode).with_key_rotation(key_rotation);
         Ok(MixProcessingResult::ForwardHop(mix_packet, Some(delay)))
     }
 
@@ -122,8 +194,10 @@ impl SphinxPacketProcessor {
             | PacketSize::ExtendedPacket32 => {
                 trace!("received a normal packet!");
                 let (ack_data, message) = self.split_hop_data_into_ack_and_message(data)?;
-                let (ack_first_hop, ack_packet) = SurbAck::try_recover_first_hop_packet(&ack_data)?;
-                let forward_ack = MixPacket::new(ack_first_hop, ack_packet, packet_mode);
+                let (ack_first_hop, key_rotation, ack_packet) =
+                    SurbAck::try_recover_first_hop_packet(&ack_data)?;
+                let forward_ack = MixPacket::new(ack_first_hop, ack_packet.into(), packet_mode)
+                    .with_key_rotation(key_rotation);
                 Ok((Some(forward_ack), message))
             }
         }
@@ -135,12 +209,10 @@ impl SphinxPacketProcessor {
     fn process_final_hop(
         &self,
         destination: DestinationAddressBytes,
-        payload: Payload,
+        packet_message: Vec<u8>,
         packet_size: PacketSize,
         packet_mode: PacketMode,
     ) -> Result<MixProcessingResult, MixProcessingError> {
-        let packet_message = payload.recover_plaintext()?;
-
         let (forward_ack, message) =
             self.split_into_ack_and_message(packet_message, packet_size, packet_mode)?;
 
@@ -155,18 +227,28 @@ impl SphinxPacketProcessor {
     /// or a final hop.
     fn perform_final_processing(
         &self,
-        packet: ProcessedPacket,
+        packet: UnwrappedPacket,
         packet_size: PacketSize,
         packet_mode: PacketMode,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         match packet {
-            ProcessedPacket::ForwardHop(packet, address, delay) => {
-                self.process_forward_hop(*packet, address, delay, packet_mode)
+            UnwrappedPacket::Sphinx(ProcessedPacket::ForwardHop(packet, address, delay)) => {
+                self.process_forward_hop(NymPacket::Sphinx(*packet), address, delay, packet_mode)
             }
             // right now there's no use for the surb_id included in the header - probably it should get removed from the
             // sphinx all together?
-            ProcessedPacket::FinalHop(destination, _, payload) => {
-                self.process_final_hop(destination, payload, packet_size, packet_mode)
+            UnwrappedPacket::Sphinx(ProcessedPacket::FinalHop(destination, _, payload)) => {
+                let packet_message = payload.recover_plaintext()?;
+                self.process_final_hop(destination, packet_message, packet_size, packet_mode)
+            }
+            UnwrappedPacket::Outfox(OutfoxProcessedPacket::ForwardHop(packet, routing)) => {
+                let address = NodeAddressBytes::from_bytes(routing.address);
+                let delay = SphinxDelay::new_from_nanos(routing.delay_nanos);
+                self.process_forward_hop(NymPacket::Outfox(*packet), address, delay, packet_mode)
+            }
+            UnwrappedPacket::Outfox(OutfoxProcessedPacket::FinalHop(routing, message)) => {
+                let destination = DestinationAddressBytes::from_bytes(routing.address);
+                self.process_final_hop(destination, message, packet_size, packet_mode)
             }
         }
     }
@@ -179,7 +261,7 @@ impl SphinxPacketProcessor {
         let packet_size = received.packet_size();
         let packet_mode = received.packet_mode();
 
-        // unwrap the sphinx packet and if possible and appropriate, cache keys
+        // unwrap the packet and if possible and appropriate, cache keys
         let processed_packet = self.perform_initial_unwrapping(received)?;
 
         // for forward packets, extract next hop and set delay (but do NOT delay here)
@@ -192,11 +274,18 @@ impl SphinxPacketProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::packet_processor::replay::ReplayProtectionConfig;
     use nymsphinx_types::crypto::keygen;
 
+    fn fixture_with_key(sphinx_key: PrivateKey) -> SphinxPacketProcessor {
+        SphinxPacketProcessor::new(
+            sphinx_key,
+            ReplayFilter::new(ReplayProtectionConfig::default()),
+        )
+    }
+
     fn fixture() -> SphinxPacketProcessor {
-        let local_keys = keygen();
-        SphinxPacketProcessor::new(local_keys.0)
+        fixture_with_key(keygen().0)
     }
 
     #[tokio::test]
@@ -234,4 +323,233 @@ mod tests {
         assert!(ack.is_none());
         assert_eq!(data, message)
     }
+
+    #[tokio::test]
+    async fn replayed_packets_are_rejected() {
+        use nymsphinx_types::builder::SphinxPacketBuilder;
+        use nymsphinx_types::{
+            Destination, Node, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        };
+        use std::convert::TryInto;
+        use std::net::SocketAddr;
+
+        let (local_private, local_public) = keygen();
+        let processor = SphinxPacketProcessor::new(
+            local_private,
+            ReplayFilter::new(ReplayProtectionConfig::default()),
+        );
+
+        let node1 = Node::new(
+            NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+            local_public,
+        );
+        // the address of the next hop has to be valid as we're going to parse it
+        let node2_address = NymNodeRoutingAddress::from(SocketAddr::from(([1, 2, 3, 4], 1789)));
+        let node2 = Node::new(node2_address.try_into().unwrap(), keygen().1);
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![
+            SphinxDelay::new_from_nanos(42),
+            SphinxDelay::new_from_nanos(42),
+        ];
+        let packet = SphinxPacketBuilder::new()
+            .with_payload_size(PacketSize::default().payload_size())
+            .build_packet(b"foomp", &[node1, node2], &destination, &delays)
+            .unwrap();
+        let packet_bytes = packet.to_bytes();
+
+        let framed = FramedSphinxPacket::new(packet.into(), Default::default(), false);
+        assert!(processor.process_received(framed).is_ok());
+
+        let replayed = SphinxPacket::from_bytes(&packet_bytes).unwrap();
+        let framed = FramedSphinxPacket::new(replayed.into(), Default::default(), false);
+        assert!(matches!(
+            processor.process_received(framed),
+            Err(MixProcessingError::ReplayedPacket)
+        ));
+        assert_eq!(processor.replay_stats().replayed_packets(), 1);
+    }
+
+    #[tokio::test]
+    async fn packets_are_processed_with_the_key_indicated_by_the_hint() {
+        use nymsphinx_types::builder::SphinxPacketBuilder;
+        use nymsphinx_types::{
+            Destination, Node, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        };
+        use std::convert::TryInto;
+        use std::net::SocketAddr;
+
+        let (old_private, old_public) = keygen();
+        let sphinx_keys = ActiveSphinxKeys::new(ReplayProtectionConfig::default());
+        sphinx_keys.insert(Some(1), old_private);
+        sphinx_keys.insert(Some(2), keygen().0);
+        let processor = SphinxPacketProcessor::new_with_keys(sphinx_keys.clone());
+
+        let node1 = Node::new(
+            NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+            old_public,
+        );
+        let node2_address = NymNodeRoutingAddress::from(SocketAddr::from(([1, 2, 3, 4], 1789)));
+        let node2 = Node::new(node2_address.try_into().unwrap(), keygen().1);
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![
+            SphinxDelay::new_from_nanos(42),
+            SphinxDelay::new_from_nanos(42),
+        ];
+        let packet = SphinxPacketBuilder::new()
+            .with_payload_size(PacketSize::default().payload_size())
+            .build_packet(b"foomp", &[node1, node2], &destination, &delays)
+            .unwrap();
+        let packet_bytes = packet.to_bytes();
+
+        let old_key_rotation = SphinxKeyRotation::from_key_epoch(Some(1));
+
+        // the packet is only unwrapped with the key it claims to have been created with
+        let framed = FramedSphinxPacket::new(packet.into(), Default::default(), false)
+            .with_key_rotation(SphinxKeyRotation::from_key_epoch(Some(2)));
+        assert!(matches!(
+            processor.process_received(framed),
+            Err(MixProcessingError::SphinxProcessingError(_))
+        ));
+
+        // the packet was created with the older key, which is still accepted
+        let resent = SphinxPacket::from_bytes(&packet_bytes).unwrap();
+        let framed = FramedSphinxPacket::new(resent.into(), Default::default(), false)
+            .with_key_rotation(old_key_rotation);
+        assert!(processor.process_received(framed).is_ok());
+
+        // once the old key is removed, the packets can no longer be processed
+        sphinx_keys.remove_older_than(2);
+        let resent = SphinxPacket::from_bytes(&packet_bytes).unwrap();
+        let framed = FramedSphinxPacket::new(resent.into(), Default::default(), false)
+            .with_key_rotation(old_key_rotation);
+        assert!(matches!(
+            processor.process_received(framed),
+            Err(MixProcessingError::NoMatchingSphinxKey { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn key_rotation_hint_is_forwarded_to_the_next_hop() {
+        use nymsphinx_types::builder::SphinxPacketBuilder;
+        use nymsphinx_types::{
+            Destination, Node, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        };
+        use std::convert::TryInto;
+        use std::net::SocketAddr;
+
+        let (local_private, local_public) = keygen();
+        let processor = fixture_with_key(local_private);
+
+        let node1 = Node::new(
+            NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
+            local_public,
+        );
+        let node2_address = NymNodeRoutingAddress::from(SocketAddr::from(([1, 2, 3, 4], 1789)));
+        let node2_key_rotation = SphinxKeyRotation::from_key_epoch(Some(7));
+        let node2 = Node::new(
+            node2_key_rotation.attach_to_node_address(node2_address.try_into().unwrap()),
+            keygen().1,
+        );
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = vec![
+            SphinxDelay::new_from_nanos(42),
+            SphinxDelay::new_from_nanos(42),
+        ];
+        let packet = SphinxPacketBuilder::new()
+            .with_payload_size(PacketSize::default().payload_size())
+            .build_packet(b"foomp", &[node1, node2], &destination, &delays)
+            .unwrap();
+
+        let framed = FramedSphinxPacket::new(packet.into(), Default::default(), false);
+        match processor.process_received(framed).unwrap() {
+            MixProcessingResult::ForwardHop(packet, _) => {
+                assert_eq!(packet.next_hop(), node2_address);
+                assert_eq!(packet.key_rotation(), node2_key_rotation);
+            }
+            MixProcessingResult::FinalHop(_) => panic!("expected a forward hop"),
+        }
+    }
+
+    #[tokio::test]
+    async fn outfox_packets_are_processed() {
+        use nymsphinx_types::{Destination, Node, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH};
+        use std::convert::TryInto;
+        use std::net::SocketAddr;
+
+        let (node1_private, node1_public) = keygen();
+        let (node2_private, node2_public) = keygen();
+        let processor1 = SphinxPacketProcessor::new(
+            node1_private,
+            ReplayFilter::new(ReplayProtectionConfig::default()),
+        );
+        let processor2 = SphinxPacketProcessor::new(
+            node2_private,
+            ReplayFilter::new(ReplayProtectionConfig::default()),
+        );
+
+        let node1_address = NymNodeRoutingAddress::from(SocketAddr::from(([1, 1, 1, 1], 1789)));
+        let node2_address = NymNodeRoutingAddress::from(SocketAddr::from(([1, 2, 3, 4], 1789)));
+        let route = [
+            Node::new(node1_address.try_into().unwrap(), node1_public),
+            Node::new(node2_address.try_into().unwrap(), node2_public),
+        ];
+        let destination_address =
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]);
+        let destination = Destination::new(destination_address, [4u8; IDENTIFIER_LENGTH]);
+        let delays = vec![
+            SphinxDelay::new_from_nanos(42),
+            SphinxDelay::new_from_nanos(42),
+        ];
+        let packet_size = PacketSize::AckPacket;
+        let packet = NymPacket::outfox_build(
+            packet_size.payload_size(),
+            b"foomp",
+            &route,
+            &destination,
+            &delays,
+        )
+        .unwrap();
+        let packet_bytes = packet.to_bytes();
+
+        let framed = FramedSphinxPacket::new(packet, Default::default(), false);
+        assert_eq!(framed.packet_size(), packet_size);
+        let forward = match processor1.process_received(framed).unwrap() {
+            MixProcessingResult::ForwardHop(packet, delay) => {
+                assert_eq!(packet.next_hop(), node2_address);
+                assert_eq!(delay.unwrap().to_nanos(), 42);
+                packet
+            }
+            MixProcessingResult::FinalHop(_) => panic!("expected a forward hop"),
+        };
+
+        let framed = FramedSphinxPacket::new(forward.into_packet(), Default::default(), false);
+        match processor2.process_received(framed).unwrap() {
+            MixProcessingResult::FinalHop(final_hop) => {
+                assert_eq!(
+                    final_hop.destination.as_bytes_ref(),
+                    destination_address.as_bytes_ref()
+                );
+                assert!(final_hop.forward_ack.is_none());
+                assert_eq!(final_hop.message, b"foomp");
+            }
+            MixProcessingResult::ForwardHop(..) => panic!("expected a final hop"),
+        }
+
+        // outfox packets are subject to the same replay protection as sphinx ones
+        let replayed = NymPacket::outfox_from_bytes(&packet_bytes).unwrap();
+        let framed = FramedSphinxPacket::new(replayed, Default::default(), false);
+        assert!(matches!(
+            processor1.process_received(framed),
+            Err(MixProcessingError::ReplayedPacket)
+        ));
+    }
 }
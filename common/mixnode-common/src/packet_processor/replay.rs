@@ -0,0 +1,265 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Length of the tag identifying particular sphinx packet at this node.
+pub const REPLAY_TAG_LENGTH: usize = 32;
+
+/// Tag identifying particular sphinx packet at this node. Currently it's the packet's
+/// (blinded) shared secret as seen by this hop, which is unique for every packet and every hop.
+pub type ReplayTag = [u8; REPLAY_TAG_LENGTH];
+
+// Approximate number of bytes used by a single entry of the `HashSet`. The tag itself
+// plus the control bytes and the spare capacity the set keeps around.
+const APPROXIMATE_ENTRY_SIZE: usize = 2 * REPLAY_TAG_LENGTH;
+
+/// By default, remember the packets for an hour.
+pub const DEFAULT_REPLAY_PROTECTION_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// By default, use at most 256MB for storing the tags.
+pub const DEFAULT_REPLAY_PROTECTION_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReplayProtectionConfig {
+    /// Specifies whether the packets should be checked against the replay filter at all.
+    pub enabled: bool,
+
+    /// Minimum duration for which a packet is going to be remembered.
+    /// Note that it should be at least as long as the lifetime of the sphinx key, as after
+    /// the key gets rotated, any packets created with the old key will be rejected anyway.
+    #[serde(with = "humantime_serde")]
+    pub window: Duration,
+
+    /// Maximum number of bytes that might be used for storing the tags of received packets.
+    /// If the budget is exceeded before the window elapses, the oldest tags are going to be
+    /// discarded early.
+    pub memory_budget: usize,
+}
+
+impl Default for ReplayProtectionConfig {
+    fn default() -> Self {
+        ReplayProtectionConfig {
+            enabled: true,
+            window: DEFAULT_REPLAY_PROTECTION_WINDOW,
+            memory_budget: DEFAULT_REPLAY_PROTECTION_MEMORY_BUDGET,
+        }
+    }
+}
+
+impl ReplayProtectionConfig {
+    fn max_entries_per_generation(&self) -> usize {
+        // the budget is split between the current and the previous generation
+        (self.memory_budget / APPROXIMATE_ENTRY_SIZE / 2).max(1)
+    }
+}
+
+/// Counters associated with the replay filter.
+#[derive(Debug, Default)]
+pub struct ReplayProtectionStats {
+    replayed_packets: AtomicU64,
+    premature_rotations: AtomicU64,
+}
+
+impl ReplayProtectionStats {
+    /// Total number of packets rejected as replays.
+    pub fn replayed_packets(&self) -> u64 {
+        self.replayed_packets.load(Ordering::Relaxed)
+    }
+
+    /// Number of times the filter had to discard tags before the end of the window
+    /// due to running out of its memory budget.
+    pub fn premature_rotations(&self) -> u64 {
+        self.premature_rotations.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+struct FilterInner {
+    current: HashSet<ReplayTag>,
+    previous: HashSet<ReplayTag>,
+    current_started: Instant,
+}
+
+/// Bounded, time-windowed filter of already seen sphinx packets.
+///
+/// The tags are kept in two generations: whenever the window elapses, the current generation
+/// becomes the previous one and the old previous generation gets discarded.
+/// This way every tag is remembered for at least the duration of the window whilst the memory
+/// usage stays bounded.
+#[derive(Debug, Clone)]
+pub struct ReplayFilter {
+    config: ReplayProtectionConfig,
+    inner: Arc<Mutex<FilterInner>>,
+    stats: Arc<ReplayProtectionStats>,
+}
+
+impl ReplayFilter {
+    pub fn new(config: ReplayProtectionConfig) -> Self {
+        Self::new_with_stats(config, Default::default())
+    }
+
+    /// Creates a new filter that reports to the provided, possibly shared, counters.
+    pub fn new_with_stats(
+        config: ReplayProtectionConfig,
+        stats: Arc<ReplayProtectionStats>,
+    ) -> Self {
+        ReplayFilter {
+            config,
+            inner: Arc::new(Mutex::new(FilterInner {
+                current: HashSet::new(),
+                previous: HashSet::new(),
+                current_started: Instant::now(),
+            })),
+            stats,
+        }
+    }
+
+    pub fn config(&self) -> ReplayProtectionConfig {
+        self.config
+    }
+
+    pub fn stats(&self) -> &ReplayProtectionStats {
+        &self.stats
+    }
+
+    pub fn shared_stats(&self) -> Arc<ReplayProtectionStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// Number of tags currently held by the filter.
+    pub fn len(&self) -> usize {
+        let guard = self.inner.lock().expect("replay filter mutex got poisoned");
+        guard.current.len() + guard.previous.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discards all stored tags. It should be called after the sphinx key got rotated and the
+    /// previous key is no longer accepted, as the old packets can't be processed anymore.
+    pub fn clear(&self) {
+        let mut guard = self.inner.lock().expect("replay filter mutex got poisoned");
+        guard.current = HashSet::new();
+        guard.previous = HashSet::new();
+        guard.current_started = Instant::now();
+    }
+
+    fn rotate(&self, inner: &mut FilterInner, now: Instant) {
+        // if we haven't rotated for two full windows, the previous generation is stale too
+        if now.duration_since(inner.current_started) >= 2 * self.config.window {
+            inner.previous = HashSet::new();
+        } else {
+            inner.previous = mem::take(&mut inner.current);
+        }
+        inner.current = HashSet::new();
+        inner.current_started = now;
+    }
+
+    /// Attempts to insert the tag into the filter. Returns `false` if it was already present,
+    /// i.e. the packet is a replay.
+    pub fn insert(&self, tag: ReplayTag) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        self.insert_at(tag, Instant::now())
+    }
+
+    fn insert_at(&self, tag: ReplayTag, now: Instant) -> bool {
+        let mut guard = self.inner.lock().expect("replay filter mutex got poisoned");
+
+        if guard.current.contains(&tag) || guard.previous.contains(&tag) {
+            self.stats.replayed_packets.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+
+        if now.duration_since(guard.current_started) >= self.config.window {
+            self.rotate(&mut guard, now);
+        } else if guard.current.len() >= self.config.max_entries_per_generation() {
+            self.stats
+                .premature_rotations
+                .fetch_add(1, Ordering::Relaxed);
+            self.rotate(&mut guard, now);
+        }
+
+        guard.current.insert(tag);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(window: Duration, max_entries: usize) -> ReplayFilter {
+        ReplayFilter::new(ReplayProtectionConfig {
+            enabled: true,
+            window,
+            memory_budget: max_entries * APPROXIMATE_ENTRY_SIZE * 2,
+        })
+    }
+
+    #[test]
+    fn duplicate_tags_are_rejected() {
+        let filter = filter(Duration::from_secs(60), 100);
+        assert!(filter.insert([1u8; 32]));
+        assert!(filter.insert([2u8; 32]));
+        assert!(!filter.insert([1u8; 32]));
+        assert_eq!(filter.stats().replayed_packets(), 1);
+        assert_eq!(filter.len(), 2);
+    }
+
+    #[test]
+    fn tags_are_remembered_for_at_least_the_window() {
+        let window = Duration::from_secs(60);
+        let filter = filter(window, 100);
+        let start = Instant::now();
+
+        assert!(filter.insert_at([1u8; 32], start));
+        // forces rotation, the first tag is moved to the previous generation
+        assert!(filter.insert_at([2u8; 32], start + window));
+        assert!(!filter.insert_at([1u8; 32], start + window));
+
+        // another rotation discards the first tag
+        assert!(filter.insert_at([3u8; 32], start + 2 * window));
+        assert!(filter.insert_at([1u8; 32], start + 2 * window));
+        assert!(!filter.insert_at([2u8; 32], start + 2 * window));
+    }
+
+    #[test]
+    fn memory_budget_is_respected() {
+        let filter = filter(Duration::from_secs(60), 10);
+        let now = Instant::now();
+        for i in 0..100u8 {
+            assert!(filter.insert_at([i; 32], now));
+        }
+        assert!(filter.len() <= 20);
+        assert!(filter.stats().premature_rotations() > 0);
+    }
+
+    #[test]
+    fn disabled_filter_accepts_everything() {
+        let filter = ReplayFilter::new(ReplayProtectionConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        assert!(filter.insert([1u8; 32]));
+        assert!(filter.insert([1u8; 32]));
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn clearing_discards_all_tags() {
+        let filter = filter(Duration::from_secs(60), 100);
+        assert!(filter.insert([1u8; 32]));
+        filter.clear();
+        assert!(filter.insert([1u8; 32]));
+    }
+}
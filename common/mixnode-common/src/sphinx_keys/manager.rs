@@ -0,0 +1,313 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sphinx_keys::{ActiveSphinxKeys, SphinxKeyRotationConfig};
+use crypto::asymmetric::{encryption, identity};
+use log::*;
+use pemstore::traits::PemStorableKeyPair;
+use pemstore::KeyPairPath;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+use task::TaskClient;
+use topology::sphinx_key::{KeyEpoch, SignedSphinxKey};
+
+fn current_unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the system clock is set to before the unix epoch")
+        .as_secs()
+}
+
+/// Signed public sphinx keys of the current and the upcoming epochs the node makes available to the clients.
+#[derive(Clone, Default)]
+pub struct AnnouncedSphinxKeys(Arc<RwLock<Vec<SignedSphinxKey>>>);
+
+impl AnnouncedSphinxKeys {
+    pub fn get(&self) -> Vec<SignedSphinxKey> {
+        self.0
+            .read()
+            .expect("announced sphinx keys lock got poisoned")
+            .clone()
+    }
+
+    fn set(&self, keys: Vec<SignedSphinxKey>) {
+        *self
+            .0
+            .write()
+            .expect("announced sphinx keys lock got poisoned") = keys;
+    }
+}
+
+/// Periodically generates sphinx keys for the upcoming epochs, announces them and updates
+/// the set of keys for which the packets are accepted.
+pub struct SphinxKeyManager {
+    config: SphinxKeyRotationConfig,
+    identity_keys: Arc<identity::KeyPair>,
+    keys_directory: PathBuf,
+
+    /// Keys of all the epochs that are either still accepted or have already been announced.
+    epoch_keys: BTreeMap<KeyEpoch, encryption::KeyPair>,
+
+    active_keys: ActiveSphinxKeys,
+    announced_keys: AnnouncedSphinxKeys,
+}
+
+impl SphinxKeyManager {
+    pub fn new<P: AsRef<Path>>(
+        config: SphinxKeyRotationConfig,
+        identity_keys: Arc<identity::KeyPair>,
+        keys_directory: P,
+        active_keys: ActiveSphinxKeys,
+    ) -> Self {
+        SphinxKeyManager {
+            config,
+            identity_keys,
+            keys_directory: keys_directory.as_ref().to_path_buf(),
+            epoch_keys: BTreeMap::new(),
+            active_keys,
+            announced_keys: Default::default(),
+        }
+    }
+
+    pub fn announced_keys(&self) -> AnnouncedSphinxKeys {
+        self.announced_keys.clone()
+    }
+
+    fn private_key_path(&self, epoch: KeyEpoch) -> PathBuf {
+        self.keys_directory
+            .join(format!("private_sphinx_{epoch}.pem"))
+    }
+
+    fn public_key_path(&self, epoch: KeyEpoch) -> PathBuf {
+        self.keys_directory
+            .join(format!("public_sphinx_{epoch}.pem"))
+    }
+
+    fn key_paths(&self, epoch: KeyEpoch) -> KeyPairPath {
+        KeyPairPath::new(self.private_key_path(epoch), self.public_key_path(epoch))
+    }
+
+    fn stored_epochs(&self) -> io::Result<Vec<KeyEpoch>> {
+        if !self.keys_directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut epochs = Vec::new();
+        for entry in fs::read_dir(&self.keys_directory)? {
+            let file_name = entry?.file_name();
+            let epoch = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix("private_sphinx_"))
+                .and_then(|name| name.strip_suffix(".pem"))
+                .and_then(|epoch| epoch.parse().ok());
+            if let Some(epoch) = epoch {
+                epochs.push(epoch)
+            }
+        }
+        Ok(epochs)
+    }
+
+    fn remove_stored_key(&self, epoch: KeyEpoch) {
+        for path in [self.private_key_path(epoch), self.public_key_path(epoch)] {
+            if let Err(err) = fs::remove_file(&path) {
+                warn!("failed to remove sphinx key file {}: {err}", path.display())
+            }
+        }
+    }
+
+    fn load_or_generate_key(&mut self, epoch: KeyEpoch) -> io::Result<()> {
+        if self.epoch_keys.contains_key(&epoch) {
+            return Ok(());
+        }
+
+        let paths = self.key_paths(epoch);
+        let keys = match pemstore::load_keypair::<encryption::KeyPair>(&paths) {
+            Ok(keys) => keys,
+            Err(_) => {
+                debug!("generating new sphinx key for epoch {epoch}");
+                let (private_key, public_key) = nymsphinx_types::crypto::keygen();
+                let keys = encryption::KeyPair::from_keys(private_key.into(), public_key.into());
+                pemstore::store_keypair(&keys, &paths)?;
+                keys
+            }
+        };
+        self.epoch_keys.insert(epoch, keys);
+        Ok(())
+    }
+
+    /// Brings the keys up to date with the provided time: makes sure the keys for the current
+    /// and all announced epochs exist, stops accepting keys past their grace period and removes them.
+    pub fn rotate(&mut self, unix_timestamp: u64) -> io::Result<()> {
+        let current_epoch = self.config.epoch_at(unix_timestamp);
+        let last_announced = current_epoch.saturating_add(self.config.announced_epochs);
+
+        for epoch in current_epoch..=last_announced {
+            self.load_or_generate_key(epoch)?;
+        }
+
+        // the previous key is still accepted during the grace period
+        let previous_epoch = current_epoch.checked_sub(1);
+        if let Some(previous) = previous_epoch {
+            if unix_timestamp < self.config.epoch_expiry(previous) {
+                self.load_or_generate_key(previous)?;
+            }
+        }
+
+        // get rid of everything that's no longer usable, including keys left over by previous runs
+        let mut expired = self.stored_epochs()?;
+        expired.extend(self.epoch_keys.keys());
+        expired.sort_unstable();
+        expired.dedup();
+        for epoch in expired {
+            if epoch > last_announced || self.config.epoch_expiry(epoch) <= unix_timestamp {
+                debug!("removing expired sphinx key for epoch {epoch}");
+                self.epoch_keys.remove(&epoch);
+                self.remove_stored_key(epoch);
+            }
+        }
+
+        // packets can only be created with the keys of epochs that have already started
+        for (epoch, keys) in self.epoch_keys.range(..=current_epoch) {
+            if !self.active_keys.contains(Some(*epoch)) {
+                info!("starting to accept packets for sphinx key of epoch {epoch}");
+                self.active_keys
+                    .insert(Some(*epoch), keys.private_key().into())
+            }
+        }
+        let oldest_accepted = self
+            .epoch_keys
+            .keys()
+            .next()
+            .copied()
+            .unwrap_or(current_epoch);
+        self.active_keys.remove_older_than(oldest_accepted);
+
+        let announced = self
+            .epoch_keys
+            .range(current_epoch..)
+            .map(|(epoch, keys)| {
+                SignedSphinxKey::new(
+                    *epoch,
+                    self.config.epoch_start(*epoch),
+                    self.config.epoch_expiry(*epoch),
+                    keys.public_key(),
+                    self.identity_keys.private_key(),
+                )
+            })
+            .collect();
+        self.announced_keys.set(announced);
+
+        Ok(())
+    }
+
+    /// Time until the next change in the set of accepted keys, i.e. either the start of the next
+    /// epoch or the end of the grace period of the previous one.
+    fn time_until_next_rotation(&self, unix_timestamp: u64) -> Duration {
+        let current_epoch = self.config.epoch_at(unix_timestamp);
+        let next_epoch_start = self.config.epoch_start(current_epoch + 1);
+        let next_change = match current_epoch.checked_sub(1) {
+            Some(previous) if self.config.epoch_expiry(previous) > unix_timestamp => {
+                self.config.epoch_expiry(previous).min(next_epoch_start)
+            }
+            _ => next_epoch_start,
+        };
+        Duration::from_secs(next_change.saturating_sub(unix_timestamp).max(1))
+    }
+
+    pub async fn run(&mut self, mut shutdown: TaskClient) {
+        debug!("Started SphinxKeyManager with graceful shutdown support");
+
+        while !shutdown.is_shutdown() {
+            let now = current_unix_timestamp();
+            if let Err(err) = self.rotate(now) {
+                error!("failed to rotate the sphinx keys: {err}");
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(self.time_until_next_rotation(now)) => {},
+                _ = shutdown.recv() => {
+                    trace!("SphinxKeyManager: Received shutdown");
+                }
+            }
+        }
+
+        trace!("SphinxKeyManager: Exiting");
+    }
+
+    /// Performs the initial rotation, so that the keys would be available before the node starts
+    /// receiving any packets, and keeps on rotating them in the background.
+    pub fn start(mut self, shutdown: TaskClient) -> io::Result<AnnouncedSphinxKeys> {
+        self.rotate(current_unix_timestamp())?;
+        let announced_keys = self.announced_keys();
+        tokio::spawn(async move { self.run(shutdown).await });
+        Ok(announced_keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(keys_directory: &Path) -> SphinxKeyManager {
+        let mut rng = rand_07::rngs::OsRng;
+        let config = SphinxKeyRotationConfig {
+            enabled: true,
+            rotation_interval: Duration::from_secs(100),
+            grace_period: Duration::from_secs(10),
+            announced_epochs: 2,
+            accept_bonded_key: false,
+        };
+        SphinxKeyManager::new(
+            config,
+            Arc::new(identity::KeyPair::new(&mut rng)),
+            keys_directory,
+            ActiveSphinxKeys::new(Default::default()),
+        )
+    }
+
+    #[test]
+    fn keys_are_rotated_with_the_epochs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = manager(dir.path());
+
+        manager.rotate(1050).unwrap();
+        assert_eq!(manager.active_keys.epochs(), vec![Some(10)]);
+        let announced = manager.announced_keys().get();
+        assert_eq!(
+            announced.iter().map(|key| key.epoch).collect::<Vec<_>>(),
+            vec![10, 11, 12]
+        );
+        assert_eq!(announced[0].valid_from, 1000);
+        assert_eq!(announced[0].valid_until, 1110);
+        assert!(announced[0]
+            .verify(manager.identity_keys.public_key())
+            .is_ok());
+
+        // within the grace period both keys are accepted
+        manager.rotate(1105).unwrap();
+        assert_eq!(manager.active_keys.epochs(), vec![Some(11), Some(10)]);
+
+        // and afterwards only the new one
+        manager.rotate(1110).unwrap();
+        assert_eq!(manager.active_keys.epochs(), vec![Some(11)]);
+        assert_eq!(manager.stored_epochs().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn keys_are_persisted_between_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager1 = manager(dir.path());
+        manager1.rotate(1050).unwrap();
+
+        let mut manager2 = manager(dir.path());
+        manager2.rotate(1050).unwrap();
+
+        assert_eq!(
+            manager1.epoch_keys[&11].public_key(),
+            manager2.epoch_keys[&11].public_key()
+        );
+    }
+}
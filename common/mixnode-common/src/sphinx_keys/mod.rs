@@ -0,0 +1,245 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::packet_processor::replay::{
+    ReplayFilter, ReplayProtectionConfig, ReplayProtectionStats,
+};
+use nymsphinx_types::PrivateKey;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::time::Duration;
+use thiserror::Error;
+
+pub mod manager;
+
+pub use topology::sphinx_key::{KeyEpoch, SignedSphinxKey};
+
+/// By default, use a new sphinx key every day.
+pub const DEFAULT_SPHINX_KEY_ROTATION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// By default, keep accepting packets created with the previous key for an hour after the rotation.
+pub const DEFAULT_SPHINX_KEY_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// By default, announce keys for the two epochs following the current one.
+pub const DEFAULT_ANNOUNCED_SPHINX_KEY_EPOCHS: u32 = 2;
+
+#[derive(Debug, Error)]
+#[error("the sphinx key grace period ({grace_period:?}) must be shorter than the rotation interval ({rotation_interval:?}) as the packets only indicate the parity of the key epoch")]
+pub struct InvalidSphinxKeyRotationConfig {
+    grace_period: Duration,
+    rotation_interval: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SphinxKeyRotationConfig {
+    /// Specifies whether the node should be periodically generating new sphinx keys.
+    pub enabled: bool,
+
+    /// Duration of a single key epoch, i.e. for how long each sphinx key is used.
+    /// Epochs are aligned to the unix epoch, so that the clients could easily determine
+    /// which key is currently in use.
+    #[serde(with = "humantime_serde")]
+    pub rotation_interval: Duration,
+
+    /// For how long after the rotation the packets created with the previous key are still accepted.
+    #[serde(with = "humantime_serde")]
+    pub grace_period: Duration,
+
+    /// Number of future epochs for which the keys are generated and announced in advance.
+    pub announced_epochs: u32,
+
+    /// Specifies whether the packets created with the key the node has bonded with are still
+    /// accepted. It should only be disabled once all clients are able to use the rotated keys.
+    pub accept_bonded_key: bool,
+}
+
+impl Default for SphinxKeyRotationConfig {
+    fn default() -> Self {
+        SphinxKeyRotationConfig {
+            enabled: false,
+            rotation_interval: DEFAULT_SPHINX_KEY_ROTATION_INTERVAL,
+            grace_period: DEFAULT_SPHINX_KEY_GRACE_PERIOD,
+            announced_epochs: DEFAULT_ANNOUNCED_SPHINX_KEY_EPOCHS,
+            accept_bonded_key: true,
+        }
+    }
+}
+
+impl SphinxKeyRotationConfig {
+    /// Makes sure that at most the keys of two consecutive epochs are ever accepted at once,
+    /// which is required for the key rotation hints of the packets to be unambiguous.
+    pub fn validate(&self) -> Result<(), InvalidSphinxKeyRotationConfig> {
+        if self.grace_period >= self.rotation_interval {
+            return Err(InvalidSphinxKeyRotationConfig {
+                grace_period: self.grace_period,
+                rotation_interval: self.rotation_interval,
+            });
+        }
+        Ok(())
+    }
+
+    fn interval_secs(&self) -> u64 {
+        self.rotation_interval.as_secs().max(1)
+    }
+
+    /// Returns the key epoch that's active at the provided unix timestamp.
+    pub fn epoch_at(&self, unix_timestamp: u64) -> KeyEpoch {
+        (unix_timestamp / self.interval_secs()) as KeyEpoch
+    }
+
+    /// Returns the unix timestamp at which the provided epoch begins.
+    pub fn epoch_start(&self, epoch: KeyEpoch) -> u64 {
+        epoch as u64 * self.interval_secs()
+    }
+
+    /// Returns the unix timestamp after which the packets created with the key of the provided epoch
+    /// are no longer accepted, i.e. the end of the epoch extended by the grace period.
+    pub fn epoch_expiry(&self, epoch: KeyEpoch) -> u64 {
+        self.epoch_start(epoch + 1) + self.grace_period.as_secs()
+    }
+}
+
+/// Private sphinx key accepted by the node alongside the filter of packets already processed with it.
+pub(crate) struct ActiveKey {
+    /// Epoch of the key. `None` implies the key the node has bonded with.
+    pub(crate) epoch: Option<KeyEpoch>,
+    pub(crate) key: PrivateKey,
+    pub(crate) replay_filter: ReplayFilter,
+}
+
+/// Set of the sphinx keys the node is currently accepting packets for, ordered from the newest.
+/// Each key has its own replay filter so that the tags are discarded together with the key.
+#[derive(Clone)]
+pub struct ActiveSphinxKeys {
+    keys: Arc<RwLock<Vec<ActiveKey>>>,
+    replay_protection: ReplayProtectionConfig,
+    replay_stats: Arc<ReplayProtectionStats>,
+}
+
+impl ActiveSphinxKeys {
+    pub fn new(replay_protection: ReplayProtectionConfig) -> Self {
+        ActiveSphinxKeys {
+            keys: Arc::new(RwLock::new(Vec::new())),
+            replay_protection,
+            replay_stats: Default::default(),
+        }
+    }
+
+    /// Creates the set consisting of only a single, never changing, key.
+    pub fn new_static(key: PrivateKey, replay_filter: ReplayFilter) -> Self {
+        ActiveSphinxKeys {
+            replay_protection: replay_filter.config(),
+            replay_stats: replay_filter.shared_stats(),
+            keys: Arc::new(RwLock::new(vec![ActiveKey {
+                epoch: None,
+                key,
+                replay_filter,
+            }])),
+        }
+    }
+
+    /// Counters aggregated over replay filters of all the keys.
+    pub fn replay_stats(&self) -> &ReplayProtectionStats {
+        &self.replay_stats
+    }
+
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Vec<ActiveKey>> {
+        self.keys.read().expect("sphinx keys lock got poisoned")
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn epochs(&self) -> Vec<Option<KeyEpoch>> {
+        self.read().iter().map(|key| key.epoch).collect()
+    }
+
+    pub fn contains(&self, epoch: Option<KeyEpoch>) -> bool {
+        self.read().iter().any(|key| key.epoch == epoch)
+    }
+
+    /// Starts accepting packets created with the provided key.
+    pub fn insert(&self, epoch: Option<KeyEpoch>, key: PrivateKey) {
+        let replay_filter =
+            ReplayFilter::new_with_stats(self.replay_protection, Arc::clone(&self.replay_stats));
+        let mut guard = self.keys.write().expect("sphinx keys lock got poisoned");
+        guard.retain(|active| active.epoch != epoch);
+        guard.push(ActiveKey {
+            epoch,
+            key,
+            replay_filter,
+        });
+        // the bonded key goes last as the clients that know about rotation won't be using it
+        guard.sort_by(|a, b| match (a.epoch, b.epoch) {
+            (Some(a), Some(b)) => b.cmp(&a),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+    }
+
+    /// Stops accepting packets created with keys of all epochs older than the provided one.
+    /// Note that it does not affect the bonded key.
+    pub fn remove_older_than(&self, epoch: KeyEpoch) {
+        let mut guard = self.keys.write().expect("sphinx keys lock got poisoned");
+        guard.retain(|active| match active.epoch {
+            Some(key_epoch) if key_epoch < epoch => {
+                // the filter might still be shared with an in-flight packet so explicitly release the memory
+                active.replay_filter.clear();
+                false
+            }
+            _ => true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymsphinx_types::crypto::keygen;
+
+    #[test]
+    fn epochs_are_aligned_to_the_rotation_interval() {
+        let config = SphinxKeyRotationConfig {
+            rotation_interval: Duration::from_secs(100),
+            grace_period: Duration::from_secs(10),
+            ..Default::default()
+        };
+        assert_eq!(config.epoch_at(0), 0);
+        assert_eq!(config.epoch_at(99), 0);
+        assert_eq!(config.epoch_at(100), 1);
+        assert_eq!(config.epoch_start(3), 300);
+        assert_eq!(config.epoch_expiry(3), 410);
+    }
+
+    #[test]
+    fn grace_period_must_be_shorter_than_the_rotation_interval() {
+        let mut config = SphinxKeyRotationConfig {
+            rotation_interval: Duration::from_secs(100),
+            grace_period: Duration::from_secs(10),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.grace_period = Duration::from_secs(100);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn keys_are_ordered_from_the_newest() {
+        let keys = ActiveSphinxKeys::new(Default::default());
+        keys.insert(None, keygen().0);
+        keys.insert(Some(1), keygen().0);
+        keys.insert(Some(3), keygen().0);
+        keys.insert(Some(2), keygen().0);
+        assert_eq!(keys.epochs(), vec![Some(3), Some(2), Some(1), None]);
+
+        keys.remove_older_than(3);
+        assert_eq!(keys.epochs(), vec![Some(3), None]);
+    }
+}
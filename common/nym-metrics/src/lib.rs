@@ -0,0 +1,111 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Thin wrapper around the `prometheus` crate used for exposing metrics of the nym binaries
+//! in the Prometheus text exposition format.
+
+use prometheus::core::Collector;
+use prometheus::{Encoder, TextEncoder};
+use thiserror::Error;
+
+pub use prometheus::{
+    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
+
+/// Value of the `Content-Type` header expected by Prometheus when scraping the text format.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+#[derive(Debug, Error)]
+pub enum MetricsError {
+    #[error("failed to gather or encode the metrics: {0}")]
+    PrometheusError(#[from] prometheus::Error),
+
+    #[error("the encoded metrics were not valid utf8: {0}")]
+    MalformedEncoding(#[from] std::string::FromUtf8Error),
+}
+
+/// Creates a new registry where the names of all registered metrics are going to be prefixed
+/// with the provided namespace, for example `nym_mixnode`.
+pub fn new_registry(namespace: &str) -> Registry {
+    // the only possible failure is due to an empty namespace which is a programming error
+    Registry::new_custom(Some(namespace.to_string()), None)
+        .expect("the metrics namespace must not be empty")
+}
+
+// note: all of the below only fail if the metric is malformed or a metric with the same name
+// has already been registered, both of which imply a programming error
+
+fn register<M: Collector + Clone + 'static>(registry: &Registry, metric: M) -> M {
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("failed to register a metric");
+    metric
+}
+
+pub fn int_counter(registry: &Registry, name: &str, help: &str) -> IntCounter {
+    register(
+        registry,
+        IntCounter::new(name, help).expect("malformed metric"),
+    )
+}
+
+pub fn int_counter_vec(
+    registry: &Registry,
+    name: &str,
+    help: &str,
+    labels: &[&str],
+) -> IntCounterVec {
+    register(
+        registry,
+        IntCounterVec::new(Opts::new(name, help), labels).expect("malformed metric"),
+    )
+}
+
+pub fn int_gauge(registry: &Registry, name: &str, help: &str) -> IntGauge {
+    register(
+        registry,
+        IntGauge::new(name, help).expect("malformed metric"),
+    )
+}
+
+pub fn int_gauge_vec(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> IntGaugeVec {
+    register(
+        registry,
+        IntGaugeVec::new(Opts::new(name, help), labels).expect("malformed metric"),
+    )
+}
+
+pub fn histogram(registry: &Registry, name: &str, help: &str, buckets: Vec<f64>) -> Histogram {
+    register(
+        registry,
+        Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets))
+            .expect("malformed metric"),
+    )
+}
+
+/// Encodes all metrics from the provided registry in the Prometheus text format.
+pub fn encode(registry: &Registry) -> Result<String, MetricsError> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&registry.gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_are_encoded_with_namespace() {
+        let registry = new_registry("nym_test");
+        let counter = int_counter(&registry, "packets_received_total", "received packets");
+        let gauge = int_gauge_vec(&registry, "cache_age_seconds", "age of caches", &["cache"]);
+
+        counter.inc_by(3);
+        gauge.with_label_values(&["topology"]).set(42);
+
+        let encoded = encode(&registry).unwrap();
+        assert!(encoded.contains("# TYPE nym_test_packets_received_total counter"));
+        assert!(encoded.contains("nym_test_packets_received_total 3"));
+        assert!(encoded.contains("nym_test_cache_age_seconds{cache=\"topology\"} 42"));
+    }
+}
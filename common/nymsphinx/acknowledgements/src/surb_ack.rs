@@ -8,7 +8,7 @@ use nymsphinx_addressing::nodes::{
     NymNodeRoutingAddress, NymNodeRoutingAddressError, MAX_NODE_ADDRESS_UNPADDED_LEN,
 };
 use nymsphinx_params::packet_sizes::PacketSize;
-use nymsphinx_params::DEFAULT_NUM_MIX_HOPS;
+use nymsphinx_params::{SphinxKeyRotation, DEFAULT_NUM_MIX_HOPS};
 use nymsphinx_types::builder::SphinxPacketBuilder;
 use nymsphinx_types::Error as SphinxError;
 use nymsphinx_types::{
@@ -16,7 +16,7 @@ use nymsphinx_types::{
     SphinxPacket,
 };
 use rand::{CryptoRng, RngCore};
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::time;
 use thiserror::Error;
 use topology::{NymTopology, NymTopologyError};
@@ -24,6 +24,7 @@ use topology::{NymTopology, NymTopologyError};
 pub struct SurbAck {
     surb_ack_packet: SphinxPacket,
     first_hop_address: NymNodeRoutingAddress,
+    first_hop_key_rotation: SphinxKeyRotation,
     expected_total_delay: Delay,
 }
 
@@ -65,12 +66,14 @@ impl SurbAck {
 
         // in our case, the last hop is a gateway that does NOT do any delays
         let expected_total_delay = delays.iter().take(delays.len() - 1).sum();
-        let first_hop_address =
-            NymNodeRoutingAddress::try_from(route.first().unwrap().address).unwrap();
+        let first_hop = route.first().unwrap();
+        let first_hop_address = NymNodeRoutingAddress::try_from(first_hop.address).unwrap();
+        let first_hop_key_rotation = SphinxKeyRotation::from_node_address(&first_hop.address);
 
         Ok(SurbAck {
             surb_ack_packet,
             first_hop_address,
+            first_hop_key_rotation,
             expected_total_delay,
         })
     }
@@ -85,35 +88,114 @@ impl SurbAck {
         self.expected_total_delay
     }
 
+    // the sphinx key rotation hint is put in the last byte of the padding of the address.
+    // the ipv6 addresses don't have any padding, so instead the hint is put in the upper bits
+    // of the address type
+    fn encode_first_hop(
+        address: NymNodeRoutingAddress,
+        key_rotation: SphinxKeyRotation,
+    ) -> Vec<u8> {
+        let mut bytes = address.as_zero_padded_bytes(MAX_NODE_ADDRESS_UNPADDED_LEN);
+        if key_rotation.is_unknown() {
+            return bytes;
+        }
+        if address.bytes_min_len() < MAX_NODE_ADDRESS_UNPADDED_LEN {
+            bytes[MAX_NODE_ADDRESS_UNPADDED_LEN - 1] = key_rotation as u8;
+        } else {
+            bytes[0] |= (key_rotation as u8) << 4;
+        }
+        bytes
+    }
+
+    fn decode_first_hop(
+        b: &[u8],
+    ) -> Result<(NymNodeRoutingAddress, SphinxKeyRotation), NymNodeRoutingAddressError> {
+        let mut bytes: [u8; MAX_NODE_ADDRESS_UNPADDED_LEN] = b[..MAX_NODE_ADDRESS_UNPADDED_LEN]
+            .try_into()
+            .expect("the slice has exactly the right length");
+        let type_hint = bytes[0] >> 4;
+        bytes[0] &= 0x0f;
+
+        let address = NymNodeRoutingAddress::try_from_bytes(&bytes)?;
+        let key_rotation = if address.bytes_min_len() < MAX_NODE_ADDRESS_UNPADDED_LEN {
+            SphinxKeyRotation::from_padded_address(&bytes)
+        } else {
+            SphinxKeyRotation::try_from(type_hint).unwrap_or_default()
+        };
+        Ok((address, key_rotation))
+    }
+
     pub fn prepare_for_sending(self) -> (Delay, Vec<u8>) {
         // SURB_FIRST_HOP || SURB_ACK
-        let surb_bytes: Vec<_> = self
-            .first_hop_address
-            .as_zero_padded_bytes(MAX_NODE_ADDRESS_UNPADDED_LEN)
-            .into_iter()
-            .chain(self.surb_ack_packet.to_bytes().into_iter())
-            .collect();
+        let surb_bytes: Vec<_> =
+            Self::encode_first_hop(self.first_hop_address, self.first_hop_key_rotation)
+                .into_iter()
+                .chain(self.surb_ack_packet.to_bytes().into_iter())
+                .collect();
         (self.expected_total_delay, surb_bytes)
     }
 
     // partial reciprocal of `prepare_for_sending` performed by the gateway
     pub fn try_recover_first_hop_packet(
         b: &[u8],
-    ) -> Result<(NymNodeRoutingAddress, SphinxPacket), SurbAckRecoveryError> {
+    ) -> Result<(NymNodeRoutingAddress, SphinxKeyRotation, SphinxPacket), SurbAckRecoveryError>
+    {
         if b.len() != Self::len() {
             Err(SurbAckRecoveryError::InvalidPacketSize {
                 received: b.len(),
                 expected: Self::len(),
             })
         } else {
-            let address = NymNodeRoutingAddress::try_from_bytes(b)?;
+            let (address, key_rotation) = Self::decode_first_hop(b)?;
 
             // TODO: this will be variable once/if we decide to introduce optimization described
             // in common/nymsphinx/chunking/src/lib.rs:available_plaintext_size()
             let address_offset = MAX_NODE_ADDRESS_UNPADDED_LEN;
             let packet = SphinxPacket::from_bytes(&b[address_offset..])?;
 
-            Ok((address, packet))
+            Ok((address, key_rotation, packet))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn first_hop_key_rotation_can_be_recovered() {
+        let addresses: [NymNodeRoutingAddress; 2] = [
+            "1.2.3.4:1789".parse::<SocketAddr>().unwrap().into(),
+            "[2001:db8::1]:1789".parse::<SocketAddr>().unwrap().into(),
+        ];
+        let rotations = [
+            SphinxKeyRotation::Unknown,
+            SphinxKeyRotation::OddRotation,
+            SphinxKeyRotation::EvenRotation,
+        ];
+
+        for address in addresses {
+            for key_rotation in rotations {
+                let bytes = SurbAck::encode_first_hop(address, key_rotation);
+                assert_eq!(bytes.len(), MAX_NODE_ADDRESS_UNPADDED_LEN);
+                let recovered = SurbAck::decode_first_hop(&bytes).unwrap();
+                assert_eq!(recovered, (address, key_rotation));
+            }
         }
     }
+
+    #[test]
+    fn first_hop_without_hint_is_compatible_with_plain_address() {
+        let address: NymNodeRoutingAddress = "1.2.3.4:1789".parse::<SocketAddr>().unwrap().into();
+        let plain = address.as_zero_padded_bytes(MAX_NODE_ADDRESS_UNPADDED_LEN);
+        assert_eq!(
+            SurbAck::encode_first_hop(address, SphinxKeyRotation::Unknown),
+            plain
+        );
+        assert_eq!(
+            SurbAck::decode_first_hop(&plain).unwrap(),
+            (address, SphinxKeyRotation::Unknown)
+        );
+    }
 }
@@ -6,7 +6,7 @@ use crypto::{generic_array::typenum::Unsigned, Digest};
 use nymsphinx_addressing::clients::Recipient;
 use nymsphinx_addressing::nodes::{NymNodeRoutingAddress, MAX_NODE_ADDRESS_UNPADDED_LEN};
 use nymsphinx_params::packet_sizes::PacketSize;
-use nymsphinx_params::{ReplySurbKeyDigestAlgorithm, DEFAULT_NUM_MIX_HOPS};
+use nymsphinx_params::{ReplySurbKeyDigestAlgorithm, SphinxKeyRotation, DEFAULT_NUM_MIX_HOPS};
 use nymsphinx_types::{delays, Error as SphinxError, SURBMaterial, SphinxPacket, SURB};
 use rand::{CryptoRng, RngCore};
 use serde::de::{Error as SerdeError, Visitor};
@@ -173,7 +173,7 @@ impl ReplySurb {
         self,
         message: M,
         packet_size: Option<PacketSize>,
-    ) -> Result<(SphinxPacket, NymNodeRoutingAddress), ReplySurbError> {
+    ) -> Result<(SphinxPacket, NymNodeRoutingAddress, SphinxKeyRotation), ReplySurbError> {
         let packet_size = packet_size.unwrap_or_default();
 
         let message_bytes = message.as_ref();
@@ -187,8 +187,9 @@ impl ReplySurb {
             .use_surb(message_bytes, packet_size.payload_size())
             .expect("this error indicates inconsistent message length checking - it shouldn't have happened!");
 
+        let first_hop_key_rotation = SphinxKeyRotation::from_node_address(&first_hop);
         let first_hop_address = NymNodeRoutingAddress::try_from(first_hop).unwrap();
 
-        Ok((packet, first_hop_address))
+        Ok((packet, first_hop_address, first_hop_key_rotation))
     }
 }
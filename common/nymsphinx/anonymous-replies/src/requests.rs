@@ -129,6 +129,12 @@ impl Display for RepliableMessage {
                     self.sender_tag,
                 )
             }
+            RepliableMessageContent::ReplacementSurbs { reply_surbs } => write!(
+                f,
+                "repliable replacement surbs message ({} reply surbs attached) from {}",
+                reply_surbs.len(),
+                self.sender_tag,
+            ),
         }
     }
 }
@@ -158,6 +164,16 @@ impl RepliableMessage {
         }
     }
 
+    pub fn new_replacement_surbs(
+        sender_tag: AnonymousSenderTag,
+        reply_surbs: Vec<ReplySurb>,
+    ) -> Self {
+        RepliableMessage {
+            sender_tag,
+            content: RepliableMessageContent::ReplacementSurbs { reply_surbs },
+        }
+    }
+
     pub fn into_bytes(self) -> Vec<u8> {
         let content_tag = self.content.tag();
 
@@ -225,6 +241,7 @@ enum RepliableMessageContentTag {
     Data = 0,
     AdditionalSurbs = 1,
     Heartbeat = 2,
+    ReplacementSurbs = 3,
 }
 
 impl TryFrom<u8> for RepliableMessageContentTag {
@@ -237,6 +254,9 @@ impl TryFrom<u8> for RepliableMessageContentTag {
                 Ok(Self::AdditionalSurbs)
             }
             _ if value == (RepliableMessageContentTag::Heartbeat as u8) => Ok(Self::Heartbeat),
+            _ if value == (RepliableMessageContentTag::ReplacementSurbs as u8) => {
+                Ok(Self::ReplacementSurbs)
+            }
             val => Err(InvalidReplyRequestError::InvalidRepliableContentTag { received: val }),
         }
     }
@@ -255,6 +275,11 @@ pub enum RepliableMessageContent {
     Heartbeat {
         additional_reply_surbs: Vec<ReplySurb>,
     },
+    // sent after the original sender has changed its gateway: all reply surbs it has sent before
+    // are no longer usable and should be replaced with the attached ones
+    ReplacementSurbs {
+        reply_surbs: Vec<ReplySurb>,
+    },
 }
 
 impl RepliableMessageContent {
@@ -273,7 +298,8 @@ impl RepliableMessageContent {
                     .chain(message.into_iter())
                     .collect()
             }
-            RepliableMessageContent::AdditionalSurbs { reply_surbs } => {
+            RepliableMessageContent::AdditionalSurbs { reply_surbs }
+            | RepliableMessageContent::ReplacementSurbs { reply_surbs } => {
                 let num_surbs = reply_surbs.len() as u32;
 
                 num_surbs
@@ -322,6 +348,9 @@ impl RepliableMessageContent {
             RepliableMessageContentTag::Heartbeat => Ok(RepliableMessageContent::Heartbeat {
                 additional_reply_surbs: reply_surbs,
             }),
+            RepliableMessageContentTag::ReplacementSurbs => {
+                Ok(RepliableMessageContent::ReplacementSurbs { reply_surbs })
+            }
         }
     }
 
@@ -332,6 +361,9 @@ impl RepliableMessageContent {
                 RepliableMessageContentTag::AdditionalSurbs
             }
             RepliableMessageContent::Heartbeat { .. } => RepliableMessageContentTag::Heartbeat,
+            RepliableMessageContent::ReplacementSurbs { .. } => {
+                RepliableMessageContentTag::ReplacementSurbs
+            }
         }
     }
 }
@@ -474,3 +506,113 @@ impl ReplyMessageContent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::Layer;
+    use nymsphinx_params::DEFAULT_NUM_MIX_HOPS;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use topology::{gateway, mix, NymTopology};
+
+    fn topology_fixture() -> NymTopology {
+        let mut rng = rand::rngs::OsRng;
+        let mut mixes = HashMap::new();
+        for (layer, mix_layer) in [(1, Layer::One), (2, Layer::Two), (3, Layer::Three)] {
+            mixes.insert(
+                layer,
+                vec![mix::Node {
+                    mix_id: layer as u32,
+                    owner: format!("owner{layer}"),
+                    host: "10.20.30.40".parse().unwrap(),
+                    mix_host: format!("10.20.30.4{layer}:1789").parse().unwrap(),
+                    http_api_port: 8000,
+                    identity_key: *identity::KeyPair::new(&mut rng).public_key(),
+                    sphinx_key: *encryption::KeyPair::new(&mut rng).public_key(),
+                    sphinx_key_epoch: None,
+                    layer: mix_layer,
+                    version: "1.1.0".to_string(),
+                    family: None,
+                    performance: None,
+                }],
+            );
+        }
+
+        let gateway = gateway::Node {
+            owner: "owner4".to_string(),
+            stake: 123,
+            location: "unknown".to_string(),
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            clients_port: 9000,
+            identity_key: *identity::KeyPair::new(&mut rng).public_key(),
+            sphinx_key: *encryption::KeyPair::new(&mut rng).public_key(),
+            sphinx_key_epoch: None,
+            version: "1.1.0".to_string(),
+        };
+
+        NymTopology::new(mixes, vec![gateway])
+    }
+
+    fn reply_surbs_fixture(amount: usize) -> Vec<ReplySurb> {
+        let mut rng = rand::rngs::OsRng;
+        let topology = topology_fixture();
+        let recipient = Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            topology.gateways()[0].identity_key,
+        );
+        (0..amount)
+            .map(|_| {
+                ReplySurb::construct(&mut rng, &recipient, Duration::from_millis(10), &topology)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replacement_surbs_message_roundtrip() {
+        let mut rng = rand::rngs::OsRng;
+        let sender_tag = AnonymousSenderTag::new_random(&mut rng);
+        let reply_surbs = reply_surbs_fixture(3);
+        let surbs_bytes = reply_surbs
+            .iter()
+            .map(|surb| surb.to_bytes())
+            .collect::<Vec<_>>();
+
+        let bytes = RepliableMessage::new_replacement_surbs(sender_tag, reply_surbs).into_bytes();
+        assert_eq!(
+            bytes[SENDER_TAG_SIZE],
+            RepliableMessageContentTag::ReplacementSurbs as u8
+        );
+
+        let recovered = RepliableMessage::try_from_bytes(&bytes, DEFAULT_NUM_MIX_HOPS).unwrap();
+        assert_eq!(recovered.sender_tag, sender_tag);
+        match recovered.content {
+            RepliableMessageContent::ReplacementSurbs { reply_surbs } => assert_eq!(
+                reply_surbs
+                    .iter()
+                    .map(|surb| surb.to_bytes())
+                    .collect::<Vec<_>>(),
+                surbs_bytes
+            ),
+            other => panic!("unexpected message content: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn replacement_surbs_are_not_confused_with_additional_surbs() {
+        let mut rng = rand::rngs::OsRng;
+        let sender_tag = AnonymousSenderTag::new_random(&mut rng);
+        let bytes =
+            RepliableMessage::new_additional_surbs(sender_tag, reply_surbs_fixture(1)).into_bytes();
+
+        let recovered = RepliableMessage::try_from_bytes(&bytes, DEFAULT_NUM_MIX_HOPS).unwrap();
+        assert!(matches!(
+            recovered.content,
+            RepliableMessageContent::AdditionalSurbs { .. }
+        ));
+    }
+}
@@ -11,7 +11,8 @@ use nymsphinx_chunking::fragment::COVER_FRAG_ID;
 use nymsphinx_forwarding::packet::MixPacket;
 use nymsphinx_params::packet_sizes::PacketSize;
 use nymsphinx_params::{
-    PacketEncryptionAlgorithm, PacketHkdfAlgorithm, PacketMode, DEFAULT_NUM_MIX_HOPS,
+    PacketEncryptionAlgorithm, PacketHkdfAlgorithm, PacketMode, SphinxKeyRotation,
+    DEFAULT_NUM_MIX_HOPS,
 };
 use nymsphinx_types::builder::SphinxPacketBuilder;
 use nymsphinx_types::{delays, Error as SphinxError};
@@ -116,10 +117,14 @@ where
         .build_packet(packet_payload, &route, &destination, &delays)
         .unwrap();
 
-    let first_hop_address =
-        NymNodeRoutingAddress::try_from(route.first().unwrap().address).unwrap();
+    let first_hop = route.first().unwrap();
+    let first_hop_address = NymNodeRoutingAddress::try_from(first_hop.address).unwrap();
+    let first_hop_key_rotation = SphinxKeyRotation::from_node_address(&first_hop.address);
 
-    Ok(MixPacket::new(first_hop_address, packet, PacketMode::Mix))
+    Ok(
+        MixPacket::new(first_hop_address, packet.into(), PacketMode::Mix)
+            .with_key_rotation(first_hop_key_rotation),
+    )
 }
 
 /// Helper function used to determine if given message represents a loop cover message.
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use nymsphinx_addressing::nodes::{NymNodeRoutingAddress, NymNodeRoutingAddressError};
-use nymsphinx_params::{PacketMode, PacketSize};
-use nymsphinx_types::SphinxPacket;
+use nymsphinx_params::packet_version::PacketVersion;
+use nymsphinx_params::{PacketMode, PacketSize, PacketType, SphinxKeyRotation};
+use nymsphinx_types::{NymPacket, OutfoxPacket, SphinxPacket};
 use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display, Formatter};
 
@@ -11,9 +12,12 @@ use std::fmt::{self, Debug, Display, Formatter};
 pub enum MixPacketFormattingError {
     TooFewBytesProvided,
     InvalidPacketMode,
+    InvalidPacketType,
+    InvalidKeyRotation,
     InvalidPacketSize(usize),
     InvalidAddress,
     MalformedSphinxPacket,
+    MalformedOutfoxPacket,
 }
 
 impl Display for MixPacketFormattingError {
@@ -31,7 +35,10 @@ impl Display for MixPacketFormattingError {
                     PacketSize::ExtendedPacket32.size()
                 ),
             MalformedSphinxPacket => write!(f, "received sphinx packet was malformed"),
-            InvalidPacketMode => write!(f, "provided packet mode is invalid")
+            MalformedOutfoxPacket => write!(f, "received outfox packet was malformed"),
+            InvalidPacketMode => write!(f, "provided packet mode is invalid"),
+            InvalidPacketType => write!(f, "provided packet type is invalid"),
+            InvalidKeyRotation => write!(f, "provided sphinx key rotation is invalid")
         }
     }
 }
@@ -46,19 +53,17 @@ impl From<NymNodeRoutingAddressError> for MixPacketFormattingError {
 
 pub struct MixPacket {
     next_hop: NymNodeRoutingAddress,
-    sphinx_packet: SphinxPacket,
+    packet: NymPacket,
     packet_mode: PacketMode,
+    key_rotation: SphinxKeyRotation,
 }
 
 impl Debug for MixPacket {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "MixPacket to {:?} with packet_mode {:?}. Sphinx header: {:?}, payload length: {}",
-            self.next_hop,
-            self.packet_mode,
-            self.sphinx_packet.header,
-            self.sphinx_packet.payload.len()
+            "MixPacket to {:?} with packet_mode {:?}. {:?}",
+            self.next_hop, self.packet_mode, self.packet
         )
     }
 }
@@ -66,35 +71,101 @@ impl Debug for MixPacket {
 impl MixPacket {
     pub fn new(
         next_hop: NymNodeRoutingAddress,
-        sphinx_packet: SphinxPacket,
+        packet: NymPacket,
         packet_mode: PacketMode,
     ) -> Self {
         MixPacket {
             next_hop,
-            sphinx_packet,
+            packet,
             packet_mode,
+            key_rotation: SphinxKeyRotation::Unknown,
         }
     }
 
+    /// Attaches the hint about the sphinx key of the next hop the packet has been created with.
+    pub fn with_key_rotation(mut self, key_rotation: SphinxKeyRotation) -> Self {
+        self.key_rotation = key_rotation;
+        self
+    }
+
     pub fn next_hop(&self) -> NymNodeRoutingAddress {
         self.next_hop
     }
 
-    pub fn sphinx_packet(&self) -> &SphinxPacket {
-        &self.sphinx_packet
+    pub fn packet(&self) -> &NymPacket {
+        &self.packet
     }
 
-    pub fn into_sphinx_packet(self) -> SphinxPacket {
-        self.sphinx_packet
+    pub fn into_packet(self) -> NymPacket {
+        self.packet
     }
 
     pub fn packet_mode(&self) -> PacketMode {
         self.packet_mode
     }
 
-    // the message is formatted as follows:
+    pub fn key_rotation(&self) -> SphinxKeyRotation {
+        self.key_rotation
+    }
+
+    pub fn packet_type(&self) -> PacketType {
+        match self.packet {
+            NymPacket::Sphinx(_) => PacketType::Sphinx,
+            NymPacket::Outfox(_) => PacketType::Outfox,
+        }
+    }
+
+    // sphinx packets are formatted as follows:
     // PACKET_MODE || FIRST_HOP || SPHINX_PACKET
+    // while any other packet type is formatted as:
+    // PACKET_VERSION || PACKET_TYPE || PACKET_MODE || FIRST_HOP || PACKET
+    // and packets created with the rotated sphinx key of the first hop as:
+    // PACKET_VERSION || PACKET_TYPE || KEY_ROTATION || PACKET_MODE || FIRST_HOP || PACKET
+    // the first byte of the sphinx variant is always a valid packet mode (0 or 1) which is
+    // never a valid packet version, so both variants can be told apart
     pub fn try_from_bytes(b: &[u8]) -> Result<Self, MixPacketFormattingError> {
+        if b.is_empty() {
+            return Err(MixPacketFormattingError::TooFewBytesProvided);
+        }
+
+        if PacketMode::try_from(b[0]).is_ok() {
+            return Self::try_from_sphinx_bytes(b);
+        }
+
+        if b.len() < 3 {
+            return Err(MixPacketFormattingError::TooFewBytesProvided);
+        }
+
+        let packet_version = PacketVersion::from(b[0]);
+        if !packet_version.is_typed() {
+            return Err(MixPacketFormattingError::InvalidPacketMode);
+        }
+
+        let packet_type = match PacketType::try_from(b[1]) {
+            Ok(packet_type) => packet_type,
+            Err(_) => return Err(MixPacketFormattingError::InvalidPacketType),
+        };
+
+        let (key_rotation, b) = if packet_version.is_keyed() {
+            if b.len() < 4 {
+                return Err(MixPacketFormattingError::TooFewBytesProvided);
+            }
+            match SphinxKeyRotation::try_from(b[2]) {
+                Ok(key_rotation) => (key_rotation, &b[3..]),
+                Err(_) => return Err(MixPacketFormattingError::InvalidKeyRotation),
+            }
+        } else {
+            (SphinxKeyRotation::Unknown, &b[2..])
+        };
+
+        let packet = match packet_type {
+            PacketType::Sphinx => Self::try_from_sphinx_bytes(b),
+            PacketType::Outfox => Self::try_from_outfox_bytes(b),
+        }?;
+        Ok(packet.with_key_rotation(key_rotation))
+    }
+
+    fn try_from_sphinx_bytes(b: &[u8]) -> Result<Self, MixPacketFormattingError> {
         let packet_mode = match PacketMode::try_from(b[0]) {
             Ok(mode) => mode,
             Err(_) => return Err(MixPacketFormattingError::InvalidPacketMode),
@@ -113,20 +184,167 @@ impl MixPacket {
                 Err(_) => return Err(MixPacketFormattingError::MalformedSphinxPacket),
             };
 
-            Ok(MixPacket {
+            Ok(MixPacket::new(
                 next_hop,
-                sphinx_packet,
+                NymPacket::Sphinx(sphinx_packet),
                 packet_mode,
-            })
+            ))
         }
     }
 
+    fn try_from_outfox_bytes(b: &[u8]) -> Result<Self, MixPacketFormattingError> {
+        let packet_mode = match PacketMode::try_from(b[0]) {
+            Ok(mode) => mode,
+            Err(_) => return Err(MixPacketFormattingError::InvalidPacketMode),
+        };
+
+        let next_hop = NymNodeRoutingAddress::try_from_bytes(&b[1..])?;
+        let addr_offset = next_hop.bytes_min_len();
+
+        let outfox_packet_data = &b[addr_offset + 1..];
+        let outfox_packet = match OutfoxPacket::from_bytes(outfox_packet_data) {
+            Ok(packet) => packet,
+            Err(_) => return Err(MixPacketFormattingError::MalformedOutfoxPacket),
+        };
+
+        // outfox packets shrink at every hop, so we can only validate the size of the payload
+        if PacketSize::get_type_from_payload_size(outfox_packet.payload_length()).is_err() {
+            return Err(MixPacketFormattingError::InvalidPacketSize(
+                outfox_packet_data.len(),
+            ));
+        }
+
+        Ok(MixPacket::new(
+            next_hop,
+            NymPacket::Outfox(outfox_packet),
+            packet_mode,
+        ))
+    }
+
     pub fn into_bytes(self) -> Vec<u8> {
-        std::iter::once(self.packet_mode as u8)
+        let packet_type = self.packet_type();
+        let prefix = if !self.key_rotation.is_unknown() {
+            vec![
+                PacketVersion::new_keyed()
+                    .as_u8()
+                    .expect("keyed packet version is never legacy"),
+                packet_type as u8,
+                self.key_rotation as u8,
+            ]
+        } else if packet_type.is_outfox() {
+            vec![
+                PacketVersion::new_typed()
+                    .as_u8()
+                    .expect("typed packet version is never legacy"),
+                packet_type as u8,
+            ]
+        } else {
+            Vec::new()
+        };
+
+        prefix
+            .into_iter()
+            .chain(std::iter::once(self.packet_mode as u8))
             .chain(self.next_hop.as_bytes().into_iter())
-            .chain(self.sphinx_packet.to_bytes().into_iter())
+            .chain(self.packet.to_bytes().into_iter())
             .collect()
     }
 }
 
-// TODO: test for serialization and errors!
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymsphinx_types::{
+        crypto, Delay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
+        DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH,
+    };
+    use std::net::SocketAddr;
+
+    fn make_packet(packet_type: PacketType) -> MixPacket {
+        let next_hop: NymNodeRoutingAddress = "1.2.3.4:1789".parse::<SocketAddr>().unwrap().into();
+        let node_address: NodeAddressBytes = next_hop.try_into().unwrap();
+
+        let (_, node_pk) = crypto::keygen();
+        let route = [Node::new(node_address, node_pk)];
+        let destination = Destination::new(
+            DestinationAddressBytes::from_bytes([3u8; DESTINATION_ADDRESS_LENGTH]),
+            [4u8; IDENTIFIER_LENGTH],
+        );
+        let delays = [Delay::new_from_nanos(42)];
+        let payload_size = PacketSize::default().payload_size();
+
+        let packet = match packet_type {
+            PacketType::Sphinx => {
+                NymPacket::sphinx_build(payload_size, b"foomp", &route, &destination, &delays)
+                    .unwrap()
+            }
+            PacketType::Outfox => {
+                NymPacket::outfox_build(payload_size, b"foomp", &route, &destination, &delays)
+                    .unwrap()
+            }
+        };
+
+        MixPacket::new(next_hop, packet, PacketMode::Mix)
+    }
+
+    #[test]
+    fn sphinx_packet_uses_legacy_serialization() {
+        let packet = make_packet(PacketType::Sphinx);
+        let packet_bytes = packet.packet().to_bytes();
+        let bytes = packet.into_bytes();
+        assert_eq!(bytes[0], PacketMode::Mix as u8);
+
+        let recovered = MixPacket::try_from_bytes(&bytes).unwrap();
+        assert_eq!(recovered.packet_type(), PacketType::Sphinx);
+        assert_eq!(recovered.packet().to_bytes(), packet_bytes);
+    }
+
+    #[test]
+    fn outfox_packet_can_be_recovered_from_bytes() {
+        let packet = make_packet(PacketType::Outfox);
+        let next_hop = packet.next_hop();
+        let packet_bytes = packet.packet().to_bytes();
+        let bytes = packet.into_bytes();
+
+        let recovered = MixPacket::try_from_bytes(&bytes).unwrap();
+        assert_eq!(recovered.next_hop(), next_hop);
+        assert_eq!(recovered.packet_mode(), PacketMode::Mix);
+        assert_eq!(recovered.packet_type(), PacketType::Outfox);
+        assert_eq!(recovered.packet().to_bytes(), packet_bytes);
+    }
+
+    #[test]
+    fn key_rotation_can_be_recovered_from_bytes() {
+        for packet_type in [PacketType::Sphinx, PacketType::Outfox] {
+            let packet =
+                make_packet(packet_type).with_key_rotation(SphinxKeyRotation::OddRotation);
+            let packet_bytes = packet.packet().to_bytes();
+            let bytes = packet.into_bytes();
+
+            let recovered = MixPacket::try_from_bytes(&bytes).unwrap();
+            assert_eq!(recovered.key_rotation(), SphinxKeyRotation::OddRotation);
+            assert_eq!(recovered.packet_type(), packet_type);
+            assert_eq!(recovered.packet().to_bytes(), packet_bytes);
+        }
+
+        let mut bytes = make_packet(PacketType::Sphinx)
+            .with_key_rotation(SphinxKeyRotation::EvenRotation)
+            .into_bytes();
+        bytes[2] = 42;
+        assert!(matches!(
+            MixPacket::try_from_bytes(&bytes),
+            Err(MixPacketFormattingError::InvalidKeyRotation)
+        ));
+    }
+
+    #[test]
+    fn recovering_from_bytes_fails_for_invalid_packet_type() {
+        let mut bytes = make_packet(PacketType::Outfox).into_bytes();
+        bytes[1] = 42;
+        assert!(matches!(
+            MixPacket::try_from_bytes(&bytes),
+            Err(MixPacketFormattingError::InvalidPacketType)
+        ));
+        assert!(MixPacket::try_from_bytes(&[]).is_err());
+    }
+}
@@ -3,10 +3,13 @@
 
 use crate::packet::{FramedSphinxPacket, Header};
 use bytes::{Buf, BufMut, BytesMut};
+use nymsphinx_params::key_rotation::InvalidSphinxKeyRotation;
 use nymsphinx_params::packet_modes::InvalidPacketMode;
 use nymsphinx_params::packet_sizes::{InvalidPacketSize, PacketSize};
+use nymsphinx_params::packet_types::InvalidPacketType;
+use nymsphinx_params::PacketType;
 use nymsphinx_types::Error as SphinxError;
-use nymsphinx_types::SphinxPacket;
+use nymsphinx_types::{NymPacket, OutFoxError};
 use std::io;
 use thiserror::Error;
 use tokio_util::codec::{Decoder, Encoder};
@@ -19,9 +22,18 @@ pub enum SphinxCodecError {
     #[error("the packet mode information was malformed - {0}")]
     InvalidPacketMode(#[from] InvalidPacketMode),
 
+    #[error("the packet type information was malformed - {0}")]
+    InvalidPacketType(#[from] InvalidPacketType),
+
+    #[error("the sphinx key rotation information was malformed - {0}")]
+    InvalidSphinxKeyRotation(#[from] InvalidSphinxKeyRotation),
+
     #[error("the actual sphinx packet was malformed - {0}")]
     MalformedSphinxPacket(#[from] SphinxError),
 
+    #[error("the actual outfox packet was malformed - {0}")]
+    MalformedOutfoxPacket(#[from] OutFoxError),
+
     #[error("encountered an IO error - {0}")]
     IoError(#[from] io::Error),
 }
@@ -35,9 +47,18 @@ impl From<SphinxCodecError> for io::Error {
             SphinxCodecError::InvalidPacketMode(source) => {
                 io::Error::new(io::ErrorKind::InvalidInput, source)
             }
+            SphinxCodecError::InvalidPacketType(source) => {
+                io::Error::new(io::ErrorKind::InvalidInput, source)
+            }
+            SphinxCodecError::InvalidSphinxKeyRotation(source) => {
+                io::Error::new(io::ErrorKind::InvalidInput, source)
+            }
             SphinxCodecError::MalformedSphinxPacket(source) => {
                 io::Error::new(io::ErrorKind::InvalidData, source)
             }
+            SphinxCodecError::MalformedOutfoxPacket(source) => {
+                io::Error::new(io::ErrorKind::InvalidData, source)
+            }
             SphinxCodecError::IoError(err) => err,
         }
     }
@@ -76,22 +97,32 @@ impl Decoder for SphinxCodec {
             None => return Ok(None), // we have some data but not enough to get header back
         };
 
-        let sphinx_packet_size = header.packet_size.size();
-        let frame_len = header.size() + sphinx_packet_size;
+        let packet_size = match header.packet_len(src) {
+            Some(packet_size) => packet_size,
+            None => {
+                // we need at least one more byte to determine the packet length
+                src.reserve(header.packet_size.size());
+                return Ok(None);
+            }
+        };
+        let frame_len = header.size() + packet_size;
 
         if src.len() < frame_len {
             // we don't have enough bytes to read the rest of frame
-            src.reserve(sphinx_packet_size);
+            src.reserve(packet_size);
             return Ok(None);
         }
 
         // advance buffer past the header - at this point we have enough bytes
         src.advance(header.size());
-        let sphinx_packet_bytes = src.split_to(sphinx_packet_size);
+        let packet_bytes = src.split_to(packet_size);
 
         // here it could be debatable whether stream is corrupt or not,
         // but let's go with the safer approach and assume it is.
-        let packet = SphinxPacket::from_bytes(&sphinx_packet_bytes)?;
+        let packet = match header.packet_type {
+            PacketType::Sphinx => NymPacket::sphinx_from_bytes(&packet_bytes)?,
+            PacketType::Outfox => NymPacket::outfox_from_bytes(&packet_bytes)?,
+        };
         let nymsphinx_packet = FramedSphinxPacket { header, packet };
 
         // As per docs:
@@ -128,13 +159,21 @@ impl Decoder for SphinxCodec {
 #[cfg(test)]
 mod packet_encoding {
     use super::*;
-    use nymsphinx_types::builder::SphinxPacketBuilder;
+    use nymsphinx_params::SphinxKeyRotation;
     use nymsphinx_types::{
         crypto, Delay as SphinxDelay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
         DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
     };
 
-    fn make_valid_sphinx_packet(size: PacketSize) -> SphinxPacket {
+    fn make_valid_sphinx_packet(size: PacketSize) -> NymPacket {
+        make_valid_packet(size, PacketType::Sphinx)
+    }
+
+    fn make_valid_outfox_packet(size: PacketSize) -> NymPacket {
+        make_valid_packet(size, PacketType::Outfox)
+    }
+
+    fn make_valid_packet(size: PacketSize, packet_type: PacketType) -> NymPacket {
         let (_, node1_pk) = crypto::keygen();
         let node1 = Node::new(
             NodeAddressBytes::from_bytes([5u8; NODE_ADDRESS_LENGTH]),
@@ -161,10 +200,24 @@ mod packet_encoding {
             SphinxDelay::new_from_nanos(42),
             SphinxDelay::new_from_nanos(42),
         ];
-        SphinxPacketBuilder::new()
-            .with_payload_size(size.payload_size())
-            .build_packet(b"foomp", &route, &destination, &delays)
-            .unwrap()
+        match packet_type {
+            PacketType::Sphinx => NymPacket::sphinx_build(
+                size.payload_size(),
+                b"foomp",
+                &route,
+                &destination,
+                &delays,
+            )
+            .unwrap(),
+            PacketType::Outfox => NymPacket::outfox_build(
+                size.payload_size(),
+                b"foomp",
+                &route,
+                &destination,
+                &delays,
+            )
+            .unwrap(),
+        }
     }
 
     #[test]
@@ -186,6 +239,101 @@ mod packet_encoding {
         assert_eq!(decoded.packet.to_bytes(), sphinx_bytes)
     }
 
+    #[test]
+    fn whole_outfox_packet_can_be_decoded_from_a_valid_encoded_instance() {
+        let outfox_packet = make_valid_outfox_packet(Default::default());
+        let outfox_bytes = outfox_packet.to_bytes();
+        let packet = FramedSphinxPacket::new(outfox_packet, Default::default(), false);
+        let header = packet.header;
+        assert_eq!(header.packet_type, PacketType::Outfox);
+
+        let mut bytes = BytesMut::new();
+        SphinxCodec.encode(packet, &mut bytes).unwrap();
+        let decoded = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+
+        assert_eq!(decoded.header, header);
+        assert!(decoded.packet.is_outfox());
+        assert_eq!(decoded.packet.to_bytes(), outfox_bytes)
+    }
+
+    #[test]
+    fn can_decode_mixed_packet_types() {
+        let sphinx_packet = FramedSphinxPacket::new(
+            make_valid_sphinx_packet(Default::default()),
+            Default::default(),
+            false,
+        );
+        let legacy_sphinx_packet = FramedSphinxPacket::new(
+            make_valid_sphinx_packet(PacketSize::AckPacket),
+            Default::default(),
+            true,
+        );
+        let outfox_packet = FramedSphinxPacket::new(
+            make_valid_outfox_packet(PacketSize::ExtendedPacket8),
+            Default::default(),
+            true,
+        );
+
+        let mut bytes = BytesMut::new();
+        SphinxCodec.encode(sphinx_packet, &mut bytes).unwrap();
+        SphinxCodec.encode(outfox_packet, &mut bytes).unwrap();
+        SphinxCodec
+            .encode(legacy_sphinx_packet, &mut bytes)
+            .unwrap();
+
+        let first = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+        assert!(first.packet.is_sphinx());
+        let second = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+        assert!(second.packet.is_outfox());
+        assert_eq!(second.packet_size(), PacketSize::ExtendedPacket8);
+        let third = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+        assert!(third.packet.is_sphinx());
+        assert_eq!(third.packet_size(), PacketSize::AckPacket);
+        assert!(SphinxCodec.decode(&mut bytes).unwrap().is_none());
+    }
+
+    #[test]
+    fn key_rotation_is_preserved_when_decoding() {
+        let packet = FramedSphinxPacket::new(
+            make_valid_sphinx_packet(Default::default()),
+            Default::default(),
+            false,
+        )
+        .with_key_rotation(SphinxKeyRotation::EvenRotation);
+
+        let mut bytes = BytesMut::new();
+        SphinxCodec.encode(packet, &mut bytes).unwrap();
+        let decoded = SphinxCodec.decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(decoded.key_rotation(), SphinxKeyRotation::EvenRotation);
+        assert!(decoded.packet.is_sphinx());
+    }
+
+    #[test]
+    fn outfox_packet_can_be_decoded_from_partial_reads() {
+        let packet = FramedSphinxPacket::new(
+            make_valid_outfox_packet(Default::default()),
+            Default::default(),
+            false,
+        );
+
+        let mut encoded = BytesMut::new();
+        SphinxCodec.encode(packet, &mut encoded).unwrap();
+
+        // header only, without the remaining hops byte
+        let mut bytes = BytesMut::new();
+        let rest = encoded.split_off(Header::TYPED_SIZE);
+        bytes.put(encoded);
+        assert!(SphinxCodec.decode(&mut bytes).unwrap().is_none());
+
+        let mut rest = rest;
+        let tail = rest.split_off(100);
+        bytes.put(rest);
+        assert!(SphinxCodec.decode(&mut bytes).unwrap().is_none());
+
+        bytes.put(tail);
+        assert!(SphinxCodec.decode(&mut bytes).unwrap().is_some());
+    }
+
     #[cfg(test)]
     mod decode_will_allocate_enough_bytes_for_next_call {
         use super::*;
@@ -218,6 +366,8 @@ mod packet_encoding {
                     packet_version: PacketVersion::Legacy,
                     packet_size,
                     packet_mode: Default::default(),
+                    packet_type: Default::default(),
+                    key_rotation: Default::default(),
                 };
                 let mut bytes = BytesMut::new();
                 header.encode(&mut bytes);
@@ -239,9 +389,11 @@ mod packet_encoding {
             ];
             for packet_size in packet_sizes {
                 let header = Header {
-                    packet_version: PacketVersion::Versioned(123),
+                    packet_version: PacketVersion::new(false),
                     packet_size,
                     packet_mode: Default::default(),
+                    packet_type: Default::default(),
+                    key_rotation: Default::default(),
                 };
                 let mut bytes = BytesMut::new();
                 header.encode(&mut bytes);
@@ -262,6 +414,8 @@ mod packet_encoding {
                     packet_version: PacketVersion::Legacy,
                     packet_size: Default::default(),
                     packet_mode: Default::default(),
+                    packet_type: Default::default(),
+                    key_rotation: Default::default(),
                 },
                 packet: make_valid_sphinx_packet(Default::default()),
             };
@@ -309,6 +463,8 @@ mod packet_encoding {
                         packet_version: PacketVersion::Legacy,
                         packet_size: Default::default(),
                         packet_mode: Default::default(),
+                        packet_type: Default::default(),
+                        key_rotation: Default::default(),
                     },
                     packet: make_valid_sphinx_packet(Default::default()),
                 };
@@ -342,7 +498,7 @@ mod packet_encoding {
 
                 let mut bytes = BytesMut::new();
                 SphinxCodec.encode(first_packet, &mut bytes).unwrap();
-                bytes.put_u8(PacketVersion::new_versioned(123).as_u8().unwrap());
+                bytes.put_u8(PacketVersion::new(false).as_u8().unwrap());
                 bytes.put_u8(packet_size as u8);
                 bytes.put_u8(PacketMode::default() as u8);
                 assert!(SphinxCodec.decode(&mut bytes).unwrap().is_some());
@@ -5,32 +5,53 @@ use crate::codec::SphinxCodecError;
 use bytes::{BufMut, BytesMut};
 use nymsphinx_params::packet_sizes::PacketSize;
 use nymsphinx_params::packet_version::PacketVersion;
-use nymsphinx_params::PacketMode;
-use nymsphinx_types::SphinxPacket;
+use nymsphinx_params::{PacketMode, PacketType, SphinxKeyRotation};
+use nymsphinx_types::{NymPacket, OutfoxPacket};
 use std::convert::TryFrom;
 
 pub struct FramedSphinxPacket {
     /// Contains any metadata helping receiver to handle the underlying packet.
     pub(crate) header: Header,
 
-    /// The actual SphinxPacket being sent.
-    pub(crate) packet: SphinxPacket,
+    /// The actual packet being sent.
+    pub(crate) packet: NymPacket,
 }
 
 impl FramedSphinxPacket {
-    pub fn new(packet: SphinxPacket, packet_mode: PacketMode, use_legacy_version: bool) -> Self {
+    pub fn new(packet: NymPacket, packet_mode: PacketMode, use_legacy_version: bool) -> Self {
         // If this fails somebody is using the library in a super incorrect way, because they
-        // already managed to somehow create a sphinx packet
-        let packet_size = PacketSize::get_type(packet.len()).unwrap();
-
-        FramedSphinxPacket {
-            header: Header {
+        // already managed to somehow create a packet
+        let header = match &packet {
+            NymPacket::Sphinx(sphinx_packet) => Header {
                 packet_version: PacketVersion::new(use_legacy_version),
-                packet_size,
+                packet_size: PacketSize::get_type(sphinx_packet.len()).unwrap(),
                 packet_mode,
+                packet_type: PacketType::Sphinx,
+                key_rotation: SphinxKeyRotation::Unknown,
             },
-            packet,
+            // nodes that don't understand the typed header wouldn't be able to process
+            // the outfox packet anyway
+            NymPacket::Outfox(outfox_packet) => Header {
+                packet_version: PacketVersion::new_typed(),
+                packet_size: PacketSize::get_type_from_payload_size(outfox_packet.payload_length())
+                    .unwrap(),
+                packet_mode,
+                packet_type: PacketType::Outfox,
+                key_rotation: SphinxKeyRotation::Unknown,
+            },
+        };
+
+        FramedSphinxPacket { header, packet }
+    }
+
+    /// Attaches the hint about the sphinx key of the receiving node the packet has been created with.
+    pub fn with_key_rotation(mut self, key_rotation: SphinxKeyRotation) -> Self {
+        if !key_rotation.is_unknown() {
+            // only the keyed version is capable of carrying the hint
+            self.header.packet_version = PacketVersion::new_keyed();
         }
+        self.header.key_rotation = key_rotation;
+        self
     }
 
     pub fn packet_size(&self) -> PacketSize {
@@ -41,7 +62,15 @@ impl FramedSphinxPacket {
         self.header.packet_mode
     }
 
-    pub fn into_inner(self) -> SphinxPacket {
+    pub fn packet_type(&self) -> PacketType {
+        self.header.packet_type
+    }
+
+    pub fn key_rotation(&self) -> SphinxKeyRotation {
+        self.header.key_rotation
+    }
+
+    pub fn into_inner(self) -> NymPacket {
         self.packet
     }
 }
@@ -67,30 +96,63 @@ pub struct Header {
     // Note: currently packet_mode is deprecated but is still left as a concept behind to not break
     // compatibility with existing network
     pub(crate) packet_mode: PacketMode,
+
+    /// Represents the format of the included packet. It's only put on the wire for the typed
+    /// packet versions, otherwise it's implicitly a sphinx packet.
+    pub(crate) packet_type: PacketType,
+
+    /// Represents the sphinx key of the receiving node used for constructing the included packet.
+    /// It's only put on the wire for the keyed packet versions, otherwise it's implicitly
+    /// the bonded key.
+    pub(crate) key_rotation: SphinxKeyRotation,
 }
 
 impl Header {
     pub(crate) const LEGACY_SIZE: usize = 2;
     pub(crate) const VERSIONED_SIZE: usize = 3;
+    pub(crate) const TYPED_SIZE: usize = 4;
+    pub(crate) const KEYED_SIZE: usize = 5;
 
     pub(crate) fn size(&self) -> usize {
         if self.packet_version.is_legacy() {
             Self::LEGACY_SIZE
+        } else if self.packet_version.is_keyed() {
+            Self::KEYED_SIZE
+        } else if self.packet_version.is_typed() {
+            Self::TYPED_SIZE
         } else {
             Self::VERSIONED_SIZE
         }
     }
 
+    /// Determines the length of the packet following this header. Since the outfox packets
+    /// shrink at every hop, it requires peeking at the first byte of the packet
+    /// (i.e. the number of remaining hops). `src` must start with the encoded header.
+    pub(crate) fn packet_len(&self, src: &[u8]) -> Option<usize> {
+        match self.packet_type {
+            PacketType::Sphinx => Some(self.packet_size.size()),
+            PacketType::Outfox => src.get(self.size()).map(|remaining_hops| {
+                OutfoxPacket::expected_length(self.packet_size.payload_size(), *remaining_hops)
+            }),
+        }
+    }
+
     pub(crate) fn encode(&self, dst: &mut BytesMut) {
         // we reserve one byte for `packet_size` and the other for `mode`
         dst.reserve(Self::LEGACY_SIZE);
         if let Some(version) = self.packet_version.as_u8() {
-            dst.reserve(Self::VERSIONED_SIZE);
+            dst.reserve(Self::KEYED_SIZE);
             dst.put_u8(version)
         }
 
         dst.put_u8(self.packet_size as u8);
         dst.put_u8(self.packet_mode as u8);
+        if self.packet_version.is_typed() {
+            dst.put_u8(self.packet_type as u8);
+        }
+        if self.packet_version.is_keyed() {
+            dst.put_u8(self.key_rotation as u8);
+        }
         // reserve bytes for the actual packet
         dst.reserve(self.packet_size.size());
     }
@@ -108,16 +170,41 @@ impl Header {
                 packet_version,
                 packet_size: PacketSize::try_from(src[0])?,
                 packet_mode: PacketMode::try_from(src[1])?,
+                packet_type: PacketType::Sphinx,
+                key_rotation: SphinxKeyRotation::Unknown,
             }))
         } else if src.len() < Self::VERSIONED_SIZE {
             // we're missing that 1 byte to read the full header...
             src.reserve(Self::VERSIONED_SIZE);
             Ok(None)
         } else {
+            let packet_size = PacketSize::try_from(src[1])?;
+            let packet_mode = PacketMode::try_from(src[2])?;
+            let packet_type = if !packet_version.is_typed() {
+                PacketType::Sphinx
+            } else if src.len() < Self::TYPED_SIZE {
+                // and for the typed variant we need yet another byte
+                src.reserve(Self::TYPED_SIZE);
+                return Ok(None);
+            } else {
+                PacketType::try_from(src[3])?
+            };
+            let key_rotation = if !packet_version.is_keyed() {
+                SphinxKeyRotation::Unknown
+            } else if src.len() < Self::KEYED_SIZE {
+                // and the keyed variant requires one more
+                src.reserve(Self::KEYED_SIZE);
+                return Ok(None);
+            } else {
+                SphinxKeyRotation::try_from(src[4])?
+            };
+
             Ok(Some(Header {
                 packet_version,
-                packet_size: PacketSize::try_from(src[1])?,
-                packet_mode: PacketMode::try_from(src[2])?,
+                packet_size,
+                packet_mode,
+                packet_type,
+                key_rotation,
             }))
         }
     }
@@ -155,6 +242,85 @@ mod header_encoding {
         assert!(Header::decode(&mut bytes).is_err())
     }
 
+    #[test]
+    fn typed_header_can_be_decoded_from_a_valid_encoded_instance() {
+        let header = Header {
+            packet_version: PacketVersion::new_typed(),
+            packet_size: Default::default(),
+            packet_mode: Default::default(),
+            packet_type: PacketType::Outfox,
+            key_rotation: SphinxKeyRotation::Unknown,
+        };
+        let mut bytes = BytesMut::new();
+        header.encode(&mut bytes);
+        assert_eq!(bytes.len(), Header::TYPED_SIZE);
+        let decoded = Header::decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(decoded, header);
+    }
+
+    #[test]
+    fn keyed_header_can_be_decoded_from_a_valid_encoded_instance() {
+        let header = Header {
+            packet_version: PacketVersion::new_keyed(),
+            packet_size: Default::default(),
+            packet_mode: Default::default(),
+            packet_type: PacketType::Sphinx,
+            key_rotation: SphinxKeyRotation::OddRotation,
+        };
+        let mut bytes = BytesMut::new();
+        header.encode(&mut bytes);
+        assert_eq!(bytes.len(), Header::KEYED_SIZE);
+        let decoded = Header::decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(decoded, header);
+    }
+
+    #[test]
+    fn decoding_will_fail_for_unknown_key_rotation() {
+        let mut bytes = BytesMut::from(
+            [
+                PacketVersion::new_keyed().as_u8().unwrap(),
+                PacketSize::default() as u8,
+                PacketMode::default() as u8,
+                PacketType::default() as u8,
+                255,
+            ]
+            .as_ref(),
+        );
+        assert!(Header::decode(&mut bytes).is_err())
+    }
+
+    #[test]
+    fn untyped_headers_are_always_sphinx() {
+        let mut bytes = BytesMut::from([PacketSize::default() as u8, 0].as_ref());
+        let decoded = Header::decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(decoded.packet_type, PacketType::Sphinx);
+
+        let mut bytes = BytesMut::from(
+            [
+                PacketVersion::new(false).as_u8().unwrap(),
+                PacketSize::default() as u8,
+                0,
+            ]
+            .as_ref(),
+        );
+        let decoded = Header::decode(&mut bytes).unwrap().unwrap();
+        assert_eq!(decoded.packet_type, PacketType::Sphinx);
+    }
+
+    #[test]
+    fn decoding_will_fail_for_unknown_packet_type() {
+        let mut bytes = BytesMut::from(
+            [
+                PacketVersion::new_typed().as_u8().unwrap(),
+                PacketSize::default() as u8,
+                PacketMode::default() as u8,
+                255,
+            ]
+            .as_ref(),
+        );
+        assert!(Header::decode(&mut bytes).is_err())
+    }
+
     #[test]
     fn decoding_will_fail_for_unknown_packet_mode() {
         let unknown_packet_mode: u8 = 255;
@@ -192,6 +358,8 @@ mod header_encoding {
                 packet_version: PacketVersion::Legacy,
                 packet_size,
                 packet_mode: Default::default(),
+                packet_type: Default::default(),
+                key_rotation: Default::default(),
             };
             let mut bytes = BytesMut::new();
             header.encode(&mut bytes);
@@ -213,6 +381,8 @@ mod header_encoding {
                 packet_version: PacketVersion::Versioned(123),
                 packet_size,
                 packet_mode: Default::default(),
+                packet_type: Default::default(),
+                key_rotation: Default::default(),
             };
             let mut bytes = BytesMut::new();
             header.encode(&mut bytes);
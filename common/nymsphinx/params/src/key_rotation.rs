@@ -0,0 +1,123 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nymsphinx_types::{NodeAddressBytes, NODE_ADDRESS_LENGTH};
+use std::convert::TryFrom;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("{received} is not a valid sphinx key rotation tag")]
+pub struct InvalidSphinxKeyRotation {
+    received: u8,
+}
+
+/// Hint telling the node which of its sphinx keys the packet has been created with, so that it
+/// would not have to attempt to unwrap it with all of them.
+///
+/// Since the key epochs are consecutive and a node never accepts keys of more than two epochs at
+/// once (the current one and the previous one, during its grace period), the parity of the epoch
+/// is sufficient to unambiguously identify the key.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SphinxKeyRotation {
+    /// The packet has been created with the sphinx key the node has bonded with
+    /// (or by a client that doesn't know about the key rotation).
+    #[default]
+    Unknown = 0,
+
+    /// The packet has been created with the sphinx key of an odd key epoch.
+    OddRotation = 1,
+
+    /// The packet has been created with the sphinx key of an even key epoch.
+    EvenRotation = 2,
+}
+
+impl SphinxKeyRotation {
+    // the hint is put in the last byte of the address of the node, which is otherwise
+    // always zero-padded, so it's ignored by the nodes that don't know about it
+    const ADDRESS_HINT_BYTE: usize = NODE_ADDRESS_LENGTH - 1;
+
+    /// Returns the hint for the key of the provided epoch, where `None` implies the bonded key.
+    pub fn from_key_epoch(epoch: Option<u32>) -> Self {
+        match epoch {
+            None => SphinxKeyRotation::Unknown,
+            Some(epoch) if epoch % 2 == 1 => SphinxKeyRotation::OddRotation,
+            Some(_) => SphinxKeyRotation::EvenRotation,
+        }
+    }
+
+    /// Checks whether the key of the provided epoch is the one described by this hint.
+    pub fn matches(self, epoch: Option<u32>) -> bool {
+        self == Self::from_key_epoch(epoch)
+    }
+
+    pub fn is_unknown(self) -> bool {
+        self == SphinxKeyRotation::Unknown
+    }
+
+    /// Recovers the hint attached to the (zero-padded) address of a node.
+    pub fn from_node_address(address: &NodeAddressBytes) -> Self {
+        Self::from_padded_address(address.as_bytes_ref())
+    }
+
+    /// Recovers the hint attached to the zero-padded address of a node represented
+    /// with an arbitrary number of bytes.
+    pub fn from_padded_address(address: &[u8]) -> Self {
+        address
+            .last()
+            .and_then(|hint| SphinxKeyRotation::try_from(*hint).ok())
+            .unwrap_or_default()
+    }
+
+    /// Attaches this hint to the provided (zero-padded) address of a node.
+    pub fn attach_to_node_address(self, address: NodeAddressBytes) -> NodeAddressBytes {
+        let mut bytes = *address.as_bytes_ref();
+        bytes[Self::ADDRESS_HINT_BYTE] = self as u8;
+        NodeAddressBytes::from_bytes(bytes)
+    }
+}
+
+impl TryFrom<u8> for SphinxKeyRotation {
+    type Error = InvalidSphinxKeyRotation;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            _ if value == (SphinxKeyRotation::Unknown as u8) => Ok(Self::Unknown),
+            _ if value == (SphinxKeyRotation::OddRotation as u8) => Ok(Self::OddRotation),
+            _ if value == (SphinxKeyRotation::EvenRotation as u8) => Ok(Self::EvenRotation),
+            v => Err(InvalidSphinxKeyRotation { received: v }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hint_identifies_consecutive_epochs() {
+        assert!(SphinxKeyRotation::from_key_epoch(None).is_unknown());
+        assert_ne!(
+            SphinxKeyRotation::from_key_epoch(Some(41)),
+            SphinxKeyRotation::from_key_epoch(Some(42))
+        );
+        assert!(SphinxKeyRotation::from_key_epoch(Some(41)).matches(Some(43)));
+        assert!(!SphinxKeyRotation::from_key_epoch(Some(41)).matches(None));
+    }
+
+    #[test]
+    fn hint_can_be_attached_to_node_address() {
+        let mut raw = [0u8; NODE_ADDRESS_LENGTH];
+        raw[..7].copy_from_slice(&[4, 7, 5, 1, 2, 3, 4]);
+        let address = NodeAddressBytes::from_bytes(raw);
+        assert!(SphinxKeyRotation::from_node_address(&address).is_unknown());
+
+        let with_hint = SphinxKeyRotation::EvenRotation.attach_to_node_address(address);
+        assert_eq!(
+            SphinxKeyRotation::from_node_address(&with_hint),
+            SphinxKeyRotation::EvenRotation
+        );
+        // the actual address is not affected
+        assert_eq!(with_hint.as_bytes_ref()[..7], raw[..7]);
+    }
+}
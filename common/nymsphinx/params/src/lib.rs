@@ -8,11 +8,15 @@ use crypto::ctr;
 type Aes128Ctr = ctr::Ctr64BE<Aes128>;
 
 // Re-export for ease of use
+pub use key_rotation::SphinxKeyRotation;
 pub use packet_modes::PacketMode;
 pub use packet_sizes::PacketSize;
+pub use packet_types::PacketType;
 
+pub mod key_rotation;
 pub mod packet_modes;
 pub mod packet_sizes;
+pub mod packet_types;
 pub mod packet_version;
 
 // If somebody can provide an argument why it might be reasonable to have more than 255 mix hops,
@@ -36,6 +40,23 @@ pub type SerializedFragmentIdentifier = [u8; FRAG_ID_LEN];
 /// Increment it whenever we perform any breaking change in the wire format!
 const CURRENT_PACKET_VERSION_NUMBER: u8 = 7;
 
+// starting with version 8, the packet header also includes the packet type, i.e.:
+// - packet_version
+// - packet_size indicator
+// - packet_mode
+// - packet_type
+// it's only used for the packets that are not sphinx so that the nodes that haven't been updated
+// could still understand (sphinx) packets sent by the updated clients
+/// The first version of the wire format that includes the packet type.
+const TYPED_PACKET_VERSION_NUMBER: u8 = 8;
+
+// starting with version 9, the packet header might additionally include the sphinx key rotation
+// hint (after the packet type). Similarly to the typed version, it's only used for the packets
+// created with the rotated sphinx keys, so that the packets using the bonded keys could still
+// be understood by the nodes that haven't been updated
+/// The first version of the wire format that includes the sphinx key rotation.
+const KEYED_PACKET_VERSION_NUMBER: u8 = 9;
+
 // TODO: ask @AP about the choice of below algorithms
 
 /// Hashing algorithm used during hkdf for ephemeral shared key generation per sphinx packet payload.
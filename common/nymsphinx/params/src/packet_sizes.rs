@@ -120,6 +120,12 @@ impl PacketSize {
         }
     }
 
+    /// Determines the packet size based on the length of the payload alone. Useful for formats
+    /// with variable header length, such as outfox.
+    pub fn get_type_from_payload_size(payload_size: usize) -> Result<Self, InvalidPacketSize> {
+        Self::get_type(payload_size + HEADER_SIZE)
+    }
+
     pub fn is_extended_size(&self) -> bool {
         match self {
             PacketSize::RegularPacket | PacketSize::AckPacket => false,
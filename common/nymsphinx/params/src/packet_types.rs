@@ -0,0 +1,64 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("{received} is not a valid packet type tag")]
+pub struct InvalidPacketType {
+    received: String,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PacketType {
+    /// Represents the original sphinx packet format.
+    #[default]
+    Sphinx = 0,
+
+    /// Represents the outfox packet format that's geared towards the layered mix topology
+    /// and cheaper processing.
+    Outfox = 1,
+}
+
+impl PacketType {
+    pub fn is_sphinx(self) -> bool {
+        self == PacketType::Sphinx
+    }
+
+    pub fn is_outfox(self) -> bool {
+        self == PacketType::Outfox
+    }
+}
+
+impl FromStr for PacketType {
+    type Err = InvalidPacketType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sphinx" => Ok(Self::Sphinx),
+            "outfox" => Ok(Self::Outfox),
+            s => Err(InvalidPacketType {
+                received: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<u8> for PacketType {
+    type Error = InvalidPacketType;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            _ if value == (PacketType::Sphinx as u8) => Ok(Self::Sphinx),
+            _ if value == (PacketType::Outfox as u8) => Ok(Self::Outfox),
+            v => Err(InvalidPacketType {
+                received: v.to_string(),
+            }),
+        }
+    }
+}
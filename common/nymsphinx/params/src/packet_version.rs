@@ -1,7 +1,10 @@
 // Copyright 2022 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{PacketSize, CURRENT_PACKET_VERSION_NUMBER};
+use crate::{
+    PacketSize, CURRENT_PACKET_VERSION_NUMBER, KEYED_PACKET_VERSION_NUMBER,
+    TYPED_PACKET_VERSION_NUMBER,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PacketVersion {
@@ -19,6 +22,16 @@ impl PacketVersion {
         }
     }
 
+    /// Creates the version that's capable of carrying the packet type.
+    pub fn new_typed() -> Self {
+        Self::new_versioned(TYPED_PACKET_VERSION_NUMBER)
+    }
+
+    /// Creates the version that's capable of carrying both the packet type and the sphinx key rotation.
+    pub fn new_keyed() -> Self {
+        Self::new_versioned(KEYED_PACKET_VERSION_NUMBER)
+    }
+
     pub fn new_legacy() -> Self {
         PacketVersion::Legacy
     }
@@ -31,6 +44,20 @@ impl PacketVersion {
         matches!(self, PacketVersion::Legacy)
     }
 
+    pub fn is_typed(&self) -> bool {
+        match self {
+            PacketVersion::Legacy => false,
+            PacketVersion::Versioned(version) => *version >= TYPED_PACKET_VERSION_NUMBER,
+        }
+    }
+
+    pub fn is_keyed(&self) -> bool {
+        match self {
+            PacketVersion::Legacy => false,
+            PacketVersion::Versioned(version) => *version >= KEYED_PACKET_VERSION_NUMBER,
+        }
+    }
+
     pub fn as_u8(&self) -> Option<u8> {
         match self {
             PacketVersion::Legacy => None,
@@ -11,9 +11,8 @@ use nymsphinx_anonymous_replies::reply_surb::ReplySurb;
 use nymsphinx_chunking::fragment::{Fragment, FragmentIdentifier};
 use nymsphinx_forwarding::packet::MixPacket;
 use nymsphinx_params::packet_sizes::PacketSize;
-use nymsphinx_params::DEFAULT_NUM_MIX_HOPS;
-use nymsphinx_types::builder::SphinxPacketBuilder;
-use nymsphinx_types::{delays, Delay};
+use nymsphinx_params::{PacketType, SphinxKeyRotation, DEFAULT_NUM_MIX_HOPS};
+use nymsphinx_types::{delays, Delay, NymPacket};
 use rand::{CryptoRng, Rng};
 use std::convert::TryFrom;
 use std::time::Duration;
@@ -48,6 +47,10 @@ pub struct MessagePreparer<R> {
     /// Size of the target [`SphinxPacket`] into which the underlying is going to get split.
     packet_size: PacketSize,
 
+    /// Format of the packets carrying the 'real' messages. Note that acknowledgements and replies
+    /// are always sent as sphinx packets.
+    packet_type: PacketType,
+
     /// Address of this client which also represent an address to which all acknowledgements
     /// and surb-based are going to be sent.
     sender_address: Recipient,
@@ -76,6 +79,7 @@ where
         MessagePreparer {
             rng,
             packet_size: Default::default(),
+            packet_type: Default::default(),
             sender_address,
             average_packet_delay,
             average_ack_delay,
@@ -95,6 +99,12 @@ where
         self
     }
 
+    /// Allows setting non-default format of the packets carrying the 'real' messages.
+    pub fn with_packet_type(mut self, packet_type: PacketType) -> Self {
+        self.packet_type = packet_type;
+        self
+    }
+
     /// Overwrites existing sender address with the provided value.
     pub fn set_sender_address(&mut self, sender_address: Recipient) {
         self.sender_address = sender_address;
@@ -152,7 +162,7 @@ where
 
         // the unwrap here is fine as the failures can only originate from attempting to use invalid payload lenghts
         // and we just very carefully constructed a (presumably) valid one
-        let (sphinx_packet, first_hop_address) = reply_surb
+        let (sphinx_packet, first_hop_address, first_hop_key_rotation) = reply_surb
             .apply_surb(packet_payload, Some(self.packet_size))
             .unwrap();
 
@@ -161,7 +171,8 @@ where
             // well as the total delay of the ack packet.
             // we don't know the delays inside the reply surbs so we use best-effort estimation from our poisson distribution
             total_delay: expected_forward_delay + ack_delay,
-            mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
+            mix_packet: MixPacket::new(first_hop_address, sphinx_packet.into(), Default::default())
+                .with_key_rotation(first_hop_key_rotation),
             fragment_identifier,
         })
     }
@@ -210,23 +221,40 @@ where
         // including set of delays
         let delays = delays::generate_from_average_duration(route.len(), self.average_packet_delay);
 
-        // create the actual sphinx packet here. With valid route and correct payload size,
+        // create the actual packet here. With valid route and correct payload size,
         // there's absolutely no reason for this call to fail.
-        let sphinx_packet = SphinxPacketBuilder::new()
-            .with_payload_size(self.packet_size.payload_size())
-            .build_packet(packet_payload, &route, &destination, &delays)
-            .unwrap();
+        let payload_size = self.packet_size.payload_size();
+        let packet = match self.packet_type {
+            PacketType::Sphinx => NymPacket::sphinx_build(
+                payload_size,
+                packet_payload.as_ref(),
+                &route,
+                &destination,
+                &delays,
+            )
+            .unwrap(),
+            PacketType::Outfox => NymPacket::outfox_build(
+                payload_size,
+                packet_payload.as_ref(),
+                &route,
+                &destination,
+                &delays,
+            )
+            .unwrap(),
+        };
 
         // from the previously constructed route extract the first hop
-        let first_hop_address =
-            NymNodeRoutingAddress::try_from(route.first().unwrap().address).unwrap();
+        let first_hop = route.first().unwrap();
+        let first_hop_address = NymNodeRoutingAddress::try_from(first_hop.address).unwrap();
+        let first_hop_key_rotation = SphinxKeyRotation::from_node_address(&first_hop.address);
 
         Ok(PreparedFragment {
             // the round-trip delay is the sum of delays of all hops on the forward route as
             // well as the total delay of the ack packet.
             // note that the last hop of the packet is a gateway that does not do any delays
             total_delay: delays.iter().take(delays.len() - 1).sum::<Delay>() + ack_delay,
-            mix_packet: MixPacket::new(first_hop_address, sphinx_packet, Default::default()),
+            mix_packet: MixPacket::new(first_hop_address, packet, Default::default())
+                .with_key_rotation(first_hop_key_rotation),
             fragment_identifier,
         })
     }
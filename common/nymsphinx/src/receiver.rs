@@ -203,6 +203,7 @@ mod message_receiver {
                 owner: "foomp1".to_string(),
                 host: "10.20.30.40".parse().unwrap(),
                 mix_host: "10.20.30.40:1789".parse().unwrap(),
+                http_api_port: 8000,
                 identity_key: identity::PublicKey::from_base58_string(
                     "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
                 )
@@ -211,8 +212,11 @@ mod message_receiver {
                     "B3GzG62aXAZNg14RoMCp3BhELNBrySLr2JqrwyfYFzRc",
                 )
                 .unwrap(),
+                sphinx_key_epoch: None,
                 layer: Layer::One,
                 version: "0.8.0-dev".to_string(),
+                family: None,
+                performance: None,
             }],
         );
 
@@ -223,6 +227,7 @@ mod message_receiver {
                 owner: "foomp2".to_string(),
                 host: "11.21.31.41".parse().unwrap(),
                 mix_host: "11.21.31.41:1789".parse().unwrap(),
+                http_api_port: 8000,
                 identity_key: identity::PublicKey::from_base58_string(
                     "D6YaMzLSY7mANtSQRKXsmMZpqgqiVkeiagKM4V4oFPFr",
                 )
@@ -231,8 +236,11 @@ mod message_receiver {
                     "5Z1VqYwM2xeKxd8H7fJpGWasNiDFijYBAee7MErkZ5QT",
                 )
                 .unwrap(),
+                sphinx_key_epoch: None,
                 layer: Layer::Two,
                 version: "0.8.0-dev".to_string(),
+                family: None,
+                performance: None,
             }],
         );
 
@@ -243,6 +251,7 @@ mod message_receiver {
                 owner: "foomp3".to_string(),
                 host: "12.22.32.42".parse().unwrap(),
                 mix_host: "12.22.32.42:1789".parse().unwrap(),
+                http_api_port: 8000,
                 identity_key: identity::PublicKey::from_base58_string(
                     "GkWDysw4AjESv1KiAiVn7JzzCMJeksxNSXVfr1PpX8wD",
                 )
@@ -251,8 +260,11 @@ mod message_receiver {
                     "9EyjhCggr2QEA2nakR88YHmXgpy92DWxoe2draDRkYof",
                 )
                 .unwrap(),
+                sphinx_key_epoch: None,
                 layer: Layer::Three,
                 version: "0.8.0-dev".to_string(),
+                family: None,
+                performance: None,
             }],
         );
 
@@ -274,6 +286,7 @@ mod message_receiver {
                     "EB42xvMFMD5rUCstE2CDazgQQJ22zLv8SPm1Luxni44c",
                 )
                 .unwrap(),
+                sphinx_key_epoch: None,
                 version: "0.8.0-dev".to_string(),
             }],
         )
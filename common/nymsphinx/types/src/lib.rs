@@ -1,6 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+mod nym_packet;
+
+pub use nym_outfox::{
+    error::OutFoxError,
+    packet::{OutfoxPacket, OutfoxProcessedPacket, OutfoxRoutingInformation},
+};
+pub use nym_packet::NymPacket;
+
 // re-exporting types and constants available in sphinx
 pub use sphinx::{
     constants::{
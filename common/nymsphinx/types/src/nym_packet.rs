@@ -0,0 +1,135 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_outfox::error::OutFoxError;
+use nym_outfox::packet::{OutfoxHop, OutfoxPacket, OutfoxRoutingInformation};
+use sphinx::header::delays::Delay;
+use sphinx::packet::builder::SphinxPacketBuilder;
+use sphinx::route::{Destination, Node};
+use sphinx::SphinxPacket;
+use std::fmt::{self, Debug, Formatter};
+
+/// Any packet format that can be sent through the mix network.
+// both formats are going to coexist until the migration to outfox is complete
+pub enum NymPacket {
+    Sphinx(SphinxPacket),
+    Outfox(OutfoxPacket),
+}
+
+impl Debug for NymPacket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NymPacket::Sphinx(packet) => write!(
+                f,
+                "Sphinx header: {:?}, payload length: {}",
+                packet.header,
+                packet.payload.len()
+            ),
+            NymPacket::Outfox(packet) => write!(
+                f,
+                "Outfox packet with {} remaining hops, payload length: {}",
+                packet.remaining_hops(),
+                packet.payload_length()
+            ),
+        }
+    }
+}
+
+impl From<SphinxPacket> for NymPacket {
+    fn from(packet: SphinxPacket) -> Self {
+        NymPacket::Sphinx(packet)
+    }
+}
+
+impl From<OutfoxPacket> for NymPacket {
+    fn from(packet: OutfoxPacket) -> Self {
+        NymPacket::Outfox(packet)
+    }
+}
+
+impl NymPacket {
+    pub fn sphinx_build(
+        payload_size: usize,
+        message: &[u8],
+        route: &[Node],
+        destination: &Destination,
+        delays: &[Delay],
+    ) -> Result<Self, sphinx::Error> {
+        SphinxPacketBuilder::new()
+            .with_payload_size(payload_size)
+            .build_packet(message, route, destination, delays)
+            .map(NymPacket::Sphinx)
+    }
+
+    /// Builds an outfox packet going through the provided route. Each hop learns the address
+    /// of the next one, apart from the final hop that learns the address of the destination.
+    pub fn outfox_build(
+        payload_size: usize,
+        message: &[u8],
+        route: &[Node],
+        destination: &Destination,
+        delays: &[Delay],
+    ) -> Result<Self, OutFoxError> {
+        if route.len() != delays.len() {
+            return Err(OutFoxError::LenMismatch {
+                expected: route.len(),
+                got: delays.len(),
+            });
+        }
+
+        let hops = route
+            .iter()
+            .zip(delays)
+            .enumerate()
+            .map(|(i, (node, delay))| {
+                let address = match route.get(i + 1) {
+                    Some(next_hop) => *next_hop.address.as_bytes_ref(),
+                    None => *destination.address.as_bytes_ref(),
+                };
+                OutfoxHop {
+                    public_key: *node.pub_key.as_bytes(),
+                    routing_information: OutfoxRoutingInformation {
+                        address,
+                        delay_nanos: delay.to_duration().as_nanos() as u64,
+                    },
+                }
+            })
+            .collect::<Vec<_>>();
+
+        OutfoxPacket::build(message, payload_size, &hops).map(NymPacket::Outfox)
+    }
+
+    pub fn sphinx_from_bytes(bytes: &[u8]) -> Result<Self, sphinx::Error> {
+        SphinxPacket::from_bytes(bytes).map(NymPacket::Sphinx)
+    }
+
+    pub fn outfox_from_bytes(bytes: &[u8]) -> Result<Self, OutFoxError> {
+        OutfoxPacket::from_bytes(bytes).map(NymPacket::Outfox)
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            NymPacket::Sphinx(packet) => packet.len(),
+            NymPacket::Outfox(packet) => packet.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_sphinx(&self) -> bool {
+        matches!(self, NymPacket::Sphinx(_))
+    }
+
+    pub fn is_outfox(&self) -> bool {
+        matches!(self, NymPacket::Outfox(_))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            NymPacket::Sphinx(packet) => packet.to_bytes(),
+            NymPacket::Outfox(packet) => packet.to_bytes(),
+        }
+    }
+}
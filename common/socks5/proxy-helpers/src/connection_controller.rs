@@ -6,7 +6,7 @@ use futures::channel::mpsc;
 use futures::StreamExt;
 use log::*;
 use ordered_buffer::{OrderedMessage, OrderedMessageBuffer, ReadContiguousData};
-use socks5_requests::ConnectionId;
+use socks5_requests::{BindStatus, ConnectionId, RemoteAddress};
 use std::{
     collections::{HashMap, HashSet},
     time::Duration,
@@ -32,6 +32,27 @@ pub type ConnectionSender = mpsc::UnboundedSender<ConnectionMessage>;
 /// Receiver part of the [`ConnectionSender`]
 pub type ConnectionReceiver = mpsc::UnboundedReceiver<ConnectionMessage>;
 
+/// A single datagram received from the mix network for a particular UDP association alongside
+/// the address of the remote that has originally sent it.
+#[derive(Debug)]
+pub struct DatagramMessage {
+    pub source_addr: RemoteAddress,
+    pub payload: Vec<u8>,
+}
+
+/// Channel responsible for sending datagrams that were received from mix network into particular
+/// UDP association. Datagrams are forwarded as soon as they are received without any reordering.
+pub type DatagramSender = mpsc::UnboundedSender<DatagramMessage>;
+
+/// Receiver part of the [`DatagramSender`]
+pub type DatagramReceiver = mpsc::UnboundedReceiver<DatagramMessage>;
+
+/// Channel responsible for notifying a pending `Bind` request about its progress.
+pub type BindSender = mpsc::UnboundedSender<BindStatus>;
+
+/// Receiver part of the [`BindSender`]
+pub type BindReceiver = mpsc::UnboundedReceiver<BindStatus>;
+
 pub type ControllerSender = mpsc::UnboundedSender<ControllerCommand>;
 pub type ControllerReceiver = mpsc::UnboundedReceiver<ControllerCommand>;
 
@@ -39,6 +60,10 @@ pub enum ControllerCommand {
     Insert(ConnectionId, ConnectionSender),
     Remove(ConnectionId),
     Send(ConnectionId, Vec<u8>, bool),
+    InsertAssociation(ConnectionId, DatagramSender),
+    SendDatagram(ConnectionId, RemoteAddress, Vec<u8>),
+    InsertBind(ConnectionId, BindSender),
+    SendBindStatus(ConnectionId, BindStatus),
 }
 
 struct ActiveConnection {
@@ -78,6 +103,12 @@ pub enum BroadcastActiveConnections {
 /// proxy.
 pub struct Controller {
     active_connections: HashMap<ConnectionId, ActiveConnection>,
+
+    // UDP associations do not need any ordering so their datagrams are forwarded directly
+    active_associations: HashMap<ConnectionId, DatagramSender>,
+
+    // connections created via `Bind` that are waiting for the remote to report its progress
+    pending_binds: HashMap<ConnectionId, BindSender>,
     receiver: ControllerReceiver,
 
     // TODO: this will need to be either completely removed (from code) or periodically cleaned
@@ -111,6 +142,8 @@ impl Controller {
         (
             Controller {
                 active_connections: HashMap::new(),
+                active_associations: HashMap::new(),
+                pending_binds: HashMap::new(),
                 receiver,
                 recently_closed: HashSet::new(),
                 client_connection_tx,
@@ -142,9 +175,44 @@ impl Controller {
         }
     }
 
+    fn insert_association(&mut self, conn_id: ConnectionId, datagram_sender: DatagramSender) {
+        if self
+            .active_associations
+            .insert(conn_id, datagram_sender)
+            .is_some()
+        {
+            error!("Received a duplicate UDP association!")
+        }
+    }
+
+    fn insert_bind(&mut self, conn_id: ConnectionId, bind_sender: BindSender) {
+        if self.pending_binds.insert(conn_id, bind_sender).is_some() {
+            error!("Received a duplicate 'Bind'!")
+        }
+    }
+
+    fn send_bind_status(&mut self, conn_id: ConnectionId, status: BindStatus) {
+        let Some(bind_sender) = self.pending_binds.get(&conn_id) else {
+            debug!("Received bind status for unknown connection {conn_id}");
+            return;
+        };
+
+        // after the connection is accepted (or it failed) there will be no further updates
+        let is_final = !matches!(status, BindStatus::Listening(_));
+        if let Err(err) = bind_sender.unbounded_send(status) {
+            debug!("Failed to forward bind status for {conn_id}: {err}");
+        }
+        if is_final {
+            self.pending_binds.remove(&conn_id);
+        }
+    }
+
     fn remove_connection(&mut self, conn_id: ConnectionId) {
         debug!("Removing {} from controller", conn_id);
-        if self.active_connections.remove(&conn_id).is_none() {
+        self.pending_binds.remove(&conn_id);
+        if self.active_connections.remove(&conn_id).is_none()
+            && self.active_associations.remove(&conn_id).is_none()
+        {
             error!(
                 "tried to remove non-existing connection with id: {:?}",
                 conn_id
@@ -167,7 +235,12 @@ impl Controller {
 
     fn broadcast_active_connections(&mut self) {
         // What about the recently closed ones? Hopefully we can ignore them ...
-        let conn_ids = self.active_connections.keys().copied().collect();
+        let conn_ids = self
+            .active_connections
+            .keys()
+            .chain(self.active_associations.keys())
+            .copied()
+            .collect();
 
         self.client_connection_tx
             .unbounded_send(ConnectionCommand::ActiveConnections(conn_ids))
@@ -229,6 +302,31 @@ impl Controller {
         }
     }
 
+    fn send_datagram(
+        &mut self,
+        conn_id: ConnectionId,
+        source_addr: RemoteAddress,
+        payload: Vec<u8>,
+    ) {
+        // datagrams are inherently unreliable, so if the association is not (or no longer) there,
+        // there's no point in buffering anything
+        let Some(datagram_sender) = self.active_associations.get(&conn_id) else {
+            debug!(
+                "Received a datagram for unknown UDP association {} ({} bytes were dropped)",
+                conn_id,
+                payload.len()
+            );
+            return;
+        };
+
+        if let Err(err) = datagram_sender.unbounded_send(DatagramMessage {
+            source_addr,
+            payload,
+        }) {
+            debug!("Failed to forward datagram to UDP association {conn_id}: {err}");
+        }
+    }
+
     pub async fn run(&mut self) {
         let mut interval = time::interval(Duration::from_millis(500));
 
@@ -242,6 +340,18 @@ impl Controller {
                         self.insert_connection(conn_id, sender)
                     }
                     Some(ControllerCommand::Remove(conn_id)) => self.remove_connection(conn_id),
+                    Some(ControllerCommand::InsertAssociation(conn_id, sender)) => {
+                        self.insert_association(conn_id, sender)
+                    }
+                    Some(ControllerCommand::SendDatagram(conn_id, source_addr, data)) => {
+                        self.send_datagram(conn_id, source_addr, data)
+                    }
+                    Some(ControllerCommand::InsertBind(conn_id, sender)) => {
+                        self.insert_bind(conn_id, sender)
+                    }
+                    Some(ControllerCommand::SendBindStatus(conn_id, status)) => {
+                        self.send_bind_status(conn_id, status)
+                    }
                     None => {
                         log::trace!("SOCKS5 Controller: Stopping since channel closed");
                         break;
@@ -13,6 +13,9 @@ pub type RemoteAddress = String;
 pub enum RequestFlag {
     Connect = 0,
     Send = 1,
+    Datagram = 2,
+    Bind = 3,
+    ReturnAddressUpdate = 4,
 }
 
 impl TryFrom<u8> for RequestFlag {
@@ -22,6 +25,9 @@ impl TryFrom<u8> for RequestFlag {
         match value {
             _ if value == (RequestFlag::Connect as u8) => Ok(Self::Connect),
             _ if value == (RequestFlag::Send as u8) => Ok(Self::Send),
+            _ if value == (RequestFlag::Datagram as u8) => Ok(Self::Datagram),
+            _ if value == (RequestFlag::Bind as u8) => Ok(Self::Bind),
+            _ if value == (RequestFlag::ReturnAddressUpdate as u8) => Ok(Self::ReturnAddressUpdate),
             _ => Err(RequestError::UnknownRequestFlag),
         }
     }
@@ -49,6 +55,9 @@ pub enum RequestError {
 
     #[error("malformed return address - {0}")]
     MalformedReturnAddress(RecipientFormattingError),
+
+    #[error("not enough bytes to recover the datagram return address flag")]
+    DatagramReturnFlagMissing,
 }
 
 impl RequestError {
@@ -65,6 +74,23 @@ pub struct ConnectRequest {
     pub return_address: Option<Recipient>,
 }
 
+#[derive(Debug)]
+pub struct BindRequest {
+    pub conn_id: ConnectionId,
+    /// Address of the remote that is expected to connect to the bound socket.
+    pub remote_addr: RemoteAddress,
+    pub return_address: Option<Recipient>,
+}
+
+#[derive(Debug)]
+pub struct DatagramRequest {
+    /// Identifier of the UDP association this datagram belongs to.
+    pub conn_id: ConnectionId,
+    pub remote_addr: RemoteAddress,
+    pub return_address: Option<Recipient>,
+    pub data: Vec<u8>,
+}
+
 /// A request from a SOCKS5 client that a Nym Socks5 service provider should
 /// take an action for an application using a (probably local) Nym Socks5 proxy.
 #[derive(Debug)]
@@ -76,6 +102,21 @@ pub enum Request {
 
     /// Re-use an existing TCP connection, sending more request data up it.
     Send(ConnectionId, Vec<u8>, bool),
+
+    /// Send a single UDP datagram to the specified `RemoteAddress` as part of the
+    /// UDP association identified by the `ConnectionId`. Datagrams are neither ordered
+    /// nor acknowledged and any responses are sent back to the specified `Recipient`.
+    Datagram(Box<DatagramRequest>),
+
+    /// Open a listening TCP socket and wait for an inbound connection from the specified
+    /// `RemoteAddress`. Once accepted, the connection is treated the same way as if it was
+    /// established via `Connect`. The bound and the accepted addresses are reported back
+    /// to the specified `Recipient`.
+    Bind(Box<BindRequest>),
+
+    /// Tell the service provider that all further responses produced on this `ConnectionId`
+    /// should be sent to the new `Recipient`, i.e. after the client has switched its gateway.
+    ReturnAddressUpdate(ConnectionId, Box<Recipient>),
 }
 
 impl Request {
@@ -97,6 +138,39 @@ impl Request {
         Request::Send(conn_id, data, local_closed)
     }
 
+    /// Construct a new Request::Bind instance
+    pub fn new_bind(
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        return_address: Option<Recipient>,
+    ) -> Request {
+        Request::Bind(Box::new(BindRequest {
+            conn_id,
+            remote_addr,
+            return_address,
+        }))
+    }
+
+    /// Construct a new Request::Datagram instance
+    pub fn new_datagram(
+        conn_id: ConnectionId,
+        remote_addr: RemoteAddress,
+        return_address: Option<Recipient>,
+        data: Vec<u8>,
+    ) -> Request {
+        Request::Datagram(Box::new(DatagramRequest {
+            conn_id,
+            remote_addr,
+            return_address,
+            data,
+        }))
+    }
+
+    /// Construct a new Request::ReturnAddressUpdate instance
+    pub fn new_return_address_update(conn_id: ConnectionId, return_address: Recipient) -> Request {
+        Request::ReturnAddressUpdate(conn_id, Box::new(return_address))
+    }
+
     /// Deserialize the request type, connection id, destination address and port,
     /// and the request body from bytes.
     ///
@@ -123,55 +197,92 @@ impl Request {
         let connection_id = u64::from_be_bytes([b[1], b[2], b[3], b[4], b[5], b[6], b[7], b[8]]);
         match RequestFlag::try_from(b[0])? {
             RequestFlag::Connect => {
-                let connect_request_bytes = &b[9..];
+                let (remote_address, return_address) = parse_remote_and_return_address(&b[9..])?;
+
+                Ok(Request::new_connect(
+                    connection_id,
+                    remote_address,
+                    return_address,
+                ))
+            }
+            RequestFlag::Bind => {
+                let (remote_address, return_address) = parse_remote_and_return_address(&b[9..])?;
 
-                // we need to be able to read at least 2 bytes that specify address length
-                if connect_request_bytes.len() < 2 {
+                Ok(Request::new_bind(
+                    connection_id,
+                    remote_address,
+                    return_address,
+                ))
+            }
+            RequestFlag::Send => {
+                let local_closed = b[9] != 0;
+                let data = b[10..].to_vec();
+
+                Ok(Request::Send(connection_id, data, local_closed))
+            }
+            RequestFlag::Datagram => {
+                let datagram_request_bytes = &b[9..];
+
+                if datagram_request_bytes.len() < 2 {
                     return Err(RequestError::AddressLengthTooShort);
                 }
 
                 let address_length =
-                    u16::from_be_bytes([connect_request_bytes[0], connect_request_bytes[1]])
+                    u16::from_be_bytes([datagram_request_bytes[0], datagram_request_bytes[1]])
                         as usize;
 
-                if connect_request_bytes.len() < 2 + address_length {
+                if datagram_request_bytes.len() < 2 + address_length {
                     return Err(RequestError::AddressTooShort);
                 }
 
                 let address_start = 2;
                 let address_end = address_start + address_length;
-                let address_bytes = &connect_request_bytes[address_start..address_end];
+                let address_bytes = &datagram_request_bytes[address_start..address_end];
                 let remote_address = String::from_utf8_lossy(address_bytes).to_string();
 
-                // just a temporary reference to mid-slice for ease of use
-                let recipient_data_bytes = &connect_request_bytes[address_end..];
+                // unlike connect requests, the datagram carries data after the (optional)
+                // return address, so we need an explicit flag to know whether it's present
+                let Some(return_flag) = datagram_request_bytes.get(address_end) else {
+                    return Err(RequestError::DatagramReturnFlagMissing);
+                };
 
-                let return_address = if recipient_data_bytes.is_empty() {
-                    None
+                let remaining = &datagram_request_bytes[address_end + 1..];
+                let (return_address, data) = if *return_flag == 0 {
+                    (None, remaining)
                 } else {
-                    if recipient_data_bytes.len() != Recipient::LEN {
+                    if remaining.len() < Recipient::LEN {
                         return Err(RequestError::ReturnAddressTooShort);
                     }
 
                     let mut return_bytes = [0u8; Recipient::LEN];
-                    return_bytes.copy_from_slice(&recipient_data_bytes[..Recipient::LEN]);
-                    Some(
-                        Recipient::try_from_bytes(return_bytes)
-                            .map_err(RequestError::MalformedReturnAddress)?,
-                    )
+                    return_bytes.copy_from_slice(&remaining[..Recipient::LEN]);
+                    let return_address = Recipient::try_from_bytes(return_bytes)
+                        .map_err(RequestError::MalformedReturnAddress)?;
+                    (Some(return_address), &remaining[Recipient::LEN..])
                 };
 
-                Ok(Request::new_connect(
+                Ok(Request::new_datagram(
                     connection_id,
                     remote_address,
                     return_address,
+                    data.to_vec(),
                 ))
             }
-            RequestFlag::Send => {
-                let local_closed = b[9] != 0;
-                let data = b[10..].to_vec();
+            RequestFlag::ReturnAddressUpdate => {
+                let return_bytes = &b[9..];
+                if return_bytes.len() != Recipient::LEN {
+                    return Err(RequestError::ReturnAddressTooShort);
+                }
 
-                Ok(Request::Send(connection_id, data, local_closed))
+                let mut recipient_bytes = [0u8; Recipient::LEN];
+                recipient_bytes.copy_from_slice(return_bytes);
+                let return_address = Recipient::try_from_bytes(recipient_bytes)
+                    .map_err(RequestError::MalformedReturnAddress)?;
+
+                Ok(Request::new_return_address_update(
+                    connection_id,
+                    return_address,
+                ))
             }
         }
     }
@@ -202,10 +313,95 @@ impl Request {
                 .chain(std::iter::once(local_closed as u8))
                 .chain(data.into_iter())
                 .collect(),
+            // bind is: BIND_FLAG || CONN_ID || REMOTE_LEN || REMOTE || RETURN
+            Request::Bind(req) => {
+                let remote_address_bytes = req.remote_addr.into_bytes();
+                let remote_address_bytes_len = remote_address_bytes.len() as u16;
+
+                let iter = std::iter::once(RequestFlag::Bind as u8)
+                    .chain(req.conn_id.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes_len.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes.into_iter());
+
+                if let Some(return_address) = req.return_address {
+                    iter.chain(return_address.to_bytes().into_iter()).collect()
+                } else {
+                    iter.collect()
+                }
+            }
+            // datagram is: DGRAM_FLAG || CONN_ID || REMOTE_LEN || REMOTE || HAS_RETURN || [RETURN] || DATA
+            Request::Datagram(req) => {
+                let remote_address_bytes = req.remote_addr.into_bytes();
+                let remote_address_bytes_len = remote_address_bytes.len() as u16;
+
+                let iter = std::iter::once(RequestFlag::Datagram as u8)
+                    .chain(req.conn_id.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes_len.to_be_bytes().into_iter())
+                    .chain(remote_address_bytes.into_iter());
+
+                if let Some(return_address) = req.return_address {
+                    iter.chain(std::iter::once(1))
+                        .chain(return_address.to_bytes().into_iter())
+                        .chain(req.data.into_iter())
+                        .collect()
+                } else {
+                    iter.chain(std::iter::once(0))
+                        .chain(req.data.into_iter())
+                        .collect()
+                }
+            }
+            // return address update is: UPDATE_FLAG || CONN_ID || RETURN
+            Request::ReturnAddressUpdate(conn_id, return_address) => {
+                std::iter::once(RequestFlag::ReturnAddressUpdate as u8)
+                    .chain(conn_id.to_be_bytes().into_iter())
+                    .chain(return_address.to_bytes().into_iter())
+                    .collect()
+            }
         }
     }
 }
 
+/// Parses the `REMOTE_LEN || REMOTE || [RETURN]` part of the connect and bind requests.
+fn parse_remote_and_return_address(
+    request_bytes: &[u8],
+) -> Result<(RemoteAddress, Option<Recipient>), RequestError> {
+    // we need to be able to read at least 2 bytes that specify address length
+    if request_bytes.len() < 2 {
+        return Err(RequestError::AddressLengthTooShort);
+    }
+
+    let address_length = u16::from_be_bytes([request_bytes[0], request_bytes[1]]) as usize;
+
+    if request_bytes.len() < 2 + address_length {
+        return Err(RequestError::AddressTooShort);
+    }
+
+    let address_start = 2;
+    let address_end = address_start + address_length;
+    let address_bytes = &request_bytes[address_start..address_end];
+    let remote_address = String::from_utf8_lossy(address_bytes).to_string();
+
+    // just a temporary reference to mid-slice for ease of use
+    let recipient_data_bytes = &request_bytes[address_end..];
+
+    let return_address = if recipient_data_bytes.is_empty() {
+        None
+    } else {
+        if recipient_data_bytes.len() != Recipient::LEN {
+            return Err(RequestError::ReturnAddressTooShort);
+        }
+
+        let mut return_bytes = [0u8; Recipient::LEN];
+        return_bytes.copy_from_slice(&recipient_data_bytes[..Recipient::LEN]);
+        Some(
+            Recipient::try_from_bytes(return_bytes)
+                .map_err(RequestError::MalformedReturnAddress)?,
+        )
+    };
+
+    Ok((remote_address, return_address))
+}
+
 #[cfg(test)]
 mod request_deserialization_tests {
     use super::*;
@@ -488,4 +684,156 @@ mod request_deserialization_tests {
             }
         }
     }
+
+    #[cfg(test)]
+    mod sending_datagrams {
+        use super::*;
+
+        #[test]
+        fn returns_error_when_return_flag_is_missing() {
+            // "foo.com" remote address and correct 8 bytes of connection_id, but no return flag
+            let request_bytes = [
+                RequestFlag::Datagram as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                0,
+                7,
+                102,
+                111,
+                111,
+                46,
+                99,
+                111,
+                109,
+            ]
+            .to_vec();
+
+            match Request::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestError::DatagramReturnFlagMissing => {}
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn serde_roundtrip_without_return_address() {
+            let request = Request::new_datagram(42, "foo.com:53".to_string(), None, vec![1, 2, 3]);
+            let bytes = request.into_bytes();
+
+            match Request::try_from_bytes(&bytes).unwrap() {
+                Request::Datagram(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert_eq!("foo.com:53".to_string(), req.remote_addr);
+                    assert!(req.return_address.is_none());
+                    assert_eq!(vec![1, 2, 3], req.data);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn serde_roundtrip_with_return_address() {
+            let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+            let request = Request::new_datagram(
+                42,
+                "foo.com:53".to_string(),
+                Some(recipient),
+                vec![255, 255, 255],
+            );
+            let bytes = request.into_bytes();
+
+            match Request::try_from_bytes(&bytes).unwrap() {
+                Request::Datagram(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert_eq!("foo.com:53".to_string(), req.remote_addr);
+                    assert_eq!(
+                        req.return_address.unwrap().to_bytes().to_vec(),
+                        recipient.to_bytes().to_vec()
+                    );
+                    assert_eq!(vec![255, 255, 255], req.data);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod binding_a_socket {
+        use super::*;
+
+        #[test]
+        fn serde_roundtrip() {
+            let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+            let request = Request::new_bind(42, "foo.com:21".to_string(), Some(recipient));
+            let bytes = request.into_bytes();
+
+            match Request::try_from_bytes(&bytes).unwrap() {
+                Request::Bind(req) => {
+                    assert_eq!(42, req.conn_id);
+                    assert_eq!("foo.com:21".to_string(), req.remote_addr);
+                    assert_eq!(
+                        req.return_address.unwrap().to_bytes().to_vec(),
+                        recipient.to_bytes().to_vec()
+                    );
+                }
+                _ => unreachable!(),
+            }
+
+            let request = Request::new_bind(42, "foo.com:21".to_string(), None);
+            let bytes = request.into_bytes();
+            match Request::try_from_bytes(&bytes).unwrap() {
+                Request::Bind(req) => assert!(req.return_address.is_none()),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod updating_the_return_address {
+        use super::*;
+
+        #[test]
+        fn serde_roundtrip() {
+            let recipient = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+            let request = Request::new_return_address_update(42, recipient);
+            let bytes = request.into_bytes();
+
+            match Request::try_from_bytes(&bytes).unwrap() {
+                Request::ReturnAddressUpdate(conn_id, return_address) => {
+                    assert_eq!(42, conn_id);
+                    assert_eq!(
+                        return_address.to_bytes().to_vec(),
+                        recipient.to_bytes().to_vec()
+                    );
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        #[test]
+        fn returns_error_when_return_address_is_missing() {
+            let request_bytes = [
+                RequestFlag::ReturnAddressUpdate as u8,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+            ]
+            .to_vec();
+
+            match Request::try_from_bytes(&request_bytes).unwrap_err() {
+                RequestError::ReturnAddressTooShort => {}
+                _ => unreachable!(),
+            }
+        }
+    }
 }
@@ -3,7 +3,7 @@
 
 use thiserror::Error;
 
-use crate::ConnectionId;
+use crate::{ConnectionId, RemoteAddress};
 
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum ResponseError {
@@ -11,6 +11,12 @@ pub enum ResponseError {
     ConnectionIdTooShort,
     #[error("no data provided")]
     NoData,
+    #[error("not enough bytes to recover the length of the address")]
+    AddressLengthTooShort,
+    #[error("not enough bytes to recover the address")]
+    AddressTooShort,
+    #[error("unknown bind status {0}")]
+    UnknownBindStatus(u8),
 }
 /// A remote network response retrieved by the Socks5 service provider. This
 /// can be serialized and sent back through the mixnet to the requesting
@@ -71,6 +77,140 @@ impl Response {
     }
 }
 
+/// A UDP datagram received by the Socks5 service provider on behalf of a UDP association.
+/// Unlike [`Response`], it is not part of any ordered stream and it carries the address
+/// of the remote that has sent it, so that the requesting application could learn its origin.
+#[derive(Debug)]
+pub struct DatagramResponse {
+    pub connection_id: ConnectionId,
+    pub source_addr: RemoteAddress,
+    pub data: Vec<u8>,
+}
+
+impl DatagramResponse {
+    pub fn new(connection_id: ConnectionId, source_addr: RemoteAddress, data: Vec<u8>) -> Self {
+        DatagramResponse {
+            connection_id,
+            source_addr,
+            data,
+        }
+    }
+
+    pub fn try_from_bytes(b: &[u8]) -> Result<DatagramResponse, ResponseError> {
+        if b.is_empty() {
+            return Err(ResponseError::NoData);
+        }
+
+        if b.len() < 8 {
+            return Err(ResponseError::ConnectionIdTooShort);
+        }
+
+        let connection_id = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+
+        if b.len() < 10 {
+            return Err(ResponseError::AddressLengthTooShort);
+        }
+        let address_length = u16::from_be_bytes([b[8], b[9]]) as usize;
+        let address_start = 10;
+        let address_end = address_start + address_length;
+        if b.len() < address_end {
+            return Err(ResponseError::AddressTooShort);
+        }
+
+        let source_addr = String::from_utf8_lossy(&b[address_start..address_end]).to_string();
+        let data = b[address_end..].to_vec();
+
+        Ok(DatagramResponse::new(connection_id, source_addr, data))
+    }
+
+    // datagram response is: CONN_ID || SOURCE_LEN || SOURCE || DATA
+    pub fn into_bytes(self) -> Vec<u8> {
+        let source_address_bytes = self.source_addr.into_bytes();
+        let source_address_bytes_len = source_address_bytes.len() as u16;
+
+        self.connection_id
+            .to_be_bytes()
+            .into_iter()
+            .chain(source_address_bytes_len.to_be_bytes().into_iter())
+            .chain(source_address_bytes.into_iter())
+            .chain(self.data.into_iter())
+            .collect()
+    }
+}
+
+/// Progress of a `Bind` request made to the Socks5 service provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindStatus {
+    /// The listening socket has been bound to the specified address.
+    Listening(RemoteAddress),
+
+    /// An inbound connection from the specified address has been accepted.
+    Accepted(RemoteAddress),
+
+    /// The service provider failed to bind the socket or to accept the connection.
+    Failed(String),
+}
+
+impl BindStatus {
+    const LISTENING: u8 = 0;
+    const ACCEPTED: u8 = 1;
+    const FAILED: u8 = 2;
+}
+
+/// A response to the `Bind` request. Note that each request produces two responses:
+/// first one once the socket is bound, and the second one once the connection is accepted.
+#[derive(Debug)]
+pub struct BindResponse {
+    pub connection_id: ConnectionId,
+    pub status: BindStatus,
+}
+
+impl BindResponse {
+    pub fn new(connection_id: ConnectionId, status: BindStatus) -> Self {
+        BindResponse {
+            connection_id,
+            status,
+        }
+    }
+
+    pub fn try_from_bytes(b: &[u8]) -> Result<BindResponse, ResponseError> {
+        if b.is_empty() {
+            return Err(ResponseError::NoData);
+        }
+
+        if b.len() < 9 {
+            return Err(ResponseError::ConnectionIdTooShort);
+        }
+
+        let connection_id = u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]);
+        let content = String::from_utf8_lossy(&b[9..]).to_string();
+        let status = match b[8] {
+            BindStatus::LISTENING => BindStatus::Listening(content),
+            BindStatus::ACCEPTED => BindStatus::Accepted(content),
+            BindStatus::FAILED => BindStatus::Failed(content),
+            other => return Err(ResponseError::UnknownBindStatus(other)),
+        };
+
+        Ok(BindResponse::new(connection_id, status))
+    }
+
+    // bind response is: CONN_ID || STATUS || CONTENT
+    pub fn into_bytes(self) -> Vec<u8> {
+        let (status, content) = match self.status {
+            BindStatus::Listening(address) => (BindStatus::LISTENING, address),
+            BindStatus::Accepted(address) => (BindStatus::ACCEPTED, address),
+            BindStatus::Failed(reason) => (BindStatus::FAILED, reason),
+        };
+
+        self.connection_id
+            .to_be_bytes()
+            .into_iter()
+            .chain(std::iter::once(status))
+            .chain(content.into_bytes().into_iter())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod constructing_socks5_responses_from_bytes {
     use super::*;
@@ -122,3 +262,76 @@ mod constructing_socks5_responses_from_bytes {
         assert_eq!(expected.is_closed, actual.is_closed);
     }
 }
+
+#[cfg(test)]
+mod datagram_response_serde_tests {
+    use super::*;
+
+    #[test]
+    fn simple_serde() {
+        let response = DatagramResponse::new(42, "1.1.1.1:53".to_string(), vec![1, 2, 3]);
+        let bytes = response.into_bytes();
+        let deserialized = DatagramResponse::try_from_bytes(&bytes).unwrap();
+
+        assert_eq!(42, deserialized.connection_id);
+        assert_eq!("1.1.1.1:53".to_string(), deserialized.source_addr);
+        assert_eq!(vec![1, 2, 3], deserialized.data);
+    }
+
+    #[test]
+    fn deserialization_errors() {
+        assert_eq!(
+            ResponseError::NoData,
+            DatagramResponse::try_from_bytes(&[]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::ConnectionIdTooShort,
+            DatagramResponse::try_from_bytes(&[1, 2, 3]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::AddressLengthTooShort,
+            DatagramResponse::try_from_bytes(&[0, 0, 0, 0, 0, 0, 0, 42, 0]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::AddressTooShort,
+            DatagramResponse::try_from_bytes(&[0, 0, 0, 0, 0, 0, 0, 42, 0, 5, 49]).unwrap_err()
+        );
+    }
+}
+
+#[cfg(test)]
+mod bind_response_serde_tests {
+    use super::*;
+
+    #[test]
+    fn simple_serde() {
+        for status in [
+            BindStatus::Listening("1.2.3.4:5678".to_string()),
+            BindStatus::Accepted("5.6.7.8:21".to_string()),
+            BindStatus::Failed("no".to_string()),
+        ] {
+            let response = BindResponse::new(42, status.clone());
+            let bytes = response.into_bytes();
+            let deserialized = BindResponse::try_from_bytes(&bytes).unwrap();
+
+            assert_eq!(42, deserialized.connection_id);
+            assert_eq!(status, deserialized.status);
+        }
+    }
+
+    #[test]
+    fn deserialization_errors() {
+        assert_eq!(
+            ResponseError::NoData,
+            BindResponse::try_from_bytes(&[]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::ConnectionIdTooShort,
+            BindResponse::try_from_bytes(&[0, 0, 0, 0, 0, 0, 0, 42]).unwrap_err()
+        );
+        assert_eq!(
+            ResponseError::UnknownBindStatus(42),
+            BindResponse::try_from_bytes(&[0, 0, 0, 0, 0, 0, 0, 42, 42]).unwrap_err()
+        );
+    }
+}
@@ -30,6 +30,7 @@ impl StatsMessage {
 pub enum StatsData {
     Service(StatsServiceData),
     Gateway(StatsGatewayData),
+    AllowedHostsReload(StatsAllowedHostsReloadData),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -47,6 +48,23 @@ impl StatsGatewayData {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatsAllowedHostsReloadData {
+    pub reason: String,
+    pub added_rules: Vec<String>,
+    pub removed_rules: Vec<String>,
+}
+
+impl StatsAllowedHostsReloadData {
+    pub fn new(reason: String, added_rules: Vec<String>, removed_rules: Vec<String>) -> Self {
+        StatsAllowedHostsReloadData {
+            reason,
+            added_rules,
+            removed_rules,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StatsServiceData {
     pub requested_service: String,
@@ -1,10 +1,12 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::sphinx_key::KeyEpoch;
 use crate::{filter, NetworkAddress};
 use crypto::asymmetric::{encryption, identity};
 use mixnet_contract_common::GatewayBond;
 use nymsphinx_addressing::nodes::{NodeIdentity, NymNodeRoutingAddress};
+use nymsphinx_params::SphinxKeyRotation;
 use nymsphinx_types::Node as SphinxNode;
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
@@ -42,6 +44,9 @@ pub struct Node {
     pub clients_port: u16,
     pub identity_key: identity::PublicKey,
     pub sphinx_key: encryption::PublicKey, // TODO: or nymsphinx::PublicKey? both are x25519
+    /// Epoch of the currently used sphinx key. `None` implies the key the node has bonded with.
+    /// Gateways do not announce any rotated keys, so for now it's always the bonded key.
+    pub sphinx_key_epoch: Option<KeyEpoch>,
     pub version: String,
 }
 
@@ -76,6 +81,9 @@ impl<'a> From<&'a Node> for SphinxNode {
         let node_address_bytes = NymNodeRoutingAddress::from(node.mix_host)
             .try_into()
             .unwrap();
+        // let the node know which of its keys we're using
+        let node_address_bytes = SphinxKeyRotation::from_key_epoch(node.sphinx_key_epoch)
+            .attach_to_node_address(node_address_bytes);
 
         SphinxNode::new(node_address_bytes, (&node.sphinx_key).into())
     }
@@ -112,6 +120,7 @@ impl<'a> TryFrom<&'a GatewayBond> for Node {
             clients_port: bond.gateway.clients_port,
             identity_key: identity::PublicKey::from_base58_string(&bond.gateway.identity_key)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&bond.gateway.sphinx_key)?,
+            sphinx_key_epoch: None,
             version: bond.gateway.version.clone(),
         })
     }
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::filter::VersionFilterable;
+use crate::route_selection::RouteSelectionPolicy;
 use log::warn;
 use mixnet_contract_common::mixnode::MixNodeDetails;
-use mixnet_contract_common::GatewayBond;
+use mixnet_contract_common::{GatewayBond, MixNodeBond};
 use nymsphinx_addressing::nodes::NodeIdentity;
 use nymsphinx_types::Node as SphinxNode;
 use rand::Rng;
@@ -19,6 +20,8 @@ use thiserror::Error;
 pub mod filter;
 pub mod gateway;
 pub mod mix;
+pub mod route_selection;
+pub mod sphinx_key;
 
 #[derive(Debug, Clone, Error)]
 pub enum NymTopologyError {
@@ -39,6 +42,9 @@ pub enum NymTopologyError {
 
     #[error("No mixnodes available on layer {layer}")]
     EmptyMixLayer { layer: MixLayer },
+
+    #[error("No route can be constructed under the current route selection policy, as none of the mixnodes on layer {layer} could be used")]
+    NoUsableMixnodes { layer: MixLayer },
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +62,14 @@ impl NetworkAddress {
             }
         }
     }
+
+    /// Base url of a plain http API exposed on the specified port.
+    pub fn http_url(&self, port: u16) -> String {
+        match self {
+            NetworkAddress::IpAddr(addr) => format!("http://{}", SocketAddr::new(*addr, port)),
+            NetworkAddress::Hostname(hostname) => format!("http://{hostname}:{port}"),
+        }
+    }
 }
 
 impl FromStr for NetworkAddress {
@@ -85,17 +99,43 @@ pub type MixLayer = u8;
 pub struct NymTopology {
     mixes: HashMap<MixLayer, Vec<mix::Node>>,
     gateways: Vec<gateway::Node>,
+    route_selection_policy: RouteSelectionPolicy,
 }
 
 impl NymTopology {
     pub fn new(mixes: HashMap<MixLayer, Vec<mix::Node>>, gateways: Vec<gateway::Node>) -> Self {
-        NymTopology { mixes, gateways }
+        NymTopology {
+            mixes,
+            gateways,
+            route_selection_policy: Default::default(),
+        }
+    }
+
+    /// Specifies the rules for choosing the mixnodes of all the routes constructed out of
+    /// this topology.
+    #[must_use]
+    pub fn with_route_selection_policy(
+        mut self,
+        route_selection_policy: RouteSelectionPolicy,
+    ) -> Self {
+        self.route_selection_policy = route_selection_policy;
+        self
+    }
+
+    pub fn route_selection_policy(&self) -> &RouteSelectionPolicy {
+        &self.route_selection_policy
     }
 
     pub fn mixes(&self) -> &HashMap<MixLayer, Vec<mix::Node>> {
         &self.mixes
     }
 
+    /// Allows updating the mixnodes in place, such as for attaching the information used by the
+    /// route selection, like their family or performance.
+    pub fn mixnodes_mut(&mut self) -> impl Iterator<Item = &mut mix::Node> {
+        self.mixes.values_mut().flat_map(|layer| layer.iter_mut())
+    }
+
     pub fn num_mixnodes(&self) -> usize {
         self.mixes.values().flat_map(|m| m.iter()).count()
     }
@@ -133,25 +173,23 @@ impl NymTopology {
     }
 
     /// Returns a vec of size of `num_mix_hops` of mixnodes, such that each subsequent node is on
-    /// next layer, starting from layer 1
-    pub fn random_mix_route<R>(
+    /// next layer, starting from layer 1. The nodes are chosen according to the route selection
+    /// policy of the topology.
+    pub(crate) fn random_mix_nodes<R>(
         &self,
         rng: &mut R,
         num_mix_hops: u8,
-    ) -> Result<Vec<SphinxNode>, NymTopologyError>
+    ) -> Result<Vec<&mix::Node>, NymTopologyError>
     where
-        // I don't think there's a need for this RNG to be crypto-secure
         R: Rng + ?Sized,
     {
-        use rand::seq::SliceRandom;
-
         if self.mixes.len() < num_mix_hops as usize {
             return Err(NymTopologyError::InvalidNumberOfHopsError {
                 available: self.mixes.len(),
                 requested: num_mix_hops as usize,
             });
         }
-        let mut route = Vec::with_capacity(num_mix_hops as usize);
+        let mut layers = Vec::with_capacity(num_mix_hops as usize);
 
         // there is no "layer 0"
         for layer in 1..=num_mix_hops {
@@ -160,16 +198,36 @@ impl NymTopology {
                 .mixes
                 .get(&layer)
                 .ok_or(NymTopologyError::EmptyMixLayer { layer })?;
-
-            // choose a random mix from the above list
-            // this can return a 'None' only if slice is empty
-            let random_mix = layer_mixes
-                .choose(rng)
-                .ok_or(NymTopologyError::EmptyMixLayer { layer })?;
-            route.push(random_mix.into());
+            if layer_mixes.is_empty() {
+                return Err(NymTopologyError::EmptyMixLayer { layer });
+            }
+            layers.push(layer_mixes.as_slice());
         }
 
-        Ok(route)
+        // choose a mix out of each of the layers, such that the route is allowed by the policy
+        self.route_selection_policy
+            .choose_route(rng, &layers)
+            .map_err(|index| NymTopologyError::NoUsableMixnodes {
+                layer: index as MixLayer + 1,
+            })
+    }
+
+    /// Returns a vec of size of `num_mix_hops` of mixnodes, such that each subsequent node is on
+    /// next layer, starting from layer 1
+    pub fn random_mix_route<R>(
+        &self,
+        rng: &mut R,
+        num_mix_hops: u8,
+    ) -> Result<Vec<SphinxNode>, NymTopologyError>
+    where
+        // I don't think there's a need for this RNG to be crypto-secure
+        R: Rng + ?Sized,
+    {
+        Ok(self
+            .random_mix_nodes(rng, num_mix_hops)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// Tries to create a route to the specified gateway, such that it goes through mixnode on layer 1,
@@ -248,6 +306,7 @@ impl NymTopology {
         NymTopology {
             mixes: self.mixes.filter_by_version(expected_mix_version),
             gateways: self.gateways.clone(),
+            route_selection_policy: self.route_selection_policy.clone(),
         }
     }
 }
@@ -255,12 +314,24 @@ impl NymTopology {
 pub fn nym_topology_from_detailed(
     mix_details: Vec<MixNodeDetails>,
     gateway_bonds: Vec<GatewayBond>,
+) -> NymTopology {
+    nym_topology_from_bonds(
+        mix_details
+            .into_iter()
+            .map(|details| details.bond_information)
+            .collect(),
+        gateway_bonds,
+    )
+}
+
+/// Constructs the topology out of the provided bonds, skipping any malformed nodes and mixnodes
+/// that are not assigned to a valid layer.
+pub fn nym_topology_from_bonds(
+    mix_bonds: Vec<MixNodeBond>,
+    gateway_bonds: Vec<GatewayBond>,
 ) -> NymTopology {
     let mut mixes = HashMap::new();
-    for bond in mix_details
-        .into_iter()
-        .map(|details| details.bond_information)
-    {
+    for bond in mix_bonds.into_iter() {
         let layer = bond.layer as MixLayer;
         if layer == 0 || layer > 3 {
             warn!(
@@ -315,6 +386,7 @@ mod converting_mixes_to_vec {
                 owner: "N/A".to_string(),
                 host: "3.3.3.3".parse().unwrap(),
                 mix_host: "3.3.3.3:1789".parse().unwrap(),
+                http_api_port: 8000,
                 identity_key: identity::PublicKey::from_base58_string(
                     "3ebjp1Fb9hdcS1AR6AZihgeJiMHkB5jjJUsvqNnfQwU7",
                 )
@@ -323,8 +395,11 @@ mod converting_mixes_to_vec {
                     "C7cown6dYCLZpLiMFC1PaBmhvLvmJmLDJGeRTbPD45bX",
                 )
                 .unwrap(),
+                sphinx_key_epoch: None,
                 layer: Layer::One,
                 version: "0.x.0".to_string(),
+                family: None,
+                performance: None,
             };
 
             let node2 = mix::Node {
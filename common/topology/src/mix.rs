@@ -1,10 +1,14 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::sphinx_key::{KeyEpoch, SignedSphinxKey, SphinxKeyError};
 use crate::{filter, NetworkAddress};
 use crypto::asymmetric::{encryption, identity};
+use mixnet_contract_common::families::FamilyHead;
+use mixnet_contract_common::reward_params::Performance;
 use mixnet_contract_common::{Layer, MixId, MixNodeBond};
 use nymsphinx_addressing::nodes::NymNodeRoutingAddress;
+use nymsphinx_params::SphinxKeyRotation;
 use nymsphinx_types::Node as SphinxNode;
 use std::convert::{TryFrom, TryInto};
 use std::io;
@@ -35,10 +39,53 @@ pub struct Node {
     // we're keeping this as separate resolved field since we do not want to be resolving the potential
     // hostname every time we want to construct a path via this node
     pub mix_host: SocketAddr,
+    pub http_api_port: u16,
     pub identity_key: identity::PublicKey,
     pub sphinx_key: encryption::PublicKey, // TODO: or nymsphinx::PublicKey? both are x25519
+    /// Epoch of the currently used sphinx key. `None` implies the key the node has bonded with.
+    pub sphinx_key_epoch: Option<KeyEpoch>,
     pub layer: Layer,
     pub version: String,
+    /// Family the node belongs to, if any. Used for diversifying the routes.
+    pub family: Option<FamilyHead>,
+    /// Performance of the node as reported by the nym-api. `None` implies it's unknown.
+    pub performance: Option<Performance>,
+}
+
+impl Node {
+    /// Replaces the sphinx key of this node with the provided key signed by its identity,
+    /// assuming it's valid at the given time and is not older than the currently used one.
+    pub fn update_sphinx_key(
+        &mut self,
+        signed_key: &SignedSphinxKey,
+        unix_timestamp: u64,
+    ) -> Result<(), SphinxKeyError> {
+        self.sphinx_key =
+            signed_key.verify_update(&self.identity_key, self.sphinx_key_epoch, unix_timestamp)?;
+        self.sphinx_key_epoch = Some(signed_key.epoch);
+        Ok(())
+    }
+
+    /// Switches to the most recent of the keys announced by this node that is valid at the given time.
+    /// Returns whether the used key has changed.
+    pub fn apply_announced_sphinx_keys(
+        &mut self,
+        announced: &[SignedSphinxKey],
+        unix_timestamp: u64,
+    ) -> Result<bool, SphinxKeyError> {
+        let latest = announced
+            .iter()
+            .filter(|key| key.is_valid_at(unix_timestamp))
+            .max_by_key(|key| key.epoch);
+
+        match latest {
+            Some(latest) if self.sphinx_key_epoch != Some(latest.epoch) => {
+                self.update_sphinx_key(latest, unix_timestamp)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 impl filter::Versioned for Node {
@@ -52,6 +99,9 @@ impl<'a> From<&'a Node> for SphinxNode {
         let node_address_bytes = NymNodeRoutingAddress::from(node.mix_host)
             .try_into()
             .unwrap();
+        // let the node know which of its keys we're using
+        let node_address_bytes = SphinxKeyRotation::from_key_epoch(node.sphinx_key_epoch)
+            .attach_to_node_address(node_address_bytes);
 
         SphinxNode::new(node_address_bytes, (&node.sphinx_key).into())
     }
@@ -84,10 +134,14 @@ impl<'a> TryFrom<&'a MixNodeBond> for Node {
             owner: bond.owner.as_str().to_owned(),
             host,
             mix_host,
+            http_api_port: bond.mix_node.http_api_port,
             identity_key: identity::PublicKey::from_base58_string(&bond.mix_node.identity_key)?,
             sphinx_key: encryption::PublicKey::from_base58_string(&bond.mix_node.sphinx_key)?,
+            sphinx_key_epoch: None,
             layer: bond.layer,
             version: bond.mix_node.version.clone(),
+            family: None,
+            performance: None,
         })
     }
 }
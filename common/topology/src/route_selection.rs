@@ -0,0 +1,541 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::mix;
+use mixnet_contract_common::families::FamilyHead;
+use nymsphinx_addressing::nodes::NodeIdentity;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::net::IpAddr;
+
+// Weight given to the nodes with (close to) zero performance, so that a route could still be
+// constructed if the whole layer is performing poorly.
+const MIN_PERFORMANCE_WEIGHT: f64 = 0.01;
+
+/// Rules for choosing the mixnodes of the routes constructed out of the topology.
+///
+/// The default policy chooses a mixnode uniformly at random on each layer.
+///
+/// Note that the topology doesn't know about the autonomous systems or the locations of the
+/// nodes, so the closest thing to ASN diversity is the `network_diversity`, while there's
+/// currently no way of diversifying the routes geographically.
+#[derive(Debug, Clone, Default)]
+pub struct RouteSelectionPolicy {
+    /// Mixnodes that are never going to be used in any route.
+    pub denied_nodes: Vec<NodeIdentity>,
+
+    /// If enabled, a single route never goes through multiple mixnodes of the same family.
+    pub family_diversity: bool,
+
+    /// If enabled, a single route never goes through multiple mixnodes within the same network,
+    /// i.e. sharing the /16 prefix of their IPv4 (or the /32 prefix of their IPv6) address.
+    /// The nodes within the same network are likely to be run by the same hosting provider.
+    pub network_diversity: bool,
+
+    /// If enabled, mixnodes are chosen with probability proportional to their performance rather
+    /// than uniformly. Nodes of unknown performance are treated as if they were fully performant.
+    pub performance_weighting: bool,
+}
+
+impl RouteSelectionPolicy {
+    fn is_uniform(&self) -> bool {
+        self.denied_nodes.is_empty()
+            && !self.family_diversity
+            && !self.network_diversity
+            && !self.performance_weighting
+    }
+
+    /// Whether the policy makes use of the families and the performance of the nodes, which
+    /// are only available from the detailed nym-api endpoints.
+    pub fn needs_node_annotations(&self) -> bool {
+        self.family_diversity || self.performance_weighting
+    }
+
+    fn is_denied(&self, node: &mix::Node) -> bool {
+        self.denied_nodes.contains(&node.identity_key)
+    }
+
+    fn shares_family(node: &mix::Node, route: &[&mix::Node]) -> bool {
+        match &node.family {
+            None => false,
+            Some(family) => route
+                .iter()
+                .any(|other| other.family.as_ref() == Some(family)),
+        }
+    }
+
+    fn network_prefix(node: &mix::Node) -> Vec<u8> {
+        match node.mix_host.ip() {
+            IpAddr::V4(ip) => ip.octets()[..2].to_vec(),
+            IpAddr::V6(ip) => ip.octets()[..4].to_vec(),
+        }
+    }
+
+    fn shares_network(node: &mix::Node, route: &[&mix::Node]) -> bool {
+        let prefix = Self::network_prefix(node);
+        route
+            .iter()
+            .any(|other| Self::network_prefix(other) == prefix)
+    }
+
+    // The properties of the node that restrict the choice of the other nodes of the same route.
+    fn restrictions<'a>(&self, node: &'a mix::Node) -> (Option<&'a FamilyHead>, Option<Vec<u8>>) {
+        let family = node.family.as_ref().filter(|_| self.family_diversity);
+        let network = self.network_diversity.then(|| Self::network_prefix(node));
+        (family, network)
+    }
+
+    fn can_extend_route(&self, node: &mix::Node, route: &[&mix::Node]) -> bool {
+        if self.is_denied(node) {
+            return false;
+        }
+        if self.family_diversity && Self::shares_family(node, route) {
+            return false;
+        }
+        !(self.network_diversity && Self::shares_network(node, route))
+    }
+
+    fn node_weight(node: &mix::Node) -> f64 {
+        match node.performance {
+            None => 1.0,
+            Some(performance) => {
+                (performance.round_to_integer() as f64 / 100.0).max(MIN_PERFORMANCE_WEIGHT)
+            }
+        }
+    }
+
+    fn choose_candidate<R>(&self, rng: &mut R, candidates: &[&mix::Node]) -> Option<usize>
+    where
+        R: Rng + ?Sized,
+    {
+        if candidates.is_empty() {
+            return None;
+        }
+        if self.performance_weighting {
+            WeightedIndex::new(candidates.iter().map(|node| Self::node_weight(node)))
+                .ok()
+                .map(|distribution| distribution.sample(rng))
+        } else {
+            Some(rng.gen_range(0, candidates.len()))
+        }
+    }
+
+    // Extends the route with a node of each of the remaining layers. If none of the nodes of some
+    // layer can be used with the nodes chosen so far, a different node is tried on the preceding
+    // layer, skipping the ones that would restrict the rest of the route in the very same way.
+    // On failure, returns the index of the deepest layer that could not be satisfied.
+    fn extend_route<'a, R>(
+        &self,
+        rng: &mut R,
+        layers: &[&'a [mix::Node]],
+        route: &mut Vec<&'a mix::Node>,
+    ) -> Result<(), usize>
+    where
+        R: Rng + ?Sized,
+    {
+        let Some(layer_mixes) = layers.get(route.len()) else {
+            return Ok(());
+        };
+
+        let mut candidates = layer_mixes
+            .iter()
+            .filter(|node| self.can_extend_route(node, route))
+            .collect::<Vec<_>>();
+
+        let mut failed_layer = route.len();
+        while let Some(index) = self.choose_candidate(rng, &candidates) {
+            let node = candidates.swap_remove(index);
+            route.push(node);
+            match self.extend_route(rng, layers, route) {
+                Ok(()) => return Ok(()),
+                Err(layer) => {
+                    failed_layer = failed_layer.max(layer);
+                    route.pop();
+                    let restrictions = self.restrictions(node);
+                    candidates.retain(|other| self.restrictions(other) != restrictions);
+                }
+            }
+        }
+        Err(failed_layer)
+    }
+
+    /// Chooses a mixnode out of each of the provided layers, such that the whole route is allowed
+    /// by the policy. A choice that leaves no usable nodes on any of the subsequent layers is
+    /// reconsidered, so a route is found whenever one exists. On failure, returns the index of
+    /// the layer whose nodes could not be used.
+    pub(crate) fn choose_route<'a, R>(
+        &self,
+        rng: &mut R,
+        layers: &[&'a [mix::Node]],
+    ) -> Result<Vec<&'a mix::Node>, usize>
+    where
+        R: Rng + ?Sized,
+    {
+        // don't bother allocating anything if there's nothing to consider
+        if self.is_uniform() {
+            return layers
+                .iter()
+                .enumerate()
+                .map(|(index, layer_mixes)| layer_mixes.choose(rng).ok_or(index))
+                .collect();
+        }
+
+        // fail fast, without exploring all the possible routes, if some layer is entirely denied
+        if let Some(index) = layers
+            .iter()
+            .position(|layer_mixes| layer_mixes.iter().all(|node| self.is_denied(node)))
+        {
+            return Err(index);
+        }
+
+        let mut route = Vec::with_capacity(layers.len());
+        self.extend_route(rng, layers, &mut route)?;
+        Ok(route)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MixLayer, NymTopology, NymTopologyError};
+    use crypto::asymmetric::{encryption, identity};
+    use mixnet_contract_common::families::FamilyHead;
+    use mixnet_contract_common::reward_params::Performance;
+    use mixnet_contract_common::Layer;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    const ROUTES: usize = 30_000;
+
+    fn mix_node(rng: &mut StdRng, mix_id: u32, layer: Layer) -> mix::Node {
+        mix::Node {
+            mix_id,
+            owner: format!("owner{mix_id}"),
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            http_api_port: 8000,
+            identity_key: *identity::KeyPair::new(rng).public_key(),
+            sphinx_key: *encryption::KeyPair::new(rng).public_key(),
+            sphinx_key_epoch: None,
+            layer,
+            version: "1.1.0".to_string(),
+            family: None,
+            performance: None,
+        }
+    }
+
+    // 3 layers with `per_layer` nodes each, with consecutive ids starting from 1 on layer 1
+    fn topology_fixture(rng: &mut StdRng, per_layer: u32) -> NymTopology {
+        let mut mixes = HashMap::new();
+        for (i, layer) in [Layer::One, Layer::Two, Layer::Three]
+            .into_iter()
+            .enumerate()
+        {
+            let nodes = (0..per_layer)
+                .map(|j| mix_node(rng, i as u32 * per_layer + j + 1, layer))
+                .collect();
+            mixes.insert(i as MixLayer + 1, nodes);
+        }
+        NymTopology::new(mixes, Vec::new())
+    }
+
+    fn update_node(topology: &mut NymTopology, mix_id: u32, f: impl FnOnce(&mut mix::Node)) {
+        f(topology
+            .mixnodes_mut()
+            .find(|node| node.mix_id == mix_id)
+            .unwrap())
+    }
+
+    fn identity_of(topology: &NymTopology, mix_id: u32) -> NodeIdentity {
+        topology
+            .mixes_as_vec()
+            .into_iter()
+            .find(|node| node.mix_id == mix_id)
+            .unwrap()
+            .identity_key
+    }
+
+    // counts how many times each node got chosen
+    fn route_counts(topology: &NymTopology, rng: &mut StdRng) -> HashMap<u32, usize> {
+        let mut counts = HashMap::new();
+        for _ in 0..ROUTES {
+            for node in topology.random_mix_nodes(rng, 3).unwrap() {
+                *counts.entry(node.mix_id).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    fn assert_close(actual: usize, expected: f64) {
+        // the tolerance is way wider than the standard deviation of any of the distributions
+        // in here, so the (seeded) tests wouldn't get flaky after changes to the rng usage
+        let tolerance = expected * 0.1;
+        assert!(
+            (actual as f64 - expected).abs() < tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn default_policy_chooses_nodes_uniformly() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let topology = topology_fixture(&mut rng, 4);
+
+        let counts = route_counts(&topology, &mut rng);
+        assert_eq!(counts.len(), 12);
+        for count in counts.values() {
+            assert_close(*count, ROUTES as f64 / 4.0);
+        }
+    }
+
+    #[test]
+    fn denied_nodes_are_never_chosen() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let topology = topology_fixture(&mut rng, 4);
+        let denied = vec![identity_of(&topology, 1), identity_of(&topology, 6)];
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            denied_nodes: denied,
+            ..Default::default()
+        });
+
+        let counts = route_counts(&topology, &mut rng);
+        assert!(!counts.contains_key(&1));
+        assert!(!counts.contains_key(&6));
+
+        // the remaining nodes on the affected layers take over the traffic
+        for mix_id in [2, 3, 4, 5, 7, 8] {
+            assert_close(counts[&mix_id], ROUTES as f64 / 3.0);
+        }
+        for mix_id in 9..=12 {
+            assert_close(counts[&mix_id], ROUTES as f64 / 4.0);
+        }
+    }
+
+    #[test]
+    fn denying_entire_layer_makes_routing_impossible() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let topology = topology_fixture(&mut rng, 2);
+        let denied = vec![identity_of(&topology, 3), identity_of(&topology, 4)];
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            denied_nodes: denied,
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            topology.random_mix_route(&mut rng, 3),
+            Err(NymTopologyError::NoUsableMixnodes { layer: 2 })
+        ));
+    }
+
+    #[test]
+    fn routes_never_contain_two_nodes_of_the_same_family() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 3);
+
+        // nodes 1, 4 and 7 are all of the same family, so are nodes 2 and 5
+        let family_a = FamilyHead::new("family-a");
+        let family_b = FamilyHead::new("family-b");
+        for mix_id in [1, 4, 7] {
+            update_node(&mut topology, mix_id, |node| {
+                node.family = Some(family_a.clone())
+            });
+        }
+        for mix_id in [2, 5] {
+            update_node(&mut topology, mix_id, |node| {
+                node.family = Some(family_b.clone())
+            });
+        }
+
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            family_diversity: true,
+            ..Default::default()
+        });
+
+        for _ in 0..ROUTES {
+            let route = topology.random_mix_nodes(&mut rng, 3).unwrap();
+            let in_family_a = route.iter().filter(|n| [1, 4, 7].contains(&n.mix_id));
+            let in_family_b = route.iter().filter(|n| [2, 5].contains(&n.mix_id));
+            assert!(in_family_a.count() <= 1);
+            assert!(in_family_b.count() <= 1);
+        }
+    }
+
+    #[test]
+    fn family_diversity_does_not_affect_first_layer() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 2);
+        let family = FamilyHead::new("family");
+        for mix_id in [1, 3, 5] {
+            update_node(&mut topology, mix_id, |node| {
+                node.family = Some(family.clone())
+            });
+        }
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            family_diversity: true,
+            ..Default::default()
+        });
+
+        // the first layer is chosen without any constraints, but once node 1 is picked,
+        // the route has to continue via 4 and 6
+        let counts = route_counts(&topology, &mut rng);
+        assert_close(counts[&1], ROUTES as f64 / 2.0);
+        assert_close(counts[&2], ROUTES as f64 / 2.0);
+        assert_close(counts[&4], ROUTES as f64 * 0.75);
+        assert_close(counts[&3], ROUTES as f64 * 0.25);
+    }
+
+    #[test]
+    fn family_diversity_reconsiders_choices_that_lead_nowhere() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 2);
+
+        // the entire last layer is of the same family as node 1, so it can never be used
+        let family = FamilyHead::new("family");
+        for mix_id in [1, 5, 6] {
+            update_node(&mut topology, mix_id, |node| {
+                node.family = Some(family.clone())
+            });
+        }
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            family_diversity: true,
+            ..Default::default()
+        });
+
+        let counts = route_counts(&topology, &mut rng);
+        assert!(!counts.contains_key(&1));
+        assert_eq!(counts[&2], ROUTES);
+        assert_close(counts[&3], ROUTES as f64 / 2.0);
+        assert_close(counts[&4], ROUTES as f64 / 2.0);
+    }
+
+    #[test]
+    fn family_diversity_fails_if_no_route_exists() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 2);
+
+        let family = FamilyHead::new("family");
+        for mix_id in [1, 2, 5, 6] {
+            update_node(&mut topology, mix_id, |node| {
+                node.family = Some(family.clone())
+            });
+        }
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            family_diversity: true,
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            topology.random_mix_route(&mut rng, 3),
+            Err(NymTopologyError::NoUsableMixnodes { layer: 3 })
+        ));
+    }
+
+    #[test]
+    fn routes_never_contain_two_nodes_of_the_same_network() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 2);
+
+        // nodes 1, 3 and 5 are all within 10.1.0.0/16
+        let hosts = [
+            (1, "10.1.0.1"),
+            (2, "10.2.0.1"),
+            (3, "10.1.0.2"),
+            (4, "10.3.0.1"),
+            (5, "10.1.200.1"),
+            (6, "10.4.0.1"),
+        ];
+        for (mix_id, host) in hosts {
+            update_node(&mut topology, mix_id, |node| {
+                node.mix_host = format!("{host}:1789").parse().unwrap()
+            });
+        }
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            network_diversity: true,
+            ..Default::default()
+        });
+
+        let counts = route_counts(&topology, &mut rng);
+        assert_eq!(counts.len(), 6);
+        for _ in 0..ROUTES {
+            let route = topology.random_mix_nodes(&mut rng, 3).unwrap();
+            let in_network = route.iter().filter(|n| [1, 3, 5].contains(&n.mix_id));
+            assert!(in_network.count() <= 1);
+        }
+    }
+
+    #[test]
+    fn node_annotations_are_only_needed_for_families_and_performance() {
+        assert!(!RouteSelectionPolicy::default().needs_node_annotations());
+        assert!(
+            !RouteSelectionPolicy {
+                denied_nodes: vec![
+                    *identity::KeyPair::new(&mut StdRng::seed_from_u64(42)).public_key()
+                ],
+                network_diversity: true,
+                ..Default::default()
+            }
+            .needs_node_annotations()
+        );
+        assert!(RouteSelectionPolicy {
+            family_diversity: true,
+            ..Default::default()
+        }
+        .needs_node_annotations());
+        assert!(RouteSelectionPolicy {
+            performance_weighting: true,
+            ..Default::default()
+        }
+        .needs_node_annotations());
+    }
+
+    #[test]
+    fn nodes_are_weighted_by_performance() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 3);
+
+        // layer 1 nodes perform at 10%, 30% and 60%, while on layer 2 one node is never
+        // performant and the other two are of unknown performance
+        let performances = [(1, 10), (2, 30), (3, 60), (4, 0)];
+        for (mix_id, performance) in performances {
+            update_node(&mut topology, mix_id, |node| {
+                node.performance = Some(Performance::from_percentage_value(performance).unwrap())
+            });
+        }
+
+        let topology = topology.with_route_selection_policy(RouteSelectionPolicy {
+            performance_weighting: true,
+            ..Default::default()
+        });
+
+        let counts = route_counts(&topology, &mut rng);
+        assert_close(counts[&1], ROUTES as f64 * 0.1);
+        assert_close(counts[&2], ROUTES as f64 * 0.3);
+        assert_close(counts[&3], ROUTES as f64 * 0.6);
+
+        // the weight of a non-performing node is not zero, but it's very rarely used
+        let weight_sum = 2.0 + MIN_PERFORMANCE_WEIGHT;
+        assert!(counts.get(&4).copied().unwrap_or_default() < ROUTES / 50);
+        assert_close(counts[&5], ROUTES as f64 / weight_sum);
+        assert_close(counts[&6], ROUTES as f64 / weight_sum);
+    }
+
+    #[test]
+    fn default_policy_ignores_families_and_performance() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut topology = topology_fixture(&mut rng, 2);
+        let family = FamilyHead::new("family");
+        for mix_id in [1, 3, 5] {
+            update_node(&mut topology, mix_id, |node| {
+                node.family = Some(family.clone());
+                node.performance = Some(Performance::zero());
+            });
+        }
+
+        let counts = route_counts(&topology, &mut rng);
+        for count in counts.values() {
+            assert_close(*count, ROUTES as f64 / 2.0);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::asymmetric::{encryption, identity};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Sequential number of the period during which a particular sphinx key is used by a node.
+pub type KeyEpoch = u32;
+
+#[derive(Error, Debug)]
+pub enum SphinxKeyError {
+    #[error("the sphinx key was malformed - {0}")]
+    MalformedSphinxKey(#[from] encryption::KeyRecoveryError),
+
+    #[error("the signature on the sphinx key was malformed - {0}")]
+    MalformedSignature(#[from] identity::Ed25519RecoveryError),
+
+    #[error("the signature on the sphinx key for epoch {epoch} is invalid")]
+    InvalidSignature { epoch: KeyEpoch },
+
+    #[error("the sphinx key for epoch {epoch} is not valid at {timestamp}")]
+    NotValidAt { epoch: KeyEpoch, timestamp: u64 },
+
+    #[error("the sphinx key for epoch {received} is older than the currently used one (epoch {current})")]
+    OutdatedEpoch {
+        current: KeyEpoch,
+        received: KeyEpoch,
+    },
+}
+
+/// Sphinx key of a node that's valid for a particular key epoch,
+/// signed with the identity key of that node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SignedSphinxKey {
+    pub epoch: KeyEpoch,
+
+    /// Unix timestamp since which the key can be used for constructing packets.
+    pub valid_from: u64,
+
+    /// Unix timestamp after which the node is no longer going to accept packets
+    /// constructed with this key.
+    pub valid_until: u64,
+
+    /// Base58-encoded x25519 public key.
+    pub sphinx_key: String,
+
+    /// Base58-encoded ed25519 signature on the above data.
+    pub signature: String,
+}
+
+impl SignedSphinxKey {
+    // EPOCH || VALID_FROM || VALID_UNTIL || SPHINX_KEY
+    fn signed_plaintext(
+        epoch: KeyEpoch,
+        valid_from: u64,
+        valid_until: u64,
+        sphinx_key: &encryption::PublicKey,
+    ) -> Vec<u8> {
+        epoch
+            .to_be_bytes()
+            .into_iter()
+            .chain(valid_from.to_be_bytes().into_iter())
+            .chain(valid_until.to_be_bytes().into_iter())
+            .chain(sphinx_key.to_bytes().into_iter())
+            .collect()
+    }
+
+    pub fn new(
+        epoch: KeyEpoch,
+        valid_from: u64,
+        valid_until: u64,
+        sphinx_key: &encryption::PublicKey,
+        identity_key: &identity::PrivateKey,
+    ) -> Self {
+        let plaintext = Self::signed_plaintext(epoch, valid_from, valid_until, sphinx_key);
+        let signature = identity_key.sign(&plaintext);
+
+        SignedSphinxKey {
+            epoch,
+            valid_from,
+            valid_until,
+            sphinx_key: sphinx_key.to_base58_string(),
+            signature: signature.to_base58_string(),
+        }
+    }
+
+    pub fn is_valid_at(&self, unix_timestamp: u64) -> bool {
+        self.valid_from <= unix_timestamp && unix_timestamp < self.valid_until
+    }
+
+    /// Verifies the signature using the provided identity key and returns the recovered sphinx key.
+    pub fn verify(
+        &self,
+        identity_key: &identity::PublicKey,
+    ) -> Result<encryption::PublicKey, SphinxKeyError> {
+        let sphinx_key = encryption::PublicKey::from_base58_string(&self.sphinx_key)?;
+        let signature = identity::Signature::from_base58_string(&self.signature)?;
+        let plaintext =
+            Self::signed_plaintext(self.epoch, self.valid_from, self.valid_until, &sphinx_key);
+
+        identity_key
+            .verify(&plaintext, &signature)
+            .map_err(|_| SphinxKeyError::InvalidSignature { epoch: self.epoch })?;
+        Ok(sphinx_key)
+    }
+
+    /// Verifies the key and checks whether it should be used at the provided time
+    /// instead of the key of the specified epoch (or the bonded key if the epoch is not known).
+    pub(crate) fn verify_update(
+        &self,
+        identity_key: &identity::PublicKey,
+        current_epoch: Option<KeyEpoch>,
+        unix_timestamp: u64,
+    ) -> Result<encryption::PublicKey, SphinxKeyError> {
+        if let Some(current) = current_epoch {
+            if self.epoch < current {
+                return Err(SphinxKeyError::OutdatedEpoch {
+                    current,
+                    received: self.epoch,
+                });
+            }
+        }
+        if !self.is_valid_at(unix_timestamp) {
+            return Err(SphinxKeyError::NotValidAt {
+                epoch: self.epoch,
+                timestamp: unix_timestamp,
+            });
+        }
+        self.verify(identity_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_key_can_be_verified() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        let sphinx_keys = encryption::KeyPair::new(&mut rng);
+
+        let signed = SignedSphinxKey::new(
+            42,
+            100,
+            200,
+            sphinx_keys.public_key(),
+            identity_keys.private_key(),
+        );
+        let recovered = signed.verify(identity_keys.public_key()).unwrap();
+        assert_eq!(&recovered, sphinx_keys.public_key());
+
+        let other_identity = identity::KeyPair::new(&mut rng);
+        assert!(signed.verify(other_identity.public_key()).is_err());
+
+        let mut tampered = signed.clone();
+        tampered.valid_until = 300;
+        assert!(tampered.verify(identity_keys.public_key()).is_err());
+    }
+
+    #[test]
+    fn updates_are_checked_for_validity() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        let sphinx_keys = encryption::KeyPair::new(&mut rng);
+        let signed = SignedSphinxKey::new(
+            42,
+            100,
+            200,
+            sphinx_keys.public_key(),
+            identity_keys.private_key(),
+        );
+
+        let identity = identity_keys.public_key();
+        assert!(signed.verify_update(identity, None, 150).is_ok());
+        assert!(signed.verify_update(identity, Some(42), 150).is_ok());
+        assert!(signed.verify_update(identity, Some(43), 150).is_err());
+        assert!(signed.verify_update(identity, None, 99).is_err());
+        assert!(signed.verify_update(identity, None, 200).is_err());
+    }
+
+    #[test]
+    fn nodes_switch_to_the_latest_valid_announced_key() {
+        let mut rng = rand::rngs::OsRng;
+        let identity_keys = identity::KeyPair::new(&mut rng);
+        let bonded_key = *encryption::KeyPair::new(&mut rng).public_key();
+        let mut node = crate::mix::Node {
+            mix_id: 1,
+            owner: "owner".to_string(),
+            host: "1.2.3.4".parse().unwrap(),
+            mix_host: "1.2.3.4:1789".parse().unwrap(),
+            http_api_port: 8000,
+            identity_key: *identity_keys.public_key(),
+            sphinx_key: bonded_key,
+            sphinx_key_epoch: None,
+            layer: mixnet_contract_common::Layer::One,
+            version: "1.1.0".to_string(),
+            family: None,
+            performance: None,
+        };
+
+        let epoch_keys = (0..3)
+            .map(|_| *encryption::KeyPair::new(&mut rng).public_key())
+            .collect::<Vec<_>>();
+        let announced = epoch_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let epoch = 10 + i as KeyEpoch;
+                let start = epoch as u64 * 100;
+                SignedSphinxKey::new(epoch, start, start + 110, key, identity_keys.private_key())
+            })
+            .collect::<Vec<_>>();
+
+        // nothing is valid yet
+        assert!(!node.apply_announced_sphinx_keys(&announced, 999).unwrap());
+        assert_eq!(node.sphinx_key, bonded_key);
+
+        // during the grace period the newer key is preferred
+        assert!(node.apply_announced_sphinx_keys(&announced, 1105).unwrap());
+        assert_eq!(node.sphinx_key_epoch, Some(11));
+        assert_eq!(node.sphinx_key, epoch_keys[1]);
+        assert!(!node.apply_announced_sphinx_keys(&announced, 1150).unwrap());
+
+        // keys signed by anyone else are rejected
+        let impostor = identity::KeyPair::new(&mut rng);
+        let forged = SignedSphinxKey::new(12, 1200, 1310, &epoch_keys[2], impostor.private_key());
+        assert!(node.apply_announced_sphinx_keys(&[forged], 1250).is_err());
+        assert_eq!(node.sphinx_key_epoch, Some(11));
+    }
+}
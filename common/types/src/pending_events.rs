@@ -5,11 +5,11 @@ use crate::currency::{DecCoin, RegisteredCoins};
 use crate::error::TypesError;
 use crate::mixnode::MixNodeCostParams;
 use mixnet_contract_common::{
-    BlockHeight, EpochEventId, IntervalEventId, IntervalRewardingParamsUpdate, MixId,
+    BlockHeight, EpochEventId, IdentityKey, IntervalEventId, IntervalRewardingParamsUpdate, MixId,
     PendingEpochEvent as MixnetContractPendingEpochEvent,
     PendingEpochEventKind as MixnetContractPendingEpochEventKind,
     PendingIntervalEvent as MixnetContractPendingIntervalEvent,
-    PendingIntervalEventKind as MixnetContractPendingIntervalEventKind,
+    PendingIntervalEventKind as MixnetContractPendingIntervalEventKind, SphinxKey,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -57,13 +57,36 @@ pub enum PendingEpochEventData {
         mix_id: MixId,
         proxy: Option<String>,
     },
+    Redelegate {
+        owner: String,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: DecCoin,
+        proxy: Option<String>,
+    },
     PledgeMore {
         mix_id: MixId,
         amount: DecCoin,
     },
+    DecreasePledge {
+        mix_id: MixId,
+        decrease_by: DecCoin,
+    },
     UnbondMixnode {
         mix_id: MixId,
     },
+    UpdateMixnodeKeys {
+        mix_id: MixId,
+        owner: String,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+    },
+    UpdateGatewayKeys {
+        identity: IdentityKey,
+        owner: String,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+    },
     UpdateActiveSetSize {
         new_size: u32,
     },
@@ -95,15 +118,57 @@ impl PendingEpochEventData {
                 mix_id,
                 proxy: proxy.map(|p| p.into_string()),
             }),
+            MixnetContractPendingEpochEventKind::Redelegate {
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            } => Ok(PendingEpochEventData::Redelegate {
+                owner: owner.into_string(),
+                from_mix_id,
+                to_mix_id,
+                amount: reg.attempt_convert_to_display_dec_coin(amount.into())?,
+                proxy: proxy.map(|p| p.into_string()),
+            }),
             MixnetContractPendingEpochEventKind::PledgeMore { mix_id, amount } => {
                 Ok(PendingEpochEventData::PledgeMore {
                     mix_id,
                     amount: reg.attempt_convert_to_display_dec_coin(amount.into())?,
                 })
             }
+            MixnetContractPendingEpochEventKind::DecreasePledge {
+                mix_id,
+                decrease_by,
+            } => Ok(PendingEpochEventData::DecreasePledge {
+                mix_id,
+                decrease_by: reg.attempt_convert_to_display_dec_coin(decrease_by.into())?,
+            }),
             MixnetContractPendingEpochEventKind::UnbondMixnode { mix_id } => {
                 Ok(PendingEpochEventData::UnbondMixnode { mix_id })
             }
+            MixnetContractPendingEpochEventKind::UpdateMixnodeKeys {
+                mix_id,
+                owner,
+                new_identity_key,
+                new_sphinx_key,
+            } => Ok(PendingEpochEventData::UpdateMixnodeKeys {
+                mix_id,
+                owner: owner.into_string(),
+                new_identity_key,
+                new_sphinx_key,
+            }),
+            MixnetContractPendingEpochEventKind::UpdateGatewayKeys {
+                identity,
+                owner,
+                new_identity_key,
+                new_sphinx_key,
+            } => Ok(PendingEpochEventData::UpdateGatewayKeys {
+                identity,
+                owner: owner.into_string(),
+                new_identity_key,
+                new_sphinx_key,
+            }),
             MixnetContractPendingEpochEventKind::UpdateActiveSetSize { new_size } => {
                 Ok(PendingEpochEventData::UpdateActiveSetSize { new_size })
             }
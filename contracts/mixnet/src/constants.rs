@@ -45,6 +45,7 @@ pub(crate) const DELEGATION_MIXNODE_IDX_NAMESPACE: &str = "dlm";
 
 pub(crate) const GATEWAYS_PK_NAMESPACE: &str = "gt";
 pub(crate) const GATEWAYS_OWNER_IDX_NAMESPACE: &str = "gto";
+pub(crate) const GATEWAYS_PENDING_OWNERSHIP_TRANSFERS_NAMESPACE: &str = "gtpot";
 
 pub(crate) const REWARDED_SET_KEY: &str = "rs";
 pub(crate) const CURRENT_INTERVAL_KEY: &str = "ci";
@@ -64,6 +65,7 @@ pub(crate) const MIXNODES_PK_NAMESPACE: &str = "mnn";
 pub(crate) const MIXNODES_OWNER_IDX_NAMESPACE: &str = "mno";
 pub(crate) const MIXNODES_IDENTITY_IDX_NAMESPACE: &str = "mni";
 pub(crate) const MIXNODES_SPHINX_IDX_NAMESPACE: &str = "mns";
+pub(crate) const MIXNODES_PENDING_OWNERSHIP_TRANSFERS_NAMESPACE: &str = "mnpot";
 
 pub(crate) const UNBONDED_MIXNODES_PK_NAMESPACE: &str = "ubm";
 pub(crate) const UNBONDED_MIXNODES_OWNER_IDX_NAMESPACE: &str = "umo";
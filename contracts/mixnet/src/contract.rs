@@ -237,6 +237,14 @@ pub fn execute(
         ExecuteMsg::PledgeMoreOnBehalf { owner } => {
             crate::mixnodes::transactions::try_increase_pledge_on_behalf(deps, env, info, owner)
         }
+        ExecuteMsg::DecreasePledge { amount } => {
+            crate::mixnodes::transactions::try_decrease_pledge(deps, env, info, amount)
+        }
+        ExecuteMsg::DecreasePledgeOnBehalf { owner, amount } => {
+            crate::mixnodes::transactions::try_decrease_pledge_on_behalf(
+                deps, env, info, amount, owner,
+            )
+        }
         ExecuteMsg::UnbondMixnode {} => {
             crate::mixnodes::transactions::try_remove_mixnode(deps, env, info)
         }
@@ -261,6 +269,58 @@ pub fn execute(
                 deps, info, new_config, owner,
             )
         }
+        ExecuteMsg::ProposeMixnodeOwnershipTransfer { new_owner } => {
+            crate::mixnodes::transactions::try_propose_mixnode_ownership_transfer(
+                deps, info, new_owner,
+            )
+        }
+        ExecuteMsg::ProposeMixnodeOwnershipTransferOnBehalf { owner, new_owner } => {
+            crate::mixnodes::transactions::try_propose_mixnode_ownership_transfer_on_behalf(
+                deps, info, owner, new_owner,
+            )
+        }
+        ExecuteMsg::CancelMixnodeOwnershipTransfer {} => {
+            crate::mixnodes::transactions::try_cancel_mixnode_ownership_transfer(deps, info)
+        }
+        ExecuteMsg::CancelMixnodeOwnershipTransferOnBehalf { owner } => {
+            crate::mixnodes::transactions::try_cancel_mixnode_ownership_transfer_on_behalf(
+                deps, info, owner,
+            )
+        }
+        ExecuteMsg::AcceptMixnodeOwnershipTransfer { mix_id } => {
+            crate::mixnodes::transactions::try_accept_mixnode_ownership_transfer(deps, info, mix_id)
+        }
+        ExecuteMsg::AcceptMixnodeOwnershipTransferOnBehalf { mix_id, new_owner } => {
+            crate::mixnodes::transactions::try_accept_mixnode_ownership_transfer_on_behalf(
+                deps, info, mix_id, new_owner,
+            )
+        }
+        ExecuteMsg::UpdateMixnodeKeys {
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+        } => crate::mixnodes::transactions::try_update_mixnode_keys(
+            deps,
+            env,
+            info,
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+        ),
+        ExecuteMsg::UpdateMixnodeKeysOnBehalf {
+            new_identity_key,
+            new_sphinx_key,
+            owner,
+            owner_signature,
+        } => crate::mixnodes::transactions::try_update_mixnode_keys_on_behalf(
+            deps,
+            env,
+            info,
+            new_identity_key,
+            new_sphinx_key,
+            owner,
+            owner_signature,
+        ),
 
         // gateway-related:
         ExecuteMsg::BondGateway {
@@ -291,6 +351,69 @@ pub fn execute(
         ExecuteMsg::UnbondGatewayOnBehalf { owner } => {
             crate::gateways::transactions::try_remove_gateway_on_behalf(deps, info, owner)
         }
+        ExecuteMsg::UpdateGatewayConfig { new_config } => {
+            crate::gateways::transactions::try_update_gateway_config(deps, info, new_config)
+        }
+        ExecuteMsg::UpdateGatewayConfigOnBehalf { new_config, owner } => {
+            crate::gateways::transactions::try_update_gateway_config_on_behalf(
+                deps, info, new_config, owner,
+            )
+        }
+        ExecuteMsg::ProposeGatewayOwnershipTransfer { new_owner } => {
+            crate::gateways::transactions::try_propose_gateway_ownership_transfer(
+                deps, info, new_owner,
+            )
+        }
+        ExecuteMsg::ProposeGatewayOwnershipTransferOnBehalf { owner, new_owner } => {
+            crate::gateways::transactions::try_propose_gateway_ownership_transfer_on_behalf(
+                deps, info, owner, new_owner,
+            )
+        }
+        ExecuteMsg::CancelGatewayOwnershipTransfer {} => {
+            crate::gateways::transactions::try_cancel_gateway_ownership_transfer(deps, info)
+        }
+        ExecuteMsg::CancelGatewayOwnershipTransferOnBehalf { owner } => {
+            crate::gateways::transactions::try_cancel_gateway_ownership_transfer_on_behalf(
+                deps, info, owner,
+            )
+        }
+        ExecuteMsg::AcceptGatewayOwnershipTransfer { identity } => {
+            crate::gateways::transactions::try_accept_gateway_ownership_transfer(
+                deps, info, identity,
+            )
+        }
+        ExecuteMsg::AcceptGatewayOwnershipTransferOnBehalf {
+            identity,
+            new_owner,
+        } => crate::gateways::transactions::try_accept_gateway_ownership_transfer_on_behalf(
+            deps, info, identity, new_owner,
+        ),
+        ExecuteMsg::UpdateGatewayKeys {
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+        } => crate::gateways::transactions::try_update_gateway_keys(
+            deps,
+            env,
+            info,
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+        ),
+        ExecuteMsg::UpdateGatewayKeysOnBehalf {
+            new_identity_key,
+            new_sphinx_key,
+            owner,
+            owner_signature,
+        } => crate::gateways::transactions::try_update_gateway_keys_on_behalf(
+            deps,
+            env,
+            info,
+            new_identity_key,
+            new_sphinx_key,
+            owner,
+            owner_signature,
+        ),
 
         // delegation-related:
         ExecuteMsg::DelegateToMixnode { mix_id } => {
@@ -311,6 +434,32 @@ pub fn execute(
                 deps, env, info, mix_id, delegate,
             )
         }
+        ExecuteMsg::RedelegateMixnode {
+            from_mix_id,
+            to_mix_id,
+            amount,
+        } => crate::delegations::transactions::try_redelegate_mixnode(
+            deps,
+            env,
+            info,
+            from_mix_id,
+            to_mix_id,
+            amount,
+        ),
+        ExecuteMsg::RedelegateMixnodeOnBehalf {
+            from_mix_id,
+            to_mix_id,
+            amount,
+            delegate,
+        } => crate::delegations::transactions::try_redelegate_mixnode_on_behalf(
+            deps,
+            env,
+            info,
+            from_mix_id,
+            to_mix_id,
+            amount,
+            delegate,
+        ),
 
         // reward-related
         ExecuteMsg::RewardMixnode {
@@ -443,6 +592,9 @@ pub fn query(
         QueryMsg::GetLayerDistribution {} => {
             to_binary(&crate::mixnodes::queries::query_layer_distribution(deps)?)
         }
+        QueryMsg::GetPendingMixnodeOwnershipTransfer { mix_id } => to_binary(
+            &crate::mixnodes::queries::query_pending_mixnode_ownership_transfer(deps, mix_id)?,
+        ),
 
         // gateway-related:
         QueryMsg::GetGateways { limit, start_after } => to_binary(
@@ -454,6 +606,9 @@ pub fn query(
         QueryMsg::GetOwnedGateway { address } => to_binary(
             &crate::gateways::queries::query_owned_gateway(deps, address)?,
         ),
+        QueryMsg::GetPendingGatewayOwnershipTransfer { identity } => to_binary(
+            &crate::gateways::queries::query_pending_gateway_ownership_transfer(deps, identity)?,
+        ),
 
         // delegation-related:
         QueryMsg::GetMixnodeDelegations {
@@ -5,13 +5,15 @@ use super::storage;
 use crate::interval::storage as interval_storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::mixnodes::storage as mixnodes_storage;
+use crate::rewards::storage as rewards_storage;
 use crate::support::helpers::validate_delegation_stake;
 use cosmwasm_std::{Addr, Coin, DepsMut, Env, MessageInfo, Response};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
-    new_pending_delegation_event, new_pending_undelegation_event,
+    new_pending_delegation_event, new_pending_redelegation_event, new_pending_undelegation_event,
 };
 use mixnet_contract_common::pending_events::PendingEpochEventKind;
+use mixnet_contract_common::rewarding::helpers::truncate_reward;
 use mixnet_contract_common::{Delegation, MixId};
 
 pub(crate) fn try_delegate_to_mixnode(
@@ -127,6 +129,115 @@ pub(crate) fn _try_remove_delegation_from_mixnode(
     Ok(Response::new().add_event(cosmos_event))
 }
 
+pub(crate) fn try_redelegate_mixnode(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+) -> Result<Response, MixnetContractError> {
+    _try_redelegate_mixnode(deps, env, from_mix_id, to_mix_id, amount, info.sender, None)
+}
+
+pub(crate) fn try_redelegate_mixnode_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    delegate: String,
+) -> Result<Response, MixnetContractError> {
+    let delegate = deps.api.addr_validate(&delegate)?;
+    _try_redelegate_mixnode(
+        deps,
+        env,
+        from_mix_id,
+        to_mix_id,
+        amount,
+        delegate,
+        Some(info.sender),
+    )
+}
+
+pub(crate) fn _try_redelegate_mixnode(
+    deps: DepsMut<'_>,
+    env: Env,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    delegate: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    if from_mix_id == to_mix_id {
+        return Err(MixnetContractError::RedelegationToSameMixnode {
+            mix_id: from_mix_id,
+        });
+    }
+
+    // check if the redelegated amount is of the appropriate denomination
+    let contract_state = mixnet_params_storage::CONTRACT_STATE.load(deps.storage)?;
+    let amount = validate_delegation_stake(
+        vec![amount],
+        contract_state.params.minimum_mixnode_delegation,
+        contract_state.rewarding_denom,
+    )?;
+
+    // see if the delegation even exists
+    let storage_key = Delegation::generate_storage_key(from_mix_id, &delegate, proxy.as_ref());
+    let delegation = match storage::delegations().may_load(deps.storage, storage_key)? {
+        Some(delegation) => delegation,
+        None => {
+            return Err(MixnetContractError::NoMixnodeDelegationFound {
+                mix_id: from_mix_id,
+                address: delegate.into_string(),
+                proxy: proxy.map(Addr::into_string),
+            })
+        }
+    };
+
+    // and whether it's worth enough (rewards are included, as they're moved alongside it)
+    let mix_rewarding = rewards_storage::MIXNODE_REWARDING
+        .may_load(deps.storage, from_mix_id)?
+        .ok_or(MixnetContractError::InconsistentState {
+            comment: "mixnode rewarding got removed from the storage whilst there's still an existing delegation"
+                .into(),
+        })?;
+    let reward = mix_rewarding.determine_delegation_reward(&delegation)?;
+    let available = truncate_reward(reward + delegation.dec_amount()?, &amount.denom);
+    if amount.amount > available.amount {
+        return Err(MixnetContractError::RedelegationExceedsDelegation {
+            requested: amount,
+            available,
+        });
+    }
+
+    // check if the target node actually exists and is still bonded
+    match mixnodes_storage::mixnode_bonds().may_load(deps.storage, to_mix_id)? {
+        None => return Err(MixnetContractError::MixNodeBondNotFound { mix_id: to_mix_id }),
+        Some(bond) if bond.is_unbonding => {
+            return Err(MixnetContractError::MixnodeIsUnbonding { mix_id: to_mix_id })
+        }
+        _ => (),
+    }
+
+    // push the event onto the queue and wait for it to be picked up at the end of the epoch
+    let cosmos_event =
+        new_pending_redelegation_event(&delegate, &proxy, &amount, from_mix_id, to_mix_id);
+
+    let epoch_event = PendingEpochEventKind::Redelegate {
+        owner: delegate,
+        from_mix_id,
+        to_mix_id,
+        amount,
+        proxy,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,4 +574,261 @@ mod tests {
             assert!(res.is_ok());
         }
     }
+
+    #[cfg(test)]
+    mod redelegating_between_mixnodes {
+        use super::*;
+        use crate::mixnodes::transactions::try_remove_mixnode;
+        use crate::support::tests::fixtures::TEST_COIN_DENOM;
+        use crate::support::tests::test_helpers::TestSetup;
+        use cosmwasm_std::coin;
+        use cosmwasm_std::testing::mock_info;
+
+        #[test]
+        fn cannot_be_performed_towards_the_same_mixnode() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner = "delegator";
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, mix_id);
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                mix_id,
+                mix_id,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::RedelegationToSameMixnode { mix_id })
+            );
+        }
+
+        #[test]
+        fn must_contain_non_zero_amount_of_coins() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner = "delegator";
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, mix_id1);
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                mock_info(owner, &[]),
+                mix_id1,
+                mix_id2,
+                coin(0, TEST_COIN_DENOM),
+            );
+            assert_eq!(res, Err(MixnetContractError::EmptyDelegation));
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                mix_id1,
+                mix_id2,
+                coin(1000, "some-weird-coin"),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::WrongDenom {
+                    received: "some-weird-coin".to_string(),
+                    expected: TEST_COIN_DENOM.to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn cannot_be_performed_if_delegation_never_existed() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner = "delegator";
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                mix_id1,
+                mix_id2,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoMixnodeDelegationFound {
+                    mix_id: mix_id1,
+                    address: owner.to_string(),
+                    proxy: None,
+                })
+            );
+        }
+
+        #[test]
+        fn cannot_move_more_than_delegated() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner = "delegator";
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, mix_id1);
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                mock_info(owner, &[]),
+                mix_id1,
+                mix_id2,
+                coin(100_000_001, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::RedelegationExceedsDelegation {
+                    requested: coin(100_000_001, TEST_COIN_DENOM),
+                    available: coin(100_000_000, TEST_COIN_DENOM),
+                })
+            );
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                mix_id1,
+                mix_id2,
+                coin(100_000_000, TEST_COIN_DENOM),
+            );
+            assert!(res.is_ok())
+        }
+
+        #[test]
+        fn can_only_be_done_towards_fully_bonded_mixnode() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner = "delegator";
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let mix_id_unbonding = test.add_dummy_mixnode("mix-owner-unbonding", None);
+            let mix_id_unbonded = test.add_dummy_mixnode("mix-owner-unbonded", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, mix_id);
+
+            try_remove_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                mock_info("mix-owner-unbonded", &[]),
+            )
+            .unwrap();
+            test.execute_all_pending_events();
+            try_remove_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                mock_info("mix-owner-unbonding", &[]),
+            )
+            .unwrap();
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                mock_info(owner, &[]),
+                mix_id,
+                mix_id_unbonding,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixnodeIsUnbonding {
+                    mix_id: mix_id_unbonding
+                })
+            );
+
+            let res = try_redelegate_mixnode(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                mix_id,
+                mix_id_unbonded,
+                coin(50_000_000, TEST_COIN_DENOM),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixNodeBondNotFound {
+                    mix_id: mix_id_unbonded
+                })
+            );
+        }
+
+        #[test]
+        fn correctly_pushes_appropriate_epoch_event() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner = "delegator";
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+            test.add_immediate_delegation(owner, 100_000_000u32, mix_id1);
+            test.add_immediate_delegation_with_proxy(
+                owner,
+                100_000_000u32,
+                mix_id1,
+                test.vesting_contract(),
+            );
+
+            let amount1 = coin(100_000_000, TEST_COIN_DENOM);
+            let amount2 = coin(50_000_000, TEST_COIN_DENOM);
+
+            let sender1 = mock_info(owner, &[]);
+            let sender2 = mock_info(test.vesting_contract().as_str(), &[]);
+
+            try_redelegate_mixnode(
+                test.deps_mut(),
+                env.clone(),
+                sender1,
+                mix_id1,
+                mix_id2,
+                amount1.clone(),
+            )
+            .unwrap();
+            try_redelegate_mixnode_on_behalf(
+                test.deps_mut(),
+                env,
+                sender2,
+                mix_id1,
+                mix_id2,
+                amount2.clone(),
+                owner.into(),
+            )
+            .unwrap();
+
+            let events = test.pending_epoch_events();
+
+            assert_eq!(
+                events[0].kind,
+                PendingEpochEventKind::Redelegate {
+                    owner: Addr::unchecked(owner),
+                    from_mix_id: mix_id1,
+                    to_mix_id: mix_id2,
+                    amount: amount1,
+                    proxy: None
+                }
+            );
+
+            assert_eq!(
+                events[1].kind,
+                PendingEpochEventKind::Redelegate {
+                    owner: Addr::unchecked(owner),
+                    from_mix_id: mix_id1,
+                    to_mix_id: mix_id2,
+                    amount: amount2,
+                    proxy: Some(test.vesting_contract())
+                }
+            );
+        }
+    }
 }
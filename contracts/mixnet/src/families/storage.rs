@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use cosmwasm_std::{Order, StdError, Storage};
+use cosmwasm_std::{Addr, Order, StdError, Storage};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Map, UniqueIndex};
 use mixnet_contract_common::families::{Family, FamilyHead};
 use mixnet_contract_common::{error::MixnetContractError, IdentityKey, IdentityKeyRef};
@@ -81,6 +81,38 @@ pub fn remove_family_member(store: &mut dyn Storage, member: IdentityKeyRef<'_>)
     MEMBERS.remove(store, member.to_string())
 }
 
+/// Moves the family membership (and headship) of the node to its new identity key.
+pub fn migrate_node_identity(
+    store: &mut dyn Storage,
+    old_identity: IdentityKeyRef<'_>,
+    new_identity: IdentityKeyRef<'_>,
+) -> Result<(), MixnetContractError> {
+    if let Some(head) = MEMBERS.may_load(store, old_identity.to_string())? {
+        MEMBERS.remove(store, old_identity.to_string());
+        MEMBERS.save(store, new_identity.to_string(), &head)?;
+    }
+
+    if let Some(family) = families().may_load(store, old_identity.to_string())? {
+        let members = get_members(&family, store)?;
+        let new_head = FamilyHead::new(new_identity);
+        // the proxy was a valid address at the time of creating the family
+        let migrated = Family::new(
+            new_head.clone(),
+            family.proxy().map(Addr::unchecked),
+            family.label(),
+        );
+
+        // remove the old entry first as otherwise the label would violate the unique constraint
+        families().remove(store, old_identity.to_string())?;
+        families().save(store, new_identity.to_string(), &migrated)?;
+        for member in members {
+            MEMBERS.save(store, member, &new_head)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 pub fn is_family_member(
     store: &dyn Storage,
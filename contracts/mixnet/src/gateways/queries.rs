@@ -7,6 +7,7 @@ use cosmwasm_std::{Deps, Order, StdResult};
 use cw_storage_plus::Bound;
 use mixnet_contract_common::{
     GatewayBond, GatewayBondResponse, GatewayOwnershipResponse, IdentityKey, PagedGatewayResponse,
+    PendingGatewayOwnershipTransferResponse,
 };
 
 pub(crate) fn query_gateways_paged(
@@ -56,6 +57,16 @@ pub fn query_gateway_bond(deps: Deps<'_>, identity: IdentityKey) -> StdResult<Ga
     })
 }
 
+pub(crate) fn query_pending_gateway_ownership_transfer(
+    deps: Deps<'_>,
+    identity: IdentityKey,
+) -> StdResult<PendingGatewayOwnershipTransferResponse> {
+    Ok(PendingGatewayOwnershipTransferResponse {
+        new_owner: storage::PENDING_OWNERSHIP_TRANSFERS.may_load(deps.storage, identity.clone())?,
+        identity,
+    })
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
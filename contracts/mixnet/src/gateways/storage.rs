@@ -1,10 +1,13 @@
 // Copyright 2021 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::constants::{GATEWAYS_OWNER_IDX_NAMESPACE, GATEWAYS_PK_NAMESPACE};
+use crate::constants::{
+    GATEWAYS_OWNER_IDX_NAMESPACE, GATEWAYS_PENDING_OWNERSHIP_TRANSFERS_NAMESPACE,
+    GATEWAYS_PK_NAMESPACE,
+};
 use cosmwasm_std::Addr;
-use cw_storage_plus::{Index, IndexList, IndexedMap, UniqueIndex};
-use mixnet_contract_common::{GatewayBond, IdentityKeyRef};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Map, UniqueIndex};
+use mixnet_contract_common::{GatewayBond, IdentityKey, IdentityKeyRef};
 
 pub(crate) struct GatewayBondIndex<'a> {
     pub(crate) owner: UniqueIndex<'a, Addr, GatewayBond>,
@@ -27,3 +30,7 @@ pub(crate) fn gateways<'a>() -> IndexedMap<'a, IdentityKeyRef<'a>, GatewayBond,
     };
     IndexedMap::new(GATEWAYS_PK_NAMESPACE, indexes)
 }
+
+// proposed new owners of the gateways, who have yet to accept the ownership
+pub(crate) const PENDING_OWNERSHIP_TRANSFERS: Map<IdentityKey, Addr> =
+    Map::new(GATEWAYS_PENDING_OWNERSHIP_TRANSFERS_NAMESPACE);
@@ -2,14 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::storage;
+use crate::interval::storage as interval_storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
 use crate::support::helpers::{
-    ensure_no_existing_bond, validate_node_identity_signature, validate_pledge,
+    ensure_no_existing_bond, ensure_proxy_match, validate_node_identity_signature, validate_pledge,
+};
+use cosmwasm_std::{
+    wasm_execute, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Storage,
 };
-use cosmwasm_std::{wasm_execute, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
 use mixnet_contract_common::error::MixnetContractError;
-use mixnet_contract_common::events::{new_gateway_bonding_event, new_gateway_unbonding_event};
-use mixnet_contract_common::{Gateway, GatewayBond};
+use mixnet_contract_common::events::{
+    new_gateway_bonding_event, new_gateway_config_update_event,
+    new_gateway_ownership_transfer_cancellation_event, new_gateway_ownership_transfer_event,
+    new_gateway_ownership_transfer_proposal_event, new_gateway_unbonding_event,
+    new_pending_gateway_keys_update_event,
+};
+use mixnet_contract_common::pending_events::PendingEpochEventKind;
+use mixnet_contract_common::{Gateway, GatewayBond, GatewayConfigUpdate, IdentityKey, SphinxKey};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
 pub fn try_add_gateway(
@@ -155,6 +164,10 @@ pub(crate) fn _try_remove_gateway(
     // remove the bond
     storage::gateways().remove(deps.storage, gateway_bond.identity())?;
 
+    // the gateway is going away, so there's nothing left to be transferred. note that this is
+    // particularly important as the same identity might get bonded again by somebody else
+    storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, gateway_bond.identity().to_string());
+
     let mut response = Response::new().add_message(return_tokens);
 
     if let Some(proxy) = &proxy {
@@ -175,20 +188,368 @@ pub(crate) fn _try_remove_gateway(
     )))
 }
 
+pub(crate) fn try_update_gateway_config(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_config: GatewayConfigUpdate,
+) -> Result<Response, MixnetContractError> {
+    let owner = info.sender;
+    _try_update_gateway_config(deps, new_config, owner, None)
+}
+
+pub(crate) fn try_update_gateway_config_on_behalf(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_config: GatewayConfigUpdate,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_update_gateway_config(deps, new_config, owner, Some(proxy))
+}
+
+pub(crate) fn _try_update_gateway_config(
+    deps: DepsMut,
+    new_config: GatewayConfigUpdate,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = match storage::gateways()
+        .idx
+        .owner
+        .item(deps.storage, owner.clone())?
+    {
+        Some(record) => record.1,
+        None => return Err(MixnetContractError::NoAssociatedGatewayBond { owner }),
+    };
+
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+
+    let cfg_update_event =
+        new_gateway_config_update_event(&owner, &proxy, existing_bond.identity(), &new_config);
+
+    // note: the bond (including its height) is preserved, so are the keys of the gateway
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.gateway.host = new_config.host;
+    updated_bond.gateway.mix_port = new_config.mix_port;
+    updated_bond.gateway.clients_port = new_config.clients_port;
+    updated_bond.gateway.location = new_config.location;
+    updated_bond.gateway.version = new_config.version;
+
+    storage::gateways().replace(
+        deps.storage,
+        existing_bond.identity(),
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+
+    Ok(Response::new().add_event(cfg_update_event))
+}
+
+fn must_get_gateway_bond_by_owner(
+    store: &dyn Storage,
+    owner: &Addr,
+) -> Result<GatewayBond, MixnetContractError> {
+    Ok(storage::gateways()
+        .idx
+        .owner
+        .item(store, owner.clone())?
+        .ok_or(MixnetContractError::NoAssociatedGatewayBond {
+            owner: owner.clone(),
+        })?
+        .1)
+}
+
+pub(crate) fn try_update_gateway_keys(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner_signature: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = info.sender;
+    _try_update_gateway_keys(
+        deps,
+        env,
+        new_identity_key,
+        new_sphinx_key,
+        owner_signature,
+        owner,
+        None,
+    )
+}
+
+pub(crate) fn try_update_gateway_keys_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner: String,
+    owner_signature: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_update_gateway_keys(
+        deps,
+        env,
+        new_identity_key,
+        new_sphinx_key,
+        owner_signature,
+        owner,
+        Some(proxy),
+    )
+}
+
+pub(crate) fn _try_update_gateway_keys(
+    deps: DepsMut<'_>,
+    env: Env,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner_signature: String,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_gateway_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+
+    if existing_bond.gateway.identity_key == new_identity_key
+        && existing_bond.gateway.sphinx_key == new_sphinx_key
+    {
+        return Err(MixnetContractError::UnchangedNodeKeys);
+    }
+
+    if existing_bond.gateway.identity_key != new_identity_key {
+        if let Some(other) = storage::gateways().may_load(deps.storage, &new_identity_key)? {
+            return Err(MixnetContractError::DuplicateGateway { owner: other.owner });
+        }
+    }
+
+    // the operator has to prove they're in possession of the new identity key
+    validate_node_identity_signature(deps.as_ref(), &owner, &owner_signature, &new_identity_key)?;
+
+    let cosmos_event = new_pending_gateway_keys_update_event(
+        existing_bond.identity(),
+        &owner,
+        &proxy,
+        &new_identity_key,
+        &new_sphinx_key,
+    );
+
+    // push the event to execute it at the end of the epoch
+    let epoch_event = PendingEpochEventKind::UpdateGatewayKeys {
+        identity: existing_bond.gateway.identity_key,
+        owner,
+        new_identity_key,
+        new_sphinx_key,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub(crate) fn try_propose_gateway_ownership_transfer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, MixnetContractError> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    _try_propose_gateway_ownership_transfer(deps, info.sender, new_owner, None)
+}
+
+pub(crate) fn try_propose_gateway_ownership_transfer_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    owner: String,
+    new_owner: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let proxy = info.sender;
+    _try_propose_gateway_ownership_transfer(deps, owner, new_owner, Some(proxy))
+}
+
+/// Proposes transferring the ownership of the gateway to `new_owner`, who has to accept it before
+/// it takes effect. The bonds made through a proxy can only be transferred through that same proxy.
+pub(crate) fn _try_propose_gateway_ownership_transfer(
+    deps: DepsMut<'_>,
+    owner: Addr,
+    new_owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_gateway_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+
+    if new_owner == owner {
+        return Err(MixnetContractError::OwnershipTransferToSelf { owner });
+    }
+
+    // if there was another proposal made before, it just gets replaced
+    storage::PENDING_OWNERSHIP_TRANSFERS.save(
+        deps.storage,
+        existing_bond.identity().to_string(),
+        &new_owner,
+    )?;
+
+    Ok(
+        Response::new().add_event(new_gateway_ownership_transfer_proposal_event(
+            existing_bond.identity(),
+            &owner,
+            &new_owner,
+        )),
+    )
+}
+
+pub(crate) fn try_cancel_gateway_ownership_transfer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+) -> Result<Response, MixnetContractError> {
+    _try_cancel_gateway_ownership_transfer(deps, info.sender, None)
+}
+
+pub(crate) fn try_cancel_gateway_ownership_transfer_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_cancel_gateway_ownership_transfer(deps, owner, Some(proxy))
+}
+
+pub(crate) fn _try_cancel_gateway_ownership_transfer(
+    deps: DepsMut<'_>,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_gateway_bond_by_owner(deps.storage, &owner)?;
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+    let identity = existing_bond.identity().to_string();
+
+    let new_owner = storage::PENDING_OWNERSHIP_TRANSFERS
+        .may_load(deps.storage, identity.clone())?
+        .ok_or_else(|| MixnetContractError::NoPendingGatewayOwnershipTransfer {
+            identity: identity.clone(),
+        })?;
+    storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, identity.clone());
+
+    Ok(
+        Response::new().add_event(new_gateway_ownership_transfer_cancellation_event(
+            &identity, &owner, &new_owner,
+        )),
+    )
+}
+
+pub(crate) fn try_accept_gateway_ownership_transfer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    identity: IdentityKey,
+) -> Result<Response, MixnetContractError> {
+    _try_accept_gateway_ownership_transfer(deps, identity, info.sender, None)
+}
+
+pub(crate) fn try_accept_gateway_ownership_transfer_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    identity: IdentityKey,
+    new_owner: String,
+) -> Result<Response, MixnetContractError> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let proxy = info.sender;
+    _try_accept_gateway_ownership_transfer(deps, identity, new_owner, Some(proxy))
+}
+
+/// Completes the ownership transfer of the gateway proposed by its current owner, including
+/// its pledge. If the gateway has been bonded through a proxy, the new owner has to accept it
+/// through the same proxy, which gets told to move the pledge over to them.
+pub(crate) fn _try_accept_gateway_ownership_transfer(
+    deps: DepsMut<'_>,
+    identity: IdentityKey,
+    new_owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let proposed_owner = storage::PENDING_OWNERSHIP_TRANSFERS
+        .may_load(deps.storage, identity.clone())?
+        .ok_or_else(|| MixnetContractError::NoPendingGatewayOwnershipTransfer {
+            identity: identity.clone(),
+        })?;
+
+    if new_owner != proposed_owner {
+        return Err(MixnetContractError::NotProposedOwner {
+            address: new_owner,
+            proposed: proposed_owner,
+        });
+    }
+
+    // the pending transfer is removed whenever the gateway unbonds
+    let existing_bond = storage::gateways()
+        .may_load(deps.storage, &identity)?
+        .ok_or_else(|| MixnetContractError::InconsistentState {
+            comment: format!(
+                "gateway {identity} has a pending ownership transfer, but is not bonded"
+            ),
+        })?;
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+
+    // the new owner can't end up with multiple nodes
+    ensure_no_existing_bond(deps.storage, &new_owner)?;
+
+    // replacing the bond also updates the owner index
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.owner = new_owner.clone();
+    storage::gateways().replace(
+        deps.storage,
+        &identity,
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+    storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, identity.clone());
+
+    let mut response = Response::new().add_event(new_gateway_ownership_transfer_event(
+        &identity,
+        &existing_bond.owner,
+        &new_owner,
+    ));
+
+    if let Some(proxy) = &existing_bond.proxy {
+        let msg = VestingContractExecuteMsg::TrackGatewayOwnershipTransfer {
+            owner: existing_bond.owner.into_string(),
+            new_owner: new_owner.into_string(),
+        };
+        let track_transfer_message = wasm_execute(proxy, &msg, vec![])?;
+        response = response.add_message(track_transfer_message);
+    }
+
+    Ok(response)
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::contract::execute;
-    use crate::gateways::transactions::try_add_gateway;
+    use crate::gateways::queries::query_pending_gateway_ownership_transfer;
+    use crate::gateways::storage;
+    use crate::gateways::transactions::{
+        try_accept_gateway_ownership_transfer, try_accept_gateway_ownership_transfer_on_behalf,
+        try_add_gateway, try_add_gateway_on_behalf, try_cancel_gateway_ownership_transfer,
+        try_cancel_gateway_ownership_transfer_on_behalf, try_propose_gateway_ownership_transfer,
+        try_propose_gateway_ownership_transfer_on_behalf, try_remove_gateway,
+        try_update_gateway_config, try_update_gateway_config_on_behalf, try_update_gateway_keys,
+        try_update_gateway_keys_on_behalf,
+    };
     use crate::interval::pending_events;
     use crate::mixnet_contract_settings::storage::minimum_gateway_pledge;
     use crate::support::tests;
     use crate::support::tests::fixtures::TEST_COIN_DENOM;
     use crate::support::tests::{fixtures, test_helpers};
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::{coin, Addr, BankMsg, Response, Uint128};
+    use cosmwasm_std::{coin, wasm_execute, Addr, BankMsg, Response, SubMsg, Uint128};
     use mixnet_contract_common::error::MixnetContractError;
     use mixnet_contract_common::events::new_gateway_unbonding_event;
-    use mixnet_contract_common::ExecuteMsg;
+    use mixnet_contract_common::{ExecuteMsg, GatewayConfigUpdate};
+    use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
     #[test]
     fn gateway_add() {
@@ -332,7 +693,7 @@ pub mod tests {
             deps.as_mut(),
             env,
             "fred",
-            tests::fixtures::good_gateway_pledge(),
+            fixtures::good_gateway_pledge(),
         );
 
         // let's make sure we now have 2 nodes:
@@ -346,7 +707,7 @@ pub mod tests {
         // we should see a funds transfer from the contract back to fred
         let expected_message = BankMsg::Send {
             to_address: String::from(info.sender),
-            amount: tests::fixtures::good_gateway_pledge(),
+            amount: fixtures::good_gateway_pledge(),
         };
 
         // run the executor and check that we got back the correct results
@@ -367,4 +728,521 @@ pub mod tests {
         assert_eq!(1, gateway_bonds.len());
         assert_eq!(&Addr::unchecked("bob"), gateway_bonds[0].owner());
     }
+
+    #[test]
+    fn updating_gateway_config() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let mut rng = test_helpers::test_rng();
+
+        let sender = "alice";
+        let info = mock_info(sender, &[]);
+        let update = GatewayConfigUpdate {
+            host: "1.1.1.1".to_string(),
+            mix_port: 1234,
+            clients_port: 1235,
+            location: "Neverland".to_string(),
+            version: "v1.2.3".to_string(),
+        };
+
+        // try updating a non existing gateway bond
+        let res = try_update_gateway_config(deps.as_mut(), info.clone(), update.clone());
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked(sender)
+            })
+        );
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env,
+            sender,
+            fixtures::good_gateway_pledge(),
+        );
+        let original = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+
+        // attempted to update on behalf with invalid proxy (current is `None`)
+        let res = try_update_gateway_config_on_behalf(
+            deps.as_mut(),
+            mock_info("proxy", &[]),
+            update.clone(),
+            sender.to_string(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "None".to_string(),
+                incoming: "proxy".to_string()
+            })
+        );
+
+        // "normal" update succeeds
+        let res = try_update_gateway_config(deps.as_mut(), info, update.clone());
+        assert!(res.is_ok());
+
+        // and the config has actually been updated
+        let gateway = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(gateway.gateway.host, update.host);
+        assert_eq!(gateway.gateway.mix_port, update.mix_port);
+        assert_eq!(gateway.gateway.clients_port, update.clients_port);
+        assert_eq!(gateway.gateway.location, update.location);
+        assert_eq!(gateway.gateway.version, update.version);
+
+        // while the rest of the bond remained intact
+        assert_eq!(gateway.gateway.identity_key, original.gateway.identity_key);
+        assert_eq!(gateway.gateway.sphinx_key, original.gateway.sphinx_key);
+        assert_eq!(gateway.block_height, original.block_height);
+        assert_eq!(gateway.pledge_amount, original.pledge_amount);
+    }
+
+    #[test]
+    fn updating_gateway_keys() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let mut rng = test_helpers::test_rng();
+
+        let sender = "alice";
+        let info = mock_info(sender, &[]);
+        let (new_gateway, signature) = test_helpers::gateway_with_signature(&mut rng, sender);
+        let new_identity = new_gateway.identity_key;
+        let new_sphinx_key = new_gateway.sphinx_key;
+
+        // try updating a non existing gateway bond
+        let res = try_update_gateway_keys(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            new_identity.clone(),
+            new_sphinx_key.clone(),
+            signature.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked(sender)
+            })
+        );
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env.clone(),
+            sender,
+            fixtures::good_gateway_pledge(),
+        );
+        let other_identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env.clone(),
+            "bob",
+            fixtures::good_gateway_pledge(),
+        );
+        let original = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+
+        // attempted to update on behalf with invalid proxy (current is `None`)
+        let res = try_update_gateway_keys_on_behalf(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("proxy", &[]),
+            new_identity.clone(),
+            new_sphinx_key.clone(),
+            sender.to_string(),
+            signature.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "None".to_string(),
+                incoming: "proxy".to_string()
+            })
+        );
+
+        // the keys have to actually change
+        let res = try_update_gateway_keys(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            original.gateway.identity_key.clone(),
+            original.gateway.sphinx_key.clone(),
+            signature.clone(),
+        );
+        assert_eq!(res, Err(MixnetContractError::UnchangedNodeKeys));
+
+        // and can't clash with another gateway
+        let res = try_update_gateway_keys(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            other_identity.clone(),
+            new_sphinx_key.clone(),
+            signature.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::DuplicateGateway {
+                owner: Addr::unchecked("bob")
+            })
+        );
+
+        // the signature must be made by the new identity key
+        let (_, bad_signature) = test_helpers::gateway_with_signature(&mut rng, sender);
+        let res = try_update_gateway_keys(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            new_identity.clone(),
+            new_sphinx_key.clone(),
+            bad_signature,
+        );
+        assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+
+        // valid update only takes effect once the epoch is over
+        let res = try_update_gateway_keys(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            new_identity.clone(),
+            new_sphinx_key.clone(),
+            signature,
+        );
+        assert!(res.is_ok());
+        let gateway = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(gateway, original);
+
+        test_helpers::execute_all_pending_events(deps.as_mut(), env);
+
+        assert!(storage::gateways()
+            .may_load(deps.as_ref().storage, &identity)
+            .unwrap()
+            .is_none());
+        let gateway = storage::gateways()
+            .load(deps.as_ref().storage, &new_identity)
+            .unwrap();
+        assert_eq!(gateway.gateway.identity_key, new_identity);
+        assert_eq!(gateway.gateway.sphinx_key, new_sphinx_key);
+
+        // while the rest of the bond remained intact
+        assert_eq!(gateway.owner, original.owner);
+        assert_eq!(gateway.gateway.host, original.gateway.host);
+        assert_eq!(gateway.block_height, original.block_height);
+        assert_eq!(gateway.pledge_amount, original.pledge_amount);
+    }
+
+    #[test]
+    fn transferring_gateway_ownership() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let mut rng = test_helpers::test_rng();
+
+        let owner = "alice";
+        let new_owner = "bob";
+        let owner_info = mock_info(owner, &[]);
+
+        // there's nothing to transfer without a bond
+        let res = try_propose_gateway_ownership_transfer(
+            deps.as_mut(),
+            owner_info.clone(),
+            new_owner.to_string(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked(owner)
+            })
+        );
+
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env.clone(),
+            owner,
+            fixtures::good_gateway_pledge(),
+        );
+        let original = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+
+        let res =
+            try_propose_gateway_ownership_transfer(deps.as_mut(), owner_info.clone(), owner.into());
+        assert_eq!(
+            res,
+            Err(MixnetContractError::OwnershipTransferToSelf {
+                owner: Addr::unchecked(owner)
+            })
+        );
+
+        // nothing to accept or cancel before the transfer is proposed
+        let no_pending = Err(MixnetContractError::NoPendingGatewayOwnershipTransfer {
+            identity: identity.clone(),
+        });
+        let res = try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info(new_owner, &[]),
+            identity.clone(),
+        );
+        assert_eq!(res, no_pending);
+        let res = try_cancel_gateway_ownership_transfer(deps.as_mut(), owner_info.clone());
+        assert_eq!(res, no_pending);
+
+        // a cancelled proposal can't be accepted
+        try_propose_gateway_ownership_transfer(
+            deps.as_mut(),
+            owner_info.clone(),
+            new_owner.to_string(),
+        )
+        .unwrap();
+        try_cancel_gateway_ownership_transfer(deps.as_mut(), owner_info.clone()).unwrap();
+        let res = try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info(new_owner, &[]),
+            identity.clone(),
+        );
+        assert_eq!(res, no_pending);
+
+        try_propose_gateway_ownership_transfer(
+            deps.as_mut(),
+            owner_info.clone(),
+            new_owner.to_string(),
+        )
+        .unwrap();
+        let pending = query_pending_gateway_ownership_transfer(deps.as_ref(), identity.clone())
+            .unwrap()
+            .new_owner;
+        assert_eq!(pending, Some(Addr::unchecked(new_owner)));
+
+        // only the proposed owner can accept it
+        let res = try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info("eve", &[]),
+            identity.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NotProposedOwner {
+                address: Addr::unchecked("eve"),
+                proposed: Addr::unchecked(new_owner),
+            })
+        );
+
+        try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info(new_owner, &[]),
+            identity.clone(),
+        )
+        .unwrap();
+        let pending = query_pending_gateway_ownership_transfer(deps.as_ref(), identity.clone())
+            .unwrap()
+            .new_owner;
+        assert!(pending.is_none());
+
+        // the bond is the same, apart from its owner
+        let gateway = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(gateway.owner, Addr::unchecked(new_owner));
+        assert_eq!(gateway.gateway, original.gateway);
+        assert_eq!(gateway.pledge_amount, original.pledge_amount);
+        assert_eq!(gateway.block_height, original.block_height);
+
+        // and it can only be unbonded by the new owner, who receives the pledge
+        let res = try_remove_gateway(deps.as_mut(), owner_info);
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NoAssociatedGatewayBond {
+                owner: Addr::unchecked(owner)
+            })
+        );
+        let res = try_remove_gateway(deps.as_mut(), mock_info(new_owner, &[])).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: new_owner.to_string(),
+                amount: vec![original.pledge_amount],
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn gateway_ownership_transfer_restrictions() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let mut rng = test_helpers::test_rng();
+
+        // the new owner can't already own a node
+        let identity = test_helpers::add_gateway(
+            &mut rng,
+            deps.as_mut(),
+            env.clone(),
+            "alice",
+            fixtures::good_gateway_pledge(),
+        );
+        test_helpers::add_mixnode(
+            &mut rng,
+            deps.as_mut(),
+            env,
+            "mix-owner",
+            tests::fixtures::good_mixnode_pledge(),
+        );
+        try_propose_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info("alice", &[]),
+            "mix-owner".to_string(),
+        )
+        .unwrap();
+        let res = try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info("mix-owner", &[]),
+            identity.clone(),
+        );
+        assert_eq!(res, Err(MixnetContractError::AlreadyOwnsMixnode));
+
+        // unbonding gets rid of the pending transfer
+        try_remove_gateway(deps.as_mut(), mock_info("alice", &[])).unwrap();
+        let pending = query_pending_gateway_ownership_transfer(deps.as_ref(), identity.clone())
+            .unwrap()
+            .new_owner;
+        assert!(pending.is_none());
+        let res = try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info("mix-owner", &[]),
+            identity.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::NoPendingGatewayOwnershipTransfer { identity })
+        );
+    }
+
+    #[test]
+    fn transferring_ownership_of_gateway_bonded_with_proxy() {
+        let mut deps = test_helpers::init_contract();
+        let env = mock_env();
+        let mut rng = test_helpers::test_rng();
+
+        let (gateway, signature) = test_helpers::gateway_with_signature(&mut rng, "vesting-owner");
+        let identity = gateway.identity_key.clone();
+        try_add_gateway_on_behalf(
+            deps.as_mut(),
+            env,
+            mock_info("proxy", &tests::fixtures::good_gateway_pledge()),
+            gateway,
+            "vesting-owner".to_string(),
+            signature,
+        )
+        .unwrap();
+
+        // the transfer can't bypass the proxy the gateway has been bonded with
+        let res = try_propose_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info("vesting-owner", &[]),
+            "bob".to_string(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "proxy".to_string(),
+                incoming: "None".to_string(),
+            })
+        );
+        let res = try_propose_gateway_ownership_transfer_on_behalf(
+            deps.as_mut(),
+            mock_info("other-proxy", &[]),
+            "vesting-owner".to_string(),
+            "bob".to_string(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "proxy".to_string(),
+                incoming: "other-proxy".to_string(),
+            })
+        );
+
+        try_propose_gateway_ownership_transfer_on_behalf(
+            deps.as_mut(),
+            mock_info("proxy", &[]),
+            "vesting-owner".to_string(),
+            "bob".to_string(),
+        )
+        .unwrap();
+
+        // and neither can the acceptance
+        let res = try_accept_gateway_ownership_transfer(
+            deps.as_mut(),
+            mock_info("bob", &[]),
+            identity.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "proxy".to_string(),
+                incoming: "None".to_string(),
+            })
+        );
+
+        let res = try_accept_gateway_ownership_transfer_on_behalf(
+            deps.as_mut(),
+            mock_info("proxy", &[]),
+            identity.clone(),
+            "bob".to_string(),
+        )
+        .unwrap();
+
+        // the proxy gets told to move the pledge to the vesting account of the new owner
+        let expected_track = wasm_execute(
+            "proxy",
+            &VestingContractExecuteMsg::TrackGatewayOwnershipTransfer {
+                owner: "vesting-owner".to_string(),
+                new_owner: "bob".to_string(),
+            },
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(expected_track)]);
+
+        // while the bond stays with the same proxy
+        let bond = storage::gateways()
+            .load(deps.as_ref().storage, &identity)
+            .unwrap();
+        assert_eq!(bond.owner, Addr::unchecked("bob"));
+        assert_eq!(bond.proxy, Some(Addr::unchecked("proxy")));
+
+        // which also has to be used for cancelling any further transfers
+        try_propose_gateway_ownership_transfer_on_behalf(
+            deps.as_mut(),
+            mock_info("proxy", &[]),
+            "bob".to_string(),
+            "alice".to_string(),
+        )
+        .unwrap();
+        let res = try_cancel_gateway_ownership_transfer(deps.as_mut(), mock_info("bob", &[]));
+        assert_eq!(
+            res,
+            Err(MixnetContractError::ProxyMismatch {
+                existing: "proxy".to_string(),
+                incoming: "None".to_string(),
+            })
+        );
+        try_cancel_gateway_ownership_transfer_on_behalf(
+            deps.as_mut(),
+            mock_info("proxy", &[]),
+            "bob".to_string(),
+        )
+        .unwrap();
+        let pending = query_pending_gateway_ownership_transfer(deps.as_ref(), identity)
+            .unwrap()
+            .new_owner;
+        assert!(pending.is_none());
+    }
 }
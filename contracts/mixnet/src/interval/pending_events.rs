@@ -3,27 +3,35 @@
 
 use crate::delegations;
 use crate::delegations::storage as delegations_storage;
+use crate::families::storage as families_storage;
+use crate::gateways::storage as gateways_storage;
 use crate::interval::helpers::change_interval_config;
 use crate::interval::storage;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
-use crate::mixnodes::helpers::{cleanup_post_unbond_mixnode_storage, get_mixnode_details_by_id};
+use crate::mixnodes::helpers::{
+    cleanup_post_unbond_mixnode_storage, ensure_keys_not_used_by_other_mixnode,
+    get_mixnode_details_by_id,
+};
 use crate::mixnodes::storage as mixnodes_storage;
 use crate::rewards::storage as rewards_storage;
 use crate::support::helpers::send_to_proxy_or_owner;
-use cosmwasm_std::{wasm_execute, Addr, Coin, DepsMut, Env, Response};
+use cosmwasm_std::{wasm_execute, Addr, Coin, Decimal, DepsMut, Env, Response, Storage};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
     new_active_set_update_event, new_delegation_event, new_delegation_on_unbonded_node_event,
-    new_mixnode_cost_params_update_event, new_mixnode_unbonding_event, new_pledge_increase_event,
-    new_rewarding_params_update_event, new_undelegation_event,
+    new_gateway_keys_update_event, new_mixnode_cost_params_update_event,
+    new_mixnode_keys_update_event, new_mixnode_unbonding_event, new_pledge_decrease_event,
+    new_pledge_increase_event, new_redelegation_event, new_redelegation_to_unbonded_node_event,
+    new_rejected_gateway_keys_update_event, new_rejected_mixnode_keys_update_event,
+    new_rejected_pledge_decrease_event, new_rewarding_params_update_event, new_undelegation_event,
 };
-use mixnet_contract_common::mixnode::MixNodeCostParams;
+use mixnet_contract_common::mixnode::{MixNodeCostParams, MixNodeRewarding};
 use mixnet_contract_common::pending_events::{
     PendingEpochEventData, PendingEpochEventKind, PendingIntervalEventData,
     PendingIntervalEventKind,
 };
 use mixnet_contract_common::reward_params::IntervalRewardingParamsUpdate;
-use mixnet_contract_common::{BlockHeight, Delegation, MixId};
+use mixnet_contract_common::{BlockHeight, Delegation, IdentityKey, MixId, SphinxKey};
 use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
 pub(crate) trait ContractExecutableEvent {
@@ -83,8 +91,39 @@ pub(crate) fn delegate(
     };
 
     let new_delegation_amount = amount.clone();
-    let mut mix_rewarding = mixnode_details.rewarding_details;
+    let unit_reward = increase_delegation(
+        deps.storage,
+        env,
+        owner.clone(),
+        mix_id,
+        amount,
+        proxy.clone(),
+        mixnode_details.rewarding_details,
+    )?;
+
+    Ok(Response::new().add_event(new_delegation_event(
+        created_at,
+        &owner,
+        &proxy,
+        &new_delegation_amount,
+        mix_id,
+        unit_reward,
+    )))
+}
 
+// Adds the tokens to the delegation of the owner towards the specified mixnode. If there's a
+// pre-existing delegation, it gets replaced by a fresh one containing the sum of both alongside
+// any rewards it might have accumulated.
+// Returns the unit reward of the mixnode at the time of creating the delegation.
+fn increase_delegation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: Addr,
+    mix_id: MixId,
+    amount: Coin,
+    proxy: Option<Addr>,
+    mut mix_rewarding: MixNodeRewarding,
+) -> Result<Decimal, MixnetContractError> {
     // the delegation_amount might get increased if there's already a pre-existing delegation on this mixnode
     // (in that case we just create a fresh delegation with the sum of both)
     let mut stored_delegation_amount = amount;
@@ -93,7 +132,7 @@ pub(crate) fn delegate(
     // with the sum of both
     let storage_key = Delegation::generate_storage_key(mix_id, &owner, proxy.as_ref());
     let old_delegation = if let Some(existing_delegation) =
-        delegations_storage::delegations().may_load(deps.storage, storage_key.clone())?
+        delegations_storage::delegations().may_load(storage, storage_key.clone())?
     {
         // completely remove the delegation from the node
         let og_with_reward = mix_rewarding.undelegate(&existing_delegation)?;
@@ -110,15 +149,6 @@ pub(crate) fn delegate(
     // add the amount we're intending to delegate (whether it's fresh or we're adding to the existing one)
     mix_rewarding.add_base_delegation(stored_delegation_amount.amount)?;
 
-    let cosmos_event = new_delegation_event(
-        created_at,
-        &owner,
-        &proxy,
-        &new_delegation_amount,
-        mix_id,
-        mix_rewarding.total_unit_reward,
-    );
-
     let delegation = Delegation::new(
         owner,
         mix_id,
@@ -130,14 +160,141 @@ pub(crate) fn delegate(
 
     // save on reading since `.save()` would have attempted to read old data that we already have on hand
     delegations_storage::delegations().replace(
-        deps.storage,
+        storage,
         storage_key,
         Some(&delegation),
         old_delegation.as_ref(),
     )?;
-    rewards_storage::MIXNODE_REWARDING.save(deps.storage, mix_id, &mix_rewarding)?;
+    rewards_storage::MIXNODE_REWARDING.save(storage, mix_id, &mix_rewarding)?;
 
-    Ok(Response::new().add_event(cosmos_event))
+    Ok(mix_rewarding.total_unit_reward)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn redelegate(
+    deps: DepsMut<'_>,
+    env: &Env,
+    created_at: BlockHeight,
+    owner: Addr,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    // see if the delegation still exists (it might have been removed by an undelegation request
+    // issued earlier in the same epoch)
+    let storage_key = Delegation::generate_storage_key(from_mix_id, &owner, proxy.as_ref());
+    let existing_delegation =
+        match delegations_storage::delegations().may_load(deps.storage, storage_key.clone())? {
+            None => return Ok(Response::default()),
+            Some(delegation) => delegation,
+        };
+
+    // check if the target node is still bonded. if not, the tokens simply stay where they are
+    let target_rewarding = match get_mixnode_details_by_id(deps.storage, to_mix_id)? {
+        Some(details)
+            if details.rewarding_details.still_bonded()
+                && !details.bond_information.is_unbonding =>
+        {
+            details.rewarding_details
+        }
+        _ => {
+            return Ok(
+                Response::new().add_event(new_redelegation_to_unbonded_node_event(
+                    &owner,
+                    &proxy,
+                    from_mix_id,
+                    to_mix_id,
+                )),
+            )
+        }
+    };
+
+    let mut source_rewarding = rewards_storage::MIXNODE_REWARDING
+        .may_load(deps.storage, from_mix_id)?
+        .ok_or(MixnetContractError::InconsistentState {
+            comment: "mixnode rewarding got removed from the storage whilst there's still an existing delegation"
+                .into(),
+        })?;
+
+    // completely remove the delegation, alongside all of its rewards, from the source node
+    let available = source_rewarding.undelegate(&existing_delegation)?;
+
+    // the amount was validated when the request was issued, but another redelegation from the same
+    // epoch might have already moved some of the tokens
+    let moved = Coin {
+        amount: amount.amount.min(available.amount),
+        denom: amount.denom,
+    };
+    let remaining = available.amount - moved.amount;
+
+    // and whatever is not moved stays with the source node as a fresh delegation
+    let remaining_delegation = if remaining.is_zero() {
+        None
+    } else {
+        source_rewarding.add_base_delegation(remaining)?;
+        Some(Delegation::new(
+            owner.clone(),
+            from_mix_id,
+            source_rewarding.total_unit_reward,
+            Coin {
+                amount: remaining,
+                denom: moved.denom.clone(),
+            },
+            env.block.height,
+            proxy.clone(),
+        ))
+    };
+    delegations_storage::delegations().replace(
+        deps.storage,
+        storage_key,
+        remaining_delegation.as_ref(),
+        Some(&existing_delegation),
+    )?;
+    rewards_storage::MIXNODE_REWARDING.save(deps.storage, from_mix_id, &source_rewarding)?;
+
+    let unit_reward = increase_delegation(
+        deps.storage,
+        env,
+        owner.clone(),
+        to_mix_id,
+        moved.clone(),
+        proxy.clone(),
+        target_rewarding,
+    )?;
+
+    let mut response = Response::new().add_event(new_redelegation_event(
+        created_at,
+        &owner,
+        &proxy,
+        &moved,
+        from_mix_id,
+        to_mix_id,
+        unit_reward,
+    ));
+
+    if let Some(proxy) = &proxy {
+        // we can only attempt to send the message to the vesting contract if the proxy IS the vesting contract
+        // otherwise, we don't care
+        let vesting_contract = mixnet_params_storage::vesting_contract_address(deps.storage)?;
+        if proxy == &vesting_contract {
+            let msg = VestingContractExecuteMsg::TrackRedelegation {
+                owner: owner.into_string(),
+                from_mix_id,
+                to_mix_id,
+                amount: moved.clone(),
+                remaining: Coin {
+                    amount: remaining,
+                    denom: moved.denom,
+                },
+            };
+
+            let track_redelegate_message = wasm_execute(proxy, &msg, vec![])?;
+            response = response.add_message(track_redelegate_message);
+        }
+    }
+
+    Ok(response)
 }
 
 pub(crate) fn undelegate(
@@ -295,6 +452,196 @@ pub(crate) fn increase_pledge(
     Ok(Response::new().add_event(new_pledge_increase_event(created_at, mix_id, &increase)))
 }
 
+pub(crate) fn decrease_pledge(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    mix_id: MixId,
+    decrease_by: Coin,
+) -> Result<Response, MixnetContractError> {
+    // the target node MUST exist - we have checked it at the time of putting this event onto the queue
+    // we have also verified there were no preceding unbond events
+    let mix_details = get_mixnode_details_by_id(deps.storage, mix_id)?.ok_or(
+        MixnetContractError::InconsistentState {
+            comment:
+                "mixnode getting processed to decrease its pledge doesn't exist in the storage"
+                    .into(),
+        },
+    )?;
+
+    // however, the pledge might have been decreased by another event from this epoch
+    // (or the minimum pledge might have been increased) so make sure it's still valid
+    let minimum_pledge = mixnet_params_storage::minimum_mixnode_pledge(deps.storage)?;
+    let current_pledge = mix_details.original_pledge();
+    if current_pledge.amount <= decrease_by.amount
+        || current_pledge.amount - decrease_by.amount < minimum_pledge.amount
+    {
+        return Ok(
+            Response::new().add_event(new_rejected_pledge_decrease_event(created_at, mix_id))
+        );
+    }
+
+    let mut updated_bond = mix_details.bond_information.clone();
+    let mut updated_rewarding = mix_details.rewarding_details;
+
+    updated_bond.original_pledge.amount -= decrease_by.amount;
+    updated_rewarding.decrease_operator_uint128(decrease_by.amount)?;
+
+    // update both, bond information and rewarding details
+    mixnodes_storage::mixnode_bonds().replace(
+        deps.storage,
+        mix_id,
+        Some(&updated_bond),
+        Some(&mix_details.bond_information),
+    )?;
+    rewards_storage::MIXNODE_REWARDING.save(deps.storage, mix_id, &updated_rewarding)?;
+
+    // and return the tokens back to the operator
+    let owner = &mix_details.bond_information.owner;
+    let proxy = &mix_details.bond_information.proxy;
+    let return_tokens = send_to_proxy_or_owner(proxy, owner, vec![decrease_by.clone()]);
+    let mut response = Response::new()
+        .add_message(return_tokens)
+        .add_event(new_pledge_decrease_event(created_at, mix_id, &decrease_by));
+
+    if let Some(proxy) = proxy {
+        // we can only attempt to send the message to the vesting contract if the proxy IS the vesting contract
+        // otherwise, we don't care
+        let vesting_contract = mixnet_params_storage::vesting_contract_address(deps.storage)?;
+        if proxy == &vesting_contract {
+            let msg = VestingContractExecuteMsg::TrackDecreasePledge {
+                owner: owner.to_string(),
+                amount: decrease_by,
+            };
+
+            let track_decrease_pledge_message = wasm_execute(proxy, &msg, vec![])?;
+            response = response.add_message(track_decrease_pledge_message);
+        }
+    }
+
+    Ok(response)
+}
+
+pub(crate) fn update_mixnode_keys(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    mix_id: MixId,
+    owner: Addr,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+) -> Result<Response, MixnetContractError> {
+    // the node might have changed its owner since the request got created, in which case
+    // the new owner hasn't agreed to use those keys
+    let existing_bond = match mixnodes_storage::mixnode_bonds().may_load(deps.storage, mix_id)? {
+        Some(bond) if bond.owner == owner && !bond.is_unbonding => bond,
+        _ => {
+            return Ok(Response::new()
+                .add_event(new_rejected_mixnode_keys_update_event(created_at, mix_id)))
+        }
+    };
+
+    // and some other node might have been bonded with the same keys in the meantime
+    match ensure_keys_not_used_by_other_mixnode(
+        deps.storage,
+        mix_id,
+        &new_identity_key,
+        &new_sphinx_key,
+    ) {
+        Err(MixnetContractError::DuplicateMixnodeKey { .. }) => {
+            return Ok(Response::new()
+                .add_event(new_rejected_mixnode_keys_update_event(created_at, mix_id)))
+        }
+        res => res?,
+    }
+
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.mix_node.identity_key = new_identity_key;
+    updated_bond.mix_node.sphinx_key = new_sphinx_key;
+
+    // replacing the bond also updates the identity and sphinx key indices
+    mixnodes_storage::mixnode_bonds().replace(
+        deps.storage,
+        mix_id,
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+    families_storage::migrate_node_identity(
+        deps.storage,
+        existing_bond.identity(),
+        updated_bond.identity(),
+    )?;
+
+    Ok(Response::new().add_event(new_mixnode_keys_update_event(
+        created_at,
+        mix_id,
+        updated_bond.identity(),
+        &updated_bond.mix_node.sphinx_key,
+    )))
+}
+
+pub(crate) fn update_gateway_keys(
+    deps: DepsMut<'_>,
+    created_at: BlockHeight,
+    identity: IdentityKey,
+    owner: Addr,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+) -> Result<Response, MixnetContractError> {
+    // gateways unbond immediately, so the gateway might be gone by now (or even re-bonded by
+    // somebody else), and it could have also changed its owner
+    let existing_bond = match gateways_storage::gateways().may_load(deps.storage, &identity)? {
+        Some(bond) if bond.owner == owner => bond,
+        _ => {
+            return Ok(
+                Response::new().add_event(new_rejected_gateway_keys_update_event(
+                    created_at, &identity,
+                )),
+            )
+        }
+    };
+
+    let identity_changes = identity != new_identity_key;
+    if identity_changes
+        && gateways_storage::gateways()
+            .may_load(deps.storage, &new_identity_key)?
+            .is_some()
+    {
+        return Ok(
+            Response::new().add_event(new_rejected_gateway_keys_update_event(
+                created_at, &identity,
+            )),
+        );
+    }
+
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.gateway.identity_key = new_identity_key;
+    updated_bond.gateway.sphinx_key = new_sphinx_key;
+
+    // the identity is the primary key of the gateway, so the bond has to be moved
+    gateways_storage::gateways().replace(deps.storage, &identity, None, Some(&existing_bond))?;
+    gateways_storage::gateways().save(deps.storage, updated_bond.identity(), &updated_bond)?;
+
+    // and so does any pending ownership transfer
+    if identity_changes {
+        if let Some(new_owner) = gateways_storage::PENDING_OWNERSHIP_TRANSFERS
+            .may_load(deps.storage, identity.clone())?
+        {
+            gateways_storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, identity.clone());
+            gateways_storage::PENDING_OWNERSHIP_TRANSFERS.save(
+                deps.storage,
+                updated_bond.identity().to_string(),
+                &new_owner,
+            )?;
+        }
+    }
+
+    Ok(Response::new().add_event(new_gateway_keys_update_event(
+        created_at,
+        &identity,
+        updated_bond.identity(),
+        &updated_bond.gateway.sphinx_key,
+    )))
+}
+
 impl ContractExecutableEvent for PendingEpochEventData {
     fn execute(self, deps: DepsMut<'_>, env: &Env) -> Result<Response, MixnetContractError> {
         // note that the basic validation on all those events was already performed before
@@ -311,12 +658,58 @@ impl ContractExecutableEvent for PendingEpochEventData {
                 mix_id,
                 proxy,
             } => undelegate(deps, self.created_at, owner, mix_id, proxy),
+            PendingEpochEventKind::Redelegate {
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            } => redelegate(
+                deps,
+                env,
+                self.created_at,
+                owner,
+                from_mix_id,
+                to_mix_id,
+                amount,
+                proxy,
+            ),
             PendingEpochEventKind::PledgeMore { mix_id, amount } => {
                 increase_pledge(deps, self.created_at, mix_id, amount)
             }
+            PendingEpochEventKind::DecreasePledge {
+                mix_id,
+                decrease_by,
+            } => decrease_pledge(deps, self.created_at, mix_id, decrease_by),
             PendingEpochEventKind::UnbondMixnode { mix_id } => {
                 unbond_mixnode(deps, env, self.created_at, mix_id)
             }
+            PendingEpochEventKind::UpdateMixnodeKeys {
+                mix_id,
+                owner,
+                new_identity_key,
+                new_sphinx_key,
+            } => update_mixnode_keys(
+                deps,
+                self.created_at,
+                mix_id,
+                owner,
+                new_identity_key,
+                new_sphinx_key,
+            ),
+            PendingEpochEventKind::UpdateGatewayKeys {
+                identity,
+                owner,
+                new_identity_key,
+                new_sphinx_key,
+            } => update_gateway_keys(
+                deps,
+                self.created_at,
+                identity,
+                owner,
+                new_identity_key,
+                new_sphinx_key,
+            ),
             PendingEpochEventKind::UpdateActiveSetSize { new_size } => {
                 update_active_set_size(deps, self.created_at, new_size)
             }
@@ -1028,43 +1421,477 @@ mod tests {
     }
 
     #[cfg(test)]
-    mod mixnode_unbonding {
+    mod redelegating {
         use super::*;
-        use crate::mixnodes::storage as mixnodes_storage;
+        use crate::mixnodes::transactions::try_remove_mixnode;
         use crate::support::tests::fixtures::TEST_COIN_DENOM;
         use crate::support::tests::test_helpers::get_bank_send_msg;
+        use cosmwasm_std::testing::mock_info;
         use cosmwasm_std::{coin, to_binary, CosmosMsg, Uint128, WasmMsg};
-        use mixnet_contract_common::mixnode::UnbondedMixnode;
         use mixnet_contract_common::rewarding::helpers::truncate_reward_amount;
+        use rand_chacha::rand_core::RngCore;
+
+        fn may_read_delegation(
+            test: &TestSetup,
+            mix_id: MixId,
+            owner: &str,
+            proxy: Option<&Addr>,
+        ) -> Option<Delegation> {
+            let storage_key =
+                Delegation::generate_storage_key(mix_id, &Addr::unchecked(owner), proxy);
+            delegations_storage::delegations()
+                .may_load(test.deps().storage, storage_key)
+                .unwrap()
+        }
+
+        // the amount the delegator would have received if they undelegated right now
+        fn delegation_value(test: &TestSetup, mix_id: MixId, owner: &str) -> Uint128 {
+            match may_read_delegation(test, mix_id, owner, None) {
+                None => Uint128::zero(),
+                Some(delegation) => {
+                    let reward = test
+                        .mix_rewarding(mix_id)
+                        .determine_delegation_reward(&delegation)
+                        .unwrap();
+                    truncate_reward_amount(reward + delegation.dec_amount().unwrap())
+                }
+            }
+        }
+
+        // uniformly-ish random value in [0, upper)
+        fn random_below(rng: &mut impl RngCore, upper: u128) -> u128 {
+            rng.next_u64() as u128 % upper
+        }
+
+        fn total_node_stake(test: &TestSetup, mix_ids: &[MixId]) -> Decimal {
+            mix_ids
+                .iter()
+                .map(|mix_id| {
+                    let rewarding = test.mix_rewarding(*mix_id);
+                    rewarding.operator + rewarding.delegates
+                })
+                .fold(Decimal::zero(), |acc, stake| acc + stake)
+        }
 
         #[test]
-        fn returns_hard_error_if_mixnode_doesnt_exist() {
-            // this should have never happened so hard error MUST be thrown here
+        fn does_nothing_if_delegation_doesnt_exist() {
             let mut test = TestSetup::new();
-            let env = test.env();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
 
-            let res = unbond_mixnode(test.deps_mut(), &env, 123, 1);
-            assert!(matches!(
-                res,
-                Err(MixnetContractError::InconsistentState { .. })
-            ));
+            let env = test.env();
+            let res = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked("delegator"),
+                mix_id1,
+                mix_id2,
+                coin(100_000_000, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+            assert_eq!(res, Response::default());
+            assert!(may_read_delegation(&test, mix_id2, "delegator", None).is_none());
         }
 
         #[test]
-        fn returns_original_pledge_alongside_any_earned_rewards() {
+        fn leaves_the_delegation_in_place_if_target_has_unbonded() {
             let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+            let mix_id3 = test.add_dummy_mixnode("mix-owner3", None);
 
-            let owner = "mix-owner";
-            let pledge = Uint128::new(250_000_000);
-            let mix_id = test.add_dummy_mixnode(owner, Some(pledge));
-            let mix_details = mixnodes_storage::mixnode_bonds()
-                .load(test.deps().storage, mix_id)
+            let delegation = 120_000_000u128;
+            let owner = "delegator";
+            test.add_immediate_delegation(owner, delegation, mix_id1);
+            let before = test.mix_rewarding(mix_id1);
+
+            test.immediately_unbond_mixnode(mix_id2);
+            let env = test.env();
+            try_remove_mixnode(test.deps_mut(), env.clone(), mock_info("mix-owner3", &[])).unwrap();
+
+            for target in [mix_id2, mix_id3] {
+                let res = redelegate(
+                    test.deps_mut(),
+                    &env,
+                    123,
+                    Addr::unchecked(owner),
+                    mix_id1,
+                    target,
+                    coin(delegation, TEST_COIN_DENOM),
+                    None,
+                )
                 .unwrap();
-            let layer = mix_details.layer;
 
-            test.update_rewarded_set(vec![mix_id]);
+                // no tokens are moving anywhere
+                assert!(res.messages.is_empty());
+                assert!(may_read_delegation(&test, target, owner, None).is_none());
+                assert_eq!(
+                    test.delegation(mix_id1, owner, &None).amount,
+                    coin(delegation, TEST_COIN_DENOM)
+                );
+                assert_eq!(test.mix_rewarding(mix_id1), before);
+            }
+        }
+
+        #[test]
+        fn moves_the_entire_delegation_with_earned_rewards() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", Some(100_000_000_000u128.into()));
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", Some(100_000_000_000u128.into()));
+
+            let owner = "delegator";
+            let delegation = 120_000_000u128;
+            test.add_immediate_delegation(owner, delegation, mix_id1);
+
+            test.update_rewarded_set(vec![mix_id1, mix_id2]);
             test.skip_to_next_epoch_end();
-            let dist1 = test.reward_with_distribution(mix_id, test_helpers::performance(100.0));
+            let dist1 = test.reward_with_distribution(mix_id1, test_helpers::performance(100.0));
+            test.skip_to_next_epoch_end();
+            let dist2 = test.reward_with_distribution(mix_id1, test_helpers::performance(100.0));
+
+            let expected_amount =
+                delegation + truncate_reward_amount(dist1.delegates + dist2.delegates).u128();
+
+            let env = test.env();
+            let res = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner),
+                mix_id1,
+                mix_id2,
+                coin(expected_amount, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+            assert!(res.messages.is_empty());
+
+            // the delegation is gone from the original node
+            assert!(may_read_delegation(&test, mix_id1, owner, None).is_none());
+            let source = test.mix_rewarding(mix_id1);
+            assert!(source.delegates.is_zero());
+            assert_eq!(source.unique_delegations, 0);
+
+            // and exists on the new node, with all of the rewards included
+            let moved = test.delegation(mix_id2, owner, &None);
+            assert_eq!(moved.amount, coin(expected_amount, TEST_COIN_DENOM));
+            let target = test.mix_rewarding(mix_id2);
+            assert_eq!(moved.cumulative_reward_ratio, target.total_unit_reward);
+            assert_eq!(
+                target.delegates,
+                Decimal::from_atomics(expected_amount, 0).unwrap()
+            );
+            assert_eq!(target.unique_delegations, 1);
+        }
+
+        #[test]
+        fn partial_redelegation_keeps_the_remainder_on_the_original_node() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", Some(100_000_000_000u128.into()));
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", Some(100_000_000_000u128.into()));
+
+            let owner = "delegator";
+            test.add_immediate_delegation(owner, 120_000_000u128, mix_id1);
+            test.add_immediate_delegation(owner, 50_000_000u128, mix_id2);
+
+            test.update_rewarded_set(vec![mix_id1, mix_id2]);
+            test.skip_to_next_epoch_end();
+            test.reward_with_distribution(mix_id1, test_helpers::performance(100.0));
+            test.reward_with_distribution(mix_id2, test_helpers::performance(100.0));
+
+            let source_value = delegation_value(&test, mix_id1, owner);
+            let target_value = delegation_value(&test, mix_id2, owner);
+
+            let to_move = 70_000_000u128;
+            let env = test.env();
+            redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner),
+                mix_id1,
+                mix_id2,
+                coin(to_move, TEST_COIN_DENOM),
+                None,
+            )
+            .unwrap();
+
+            // whatever remained (alongside the rewards) got re-delegated to the original node
+            let remaining = test.delegation(mix_id1, owner, &None);
+            assert_eq!(
+                remaining.amount.amount,
+                source_value - Uint128::new(to_move)
+            );
+            assert_eq!(test.mix_rewarding(mix_id1).unique_delegations, 1);
+
+            // and the moved tokens got merged with the existing delegation
+            let merged = test.delegation(mix_id2, owner, &None);
+            assert_eq!(merged.amount.amount, target_value + Uint128::new(to_move));
+            assert_eq!(test.mix_rewarding(mix_id2).unique_delegations, 1);
+        }
+
+        #[test]
+        fn only_moves_whats_still_available() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+            let mix_id3 = test.add_dummy_mixnode("mix-owner3", None);
+
+            // the delegator issued two redelegations in the same epoch that, together,
+            // exceed the delegated amount
+            let owner = "delegator";
+            test.add_immediate_delegation(owner, 100_000_000u128, mix_id1);
+
+            let env = test.env();
+            for target in [mix_id2, mix_id3] {
+                redelegate(
+                    test.deps_mut(),
+                    &env,
+                    123,
+                    Addr::unchecked(owner),
+                    mix_id1,
+                    target,
+                    coin(60_000_000, TEST_COIN_DENOM),
+                    None,
+                )
+                .unwrap();
+            }
+
+            assert!(may_read_delegation(&test, mix_id1, owner, None).is_none());
+            assert_eq!(
+                test.delegation(mix_id2, owner, &None).amount,
+                coin(60_000_000, TEST_COIN_DENOM)
+            );
+            assert_eq!(
+                test.delegation(mix_id3, owner, &None).amount,
+                coin(40_000_000, TEST_COIN_DENOM)
+            );
+        }
+
+        #[test]
+        fn attaches_vesting_contract_track_message() {
+            let mut test = TestSetup::new();
+            let mix_id1 = test.add_dummy_mixnode("mix-owner1", None);
+            let mix_id2 = test.add_dummy_mixnode("mix-owner2", None);
+
+            let owner1 = "delegator1";
+            let owner2 = "delegator2";
+            let vesting_contract = test.vesting_contract();
+            let dummy_proxy = Addr::unchecked("not-vesting-contract");
+
+            test.add_immediate_delegation_with_proxy(
+                owner1,
+                100_000_000u128,
+                mix_id1,
+                vesting_contract.clone(),
+            );
+            test.add_immediate_delegation_with_proxy(
+                owner2,
+                100_000_000u128,
+                mix_id1,
+                dummy_proxy.clone(),
+            );
+
+            let env = test.env();
+            let res_vesting = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner1),
+                mix_id1,
+                mix_id2,
+                coin(30_000_000, TEST_COIN_DENOM),
+                Some(vesting_contract.clone()),
+            )
+            .unwrap();
+
+            // no tokens are sent anywhere, but the vesting contract is informed about the change
+            assert!(get_bank_send_msg(&res_vesting).is_none());
+            assert_eq!(res_vesting.messages.len(), 1);
+            match &res_vesting.messages[0].msg {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg,
+                    funds,
+                }) => {
+                    assert_eq!(contract_addr, vesting_contract.as_str());
+                    let expected_msg = to_binary(&VestingContractExecuteMsg::TrackRedelegation {
+                        owner: owner1.to_string(),
+                        from_mix_id: mix_id1,
+                        to_mix_id: mix_id2,
+                        amount: coin(30_000_000, TEST_COIN_DENOM),
+                        remaining: coin(70_000_000, TEST_COIN_DENOM),
+                    })
+                    .unwrap();
+                    assert_eq!(&expected_msg, msg);
+                    assert!(funds.is_empty())
+                }
+                _ => panic!("unexpected message"),
+            }
+            assert!(may_read_delegation(&test, mix_id2, owner1, Some(&vesting_contract)).is_some());
+
+            let res_other_proxy = redelegate(
+                test.deps_mut(),
+                &env,
+                123,
+                Addr::unchecked(owner2),
+                mix_id1,
+                mix_id2,
+                coin(30_000_000, TEST_COIN_DENOM),
+                Some(dummy_proxy.clone()),
+            )
+            .unwrap();
+            assert!(res_other_proxy.messages.is_empty());
+            assert!(may_read_delegation(&test, mix_id2, owner2, Some(&dummy_proxy)).is_some());
+        }
+
+        #[test]
+        fn total_stake_is_conserved_across_random_redelegations() {
+            let mut test = TestSetup::new();
+            let mix_ids = (0..4)
+                .map(|i| {
+                    test.add_dummy_mixnode(
+                        &format!("mix-owner{i}"),
+                        Some(100_000_000_000u128.into()),
+                    )
+                })
+                .collect::<Vec<_>>();
+            test.update_rewarded_set(mix_ids.clone());
+
+            let delegators = ["delegator1", "delegator2", "delegator3"];
+            for delegator in delegators {
+                for mix_id in &mix_ids {
+                    let amount = 1_000_000 + random_below(&mut test.rng, 1_000_000_000);
+                    test.add_immediate_delegation(delegator, amount, *mix_id);
+                }
+            }
+
+            for _ in 0..50 {
+                // sometimes distribute rewards, so that the delegations would have accumulated
+                // some before getting moved
+                if random_below(&mut test.rng, 10) < 3 {
+                    test.skip_to_next_epoch_end();
+                    for mix_id in &mix_ids {
+                        let performance = 50 + random_below(&mut test.rng, 51);
+                        test.reward_with_distribution(
+                            *mix_id,
+                            test_helpers::performance(performance as f32),
+                        );
+                    }
+                }
+
+                let delegator = delegators[random_below(&mut test.rng, 3) as usize];
+                let from = mix_ids[random_below(&mut test.rng, 4) as usize];
+                let to = loop {
+                    let to = mix_ids[random_below(&mut test.rng, 4) as usize];
+                    if to != from {
+                        break to;
+                    }
+                };
+
+                let total_before = mix_ids
+                    .iter()
+                    .map(|mix_id| delegation_value(&test, *mix_id, delegator))
+                    .fold(Uint128::zero(), |acc, value| acc + value);
+                let available = delegation_value(&test, from, delegator);
+                let stake_before = total_node_stake(&test, &mix_ids);
+
+                // occasionally attempt to move more than available or the whole thing
+                let amount = match random_below(&mut test.rng, 4) {
+                    0 => available.u128() * 2 + 1,
+                    1 => available.u128(),
+                    _ => 1 + random_below(&mut test.rng, available.u128().max(1)),
+                };
+
+                let env = test.env();
+                let res = redelegate(
+                    test.deps_mut(),
+                    &env,
+                    123,
+                    Addr::unchecked(delegator),
+                    from,
+                    to,
+                    coin(amount, TEST_COIN_DENOM),
+                    None,
+                )
+                .unwrap();
+
+                // no tokens ever leave the contract
+                assert!(res.messages.is_empty());
+
+                // the delegator hasn't lost (or gained) anything
+                let total_after = mix_ids
+                    .iter()
+                    .map(|mix_id| delegation_value(&test, *mix_id, delegator))
+                    .fold(Uint128::zero(), |acc, value| acc + value);
+                assert_eq!(total_before, total_after);
+                let moved = amount.min(available.u128());
+                assert_eq!(
+                    delegation_value(&test, from, delegator),
+                    available - Uint128::new(moved)
+                );
+
+                // and neither did the nodes, apart from the truncated decimal dust
+                // of (at most) two delegations
+                let stake_after = total_node_stake(&test, &mix_ids);
+                assert!(stake_after <= stake_before);
+                assert!(stake_before - stake_after < Decimal::from_atomics(2u32, 0).unwrap());
+
+                // finally, the delegation counters have to match the actual storage
+                for mix_id in &mix_ids {
+                    let existing = delegators
+                        .iter()
+                        .filter(|d| may_read_delegation(&test, *mix_id, d, None).is_some())
+                        .count();
+                    assert_eq!(
+                        test.mix_rewarding(*mix_id).unique_delegations as usize,
+                        existing
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod mixnode_unbonding {
+        use super::*;
+        use crate::mixnodes::storage as mixnodes_storage;
+        use crate::support::tests::fixtures::TEST_COIN_DENOM;
+        use crate::support::tests::test_helpers::get_bank_send_msg;
+        use cosmwasm_std::{coin, to_binary, CosmosMsg, Uint128, WasmMsg};
+        use mixnet_contract_common::mixnode::UnbondedMixnode;
+        use mixnet_contract_common::rewarding::helpers::truncate_reward_amount;
+
+        #[test]
+        fn returns_hard_error_if_mixnode_doesnt_exist() {
+            // this should have never happened so hard error MUST be thrown here
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let res = unbond_mixnode(test.deps_mut(), &env, 123, 1);
+            assert!(matches!(
+                res,
+                Err(MixnetContractError::InconsistentState { .. })
+            ));
+        }
+
+        #[test]
+        fn returns_original_pledge_alongside_any_earned_rewards() {
+            let mut test = TestSetup::new();
+
+            let owner = "mix-owner";
+            let pledge = Uint128::new(250_000_000);
+            let mix_id = test.add_dummy_mixnode(owner, Some(pledge));
+            let mix_details = mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            let layer = mix_details.layer;
+
+            test.update_rewarded_set(vec![mix_id]);
+            test.skip_to_next_epoch_end();
+            let dist1 = test.reward_with_distribution(mix_id, test_helpers::performance(100.0));
             test.skip_to_next_epoch_end();
             let dist2 = test.reward_with_distribution(mix_id, test_helpers::performance(100.0));
 
@@ -1412,6 +2239,568 @@ mod tests {
         assert_eq!(updated.active_set_size, 50)
     }
 
+    #[cfg(test)]
+    mod decreasing_pledge {
+        use super::*;
+        use crate::mixnet_contract_settings::storage::minimum_mixnode_pledge;
+        use crate::support::tests::test_helpers::get_bank_send_msg;
+        use cosmwasm_std::{to_binary, CosmosMsg, Uint128, WasmMsg};
+
+        #[test]
+        fn returns_hard_error_if_mixnode_doesnt_exist() {
+            // this should have never happened so hard error MUST be thrown here
+            let mut test = TestSetup::new();
+
+            let amount = test.coin(123);
+            let res = decrease_pledge(test.deps_mut(), 123, 1, amount);
+            assert!(matches!(
+                res,
+                Err(MixnetContractError::InconsistentState { .. })
+            ));
+        }
+
+        #[test]
+        fn updates_stored_bond_information_and_rewarding_details() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", Some(Uint128::new(200_000_000_000)));
+
+            let old_details = get_mixnode_details_by_id(test.deps().storage, mix_id)
+                .unwrap()
+                .unwrap();
+
+            let amount = test.coin(12345);
+            let res = decrease_pledge(test.deps_mut(), 123, mix_id, amount.clone()).unwrap();
+
+            let updated_details = get_mixnode_details_by_id(test.deps().storage, mix_id)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                updated_details.bond_information.original_pledge.amount,
+                old_details.bond_information.original_pledge.amount - amount.amount
+            );
+
+            assert_eq!(
+                updated_details.rewarding_details.operator,
+                old_details.rewarding_details.operator
+                    - Decimal::from_atomics(amount.amount, 0).unwrap()
+            );
+
+            // and the tokens are returned back to the operator
+            let (receiver, sent_amount) = get_bank_send_msg(&res).unwrap();
+            assert_eq!(receiver, "mix-owner");
+            assert_eq!(sent_amount[0], amount);
+        }
+
+        #[test]
+        fn is_rejected_if_pledge_would_fall_below_the_minimum() {
+            let mut test = TestSetup::new();
+            let minimum = minimum_mixnode_pledge(test.deps().storage).unwrap();
+            let pledge = minimum.amount + Uint128::new(100_000_000);
+            let mix_id = test.add_dummy_mixnode("mix-owner", Some(pledge));
+
+            // another decrease from the same epoch has already been applied
+            decrease_pledge(test.deps_mut(), 123, mix_id, test.coin(60_000_000)).unwrap();
+
+            let old_details = get_mixnode_details_by_id(test.deps().storage, mix_id)
+                .unwrap()
+                .unwrap();
+            let res = decrease_pledge(test.deps_mut(), 123, mix_id, test.coin(60_000_000)).unwrap();
+            assert!(res.messages.is_empty());
+            assert_eq!(
+                res.events,
+                vec![new_rejected_pledge_decrease_event(123, mix_id)]
+            );
+            assert_ne!(
+                res.events[0].ty,
+                new_pledge_decrease_event(123, mix_id, &test.coin(60_000_000)).ty
+            );
+
+            let updated_details = get_mixnode_details_by_id(test.deps().storage, mix_id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(old_details, updated_details);
+        }
+
+        #[test]
+        fn attaches_vesting_contract_track_message() {
+            let mut test = TestSetup::new();
+            let vesting_contract = test.vesting_contract();
+            let mix_id = test.add_dummy_mixnode_with_proxy(
+                "mix-owner",
+                Some(Uint128::new(200_000_000_000)),
+                vesting_contract.clone(),
+            );
+
+            let amount = test.coin(50_000_000);
+            let res = decrease_pledge(test.deps_mut(), 123, mix_id, amount.clone()).unwrap();
+
+            // tokens are sent back to the proxy
+            let (receiver, sent_amount) = get_bank_send_msg(&res).unwrap();
+            assert_eq!(receiver, vesting_contract.as_str());
+            assert_eq!(sent_amount[0], amount);
+
+            // alongside the track message
+            let mut found_track = false;
+            for msg in &res.messages {
+                if let CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg,
+                    funds,
+                }) = &msg.msg
+                {
+                    found_track = true;
+                    assert_eq!(contract_addr, vesting_contract.as_str());
+                    let expected_msg = to_binary(&VestingContractExecuteMsg::TrackDecreasePledge {
+                        owner: "mix-owner".to_string(),
+                        amount: amount.clone(),
+                    })
+                    .unwrap();
+                    assert_eq!(&expected_msg, msg);
+                    assert!(funds.is_empty())
+                }
+            }
+            assert!(found_track);
+        }
+
+        #[test]
+        fn without_any_events_in_between_is_equivalent_to_pledging_the_same_amount_immediately() {
+            let mut test = TestSetup::new();
+            let pledge1 = Uint128::new(250_000_000);
+            let pledge2 = Uint128::new(50_000_000);
+            let pledge3 = Uint128::new(200_000_000);
+
+            let mix_id_decreased = test.add_dummy_mixnode("mix-owner1", Some(pledge1));
+            let decrease = test.coin(pledge2.u128());
+            decrease_pledge(test.deps_mut(), 123, mix_id_decreased, decrease).unwrap();
+
+            let mix_id_full_pledge = test.add_dummy_mixnode("mix-owner2", Some(pledge3));
+
+            test.add_immediate_delegation("alice", 123_456_789u128, mix_id_decreased);
+            test.add_immediate_delegation("bob", 500_000_000u128, mix_id_decreased);
+
+            test.add_immediate_delegation("alice", 123_456_789u128, mix_id_full_pledge);
+            test.add_immediate_delegation("bob", 500_000_000u128, mix_id_full_pledge);
+
+            test.skip_to_next_epoch_end();
+            test.update_rewarded_set(vec![mix_id_decreased, mix_id_full_pledge]);
+
+            let dist1 =
+                test.reward_with_distribution(mix_id_decreased, test_helpers::performance(100.0));
+            let dist2 =
+                test.reward_with_distribution(mix_id_full_pledge, test_helpers::performance(100.0));
+
+            assert_eq!(dist1, dist2)
+        }
+    }
+
+    #[cfg(test)]
+    mod updating_mixnode_keys {
+        use super::*;
+        use crate::families::storage::{add_family_member, create_family, get_family, MEMBERS};
+        use cosmwasm_std::Uint128;
+        use mixnet_contract_common::families::{Family, FamilyHead};
+
+        fn new_keys(test: &mut TestSetup) -> (IdentityKey, SphinxKey) {
+            let (mix_node, _, _) = test_helpers::mixnode_with_signature(&mut test.rng, "foomp");
+            (mix_node.identity_key, mix_node.sphinx_key)
+        }
+
+        fn identity_of(test: &TestSetup, mix_id: MixId) -> IdentityKey {
+            mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap()
+                .mix_node
+                .identity_key
+        }
+
+        #[test]
+        fn replaces_keys_and_migrates_indices() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", Some(Uint128::new(200_000_000_000)));
+            test.add_immediate_delegation("alice", 123_456_789u128, mix_id);
+
+            let old_details = get_mixnode_details_by_id(test.deps().storage, mix_id)
+                .unwrap()
+                .unwrap();
+            let old_identity = old_details.bond_information.mix_node.identity_key.clone();
+            let old_sphinx_key = old_details.bond_information.mix_node.sphinx_key.clone();
+
+            let (identity, sphinx_key) = new_keys(&mut test);
+            update_mixnode_keys(
+                test.deps_mut(),
+                123,
+                mix_id,
+                Addr::unchecked("mix-owner"),
+                identity.clone(),
+                sphinx_key.clone(),
+            )
+            .unwrap();
+
+            let updated_details = get_mixnode_details_by_id(test.deps().storage, mix_id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                updated_details.bond_information.mix_node.identity_key,
+                identity
+            );
+            assert_eq!(
+                updated_details.bond_information.mix_node.sphinx_key,
+                sphinx_key
+            );
+
+            // the stake and the rest of the node's history is untouched
+            let mut expected_bond = old_details.bond_information.clone();
+            expected_bond.mix_node.identity_key = identity.clone();
+            expected_bond.mix_node.sphinx_key = sphinx_key.clone();
+            assert_eq!(updated_details.bond_information, expected_bond);
+            assert_eq!(
+                updated_details.rewarding_details,
+                old_details.rewarding_details
+            );
+
+            // the node can only be found via its new keys
+            let bonds = mixnodes_storage::mixnode_bonds();
+            let by_identity = bonds
+                .idx
+                .identity_key
+                .item(test.deps().storage, identity)
+                .unwrap();
+            assert_eq!(by_identity.unwrap().1.mix_id, mix_id);
+            let by_sphinx = bonds
+                .idx
+                .sphinx_key
+                .item(test.deps().storage, sphinx_key)
+                .unwrap();
+            assert_eq!(by_sphinx.unwrap().1.mix_id, mix_id);
+            assert!(bonds
+                .idx
+                .identity_key
+                .item(test.deps().storage, old_identity)
+                .unwrap()
+                .is_none());
+            assert!(bonds
+                .idx
+                .sphinx_key
+                .item(test.deps().storage, old_sphinx_key)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn is_rejected_if_node_changed_owner_or_unbonded() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let unbonding_mix_id = test.add_dummy_mixnode("unbonding-owner", None);
+            test.start_unbonding_mixnode(unbonding_mix_id);
+            let unbonded_mix_id = test.add_dummy_mixnode("unbonded-owner", None);
+            test.immediately_unbond_mixnode(unbonded_mix_id);
+
+            let cases = [
+                (mix_id, "previous-owner"),
+                (unbonding_mix_id, "unbonding-owner"),
+                (unbonded_mix_id, "unbonded-owner"),
+            ];
+            for (mix_id, owner) in cases {
+                let old_bond = mixnodes_storage::mixnode_bonds()
+                    .may_load(test.deps().storage, mix_id)
+                    .unwrap();
+
+                let (identity, sphinx_key) = new_keys(&mut test);
+                let res = update_mixnode_keys(
+                    test.deps_mut(),
+                    123,
+                    mix_id,
+                    Addr::unchecked(owner),
+                    identity,
+                    sphinx_key,
+                )
+                .unwrap();
+                assert_eq!(
+                    res.events,
+                    vec![new_rejected_mixnode_keys_update_event(123, mix_id)]
+                );
+
+                let new_bond = mixnodes_storage::mixnode_bonds()
+                    .may_load(test.deps().storage, mix_id)
+                    .unwrap();
+                assert_eq!(old_bond, new_bond);
+            }
+        }
+
+        #[test]
+        fn is_rejected_if_keys_got_taken_in_the_meantime() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let other_mix_id = test.add_dummy_mixnode("other-owner", None);
+            let other_bond = mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, other_mix_id)
+                .unwrap();
+            let old_bond = mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+
+            let (identity, sphinx_key) = new_keys(&mut test);
+            let cases = [
+                (other_bond.mix_node.identity_key.clone(), sphinx_key),
+                (identity, other_bond.mix_node.sphinx_key.clone()),
+            ];
+            for (identity, sphinx_key) in cases {
+                let res = update_mixnode_keys(
+                    test.deps_mut(),
+                    123,
+                    mix_id,
+                    Addr::unchecked("mix-owner"),
+                    identity,
+                    sphinx_key,
+                )
+                .unwrap();
+                assert_eq!(
+                    res.events,
+                    vec![new_rejected_mixnode_keys_update_event(123, mix_id)]
+                );
+                assert_ne!(
+                    res.events[0].ty,
+                    new_mixnode_keys_update_event(123, mix_id, "identity", "sphinx-key").ty
+                );
+            }
+
+            let new_bond = mixnodes_storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            assert_eq!(old_bond, new_bond);
+        }
+
+        #[test]
+        fn migrates_family_membership() {
+            let mut test = TestSetup::new();
+            let head_id = test.add_dummy_mixnode("head-owner", None);
+            let member_id = test.add_dummy_mixnode("member-owner", None);
+            let other_member_id = test.add_dummy_mixnode("other-member-owner", None);
+
+            let family = Family::new(
+                FamilyHead::new(&identity_of(&test, head_id)),
+                None,
+                "family",
+            );
+            create_family(&family, test.deps_mut().storage).unwrap();
+            for member in [member_id, other_member_id] {
+                let identity = identity_of(&test, member);
+                add_family_member(&family, test.deps_mut().storage, &identity).unwrap();
+            }
+
+            // the member gets new keys
+            let old_member_identity = identity_of(&test, member_id);
+            let (identity, sphinx_key) = new_keys(&mut test);
+            update_mixnode_keys(
+                test.deps_mut(),
+                123,
+                member_id,
+                Addr::unchecked("member-owner"),
+                identity.clone(),
+                sphinx_key,
+            )
+            .unwrap();
+
+            assert!(MEMBERS
+                .may_load(test.deps().storage, old_member_identity)
+                .unwrap()
+                .is_none());
+            assert_eq!(
+                MEMBERS.load(test.deps().storage, identity).unwrap(),
+                *family.head()
+            );
+
+            // and so does the head
+            let (identity, sphinx_key) = new_keys(&mut test);
+            update_mixnode_keys(
+                test.deps_mut(),
+                123,
+                head_id,
+                Addr::unchecked("head-owner"),
+                identity.clone(),
+                sphinx_key,
+            )
+            .unwrap();
+
+            let new_head = FamilyHead::new(&identity);
+            assert!(get_family(family.head(), test.deps().storage).is_err());
+            let migrated = get_family(&new_head, test.deps().storage).unwrap();
+            assert_eq!(migrated.label(), "family");
+
+            for member in [member_id, other_member_id] {
+                let head = MEMBERS
+                    .load(test.deps().storage, identity_of(&test, member))
+                    .unwrap();
+                assert_eq!(head, new_head);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod updating_gateway_keys {
+        use super::*;
+        use crate::gateways::storage::PENDING_OWNERSHIP_TRANSFERS;
+        use crate::support::tests::fixtures;
+
+        fn add_gateway(test: &mut TestSetup, owner: &str) -> IdentityKey {
+            let env = test.env();
+            test_helpers::add_gateway(
+                &mut test.rng,
+                test.deps.as_mut(),
+                env,
+                owner,
+                fixtures::good_gateway_pledge(),
+            )
+        }
+
+        fn new_keys(test: &mut TestSetup) -> (IdentityKey, SphinxKey) {
+            let (gateway, _) = test_helpers::gateway_with_signature(&mut test.rng, "foomp");
+            (gateway.identity_key, gateway.sphinx_key)
+        }
+
+        #[test]
+        fn moves_the_bond_to_the_new_identity() {
+            let mut test = TestSetup::new();
+            let identity = add_gateway(&mut test, "gateway-owner");
+            let old_bond = gateways_storage::gateways()
+                .load(test.deps().storage, &identity)
+                .unwrap();
+            PENDING_OWNERSHIP_TRANSFERS
+                .save(
+                    test.deps_mut().storage,
+                    identity.clone(),
+                    &Addr::unchecked("new-owner"),
+                )
+                .unwrap();
+
+            let (new_identity, new_sphinx_key) = new_keys(&mut test);
+            update_gateway_keys(
+                test.deps_mut(),
+                123,
+                identity.clone(),
+                Addr::unchecked("gateway-owner"),
+                new_identity.clone(),
+                new_sphinx_key.clone(),
+            )
+            .unwrap();
+
+            assert!(gateways_storage::gateways()
+                .may_load(test.deps().storage, &identity)
+                .unwrap()
+                .is_none());
+            let new_bond = gateways_storage::gateways()
+                .load(test.deps().storage, &new_identity)
+                .unwrap();
+
+            let mut expected_bond = old_bond;
+            expected_bond.gateway.identity_key = new_identity.clone();
+            expected_bond.gateway.sphinx_key = new_sphinx_key;
+            assert_eq!(new_bond, expected_bond);
+
+            // the owner index points to the new entry
+            let by_owner = gateways_storage::gateways()
+                .idx
+                .owner
+                .item(test.deps().storage, Addr::unchecked("gateway-owner"))
+                .unwrap()
+                .unwrap()
+                .1;
+            assert_eq!(by_owner, new_bond);
+
+            // and so does the pending ownership transfer
+            assert!(PENDING_OWNERSHIP_TRANSFERS
+                .may_load(test.deps().storage, identity)
+                .unwrap()
+                .is_none());
+            assert_eq!(
+                PENDING_OWNERSHIP_TRANSFERS
+                    .load(test.deps().storage, new_identity)
+                    .unwrap(),
+                Addr::unchecked("new-owner")
+            );
+        }
+
+        #[test]
+        fn is_rejected_if_gateway_unbonded_or_changed_owner() {
+            let mut test = TestSetup::new();
+            let identity = add_gateway(&mut test, "gateway-owner");
+            let old_bond = gateways_storage::gateways()
+                .load(test.deps().storage, &identity)
+                .unwrap();
+
+            let (new_identity, new_sphinx_key) = new_keys(&mut test);
+            for (gateway, owner) in [
+                (identity.clone(), "previous-owner"),
+                ("unbonded-gateway".to_string(), "gateway-owner"),
+            ] {
+                let res = update_gateway_keys(
+                    test.deps_mut(),
+                    123,
+                    gateway.clone(),
+                    Addr::unchecked(owner),
+                    new_identity.clone(),
+                    new_sphinx_key.clone(),
+                )
+                .unwrap();
+                assert_eq!(
+                    res.events,
+                    vec![new_rejected_gateway_keys_update_event(123, &gateway)]
+                );
+            }
+
+            let bond = gateways_storage::gateways()
+                .load(test.deps().storage, &identity)
+                .unwrap();
+            assert_eq!(bond, old_bond);
+            assert!(gateways_storage::gateways()
+                .may_load(test.deps().storage, &new_identity)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn is_rejected_if_identity_got_taken_in_the_meantime() {
+            let mut test = TestSetup::new();
+            let identity = add_gateway(&mut test, "gateway-owner");
+            let other_identity = add_gateway(&mut test, "other-owner");
+            let old_bond = gateways_storage::gateways()
+                .load(test.deps().storage, &identity)
+                .unwrap();
+            let other_bond = gateways_storage::gateways()
+                .load(test.deps().storage, &other_identity)
+                .unwrap();
+
+            let (_, new_sphinx_key) = new_keys(&mut test);
+            let res = update_gateway_keys(
+                test.deps_mut(),
+                123,
+                identity.clone(),
+                Addr::unchecked("gateway-owner"),
+                other_identity.clone(),
+                new_sphinx_key,
+            )
+            .unwrap();
+            assert_eq!(
+                res.events,
+                vec![new_rejected_gateway_keys_update_event(123, &identity)]
+            );
+            assert_ne!(
+                res.events[0].ty,
+                new_gateway_keys_update_event(123, &identity, &other_identity, "sphinx-key").ty
+            );
+
+            let bond = gateways_storage::gateways()
+                .load(test.deps().storage, &identity)
+                .unwrap();
+            assert_eq!(bond, old_bond);
+            let bond = gateways_storage::gateways()
+                .load(test.deps().storage, &other_identity)
+                .unwrap();
+            assert_eq!(bond, other_bond);
+        }
+    }
+
     #[cfg(test)]
     mod changing_mix_cost_params {
         use super::*;
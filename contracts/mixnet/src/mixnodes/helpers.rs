@@ -5,12 +5,13 @@ use super::storage;
 use crate::interval::storage as interval_storage;
 use crate::mixnodes::storage::{assign_layer, next_mixnode_id_counter};
 use crate::rewards::storage as rewards_storage;
-use cosmwasm_std::{Addr, Coin, Decimal, Env, StdResult, Storage};
+use cosmwasm_std::{Addr, Coin, Decimal, Env, Order, StdResult, Storage};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::mixnode::{
     MixNodeCostParams, MixNodeDetails, MixNodeRewarding, UnbondedMixnode,
 };
-use mixnet_contract_common::{Layer, MixId, MixNode, MixNodeBond};
+use mixnet_contract_common::pending_events::PendingEpochEventKind;
+use mixnet_contract_common::{IdentityKeyRef, Layer, MixId, MixNode, MixNodeBond, SphinxKeyRef};
 
 pub(crate) fn must_get_mixnode_bond_by_owner(
     store: &dyn Storage,
@@ -65,6 +66,54 @@ pub(crate) fn get_mixnode_details_by_owner(
     }
 }
 
+// Makes sure that neither of the provided keys is used by any mixnode other than `mix_id`.
+pub(crate) fn ensure_keys_not_used_by_other_mixnode(
+    store: &dyn Storage,
+    mix_id: MixId,
+    identity_key: IdentityKeyRef<'_>,
+    sphinx_key: SphinxKeyRef<'_>,
+) -> Result<(), MixnetContractError> {
+    let bonds = storage::mixnode_bonds();
+    let by_identity = bonds
+        .idx
+        .identity_key
+        .item(store, identity_key.to_owned())?;
+    let by_sphinx_key = bonds.idx.sphinx_key.item(store, sphinx_key.to_owned())?;
+
+    for (key, record) in [(identity_key, by_identity), (sphinx_key, by_sphinx_key)] {
+        if let Some((_, bond)) = record {
+            if bond.mix_id != mix_id {
+                return Err(MixnetContractError::DuplicateMixnodeKey {
+                    key: key.to_owned(),
+                    mix_id: bond.mix_id,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+// Makes sure there are no pledge changes of the mixnode waiting for the end of the epoch,
+// as they are going to be settled with whoever happens to own the node at that point.
+pub(crate) fn ensure_no_pending_pledge_changes(
+    store: &dyn Storage,
+    mix_id: MixId,
+) -> Result<(), MixnetContractError> {
+    for event in interval_storage::PENDING_EPOCH_EVENTS.range(store, None, None, Order::Ascending) {
+        let (_, event) = event?;
+        match event.kind {
+            PendingEpochEventKind::PledgeMore { mix_id: id, .. }
+            | PendingEpochEventKind::DecreasePledge { mix_id: id, .. }
+                if id == mix_id =>
+            {
+                return Err(MixnetContractError::PendingPledgeChange { mix_id })
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn save_new_mixnode(
     storage: &mut dyn Storage,
     env: Env,
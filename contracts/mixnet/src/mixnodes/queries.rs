@@ -13,7 +13,8 @@ use cosmwasm_std::{Deps, Order, StdResult, Storage};
 use cw_storage_plus::Bound;
 use mixnet_contract_common::mixnode::{
     MixNodeBond, MixNodeDetails, MixnodeRewardingDetailsResponse, PagedMixnodesDetailsResponse,
-    PagedUnbondedMixnodesResponse, StakeSaturationResponse, UnbondedMixnodeResponse,
+    PagedUnbondedMixnodesResponse, PendingMixnodeOwnershipTransferResponse,
+    StakeSaturationResponse, UnbondedMixnodeResponse,
 };
 use mixnet_contract_common::{
     IdentityKey, LayerDistribution, MixId, MixOwnershipResponse, MixnodeDetailsResponse,
@@ -260,6 +261,16 @@ pub(crate) fn query_layer_distribution(deps: Deps<'_>) -> StdResult<LayerDistrib
     storage::LAYERS.load(deps.storage)
 }
 
+pub(crate) fn query_pending_mixnode_ownership_transfer(
+    deps: Deps<'_>,
+    mix_id: MixId,
+) -> StdResult<PendingMixnodeOwnershipTransferResponse> {
+    Ok(PendingMixnodeOwnershipTransferResponse {
+        mix_id,
+        new_owner: storage::PENDING_OWNERSHIP_TRANSFERS.may_load(deps.storage, mix_id)?,
+    })
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
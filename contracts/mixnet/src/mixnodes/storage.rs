@@ -3,12 +3,12 @@
 
 use crate::constants::{
     LAYER_DISTRIBUTION_KEY, MIXNODES_IDENTITY_IDX_NAMESPACE, MIXNODES_OWNER_IDX_NAMESPACE,
-    MIXNODES_PK_NAMESPACE, MIXNODES_SPHINX_IDX_NAMESPACE, NODE_ID_COUNTER_KEY,
-    UNBONDED_MIXNODES_IDENTITY_IDX_NAMESPACE, UNBONDED_MIXNODES_OWNER_IDX_NAMESPACE,
-    UNBONDED_MIXNODES_PK_NAMESPACE,
+    MIXNODES_PENDING_OWNERSHIP_TRANSFERS_NAMESPACE, MIXNODES_PK_NAMESPACE,
+    MIXNODES_SPHINX_IDX_NAMESPACE, NODE_ID_COUNTER_KEY, UNBONDED_MIXNODES_IDENTITY_IDX_NAMESPACE,
+    UNBONDED_MIXNODES_OWNER_IDX_NAMESPACE, UNBONDED_MIXNODES_PK_NAMESPACE,
 };
 use cosmwasm_std::{StdResult, Storage};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex, UniqueIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::mixnode::UnbondedMixnode;
 use mixnet_contract_common::SphinxKey;
@@ -51,6 +51,10 @@ pub(crate) fn unbonded_mixnodes<'a>(
 pub(crate) const LAYERS: Item<'_, LayerDistribution> = Item::new(LAYER_DISTRIBUTION_KEY);
 pub const MIXNODE_ID_COUNTER: Item<MixId> = Item::new(NODE_ID_COUNTER_KEY);
 
+// proposed new owners of the mixnodes, who have yet to accept the ownership
+pub(crate) const PENDING_OWNERSHIP_TRANSFERS: Map<MixId, Addr> =
+    Map::new(MIXNODES_PENDING_OWNERSHIP_TRANSFERS_NAMESPACE);
+
 pub(crate) struct MixnodeBondIndex<'a> {
     pub(crate) owner: UniqueIndex<'a, Addr, MixNodeBond>,
 
@@ -5,24 +5,28 @@ use super::storage;
 use crate::interval::storage as interval_storage;
 use crate::interval::storage::push_new_interval_event;
 use crate::mixnet_contract_settings::storage as mixnet_params_storage;
-use crate::mixnet_contract_settings::storage::rewarding_denom;
+use crate::mixnet_contract_settings::storage::{minimum_mixnode_pledge, rewarding_denom};
 use crate::mixnodes::helpers::{
+    ensure_keys_not_used_by_other_mixnode, ensure_no_pending_pledge_changes,
     get_mixnode_details_by_owner, must_get_mixnode_bond_by_owner, save_new_mixnode,
 };
 use crate::support::helpers::{
     ensure_bonded, ensure_is_authorized, ensure_no_existing_bond, ensure_proxy_match,
     validate_node_identity_signature, validate_pledge,
 };
-use cosmwasm_std::{coin, Addr, Coin, DepsMut, Env, MessageInfo, Response, Storage};
+use cosmwasm_std::{coin, wasm_execute, Addr, Coin, DepsMut, Env, MessageInfo, Response, Storage};
 use mixnet_contract_common::error::MixnetContractError;
 use mixnet_contract_common::events::{
     new_mixnode_bonding_event, new_mixnode_config_update_event,
-    new_mixnode_pending_cost_params_update_event, new_pending_mixnode_unbonding_event,
-    new_pending_pledge_increase_event,
+    new_mixnode_ownership_transfer_cancellation_event, new_mixnode_ownership_transfer_event,
+    new_mixnode_ownership_transfer_proposal_event, new_mixnode_pending_cost_params_update_event,
+    new_pending_mixnode_keys_update_event, new_pending_mixnode_unbonding_event,
+    new_pending_pledge_decrease_event, new_pending_pledge_increase_event,
 };
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
 use mixnet_contract_common::pending_events::{PendingEpochEventKind, PendingIntervalEventKind};
-use mixnet_contract_common::{Layer, MixId, MixNode};
+use mixnet_contract_common::{IdentityKey, Layer, MixId, MixNode, SphinxKey};
+use vesting_contract_common::messages::ExecuteMsg as VestingContractExecuteMsg;
 
 pub(crate) fn update_mixnode_layer(
     mix_id: MixId,
@@ -199,6 +203,68 @@ pub fn _try_increase_pledge(
     Ok(Response::new().add_event(cosmos_event))
 }
 
+pub fn try_decrease_pledge(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    decrease_by: Coin,
+) -> Result<Response, MixnetContractError> {
+    _try_decrease_pledge(deps, env, decrease_by, info.sender, None)
+}
+
+pub fn try_decrease_pledge_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    decrease_by: Coin,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let proxy = info.sender;
+    let owner = deps.api.addr_validate(&owner)?;
+    _try_decrease_pledge(deps, env, decrease_by, owner, Some(proxy))
+}
+
+pub fn _try_decrease_pledge(
+    deps: DepsMut<'_>,
+    env: Env,
+    decrease_by: Coin,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let mix_details = get_mixnode_details_by_owner(deps.storage, owner.clone())?
+        .ok_or(MixnetContractError::NoAssociatedMixNodeBond { owner })?;
+    let mix_id = mix_details.mix_id();
+
+    ensure_proxy_match(&proxy, &mix_details.bond_information.proxy)?;
+    ensure_bonded(&mix_details.bond_information)?;
+
+    let minimum_pledge = minimum_mixnode_pledge(deps.storage)?;
+    let decrease_by = validate_pledge(vec![decrease_by], coin(1, &minimum_pledge.denom))?;
+
+    // the remaining pledge must still be at least the minimum
+    let current_pledge = mix_details.original_pledge();
+    if current_pledge.amount <= decrease_by.amount
+        || current_pledge.amount - decrease_by.amount < minimum_pledge.amount
+    {
+        return Err(MixnetContractError::InvalidPledgeReduction {
+            current: current_pledge.clone(),
+            decrease_by,
+            minimum: minimum_pledge,
+        });
+    }
+
+    let cosmos_event = new_pending_pledge_decrease_event(mix_id, &decrease_by);
+
+    // push the event to execute it at the end of the epoch
+    let epoch_event = PendingEpochEventKind::DecreasePledge {
+        mix_id,
+        decrease_by,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
 pub fn try_remove_mixnode_on_behalf(
     deps: DepsMut<'_>,
     env: Env,
@@ -235,6 +301,9 @@ pub(crate) fn _try_remove_mixnode(
     ensure_proxy_match(&proxy, &existing_bond.proxy)?;
     ensure_bonded(&existing_bond)?;
 
+    // the node is going away, so there's nothing left to be transferred
+    storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, existing_bond.mix_id);
+
     // set `is_unbonding` field
     let mut updated_bond = existing_bond.clone();
     updated_bond.is_unbonding = true;
@@ -364,6 +433,270 @@ pub(crate) fn _try_update_mixnode_cost_params(
     Ok(Response::new().add_event(cosmos_event))
 }
 
+pub(crate) fn try_update_mixnode_keys(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner_signature: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = info.sender;
+    _try_update_mixnode_keys(
+        deps,
+        env,
+        new_identity_key,
+        new_sphinx_key,
+        owner_signature,
+        owner,
+        None,
+    )
+}
+
+pub(crate) fn try_update_mixnode_keys_on_behalf(
+    deps: DepsMut<'_>,
+    env: Env,
+    info: MessageInfo,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner: String,
+    owner_signature: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_update_mixnode_keys(
+        deps,
+        env,
+        new_identity_key,
+        new_sphinx_key,
+        owner_signature,
+        owner,
+        Some(proxy),
+    )
+}
+
+pub(crate) fn _try_update_mixnode_keys(
+    deps: DepsMut<'_>,
+    env: Env,
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner_signature: String,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_mixnode_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+    ensure_bonded(&existing_bond)?;
+
+    if existing_bond.mix_node.identity_key == new_identity_key
+        && existing_bond.mix_node.sphinx_key == new_sphinx_key
+    {
+        return Err(MixnetContractError::UnchangedNodeKeys);
+    }
+
+    ensure_keys_not_used_by_other_mixnode(
+        deps.storage,
+        existing_bond.mix_id,
+        &new_identity_key,
+        &new_sphinx_key,
+    )?;
+
+    // the operator has to prove they're in possession of the new identity key
+    validate_node_identity_signature(deps.as_ref(), &owner, &owner_signature, &new_identity_key)?;
+
+    let cosmos_event = new_pending_mixnode_keys_update_event(
+        existing_bond.mix_id,
+        &owner,
+        &proxy,
+        &new_identity_key,
+        &new_sphinx_key,
+    );
+
+    // push the event to execute it at the end of the epoch
+    let epoch_event = PendingEpochEventKind::UpdateMixnodeKeys {
+        mix_id: existing_bond.mix_id,
+        owner,
+        new_identity_key,
+        new_sphinx_key,
+    };
+    interval_storage::push_new_epoch_event(deps.storage, &env, epoch_event)?;
+
+    Ok(Response::new().add_event(cosmos_event))
+}
+
+pub(crate) fn try_propose_mixnode_ownership_transfer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, MixnetContractError> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    _try_propose_mixnode_ownership_transfer(deps, info.sender, new_owner, None)
+}
+
+pub(crate) fn try_propose_mixnode_ownership_transfer_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    owner: String,
+    new_owner: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let proxy = info.sender;
+    _try_propose_mixnode_ownership_transfer(deps, owner, new_owner, Some(proxy))
+}
+
+/// Proposes transferring the ownership of the mixnode to `new_owner`, who has to accept it before
+/// it takes effect. The bonds made through a proxy (i.e. with vesting tokens) can only be transferred
+/// through that same proxy.
+pub(crate) fn _try_propose_mixnode_ownership_transfer(
+    deps: DepsMut<'_>,
+    owner: Addr,
+    new_owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_mixnode_bond_by_owner(deps.storage, &owner)?;
+
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+    ensure_bonded(&existing_bond)?;
+
+    if new_owner == owner {
+        return Err(MixnetContractError::OwnershipTransferToSelf { owner });
+    }
+
+    // if there was another proposal made before, it just gets replaced
+    storage::PENDING_OWNERSHIP_TRANSFERS.save(deps.storage, existing_bond.mix_id, &new_owner)?;
+
+    Ok(
+        Response::new().add_event(new_mixnode_ownership_transfer_proposal_event(
+            existing_bond.mix_id,
+            &owner,
+            &new_owner,
+        )),
+    )
+}
+
+pub(crate) fn try_cancel_mixnode_ownership_transfer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+) -> Result<Response, MixnetContractError> {
+    _try_cancel_mixnode_ownership_transfer(deps, info.sender, None)
+}
+
+pub(crate) fn try_cancel_mixnode_ownership_transfer_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, MixnetContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let proxy = info.sender;
+    _try_cancel_mixnode_ownership_transfer(deps, owner, Some(proxy))
+}
+
+pub(crate) fn _try_cancel_mixnode_ownership_transfer(
+    deps: DepsMut<'_>,
+    owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let existing_bond = must_get_mixnode_bond_by_owner(deps.storage, &owner)?;
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+    let mix_id = existing_bond.mix_id;
+
+    let new_owner = storage::PENDING_OWNERSHIP_TRANSFERS
+        .may_load(deps.storage, mix_id)?
+        .ok_or(MixnetContractError::NoPendingMixnodeOwnershipTransfer { mix_id })?;
+    storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, mix_id);
+
+    Ok(
+        Response::new().add_event(new_mixnode_ownership_transfer_cancellation_event(
+            mix_id, &owner, &new_owner,
+        )),
+    )
+}
+
+pub(crate) fn try_accept_mixnode_ownership_transfer(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    mix_id: MixId,
+) -> Result<Response, MixnetContractError> {
+    _try_accept_mixnode_ownership_transfer(deps, mix_id, info.sender, None)
+}
+
+pub(crate) fn try_accept_mixnode_ownership_transfer_on_behalf(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    mix_id: MixId,
+    new_owner: String,
+) -> Result<Response, MixnetContractError> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let proxy = info.sender;
+    _try_accept_mixnode_ownership_transfer(deps, mix_id, new_owner, Some(proxy))
+}
+
+/// Completes the ownership transfer of the mixnode proposed by its current owner. Note that
+/// everything associated with the node, including its pledge and any operator rewards
+/// that haven't been withdrawn yet, now belongs to the new owner. If the node has been bonded
+/// through a proxy, the new owner has to accept it through the same proxy, which gets told
+/// to move the pledge over to them.
+pub(crate) fn _try_accept_mixnode_ownership_transfer(
+    deps: DepsMut<'_>,
+    mix_id: MixId,
+    new_owner: Addr,
+    proxy: Option<Addr>,
+) -> Result<Response, MixnetContractError> {
+    let proposed_owner = storage::PENDING_OWNERSHIP_TRANSFERS
+        .may_load(deps.storage, mix_id)?
+        .ok_or(MixnetContractError::NoPendingMixnodeOwnershipTransfer { mix_id })?;
+
+    if new_owner != proposed_owner {
+        return Err(MixnetContractError::NotProposedOwner {
+            address: new_owner,
+            proposed: proposed_owner,
+        });
+    }
+
+    let existing_bond = storage::mixnode_bonds()
+        .may_load(deps.storage, mix_id)?
+        .ok_or(MixnetContractError::MixNodeBondNotFound { mix_id })?;
+    ensure_bonded(&existing_bond)?;
+    ensure_proxy_match(&proxy, &existing_bond.proxy)?;
+
+    // the pending pledge changes don't know about the owner, so the tokens of the old one
+    // would have ended up with the new one (or the other way around)
+    ensure_no_pending_pledge_changes(deps.storage, mix_id)?;
+
+    // the new owner can't end up with multiple nodes
+    ensure_no_existing_bond(deps.storage, &new_owner)?;
+
+    // replacing the bond also updates the owner index
+    let mut updated_bond = existing_bond.clone();
+    updated_bond.owner = new_owner.clone();
+    storage::mixnode_bonds().replace(
+        deps.storage,
+        mix_id,
+        Some(&updated_bond),
+        Some(&existing_bond),
+    )?;
+    storage::PENDING_OWNERSHIP_TRANSFERS.remove(deps.storage, mix_id);
+
+    let mut response = Response::new().add_event(new_mixnode_ownership_transfer_event(
+        mix_id,
+        &existing_bond.owner,
+        &new_owner,
+    ));
+
+    if let Some(proxy) = &existing_bond.proxy {
+        let msg = VestingContractExecuteMsg::TrackMixnodeOwnershipTransfer {
+            owner: existing_bond.owner.into_string(),
+            new_owner: new_owner.into_string(),
+        };
+        let track_transfer_message = wasm_execute(proxy, &msg, vec![])?;
+        response = response.add_message(track_transfer_message);
+    }
+
+    Ok(response)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -945,4 +1278,847 @@ pub mod tests {
             );
         }
     }
+
+    #[cfg(test)]
+    mod decreasing_mixnode_pledge {
+        use super::*;
+        use crate::mixnodes::helpers::tests::{setup_mix_combinations, OWNER_UNBONDING};
+        use crate::support::tests::test_helpers::TestSetup;
+
+        #[test]
+        fn is_not_allowed_if_account_doesnt_own_mixnode() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let sender = mock_info("not-mix-owner", &[]);
+
+            let res = try_decrease_pledge(test.deps_mut(), env, sender, test.coin(1000));
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoAssociatedMixNodeBond {
+                    owner: Addr::unchecked("not-mix-owner")
+                })
+            )
+        }
+
+        #[test]
+        fn is_not_allowed_if_theres_proxy_mismatch() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner_with_proxy = Addr::unchecked("with-proxy");
+            let proxy = Addr::unchecked("proxy");
+            test.add_dummy_mixnode_with_proxy(owner_with_proxy.as_str(), None, proxy);
+
+            let res = _try_decrease_pledge(
+                test.deps_mut(),
+                env,
+                test.coin(1000),
+                owner_with_proxy,
+                None,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::ProxyMismatch {
+                    existing: "proxy".to_string(),
+                    incoming: "None".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn is_not_allowed_if_mixnode_is_unbonding() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let ids = setup_mix_combinations(&mut test);
+            let mix_id_unbonding = ids[1];
+
+            let res = try_decrease_pledge(
+                test.deps_mut(),
+                env,
+                mock_info(OWNER_UNBONDING, &[]),
+                test.coin(1000),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixnodeIsUnbonding {
+                    mix_id: mix_id_unbonding
+                })
+            );
+        }
+
+        #[test]
+        fn is_not_allowed_to_go_below_the_minimum_pledge() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "mix-owner";
+            let minimum = minimum_mixnode_pledge(test.deps().storage).unwrap();
+            let pledge = minimum.amount + Uint128::new(1000);
+            test.add_dummy_mixnode(owner, Some(pledge));
+
+            let sender = mock_info(owner, &[]);
+            let res =
+                try_decrease_pledge(test.deps_mut(), env.clone(), sender.clone(), test.coin(0));
+            assert_eq!(
+                res,
+                Err(MixnetContractError::InsufficientPledge {
+                    received: test.coin(0),
+                    minimum: test.coin(1)
+                })
+            );
+
+            for decrease in [1001, pledge.u128(), pledge.u128() + 1] {
+                let res = try_decrease_pledge(
+                    test.deps_mut(),
+                    env.clone(),
+                    sender.clone(),
+                    test.coin(decrease),
+                );
+                assert_eq!(
+                    res,
+                    Err(MixnetContractError::InvalidPledgeReduction {
+                        current: test.coin(pledge.u128()),
+                        decrease_by: test.coin(decrease),
+                        minimum: minimum.clone(),
+                    })
+                );
+            }
+
+            let res = try_decrease_pledge(test.deps_mut(), env, sender, test.coin(1000));
+            assert!(res.is_ok());
+        }
+
+        #[test]
+        fn with_valid_information_creates_pending_event() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "mix-owner";
+            let mix_id = test.add_dummy_mixnode(owner, Some(Uint128::new(200_000_000_000)));
+
+            let events = test.pending_epoch_events();
+            assert!(events.is_empty());
+
+            let sender = mock_info(owner, &[]);
+            try_decrease_pledge(test.deps_mut(), env, sender, test.coin(1000)).unwrap();
+
+            let events = test.pending_epoch_events();
+
+            assert_eq!(
+                events[0].kind,
+                PendingEpochEventKind::DecreasePledge {
+                    mix_id,
+                    decrease_by: test.coin(1000)
+                }
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod updating_mixnode_keys {
+        use super::*;
+        use crate::mixnodes::helpers::tests::{setup_mix_combinations, OWNER_UNBONDING};
+        use crate::support::tests::test_helpers::TestSetup;
+
+        // new keys of a node alongside the signature of the new identity key over the owner address
+        fn new_keys(test: &mut TestSetup, owner: &str) -> (IdentityKey, SphinxKey, String) {
+            let (mix_node, signature, _) =
+                test_helpers::mixnode_with_signature(&mut test.rng, owner);
+            (mix_node.identity_key, mix_node.sphinx_key, signature)
+        }
+
+        #[test]
+        fn is_not_allowed_if_account_doesnt_own_mixnode() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let (identity, sphinx_key, signature) = new_keys(&mut test, "not-mix-owner");
+
+            let res = try_update_mixnode_keys(
+                test.deps_mut(),
+                env,
+                mock_info("not-mix-owner", &[]),
+                identity,
+                sphinx_key,
+                signature,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoAssociatedMixNodeBond {
+                    owner: Addr::unchecked("not-mix-owner")
+                })
+            )
+        }
+
+        #[test]
+        fn is_not_allowed_if_theres_proxy_mismatch() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let owner_with_proxy = Addr::unchecked("with-proxy");
+            let proxy = Addr::unchecked("proxy");
+            test.add_dummy_mixnode_with_proxy(owner_with_proxy.as_str(), None, proxy);
+            let (identity, sphinx_key, signature) = new_keys(&mut test, "with-proxy");
+
+            let res = _try_update_mixnode_keys(
+                test.deps_mut(),
+                env,
+                identity,
+                sphinx_key,
+                signature,
+                owner_with_proxy,
+                None,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::ProxyMismatch {
+                    existing: "proxy".to_string(),
+                    incoming: "None".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn is_not_allowed_if_mixnode_is_unbonding() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+
+            let ids = setup_mix_combinations(&mut test);
+            let mix_id_unbonding = ids[1];
+            let (identity, sphinx_key, signature) = new_keys(&mut test, OWNER_UNBONDING);
+
+            let res = try_update_mixnode_keys(
+                test.deps_mut(),
+                env,
+                mock_info(OWNER_UNBONDING, &[]),
+                identity,
+                sphinx_key,
+                signature,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixnodeIsUnbonding {
+                    mix_id: mix_id_unbonding
+                })
+            );
+        }
+
+        #[test]
+        fn requires_the_new_identity_key_to_sign_the_owner_address() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "mix-owner";
+            test.add_dummy_mixnode(owner, None);
+
+            // signature made by a different key
+            let (identity, sphinx_key, _) = new_keys(&mut test, owner);
+            let (_, _, other_signature) = new_keys(&mut test, owner);
+            let res = try_update_mixnode_keys(
+                test.deps_mut(),
+                env.clone(),
+                mock_info(owner, &[]),
+                identity,
+                sphinx_key,
+                other_signature,
+            );
+            assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+
+            // signature over a different address
+            let (identity, sphinx_key, signature) = new_keys(&mut test, "another-address");
+            let res = try_update_mixnode_keys(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                identity,
+                sphinx_key,
+                signature,
+            );
+            assert_eq!(res, Err(MixnetContractError::InvalidEd25519Signature));
+
+            assert!(test.pending_epoch_events().is_empty());
+        }
+
+        #[test]
+        fn is_not_allowed_to_use_keys_of_another_node() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "mix-owner";
+            let mix_id = test.add_dummy_mixnode(owner, None);
+            let other_mix_id = test.add_dummy_mixnode("other-owner", None);
+
+            let bond = storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            let other_bond = storage::mixnode_bonds()
+                .load(test.deps().storage, other_mix_id)
+                .unwrap();
+
+            // using exactly the same keys is pointless
+            let res = try_update_mixnode_keys(
+                test.deps_mut(),
+                env.clone(),
+                mock_info(owner, &[]),
+                bond.mix_node.identity_key.clone(),
+                bond.mix_node.sphinx_key.clone(),
+                "irrelevant".to_string(),
+            );
+            assert_eq!(res, Err(MixnetContractError::UnchangedNodeKeys));
+
+            let (identity, _, signature) = new_keys(&mut test, owner);
+            let res = try_update_mixnode_keys(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                identity,
+                other_bond.mix_node.sphinx_key.clone(),
+                signature,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::DuplicateMixnodeKey {
+                    key: other_bond.mix_node.sphinx_key,
+                    mix_id: other_mix_id
+                })
+            );
+        }
+
+        #[test]
+        fn with_valid_information_creates_pending_event() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let owner = "mix-owner";
+            let mix_id = test.add_dummy_mixnode(owner, None);
+            let bond = storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+
+            let (identity, sphinx_key, signature) = new_keys(&mut test, owner);
+            try_update_mixnode_keys(
+                test.deps_mut(),
+                env,
+                mock_info(owner, &[]),
+                identity.clone(),
+                sphinx_key.clone(),
+                signature,
+            )
+            .unwrap();
+
+            let events = test.pending_epoch_events();
+            assert_eq!(
+                events[0].kind,
+                PendingEpochEventKind::UpdateMixnodeKeys {
+                    mix_id,
+                    owner: Addr::unchecked(owner),
+                    new_identity_key: identity,
+                    new_sphinx_key: sphinx_key,
+                }
+            );
+
+            // nothing changes until the epoch is over
+            let current_bond = storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            assert_eq!(current_bond, bond);
+        }
+    }
+
+    #[cfg(test)]
+    mod transferring_mixnode_ownership {
+        use super::*;
+        use crate::mixnodes::helpers::tests::{setup_mix_combinations, OWNER_UNBONDING};
+        use crate::mixnodes::queries::query_pending_mixnode_ownership_transfer;
+        use crate::rewards::storage as rewards_storage;
+        use crate::support::tests::test_helpers::TestSetup;
+        use cosmwasm_std::SubMsg;
+        use mixnet_contract_common::MixNodeBond;
+
+        fn pending_new_owner(test: &TestSetup, mix_id: MixId) -> Option<Addr> {
+            query_pending_mixnode_ownership_transfer(test.deps(), mix_id)
+                .unwrap()
+                .new_owner
+        }
+
+        #[test]
+        fn is_not_allowed_if_account_doesnt_own_mixnode() {
+            let mut test = TestSetup::new();
+
+            let res = try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("not-mix-owner", &[]),
+                "new-owner".to_string(),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoAssociatedMixNodeBond {
+                    owner: Addr::unchecked("not-mix-owner")
+                })
+            );
+
+            let res = try_cancel_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("not-mix-owner", &[]),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoAssociatedMixNodeBond {
+                    owner: Addr::unchecked("not-mix-owner")
+                })
+            );
+        }
+
+        #[test]
+        fn can_only_be_done_through_the_proxy_the_mixnode_was_bonded_with() {
+            let mut test = TestSetup::new();
+
+            let proxy = test.vesting_contract();
+            let mix_id = test.add_dummy_mixnode_with_proxy("mix-owner", None, proxy.clone());
+
+            let res = try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                "new-owner".to_string(),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::ProxyMismatch {
+                    existing: proxy.to_string(),
+                    incoming: "None".to_string(),
+                })
+            );
+
+            let res = try_propose_mixnode_ownership_transfer_on_behalf(
+                test.deps_mut(),
+                mock_info("other-proxy", &[]),
+                "mix-owner".to_string(),
+                "new-owner".to_string(),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::ProxyMismatch {
+                    existing: proxy.to_string(),
+                    incoming: "other-proxy".to_string(),
+                })
+            );
+
+            try_propose_mixnode_ownership_transfer_on_behalf(
+                test.deps_mut(),
+                mock_info(proxy.as_str(), &[]),
+                "mix-owner".to_string(),
+                "new-owner".to_string(),
+            )
+            .unwrap();
+            assert_eq!(
+                pending_new_owner(&test, mix_id),
+                Some(Addr::unchecked("new-owner"))
+            );
+
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::ProxyMismatch {
+                    existing: proxy.to_string(),
+                    incoming: "None".to_string(),
+                })
+            );
+
+            let res =
+                try_cancel_mixnode_ownership_transfer(test.deps_mut(), mock_info("mix-owner", &[]));
+            assert_eq!(
+                res,
+                Err(MixnetContractError::ProxyMismatch {
+                    existing: proxy.to_string(),
+                    incoming: "None".to_string(),
+                })
+            );
+
+            try_cancel_mixnode_ownership_transfer_on_behalf(
+                test.deps_mut(),
+                mock_info(proxy.as_str(), &[]),
+                "mix-owner".to_string(),
+            )
+            .unwrap();
+            assert!(pending_new_owner(&test, mix_id).is_none());
+        }
+
+        #[test]
+        fn accepting_on_behalf_tells_the_proxy_to_move_the_pledge() {
+            let mut test = TestSetup::new();
+
+            let proxy = test.vesting_contract();
+            let mix_id = test.add_dummy_mixnode_with_proxy("mix-owner", None, proxy.clone());
+
+            try_propose_mixnode_ownership_transfer_on_behalf(
+                test.deps_mut(),
+                mock_info(proxy.as_str(), &[]),
+                "mix-owner".to_string(),
+                "new-owner".to_string(),
+            )
+            .unwrap();
+            let res = try_accept_mixnode_ownership_transfer_on_behalf(
+                test.deps_mut(),
+                mock_info(proxy.as_str(), &[]),
+                mix_id,
+                "new-owner".to_string(),
+            )
+            .unwrap();
+
+            let expected_track = wasm_execute(
+                proxy.clone(),
+                &VestingContractExecuteMsg::TrackMixnodeOwnershipTransfer {
+                    owner: "mix-owner".to_string(),
+                    new_owner: "new-owner".to_string(),
+                },
+                vec![],
+            )
+            .unwrap();
+            assert_eq!(res.messages, vec![SubMsg::new(expected_track)]);
+
+            // the bond now belongs to the new owner, but it's still tied to the same proxy
+            let bond =
+                must_get_mixnode_bond_by_owner(test.deps().storage, &Addr::unchecked("new-owner"))
+                    .unwrap();
+            assert_eq!(bond.mix_id, mix_id);
+            assert_eq!(bond.proxy, Some(proxy));
+        }
+
+        #[test]
+        fn is_not_allowed_if_mixnode_is_unbonding() {
+            let mut test = TestSetup::new();
+
+            let ids = setup_mix_combinations(&mut test);
+            let mix_id_unbonding = ids[1];
+
+            let res = try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info(OWNER_UNBONDING, &[]),
+                "new-owner".to_string(),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::MixnodeIsUnbonding {
+                    mix_id: mix_id_unbonding
+                })
+            );
+        }
+
+        #[test]
+        fn is_not_allowed_to_transfer_to_current_owner() {
+            let mut test = TestSetup::new();
+            test.add_dummy_mixnode("mix-owner", None);
+
+            let res = try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                "mix-owner".to_string(),
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::OwnershipTransferToSelf {
+                    owner: Addr::unchecked("mix-owner")
+                })
+            );
+        }
+
+        #[test]
+        fn can_only_be_accepted_by_the_proposed_owner() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+
+            // nothing has been proposed yet
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoPendingMixnodeOwnershipTransfer { mix_id })
+            );
+
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                "new-owner".to_string(),
+            )
+            .unwrap();
+            assert_eq!(
+                pending_new_owner(&test, mix_id),
+                Some(Addr::unchecked("new-owner"))
+            );
+
+            for sender in ["mix-owner", "someone-else"] {
+                let res = try_accept_mixnode_ownership_transfer(
+                    test.deps_mut(),
+                    mock_info(sender, &[]),
+                    mix_id,
+                );
+                assert_eq!(
+                    res,
+                    Err(MixnetContractError::NotProposedOwner {
+                        address: Addr::unchecked(sender),
+                        proposed: Addr::unchecked("new-owner"),
+                    })
+                );
+            }
+
+            // the bond is still owned by the original owner
+            let bond =
+                must_get_mixnode_bond_by_owner(test.deps().storage, &Addr::unchecked("mix-owner"))
+                    .unwrap();
+            assert_eq!(bond.mix_id, mix_id);
+        }
+
+        #[test]
+        fn proposal_can_be_replaced_and_cancelled() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            let sender = mock_info("mix-owner", &[]);
+
+            let res = try_cancel_mixnode_ownership_transfer(test.deps_mut(), sender.clone());
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoPendingMixnodeOwnershipTransfer { mix_id })
+            );
+
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                sender.clone(),
+                "first-owner".to_string(),
+            )
+            .unwrap();
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                sender.clone(),
+                "second-owner".to_string(),
+            )
+            .unwrap();
+            assert_eq!(
+                pending_new_owner(&test, mix_id),
+                Some(Addr::unchecked("second-owner"))
+            );
+
+            // the first proposal is no longer valid
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("first-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NotProposedOwner {
+                    address: Addr::unchecked("first-owner"),
+                    proposed: Addr::unchecked("second-owner"),
+                })
+            );
+
+            try_cancel_mixnode_ownership_transfer(test.deps_mut(), sender).unwrap();
+            assert!(pending_new_owner(&test, mix_id).is_none());
+
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("second-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoPendingMixnodeOwnershipTransfer { mix_id })
+            );
+        }
+
+        #[test]
+        fn is_not_allowed_if_new_owner_already_has_a_node() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            test.add_dummy_mixnode("other-mix-owner", None);
+            test_helpers::add_gateway(
+                &mut test.rng,
+                test.deps.as_mut(),
+                env,
+                "gateway-owner",
+                fixtures::good_gateway_pledge(),
+            );
+
+            for (new_owner, err) in [
+                ("other-mix-owner", MixnetContractError::AlreadyOwnsMixnode),
+                ("gateway-owner", MixnetContractError::AlreadyOwnsGateway),
+            ] {
+                try_propose_mixnode_ownership_transfer(
+                    test.deps_mut(),
+                    mock_info("mix-owner", &[]),
+                    new_owner.to_string(),
+                )
+                .unwrap();
+
+                let res = try_accept_mixnode_ownership_transfer(
+                    test.deps_mut(),
+                    mock_info(new_owner, &[]),
+                    mix_id,
+                );
+                assert_eq!(res, Err(err));
+            }
+        }
+
+        #[test]
+        fn is_not_allowed_with_pending_pledge_changes() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let mix_id = test.add_dummy_mixnode("mix-owner", Some(Uint128::new(100_000_000_000)));
+            let denom = rewarding_denom(test.deps().storage).unwrap();
+
+            try_increase_pledge(
+                test.deps_mut(),
+                env.clone(),
+                mock_info("mix-owner", &[coin(1000, &denom)]),
+            )
+            .unwrap();
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                "new-owner".to_string(),
+            )
+            .unwrap();
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::PendingPledgeChange { mix_id })
+            );
+
+            // once the increase is settled with the current owner, the transfer can go through
+            test.execute_all_pending_events();
+            try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                mix_id,
+            )
+            .unwrap();
+
+            // and the same applies to decreasing the pledge
+            try_decrease_pledge(
+                test.deps_mut(),
+                env,
+                mock_info("new-owner", &[]),
+                coin(1000, &denom),
+            )
+            .unwrap();
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                "mix-owner".to_string(),
+            )
+            .unwrap();
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::PendingPledgeChange { mix_id })
+            );
+
+            test.execute_all_pending_events();
+            try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                mix_id,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn unbonding_removes_pending_transfer() {
+            let mut test = TestSetup::new();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                "new-owner".to_string(),
+            )
+            .unwrap();
+            test.start_unbonding_mixnode(mix_id);
+            assert!(pending_new_owner(&test, mix_id).is_none());
+
+            let res = try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                mix_id,
+            );
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoPendingMixnodeOwnershipTransfer { mix_id })
+            );
+        }
+
+        #[test]
+        fn accepting_moves_the_bond_to_the_new_owner() {
+            let mut test = TestSetup::new();
+            let env = test.env();
+            let mix_id = test.add_dummy_mixnode("mix-owner", None);
+            test.add_immediate_delegation("delegator", 100_000_000u128, mix_id);
+
+            let old_bond = storage::mixnode_bonds()
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            let old_rewarding = rewards_storage::MIXNODE_REWARDING
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+
+            try_propose_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("mix-owner", &[]),
+                "new-owner".to_string(),
+            )
+            .unwrap();
+            try_accept_mixnode_ownership_transfer(
+                test.deps_mut(),
+                mock_info("new-owner", &[]),
+                mix_id,
+            )
+            .unwrap();
+            assert!(pending_new_owner(&test, mix_id).is_none());
+
+            // the owner index got updated
+            let new_bond =
+                must_get_mixnode_bond_by_owner(test.deps().storage, &Addr::unchecked("new-owner"))
+                    .unwrap();
+            assert_eq!(
+                must_get_mixnode_bond_by_owner(test.deps().storage, &Addr::unchecked("mix-owner")),
+                Err(MixnetContractError::NoAssociatedMixNodeBond {
+                    owner: Addr::unchecked("mix-owner")
+                })
+            );
+
+            // while everything else about the node is exactly the same, delegations included
+            assert_eq!(
+                MixNodeBond {
+                    owner: Addr::unchecked("mix-owner"),
+                    ..new_bond
+                },
+                old_bond
+            );
+            let new_rewarding = rewards_storage::MIXNODE_REWARDING
+                .load(test.deps().storage, mix_id)
+                .unwrap();
+            assert_eq!(new_rewarding, old_rewarding);
+
+            // and it's the new owner who is in control of it now
+            let res = try_remove_mixnode(test.deps_mut(), env.clone(), mock_info("mix-owner", &[]));
+            assert_eq!(
+                res,
+                Err(MixnetContractError::NoAssociatedMixNodeBond {
+                    owner: Addr::unchecked("mix-owner")
+                })
+            );
+            let res = try_remove_mixnode(test.deps_mut(), env, mock_info("new-owner", &[]));
+            assert!(res.is_ok());
+        }
+    }
 }
@@ -14,11 +14,15 @@ use cosmwasm_std::{
 };
 use cw_storage_plus::Bound;
 use mixnet_contract_common::mixnode::{MixNodeConfigUpdate, MixNodeCostParams};
-use mixnet_contract_common::{Gateway, MixId, MixNode};
+use mixnet_contract_common::{
+    Gateway, GatewayConfigUpdate, IdentityKey, MixId, MixNode, SphinxKey,
+};
 use vesting_contract_common::events::{
     new_ownership_transfer_event, new_periodic_vesting_account_event,
-    new_staking_address_update_event, new_track_gateway_unbond_event,
-    new_track_mixnode_unbond_event, new_track_reward_event, new_track_undelegation_event,
+    new_staking_address_update_event, new_track_decrease_pledge_event,
+    new_track_gateway_ownership_transfer_event, new_track_gateway_unbond_event,
+    new_track_mixnode_ownership_transfer_event, new_track_mixnode_unbond_event,
+    new_track_redelegation_event, new_track_reward_event, new_track_undelegation_event,
     new_vested_coins_withdraw_event,
 };
 use vesting_contract_common::messages::{
@@ -100,9 +104,32 @@ pub fn execute(
         ExecuteMsg::UpdateMixnodeConfig { new_config } => {
             try_update_mixnode_config(new_config, info, deps)
         }
+        ExecuteMsg::UpdateMixnodeKeys {
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+        } => try_update_mixnode_keys(
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+            info,
+            deps,
+        ),
         ExecuteMsg::UpdateMixnodeCostParams { new_costs } => {
             try_update_mixnode_cost_params(new_costs, info, deps)
         }
+        ExecuteMsg::ProposeMixnodeOwnershipTransfer { new_owner } => {
+            try_propose_mixnode_ownership_transfer(&new_owner, info, env, deps)
+        }
+        ExecuteMsg::CancelMixnodeOwnershipTransfer {} => {
+            try_cancel_mixnode_ownership_transfer(info, deps)
+        }
+        ExecuteMsg::AcceptMixnodeOwnershipTransfer { mix_id } => {
+            try_accept_mixnode_ownership_transfer(mix_id, info, deps)
+        }
+        ExecuteMsg::TrackMixnodeOwnershipTransfer { owner, new_owner } => {
+            try_track_mixnode_ownership_transfer(&owner, &new_owner, info, env, deps)
+        }
         ExecuteMsg::UpdateMixnetAddress { address } => {
             try_update_mixnet_address(address, info, deps)
         }
@@ -115,6 +142,12 @@ pub fn execute(
             mix_id,
             on_behalf_of,
         } => try_undelegate_from_mixnode(mix_id, on_behalf_of, info, deps),
+        ExecuteMsg::RedelegateMixnode {
+            from_mix_id,
+            to_mix_id,
+            amount,
+            on_behalf_of,
+        } => try_redelegate_mixnode(from_mix_id, to_mix_id, amount, on_behalf_of, info, deps),
         ExecuteMsg::CreateAccount {
             owner_address,
             staking_address,
@@ -137,6 +170,21 @@ pub fn execute(
             mix_id,
             amount,
         } => try_track_undelegation(&owner, mix_id, amount, info, deps),
+        ExecuteMsg::TrackRedelegation {
+            owner,
+            from_mix_id,
+            to_mix_id,
+            amount,
+            remaining,
+        } => try_track_redelegation(
+            &owner,
+            from_mix_id,
+            to_mix_id,
+            amount,
+            remaining,
+            info,
+            deps,
+        ),
         ExecuteMsg::BondMixnode {
             mix_node,
             cost_params,
@@ -152,19 +200,49 @@ pub fn execute(
             deps,
         ),
         ExecuteMsg::PledgeMore { amount } => try_pledge_more(deps, env, info, amount),
+        ExecuteMsg::DecreasePledge { amount } => try_decrease_pledge(deps, info, amount),
         ExecuteMsg::UnbondMixnode {} => try_unbond_mixnode(info, deps),
         ExecuteMsg::TrackUnbondMixnode { owner, amount } => {
             try_track_unbond_mixnode(&owner, amount, info, deps)
         }
+        ExecuteMsg::TrackDecreasePledge { owner, amount } => {
+            try_track_decrease_pledge(&owner, amount, info, deps)
+        }
         ExecuteMsg::BondGateway {
             gateway,
             owner_signature,
             amount,
         } => try_bond_gateway(gateway, owner_signature, amount, info, env, deps),
         ExecuteMsg::UnbondGateway {} => try_unbond_gateway(info, deps),
+        ExecuteMsg::UpdateGatewayConfig { new_config } => {
+            try_update_gateway_config(new_config, info, deps)
+        }
+        ExecuteMsg::UpdateGatewayKeys {
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+        } => try_update_gateway_keys(
+            new_identity_key,
+            new_sphinx_key,
+            owner_signature,
+            info,
+            deps,
+        ),
         ExecuteMsg::TrackUnbondGateway { owner, amount } => {
             try_track_unbond_gateway(&owner, amount, info, deps)
         }
+        ExecuteMsg::ProposeGatewayOwnershipTransfer { new_owner } => {
+            try_propose_gateway_ownership_transfer(&new_owner, info, env, deps)
+        }
+        ExecuteMsg::CancelGatewayOwnershipTransfer {} => {
+            try_cancel_gateway_ownership_transfer(info, deps)
+        }
+        ExecuteMsg::AcceptGatewayOwnershipTransfer { identity } => {
+            try_accept_gateway_ownership_transfer(identity, info, deps)
+        }
+        ExecuteMsg::TrackGatewayOwnershipTransfer { owner, new_owner } => {
+            try_track_gateway_ownership_transfer(&owner, &new_owner, info, env, deps)
+        }
         ExecuteMsg::TransferOwnership { to_address } => {
             try_transfer_ownership(to_address, info, deps)
         }
@@ -240,6 +318,54 @@ pub fn try_update_mixnode_config(
     account.try_update_mixnode_config(new_config, deps.storage)
 }
 
+/// Rotate the keys of a mixnode bonded with vesting account, sends [mixnet_contract_common::ExecuteMsg::UpdateMixnodeKeysOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_update_mixnode_keys(
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner_signature: String,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_update_mixnode_keys(
+        new_identity_key,
+        new_sphinx_key,
+        owner_signature,
+        deps.storage,
+    )
+}
+
+/// Propose transferring the ownership of a mixnode bonded with vesting account to another vesting account, sends [mixnet_contract_common::ExecuteMsg::ProposeMixnodeOwnershipTransferOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_propose_mixnode_ownership_transfer(
+    new_owner: &str,
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    let new_owner_account = account_from_address(new_owner, deps.storage, deps.api)?;
+    account.try_propose_mixnode_ownership_transfer(&new_owner_account, &env, deps.storage)
+}
+
+/// Cancel the pending mixnode ownership transfer, sends [mixnet_contract_common::ExecuteMsg::CancelMixnodeOwnershipTransferOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_cancel_mixnode_ownership_transfer(
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_cancel_mixnode_ownership_transfer(deps.storage)
+}
+
+/// Accept the ownership of a mixnode bonded with another vesting account, sends [mixnet_contract_common::ExecuteMsg::AcceptMixnodeOwnershipTransferOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_accept_mixnode_ownership_transfer(
+    mix_id: MixId,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_accept_mixnode_ownership_transfer(mix_id, deps.storage)
+}
+
 pub fn try_update_mixnode_cost_params(
     new_costs: MixNodeCostParams,
     info: MessageInfo,
@@ -370,6 +496,33 @@ pub fn try_unbond_gateway(info: MessageInfo, deps: DepsMut<'_>) -> Result<Respon
     account.try_unbond_gateway(deps.storage)
 }
 
+/// Update gateway configuration, sends [mixnet_contract_common::ExecuteMsg::UpdateGatewayConfigOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_update_gateway_config(
+    new_config: GatewayConfigUpdate,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_update_gateway_config(new_config, deps.storage)
+}
+
+/// Rotate the keys of a gateway bonded with vesting account, sends [mixnet_contract_common::ExecuteMsg::UpdateGatewayKeysOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_update_gateway_keys(
+    new_identity_key: IdentityKey,
+    new_sphinx_key: SphinxKey,
+    owner_signature: String,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_update_gateway_keys(
+        new_identity_key,
+        new_sphinx_key,
+        owner_signature,
+        deps.storage,
+    )
+}
+
 /// Track gateway unbonding, invoked by the mixnet contract after succesful unbonding, message containes coins returned including any accrued rewards.
 pub fn try_track_unbond_gateway(
     owner: &str,
@@ -385,6 +538,54 @@ pub fn try_track_unbond_gateway(
     Ok(Response::new().add_event(new_track_gateway_unbond_event()))
 }
 
+/// Propose transferring the ownership of a gateway bonded with vesting account to another vesting account, sends [mixnet_contract_common::ExecuteMsg::ProposeGatewayOwnershipTransferOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_propose_gateway_ownership_transfer(
+    new_owner: &str,
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    let new_owner_account = account_from_address(new_owner, deps.storage, deps.api)?;
+    account.try_propose_gateway_ownership_transfer(&new_owner_account, &env, deps.storage)
+}
+
+/// Cancel the pending gateway ownership transfer, sends [mixnet_contract_common::ExecuteMsg::CancelGatewayOwnershipTransferOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_cancel_gateway_ownership_transfer(
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_cancel_gateway_ownership_transfer(deps.storage)
+}
+
+/// Accept the ownership of a gateway bonded with another vesting account, sends [mixnet_contract_common::ExecuteMsg::AcceptGatewayOwnershipTransferOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_accept_gateway_ownership_transfer(
+    identity: IdentityKey,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_accept_gateway_ownership_transfer(identity, deps.storage)
+}
+
+/// Track gateway ownership transfer, invoked by the mixnet contract once the new owner accepted it, moves the pledge between the vesting accounts.
+pub fn try_track_gateway_ownership_transfer(
+    owner: &str,
+    new_owner: &str,
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    if info.sender != MIXNET_CONTRACT_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::NotMixnetContract(info.sender));
+    }
+    let account = account_from_address(owner, deps.storage, deps.api)?;
+    let new_owner_account = account_from_address(new_owner, deps.storage, deps.api)?;
+    account.try_track_gateway_ownership_transfer(&new_owner_account, &env, deps.storage)?;
+    Ok(Response::new().add_event(new_track_gateway_ownership_transfer_event()))
+}
+
 /// Bond a mixnode, sends [mixnet_contract_common::ExecuteMsg::BondMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
 pub fn try_bond_mixnode(
     mix_node: MixNode,
@@ -421,6 +622,19 @@ pub fn try_pledge_more(
     account.try_pledge_additional_tokens(additional_pledge, &env, deps.storage)
 }
 
+/// Decrease the pledge of a mixnode, sends [mixnet_contract_common::ExecuteMsg::DecreasePledgeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+pub fn try_decrease_pledge(
+    deps: DepsMut<'_>,
+    info: MessageInfo,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    let mix_denom = MIX_DENOM.load(deps.storage)?;
+    let decrease_by = validate_funds(&[amount], mix_denom)?;
+
+    let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
+    account.try_decrease_pledge(decrease_by, deps.storage)
+}
+
 /// Unbond a mixnode, sends [mixnet_contract_common::ExecuteMsg::UnbondMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
 pub fn try_unbond_mixnode(info: MessageInfo, deps: DepsMut<'_>) -> Result<Response, ContractError> {
     let account = account_from_address(info.sender.as_str(), deps.storage, deps.api)?;
@@ -442,6 +656,38 @@ pub fn try_track_unbond_mixnode(
     Ok(Response::new().add_event(new_track_mixnode_unbond_event()))
 }
 
+/// Track mixnode ownership transfer, invoked by the mixnet contract once the new owner accepted it, moves the pledge between the vesting accounts.
+pub fn try_track_mixnode_ownership_transfer(
+    owner: &str,
+    new_owner: &str,
+    info: MessageInfo,
+    env: Env,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    if info.sender != MIXNET_CONTRACT_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::NotMixnetContract(info.sender));
+    }
+    let account = account_from_address(owner, deps.storage, deps.api)?;
+    let new_owner_account = account_from_address(new_owner, deps.storage, deps.api)?;
+    account.try_track_mixnode_ownership_transfer(&new_owner_account, &env, deps.storage)?;
+    Ok(Response::new().add_event(new_track_mixnode_ownership_transfer_event()))
+}
+
+/// Track pledge decrease, invoked by the mixnet contract after the pending decrease got executed, message contains the coins returned to the account.
+pub fn try_track_decrease_pledge(
+    owner: &str,
+    amount: Coin,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    if info.sender != MIXNET_CONTRACT_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::NotMixnetContract(info.sender));
+    }
+    let account = account_from_address(owner, deps.storage, deps.api)?;
+    account.try_track_decrease_pledge(amount, deps.storage)?;
+    Ok(Response::new().add_event(new_track_decrease_pledge_event()))
+}
+
 /// Track reward collection, invoked by the mixnert contract after sucessful reward compounding or claiming
 fn try_track_reward(
     deps: DepsMut<'_>,
@@ -474,6 +720,24 @@ fn try_track_undelegation(
     Ok(Response::new().add_event(new_track_undelegation_event()))
 }
 
+fn try_track_redelegation(
+    address: &str,
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    remaining: Coin,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    if info.sender != MIXNET_CONTRACT_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::NotMixnetContract(info.sender));
+    }
+    let account = account_from_address(address, deps.storage, deps.api)?;
+
+    account.track_redelegation(from_mix_id, to_mix_id, amount, remaining, deps.storage)?;
+    Ok(Response::new().add_event(new_track_redelegation_event()))
+}
+
 /// Delegate to mixnode, sends [mixnet_contract_common::ExecuteMsg::DelegateToMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS]..
 fn try_delegate_to_mixnode(
     mix_id: MixId,
@@ -539,6 +803,31 @@ fn try_undelegate_from_mixnode(
     account.try_undelegate_from_mixnode(mix_id, deps.storage)
 }
 
+/// Moves (part of) a delegation to a different mixnode, sends [mixnet_contract_common::ExecuteMsg::RedelegateMixnodeOnBehalf] to [crate::storage::MIXNET_CONTRACT_ADDRESS].
+fn try_redelegate_mixnode(
+    from_mix_id: MixId,
+    to_mix_id: MixId,
+    amount: Coin,
+    on_behalf_of: Option<String>,
+    info: MessageInfo,
+    deps: DepsMut<'_>,
+) -> Result<Response, ContractError> {
+    let mix_denom = MIX_DENOM.load(deps.storage)?;
+    let amount = validate_funds(&[amount], mix_denom)?;
+
+    let account = match on_behalf_of {
+        Some(account_owner) => {
+            let account = account_from_address(&account_owner, deps.storage, deps.api)?;
+            ensure_staking_permission(&info.sender, &account)?;
+            account
+        }
+        // you're the owner, you can do what you want
+        None => account_from_address(info.sender.as_str(), deps.storage, deps.api)?,
+    };
+
+    account.try_redelegate_mixnode(from_mix_id, to_mix_id, amount, deps.storage)
+}
+
 /// Creates a new periodic vesting account, and deposits funds to vest into the contract.
 ///
 /// Callable by ADMIN only, see [instantiate].
@@ -69,6 +69,9 @@ pub enum ContractError {
     #[error("VESTING ({}): Too few coins sent for vesting account creation, sent {sent}, need at least {need}", line!())]
     MinVestingFunds { sent: u128, need: u128 },
 
+    #[error("VESTING ({}): {amount} of the pledge of {owner} is still vesting, thus the node cannot change owners", line!())]
+    PledgeStillVesting { owner: Addr, amount: Uint128 },
+
     #[error("VESTING ({}): Maximum amount of locked coins has already been pledged: {current}, cap is {cap}", line!())]
     LockedPledgeCapReached { current: Uint128, cap: Uint128 },
 
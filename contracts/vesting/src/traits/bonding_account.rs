@@ -1,8 +1,9 @@
 use crate::errors::ContractError;
+use crate::vesting::Account;
 use cosmwasm_std::{Coin, Env, Response, Storage};
 use mixnet_contract_common::{
     mixnode::{MixNodeConfigUpdate, MixNodeCostParams},
-    Gateway, MixNode,
+    Gateway, GatewayConfigUpdate, IdentityKey, MixId, MixNode, SphinxKey,
 };
 
 pub trait MixnodeBondingAccount {
@@ -25,6 +26,18 @@ pub trait MixnodeBondingAccount {
         storage: &mut dyn Storage,
     ) -> Result<Response, ContractError>;
 
+    fn try_decrease_pledge(
+        &self,
+        decrease_by: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_track_decrease_pledge(
+        &self,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError>;
+
     fn try_unbond_mixnode(&self, storage: &dyn Storage) -> Result<Response, ContractError>;
 
     fn try_track_unbond_mixnode(
@@ -44,6 +57,39 @@ pub trait MixnodeBondingAccount {
         new_costs: MixNodeCostParams,
         storage: &mut dyn Storage,
     ) -> Result<Response, ContractError>;
+
+    fn try_update_mixnode_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_propose_mixnode_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_cancel_mixnode_ownership_transfer(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_accept_mixnode_ownership_transfer(
+        &self,
+        mix_id: MixId,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_track_mixnode_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError>;
 }
 
 pub trait GatewayBondingAccount {
@@ -63,4 +109,43 @@ pub trait GatewayBondingAccount {
         amount: Coin,
         storage: &mut dyn Storage,
     ) -> Result<(), ContractError>;
+
+    fn try_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        storage: &mut dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_update_gateway_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_propose_gateway_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_cancel_gateway_ownership_transfer(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_accept_gateway_ownership_transfer(
+        &self,
+        identity: IdentityKey,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
+    fn try_track_gateway_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError>;
 }
@@ -23,6 +23,14 @@ pub trait DelegatingAccount {
         storage: &dyn Storage,
     ) -> Result<Response, ContractError>;
 
+    fn try_redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError>;
+
     // track_delegation performs internal vesting accounting necessary when
     // delegating from a vesting account. It accepts the current block height, the
     // delegation amount and balance of all coins whose denomination exists in
@@ -44,4 +52,15 @@ pub trait DelegatingAccount {
         amount: Coin,
         storage: &mut dyn Storage,
     ) -> Result<(), ContractError>;
+    // track_redelegation performs internal vesting accounting necessary when
+    // (part of) a delegation of a vesting account got moved to a different mixnode.
+    // No tokens are returned to the account, so its balance is not affected.
+    fn track_redelegation(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        remaining: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError>;
 }
@@ -7,7 +7,7 @@ use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
 use mixnet_contract_common::ExecuteMsg as MixnetExecuteMsg;
 use mixnet_contract_common::MixId;
 use vesting_contract_common::events::{
-    new_vesting_delegation_event, new_vesting_undelegation_event,
+    new_vesting_delegation_event, new_vesting_redelegation_event, new_vesting_undelegation_event,
 };
 
 use super::Account;
@@ -101,6 +101,34 @@ impl DelegatingAccount for Account {
             .add_event(new_vesting_undelegation_event()))
     }
 
+    fn try_redelegate_mixnode(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if !self.any_delegation_for_mix(from_mix_id, storage) {
+            return Err(ContractError::NoSuchDelegation(
+                self.owner_address(),
+                from_mix_id,
+            ));
+        }
+
+        let msg = MixnetExecuteMsg::RedelegateMixnodeOnBehalf {
+            from_mix_id,
+            to_mix_id,
+            amount,
+            delegate: self.owner_address().into_string(),
+        };
+        let redelegate_mixnode =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(redelegate_mixnode)
+            .add_event(new_vesting_redelegation_event()))
+    }
+
     fn track_delegation(
         &self,
         block_timestamp_secs: u64,
@@ -130,4 +158,22 @@ impl DelegatingAccount for Account {
         self.save_balance(new_balance, storage)?;
         Ok(())
     }
+
+    fn track_redelegation(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Coin,
+        remaining: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        // the moved tokens include the rewards, so move the same share of the tracked delegations
+        let tracked = self.total_delegations_for_mix(from_mix_id, storage)?;
+        let to_move = if remaining.amount.is_zero() {
+            tracked
+        } else {
+            tracked.multiply_ratio(amount.amount, amount.amount + remaining.amount)
+        };
+        self.move_delegations(from_mix_id, to_mix_id, to_move, storage)
+    }
 }
@@ -4,9 +4,14 @@ use crate::storage::MIXNET_CONTRACT_ADDRESS;
 use crate::traits::GatewayBondingAccount;
 use crate::traits::VestingAccount;
 use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
-use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, Gateway};
+use mixnet_contract_common::{
+    ExecuteMsg as MixnetExecuteMsg, Gateway, GatewayConfigUpdate, IdentityKey, SphinxKey,
+};
 use vesting_contract_common::events::{
-    new_vesting_gateway_bonding_event, new_vesting_gateway_unbonding_event,
+    new_vesting_gateway_bonding_event, new_vesting_gateway_ownership_transfer_cancellation_event,
+    new_vesting_gateway_ownership_transfer_event,
+    new_vesting_gateway_ownership_transfer_proposal_event, new_vesting_gateway_unbonding_event,
+    new_vesting_update_gateway_config_event, new_vesting_update_gateway_keys_event,
 };
 
 use super::Account;
@@ -95,4 +100,147 @@ impl GatewayBondingAccount for Account {
         self.remove_gateway_pledge(storage)?;
         Ok(())
     }
+
+    fn try_update_gateway_config(
+        &self,
+        new_config: GatewayConfigUpdate,
+        storage: &mut dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let msg = MixnetExecuteMsg::UpdateGatewayConfigOnBehalf {
+            new_config,
+            owner: self.owner_address().into_string(),
+        };
+
+        let update_gateway_config_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(update_gateway_config_msg)
+            .add_event(new_vesting_update_gateway_config_event()))
+    }
+
+    fn try_update_gateway_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let msg = MixnetExecuteMsg::UpdateGatewayKeysOnBehalf {
+            new_identity_key,
+            new_sphinx_key,
+            owner: self.owner_address().into_string(),
+            owner_signature,
+        };
+
+        let update_gateway_keys_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(update_gateway_keys_msg)
+            .add_event(new_vesting_update_gateway_keys_event()))
+    }
+
+    fn try_propose_gateway_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_gateway_pledge(storage)?.is_none() {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // fail early rather than only once the new owner accepts the transfer
+        self.ensure_pledge_vested(env, storage)?;
+
+        let msg = MixnetExecuteMsg::ProposeGatewayOwnershipTransferOnBehalf {
+            owner: self.owner_address().into_string(),
+            new_owner: new_owner.owner_address().into_string(),
+        };
+
+        let propose_transfer_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(propose_transfer_msg)
+            .add_event(new_vesting_gateway_ownership_transfer_proposal_event()))
+    }
+
+    fn try_cancel_gateway_ownership_transfer(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let msg = MixnetExecuteMsg::CancelGatewayOwnershipTransferOnBehalf {
+            owner: self.owner_address().into_string(),
+        };
+
+        let cancel_transfer_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(cancel_transfer_msg)
+            .add_event(new_vesting_gateway_ownership_transfer_cancellation_event()))
+    }
+
+    fn try_accept_gateway_ownership_transfer(
+        &self,
+        identity: IdentityKey,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_mixnode_pledge(storage)?.is_some()
+            || self.load_gateway_pledge(storage)?.is_some()
+        {
+            return Err(ContractError::AlreadyBonded(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // the pledge itself is moved over once the mixnet contract tracks the transfer
+        let msg = MixnetExecuteMsg::AcceptGatewayOwnershipTransferOnBehalf {
+            identity,
+            new_owner: self.owner_address().into_string(),
+        };
+
+        let accept_transfer_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(accept_transfer_msg)
+            .add_event(new_vesting_gateway_ownership_transfer_event()))
+    }
+
+    fn try_track_gateway_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        let pledge_data = if let Some(pledge_data) = self.load_gateway_pledge(storage)? {
+            pledge_data
+        } else {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        };
+
+        if new_owner.load_mixnode_pledge(storage)?.is_some()
+            || new_owner.load_gateway_pledge(storage)?.is_some()
+        {
+            return Err(ContractError::AlreadyBonded(
+                new_owner.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // the pledge has to be fully vested from the point of view of both accounts,
+        // so that none of the tokens could escape either of the vesting schedules
+        self.ensure_pledge_vested(env, storage)?;
+
+        self.remove_gateway_pledge(storage)?;
+        new_owner.save_gateway_pledge(pledge_data, storage)?;
+
+        new_owner.ensure_pledge_vested(env, storage)
+    }
 }
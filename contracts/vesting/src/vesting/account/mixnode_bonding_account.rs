@@ -9,11 +9,16 @@ use crate::traits::VestingAccount;
 use cosmwasm_std::{wasm_execute, Coin, Env, Response, Storage, Uint128};
 use mixnet_contract_common::mixnode::MixNodeConfigUpdate;
 use mixnet_contract_common::mixnode::MixNodeCostParams;
-use mixnet_contract_common::{ExecuteMsg as MixnetExecuteMsg, MixNode};
+use mixnet_contract_common::{
+    ExecuteMsg as MixnetExecuteMsg, IdentityKey, MixId, MixNode, SphinxKey,
+};
 use vesting_contract_common::events::{
-    new_vesting_mixnode_bonding_event, new_vesting_mixnode_unbonding_event,
+    new_vesting_decrease_pledge_event, new_vesting_mixnode_bonding_event,
+    new_vesting_mixnode_ownership_transfer_cancellation_event,
+    new_vesting_mixnode_ownership_transfer_event,
+    new_vesting_mixnode_ownership_transfer_proposal_event, new_vesting_mixnode_unbonding_event,
     new_vesting_pledge_more_event, new_vesting_update_mixnode_config_event,
-    new_vesting_update_mixnode_cost_params_event,
+    new_vesting_update_mixnode_cost_params_event, new_vesting_update_mixnode_keys_event,
 };
 use vesting_contract_common::PledgeData;
 
@@ -143,6 +148,53 @@ impl MixnodeBondingAccount for Account {
             .add_event(new_vesting_pledge_more_event()))
     }
 
+    fn try_decrease_pledge(
+        &self,
+        decrease_by: Coin,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_mixnode_pledge(storage)?.is_none() {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // the pledge data is only going to get updated once the mixnet contract actually
+        // returns the tokens at the end of the epoch
+        let msg = MixnetExecuteMsg::DecreasePledgeOnBehalf {
+            owner: self.owner_address().into_string(),
+            amount: decrease_by,
+        };
+
+        let decrease_pledge_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(decrease_pledge_msg)
+            .add_event(new_vesting_decrease_pledge_event()))
+    }
+
+    fn try_track_decrease_pledge(
+        &self,
+        amount: Coin,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        let mut pledge_data = if let Some(pledge_data) = self.load_mixnode_pledge(storage)? {
+            pledge_data
+        } else {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        };
+
+        let new_balance = Uint128::new(self.load_balance(storage)?.u128() + amount.amount.u128());
+        pledge_data.amount.amount = pledge_data.amount.amount.saturating_sub(amount.amount);
+
+        self.save_balance(new_balance, storage)?;
+        self.save_mixnode_pledge(pledge_data, storage)?;
+        Ok(())
+    }
+
     fn try_unbond_mixnode(&self, storage: &dyn Storage) -> Result<Response, ContractError> {
         let msg = MixnetExecuteMsg::UnbondMixnodeOnBehalf {
             owner: self.owner_address().into_string(),
@@ -191,6 +243,28 @@ impl MixnodeBondingAccount for Account {
             .add_event(new_vesting_update_mixnode_config_event()))
     }
 
+    fn try_update_mixnode_keys(
+        &self,
+        new_identity_key: IdentityKey,
+        new_sphinx_key: SphinxKey,
+        owner_signature: String,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let msg = MixnetExecuteMsg::UpdateMixnodeKeysOnBehalf {
+            new_identity_key,
+            new_sphinx_key,
+            owner: self.owner_address().into_string(),
+            owner_signature,
+        };
+
+        let update_mixnode_keys_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(update_mixnode_keys_msg)
+            .add_event(new_vesting_update_mixnode_keys_event()))
+    }
+
     fn try_update_mixnode_cost_params(
         &self,
         new_costs: MixNodeCostParams,
@@ -208,4 +282,107 @@ impl MixnodeBondingAccount for Account {
             .add_message(update_mixnode_costs_msg)
             .add_event(new_vesting_update_mixnode_cost_params_event()))
     }
+
+    fn try_propose_mixnode_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_mixnode_pledge(storage)?.is_none() {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // fail early rather than only once the new owner accepts the transfer
+        self.ensure_pledge_vested(env, storage)?;
+
+        let msg = MixnetExecuteMsg::ProposeMixnodeOwnershipTransferOnBehalf {
+            owner: self.owner_address().into_string(),
+            new_owner: new_owner.owner_address().into_string(),
+        };
+
+        let propose_transfer_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(propose_transfer_msg)
+            .add_event(new_vesting_mixnode_ownership_transfer_proposal_event()))
+    }
+
+    fn try_cancel_mixnode_ownership_transfer(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        let msg = MixnetExecuteMsg::CancelMixnodeOwnershipTransferOnBehalf {
+            owner: self.owner_address().into_string(),
+        };
+
+        let cancel_transfer_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(cancel_transfer_msg)
+            .add_event(new_vesting_mixnode_ownership_transfer_cancellation_event()))
+    }
+
+    fn try_accept_mixnode_ownership_transfer(
+        &self,
+        mix_id: MixId,
+        storage: &dyn Storage,
+    ) -> Result<Response, ContractError> {
+        if self.load_mixnode_pledge(storage)?.is_some()
+            || self.load_gateway_pledge(storage)?.is_some()
+        {
+            return Err(ContractError::AlreadyBonded(
+                self.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // the pledge itself is moved over once the mixnet contract tracks the transfer
+        let msg = MixnetExecuteMsg::AcceptMixnodeOwnershipTransferOnBehalf {
+            mix_id,
+            new_owner: self.owner_address().into_string(),
+        };
+
+        let accept_transfer_msg =
+            wasm_execute(MIXNET_CONTRACT_ADDRESS.load(storage)?, &msg, vec![])?;
+
+        Ok(Response::new()
+            .add_message(accept_transfer_msg)
+            .add_event(new_vesting_mixnode_ownership_transfer_event()))
+    }
+
+    fn try_track_mixnode_ownership_transfer(
+        &self,
+        new_owner: &Account,
+        env: &Env,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        let pledge_data = if let Some(pledge_data) = self.load_mixnode_pledge(storage)? {
+            pledge_data
+        } else {
+            return Err(ContractError::NoBondFound(
+                self.owner_address().as_str().to_string(),
+            ));
+        };
+
+        if new_owner.load_mixnode_pledge(storage)?.is_some()
+            || new_owner.load_gateway_pledge(storage)?.is_some()
+        {
+            return Err(ContractError::AlreadyBonded(
+                new_owner.owner_address().as_str().to_string(),
+            ));
+        }
+
+        // the pledge has to be fully vested from the point of view of both accounts,
+        // so that none of the tokens could escape either of the vesting schedules
+        self.ensure_pledge_vested(env, storage)?;
+
+        self.remove_mixnode_pledge(storage)?;
+        new_owner.save_mixnode_pledge(pledge_data, storage)?;
+
+        new_owner.ensure_pledge_vested(env, storage)
+    }
 }
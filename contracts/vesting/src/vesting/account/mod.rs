@@ -3,10 +3,11 @@ use crate::errors::ContractError;
 use crate::storage::{
     load_balance, load_bond_pledge, load_gateway_pledge, load_withdrawn, remove_bond_pledge,
     remove_delegation, remove_gateway_pledge, save_account, save_balance, save_bond_pledge,
-    save_gateway_pledge, save_withdrawn, AccountStorageKey, BlockTimestampSecs, DELEGATIONS, KEY,
+    save_delegation, save_gateway_pledge, save_withdrawn, AccountStorageKey, BlockTimestampSecs,
+    DELEGATIONS, KEY,
 };
 use crate::traits::VestingAccount;
-use cosmwasm_std::{Addr, Coin, Order, Storage, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Coin, Env, Order, StdResult, Storage, Timestamp, Uint128};
 use cw_storage_plus::Bound;
 use mixnet_contract_common::MixId;
 use schemars::JsonSchema;
@@ -236,6 +237,23 @@ impl Account {
         remove_gateway_pledge(self.storage_key(), storage)
     }
 
+    /// Makes sure no part of the node pledge counts towards the coins that are still vesting,
+    /// as otherwise they could become spendable by another account once the node changes owners.
+    pub fn ensure_pledge_vested(
+        &self,
+        env: &Env,
+        storage: &dyn Storage,
+    ) -> Result<(), ContractError> {
+        let pledged_vesting = self.get_pledged_vesting(None, env, storage)?;
+        if !pledged_vesting.amount.is_zero() {
+            return Err(ContractError::PledgeStillVesting {
+                owner: self.owner_address(),
+                amount: pledged_vesting.amount,
+            });
+        }
+        Ok(())
+    }
+
     pub fn any_delegation_for_mix(&self, mix_id: MixId, storage: &dyn Storage) -> bool {
         DELEGATIONS
             .prefix((self.storage_key(), mix_id))
@@ -281,6 +299,42 @@ impl Account {
         Ok(())
     }
 
+    /// Moves the specified amount of tracked delegations towards a different mixnode,
+    /// starting from the oldest ones. The timestamps of the delegations are preserved.
+    pub fn move_delegations(
+        &self,
+        from_mix_id: MixId,
+        to_mix_id: MixId,
+        amount: Uint128,
+        storage: &mut dyn Storage,
+    ) -> Result<(), ContractError> {
+        let delegations = DELEGATIONS
+            .prefix((self.storage_key(), from_mix_id))
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut left = amount;
+        for (block_timestamp, delegation) in delegations {
+            if left.is_zero() {
+                break;
+            }
+            let moved = delegation.min(left);
+            left -= moved;
+
+            let from_key = (self.storage_key(), from_mix_id, block_timestamp);
+            if moved == delegation {
+                remove_delegation(from_key, storage)?;
+            } else {
+                save_delegation(from_key, delegation - moved, storage)?;
+            }
+
+            let to_key = (self.storage_key(), to_mix_id, block_timestamp);
+            let existing = DELEGATIONS.may_load(storage, to_key)?.unwrap_or_default();
+            save_delegation(to_key, existing + moved, storage)?;
+        }
+        Ok(())
+    }
+
     pub fn total_delegations_for_mix(
         &self,
         mix_id: MixId,
@@ -739,6 +739,137 @@ mod tests {
         assert_eq!(Uint128::zero(), delegated_free.amount);
     }
 
+    #[test]
+    fn test_redelegations() {
+        let mut deps = init_contract();
+        let env = mock_env();
+        let mut later_env = mock_env();
+        later_env.block.time = env.block.time.plus_seconds(3600);
+
+        let msg = ExecuteMsg::CreateAccount {
+            owner_address: "owner".to_string(),
+            staking_address: None,
+            vesting_spec: None,
+            cap: Some(PledgeCap::Absolute(Uint128::from(100_000_000_000u128))),
+        };
+        let info = mock_info("admin", &coins(1_000_000_000_000, TEST_COIN_DENOM));
+        let _response = execute(deps.as_mut(), env.clone(), info, msg);
+        let account = load_account(Addr::unchecked("owner"), &deps.storage)
+            .unwrap()
+            .unwrap();
+
+        account
+            .try_delegate_to_mixnode(
+                1,
+                coin(30_000_000_000, TEST_COIN_DENOM),
+                &env,
+                &mut deps.storage,
+            )
+            .unwrap();
+        account
+            .try_delegate_to_mixnode(
+                1,
+                coin(10_000_000_000, TEST_COIN_DENOM),
+                &later_env,
+                &mut deps.storage,
+            )
+            .unwrap();
+        account
+            .try_delegate_to_mixnode(
+                2,
+                coin(5_000_000_000, TEST_COIN_DENOM),
+                &env,
+                &mut deps.storage,
+            )
+            .unwrap();
+        let balance = account.load_balance(&deps.storage).unwrap();
+
+        // can't move delegation that doesn't exist
+        let err = account
+            .try_redelegate_mixnode(3, 2, coin(1_000_000, TEST_COIN_DENOM), &deps.storage)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NoSuchDelegation(account.owner_address(), 3)
+        );
+        assert!(account
+            .try_redelegate_mixnode(1, 2, coin(1_000_000, TEST_COIN_DENOM), &deps.storage)
+            .is_ok());
+
+        // only the mixnet contract can tell us about the completed redelegation
+        let msg = ExecuteMsg::TrackRedelegation {
+            owner: "owner".to_string(),
+            from_mix_id: 1,
+            to_mix_id: 2,
+            amount: coin(24_000_000_000, TEST_COIN_DENOM),
+            remaining: coin(24_000_000_000, TEST_COIN_DENOM),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotMixnetContract(Addr::unchecked("owner"))
+        );
+
+        // half of the delegation (alongside its rewards) got moved,
+        // so half of the tracked tokens are moved as well, oldest first
+        let mixnet_contract = MIXNET_CONTRACT_ADDRESS.load(&deps.storage).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(mixnet_contract.as_str(), &[]),
+            msg,
+        )
+        .unwrap();
+
+        let start = env.block.time.seconds();
+        let later = later_env.block.time.seconds();
+        let load = |mix_id, timestamp| {
+            DELEGATIONS
+                .may_load(&deps.storage, (account.storage_key(), mix_id, timestamp))
+                .unwrap()
+        };
+        assert_eq!(load(1, start), Some(Uint128::new(10_000_000_000)));
+        assert_eq!(load(1, later), Some(Uint128::new(10_000_000_000)));
+        assert_eq!(load(2, start), Some(Uint128::new(25_000_000_000)));
+        assert_eq!(load(2, later), None);
+
+        // tokens never left the contract
+        assert_eq!(balance, account.load_balance(&deps.storage).unwrap());
+        assert_eq!(
+            Uint128::new(45_000_000_000),
+            account.total_delegations(&deps.storage).unwrap()
+        );
+
+        // if nothing remains on the original node, everything is moved
+        account
+            .track_redelegation(
+                1,
+                2,
+                coin(30_000_000_000, TEST_COIN_DENOM),
+                coin(0, TEST_COIN_DENOM),
+                &mut deps.storage,
+            )
+            .unwrap();
+        assert!(!account.any_delegation_for_mix(1, &deps.storage));
+        assert_eq!(
+            Uint128::new(45_000_000_000),
+            account.total_delegations_for_mix(2, &deps.storage).unwrap()
+        );
+        assert_eq!(
+            DELEGATIONS
+                .may_load(&deps.storage, (account.storage_key(), 2, later))
+                .unwrap(),
+            Some(Uint128::new(10_000_000_000))
+        );
+        assert_eq!(balance, account.load_balance(&deps.storage).unwrap());
+    }
+
     #[test]
     fn test_mixnode_bonds() {
         let mut deps = init_contract();
@@ -869,6 +1000,299 @@ mod tests {
         assert_eq!(Uint128::zero(), bonded_vesting.amount);
     }
 
+    #[test]
+    fn test_mixnode_pledge_decrease() {
+        let mut deps = init_contract();
+        let env = mock_env();
+
+        let account = vesting_account_new_fixture(&mut deps.storage, &env);
+        let decrease_by = Coin {
+            amount: Uint128::new(30_000_000_000),
+            denom: TEST_COIN_DENOM.to_string(),
+        };
+
+        // can't decrease the pledge without having bonded anything
+        let err = account.try_decrease_pledge(decrease_by.clone(), &deps.storage);
+        assert!(err.is_err());
+
+        let mix_node = MixNode {
+            host: "mix.node.org".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: "sphinx".to_string(),
+            identity_key: "identity".to_string(),
+            version: "0.10.0".to_string(),
+        };
+        let cost_params = MixNodeCostParams {
+            profit_margin_percent: Percent::from_percentage_value(10).unwrap(),
+            interval_operating_cost: Coin {
+                denom: "NYM".to_string(),
+                amount: Uint128::new(40),
+            },
+        };
+        account
+            .try_bond_mixnode(
+                mix_node,
+                cost_params,
+                "alice".to_string(),
+                Coin {
+                    amount: Uint128::new(90_000_000_000),
+                    denom: TEST_COIN_DENOM.to_string(),
+                },
+                &env,
+                &mut deps.storage,
+            )
+            .unwrap();
+
+        // the request itself doesn't change anything until the mixnet contract processes it
+        let ok = account.try_decrease_pledge(decrease_by.clone(), &deps.storage);
+        assert!(ok.is_ok());
+        let balance = account.load_balance(&deps.storage).unwrap();
+        assert_eq!(balance, Uint128::new(910_000_000_000));
+        let pledge = account.load_mixnode_pledge(&deps.storage).unwrap().unwrap();
+        assert_eq!(Uint128::new(90_000_000_000), pledge.amount().amount);
+
+        account
+            .try_track_decrease_pledge(decrease_by, &mut deps.storage)
+            .unwrap();
+        let balance = account.load_balance(&deps.storage).unwrap();
+        assert_eq!(balance, Uint128::new(940_000_000_000));
+        let pledge = account.load_mixnode_pledge(&deps.storage).unwrap().unwrap();
+        assert_eq!(Uint128::new(60_000_000_000), pledge.amount().amount);
+    }
+
+    fn account_with_vesting_start(
+        owner: &str,
+        start_time: u64,
+        storage: &mut dyn cosmwasm_std::Storage,
+    ) -> Account {
+        let periods = populate_vesting_periods(
+            start_time,
+            VestingSpecification::new(None, Some(3600), None),
+        );
+        Account::new(
+            Addr::unchecked(owner),
+            None,
+            coin(1_000_000_000_000, TEST_COIN_DENOM),
+            Timestamp::from_seconds(start_time),
+            periods,
+            None,
+            storage,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mixnode_ownership_transfer() {
+        let mut deps = init_contract();
+        let env = mock_env();
+        let now = env.block.time.seconds();
+
+        // all of the tokens of those accounts have already vested
+        let account = account_with_vesting_start("owner", now - 9 * 3600, &mut deps.storage);
+        let new_owner = account_with_vesting_start("new_owner", now - 9 * 3600, &mut deps.storage);
+        // while these are still vesting
+        let vesting_owner = account_with_vesting_start("vesting_owner", now, &mut deps.storage);
+
+        let mix_node = MixNode {
+            host: "mix.node.org".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: "sphinx".to_string(),
+            identity_key: "identity".to_string(),
+            version: "0.10.0".to_string(),
+        };
+        let cost_params = MixNodeCostParams {
+            profit_margin_percent: Percent::from_percentage_value(10).unwrap(),
+            interval_operating_cost: Coin {
+                denom: "NYM".to_string(),
+                amount: Uint128::new(40),
+            },
+        };
+        let pledge = coin(90_000_000_000, TEST_COIN_DENOM);
+
+        // there's nothing to transfer yet
+        let msg = ExecuteMsg::ProposeMixnodeOwnershipTransfer {
+            new_owner: "new_owner".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+        assert_eq!(res, Err(ContractError::NoBondFound("owner".to_string())));
+
+        for bonding_account in [&account, &vesting_owner] {
+            bonding_account
+                .try_bond_mixnode(
+                    mix_node.clone(),
+                    cost_params.clone(),
+                    "alice".to_string(),
+                    pledge.clone(),
+                    &env,
+                    &mut deps.storage,
+                )
+                .unwrap();
+        }
+
+        // the new owner must have a vesting account
+        let msg = ExecuteMsg::ProposeMixnodeOwnershipTransfer {
+            new_owner: "liquid_owner".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+        assert_eq!(
+            res,
+            Err(ContractError::NoAccountForAddress(
+                "liquid_owner".to_string()
+            ))
+        );
+
+        // and the pledge can't include any tokens that are still vesting
+        let msg = ExecuteMsg::ProposeMixnodeOwnershipTransfer {
+            new_owner: "new_owner".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("vesting_owner", &[]),
+            msg.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(ContractError::PledgeStillVesting {
+                owner: Addr::unchecked("vesting_owner"),
+                amount: pledge.amount,
+            })
+        );
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // the account accepting the transfer can't have bonded anything already
+        let msg = ExecuteMsg::AcceptMixnodeOwnershipTransfer { mix_id: 1 };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("vesting_owner", &[]),
+            msg.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(ContractError::AlreadyBonded("vesting_owner".to_string()))
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info("new_owner", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // the pledge only moves once the mixnet contract has transferred the bond
+        let msg = ExecuteMsg::TrackMixnodeOwnershipTransfer {
+            owner: "owner".to_string(),
+            new_owner: "new_owner".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        );
+        assert_eq!(
+            res,
+            Err(ContractError::NotMixnetContract(Addr::unchecked("owner")))
+        );
+
+        let balance_before = account.load_balance(&deps.storage).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("test", &[]), msg).unwrap();
+        assert!(account
+            .load_mixnode_pledge(&deps.storage)
+            .unwrap()
+            .is_none());
+        let moved_pledge = new_owner
+            .load_mixnode_pledge(&deps.storage)
+            .unwrap()
+            .unwrap();
+        assert_eq!(moved_pledge.amount(), pledge);
+        assert_eq!(account.load_balance(&deps.storage).unwrap(), balance_before);
+        assert_eq!(
+            new_owner.load_balance(&deps.storage).unwrap(),
+            Uint128::new(1_000_000_000_000)
+        );
+
+        // the tokens can't end up with an account that's still vesting either,
+        // as they'd become spendable once the node unbonds
+        let fresh_owner = account_with_vesting_start("fresh_owner", now, &mut deps.storage);
+        let res =
+            new_owner.try_track_mixnode_ownership_transfer(&fresh_owner, &env, &mut deps.storage);
+        assert_eq!(
+            res,
+            Err(ContractError::PledgeStillVesting {
+                owner: Addr::unchecked("fresh_owner"),
+                amount: pledge.amount,
+            })
+        );
+    }
+
+    #[test]
+    fn test_gateway_ownership_transfer() {
+        let mut deps = init_contract();
+        let env = mock_env();
+        let now = env.block.time.seconds();
+
+        let account = account_with_vesting_start("owner", now - 9 * 3600, &mut deps.storage);
+        let new_owner = account_with_vesting_start("new_owner", now - 9 * 3600, &mut deps.storage);
+
+        let gateway = Gateway {
+            host: "1.1.1.1".to_string(),
+            mix_port: 1789,
+            clients_port: 9000,
+            location: "Sweden".to_string(),
+            sphinx_key: "sphinx".to_string(),
+            identity_key: "identity".to_string(),
+            version: "0.10.0".to_string(),
+        };
+        let pledge = coin(90_000_000_000, TEST_COIN_DENOM);
+        account
+            .try_bond_gateway(
+                gateway,
+                "alice".to_string(),
+                pledge.clone(),
+                &env,
+                &mut deps.storage,
+            )
+            .unwrap();
+
+        let msg = ExecuteMsg::ProposeGatewayOwnershipTransfer {
+            new_owner: "liquid_owner".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+        assert_eq!(
+            res,
+            Err(ContractError::NoAccountForAddress(
+                "liquid_owner".to_string()
+            ))
+        );
+
+        let msg = ExecuteMsg::ProposeGatewayOwnershipTransfer {
+            new_owner: "new_owner".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::AcceptGatewayOwnershipTransfer {
+            identity: "identity".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::TrackGatewayOwnershipTransfer {
+            owner: "owner".to_string(),
+            new_owner: "new_owner".to_string(),
+        };
+        execute(deps.as_mut(), env, mock_info("test", &[]), msg).unwrap();
+        assert!(account
+            .load_gateway_pledge(&deps.storage)
+            .unwrap()
+            .is_none());
+        let moved_pledge = new_owner
+            .load_gateway_pledge(&deps.storage)
+            .unwrap()
+            .unwrap();
+        assert_eq!(moved_pledge.amount(), pledge);
+    }
+
     #[test]
     fn test_gateway_bonds() {
         let mut deps = init_contract();
@@ -0,0 +1,12 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+-- unix timestamp of when the message got received by the gateway
+ALTER TABLE message_store ADD COLUMN timestamp INTEGER NOT NULL DEFAULT 0;
+
+-- we don't know when the existing messages were received, so start their expiry from now
+UPDATE message_store SET timestamp = CAST(strftime('%s', 'now') AS INTEGER);
+
+CREATE INDEX `message_store_timestamp_index` ON `message_store` (`timestamp`);
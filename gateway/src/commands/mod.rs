@@ -15,6 +15,7 @@ use validator_client::nyxd::{self, AccountId};
 
 pub(crate) mod init;
 pub(crate) mod node_details;
+pub(crate) mod rotate_keys;
 pub(crate) mod run;
 pub(crate) mod sign;
 pub(crate) mod upgrade;
@@ -33,6 +34,9 @@ pub(crate) enum Commands {
     /// Sign text to prove ownership of this mixnode
     Sign(sign::Sign),
 
+    /// Generate new identity and sphinx keys to replace the bonded ones
+    RotateKeys(rotate_keys::RotateKeys),
+
     /// Try to upgrade the gateway
     Upgrade(upgrade::Upgrade),
 
@@ -73,6 +77,7 @@ pub(crate) async fn execute(args: Cli) -> Result<(), Box<dyn Error + Send + Sync
         Commands::NodeDetails(m) => node_details::execute(m, output.clone()).await?,
         Commands::Run(m) => run::execute(m, output.clone()).await?,
         Commands::Sign(m) => sign::execute(m)?,
+        Commands::RotateKeys(m) => rotate_keys::execute(m)?,
         Commands::Upgrade(m) => upgrade::execute(&m).await,
         Commands::Completions(s) => s.generate(&mut crate::Cli::command(), bin_name),
         Commands::GenerateFigSpec => fig_generate(&mut crate::Cli::command(), bin_name),
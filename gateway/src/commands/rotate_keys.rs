@@ -0,0 +1,128 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::commands::{
+    ensure_config_version_compatibility, ensure_correct_bech32_prefix, OverrideConfig,
+};
+use crate::config::persistence::pathfinder::GatewayPathfinder;
+use crate::error::GatewayError;
+use crate::support::config::build_config;
+use clap::Args;
+use crypto::asymmetric::{encryption, identity};
+use std::error::Error;
+use std::fs;
+use validator_client::nyxd;
+
+#[derive(Args, Clone)]
+pub struct RotateKeys {
+    /// The id of the gateway whose keys you want to rotate
+    #[clap(long)]
+    id: String,
+
+    /// The wallet address the gateway is bonded with, if it's different from the one in the config
+    #[clap(long)]
+    wallet_address: Option<nyxd::AccountId>,
+
+    /// Replaces the current keys with the ones generated before. Only do it once the keys update
+    /// has taken effect at the end of the epoch
+    #[clap(long, conflicts_with = "wallet_address")]
+    swap: bool,
+}
+
+fn generate_keys(
+    pathfinder: &GatewayPathfinder,
+    wallet_address: nyxd::AccountId,
+) -> Result<(), GatewayError> {
+    // perform extra validation to ensure we have correct prefix
+    ensure_correct_bech32_prefix(&wallet_address)?;
+
+    let rotated = pathfinder.rotated();
+    if let Some(existing) = rotated.key_files().into_iter().find(|path| path.exists()) {
+        return Err(GatewayError::RotatedKeysAlreadyExist {
+            path: existing.to_owned(),
+        });
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let identity_keys = identity::KeyPair::new(&mut rng);
+    let sphinx_keys = encryption::KeyPair::new(&mut rng);
+
+    pemstore::store_keypair(
+        &sphinx_keys,
+        &pemstore::KeyPairPath::new(
+            rotated.private_encryption_key().to_owned(),
+            rotated.public_encryption_key().to_owned(),
+        ),
+    )
+    .expect("Failed to save the new sphinx keys");
+    pemstore::store_keypair(
+        &identity_keys,
+        &pemstore::KeyPairPath::new(
+            rotated.private_identity_key().to_owned(),
+            rotated.public_identity_key().to_owned(),
+        ),
+    )
+    .expect("Failed to save the new identity keys");
+
+    let signature = identity_keys
+        .private_key()
+        .sign_text(wallet_address.as_ref());
+
+    eprintln!("Saved the new identity and sphinx keys next to the current ones");
+    eprintln!(
+        "New identity key: {}",
+        identity_keys.public_key().to_base58_string()
+    );
+    eprintln!(
+        "New sphinx key: {}",
+        sphinx_keys.public_key().to_base58_string()
+    );
+    eprintln!("The base58-encoded signature of the new identity key on '{wallet_address}' is: {signature}");
+    eprintln!("Submit them with the `UpdateGatewayKeys` transaction and run `rotate-keys --swap` once the update has taken effect at the end of the current epoch");
+    Ok(())
+}
+
+fn swap_keys(pathfinder: &GatewayPathfinder) -> Result<(), GatewayError> {
+    let rotated = pathfinder.rotated();
+    if let Some(missing) = rotated.key_files().into_iter().find(|path| !path.exists()) {
+        return Err(GatewayError::MissingRotatedKeys {
+            path: missing.to_owned(),
+        });
+    }
+
+    let previous = pathfinder.previous();
+    for ((current, rotated), previous) in pathfinder
+        .key_files()
+        .into_iter()
+        .zip(rotated.key_files())
+        .zip(previous.key_files())
+    {
+        fs::rename(current, previous).expect("Failed to move away the current keys");
+        fs::rename(rotated, current).expect("Failed to move the new keys into place");
+    }
+
+    eprintln!(
+        "Swapped in the new keys, the previous ones have been kept as {}",
+        previous.private_identity_key().display()
+    );
+    eprintln!("Restart the gateway to start using them");
+    Ok(())
+}
+
+pub fn execute(args: RotateKeys) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let config = build_config(args.id.clone(), OverrideConfig::default())?;
+    ensure_config_version_compatibility(&config)?;
+
+    let pathfinder = GatewayPathfinder::new_from_config(&config);
+    if args.swap {
+        swap_keys(&pathfinder)?;
+        return Ok(());
+    }
+
+    let wallet_address = args
+        .wallet_address
+        .or_else(|| config.get_wallet_address())
+        .ok_or(GatewayError::UnknownWalletAddress)?;
+    generate_keys(&pathfinder, wallet_address)?;
+    Ok(())
+}
@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::template::config_template;
-use config::defaults::{DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT};
+use config::defaults::{
+    DEFAULT_CLIENT_LISTENING_PORT, DEFAULT_HTTP_API_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT,
+};
 use config::NymConfig;
+use mixnode_common::packet_processor::replay::{
+    ReplayProtectionConfig, DEFAULT_REPLAY_PROTECTION_MEMORY_BUDGET,
+    DEFAULT_REPLAY_PROTECTION_WINDOW,
+};
 use network_defaults::mainnet::{NYM_API, NYXD_URL, STATISTICS_SERVICE_DOMAIN_ADDRESS};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::net::IpAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -28,6 +34,23 @@ const DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE: usize = 128;
 
 const DEFAULT_STORED_MESSAGE_FILENAME_LENGTH: u16 = 16;
 const DEFAULT_MESSAGE_RETRIEVAL_LIMIT: i64 = 100;
+const DEFAULT_STORED_MESSAGES_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const DEFAULT_STORED_MESSAGES_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_MAX_CLIENT_STORED_MESSAGES: i64 = 10_000;
+const DEFAULT_MAX_CLIENT_STORED_BYTES: i64 = 32 * 1024 * 1024;
+
+fn de_non_zero_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let duration: Duration = humantime_serde::deserialize(deserializer)?;
+    if duration.is_zero() {
+        return Err(serde::de::Error::custom(
+            "the duration must be greater than zero",
+        ));
+    }
+    Ok(duration)
+}
 
 pub fn missing_string_value() -> String {
     MISSING_VALUE.to_string()
@@ -49,6 +72,8 @@ fn default_clients_port() -> u16 {
 pub struct Config {
     gateway: Gateway,
 
+    #[serde(default)]
+    metrics: Metrics,
     #[serde(default)]
     logging: Logging,
     #[serde(default)]
@@ -267,6 +292,14 @@ impl Config {
         self.gateway.persistent_storage.clone()
     }
 
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.metrics.enabled
+    }
+
+    pub fn get_metrics_port(&self) -> u16 {
+        self.metrics.port
+    }
+
     pub fn get_packet_forwarding_initial_backoff(&self) -> Duration {
         self.debug.packet_forwarding_initial_backoff
     }
@@ -287,10 +320,34 @@ impl Config {
         self.debug.use_legacy_framed_packet_version
     }
 
+    pub fn get_replay_protection_config(&self) -> ReplayProtectionConfig {
+        ReplayProtectionConfig {
+            enabled: !self.debug.disable_replay_protection,
+            window: self.debug.replay_protection_window,
+            memory_budget: self.debug.replay_protection_memory_budget,
+        }
+    }
+
     pub fn get_message_retrieval_limit(&self) -> i64 {
         self.debug.message_retrieval_limit
     }
 
+    pub fn get_stored_messages_ttl(&self) -> Duration {
+        self.debug.stored_messages_ttl
+    }
+
+    pub fn get_stored_messages_pruning_interval(&self) -> Duration {
+        self.debug.stored_messages_pruning_interval
+    }
+
+    pub fn get_max_client_stored_messages(&self) -> i64 {
+        self.debug.max_client_stored_messages
+    }
+
+    pub fn get_max_client_stored_bytes(&self) -> i64 {
+        self.debug.max_client_stored_bytes
+    }
+
     pub fn get_version(&self) -> &str {
         &self.gateway.version
     }
@@ -425,6 +482,27 @@ impl Default for Gateway {
     }
 }
 
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct Metrics {
+    /// Specifies whether the gateway should expose its metrics in the Prometheus format
+    /// under the `/metrics` endpoint.
+    enabled: bool,
+
+    /// Port used for serving the metrics endpoint.
+    /// (default: 8000)
+    port: u16,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            enabled: false,
+            port: DEFAULT_HTTP_API_LISTENING_PORT,
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 struct Logging {}
@@ -459,11 +537,43 @@ struct Debug {
     /// Number of messages from offline client that can be pulled at once from the storage.
     message_retrieval_limit: i64,
 
+    /// Duration for which the messages of offline clients are stored before getting removed.
+    #[serde(with = "humantime_serde")]
+    stored_messages_ttl: Duration,
+
+    /// How often the expired messages of offline clients are removed from the storage.
+    #[serde(
+        serialize_with = "humantime_serde::serialize",
+        deserialize_with = "de_non_zero_duration"
+    )]
+    stored_messages_pruning_interval: Duration,
+
+    /// Maximum number of messages stored for a single offline client.
+    /// Once exceeded, the oldest messages of the client are removed.
+    max_client_stored_messages: i64,
+
+    /// Maximum total size, in bytes, of messages stored for a single offline client.
+    /// Once exceeded, the oldest messages of the client are removed.
+    max_client_stored_bytes: i64,
+
     /// Specifies whether the mixnode should be using the legacy framing for the sphinx packets.
     // it's set to true by default. The reason for that decision is to preserve compatibility with the
     // existing nodes whilst everyone else is upgrading and getting the code for handling the new field.
     // It shall be disabled in the subsequent releases.
     use_legacy_framed_packet_version: bool,
+
+    /// Specifies whether the node should stop rejecting sphinx packets it has already processed.
+    disable_replay_protection: bool,
+
+    /// Minimum duration for which the received sphinx packets are remembered for the purposes of
+    /// the replay detection. It should be at least as long as the lifetime of the sphinx key,
+    /// since packets created for the previous keys get rejected anyway.
+    #[serde(with = "humantime_serde")]
+    replay_protection_window: Duration,
+
+    /// Maximum number of bytes that can be used for storing the tags of received sphinx packets.
+    /// If it's exceeded before the end of the window, the oldest tags are discarded early.
+    replay_protection_memory_budget: usize,
 }
 
 impl Default for Debug {
@@ -476,8 +586,15 @@ impl Default for Debug {
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
             stored_messages_filename_length: DEFAULT_STORED_MESSAGE_FILENAME_LENGTH,
             message_retrieval_limit: DEFAULT_MESSAGE_RETRIEVAL_LIMIT,
+            stored_messages_ttl: DEFAULT_STORED_MESSAGES_TTL,
+            stored_messages_pruning_interval: DEFAULT_STORED_MESSAGES_PRUNING_INTERVAL,
+            max_client_stored_messages: DEFAULT_MAX_CLIENT_STORED_MESSAGES,
+            max_client_stored_bytes: DEFAULT_MAX_CLIENT_STORED_BYTES,
             // TODO: remember to change it in one of future releases!!
             use_legacy_framed_packet_version: true,
+            disable_replay_protection: false,
+            replay_protection_window: DEFAULT_REPLAY_PROTECTION_WINDOW,
+            replay_protection_memory_budget: DEFAULT_REPLAY_PROTECTION_MEMORY_BUDGET,
         }
     }
 }
@@ -39,4 +39,39 @@ impl GatewayPathfinder {
     pub fn public_encryption_key(&self) -> &Path {
         &self.public_sphinx_key
     }
+
+    /// Paths of the keys generated by `rotate-keys`, kept next to the current ones until the
+    /// keys update takes effect at the end of the epoch.
+    pub fn rotated(&self) -> Self {
+        self.with_file_name_prefix("rotated_")
+    }
+
+    /// Paths the replaced keys are moved to once the rotated keys are swapped in.
+    pub fn previous(&self) -> Self {
+        self.with_file_name_prefix("previous_")
+    }
+
+    pub fn key_files(&self) -> [&Path; 4] {
+        [
+            self.private_identity_key(),
+            self.public_identity_key(),
+            self.private_encryption_key(),
+            self.public_encryption_key(),
+        ]
+    }
+
+    fn with_file_name_prefix(&self, prefix: &str) -> Self {
+        let prefixed = |path: &Path| {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            path.with_file_name(format!("{prefix}{file_name}"))
+        };
+
+        GatewayPathfinder {
+            config_dir: self.config_dir.clone(),
+            private_sphinx_key: prefixed(&self.private_sphinx_key),
+            public_sphinx_key: prefixed(&self.public_sphinx_key),
+            private_identity_key: prefixed(&self.private_identity_key),
+            public_identity_key: prefixed(&self.public_identity_key),
+        }
+    }
 }
@@ -88,6 +88,18 @@ nym_root_directory = '{{ gateway.nym_root_directory }}'
 # derived shared keys and available client bandwidths.
 persistent_storage = '{{ gateway.persistent_storage }}'
 
+##### metrics configuration options #####
+
+[metrics]
+
+# Specifies whether the gateway should expose its metrics in the Prometheus format
+# under the `/metrics` endpoint.
+enabled = {{ metrics.enabled }}
+
+# Port used for serving the metrics endpoint.
+# (default: 8000)
+port = {{ metrics.port }}
+
 ##### logging configuration options #####
 
 [logging]
@@ -32,6 +32,15 @@ pub(crate) enum GatewayError {
         remote_identity: String,
     },
 
+    #[error("new keys have already been generated ({path}). run with `--swap` once they've taken effect or remove them to start over")]
+    RotatedKeysAlreadyExist { path: PathBuf },
+
+    #[error("there are no new keys to swap in ({path} does not exist). run `rotate-keys` without `--swap` to generate them first")]
+    MissingRotatedKeys { path: PathBuf },
+
+    #[error("the wallet address of the gateway is unknown. provide it with `--wallet-address`")]
+    UnknownWalletAddress,
+
     #[error("could not obtain the information about current gateways on the network: {source}")]
     NetworkGatewaysQueryFailure {
         #[source]
@@ -333,7 +333,7 @@ where
         &self,
         mix_packet: MixPacket,
     ) -> Result<ServerResponse, RequestHandlingError> {
-        let consumed_bandwidth = mix_packet.sphinx_packet().len() as i64;
+        let consumed_bandwidth = mix_packet.packet().len() as i64;
 
         let available_bandwidth = self.get_available_bandwidth().await?;
 
@@ -346,6 +346,12 @@ where
         self.consume_bandwidth(consumed_bandwidth).await?;
         self.forward_packet(mix_packet);
 
+        self.inner.metrics.client_packets_forwarded.inc();
+        self.inner
+            .metrics
+            .bandwidth_consumed
+            .inc_by(consumed_bandwidth as u64);
+
         Ok(ServerResponse::Send {
             remaining_bandwidth: available_bandwidth - consumed_bandwidth,
         })
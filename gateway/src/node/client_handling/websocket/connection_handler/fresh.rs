@@ -7,6 +7,7 @@ use crate::node::client_handling::websocket::connection_handler::coconut::Coconu
 use crate::node::client_handling::websocket::connection_handler::{
     AuthenticatedHandler, ClientDetails, InitialAuthResult, SocketStream,
 };
+use crate::node::metrics::GatewayMetrics;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::Storage;
 use crypto::asymmetric::identity;
@@ -75,6 +76,7 @@ pub(crate) struct FreshHandler<R, S, St> {
     pub(crate) outbound_mix_sender: MixForwardingSender,
     pub(crate) socket_connection: SocketStream<S>,
     pub(crate) storage: St,
+    pub(crate) metrics: GatewayMetrics,
 
     #[cfg(feature = "coconut")]
     pub(crate) coconut_verifier: Arc<CoconutVerifier>,
@@ -98,6 +100,7 @@ where
         local_identity: Arc<identity::KeyPair>,
         storage: St,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
         #[cfg(feature = "coconut")] coconut_verifier: Arc<CoconutVerifier>,
     ) -> Self {
         FreshHandler {
@@ -108,6 +111,7 @@ where
             socket_connection: SocketStream::RawTcp(conn),
             local_identity,
             storage,
+            metrics,
             #[cfg(feature = "coconut")]
             coconut_verifier,
         }
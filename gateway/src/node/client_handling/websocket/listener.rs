@@ -3,6 +3,7 @@
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket::connection_handler::FreshHandler;
+use crate::node::metrics::GatewayMetrics;
 use crate::node::storage::Storage;
 use crypto::asymmetric::identity;
 use log::*;
@@ -20,6 +21,7 @@ pub(crate) struct Listener {
     address: SocketAddr,
     local_identity: Arc<identity::KeyPair>,
     only_coconut_credentials: bool,
+    metrics: GatewayMetrics,
 
     #[cfg(feature = "coconut")]
     pub(crate) coconut_verifier: Arc<CoconutVerifier>,
@@ -30,12 +32,14 @@ impl Listener {
         address: SocketAddr,
         local_identity: Arc<identity::KeyPair>,
         only_coconut_credentials: bool,
+        metrics: GatewayMetrics,
         #[cfg(feature = "coconut")] coconut_verifier: Arc<CoconutVerifier>,
     ) -> Self {
         Listener {
             address,
             local_identity,
             only_coconut_credentials,
+            metrics,
             #[cfg(feature = "coconut")]
             coconut_verifier,
         }
@@ -81,6 +85,7 @@ impl Listener {
                                 Arc::clone(&self.local_identity),
                                 storage.clone(),
                                 active_clients_store.clone(),
+                                self.metrics.clone(),
                                 #[cfg(feature = "coconut")]
                                 Arc::clone(&self.coconut_verifier),
                             );
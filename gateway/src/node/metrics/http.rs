@@ -0,0 +1,79 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::client_handling::active_clients::ActiveClientsStore;
+use crate::node::metrics::GatewayMetrics;
+use crate::node::storage::Storage;
+use log::*;
+use nym_metrics::PROMETHEUS_CONTENT_TYPE;
+use rocket::http::{ContentType, Status};
+use rocket::State;
+use std::net::SocketAddr;
+
+/// Everything required for refreshing the gauges of the gateway at the time of the scrape.
+pub(crate) struct MetricsState {
+    metrics: GatewayMetrics,
+    active_clients_store: ActiveClientsStore,
+    storage: Box<dyn Storage>,
+}
+
+impl MetricsState {
+    pub(crate) fn new(
+        metrics: GatewayMetrics,
+        active_clients_store: ActiveClientsStore,
+        storage: Box<dyn Storage>,
+    ) -> Self {
+        MetricsState {
+            metrics,
+            active_clients_store,
+            storage,
+        }
+    }
+
+    async fn refresh_gauges(&self) {
+        self.metrics
+            .active_clients
+            .set(self.active_clients_store.size() as i64);
+
+        match self.storage.get_inbox_statistics().await {
+            Ok(statistics) => {
+                self.metrics.inbox_messages.set(statistics.messages);
+                self.metrics.inbox_bytes.set(statistics.bytes);
+            }
+            Err(err) => warn!("failed to obtain the inbox statistics: {err}"),
+        }
+    }
+}
+
+/// Provides the metrics of this gateway in the Prometheus text exposition format.
+#[rocket::get("/metrics")]
+pub(crate) async fn metrics(state: &State<MetricsState>) -> Result<(ContentType, String), Status> {
+    state.refresh_gauges().await;
+
+    let encoded = state.metrics.encode().map_err(|err| {
+        error!("failed to encode the metrics: {err}");
+        Status::InternalServerError
+    })?;
+    let content_type =
+        ContentType::parse_flexible(PROMETHEUS_CONTENT_TYPE).unwrap_or(ContentType::Plain);
+    Ok((content_type, encoded))
+}
+
+pub(crate) fn start_metrics_server(address: SocketAddr, state: MetricsState) {
+    info!("Exposing Prometheus metrics on http://{address}/metrics");
+
+    let mut config = rocket::config::Config::release_default();
+    config.address = address.ip();
+    config.port = address.port();
+
+    let rocket = rocket::build()
+        .configure(config)
+        .mount("/", rocket::routes![metrics])
+        .manage(state);
+
+    tokio::spawn(async move {
+        if let Err(err) = rocket.launch().await {
+            error!("the metrics server has failed - {err}")
+        }
+    });
+}
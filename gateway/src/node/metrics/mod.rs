@@ -0,0 +1,95 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_metrics::{IntCounter, IntGauge, MetricsError, Registry};
+
+pub(crate) mod http;
+
+/// Prometheus metrics of the gateway. Cloning it is cheap as all metrics are reference counted.
+#[derive(Clone)]
+pub(crate) struct GatewayMetrics {
+    registry: Registry,
+
+    pub(crate) mix_packets_received: IntCounter,
+    pub(crate) packets_pushed_to_clients: IntCounter,
+    pub(crate) packets_stored: IntCounter,
+    pub(crate) packets_storage_failures: IntCounter,
+    pub(crate) inbox_messages_expired: IntCounter,
+    pub(crate) inbox_messages_evicted: IntCounter,
+    pub(crate) client_packets_forwarded: IntCounter,
+    pub(crate) bandwidth_consumed: IntCounter,
+
+    // the below are only updated when the metrics are being scraped
+    pub(crate) active_clients: IntGauge,
+    pub(crate) inbox_messages: IntGauge,
+    pub(crate) inbox_bytes: IntGauge,
+}
+
+impl GatewayMetrics {
+    pub(crate) fn new() -> Self {
+        let registry = nym_metrics::new_registry("nym_gateway");
+
+        GatewayMetrics {
+            mix_packets_received: nym_metrics::int_counter(
+                &registry,
+                "mix_packets_received_total",
+                "Number of mix packets received from the mixnet",
+            ),
+            packets_pushed_to_clients: nym_metrics::int_counter(
+                &registry,
+                "packets_pushed_to_clients_total",
+                "Number of received packets pushed directly to the connected clients",
+            ),
+            packets_stored: nym_metrics::int_counter(
+                &registry,
+                "packets_stored_total",
+                "Number of received packets stored in the inboxes of the offline clients",
+            ),
+            packets_storage_failures: nym_metrics::int_counter(
+                &registry,
+                "packets_storage_failures_total",
+                "Number of received packets that could not be stored for the offline clients",
+            ),
+            inbox_messages_expired: nym_metrics::int_counter(
+                &registry,
+                "inbox_messages_expired_total",
+                "Number of stored messages removed after exceeding their time-to-live",
+            ),
+            inbox_messages_evicted: nym_metrics::int_counter(
+                &registry,
+                "inbox_messages_evicted_total",
+                "Number of stored messages removed due to their client exceeding its inbox quota",
+            ),
+            client_packets_forwarded: nym_metrics::int_counter(
+                &registry,
+                "client_packets_forwarded_total",
+                "Number of packets received from the clients and forwarded into the mixnet",
+            ),
+            bandwidth_consumed: nym_metrics::int_counter(
+                &registry,
+                "bandwidth_consumed_bytes_total",
+                "Amount of client bandwidth consumed by the forwarded packets",
+            ),
+            active_clients: nym_metrics::int_gauge(
+                &registry,
+                "active_clients",
+                "Number of clients currently connected to the gateway",
+            ),
+            inbox_messages: nym_metrics::int_gauge(
+                &registry,
+                "inbox_messages",
+                "Number of messages currently stored for the offline clients",
+            ),
+            inbox_bytes: nym_metrics::int_gauge(
+                &registry,
+                "inbox_bytes",
+                "Total size of the messages currently stored for the offline clients",
+            ),
+            registry,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Result<String, MetricsError> {
+        nym_metrics::encode(&self.registry)
+    }
+}
@@ -3,6 +3,7 @@
 
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket::message_receiver::MixMessageSender;
+use crate::node::metrics::GatewayMetrics;
 use crate::node::mixnet_handling::receiver::packet_processing::PacketProcessor;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::Storage;
@@ -31,6 +32,7 @@ pub(crate) struct ConnectionHandler<St: Storage> {
     active_clients_store: ActiveClientsStore,
     storage: St,
     ack_sender: MixForwardingSender,
+    metrics: GatewayMetrics,
 }
 
 impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
@@ -49,6 +51,7 @@ impl<St: Storage + Clone> Clone for ConnectionHandler<St> {
             active_clients_store: self.active_clients_store.clone(),
             storage: self.storage.clone(),
             ack_sender: self.ack_sender.clone(),
+            metrics: self.metrics.clone(),
         }
     }
 }
@@ -59,6 +62,7 @@ impl<St: Storage> ConnectionHandler<St> {
         storage: St,
         ack_sender: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
     ) -> Self {
         ConnectionHandler {
             packet_processor,
@@ -66,6 +70,7 @@ impl<St: Storage> ConnectionHandler<St> {
             storage,
             active_clients_store,
             ack_sender,
+            metrics,
         }
     }
 
@@ -116,7 +121,12 @@ impl<St: Storage> ConnectionHandler<St> {
             client_address
         );
 
-        self.storage.store_message(client_address, message).await
+        let evicted = self.storage.store_message(client_address, message).await?;
+        if evicted > 0 {
+            debug!("{client_address} has exceeded its inbox quota - removed {evicted} of its oldest messages");
+            self.metrics.inbox_messages_evicted.inc_by(evicted);
+        }
+        Ok(())
     }
 
     fn forward_ack(&self, forward_ack: Option<MixPacket>, client_address: DestinationAddressBytes) {
@@ -143,10 +153,19 @@ impl<St: Storage> ConnectionHandler<St> {
                 .store_processed_packet_payload(client_address, unsent_plaintext)
                 .await
             {
-                Err(err) => error!("Failed to store client data - {err}"),
-                Ok(_) => trace!("Stored packet for {}", client_address),
+                Err(err) => {
+                    self.metrics.packets_storage_failures.inc();
+                    error!("Failed to store client data - {err}")
+                }
+                Ok(_) => {
+                    self.metrics.packets_stored.inc();
+                    trace!("Stored packet for {}", client_address)
+                }
             },
-            Ok(_) => trace!("Pushed received packet to {}", client_address),
+            Ok(_) => {
+                self.metrics.packets_pushed_to_clients.inc();
+                trace!("Pushed received packet to {}", client_address)
+            }
         }
 
         // if we managed to either push message directly to the [online] client or store it at
@@ -161,6 +180,7 @@ impl<St: Storage> ConnectionHandler<St> {
         // packet processor for vpn packets,
         // question: can it also be per connection vs global?
         //
+        self.metrics.mix_packets_received.inc();
 
         let processed_final_hop = match self.packet_processor.process_received(framed_sphinx_packet)
         {
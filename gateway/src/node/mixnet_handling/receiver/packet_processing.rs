@@ -1,10 +1,10 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crypto::asymmetric::encryption;
 use mixnode_common::packet_processor::error::MixProcessingError;
 pub use mixnode_common::packet_processor::processor::MixProcessingResult;
 use mixnode_common::packet_processor::processor::{ProcessedFinalHop, SphinxPacketProcessor};
+use mixnode_common::sphinx_keys::ActiveSphinxKeys;
 use nymsphinx::framing::packet::FramedSphinxPacket;
 use thiserror::Error;
 
@@ -24,9 +24,9 @@ pub struct PacketProcessor {
 }
 
 impl PacketProcessor {
-    pub(crate) fn new(encryption_key: &encryption::PrivateKey) -> Self {
+    pub(crate) fn new(sphinx_keys: ActiveSphinxKeys) -> Self {
         PacketProcessor {
-            inner_processor: SphinxPacketProcessor::new(encryption_key.into()),
+            inner_processor: SphinxPacketProcessor::new_with_keys(sphinx_keys),
         }
     }
 
@@ -8,14 +8,19 @@ use crate::config::Config;
 use crate::error::GatewayError;
 use crate::node::client_handling::active_clients::ActiveClientsStore;
 use crate::node::client_handling::websocket;
+use crate::node::metrics::http::{start_metrics_server, MetricsState};
+use crate::node::metrics::GatewayMetrics;
 use crate::node::mixnet_handling::receiver::connection_handler::ConnectionHandler;
 use crate::node::statistics::collector::GatewayStatisticsCollector;
-use crate::node::storage::Storage;
+use crate::node::storage::pruner::InboxPruner;
+use crate::node::storage::{InboxQuota, Storage};
 use crate::{commands::sign::load_identity_keys, OutputFormat};
 use colored::Colorize;
 use crypto::asymmetric::{encryption, identity};
 use log::*;
 use mixnet_client::forwarder::{MixForwardingSender, PacketForwarder};
+use mixnode_common::packet_processor::replay::ReplayFilter;
+use mixnode_common::sphinx_keys::ActiveSphinxKeys;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use statistics_common::collector::StatisticsSender;
@@ -35,6 +40,7 @@ use network_defaults::NymNetworkDetails;
 use validator_client::{Client, CoconutApiClient};
 
 pub(crate) mod client_handling;
+pub(crate) mod metrics;
 pub(crate) mod mixnet_handling;
 pub(crate) mod statistics;
 pub(crate) mod storage;
@@ -48,7 +54,11 @@ pub(crate) async fn create_gateway(config: Config) -> Gateway<PersistentStorage>
 async fn initialise_storage(config: &Config) -> PersistentStorage {
     let path = config.get_persistent_store_path();
     let retrieval_limit = config.get_message_retrieval_limit();
-    match PersistentStorage::init(path, retrieval_limit).await {
+    let inbox_quota = InboxQuota {
+        max_messages: config.get_max_client_stored_messages(),
+        max_bytes: config.get_max_client_stored_bytes(),
+    };
+    match PersistentStorage::init(path, retrieval_limit, inbox_quota).await {
         Err(err) => panic!("failed to initialise gateway storage - {err}"),
         Ok(storage) => storage,
     }
@@ -165,18 +175,20 @@ where
         &self,
         ack_sender: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        sphinx_keys: ActiveSphinxKeys,
+        metrics: GatewayMetrics,
         shutdown: TaskClient,
     ) {
         info!("Starting mix socket listener...");
 
-        let packet_processor =
-            mixnet_handling::PacketProcessor::new(self.sphinx_keypair.private_key());
+        let packet_processor = mixnet_handling::PacketProcessor::new(sphinx_keys);
 
         let connection_handler = ConnectionHandler::new(
             packet_processor,
             self.storage.clone(),
             ack_sender,
             active_clients_store,
+            metrics,
         );
 
         let listening_address = SocketAddr::new(
@@ -191,6 +203,7 @@ where
         &self,
         forwarding_channel: MixForwardingSender,
         active_clients_store: ActiveClientsStore,
+        metrics: GatewayMetrics,
         shutdown: TaskClient,
         #[cfg(feature = "coconut")] coconut_verifier: Arc<CoconutVerifier>,
     ) {
@@ -205,6 +218,7 @@ where
             listening_address,
             Arc::clone(&self.identity_keypair),
             self.config.get_only_coconut_credentials(),
+            metrics,
             #[cfg(feature = "coconut")]
             coconut_verifier,
         )
@@ -216,6 +230,35 @@ where
         );
     }
 
+    fn start_metrics_server(
+        &self,
+        metrics: GatewayMetrics,
+        active_clients_store: ActiveClientsStore,
+    ) {
+        let address = SocketAddr::new(
+            self.config.get_listening_address(),
+            self.config.get_metrics_port(),
+        );
+        let state = MetricsState::new(
+            metrics,
+            active_clients_store,
+            Box::new(self.storage.clone()),
+        );
+        start_metrics_server(address, state);
+    }
+
+    fn start_inbox_pruner(&self, metrics: GatewayMetrics, shutdown: TaskClient) {
+        info!("Starting inbox pruner...");
+
+        InboxPruner::new(
+            self.storage.clone(),
+            self.config.get_stored_messages_ttl(),
+            self.config.get_stored_messages_pruning_interval(),
+            metrics,
+        )
+        .start(shutdown);
+    }
+
     fn start_packet_forwarder(&self, shutdown: TaskClient) -> MixForwardingSender {
         info!("Starting mix packet forwarder...");
 
@@ -333,12 +376,27 @@ where
                 .map_err(|source| GatewayError::CoconutVerifierCreationFailure { source })?
         };
 
+        // gateways do not announce any rotated keys, so the clients always use the bonded one
+        let sphinx_keys = ActiveSphinxKeys::new_static(
+            self.sphinx_keypair.private_key().into(),
+            ReplayFilter::new(self.config.get_replay_protection_config()),
+        );
+
         let mix_forwarding_channel = self.start_packet_forwarder(shutdown.subscribe());
 
         let active_clients_store = ActiveClientsStore::new();
+        let metrics = GatewayMetrics::new();
+        if self.config.get_metrics_enabled() {
+            self.start_metrics_server(metrics.clone(), active_clients_store.clone());
+        }
+
+        self.start_inbox_pruner(metrics.clone(), shutdown.subscribe());
+
         self.start_mix_socket_listener(
             mix_forwarding_channel.clone(),
             active_clients_store.clone(),
+            sphinx_keys,
+            metrics.clone(),
             shutdown.subscribe(),
         );
 
@@ -358,6 +416,7 @@ where
         self.start_client_websocket_listener(
             mix_forwarding_channel,
             active_clients_store,
+            metrics,
             shutdown.subscribe(),
             #[cfg(feature = "coconut")]
             Arc::new(coconut_verifier),
@@ -10,4 +10,7 @@ pub(crate) enum StorageError {
 
     #[error("Failed to perform database migration - {0}")]
     MigrationError(#[from] sqlx::migrate::MigrateError),
+
+    #[error("The message of {size} bytes exceeds the per-client inbox quota of {max_bytes} bytes")]
+    MessageTooLarge { size: usize, max_bytes: i64 },
 }
@@ -1,7 +1,24 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::node::storage::models::StoredMessage;
+use crate::node::storage::models::{InboxStatistics, StoredMessage};
+
+/// Limits on the amount of data that can be stored for a single offline client.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InboxQuota {
+    /// Maximum number of messages that can be stored for a single client.
+    pub(crate) max_messages: i64,
+
+    /// Maximum total size, in bytes, of all messages stored for a single client.
+    pub(crate) max_bytes: i64,
+}
+
+impl InboxQuota {
+    /// Checks whether a message of the provided size could ever be stored within the quota.
+    pub(crate) fn fits(&self, message_size: usize) -> bool {
+        message_size as i64 <= self.max_bytes
+    }
+}
 
 #[derive(Clone)]
 pub(crate) struct InboxManager {
@@ -10,6 +27,9 @@ pub(crate) struct InboxManager {
     /// It is used to prevent out of memory errors in the case of client receiving a lot of data while
     /// offline and then loading it all at once when he comes back online.
     retrieval_limit: i64,
+
+    /// Limits on the data stored for each client. Once exceeded, the oldest messages get removed.
+    quota: InboxQuota,
 }
 
 impl InboxManager {
@@ -18,32 +38,78 @@ impl InboxManager {
     /// # Arguments
     ///
     /// * `connection_pool`: database connection pool to use.
-    pub(crate) fn new(connection_pool: sqlx::SqlitePool, retrieval_limit: i64) -> Self {
+    /// * `retrieval_limit`: maximum number of messages that can be obtained at once.
+    /// * `quota`: limits on the data stored for each client.
+    pub(crate) fn new(
+        connection_pool: sqlx::SqlitePool,
+        retrieval_limit: i64,
+        quota: InboxQuota,
+    ) -> Self {
         InboxManager {
             connection_pool,
             retrieval_limit,
+            quota,
         }
     }
 
+    /// Limits on the data stored for each client.
+    pub(crate) fn quota(&self) -> InboxQuota {
+        self.quota
+    }
+
     /// Inserts new message to the storage for an offline client for future retrieval.
+    /// If afterwards the client exceeds its quota, its oldest messages are removed.
+    /// Note that the message is expected to fit within the quota on its own.
     ///
     /// # Arguments
     ///
     /// * `client_address_bs58`: base58-encoded address of the client
     /// * `content`: raw content of the message to store.
+    /// * `timestamp`: unix timestamp of when the message got received.
+    ///
+    /// returns the number of messages removed in order to stay within the quota.
     pub(crate) async fn insert_message(
         &self,
         client_address_bs58: &str,
         content: Vec<u8>,
-    ) -> Result<(), sqlx::Error> {
+        timestamp: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = self.connection_pool.begin().await?;
+
         sqlx::query!(
-            "INSERT INTO message_store(client_address_bs58, content) VALUES (?, ?)",
+            "INSERT INTO message_store(client_address_bs58, content, timestamp) VALUES (?, ?, ?)",
             client_address_bs58,
             content,
+            timestamp,
         )
-        .execute(&self.connection_pool)
+        .execute(&mut tx)
         .await?;
-        Ok(())
+
+        // keep the newest messages whilst both of the limits are satisfied
+        let evicted = sqlx::query!(
+            r#"
+                DELETE FROM message_store
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id,
+                            ROW_NUMBER() OVER (ORDER BY id DESC) AS position,
+                            SUM(LENGTH(content)) OVER (ORDER BY id DESC) AS total_size
+                        FROM message_store
+                        WHERE client_address_bs58 = ?
+                    )
+                    WHERE position > ? OR total_size > ?
+                );
+            "#,
+            client_address_bs58,
+            self.quota.max_messages,
+            self.quota.max_bytes,
+        )
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(evicted)
     }
 
     /// Retrieves messages stored for the particular client specified by the provided address.
@@ -119,4 +185,39 @@ impl InboxManager {
             .await?;
         Ok(())
     }
+
+    /// Removes all messages received before the provided unix timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `received_before`: unix timestamp before which the messages are considered expired.
+    ///
+    /// returns the number of removed messages.
+    pub(crate) async fn remove_expired_messages(
+        &self,
+        received_before: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let removed = sqlx::query!(
+            "DELETE FROM message_store WHERE timestamp < ?",
+            received_before
+        )
+        .execute(&self.connection_pool)
+        .await?
+        .rows_affected();
+        Ok(removed)
+    }
+
+    /// Retrieves the total number and the total size of all messages currently stored
+    /// for all clients.
+    pub(crate) async fn get_statistics(&self) -> Result<InboxStatistics, sqlx::Error> {
+        sqlx::query_as!(
+            InboxStatistics,
+            r#"
+                SELECT COUNT(*) as "messages!: i64", COALESCE(SUM(LENGTH(content)), 0) as "bytes!: i64"
+                FROM message_store;
+            "#
+        )
+        .fetch_one(&self.connection_pool)
+        .await
+    }
 }
@@ -4,7 +4,7 @@
 use crate::node::storage::bandwidth::BandwidthManager;
 use crate::node::storage::error::StorageError;
 use crate::node::storage::inboxes::InboxManager;
-use crate::node::storage::models::{PersistedSharedKeys, StoredMessage};
+use crate::node::storage::models::{InboxStatistics, PersistedSharedKeys, StoredMessage};
 use crate::node::storage::shared_keys::SharedKeysManager;
 use async_trait::async_trait;
 use gateway_requests::registration::handshake::SharedKeys;
@@ -12,13 +12,24 @@ use log::{debug, error};
 use nymsphinx::DestinationAddressBytes;
 use sqlx::ConnectOptions;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 mod bandwidth;
 pub(crate) mod error;
 mod inboxes;
 mod models;
+pub(crate) mod pruner;
 mod shared_keys;
 
+pub(crate) use inboxes::InboxQuota;
+
+pub(crate) fn current_unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("the system clock is set to before the unix epoch")
+        .as_secs() as i64
+}
+
 #[async_trait]
 pub(crate) trait Storage: Send + Sync {
     /// Inserts provided derived shared keys into the database.
@@ -58,16 +69,20 @@ pub(crate) trait Storage: Send + Sync {
     ) -> Result<(), StorageError>;
 
     /// Inserts new message to the storage for an offline client for future retrieval.
+    /// If the client exceeds its quota afterwards, its oldest messages are removed.
+    /// Messages that on their own exceed the quota are rejected.
     ///
     /// # Arguments
     ///
     /// * `client_address`: address of the client
     /// * `message`: raw message to store.
+    ///
+    /// returns the number of messages removed in order to stay within the quota.
     async fn store_message(
         &self,
         client_address: DestinationAddressBytes,
         message: Vec<u8>,
-    ) -> Result<(), StorageError>;
+    ) -> Result<u64, StorageError>;
 
     /// Retrieves messages stored for the particular client specified by the provided address.
     ///
@@ -91,6 +106,18 @@ pub(crate) trait Storage: Send + Sync {
     /// * `ids`: ids of the messages to remove
     async fn remove_messages(&self, ids: Vec<i64>) -> Result<(), StorageError>;
 
+    /// Removes messages of all clients that were received before the specified time.
+    ///
+    /// # Arguments
+    ///
+    /// * `received_before`: unix timestamp before which the messages are considered expired.
+    ///
+    /// returns the number of removed messages.
+    async fn remove_expired_messages(&self, received_before: i64) -> Result<u64, StorageError>;
+
+    /// Retrieves the total number and size of messages stored for all offline clients.
+    async fn get_inbox_statistics(&self) -> Result<InboxStatistics, StorageError>;
+
     /// Creates a new bandwidth entry for the particular client.
     ///
     /// # Arguments
@@ -151,9 +178,11 @@ impl PersistentStorage {
     ///
     /// * `database_path`: path to the database.
     /// * `message_retrieval_limit`: maximum number of stored client messages that can be retrieved at once.
+    /// * `inbox_quota`: limits on the data stored for each offline client.
     pub async fn init<P: AsRef<Path> + Send>(
         database_path: P,
         message_retrieval_limit: i64,
+        inbox_quota: InboxQuota,
     ) -> Result<Self, StorageError> {
         debug!(
             "Attempting to connect to database {:?}",
@@ -186,7 +215,11 @@ impl PersistentStorage {
         // the cloning here are cheap as connection pool is stored behind an Arc
         Ok(PersistentStorage {
             shared_key_manager: SharedKeysManager::new(connection_pool.clone()),
-            inbox_manager: InboxManager::new(connection_pool.clone(), message_retrieval_limit),
+            inbox_manager: InboxManager::new(
+                connection_pool.clone(),
+                message_retrieval_limit,
+                inbox_quota,
+            ),
             bandwidth_manager: BandwidthManager::new(connection_pool),
         })
     }
@@ -235,11 +268,25 @@ impl Storage for PersistentStorage {
         &self,
         client_address: DestinationAddressBytes,
         message: Vec<u8>,
-    ) -> Result<(), StorageError> {
-        self.inbox_manager
-            .insert_message(&client_address.as_base58_string(), message)
+    ) -> Result<u64, StorageError> {
+        // otherwise it would evict all the other messages of the client, and then itself
+        let quota = self.inbox_manager.quota();
+        if !quota.fits(message.len()) {
+            return Err(StorageError::MessageTooLarge {
+                size: message.len(),
+                max_bytes: quota.max_bytes,
+            });
+        }
+
+        let evicted = self
+            .inbox_manager
+            .insert_message(
+                &client_address.as_base58_string(),
+                message,
+                current_unix_timestamp(),
+            )
             .await?;
-        Ok(())
+        Ok(evicted)
     }
 
     async fn retrieve_messages(
@@ -261,6 +308,19 @@ impl Storage for PersistentStorage {
         Ok(())
     }
 
+    async fn remove_expired_messages(&self, received_before: i64) -> Result<u64, StorageError> {
+        let removed = self
+            .inbox_manager
+            .remove_expired_messages(received_before)
+            .await?;
+        Ok(removed)
+    }
+
+    async fn get_inbox_statistics(&self) -> Result<InboxStatistics, StorageError> {
+        let statistics = self.inbox_manager.get_statistics().await?;
+        Ok(statistics)
+    }
+
     async fn create_bandwidth_entry(
         &self,
         client_address: DestinationAddressBytes,
@@ -348,7 +408,7 @@ impl Storage for InMemStorage {
         &self,
         _client_address: DestinationAddressBytes,
         _message: Vec<u8>,
-    ) -> Result<(), StorageError> {
+    ) -> Result<u64, StorageError> {
         todo!()
     }
 
@@ -364,6 +424,14 @@ impl Storage for InMemStorage {
         todo!()
     }
 
+    async fn remove_expired_messages(&self, _received_before: i64) -> Result<u64, StorageError> {
+        todo!()
+    }
+
+    async fn get_inbox_statistics(&self) -> Result<InboxStatistics, StorageError> {
+        todo!()
+    }
+
     async fn create_bandwidth_entry(
         &self,
         _client_address: DestinationAddressBytes,
@@ -394,3 +462,112 @@ impl Storage for InMemStorage {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_storage(database_path: &Path, inbox_quota: InboxQuota) -> PersistentStorage {
+        PersistentStorage::init(database_path, 100, inbox_quota)
+            .await
+            .unwrap()
+    }
+
+    async fn stored_contents(
+        storage: &PersistentStorage,
+        client_address: DestinationAddressBytes,
+    ) -> Vec<Vec<u8>> {
+        let (messages, _) = storage
+            .retrieve_messages(client_address, None)
+            .await
+            .unwrap();
+        messages
+            .into_iter()
+            .map(|message| message.content)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn oldest_messages_are_evicted_once_the_quota_is_exceeded() {
+        let dir = tempfile::tempdir().unwrap();
+        let quota = InboxQuota {
+            max_messages: 3,
+            max_bytes: 1000,
+        };
+        let storage = test_storage(&dir.path().join("db.sqlite"), quota).await;
+
+        let client = DestinationAddressBytes::from_bytes([1u8; 32]);
+        let mut evicted = 0;
+        for i in 0..5u8 {
+            evicted += storage.store_message(client, vec![i; 10]).await.unwrap();
+        }
+        assert_eq!(evicted, 2);
+        assert_eq!(
+            stored_contents(&storage, client).await,
+            vec![vec![2u8; 10], vec![3u8; 10], vec![4u8; 10]]
+        );
+
+        // the quota is applied per client and also limits the total size
+        let other_client = DestinationAddressBytes::from_bytes([2u8; 32]);
+        assert_eq!(
+            storage
+                .store_message(other_client, vec![1u8; 600])
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            storage
+                .store_message(other_client, vec![2u8; 600])
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            stored_contents(&storage, other_client).await,
+            vec![vec![2u8; 600]]
+        );
+        assert_eq!(stored_contents(&storage, client).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn messages_exceeding_the_quota_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let quota = InboxQuota {
+            max_messages: 100,
+            max_bytes: 100,
+        };
+        let storage = test_storage(&dir.path().join("db.sqlite"), quota).await;
+
+        let client = DestinationAddressBytes::from_bytes([1u8; 32]);
+        storage.store_message(client, vec![1u8; 60]).await.unwrap();
+
+        assert!(matches!(
+            storage.store_message(client, vec![2u8; 101]).await,
+            Err(StorageError::MessageTooLarge { size: 101, .. })
+        ));
+        // and the existing messages are not affected
+        assert_eq!(stored_contents(&storage, client).await, vec![vec![1u8; 60]]);
+    }
+
+    #[tokio::test]
+    async fn only_expired_messages_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let quota = InboxQuota {
+            max_messages: 100,
+            max_bytes: 100_000,
+        };
+        let storage = test_storage(&dir.path().join("db.sqlite"), quota).await;
+
+        let client = DestinationAddressBytes::from_bytes([1u8; 32]);
+        storage.store_message(client, vec![1u8; 10]).await.unwrap();
+        storage.store_message(client, vec![2u8; 10]).await.unwrap();
+
+        let now = current_unix_timestamp();
+        assert_eq!(storage.remove_expired_messages(now - 60).await.unwrap(), 0);
+        assert_eq!(stored_contents(&storage, client).await.len(), 2);
+
+        assert_eq!(storage.remove_expired_messages(now + 1).await.unwrap(), 2);
+        assert!(stored_contents(&storage, client).await.is_empty());
+    }
+}
@@ -11,6 +11,8 @@ pub(crate) struct StoredMessage {
     #[allow(dead_code)]
     pub(crate) client_address_bs58: String,
     pub(crate) content: Vec<u8>,
+    #[allow(dead_code)]
+    pub(crate) timestamp: i64,
 }
 
 pub(crate) struct PersistedBandwidth {
@@ -18,3 +20,8 @@ pub(crate) struct PersistedBandwidth {
     pub(crate) client_address_bs58: String,
     pub(crate) available: i64,
 }
+
+pub(crate) struct InboxStatistics {
+    pub(crate) messages: i64,
+    pub(crate) bytes: i64,
+}
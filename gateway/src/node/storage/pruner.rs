@@ -0,0 +1,68 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::metrics::GatewayMetrics;
+use crate::node::storage::{current_unix_timestamp, Storage};
+use log::*;
+use std::time::Duration;
+use task::TaskClient;
+
+/// Periodically removes the messages that have been stored for the offline clients for longer
+/// than the configured time-to-live.
+pub(crate) struct InboxPruner<St> {
+    storage: St,
+    message_ttl: Duration,
+    pruning_interval: Duration,
+    metrics: GatewayMetrics,
+}
+
+impl<St> InboxPruner<St>
+where
+    St: Storage + 'static,
+{
+    pub(crate) fn new(
+        storage: St,
+        message_ttl: Duration,
+        pruning_interval: Duration,
+        metrics: GatewayMetrics,
+    ) -> Self {
+        InboxPruner {
+            storage,
+            message_ttl,
+            pruning_interval,
+            metrics,
+        }
+    }
+
+    async fn prune(&self) {
+        let received_before = current_unix_timestamp() - self.message_ttl.as_secs() as i64;
+        match self.storage.remove_expired_messages(received_before).await {
+            Ok(0) => trace!("there were no expired messages to remove"),
+            Ok(removed) => {
+                info!("removed {removed} expired messages of offline clients");
+                self.metrics.inbox_messages_expired.inc_by(removed);
+            }
+            Err(err) => error!("failed to remove expired messages - {err}"),
+        }
+    }
+
+    pub(crate) async fn run(&self, mut shutdown: TaskClient) {
+        debug!("Started InboxPruner with graceful shutdown support");
+
+        let mut pruning_interval = tokio::time::interval(self.pruning_interval);
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                _ = pruning_interval.tick() => self.prune().await,
+                _ = shutdown.recv() => {
+                    trace!("InboxPruner: Received shutdown");
+                }
+            }
+        }
+
+        trace!("InboxPruner: Exiting");
+    }
+
+    pub(crate) fn start(self, shutdown: TaskClient) {
+        tokio::spawn(async move { self.run(shutdown).await });
+    }
+}
@@ -16,6 +16,7 @@ use validator_client::nyxd;
 mod describe;
 mod init;
 mod node_details;
+mod rotate_keys;
 mod run;
 mod sign;
 mod upgrade;
@@ -34,6 +35,9 @@ pub(crate) enum Commands {
     /// Sign text to prove ownership of this mixnode
     Sign(sign::Sign),
 
+    /// Generate new identity and sphinx keys to replace the bonded ones
+    RotateKeys(rotate_keys::RotateKeys),
+
     /// Try to upgrade the mixnode
     Upgrade(upgrade::Upgrade),
 
@@ -69,6 +73,7 @@ pub(crate) async fn execute(args: Cli) {
         Commands::Init(m) => init::execute(&m, output),
         Commands::Run(m) => run::execute(&m, output).await,
         Commands::Sign(m) => sign::execute(&m),
+        Commands::RotateKeys(m) => rotate_keys::execute(&m),
         Commands::Upgrade(m) => upgrade::execute(&m),
         Commands::NodeDetails(m) => node_details::execute(&m, output),
         Commands::Completions(s) => s.generate(&mut crate::Cli::command(), bin_name),
@@ -0,0 +1,141 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::commands::{validate_bech32_address_or_exit, version_check};
+use crate::config::{persistence::pathfinder::MixNodePathfinder, Config};
+use clap::Args;
+use config::NymConfig;
+use crypto::asymmetric::{encryption, identity};
+use log::error;
+use std::fs;
+use validator_client::nyxd;
+
+#[derive(Args, Clone)]
+pub(crate) struct RotateKeys {
+    /// The id of the mixnode whose keys you want to rotate
+    #[clap(long)]
+    id: String,
+
+    /// The wallet address the mixnode is bonded with, if it's different from the one in the config
+    #[clap(long)]
+    wallet_address: Option<nyxd::AccountId>,
+
+    /// Replaces the current keys with the ones generated before. Only do it once the keys update
+    /// has taken effect at the end of the epoch
+    #[clap(long, conflicts_with = "wallet_address")]
+    swap: bool,
+}
+
+fn generate_keys(pathfinder: &MixNodePathfinder, wallet_address: nyxd::AccountId) {
+    // perform extra validation to ensure we have correct prefix
+    validate_bech32_address_or_exit(wallet_address.as_ref());
+
+    let rotated = pathfinder.rotated();
+    if let Some(existing) = rotated.key_files().into_iter().find(|path| path.exists()) {
+        error!(
+            "New keys have already been generated ({}). Run with `--swap` once they've taken effect or remove them to start over",
+            existing.display()
+        );
+        return;
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let identity_keys = identity::KeyPair::new(&mut rng);
+    let sphinx_keys = encryption::KeyPair::new(&mut rng);
+
+    pemstore::store_keypair(
+        &identity_keys,
+        &pemstore::KeyPairPath::new(
+            rotated.private_identity_key().to_owned(),
+            rotated.public_identity_key().to_owned(),
+        ),
+    )
+    .expect("Failed to save the new identity keys");
+    pemstore::store_keypair(
+        &sphinx_keys,
+        &pemstore::KeyPairPath::new(
+            rotated.private_encryption_key().to_owned(),
+            rotated.public_encryption_key().to_owned(),
+        ),
+    )
+    .expect("Failed to save the new sphinx keys");
+
+    let signature = identity_keys
+        .private_key()
+        .sign_text(wallet_address.as_ref());
+
+    println!("Saved the new identity and sphinx keys next to the current ones");
+    println!(
+        "New identity key: {}",
+        identity_keys.public_key().to_base58_string()
+    );
+    println!(
+        "New sphinx key: {}",
+        sphinx_keys.public_key().to_base58_string()
+    );
+    println!("The base58-encoded signature of the new identity key on '{wallet_address}' is: {signature}");
+    println!("Submit them with the `UpdateMixnodeKeys` transaction and run `rotate-keys --swap` once the update has taken effect at the end of the current epoch");
+}
+
+fn swap_keys(pathfinder: &MixNodePathfinder) {
+    let rotated = pathfinder.rotated();
+    if let Some(missing) = rotated.key_files().into_iter().find(|path| !path.exists()) {
+        error!(
+            "There are no new keys to swap in ({} does not exist). Run `rotate-keys` without `--swap` to generate them first",
+            missing.display()
+        );
+        return;
+    }
+
+    let previous = pathfinder.previous();
+    for ((current, rotated), previous) in pathfinder
+        .key_files()
+        .into_iter()
+        .zip(rotated.key_files())
+        .zip(previous.key_files())
+    {
+        fs::rename(current, previous).expect("Failed to move away the current keys");
+        fs::rename(rotated, current).expect("Failed to move the new keys into place");
+    }
+
+    println!(
+        "Swapped in the new keys, the previous ones have been kept as {}",
+        previous.private_identity_key().display()
+    );
+    println!("Restart the mixnode to start using them");
+}
+
+pub(crate) fn execute(args: &RotateKeys) {
+    let config = match Config::load_from_file(Some(&args.id)) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            error!(
+                "Failed to load config for {}. Are you sure you have run `init` before? (Error was: {})",
+                args.id,
+                err,
+            );
+            return;
+        }
+    };
+
+    if !version_check(&config) {
+        error!("Failed the local version check");
+        return;
+    }
+
+    let pathfinder = MixNodePathfinder::new_from_config(&config);
+    if args.swap {
+        swap_keys(&pathfinder);
+        return;
+    }
+
+    let Some(wallet_address) = args
+        .wallet_address
+        .clone()
+        .or_else(|| config.get_wallet_address())
+    else {
+        error!("The wallet address of the mixnode is unknown. Provide it with `--wallet-address`");
+        return;
+    };
+    generate_keys(&pathfinder, wallet_address);
+}
@@ -7,6 +7,14 @@ use config::defaults::{
     DEFAULT_HTTP_API_LISTENING_PORT, DEFAULT_MIX_LISTENING_PORT, DEFAULT_VERLOC_LISTENING_PORT,
 };
 use config::NymConfig;
+use mixnode_common::packet_processor::replay::{
+    ReplayProtectionConfig, DEFAULT_REPLAY_PROTECTION_MEMORY_BUDGET,
+    DEFAULT_REPLAY_PROTECTION_WINDOW,
+};
+use mixnode_common::sphinx_keys::{
+    SphinxKeyRotationConfig, DEFAULT_ANNOUNCED_SPHINX_KEY_EPOCHS, DEFAULT_SPHINX_KEY_GRACE_PERIOD,
+    DEFAULT_SPHINX_KEY_ROTATION_INTERVAL,
+};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
@@ -81,6 +89,8 @@ pub struct Config {
     #[serde(default)]
     verloc: Verloc,
     #[serde(default)]
+    metrics: Metrics,
+    #[serde(default)]
     logging: Logging,
     #[serde(default)]
     debug: Debug,
@@ -278,10 +288,36 @@ impl Config {
         self.debug.use_legacy_framed_packet_version
     }
 
+    pub fn get_replay_protection_config(&self) -> ReplayProtectionConfig {
+        ReplayProtectionConfig {
+            enabled: !self.debug.disable_replay_protection,
+            window: self.debug.replay_protection_window,
+            memory_budget: self.debug.replay_protection_memory_budget,
+        }
+    }
+
+    pub fn get_sphinx_key_rotation_config(&self) -> SphinxKeyRotationConfig {
+        SphinxKeyRotationConfig {
+            enabled: self.debug.enable_sphinx_key_rotation,
+            rotation_interval: self.debug.sphinx_key_rotation_interval,
+            grace_period: self.debug.sphinx_key_grace_period,
+            announced_epochs: self.debug.announced_sphinx_key_epochs,
+            accept_bonded_key: self.debug.accept_bonded_sphinx_key,
+        }
+    }
+
+    pub fn get_sphinx_keys_directory(&self) -> PathBuf {
+        self.data_directory().join("sphinx_keys")
+    }
+
     pub fn get_version(&self) -> &str {
         &self.mixnode.version
     }
 
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.metrics.enabled
+    }
+
     pub fn get_measurement_packets_per_node(&self) -> usize {
         self.verloc.packets_per_node
     }
@@ -417,6 +453,14 @@ impl Default for MixNode {
 #[serde(deny_unknown_fields)]
 struct Logging {}
 
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct Metrics {
+    /// Specifies whether the node should expose its metrics in the Prometheus format
+    /// under the `/metrics` endpoint of its http API.
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 struct Verloc {
@@ -490,6 +534,37 @@ struct Debug {
     // existing nodes whilst everyone else is upgrading and getting the code for handling the new field.
     // It shall be disabled in the subsequent releases.
     use_legacy_framed_packet_version: bool,
+
+    /// Specifies whether the node should stop rejecting sphinx packets it has already processed.
+    disable_replay_protection: bool,
+
+    /// Minimum duration for which the received sphinx packets are remembered for the purposes of
+    /// the replay detection. It should be at least as long as the lifetime of the sphinx key,
+    /// since packets created for the previous keys get rejected anyway.
+    #[serde(with = "humantime_serde")]
+    replay_protection_window: Duration,
+
+    /// Maximum number of bytes that can be used for storing the tags of received sphinx packets.
+    /// If it's exceeded before the end of the window, the oldest tags are discarded early.
+    replay_protection_memory_budget: usize,
+
+    /// Specifies whether the node should periodically generate and announce new sphinx keys.
+    enable_sphinx_key_rotation: bool,
+
+    /// Duration of a single sphinx key epoch, i.e. how often the keys are rotated.
+    #[serde(with = "humantime_serde")]
+    sphinx_key_rotation_interval: Duration,
+
+    /// For how long after the rotation the packets created with the previous sphinx key are still accepted.
+    #[serde(with = "humantime_serde")]
+    sphinx_key_grace_period: Duration,
+
+    /// Number of future epochs for which the sphinx keys are announced in advance.
+    announced_sphinx_key_epochs: u32,
+
+    /// Specifies whether the packets created with the sphinx key the node has bonded with are still
+    /// accepted once the key rotation is enabled.
+    accept_bonded_sphinx_key: bool,
 }
 
 impl Default for Debug {
@@ -503,6 +578,14 @@ impl Default for Debug {
             maximum_connection_buffer_size: DEFAULT_MAXIMUM_CONNECTION_BUFFER_SIZE,
             // TODO: remember to change it in one of future releases!!
             use_legacy_framed_packet_version: true,
+            disable_replay_protection: false,
+            replay_protection_window: DEFAULT_REPLAY_PROTECTION_WINDOW,
+            replay_protection_memory_budget: DEFAULT_REPLAY_PROTECTION_MEMORY_BUDGET,
+            enable_sphinx_key_rotation: false,
+            sphinx_key_rotation_interval: DEFAULT_SPHINX_KEY_ROTATION_INTERVAL,
+            sphinx_key_grace_period: DEFAULT_SPHINX_KEY_GRACE_PERIOD,
+            announced_sphinx_key_epochs: DEFAULT_ANNOUNCED_SPHINX_KEY_EPOCHS,
+            accept_bonded_sphinx_key: true,
         }
     }
 }
@@ -69,6 +69,14 @@ wallet_address = '{{ mixnode.wallet_address }}'
 nym_root_directory = '{{ mixnode.nym_root_directory }}'
 
 
+##### metrics configuration options #####
+
+[metrics]
+
+# Specifies whether the node should expose its metrics in the Prometheus format
+# under the `/metrics` endpoint of its http API.
+enabled = {{ metrics.enabled }}
+
 ##### logging configuration options #####
 
 [logging]
@@ -0,0 +1,22 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::metrics::MixnodeMetrics;
+use log::error;
+use nym_metrics::PROMETHEUS_CONTENT_TYPE;
+use rocket::http::{ContentType, Status};
+use rocket::State;
+
+/// Provides the metrics of this mixnode in the Prometheus text exposition format.
+#[get("/metrics")]
+pub(crate) async fn metrics(
+    metrics: &State<MixnodeMetrics>,
+) -> Result<(ContentType, String), Status> {
+    let encoded = metrics.encode().map_err(|err| {
+        error!("failed to encode the metrics: {err}");
+        Status::InternalServerError
+    })?;
+    let content_type =
+        ContentType::parse_flexible(PROMETHEUS_CONTENT_TYPE).unwrap_or(ContentType::Plain);
+    Ok((content_type, encoded))
+}
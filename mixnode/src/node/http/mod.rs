@@ -1,5 +1,7 @@
 pub(crate) mod description;
 pub(crate) mod hardware;
+pub(crate) mod metrics;
+pub(crate) mod sphinx_keys;
 pub(crate) mod stats;
 pub(crate) mod verloc;
 
@@ -0,0 +1,14 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use mixnode_common::sphinx_keys::manager::AnnouncedSphinxKeys;
+use mixnode_common::sphinx_keys::SignedSphinxKey;
+use rocket::serde::json::Json;
+use rocket::State;
+
+/// Provides the sphinx keys of this mixnode for the current and the upcoming key epochs,
+/// each signed with the node's identity key. It's empty if the key rotation is disabled.
+#[get("/sphinx-keys")]
+pub(crate) async fn sphinx_keys(state: &State<AnnouncedSphinxKeys>) -> Json<Vec<SignedSphinxKey>> {
+    Json(state.get())
+}
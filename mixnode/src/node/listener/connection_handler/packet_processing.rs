@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::node::node_statistics;
-use crypto::asymmetric::encryption;
 use mixnode_common::packet_processor::error::MixProcessingError;
 pub use mixnode_common::packet_processor::processor::MixProcessingResult;
 use mixnode_common::packet_processor::processor::SphinxPacketProcessor;
+use mixnode_common::sphinx_keys::ActiveSphinxKeys;
 use nymsphinx::framing::packet::FramedSphinxPacket;
 
 // PacketProcessor contains all data required to correctly unwrap and forward sphinx packets
@@ -20,11 +20,11 @@ pub struct PacketProcessor {
 
 impl PacketProcessor {
     pub(crate) fn new(
-        encryption_key: &encryption::PrivateKey,
+        sphinx_keys: ActiveSphinxKeys,
         node_stats_update_sender: node_statistics::UpdateSender,
     ) -> Self {
         PacketProcessor {
-            inner_processor: SphinxPacketProcessor::new(encryption_key.into()),
+            inner_processor: SphinxPacketProcessor::new_with_keys(sphinx_keys),
             node_stats_update_sender,
         }
     }
@@ -34,6 +34,10 @@ impl PacketProcessor {
         received: FramedSphinxPacket,
     ) -> Result<MixProcessingResult, MixProcessingError> {
         self.node_stats_update_sender.report_received();
-        self.inner_processor.process_received(received)
+        let res = self.inner_processor.process_received(received);
+        if let Err(MixProcessingError::ReplayedPacket) = res {
+            self.node_stats_update_sender.report_replayed();
+        }
+        res
     }
 }
@@ -0,0 +1,57 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_metrics::{IntCounter, IntGauge, MetricsError, Registry};
+
+/// Prometheus metrics of the mixnode. Cloning it is cheap as all metrics are reference counted.
+#[derive(Clone)]
+pub(crate) struct MixnodeMetrics {
+    registry: Registry,
+
+    pub(crate) packets_received: IntCounter,
+    pub(crate) packets_forwarded: IntCounter,
+    pub(crate) packets_dropped: IntCounter,
+    pub(crate) packets_replayed: IntCounter,
+
+    /// Number of packets currently waiting in the queue of the `DelayForwarder`.
+    pub(crate) delay_queue_size: IntGauge,
+}
+
+impl MixnodeMetrics {
+    pub(crate) fn new() -> Self {
+        let registry = nym_metrics::new_registry("nym_mixnode");
+
+        MixnodeMetrics {
+            packets_received: nym_metrics::int_counter(
+                &registry,
+                "packets_received_total",
+                "Number of mix packets received by the node",
+            ),
+            packets_forwarded: nym_metrics::int_counter(
+                &registry,
+                "packets_forwarded_total",
+                "Number of mix packets sent (but not necessarily delivered) to the next hop",
+            ),
+            packets_dropped: nym_metrics::int_counter(
+                &registry,
+                "packets_dropped_total",
+                "Number of mix packets explicitly dropped due to full connection buffers",
+            ),
+            packets_replayed: nym_metrics::int_counter(
+                &registry,
+                "packets_replayed_total",
+                "Number of mix packets rejected as replays",
+            ),
+            delay_queue_size: nym_metrics::int_gauge(
+                &registry,
+                "delay_queue_size",
+                "Number of mix packets currently being delayed before getting forwarded",
+            ),
+            registry,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Result<String, MetricsError> {
+        nym_metrics::encode(&self.registry)
+    }
+}
@@ -7,13 +7,16 @@ use crate::config::Config;
 use crate::node::http::{
     description::description,
     hardware::hardware,
+    metrics::metrics as metricsRoute,
     not_found,
+    sphinx_keys::sphinx_keys,
     stats::stats,
     verloc::{verloc as verlocRoute, VerlocState},
 };
 use crate::node::listener::connection_handler::packet_processing::PacketProcessor;
 use crate::node::listener::connection_handler::ConnectionHandler;
 use crate::node::listener::Listener;
+use crate::node::metrics::MixnodeMetrics;
 use crate::node::node_description::NodeDescription;
 use crate::node::node_statistics::SharedNodeStats;
 use crate::node::packet_delayforwarder::{DelayForwarder, PacketDelayForwardSender};
@@ -22,6 +25,9 @@ use ::crypto::asymmetric::{encryption, identity};
 use colored::Colorize;
 use config::NymConfig;
 use log::{error, info, warn};
+use mixnode_common::packet_processor::replay::ReplayFilter;
+use mixnode_common::sphinx_keys::manager::{AnnouncedSphinxKeys, SphinxKeyManager};
+use mixnode_common::sphinx_keys::ActiveSphinxKeys;
 use mixnode_common::verloc::{self, AtomicVerlocResult, VerlocMeasurer};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -33,6 +39,7 @@ use version_checker::parse_version;
 
 mod http;
 mod listener;
+mod metrics;
 pub(crate) mod node_description;
 mod node_statistics;
 mod packet_delayforwarder;
@@ -130,6 +137,8 @@ impl MixNode {
         &self,
         atomic_verloc_result: AtomicVerlocResult,
         node_stats_pointer: SharedNodeStats,
+        announced_sphinx_keys: AnnouncedSphinxKeys,
+        metrics: MixnodeMetrics,
     ) {
         info!("Starting HTTP API on http://localhost:8000");
 
@@ -142,27 +151,36 @@ impl MixNode {
         let verloc_state = VerlocState::new(atomic_verloc_result);
         let descriptor = self.descriptor.clone();
 
-        tokio::spawn(async move {
-            rocket::build()
-                .configure(config)
-                .mount("/", routes![verlocRoute, description, stats, hardware])
-                .register("/", catchers![not_found])
-                .manage(verloc_state)
-                .manage(descriptor)
-                .manage(node_stats_pointer)
-                .launch()
-                .await
-        });
+        let mut rocket = rocket::build()
+            .configure(config)
+            .mount(
+                "/",
+                routes![verlocRoute, description, stats, hardware, sphinx_keys],
+            )
+            .register("/", catchers![not_found])
+            .manage(verloc_state)
+            .manage(descriptor)
+            .manage(node_stats_pointer)
+            .manage(announced_sphinx_keys);
+
+        if self.config.get_metrics_enabled() {
+            info!("Exposing Prometheus metrics under /metrics");
+            rocket = rocket.mount("/", routes![metricsRoute]).manage(metrics);
+        }
+
+        tokio::spawn(async move { rocket.launch().await });
     }
 
     fn start_node_stats_controller(
         &self,
+        metrics: MixnodeMetrics,
         shutdown: TaskClient,
     ) -> (SharedNodeStats, node_statistics::UpdateSender) {
         info!("Starting node stats controller...");
         let controller = node_statistics::Controller::new(
             self.config.get_node_stats_logging_delay(),
             self.config.get_node_stats_updating_delay(),
+            metrics,
             shutdown,
         );
         let node_stats_pointer = controller.get_node_stats_data_pointer();
@@ -171,16 +189,60 @@ impl MixNode {
         (node_stats_pointer, update_sender)
     }
 
+    fn start_sphinx_key_manager(
+        &self,
+        shutdown: TaskClient,
+    ) -> (ActiveSphinxKeys, AnnouncedSphinxKeys) {
+        let rotation_config = self.config.get_sphinx_key_rotation_config();
+        let replay_protection = self.config.get_replay_protection_config();
+        if !rotation_config.enabled {
+            let active_keys = ActiveSphinxKeys::new_static(
+                self.sphinx_keypair.private_key().into(),
+                ReplayFilter::new(replay_protection),
+            );
+            return (active_keys, AnnouncedSphinxKeys::default());
+        }
+
+        if let Err(err) = rotation_config.validate() {
+            error!("{err}");
+            process::exit(1)
+        }
+
+        info!("Starting sphinx key manager...");
+        let active_keys = ActiveSphinxKeys::new(replay_protection);
+        if rotation_config.accept_bonded_key {
+            active_keys.insert(None, self.sphinx_keypair.private_key().into());
+        }
+
+        let keys_directory = self.config.get_sphinx_keys_directory();
+        let announced_keys = SphinxKeyManager::new(
+            rotation_config,
+            Arc::clone(&self.identity_keypair),
+            &keys_directory,
+            active_keys.clone(),
+        )
+        .start(shutdown)
+        .unwrap_or_else(|err| {
+            error!(
+                "failed to set up the rotating sphinx keys in {}: {err}",
+                keys_directory.display()
+            );
+            process::exit(1)
+        });
+
+        (active_keys, announced_keys)
+    }
+
     fn start_socket_listener(
         &self,
+        sphinx_keys: ActiveSphinxKeys,
         node_stats_update_sender: node_statistics::UpdateSender,
         delay_forwarding_channel: PacketDelayForwardSender,
         shutdown: TaskClient,
     ) {
         info!("Starting socket listener...");
 
-        let packet_processor =
-            PacketProcessor::new(self.sphinx_keypair.private_key(), node_stats_update_sender);
+        let packet_processor = PacketProcessor::new(sphinx_keys, node_stats_update_sender);
 
         let connection_handler = ConnectionHandler::new(packet_processor, delay_forwarding_channel);
 
@@ -195,6 +257,7 @@ impl MixNode {
     fn start_packet_delay_forwarder(
         &mut self,
         node_stats_update_sender: node_statistics::UpdateSender,
+        metrics: MixnodeMetrics,
         shutdown: TaskClient,
     ) -> PacketDelayForwardSender {
         info!("Starting packet delay-forwarder...");
@@ -210,6 +273,7 @@ impl MixNode {
         let mut packet_forwarder = DelayForwarder::new(
             mixnet_client::Client::new(client_config),
             node_stats_update_sender,
+            metrics,
             shutdown,
         );
 
@@ -314,11 +378,18 @@ impl MixNode {
 
         let shutdown = TaskManager::default();
 
+        let metrics = MixnodeMetrics::new();
         let (node_stats_pointer, node_stats_update_sender) =
-            self.start_node_stats_controller(shutdown.subscribe());
-        let delay_forwarding_channel = self
-            .start_packet_delay_forwarder(node_stats_update_sender.clone(), shutdown.subscribe());
+            self.start_node_stats_controller(metrics.clone(), shutdown.subscribe());
+        let delay_forwarding_channel = self.start_packet_delay_forwarder(
+            node_stats_update_sender.clone(),
+            metrics.clone(),
+            shutdown.subscribe(),
+        );
+        let (sphinx_keys, announced_sphinx_keys) =
+            self.start_sphinx_key_manager(shutdown.subscribe());
         self.start_socket_listener(
+            sphinx_keys,
             node_stats_update_sender,
             delay_forwarding_channel,
             shutdown.subscribe(),
@@ -328,7 +399,12 @@ impl MixNode {
         // Rocket handles shutdown on it's own, but its shutdown handling should be incorporated
         // with that of the rest of the tasks.
         // Currently it's runtime is forcefully terminated once the mixnode exits.
-        self.start_http_api(atomic_verloc_results, node_stats_pointer);
+        self.start_http_api(
+            atomic_verloc_results,
+            node_stats_pointer,
+            announced_sphinx_keys,
+            metrics,
+        );
 
         info!("Finished nym mixnode startup procedure - it should now be able to receive mix traffic!");
         self.wait_for_interrupt(shutdown).await
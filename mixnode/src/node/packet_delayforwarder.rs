@@ -1,6 +1,7 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::node::metrics::MixnodeMetrics;
 use crate::node::node_statistics::UpdateSender;
 use futures::channel::mpsc;
 use futures::StreamExt;
@@ -28,6 +29,7 @@ where
     packet_sender: PacketDelayForwardSender,
     packet_receiver: PacketDelayForwardReceiver,
     node_stats_update_sender: UpdateSender,
+    metrics: MixnodeMetrics,
     shutdown: TaskClient,
 }
 
@@ -38,6 +40,7 @@ where
     pub(crate) fn new(
         client: C,
         node_stats_update_sender: UpdateSender,
+        metrics: MixnodeMetrics,
         shutdown: TaskClient,
     ) -> DelayForwarder<C> {
         let (packet_sender, packet_receiver) = mpsc::unbounded();
@@ -48,6 +51,7 @@ where
             packet_sender,
             packet_receiver,
             node_stats_update_sender,
+            metrics,
             shutdown,
         }
     }
@@ -59,11 +63,12 @@ where
     fn forward_packet(&mut self, packet: MixPacket) {
         let next_hop = packet.next_hop();
         let packet_mode = packet.packet_mode();
-        let sphinx_packet = packet.into_sphinx_packet();
+        let key_rotation = packet.key_rotation();
+        let packet = packet.into_packet();
 
         if let Err(err) =
             self.mixnet_client
-                .send_without_response(next_hop, sphinx_packet, packet_mode)
+                .send_without_response(next_hop, packet, packet_mode, key_rotation)
         {
             if err.kind() == io::ErrorKind::WouldBlock {
                 // we only know for sure if we dropped a packet if our sending queue was full
@@ -85,6 +90,9 @@ where
     /// Upon packet being finished getting delayed, forward it to the mixnet.
     fn handle_done_delaying(&mut self, packet: Expired<MixPacket>) {
         let delayed_packet = packet.into_inner();
+        self.metrics
+            .delay_queue_size
+            .set(self.delay_queue.len() as i64);
         self.forward_packet(delayed_packet)
     }
 
@@ -98,6 +106,9 @@ where
                 self.forward_packet(new_packet.0)
             } else {
                 self.delay_queue.insert_at(new_packet.0, instant);
+                self.metrics
+                    .delay_queue_size
+                    .set(self.delay_queue.len() as i64);
             }
         } else {
             self.forward_packet(new_packet.0)
@@ -138,24 +149,26 @@ mod tests {
 
     use nymsphinx::addressing::nodes::NymNodeRoutingAddress;
     use nymsphinx_params::packet_sizes::PacketSize;
-    use nymsphinx_params::PacketMode;
+    use nymsphinx_params::{PacketMode, SphinxKeyRotation};
     use nymsphinx_types::builder::SphinxPacketBuilder;
     use nymsphinx_types::{
         crypto, Delay as SphinxDelay, Destination, DestinationAddressBytes, Node, NodeAddressBytes,
-        SphinxPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH, NODE_ADDRESS_LENGTH,
+        NymPacket, SphinxPacket, DESTINATION_ADDRESS_LENGTH, IDENTIFIER_LENGTH,
+        NODE_ADDRESS_LENGTH,
     };
 
     #[derive(Default)]
     struct TestClient {
-        pub packets_sent: Arc<Mutex<Vec<(NymNodeRoutingAddress, SphinxPacket, PacketMode)>>>,
+        pub packets_sent: Arc<Mutex<Vec<(NymNodeRoutingAddress, NymPacket, PacketMode)>>>,
     }
 
     impl mixnet_client::SendWithoutResponse for TestClient {
         fn send_without_response(
             &mut self,
             address: NymNodeRoutingAddress,
-            packet: SphinxPacket,
+            packet: NymPacket,
             packet_mode: PacketMode,
+            _key_rotation: SphinxKeyRotation,
         ) -> io::Result<()> {
             self.packets_sent
                 .lock()
@@ -206,8 +219,12 @@ mod tests {
         let client = TestClient::default();
         let client_packets_sent = client.packets_sent.clone();
         let shutdown = TaskManager::default();
-        let mut delay_forwarder =
-            DelayForwarder::new(client, node_stats_update_sender, shutdown.subscribe());
+        let mut delay_forwarder = DelayForwarder::new(
+            client,
+            node_stats_update_sender,
+            MixnodeMetrics::new(),
+            shutdown.subscribe(),
+        );
         let packet_sender = delay_forwarder.sender();
 
         // Spawn the worker, listening on packet_sender channel
@@ -218,7 +235,7 @@ mod tests {
             NymNodeRoutingAddress::from(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 42));
         let mix_packet = MixPacket::new(
             next_hop,
-            make_valid_sphinx_packet(PacketSize::default()),
+            make_valid_sphinx_packet(PacketSize::default()).into(),
             PacketMode::default(),
         );
         let forward_instant = None;
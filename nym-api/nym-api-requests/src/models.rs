@@ -12,6 +12,7 @@ use mixnet_contract_common::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{fmt, time::Duration};
+use topology::sphinx_key::SignedSphinxKey;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct RequestError {
@@ -114,6 +115,15 @@ impl MixNodeBondAnnotated {
     }
 }
 
+/// Sphinx keys announced by a mixnode, whose signatures have already been checked
+/// against its identity key.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MixNodeSphinxKeys {
+    pub mix_id: MixId,
+    pub identity_key: IdentityKey,
+    pub keys: Vec<SignedSphinxKey>,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ComputeRewardEstParam {
     pub performance: Option<Performance>,
@@ -7,7 +7,10 @@ use nym_api_requests::models::CirculatingSupplyResponse;
 use rocket::fairing::AdHoc;
 use std::ops::Deref;
 use std::{
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use thiserror::Error;
@@ -67,6 +70,20 @@ impl CirculatingSupplyCache {
         }
     }
 
+    /// Returns the unix timestamp of the last cache update, if it has been initialised.
+    pub(crate) async fn last_updated(&self) -> Option<i64> {
+        if !self.initialised.load(Ordering::Relaxed) {
+            return None;
+        }
+        match time::timeout(Duration::from_millis(100), self.data.read()).await {
+            Ok(cache) => Some(cache.circulating_supply.timestamp()),
+            Err(err) => {
+                error!("Failed to get circulating supply: {err}");
+                None
+            }
+        }
+    }
+
     pub(crate) fn stage(mix_denom: String) -> AdHoc {
         AdHoc::on_ignite("Circulating Supply Cache Stage", |rocket| async {
             rocket.manage(Self::new(mix_denom))
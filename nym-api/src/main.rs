@@ -9,6 +9,7 @@ use crate::node_status_api::uptime_updater::HistoricalUptimeUpdater;
 use crate::support::cli;
 use crate::support::cli::CliArgs;
 use crate::support::config::Config;
+use crate::support::metrics::NymApiMetrics;
 use crate::support::storage;
 use crate::support::storage::NymApiStorage;
 use ::config::defaults::setup_env;
@@ -20,6 +21,7 @@ use log::info;
 use logging::setup_logging;
 use node_status_api::NodeStatusCache;
 use nym_contract_cache::cache::NymContractCache;
+use sphinx_keys::cache::SphinxKeysCache;
 use std::error::Error;
 use support::{http, nyxd};
 use task::TaskManager;
@@ -35,6 +37,7 @@ mod epoch_operations;
 mod network_monitor;
 pub(crate) mod node_status_api;
 pub(crate) mod nym_contract_cache;
+mod sphinx_keys;
 pub(crate) mod support;
 
 #[cfg(feature = "coconut")]
@@ -95,7 +98,9 @@ async fn start_nym_api_tasks(
     let nym_contract_cache_state = rocket.state::<NymContractCache>().unwrap();
     let node_status_cache_state = rocket.state::<NodeStatusCache>().unwrap();
     let circulating_supply_cache_state = rocket.state::<CirculatingSupplyCache>().unwrap();
+    let sphinx_keys_cache_state = rocket.state::<SphinxKeysCache>().unwrap();
     let maybe_storage = rocket.state::<NymApiStorage>();
+    let metrics = rocket.state::<NymApiMetrics>().unwrap();
 
     // start all the caches first
     let nym_contract_cache_listener = nym_contract_cache::start_refresher(
@@ -118,6 +123,12 @@ async fn start_nym_api_tasks(
         circulating_supply_cache_state,
         &shutdown,
     );
+    sphinx_keys::start_cache_refresh(
+        &config,
+        nym_contract_cache_state,
+        sphinx_keys_cache_state,
+        &shutdown,
+    );
 
     // start dkg task
     #[cfg(feature = "coconut")]
@@ -140,6 +151,7 @@ async fn start_nym_api_tasks(
             &config,
             nym_contract_cache_state,
             storage,
+            metrics,
             nyxd_client.clone(),
             system_version,
             &shutdown,
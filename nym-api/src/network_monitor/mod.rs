@@ -15,6 +15,7 @@ use crate::network_monitor::monitor::Monitor;
 use crate::nym_contract_cache::cache::NymContractCache;
 use crate::storage::NymApiStorage;
 use crate::support::config::Config;
+use crate::support::metrics::NymApiMetrics;
 use crate::support::nyxd;
 use credential_storage::PersistentStorage;
 use crypto::asymmetric::{encryption, identity};
@@ -35,6 +36,7 @@ pub(crate) fn setup<'a>(
     config: &'a Config,
     nym_contract_cache_state: &NymContractCache,
     storage: &NymApiStorage,
+    metrics: &NymApiMetrics,
     _nyxd_client: nyxd::Client,
     system_version: &str,
 ) -> NetworkMonitorBuilder<'a> {
@@ -44,6 +46,7 @@ pub(crate) fn setup<'a>(
         system_version,
         storage.to_owned(),
         nym_contract_cache_state.to_owned(),
+        metrics.to_owned(),
     )
 }
 
@@ -53,6 +56,7 @@ pub(crate) struct NetworkMonitorBuilder<'a> {
     system_version: String,
     node_status_storage: NymApiStorage,
     validator_cache: NymContractCache,
+    metrics: NymApiMetrics,
 }
 
 impl<'a> NetworkMonitorBuilder<'a> {
@@ -62,6 +66,7 @@ impl<'a> NetworkMonitorBuilder<'a> {
         system_version: &str,
         node_status_storage: NymApiStorage,
         validator_cache: NymContractCache,
+        metrics: NymApiMetrics,
     ) -> Self {
         NetworkMonitorBuilder {
             config,
@@ -69,6 +74,7 @@ impl<'a> NetworkMonitorBuilder<'a> {
             system_version: system_version.to_string(),
             node_status_storage,
             validator_cache,
+            metrics,
         }
     }
 
@@ -139,6 +145,7 @@ impl<'a> NetworkMonitorBuilder<'a> {
             received_processor,
             summary_producer,
             self.node_status_storage,
+            self.metrics,
         );
 
         NetworkMonitorRunnables {
@@ -229,6 +236,7 @@ pub(crate) async fn start(
     config: &Config,
     nym_contract_cache_state: &NymContractCache,
     storage: &NymApiStorage,
+    metrics: &NymApiMetrics,
     nyxd_client: nyxd::Client,
     system_version: &str,
     shutdown: &TaskManager,
@@ -237,6 +245,7 @@ pub(crate) async fn start(
         config,
         nym_contract_cache_state,
         storage,
+        metrics,
         nyxd_client,
         system_version,
     );
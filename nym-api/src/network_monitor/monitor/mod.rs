@@ -9,6 +9,7 @@ use crate::network_monitor::test_packet::TestPacket;
 use crate::network_monitor::test_route::TestRoute;
 use crate::storage::NymApiStorage;
 use crate::support::config::Config;
+use crate::support::metrics::NymApiMetrics;
 use log::{debug, error, info};
 use std::collections::{HashMap, HashSet};
 use std::process;
@@ -30,6 +31,7 @@ pub(super) struct Monitor {
     received_processor: ReceivedProcessor,
     summary_producer: SummaryProducer,
     node_status_storage: NymApiStorage,
+    metrics: NymApiMetrics,
     run_interval: Duration,
     gateway_ping_interval: Duration,
     packet_delivery_timeout: Duration,
@@ -53,6 +55,7 @@ impl Monitor {
         received_processor: ReceivedProcessor,
         summary_producer: SummaryProducer,
         node_status_storage: NymApiStorage,
+        metrics: NymApiMetrics,
     ) -> Self {
         Monitor {
             test_nonce: 1,
@@ -61,6 +64,7 @@ impl Monitor {
             received_processor,
             summary_producer,
             node_status_storage,
+            metrics,
             run_interval: config.get_network_monitor_run_interval(),
             gateway_ping_interval: config.get_gateway_ping_interval(),
             packet_delivery_timeout: config.get_packet_delivery_timeout(),
@@ -293,10 +297,16 @@ impl Monitor {
             );
             self.test_network_against(&test_routes).await;
         } else {
+            self.metrics.failed_monitor_runs.inc();
             error!("We failed to construct sufficient number of test routes to test the network against")
         }
 
-        debug!("Test run took {:?}", Instant::now().duration_since(start));
+        let run_duration = Instant::now().duration_since(start);
+        debug!("Test run took {:?}", run_duration);
+        self.metrics.monitor_runs.inc();
+        self.metrics
+            .monitor_run_duration
+            .observe(run_duration.as_secs_f64());
 
         self.test_nonce += 1;
     }
@@ -103,6 +103,17 @@ impl NodeStatusCache {
         self.get(|c| c.inclusion_probabilities.clone()).await
     }
 
+    /// Returns the unix timestamp of the last cache update.
+    pub(crate) async fn last_updated(&self) -> Option<i64> {
+        match time::timeout(Duration::from_millis(CACHE_TIMEOUT_MS), self.inner.read()).await {
+            Ok(cache) => Some(cache.mixnodes_annotated.timestamp()),
+            Err(e) => {
+                error!("{e}");
+                None
+            }
+        }
+    }
+
     pub async fn mixnode_details(
         &self,
         mix_id: MixId,
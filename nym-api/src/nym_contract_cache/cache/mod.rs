@@ -293,6 +293,20 @@ impl NymContractCache {
         self.initialised.load(Ordering::Relaxed)
     }
 
+    /// Returns the unix timestamp of the last cache update, if it has been initialised.
+    pub(crate) async fn last_updated(&self) -> Option<i64> {
+        if !self.initialised() {
+            return None;
+        }
+        match time::timeout(Duration::from_millis(100), self.inner.read()).await {
+            Ok(cache) => Some(cache.mixnodes.timestamp()),
+            Err(err) => {
+                error!("{err}");
+                None
+            }
+        }
+    }
+
     pub(crate) async fn wait_for_initial_values(&self) {
         let initialisation_backoff = Duration::from_secs(5);
         loop {
@@ -0,0 +1,65 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::support::caching::Cache;
+use nym_api_requests::models::MixNodeSphinxKeys;
+use rocket::fairing::AdHoc;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::RwLock;
+use tokio::time;
+
+pub(crate) mod refresher;
+
+/// A cache for the sphinx keys announced by the mixnodes, so that the clients could learn about
+/// the rotated keys without having to contact every single node in the network themselves.
+///
+/// Only the keys with valid signatures are kept. Nodes that don't announce any keys are omitted
+/// and the clients are expected to keep using their bonded keys.
+#[derive(Clone)]
+pub(crate) struct SphinxKeysCache {
+    initialised: Arc<AtomicBool>,
+    data: Arc<RwLock<Cache<Vec<MixNodeSphinxKeys>>>>,
+}
+
+impl SphinxKeysCache {
+    fn new() -> SphinxKeysCache {
+        SphinxKeysCache {
+            initialised: Arc::new(AtomicBool::new(false)),
+            data: Arc::new(RwLock::new(Cache::default())),
+        }
+    }
+
+    pub(crate) fn stage() -> AdHoc {
+        AdHoc::on_ignite("Sphinx Keys Cache Stage", |rocket| async {
+            rocket.manage(Self::new())
+        })
+    }
+
+    pub(crate) async fn get_mixnodes_sphinx_keys(&self) -> Option<Vec<MixNodeSphinxKeys>> {
+        if !self.initialised.load(Ordering::Relaxed) {
+            return None;
+        }
+        match time::timeout(Duration::from_millis(100), self.data.read()).await {
+            Ok(cache) => Some(cache.value.clone()),
+            Err(err) => {
+                error!("Failed to get the mixnodes sphinx keys: {err}");
+                None
+            }
+        }
+    }
+
+    pub(crate) async fn update(&self, sphinx_keys: Vec<MixNodeSphinxKeys>) {
+        info!(
+            "Updating sphinx keys cache. {} mixnodes have announced their keys",
+            sphinx_keys.len()
+        );
+        self.data.write().await.update(sphinx_keys);
+        self.initialised.store(true, Ordering::Relaxed)
+    }
+}
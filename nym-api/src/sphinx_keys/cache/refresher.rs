@@ -0,0 +1,153 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::SphinxKeysCache;
+use crate::nym_contract_cache::cache::NymContractCache;
+use crypto::asymmetric::identity;
+use futures::{stream, StreamExt};
+use mixnet_contract_common::MixNodeDetails;
+use nym_api_requests::models::MixNodeSphinxKeys;
+use std::time::Duration;
+use task::TaskClient;
+use time::OffsetDateTime;
+use topology::sphinx_key::SignedSphinxKey;
+use topology::NetworkAddress;
+
+/// How long we're willing to wait for a mixnode to tell us about its sphinx keys.
+const SPHINX_KEYS_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum number of mixnodes that are being queried at the same time.
+const MAX_CONCURRENT_QUERIES: usize = 50;
+
+/// How often we check whether the contract cache has already learned about the bonded mixnodes.
+const CONTRACT_CACHE_POLLING_RATE: Duration = Duration::from_secs(1);
+
+pub(crate) struct SphinxKeysCacheRefresher {
+    nym_contract_cache: NymContractCache,
+    cache: SphinxKeysCache,
+    caching_interval: Duration,
+    client: reqwest::Client,
+}
+
+impl SphinxKeysCacheRefresher {
+    pub(crate) fn new(
+        nym_contract_cache: NymContractCache,
+        cache: SphinxKeysCache,
+        caching_interval: Duration,
+    ) -> Self {
+        SphinxKeysCacheRefresher {
+            nym_contract_cache,
+            cache,
+            caching_interval,
+            client: reqwest::Client::builder()
+                .timeout(SPHINX_KEYS_REQUEST_TIMEOUT)
+                .build()
+                .expect("failed to build the http client"),
+        }
+    }
+
+    async fn wait_for_contract_cache(&self) {
+        while !self.nym_contract_cache.initialised() {
+            tokio::time::sleep(CONTRACT_CACHE_POLLING_RATE).await
+        }
+    }
+
+    pub(crate) async fn run(&self, mut shutdown: TaskClient) {
+        // there's no point in querying anything before we know which mixnodes are bonded
+        tokio::select! {
+            biased;
+            _ = shutdown.recv() => {
+                trace!("SphinxKeysCacheRefresher: Received shutdown");
+                return;
+            }
+            _ = self.wait_for_contract_cache() => {}
+        }
+
+        let mut interval = tokio::time::interval(self.caching_interval);
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                _ = interval.tick() => {
+                    tokio::select! {
+                        biased;
+                        _ = shutdown.recv() => {
+                            trace!("SphinxKeysCacheRefresher: Received shutdown");
+                        }
+                        _ = self.refresh() => {}
+                    }
+                }
+                _ = shutdown.recv() => {
+                    trace!("SphinxKeysCacheRefresher: Received shutdown");
+                }
+            }
+        }
+    }
+
+    async fn query_mixnode(&self, mixnode: &MixNodeDetails) -> Option<Vec<SignedSphinxKey>> {
+        let mix_node = &mixnode.bond_information.mix_node;
+        let host: NetworkAddress = mix_node.host.parse().ok()?;
+        let url = format!("{}/sphinx-keys", host.http_url(mix_node.http_api_port));
+
+        let response = match self.client.get(&url).send().await {
+            Ok(response) => response,
+            Err(err) => {
+                debug!("failed to query {url} for the announced sphinx keys - {err}");
+                return None;
+            }
+        };
+        match response.error_for_status() {
+            Ok(response) => response.json().await.ok(),
+            Err(err) => {
+                debug!("{url} did not return the announced sphinx keys - {err}");
+                None
+            }
+        }
+    }
+
+    /// Retrieves the keys announced by the mixnode and discards any that have already expired
+    /// or that have not been signed with its identity key.
+    async fn get_verified_keys(
+        &self,
+        mixnode: &MixNodeDetails,
+        unix_timestamp: u64,
+    ) -> Option<MixNodeSphinxKeys> {
+        let identity_key = &mixnode.bond_information.mix_node.identity_key;
+        let identity = identity::PublicKey::from_base58_string(identity_key).ok()?;
+
+        let keys = self
+            .query_mixnode(mixnode)
+            .await?
+            .into_iter()
+            .filter(|key| unix_timestamp < key.valid_until)
+            .filter(|key| match key.verify(&identity) {
+                Ok(_) => true,
+                Err(err) => {
+                    warn!("mixnode {identity_key} has announced an invalid sphinx key - {err}");
+                    false
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if keys.is_empty() {
+            return None;
+        }
+        Some(MixNodeSphinxKeys {
+            mix_id: mixnode.mix_id(),
+            identity_key: identity_key.clone(),
+            keys,
+        })
+    }
+
+    async fn refresh(&self) {
+        let mixnodes = self.nym_contract_cache.mixnodes().await;
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+
+        let sphinx_keys = stream::iter(mixnodes.iter())
+            .map(|mixnode| self.get_verified_keys(mixnode, now))
+            .buffer_unordered(MAX_CONCURRENT_QUERIES)
+            .filter_map(|keys| async move { keys })
+            .collect::<Vec<_>>()
+            .await;
+
+        self.cache.update(sphinx_keys).await;
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use okapi::openapi3::OpenApi;
+use rocket::Route;
+use rocket_okapi::{openapi_get_routes_spec, settings::OpenApiSettings};
+use task::TaskManager;
+
+use crate::nym_contract_cache::cache::NymContractCache;
+use crate::support::config::Config;
+
+use self::cache::refresher::SphinxKeysCacheRefresher;
+
+pub(crate) mod cache;
+pub(crate) mod routes;
+
+/// Merges the routes with http information and returns it to Rocket for serving
+pub(crate) fn sphinx_keys_routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+    openapi_get_routes_spec![settings: routes::get_mixnodes_sphinx_keys]
+}
+
+/// Spawn the refresher of the sphinx keys announced by the mixnodes.
+pub(crate) fn start_cache_refresh(
+    config: &Config,
+    nym_contract_cache_state: &NymContractCache,
+    sphinx_keys_cache: &cache::SphinxKeysCache,
+    shutdown: &TaskManager,
+) {
+    if config.get_sphinx_keys_enabled() {
+        let refresher = SphinxKeysCacheRefresher::new(
+            nym_contract_cache_state.to_owned(),
+            sphinx_keys_cache.to_owned(),
+            config.get_sphinx_keys_caching_interval(),
+        );
+        let shutdown_listener = shutdown.subscribe();
+        tokio::spawn(async move { refresher.run(shutdown_listener).await });
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::node_status_api::models::ErrorResponse;
+use crate::sphinx_keys::cache::SphinxKeysCache;
+use nym_api_requests::models::MixNodeSphinxKeys;
+use rocket_okapi::openapi;
+
+#[openapi(tag = "sphinx-keys")]
+#[get("/mixnodes/sphinx-keys")]
+pub(crate) async fn get_mixnodes_sphinx_keys(
+    cache: &State<SphinxKeysCache>,
+) -> Result<Json<Vec<MixNodeSphinxKeys>>, ErrorResponse> {
+    match cache.get_mixnodes_sphinx_keys().await {
+        Some(value) => Ok(Json(value)),
+        None => Err(ErrorResponse::new(
+            "unavailable",
+            Status::InternalServerError,
+        )),
+    }
+}
@@ -36,6 +36,7 @@ const DEFAULT_PER_NODE_TEST_PACKETS: usize = 3;
 const DEFAULT_TOPOLOGY_CACHE_INTERVAL: Duration = Duration::from_secs(30);
 const DEFAULT_NODE_STATUS_CACHE_INTERVAL: Duration = Duration::from_secs(120);
 const DEFAULT_CIRCULATING_SUPPLY_CACHE_INTERVAL: Duration = Duration::from_secs(3600);
+const DEFAULT_SPHINX_KEYS_CACHE_INTERVAL: Duration = Duration::from_secs(600);
 const DEFAULT_MONITOR_THRESHOLD: u8 = 60;
 const DEFAULT_MIN_MIXNODE_RELIABILITY: u8 = 50;
 const DEFAULT_MIN_GATEWAY_RELIABILITY: u8 = 20;
@@ -57,11 +58,17 @@ pub struct Config {
     #[serde(default)]
     circulating_supply_cacher: CirculatingSupplyCacher,
 
+    #[serde(default)]
+    sphinx_keys_cacher: SphinxKeysCacher,
+
     #[serde(default)]
     rewarding: Rewarding,
 
     #[serde(default)]
     coconut_signer: CoconutSigner,
+
+    #[serde(default)]
+    metrics: Metrics,
 }
 
 impl NymConfig for Config {
@@ -289,6 +296,26 @@ impl Default for CirculatingSupplyCacher {
     }
 }
 
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct SphinxKeysCacher {
+    /// Specifies whether the sphinx keys announced by the mixnodes should be collected,
+    /// so that the clients would not have to query the nodes themselves.
+    enabled: bool,
+
+    #[serde(with = "humantime_serde")]
+    caching_interval: Duration,
+}
+
+impl Default for SphinxKeysCacher {
+    fn default() -> Self {
+        SphinxKeysCacher {
+            enabled: true,
+            caching_interval: DEFAULT_SPHINX_KEYS_CACHE_INTERVAL,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
 pub struct Rewarding {
@@ -310,6 +337,14 @@ impl Default for Rewarding {
     }
 }
 
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct Metrics {
+    /// Specifies whether the metrics of this process should be exposed in the Prometheus format
+    /// under the `/metrics` endpoint.
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
 pub struct CoconutSigner {
@@ -569,6 +604,18 @@ impl Config {
         self.circulating_supply_cacher.enabled
     }
 
+    pub fn get_sphinx_keys_caching_interval(&self) -> Duration {
+        self.sphinx_keys_cacher.caching_interval
+    }
+
+    pub fn get_sphinx_keys_enabled(&self) -> bool {
+        self.sphinx_keys_cacher.enabled
+    }
+
+    pub fn get_metrics_enabled(&self) -> bool {
+        self.metrics.enabled
+    }
+
     pub fn get_node_status_api_database_path(&self) -> PathBuf {
         self.node_status_api.database_path.clone()
     }
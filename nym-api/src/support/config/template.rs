@@ -113,5 +113,11 @@ decryption_key_path = '{{ coconut_signer.decryption_key_path }}'
 # Path to the dkg dealer public key with proof
 public_key_with_proof_path = '{{ coconut_signer.public_key_with_proof_path }}'
 
+[metrics]
+
+# Specifies whether the metrics of this process should be exposed in the Prometheus format
+# under the `/metrics` endpoint.
+enabled = {{ metrics.enabled }}
+
 "#
 }
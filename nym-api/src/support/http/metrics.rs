@@ -0,0 +1,39 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::circulating_supply_api::cache::CirculatingSupplyCache;
+use crate::node_status_api::NodeStatusCache;
+use crate::nym_contract_cache::cache::NymContractCache;
+use crate::support::metrics::NymApiMetrics;
+use nym_metrics::PROMETHEUS_CONTENT_TYPE;
+use rocket::http::{ContentType, Status};
+use rocket::State;
+use time::OffsetDateTime;
+
+/// Provides the metrics of this nym-api in the Prometheus text exposition format.
+#[get("/metrics")]
+pub(crate) async fn metrics(
+    metrics: &State<NymApiMetrics>,
+    contract_cache: &State<NymContractCache>,
+    node_status_cache: &State<NodeStatusCache>,
+    circulating_supply_cache: &State<CirculatingSupplyCache>,
+) -> Result<(ContentType, String), Status> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    if let Some(last_updated) = contract_cache.last_updated().await {
+        metrics.set_cache_age("nym_contract", last_updated, now)
+    }
+    if let Some(last_updated) = node_status_cache.last_updated().await {
+        metrics.set_cache_age("node_status", last_updated, now)
+    }
+    if let Some(last_updated) = circulating_supply_cache.last_updated().await {
+        metrics.set_cache_age("circulating_supply", last_updated, now)
+    }
+
+    let encoded = metrics.encode().map_err(|err| {
+        error!("failed to encode the metrics: {err}");
+        Status::InternalServerError
+    })?;
+    let content_type =
+        ContentType::parse_flexible(PROMETHEUS_CONTENT_TYPE).unwrap_or(ContentType::Plain);
+    Ok((content_type, encoded))
+}
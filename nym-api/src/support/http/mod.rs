@@ -4,9 +4,11 @@
 use crate::circulating_supply_api::cache::CirculatingSupplyCache;
 use crate::node_status_api::{self, NodeStatusCache};
 use crate::nym_contract_cache::cache::NymContractCache;
+use crate::sphinx_keys::cache::SphinxKeysCache;
 use crate::support::config::Config;
+use crate::support::metrics::NymApiMetrics;
 use crate::support::{nyxd, storage};
-use crate::{circulating_supply_api, nym_contract_cache};
+use crate::{circulating_supply_api, nym_contract_cache, sphinx_keys};
 use anyhow::Result;
 use rocket::http::Method;
 use rocket::{Ignite, Rocket};
@@ -17,6 +19,7 @@ use rocket_okapi::swagger_ui::make_swagger_ui;
 #[cfg(feature = "coconut")]
 use crate::coconut::{self, comm::QueryCommunicationChannel, InternalSignRequest};
 
+pub(crate) mod metrics;
 pub(crate) mod openapi;
 
 pub(crate) async fn setup_rocket(
@@ -35,6 +38,7 @@ pub(crate) async fn setup_rocket(
         "/" => (vec![], openapi::custom_openapi_spec()),
         "" => circulating_supply_api::circulating_supply_routes(&openapi_settings),
         "" => nym_contract_cache::nym_contract_cache_routes(&openapi_settings),
+        "" => sphinx_keys::sphinx_keys_routes(&openapi_settings),
         "/status" => node_status_api::node_status_routes(&openapi_settings, config.get_network_monitor_enabled()),
     }
 
@@ -43,7 +47,16 @@ pub(crate) async fn setup_rocket(
         .attach(setup_cors()?)
         .attach(NymContractCache::stage())
         .attach(NodeStatusCache::stage())
-        .attach(CirculatingSupplyCache::stage(mix_denom.clone()));
+        .attach(CirculatingSupplyCache::stage(mix_denom.clone()))
+        .attach(SphinxKeysCache::stage())
+        .manage(NymApiMetrics::new());
+
+    let rocket = if config.get_metrics_enabled() {
+        info!("Exposing Prometheus metrics under /metrics");
+        rocket.mount("/", routes![metrics::metrics])
+    } else {
+        rocket
+    };
 
     // This is not a very nice approach. A lazy value would be more suitable, but that's still
     // a nightly feature: https://github.com/rust-lang/rust/issues/74465
@@ -0,0 +1,64 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use nym_metrics::{Histogram, IntCounter, IntGaugeVec, MetricsError, Registry};
+
+// the monitor runs can take anywhere from few seconds (if no routes could be constructed)
+// up to several minutes
+const MONITOR_RUN_DURATION_BUCKETS: &[f64] = &[5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+
+/// Prometheus metrics of the nym-api. Cloning it is cheap as all metrics are reference counted.
+#[derive(Clone)]
+pub(crate) struct NymApiMetrics {
+    registry: Registry,
+
+    pub(crate) monitor_runs: IntCounter,
+    pub(crate) failed_monitor_runs: IntCounter,
+    pub(crate) monitor_run_duration: Histogram,
+
+    /// Number of seconds since each of the caches got updated. It's only updated when the metrics
+    /// are being scraped.
+    pub(crate) cache_age: IntGaugeVec,
+}
+
+impl NymApiMetrics {
+    pub(crate) fn new() -> Self {
+        let registry = nym_metrics::new_registry("nym_api");
+
+        NymApiMetrics {
+            monitor_runs: nym_metrics::int_counter(
+                &registry,
+                "network_monitor_runs_total",
+                "Number of network monitor test runs performed",
+            ),
+            failed_monitor_runs: nym_metrics::int_counter(
+                &registry,
+                "network_monitor_failed_runs_total",
+                "Number of network monitor test runs that failed to construct sufficient number of test routes",
+            ),
+            monitor_run_duration: nym_metrics::histogram(
+                &registry,
+                "network_monitor_run_duration_seconds",
+                "Duration of the network monitor test runs",
+                MONITOR_RUN_DURATION_BUCKETS.to_vec(),
+            ),
+            cache_age: nym_metrics::int_gauge_vec(
+                &registry,
+                "cache_age_seconds",
+                "Number of seconds since the cache got last updated",
+                &["cache"],
+            ),
+            registry,
+        }
+    }
+
+    pub(crate) fn set_cache_age(&self, cache: &str, last_updated: i64, now: i64) {
+        self.cache_age
+            .with_label_values(&[cache])
+            .set(now.saturating_sub(last_updated));
+    }
+
+    pub(crate) fn encode(&self) -> Result<String, MetricsError> {
+        nym_metrics::encode(&self.registry)
+    }
+}
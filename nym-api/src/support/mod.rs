@@ -5,5 +5,6 @@ pub(crate) mod caching;
 pub(crate) mod cli;
 pub(crate) mod config;
 pub(crate) mod http;
+pub(crate) mod metrics;
 pub(crate) mod nyxd;
 pub(crate) mod storage;
@@ -17,4 +17,12 @@ pub enum OutFoxError {
     InvalidKeyLength,
     #[error("Message length must be greater then {MIN_MESSAGE_LEN} bytes")]
     InvalidMessageLength,
+    #[error("Route must consist of between 1 and {max} hops, got {got}")]
+    InvalidRouteLength { max: usize, got: usize },
+    #[error("Payload of {got} bytes does not fit in the packet, maximum is {max} bytes")]
+    PayloadTooLong { max: usize, got: usize },
+    #[error("Failed to obtain randomness for the ephemeral keys")]
+    RandomnessUnavailable,
+    #[error("Packet is malformed - {0}")]
+    MalformedPacket(&'static str),
 }
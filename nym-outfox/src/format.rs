@@ -65,8 +65,8 @@ use curve25519_dalek::scalar::Scalar;
 
 use std::convert::TryInto;
 
-const GROUPELEMENTBYTES: usize = 32;
-const TAGBYTES: usize = 16;
+pub const GROUPELEMENTBYTES: usize = 32;
+pub const TAGBYTES: usize = 16;
 
 use std::ops::Range;
 
@@ -0,0 +1,364 @@
+//! # Outfox packets
+//!
+//! A complete mix packet built on top of the layer encoding defined in [crate::format].
+//!
+//! Every hop of the route (including the final one) learns a fixed-size [OutfoxRoutingInformation],
+//! i.e. the address of the next hop (or the final destination) and the delay it should apply
+//! before forwarding the packet. Since the outfox header shrinks with every processed layer, the
+//! serialized packet is prefixed with the number of remaining hops so that its expected length
+//! could be determined without any external context:
+//!
+//! `[remaining_hops, Pk_0, Tag_0, Header_0, Payload]`
+//!
+//! The payload is padded to a fixed length as `[0; PAYLOAD_ZERO_PREFIX] || message || 1 || 0...0`.
+//! Since lion is an all-or-nothing transform, any tampering with the payload along the route
+//! results in the zero prefix getting garbled, which is detected by the final hop.
+
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use std::convert::TryInto;
+
+use crate::error::OutFoxError;
+use crate::format::{MixCreationParameters, MixStageParameters, GROUPELEMENTBYTES, TAGBYTES};
+
+/// Length of the address (of either the next hop or the final destination) included in the routing data.
+pub const OUTFOX_ADDRESS_LENGTH: usize = 32;
+
+/// Length of the routing data revealed to each hop: the address and the 8-byte delay.
+pub const OUTFOX_ROUTING_INFORMATION_LENGTH: usize = OUTFOX_ADDRESS_LENGTH + 8;
+
+/// Number of bytes each hop adds to the packet.
+pub const OUTFOX_PER_HOP_OVERHEAD: usize =
+    GROUPELEMENTBYTES + TAGBYTES + OUTFOX_ROUTING_INFORMATION_LENGTH;
+
+/// Number of zero bytes prepended to the payload in order to detect any tampering.
+const PAYLOAD_ZERO_PREFIX: usize = 16;
+
+/// Number of bytes of the payload that can't be used for the actual message.
+pub const OUTFOX_PAYLOAD_OVERHEAD_SIZE: usize = PAYLOAD_ZERO_PREFIX + 1;
+
+/// Routing data revealed to a single hop of the route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutfoxRoutingInformation {
+    /// Address of the next hop or, if this is the final hop, of the destination.
+    pub address: [u8; OUTFOX_ADDRESS_LENGTH],
+
+    /// Delay, in nanoseconds, the hop should apply before forwarding the packet.
+    pub delay_nanos: u64,
+}
+
+impl OutfoxRoutingInformation {
+    pub fn to_bytes(&self) -> [u8; OUTFOX_ROUTING_INFORMATION_LENGTH] {
+        let mut bytes = [0u8; OUTFOX_ROUTING_INFORMATION_LENGTH];
+        bytes[..OUTFOX_ADDRESS_LENGTH].copy_from_slice(&self.address);
+        bytes[OUTFOX_ADDRESS_LENGTH..].copy_from_slice(&self.delay_nanos.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OutFoxError> {
+        if bytes.len() != OUTFOX_ROUTING_INFORMATION_LENGTH {
+            return Err(OutFoxError::LenMismatch {
+                expected: OUTFOX_ROUTING_INFORMATION_LENGTH,
+                got: bytes.len(),
+            });
+        }
+
+        Ok(OutfoxRoutingInformation {
+            address: bytes[..OUTFOX_ADDRESS_LENGTH].try_into().unwrap(),
+            delay_nanos: u64::from_be_bytes(bytes[OUTFOX_ADDRESS_LENGTH..].try_into().unwrap()),
+        })
+    }
+}
+
+/// A single hop of the route the packet is going to take.
+#[derive(Debug, Clone, Copy)]
+pub struct OutfoxHop {
+    /// x25519 public key of the node.
+    pub public_key: [u8; 32],
+
+    /// Routing data that is going to be revealed to the node.
+    pub routing_information: OutfoxRoutingInformation,
+}
+
+/// Result of processing a single layer of an [OutfoxPacket].
+pub enum OutfoxProcessedPacket {
+    /// The packet should be forwarded to the address included in the routing information.
+    ForwardHop(Box<OutfoxPacket>, OutfoxRoutingInformation),
+
+    /// This was the final hop, the routing information contains the destination address
+    /// alongside the recovered message.
+    FinalHop(OutfoxRoutingInformation, Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutfoxPacket {
+    remaining_hops: u8,
+    buffer: Vec<u8>,
+}
+
+impl OutfoxPacket {
+    /// Length of the serialized packet with the given payload length and number of remaining hops.
+    pub fn expected_length(payload_length: usize, remaining_hops: u8) -> usize {
+        1 + payload_length + remaining_hops as usize * OUTFOX_PER_HOP_OVERHEAD
+    }
+
+    /// Builds a packet with the provided message padded to `payload_length` bytes
+    /// that is going to traverse the provided route.
+    pub fn build(
+        message: &[u8],
+        payload_length: usize,
+        route: &[OutfoxHop],
+    ) -> Result<Self, OutFoxError> {
+        if route.is_empty() || route.len() > u8::MAX as usize {
+            return Err(OutFoxError::InvalidRouteLength {
+                max: u8::MAX as usize,
+                got: route.len(),
+            });
+        }
+
+        if message.len() + OUTFOX_PAYLOAD_OVERHEAD_SIZE > payload_length {
+            return Err(OutFoxError::PayloadTooLong {
+                max: payload_length.saturating_sub(OUTFOX_PAYLOAD_OVERHEAD_SIZE),
+                got: message.len(),
+            });
+        }
+
+        let mut params = MixCreationParameters::new(payload_length);
+        for _ in route {
+            params.add_outer_layer(OUTFOX_ROUTING_INFORMATION_LENGTH);
+        }
+
+        let mut buffer = vec![0u8; params.total_packet_length()];
+        let payload_start = buffer.len() - payload_length;
+        let message_start = payload_start + PAYLOAD_ZERO_PREFIX;
+        buffer[message_start..message_start + message.len()].copy_from_slice(message);
+        buffer[message_start + message.len()] = 1;
+
+        // the layers are encoded starting with the innermost one, i.e. the final hop
+        for (layer, hop) in route.iter().rev().enumerate() {
+            let (range, stage_params) = params.get_stage_params(layer);
+            stage_params.encode_mix_layer(
+                &mut buffer[range],
+                &random_scalar()?,
+                &MontgomeryPoint(hop.public_key),
+                &hop.routing_information.to_bytes(),
+            )?;
+        }
+
+        Ok(OutfoxPacket {
+            remaining_hops: route.len() as u8,
+            buffer,
+        })
+    }
+
+    pub fn remaining_hops(&self) -> u8 {
+        self.remaining_hops
+    }
+
+    /// Length of the serialized packet.
+    pub fn len(&self) -> usize {
+        1 + self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn payload_length(&self) -> usize {
+        self.buffer.len() - self.remaining_hops as usize * OUTFOX_PER_HOP_OVERHEAD
+    }
+
+    /// Public group element of the current layer. It's unique for every packet and every hop,
+    /// so it can be used for detecting replays.
+    pub fn replay_tag(&self) -> [u8; GROUPELEMENTBYTES] {
+        self.buffer[..GROUPELEMENTBYTES].try_into().unwrap()
+    }
+
+    fn stage_params(&self) -> MixStageParameters {
+        MixStageParameters {
+            routing_information_length_bytes: OUTFOX_ROUTING_INFORMATION_LENGTH,
+            remaining_header_length_bytes: (self.remaining_hops as usize - 1)
+                * OUTFOX_PER_HOP_OVERHEAD,
+            payload_length_bytes: self.payload_length(),
+        }
+    }
+
+    /// Removes a single layer of encryption using the provided x25519 private key of the node.
+    pub fn process(mut self, private_key: &[u8; 32]) -> Result<OutfoxProcessedPacket, OutFoxError> {
+        let stage_params = self.stage_params();
+        let secret = Scalar::from_bytes_mod_order(clamp_scalar_bytes(*private_key));
+        stage_params.decode_mix_layer(&mut self.buffer, &secret)?;
+
+        let routing_information =
+            OutfoxRoutingInformation::from_bytes(&self.buffer[stage_params.routing_data_range()])?;
+        let remaining = self.buffer.split_off(stage_params.routing_data_range().end);
+
+        if self.remaining_hops == 1 {
+            let message = recover_message(remaining)?;
+            Ok(OutfoxProcessedPacket::FinalHop(
+                routing_information,
+                message,
+            ))
+        } else {
+            let next_packet = OutfoxPacket {
+                remaining_hops: self.remaining_hops - 1,
+                buffer: remaining,
+            };
+            Ok(OutfoxProcessedPacket::ForwardHop(
+                Box::new(next_packet),
+                routing_information,
+            ))
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        std::iter::once(self.remaining_hops)
+            .chain(self.buffer.iter().copied())
+            .collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OutFoxError> {
+        if bytes.is_empty() {
+            return Err(OutFoxError::MalformedPacket("no bytes provided"));
+        }
+
+        let remaining_hops = bytes[0];
+        if remaining_hops == 0 {
+            return Err(OutFoxError::MalformedPacket("no remaining hops"));
+        }
+
+        let header_length = remaining_hops as usize * OUTFOX_PER_HOP_OVERHEAD;
+        if bytes.len() - 1 < header_length + crate::lion::MIN_MESSAGE_LEN {
+            return Err(OutFoxError::MalformedPacket("too few bytes provided"));
+        }
+
+        Ok(OutfoxPacket {
+            remaining_hops,
+            buffer: bytes[1..].to_vec(),
+        })
+    }
+}
+
+fn random_scalar() -> Result<Scalar, OutFoxError> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|_| OutFoxError::RandomnessUnavailable)?;
+    Ok(Scalar::from_bytes_mod_order(bytes))
+}
+
+// the same clamping as performed on x25519 private keys, so that the keys used for sphinx
+// could be directly used for outfox as well
+fn clamp_scalar_bytes(mut bytes: [u8; 32]) -> [u8; 32] {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    bytes
+}
+
+fn recover_message(mut payload: Vec<u8>) -> Result<Vec<u8>, OutFoxError> {
+    if payload.len() < OUTFOX_PAYLOAD_OVERHEAD_SIZE
+        || payload[..PAYLOAD_ZERO_PREFIX].iter().any(|b| *b != 0)
+    {
+        return Err(OutFoxError::MalformedPacket(
+            "payload integrity check failed",
+        ));
+    }
+
+    let padding_start = match payload.iter().rposition(|b| *b == 1) {
+        Some(position) => position,
+        None => return Err(OutFoxError::MalformedPacket("payload padding is missing")),
+    };
+    if padding_start < PAYLOAD_ZERO_PREFIX || payload[padding_start + 1..].iter().any(|b| *b != 0) {
+        return Err(OutFoxError::MalformedPacket("payload padding is malformed"));
+    }
+
+    payload.truncate(padding_start);
+    Ok(payload.split_off(PAYLOAD_ZERO_PREFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+
+    fn keypair() -> ([u8; 32], [u8; 32]) {
+        let mut private = [0u8; 32];
+        getrandom::getrandom(&mut private).unwrap();
+        let private = clamp_scalar_bytes(private);
+        let public = (&ED25519_BASEPOINT_TABLE * &Scalar::from_bytes_mod_order(private))
+            .to_montgomery()
+            .to_bytes();
+        (private, public)
+    }
+
+    fn route(keys: &[([u8; 32], [u8; 32])]) -> Vec<OutfoxHop> {
+        keys.iter()
+            .enumerate()
+            .map(|(i, (_, public_key))| OutfoxHop {
+                public_key: *public_key,
+                routing_information: OutfoxRoutingInformation {
+                    address: [i as u8; OUTFOX_ADDRESS_LENGTH],
+                    delay_nanos: 42 + i as u64,
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn packet_can_be_processed_by_every_hop() {
+        let keys = vec![keypair(), keypair(), keypair(), keypair()];
+        let message = b"hello outfox".to_vec();
+        let payload_length = 2048;
+
+        let mut packet = OutfoxPacket::build(&message, payload_length, &route(&keys)).unwrap();
+        assert_eq!(
+            packet.len(),
+            OutfoxPacket::expected_length(payload_length, 4)
+        );
+
+        for (i, (private_key, _)) in keys.iter().enumerate() {
+            // make sure the packet survives serialization at every hop
+            let received = OutfoxPacket::from_bytes(&packet.to_bytes()).unwrap();
+            match received.process(private_key).unwrap() {
+                OutfoxProcessedPacket::ForwardHop(next, routing) => {
+                    assert_eq!(routing.address, [i as u8; OUTFOX_ADDRESS_LENGTH]);
+                    assert_eq!(routing.delay_nanos, 42 + i as u64);
+                    assert_eq!(next.remaining_hops() as usize, keys.len() - i - 1);
+                    packet = *next;
+                }
+                OutfoxProcessedPacket::FinalHop(routing, recovered) => {
+                    assert_eq!(i, keys.len() - 1);
+                    assert_eq!(routing.address, [i as u8; OUTFOX_ADDRESS_LENGTH]);
+                    assert_eq!(recovered, message);
+                    return;
+                }
+            }
+        }
+        panic!("the packet never reached its final hop")
+    }
+
+    #[test]
+    fn processing_with_invalid_key_fails() {
+        let keys = vec![keypair(), keypair()];
+        let packet = OutfoxPacket::build(b"foomp", 1024, &route(&keys)).unwrap();
+        assert!(packet.process(&keypair().0).is_err());
+    }
+
+    #[test]
+    fn tampered_payload_is_detected() {
+        let keys = vec![keypair()];
+        let packet = OutfoxPacket::build(b"foomp", 1024, &route(&keys)).unwrap();
+        let mut bytes = packet.to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+
+        let tampered = OutfoxPacket::from_bytes(&bytes).unwrap();
+        assert!(tampered.process(&keys[0].0).is_err());
+    }
+
+    #[test]
+    fn building_fails_for_too_long_message() {
+        let keys = vec![keypair()];
+        assert!(OutfoxPacket::build(&[42u8; 1024], 1024, &route(&keys)).is_err());
+        assert!(OutfoxPacket::build(&[42u8; 100], 1024, &[]).is_err());
+    }
+}
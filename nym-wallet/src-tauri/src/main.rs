@@ -56,6 +56,7 @@ fn main() {
             mixnet::bond::bond_gateway,
             mixnet::bond::bond_mixnode,
             mixnet::bond::pledge_more,
+            mixnet::bond::decrease_pledge,
             mixnet::bond::gateway_bond_details,
             mixnet::bond::get_pending_operator_rewards,
             mixnet::bond::mixnode_bond_details,
@@ -114,6 +115,7 @@ fn main() {
             vesting::bond::vesting_bond_gateway,
             vesting::bond::vesting_bond_mixnode,
             vesting::bond::vesting_pledge_more,
+            vesting::bond::vesting_decrease_pledge,
             vesting::bond::vesting_unbond_gateway,
             vesting::bond::vesting_unbond_mixnode,
             vesting::bond::vesting_update_mixnode_cost_params,
@@ -140,6 +142,7 @@ fn main() {
             simulate::mixnet::simulate_unbond_gateway,
             simulate::mixnet::simulate_bond_mixnode,
             simulate::mixnet::simulate_pledge_more,
+            simulate::mixnet::simulate_decrease_pledge,
             simulate::mixnet::simulate_unbond_mixnode,
             simulate::mixnet::simulate_update_mixnode_config,
             simulate::mixnet::simulate_update_mixnode_cost_params,
@@ -151,6 +154,7 @@ fn main() {
             simulate::vesting::simulate_vesting_unbond_gateway,
             simulate::vesting::simulate_vesting_bond_mixnode,
             simulate::vesting::simulate_vesting_pledge_more,
+            simulate::vesting::simulate_vesting_decrease_pledge,
             simulate::vesting::simulate_vesting_unbond_mixnode,
             simulate::vesting::simulate_vesting_update_mixnode_config,
             simulate::vesting::simulate_vesting_update_mixnode_cost_params,
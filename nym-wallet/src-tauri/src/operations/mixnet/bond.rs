@@ -129,6 +129,33 @@ pub async fn pledge_more(
     )?)
 }
 
+#[tauri::command]
+pub async fn decrease_pledge(
+    fee: Option<Fee>,
+    decrease_by: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<TransactionExecuteResult, BackendError> {
+    let guard = state.read().await;
+    let decrease_by_base = guard.attempt_convert_to_base_coin(decrease_by.clone())?;
+    let fee_amount = guard.convert_tx_fee(fee.as_ref());
+    log::info!(
+        ">>> Decrease pledge, decrease_by_display = {}, decrease_by_base = {}, fee = {:?}",
+        decrease_by,
+        decrease_by_base,
+        fee,
+    );
+    let res = guard
+        .current_client()?
+        .nyxd
+        .decrease_pledge(decrease_by_base, fee)
+        .await?;
+    log::info!("<<< tx hash = {}", res.transaction_hash);
+    log::trace!("<<< {:?}", res);
+    Ok(TransactionExecuteResult::from_execute_result(
+        res, fee_amount,
+    )?)
+}
+
 #[tauri::command]
 pub async fn unbond_mixnode(
     fee: Option<Fee>,
@@ -92,6 +92,17 @@ pub async fn simulate_pledge_more(
     simulate_mixnet_operation(ExecuteMsg::PledgeMore {}, Some(additional_pledge), &state).await
 }
 
+#[tauri::command]
+pub async fn simulate_decrease_pledge(
+    decrease_by: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<FeeDetails, BackendError> {
+    let guard = state.read().await;
+    let amount = guard.attempt_convert_to_base_coin(decrease_by)?.into();
+
+    simulate_mixnet_operation(ExecuteMsg::DecreasePledge { amount }, None, &state).await
+}
+
 #[tauri::command]
 pub async fn simulate_unbond_mixnode(
     state: tauri::State<'_, WalletState>,
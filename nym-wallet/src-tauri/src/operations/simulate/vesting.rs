@@ -104,6 +104,17 @@ pub async fn simulate_vesting_pledge_more(
     simulate_vesting_operation(ExecuteMsg::PledgeMore { amount }, None, &state).await
 }
 
+#[tauri::command]
+pub async fn simulate_vesting_decrease_pledge(
+    decrease_by: DecCoin,
+    state: tauri::State<'_, WalletState>,
+) -> Result<FeeDetails, BackendError> {
+    let guard = state.read().await;
+    let amount = guard.attempt_convert_to_base_coin(decrease_by)?.into();
+
+    simulate_vesting_operation(ExecuteMsg::DecreasePledge { amount }, None, &state).await
+}
+
 #[tauri::command]
 pub async fn simulate_vesting_unbond_mixnode(
     state: tauri::State<'_, WalletState>,
@@ -35,3 +35,6 @@ export const unbond = async (type: EnumNodeType) => {
 
 export const bondMore = async (args: TBondMoreArgs) =>
   invokeWrapper<TransactionExecuteResult>('pledge_more', args);
+
+export const decreasePledge = async ({ fee, decreaseBy }: { fee?: Fee; decreaseBy: DecCoin }) =>
+  invokeWrapper<TransactionExecuteResult>('decrease_pledge', { fee, decreaseBy });
@@ -69,3 +69,9 @@ export const simulateBondMore = async (args: any) =>
 
 export const simulateVestingBondMore = async (args: any) =>
   invokeWrapper<FeeDetails>('simulate_vesting_pledge_more', args);
+
+export const simulateDecreasePledge = async (args: { decreaseBy: DecCoin }) =>
+  invokeWrapper<FeeDetails>('simulate_decrease_pledge', args);
+
+export const simulateVestingDecreasePledge = async (args: { decreaseBy: DecCoin }) =>
+  invokeWrapper<FeeDetails>('simulate_vesting_decrease_pledge', args);
@@ -116,3 +116,6 @@ export const vestingBondMore = async ({
   fee,
   additionalPledge,
 });
+
+export const vestingDecreasePledge = async ({ fee, decreaseBy }: { fee?: Fee; decreaseBy: DecCoin }) =>
+  invokeWrapper<TransactionExecuteResult>('vesting_decrease_pledge', { fee, decreaseBy });
@@ -0,0 +1,29 @@
+use nym_sdk::mixnet::{self, Recipient};
+
+// Sends a request to a service provider, such as the one from the `service_provider` example,
+// without revealing our address, and waits for its reply that is going to arrive via the
+// reply SURBs attached to the request.
+#[tokio::main]
+async fn main() {
+    logging::setup_logging();
+
+    let provider = std::env::args()
+        .nth(1)
+        .expect("usage: anonymous_request <PROVIDER_ADDRESS>");
+    let provider = Recipient::try_from_base58_string(provider).unwrap();
+
+    let mut client = mixnet::Client::connect().await.unwrap();
+
+    // A handful of reply SURBs is enough for a short reply. If the provider needs more of them,
+    // it will ask our client for them, which happens transparently
+    client.send_anonymous_str(provider, "hello there", 10).await;
+
+    println!("Waiting for reply");
+    if let Some(messages) = client.wait_for_messages().await {
+        for message in messages {
+            println!("Received: {}", String::from_utf8_lossy(&message.message));
+        }
+    }
+
+    client.disconnect().await;
+}
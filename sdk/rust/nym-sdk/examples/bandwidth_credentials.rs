@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use nym_sdk::mixnet;
+
+// Connects to a gateway that requires bandwidth credentials. The credentials are taken from the
+// database in the client directory, which can be filled in with the `credential` binary, i.e.
+// `credential run --client-home-directory /tmp/mixnet-client-credentials ...`
+#[tokio::main]
+async fn main() {
+    logging::setup_logging();
+
+    let config_dir = PathBuf::from("/tmp/mixnet-client-credentials");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let paths = mixnet::StoragePaths::new_from_dir(mixnet::KeyMode::Keep, &config_dir).unwrap();
+
+    let config = mixnet::Config {
+        enabled_credentials_mode: true,
+        // spend the next credential whenever the bandwidth runs out
+        automatic_bandwidth_top_up: true,
+        ..Default::default()
+    };
+
+    let client = mixnet::ClientBuilder::new(Some(config), Some(paths)).unwrap();
+    let mut client = client.connect_to_mixnet().await.unwrap();
+    println!("Remaining bandwidth: {}", client.remaining_bandwidth());
+
+    // Spend another credential right away, rather than waiting for the bandwidth to run out
+    match client.top_up_bandwidth().await {
+        Ok(remaining) => println!("Remaining bandwidth after the top up: {remaining}"),
+        Err(err) => println!("Could not top up the bandwidth: {err}"),
+    }
+
+    client.disconnect().await;
+}
@@ -0,0 +1,36 @@
+use nym_sdk::mixnet;
+
+// Runs a client against a hand-written topology, e.g. of a local testnet, instead of querying
+// the nym-api.
+#[tokio::main]
+async fn main() {
+    logging::setup_logging();
+
+    let Some(topology_path) = std::env::args().nth(1) else {
+        eprintln!("Usage: custom_topology <topology.json|topology.toml>");
+        return;
+    };
+
+    let topology_provider = mixnet::StaticTopologyProvider::from_file(topology_path).unwrap();
+
+    let mut client_builder = mixnet::ClientBuilder::new(None, None).unwrap();
+    client_builder.set_topology_provider(Box::new(topology_provider));
+    let mut client = client_builder.connect_to_mixnet().await.unwrap();
+
+    let our_address = client.nym_address();
+    println!("Our client nym address is: {our_address}");
+
+    // Send a message through the mixnet to ourselves
+    client
+        .send_str(&our_address.to_string(), "hello there")
+        .await;
+
+    println!("Waiting for message");
+    if let Some(received) = client.wait_for_messages().await {
+        for r in received {
+            println!("Received: {}", String::from_utf8_lossy(&r.message));
+        }
+    }
+
+    client.disconnect().await;
+}
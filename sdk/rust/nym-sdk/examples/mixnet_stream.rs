@@ -0,0 +1,41 @@
+use nym_sdk::mixnet;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+// Opens a stream between two clients and sends some data back and forth, just as it would be done
+// with a TCP connection.
+#[tokio::main]
+async fn main() {
+    logging::setup_logging();
+
+    let mut server = mixnet::Client::connect()
+        .await
+        .unwrap()
+        .into_stream_listener();
+    let client = mixnet::Client::connect()
+        .await
+        .unwrap()
+        .into_stream_listener();
+    let server_address = server.nym_address();
+
+    // Echo back everything received on each accepted stream
+    tokio::spawn(async move {
+        while let Some(stream) = server.accept().await {
+            println!("Accepted stream from {}", stream.peer_address());
+            tokio::spawn(async move {
+                let (mut reader, mut writer) = tokio::io::split(stream);
+                tokio::io::copy(&mut reader, &mut writer).await.unwrap();
+                writer.shutdown().await.unwrap();
+            });
+        }
+    });
+
+    let mut stream = client.connect(server_address).await;
+    stream.write_all(b"hello there").await.unwrap();
+
+    // Let the server know we're not going to send anything more
+    stream.shutdown().await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    println!("Received: {response}");
+}
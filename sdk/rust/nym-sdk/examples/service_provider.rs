@@ -0,0 +1,31 @@
+use nym_sdk::mixnet;
+
+// A minimal service provider built only on the sdk: it answers every anonymous request
+// with the uppercased request content, without ever learning the address of its clients.
+//
+// Run it, then use the printed address with the `anonymous_request` example.
+#[tokio::main]
+async fn main() {
+    logging::setup_logging();
+
+    let mut client = mixnet::Client::connect().await.unwrap();
+    println!("Service provider nym address is: {}", client.nym_address());
+
+    println!("Waiting for requests");
+    while let Some(messages) = client.wait_for_messages().await {
+        for message in messages {
+            let request = String::from_utf8_lossy(&message.message).to_string();
+
+            // Only the messages sent with reply SURBs come with a sender tag we can reply to
+            let Some(sender_tag) = message.sender_tag else {
+                println!("Received a request without any reply SURBs, ignoring it: {request}");
+                continue;
+            };
+
+            println!("Received request from {sender_tag}: {request}");
+            client
+                .send_reply_str(sender_tag, &request.to_uppercase())
+                .await;
+        }
+    }
+}
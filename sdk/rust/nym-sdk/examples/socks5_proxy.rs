@@ -0,0 +1,29 @@
+use nym_sdk::{mixnet, socks5};
+
+#[tokio::main]
+async fn main() {
+    logging::setup_logging();
+
+    let Some(service_provider) = std::env::args().nth(1) else {
+        eprintln!("Usage: socks5_proxy <network requester address>");
+        return;
+    };
+
+    let config = socks5::Socks5Config::new(service_provider.parse().unwrap());
+    let client_builder = mixnet::ClientBuilder::new(None, None).unwrap();
+    let mut proxy = socks5::Socks5Proxy::start(client_builder, config)
+        .await
+        .unwrap();
+
+    println!("Our client nym address is: {}", proxy.nym_address());
+    println!("Socks5 proxy listening on {}", proxy.listening_address());
+
+    // Run until either the proxy fails or we get interrupted
+    tokio::select! {
+        err = proxy.wait_for_error() => println!("Proxy stopped: {err:?}"),
+        _ = tokio::signal::ctrl_c() => println!("Received interrupt"),
+    }
+
+    println!("Stopping the proxy");
+    proxy.stop().await;
+}
@@ -14,12 +14,21 @@ pub enum Error {
     #[error(transparent)]
     ClientCoreError(#[from] client_core::error::ClientCoreError),
 
+    #[error("credential storage error: {0}")]
+    CredentialStorageError(#[from] credential_storage::error::StorageError),
+
     #[error("key file encountered that we don't want to overwrite: {0}")]
     DontOverwrite(PathBuf),
     #[error("shared gateway key file encountered that we don't want to overwrite: {0}")]
     DontOverwriteGatewayKey(PathBuf),
     #[error("no gateway config available for writing")]
     GatewayNotAvailableForWriting,
+    #[error("no registered gateway available to switch away from")]
+    NoGatewayToSwitchFrom,
+    #[error("credentials mode is enabled, but no credential storage path has been provided")]
+    NoCredentialStoragePath,
+    #[error("credentials mode is enabled, but no nyxd endpoint has been provided")]
+    NoNyxdEndpoint,
 
     #[error("expected to received a directory, received: {0}")]
     ExpectedDirectory(PathBuf),
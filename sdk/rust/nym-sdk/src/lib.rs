@@ -1,2 +1,4 @@
 pub mod error;
 pub mod mixnet;
+#[cfg(feature = "socks5")]
+pub mod socks5;
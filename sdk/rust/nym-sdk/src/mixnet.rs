@@ -3,15 +3,27 @@ mod config;
 mod connection_state;
 mod keys;
 mod paths;
+mod stream;
 
+pub use client_connections::TransmissionLane;
+pub use client_core::client::replies::reply_storage::{
+    fs_backend, CombinedReplyStorage, Empty as EmptyReplyStorage, ReplyStorageBackend,
+};
+pub use client_core::client::topology_control::{
+    NymApiTopologyProvider, StaticTopologyDescription, StaticTopologyError, StaticTopologyProvider,
+    TopologyProvider,
+};
 pub use client_core::config::GatewayEndpointConfig;
 pub use nymsphinx::{
     addressing::clients::{ClientIdentity, Recipient},
+    anonymous_replies::requests::AnonymousSenderTag,
     receiver::ReconstructedMessage,
 };
+pub use topology::{route_selection::RouteSelectionPolicy, NymTopology};
 
 pub use keys::{Keys, KeysArc};
 pub use paths::{GatewayKeyMode, KeyMode, StoragePaths};
 
 pub use client::{Client, ClientBuilder};
 pub use config::Config;
+pub use stream::{MixnetListener, MixnetStream};
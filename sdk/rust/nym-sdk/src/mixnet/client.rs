@@ -1,28 +1,43 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use client_connections::TransmissionLane;
 use client_core::{
     client::{
         base_client::{
-            non_wasm_helpers, BaseClientBuilder, ClientInput, ClientOutput, ClientState,
-            CredentialsToggle,
+            non_wasm_helpers, BaseClient, BaseClientBuilder, ClientInput, ClientOutput,
+            ClientState, CredentialsToggle,
         },
         inbound_messages::InputMessage,
         key_manager::KeyManager,
         received_buffer::ReconstructedMessagesReceiver,
+        replies::reply_storage::{fs_backend, ReplyStorageBackend},
+        topology_control::TopologyProvider,
+    },
+    config::{
+        persistence::key_pathfinder::ClientKeyPathfinder, DebugConfig, GatewayEndpointConfig,
     },
-    config::{persistence::key_pathfinder::ClientKeyPathfinder, GatewayEndpointConfig},
+    error::ClientCoreError,
 };
+use credential_storage::PersistentStorage;
 use crypto::asymmetric::identity;
+use gateway_client::bandwidth::BandwidthController;
 use nymsphinx::{
     addressing::clients::{ClientIdentity, Recipient},
+    anonymous_replies::requests::AnonymousSenderTag,
     receiver::ReconstructedMessage,
 };
 use task::TaskManager;
+use topology::{route_selection::RouteSelectionPolicy, NymTopologyError};
 
 use futures::StreamExt;
 
-use super::{connection_state::BuilderState, Config, GatewayKeyMode, Keys, KeysArc, StoragePaths};
+use super::{
+    connection_state::BuilderState, Config, GatewayKeyMode, Keys, KeysArc, MixnetListener,
+    StoragePaths,
+};
 use crate::error::{Error, Result};
 
 pub struct ClientBuilder {
@@ -38,6 +53,18 @@ pub struct ClientBuilder {
     /// The client can be in one of multiple states, depending on how it is created and if it's
     /// connected to the mixnet.
     state: BuilderState,
+
+    /// Gateways the client fails over to, in order, if its primary gateway becomes unavailable.
+    standby_gateways: Vec<GatewayEndpointConfig>,
+
+    /// Database holding the bandwidth credentials, used if the credentials mode is enabled.
+    credential_storage_path: Option<PathBuf>,
+
+    /// Source of the network topology, if it's not to be retrieved from the nym-api.
+    custom_topology_provider: Option<Box<dyn TopologyProvider>>,
+
+    /// Rules for choosing the mixnodes the packets are routed through.
+    route_selection_policy: RouteSelectionPolicy,
 }
 
 impl ClientBuilder {
@@ -81,11 +108,19 @@ impl ClientBuilder {
             client_core::init::new_client_keys()
         };
 
+        let credential_storage_path = paths
+            .as_ref()
+            .map(|paths| paths.credential_database_path.clone());
+
         Ok(Self {
             key_manager,
             config,
             storage_paths: paths,
             state: BuilderState::New,
+            standby_gateways: Vec::new(),
+            credential_storage_path,
+            custom_topology_provider: None,
+            route_selection_policy: Default::default(),
         })
     }
 
@@ -120,6 +155,47 @@ impl ClientBuilder {
         self.state.gateway_endpoint_config()
     }
 
+    /// Set the gateways the client is going to fail over to, in the provided order, if the
+    /// primary gateway becomes unreachable or disappears from the network topology.
+    /// When that happens, the gateway part of the client's nym address changes.
+    pub fn set_standby_gateway_endpoints(&mut self, standby_gateways: Vec<GatewayEndpointConfig>) {
+        self.standby_gateways = standby_gateways;
+    }
+
+    pub fn get_standby_gateway_endpoints(&self) -> &[GatewayEndpointConfig] {
+        &self.standby_gateways
+    }
+
+    /// Set the path to the database holding the bandwidth credentials, overriding the one from the
+    /// storage paths. It's only used if the credentials mode is enabled in the config.
+    ///
+    /// The credentials can be acquired with the `credential` binary pointed at the same database.
+    pub fn set_credential_storage_path(&mut self, credential_storage_path: PathBuf) {
+        self.credential_storage_path = Some(credential_storage_path);
+    }
+
+    pub fn get_credential_storage_path(&self) -> Option<&Path> {
+        self.credential_storage_path.as_deref()
+    }
+
+    /// Use the provided source of the network topology instead of the nym-api endpoints from the
+    /// config, for example a [`StaticTopologyProvider`](super::StaticTopologyProvider) with a
+    /// hand-written topology of a local testnet. If the client has to register with a gateway,
+    /// it's chosen from this topology as well.
+    pub fn set_topology_provider(&mut self, topology_provider: Box<dyn TopologyProvider>) {
+        self.custom_topology_provider = Some(topology_provider);
+    }
+
+    /// Set the rules for choosing the mixnodes the packets are routed through, such as avoiding
+    /// multiple nodes of the same family in a single route or excluding particular nodes.
+    pub fn set_route_selection_policy(&mut self, route_selection_policy: RouteSelectionPolicy) {
+        self.route_selection_policy = route_selection_policy;
+    }
+
+    pub fn get_route_selection_policy(&self) -> &RouteSelectionPolicy {
+        &self.route_selection_policy
+    }
+
     pub async fn register_with_gateway(&mut self) -> Result<()> {
         assert!(
             matches!(self.state, BuilderState::New),
@@ -133,15 +209,83 @@ impl ClientBuilder {
             .map(identity::PublicKey::from_base58_string)
             .transpose()?;
 
-        let gateway_config = client_core::init::register_with_gateway(
-            &mut self.key_manager,
+        let gateway_config = if let Some(topology_provider) = &mut self.custom_topology_provider {
+            // with a custom topology there might not be any nym-api to query the gateways from
+            let topology = topology_provider.get_new_topology().await.ok_or(
+                ClientCoreError::InsufficientNetworkTopology(
+                    NymTopologyError::EmptyNetworkTopology,
+                ),
+            )?;
+            client_core::init::register_with_gateway_from_topology(
+                &mut self.key_manager,
+                &topology,
+                user_chosen_gateway,
+            )
+            .await?
+        } else {
+            client_core::init::register_with_gateway(
+                &mut self.key_manager,
+                self.config.nym_api_endpoints.clone(),
+                user_chosen_gateway,
+            )
+            .await?
+        };
+
+        self.state = BuilderState::Registered {
+            gateway_endpoint_config: gateway_config,
+        };
+        Ok(())
+    }
+
+    /// Move an already registered client to a different gateway while keeping its identity,
+    /// encryption and ack keys. If no gateway is specified, a random one, other than the current
+    /// one, is chosen.
+    ///
+    /// A fresh handshake is performed with the new gateway and, if the client uses on-disk
+    /// storage, the gateway shared key and the gateway endpoint config are replaced together.
+    /// On any failure the client keeps using its previous gateway.
+    pub async fn switch_gateway(
+        &mut self,
+        new_gateway_id: Option<identity::PublicKey>,
+    ) -> Result<()> {
+        // If we haven't loaded the gateway we're switching away from, do it now
+        if matches!(self.state, BuilderState::New) && self.has_gateway_key() {
+            if let Some(paths) = &self.storage_paths {
+                let gateway_endpoint_config_path = paths.gateway_endpoint_config.clone();
+                self.read_gateway_endpoint_config(&gateway_endpoint_config_path)?;
+            }
+        }
+
+        let current_gateway_id = self
+            .get_gateway_endpoint()
+            .ok_or(Error::NoGatewayToSwitchFrom)?
+            .gateway_id
+            .clone();
+
+        let (gateway_endpoint_config, shared_key) = client_core::init::register_with_new_gateway(
+            &self.key_manager,
             self.config.nym_api_endpoints.clone(),
-            user_chosen_gateway,
+            &current_gateway_id,
+            new_gateway_id,
         )
         .await?;
 
+        if let Some(paths) = &self.storage_paths {
+            let serialized_config = toml::to_string(&gateway_endpoint_config)?;
+            if let Some(parent_dir) = paths.gateway_endpoint_config.parent() {
+                std::fs::create_dir_all(parent_dir)?;
+            }
+            client_core::init::persist_gateway_switch(
+                &shared_key,
+                &paths.gateway_shared_key,
+                &paths.gateway_endpoint_config,
+                |path| std::fs::write(path, serialized_config),
+            )?;
+        }
+
+        self.key_manager.insert_gateway_shared_key(shared_key);
         self.state = BuilderState::Registered {
-            gateway_endpoint_config: gateway_config,
+            gateway_endpoint_config,
         };
         Ok(())
     }
@@ -182,8 +326,106 @@ impl ClientBuilder {
         Ok(())
     }
 
-    /// Connects to the mixnet via the gateway in the client config
-    pub async fn connect_to_mixnet(mut self) -> Result<Client> {
+    async fn create_bandwidth_controller(&self) -> Result<BandwidthController> {
+        let credential_storage_path = self
+            .credential_storage_path
+            .as_ref()
+            .ok_or(Error::NoCredentialStoragePath)?;
+
+        // Ensure the whole directory structure exists
+        if let Some(parent_dir) = credential_storage_path.parent() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let storage = PersistentStorage::init(credential_storage_path).await?;
+
+        #[cfg(feature = "coconut")]
+        let bandwidth_controller = {
+            let details = network_defaults::NymNetworkDetails::new_from_env();
+            let nyxd_url = self
+                .config
+                .nyxd_endpoints
+                .first()
+                .cloned()
+                .ok_or(Error::NoNyxdEndpoint)?;
+            let api_url = self
+                .config
+                .nym_api_endpoints
+                .first()
+                .cloned()
+                .ok_or(ClientCoreError::ListOfNymApisIsEmpty)?;
+
+            // overwrite env configuration with config URLs
+            let client_config = validator_client::Config::try_from_nym_network_details(&details)
+                .map_err(ClientCoreError::from)?
+                .with_urls(nyxd_url, api_url);
+            let client = validator_client::Client::new_query(client_config)
+                .map_err(ClientCoreError::from)?;
+            let coconut_api_clients =
+                validator_client::CoconutApiClient::all_coconut_api_clients(&client)
+                    .await
+                    .map_err(ClientCoreError::from)?;
+            BandwidthController::new(storage, coconut_api_clients)
+        };
+        #[cfg(not(feature = "coconut"))]
+        let bandwidth_controller =
+            BandwidthController::new(storage).map_err(ClientCoreError::from)?;
+
+        Ok(bandwidth_controller)
+    }
+
+    /// Connects to the mixnet via the gateway in the client config.
+    ///
+    /// If storage paths were provided, the reply SURBs are persisted in the sqlite database at
+    /// `reply_surb_database_path`, so that they survive restarts. Otherwise they're only kept
+    /// in memory.
+    pub async fn connect_to_mixnet(self) -> Result<Client> {
+        let key_manager = self.key_manager.clone();
+        let started_client = self.start_base_client().await?;
+        Client::from_base_client(key_manager, started_client)
+    }
+
+    /// Connects to the mixnet via the gateway in the client config, using the provided backend
+    /// for persisting the reply SURBs, the reply keys and the sender tags.
+    pub async fn connect_to_mixnet_with_reply_storage<B>(
+        self,
+        reply_storage_backend: B,
+    ) -> Result<Client>
+    where
+        B: ReplyStorageBackend + Send + Sync + 'static,
+        <B as ReplyStorageBackend>::StorageError: Sync + Send,
+    {
+        let key_manager = self.key_manager.clone();
+        let started_client = self
+            .start_base_client_with_reply_storage(reply_storage_backend)
+            .await?;
+        Client::from_base_client(key_manager, started_client)
+    }
+
+    /// Starts the base client, without registering anything for receiving the reconstructed
+    /// messages. The reply storage backend is picked the same way as in
+    /// [`connect_to_mixnet`](Self::connect_to_mixnet).
+    pub(crate) async fn start_base_client(self) -> Result<BaseClient> {
+        if let Some(paths) = &self.storage_paths {
+            let reply_storage_backend =
+                setup_persistent_reply_storage(paths, &self.config.debug_config).await?;
+            self.start_base_client_with_reply_storage(reply_storage_backend)
+                .await
+        } else {
+            let reply_storage_backend =
+                non_wasm_helpers::setup_empty_reply_surb_backend(&self.config.debug_config);
+            self.start_base_client_with_reply_storage(reply_storage_backend)
+                .await
+        }
+    }
+
+    async fn start_base_client_with_reply_storage<B>(
+        mut self,
+        reply_storage_backend: B,
+    ) -> Result<BaseClient>
+    where
+        B: ReplyStorageBackend + Send + Sync + 'static,
+        <B as ReplyStorageBackend>::StorageError: Sync + Send,
+    {
         // For some simple cases we can figure how to setup gateway without it having to have been
         // called in advance.
         if matches!(self.state, BuilderState::New) {
@@ -210,53 +452,59 @@ impl ClientBuilder {
 
         // At this point we should be in a registered state, either at function entry or by the
         // above convenience logic.
-        let BuilderState::Registered { gateway_endpoint_config } = self.state else {
+        let BuilderState::Registered {
+            gateway_endpoint_config,
+        } = self.state
+        else {
             todo!();
         };
 
-        let nym_address =
-            client_core::init::get_client_address(&self.key_manager, &gateway_endpoint_config);
-
-        // TODO: we currently don't support having a bandwidth controller
-        let bandwidth_controller = None;
-
-        // TODO: currently we only support in-memory reply surb storage.
-        let reply_storage_backend =
-            non_wasm_helpers::setup_empty_reply_surb_backend(&self.config.debug_config);
+        let bandwidth_controller = if self.config.enabled_credentials_mode {
+            Some(self.create_bandwidth_controller().await?)
+        } else {
+            None
+        };
 
-        let base_builder = BaseClientBuilder::new(
+        let mut base_builder = BaseClientBuilder::new(
             &gateway_endpoint_config,
             &self.config.debug_config,
             self.key_manager.clone(),
             bandwidth_controller,
             reply_storage_backend,
-            CredentialsToggle::Disabled,
+            CredentialsToggle::from(self.config.enabled_credentials_mode),
             self.config.nym_api_endpoints.clone(),
-        );
+        )
+        .with_standby_gateways(self.standby_gateways)
+        .with_route_selection_policy(self.route_selection_policy)
+        .with_automatic_bandwidth_top_up(self.config.automatic_bandwidth_top_up);
 
-        let mut started_client = base_builder.start_base().await.unwrap();
-        let client_input = started_client.client_input.register_producer();
-        let mut client_output = started_client.client_output.register_consumer();
-        let client_state = started_client.client_state;
+        if let Some(topology_provider) = self.custom_topology_provider {
+            base_builder = base_builder.with_topology_provider(topology_provider);
+        }
+        if let Some(paths) = self.storage_paths {
+            base_builder = base_builder.with_key_pathfinder(ClientKeyPathfinder::from(paths));
+        }
 
-        // Register our receiver
-        let reconstructed_receiver = client_output.register_receiver().unwrap();
+        Ok(base_builder.start_base().await?)
+    }
+}
 
-        Ok(Client {
-            nym_address,
-            key_manager: self.key_manager,
-            client_input,
-            client_output,
-            client_state,
-            reconstructed_receiver,
-            task_manager: started_client.task_manager,
-        })
+// Sets up the sqlite reply storage at the location specified by the storage paths, creating
+// any missing directories along the way. An existing database is loaded rather than replaced.
+async fn setup_persistent_reply_storage(
+    paths: &StoragePaths,
+    debug_config: &DebugConfig,
+) -> Result<fs_backend::Backend> {
+    let db_path = &paths.reply_surb_database_path;
+
+    // Ensure the whole directory structure exists
+    if let Some(parent_dir) = db_path.parent() {
+        std::fs::create_dir_all(parent_dir)?;
     }
+    Ok(non_wasm_helpers::setup_fs_reply_surb_backend(db_path, debug_config).await?)
 }
 
 pub struct Client {
-    nym_address: Recipient,
-
     /// Keys handled by the client
     key_manager: KeyManager,
 
@@ -265,7 +513,6 @@ pub struct Client {
     #[allow(dead_code)]
     client_output: ClientOutput,
 
-    #[allow(dead_code)]
     client_state: ClientState,
 
     reconstructed_receiver: ReconstructedMessagesReceiver,
@@ -274,6 +521,24 @@ pub struct Client {
 }
 
 impl Client {
+    fn from_base_client(key_manager: KeyManager, mut started_client: BaseClient) -> Result<Self> {
+        let client_input = started_client.client_input.register_producer();
+        let mut client_output = started_client.client_output.register_consumer();
+        let client_state = started_client.client_state;
+
+        // Register our receiver
+        let reconstructed_receiver = client_output.register_receiver()?;
+
+        Ok(Client {
+            key_manager,
+            client_input,
+            client_output,
+            client_state,
+            reconstructed_receiver,
+            task_manager: started_client.task_manager,
+        })
+    }
+
     pub async fn connect() -> Result<Self> {
         let client = ClientBuilder::new(None, None)?;
         client.connect_to_mixnet().await
@@ -286,8 +551,28 @@ impl Client {
 
     /// Get the nym address for this client, if it is available. The nym address is composed of the
     /// client identity, the client encryption key, and the gateway identity.
-    pub fn nym_address(&self) -> &Recipient {
-        &self.nym_address
+    ///
+    /// Note that the address changes if the client fails over to one of its standby gateways.
+    pub fn nym_address(&self) -> Recipient {
+        self.client_state.self_address.get()
+    }
+
+    /// Get the amount of bandwidth, in bytes, that is still available to us at our gateway.
+    /// It's only kept up to date as we send packets if the automatic bandwidth top up is enabled
+    /// in the config, in which case more is claimed as soon as it's used up, as long as there are
+    /// unused credentials in the credential storage.
+    pub fn remaining_bandwidth(&self) -> i64 {
+        self.client_state.remaining_bandwidth.get()
+    }
+
+    /// Top up the bandwidth available at our gateway by spending the next unused credential from
+    /// the credential storage. Returns the total amount of bandwidth available afterwards.
+    pub async fn top_up_bandwidth(&self) -> Result<i64> {
+        Ok(self
+            .client_state
+            .bandwidth_request_sender
+            .claim_bandwidth()
+            .await?)
     }
 
     /// Sends stringy data to the supplied Nym address
@@ -301,9 +586,101 @@ impl Client {
     pub async fn send_bytes(&self, address: &str, message: Vec<u8>) {
         log::debug!("send_bytes");
 
-        let lane = TransmissionLane::General;
         let recipient = Recipient::try_from_base58_string(address).unwrap();
-        let input_msg = InputMessage::new_regular(recipient, message, lane);
+        self.send_bytes_to(recipient, message).await;
+    }
+
+    /// Sends stringy data to the supplied recipient
+    pub async fn send_str_to(&self, recipient: Recipient, message: &str) {
+        log::debug!("send_str_to");
+        let message_bytes = message.to_string().into_bytes();
+        self.send_bytes_to(recipient, message_bytes).await;
+    }
+
+    /// Sends bytes to the supplied recipient
+    pub async fn send_bytes_to(&self, recipient: Recipient, message: Vec<u8>) {
+        log::debug!("send_bytes_to");
+        self.send_bytes_on_lane(recipient, message, TransmissionLane::General)
+            .await;
+    }
+
+    /// Sends bytes to the supplied Nym address using the specified transmission lane.
+    /// Our own address is attached to the message, so the recipient can respond directly.
+    pub async fn send_bytes_on_lane(
+        &self,
+        recipient: Recipient,
+        message: Vec<u8>,
+        lane: TransmissionLane,
+    ) {
+        log::debug!("send_bytes_on_lane");
+
+        self.send(InputMessage::new_regular(recipient, message, lane))
+            .await;
+    }
+
+    /// Sends stringy data to the supplied Nym address without revealing our own address.
+    /// See [`Client::send_anonymous_bytes`] for details.
+    pub async fn send_anonymous_str(&self, recipient: Recipient, message: &str, reply_surbs: u32) {
+        log::debug!("send_anonymous_str");
+        let message_bytes = message.to_string().into_bytes();
+        self.send_anonymous_bytes(
+            recipient,
+            message_bytes,
+            reply_surbs,
+            TransmissionLane::General,
+        )
+        .await;
+    }
+
+    /// Sends bytes to the supplied Nym address without revealing our own address.
+    ///
+    /// Instead, the specified number of reply SURBs is attached alongside the sender tag of this
+    /// client, so the recipient can respond with [`Client::send_reply_bytes`]. If the recipient
+    /// runs out of SURBs, it will ask us for more.
+    pub async fn send_anonymous_bytes(
+        &self,
+        recipient: Recipient,
+        message: Vec<u8>,
+        reply_surbs: u32,
+        lane: TransmissionLane,
+    ) {
+        log::debug!("send_anonymous_bytes");
+
+        self.send(InputMessage::new_anonymous(
+            recipient,
+            message,
+            reply_surbs,
+            lane,
+        ))
+        .await;
+    }
+
+    /// Replies with stringy data to the anonymous sender of a previously received message.
+    /// See [`Client::send_reply_bytes`] for details.
+    pub async fn send_reply_str(&self, recipient_tag: AnonymousSenderTag, message: &str) {
+        log::debug!("send_reply_str");
+        let message_bytes = message.to_string().into_bytes();
+        self.send_reply_bytes(recipient_tag, message_bytes, TransmissionLane::General)
+            .await;
+    }
+
+    /// Replies with bytes to the anonymous sender of a previously received message, identified
+    /// by the `sender_tag` of that [`ReconstructedMessage`], using the reply SURBs it had sent us.
+    ///
+    /// If we don't have enough reply SURBs, the reply is queued until more are received.
+    pub async fn send_reply_bytes(
+        &self,
+        recipient_tag: AnonymousSenderTag,
+        message: Vec<u8>,
+        lane: TransmissionLane,
+    ) {
+        log::debug!("send_reply_bytes");
+
+        self.send(InputMessage::new_reply(recipient_tag, message, lane))
+            .await;
+    }
+
+    async fn send(&self, input_msg: InputMessage) {
         self.client_input
             .input_sender
             .send(input_msg)
@@ -331,6 +708,19 @@ impl Client {
         }
     }
 
+    /// Turn the client into a [`MixnetListener`], which multiplexes
+    /// [`MixnetStream`](super::MixnetStream)s over it. From then on, all the messages received
+    /// by the client are expected to belong to the streams.
+    pub fn into_stream_listener(self) -> MixnetListener {
+        MixnetListener::new(
+            self.client_input,
+            self.client_output,
+            self.client_state,
+            self.reconstructed_receiver,
+            self.task_manager,
+        )
+    }
+
     /// Disconnect from the mixnet. Currently it is not supported to reconnect a disconnected
     /// client.
     pub async fn disconnect(&mut self) {
@@ -338,3 +728,52 @@ impl Client {
         self.task_manager.wait_for_shutdown().await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mixnet::KeyMode;
+
+    fn storage_paths_with_reply_database(dir: &Path, db_path: PathBuf) -> StoragePaths {
+        let mut paths = StoragePaths::new_from_dir(KeyMode::Keep, dir).unwrap();
+        paths.reply_surb_database_path = db_path;
+        paths
+    }
+
+    #[tokio::test]
+    async fn persistent_reply_storage_is_created_alongside_missing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("nested").join("replies.sqlite");
+        let paths = storage_paths_with_reply_database(dir.path(), db_path.clone());
+
+        let backend = setup_persistent_reply_storage(&paths, &DebugConfig::default())
+            .await
+            .unwrap();
+        assert!(db_path.exists());
+        assert!(backend.load_surb_storage().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn existing_persistent_reply_storage_is_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("replies.sqlite");
+        let paths = storage_paths_with_reply_database(dir.path(), db_path);
+
+        let backend = setup_persistent_reply_storage(&paths, &DebugConfig::default())
+            .await
+            .unwrap();
+        drop(backend);
+
+        let backend = setup_persistent_reply_storage(&paths, &DebugConfig::default())
+            .await
+            .unwrap();
+        assert!(backend.load_surb_storage().await.is_ok());
+
+        // the existing database has been loaded rather than archived and replaced
+        let archived = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name().to_string_lossy().contains("corrupted"));
+        assert!(!archived);
+    }
+}
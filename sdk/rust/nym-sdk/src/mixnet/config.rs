@@ -9,6 +9,19 @@ pub struct Config {
     /// List of nym-api endpoints
     pub nym_api_endpoints: Vec<Url>,
 
+    /// List of nyxd endpoints, used for finding the coconut signers when verifying bandwidth
+    /// credentials.
+    pub nyxd_endpoints: Vec<Url>,
+
+    /// If enabled, the client pays for the gateway bandwidth with the credentials from its
+    /// credential storage, rather than relying on the gateway running in disabled credentials mode.
+    pub enabled_credentials_mode: bool,
+
+    /// If enabled, the client keeps track of the bandwidth it uses at the gateway and claims more
+    /// as soon as it runs out, spending the next credential from its credential storage in the
+    /// credentials mode. Otherwise, more bandwidth is only claimed with `Client::top_up_bandwidth`.
+    pub automatic_bandwidth_top_up: bool,
+
     /// Flags controlling all sorts of internal client behaviour.
     /// Changing these risk compromising network anonymity!
     pub debug_config: DebugConfig,
@@ -17,9 +30,13 @@ pub struct Config {
 impl Default for Config {
     fn default() -> Self {
         let nym_api_endpoints = vec![mainnet::NYM_API.to_string().parse().unwrap()];
+        let nyxd_endpoints = vec![mainnet::NYXD_URL.to_string().parse().unwrap()];
         Self {
             user_chosen_gateway: Default::default(),
             nym_api_endpoints,
+            nyxd_endpoints,
+            enabled_credentials_mode: false,
+            automatic_bandwidth_top_up: false,
             debug_config: Default::default(),
         }
     }
@@ -30,7 +47,7 @@ impl Config {
         Self {
             user_chosen_gateway,
             nym_api_endpoints,
-            debug_config: DebugConfig::default(),
+            ..Default::default()
         }
     }
 }
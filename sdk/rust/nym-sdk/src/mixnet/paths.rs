@@ -61,7 +61,7 @@ pub struct StoragePaths {
 
 impl StoragePaths {
     pub fn new_from_dir(operating_mode: KeyMode, dir: &Path) -> Result<Self> {
-        if !dir.is_file() {
+        if !dir.is_dir() {
             return Err(Error::ExpectedDirectory(dir.to_owned()));
         }
 
@@ -94,3 +94,34 @@ impl From<StoragePaths> for ClientKeyPathfinder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_paths_are_created_inside_existing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = StoragePaths::new_from_dir(KeyMode::Keep, dir.path()).unwrap();
+        assert_eq!(
+            paths.reply_surb_database_path,
+            dir.path().join("persistent_reply_store.sqlite")
+        );
+    }
+
+    #[test]
+    fn storage_paths_require_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"not a directory").unwrap();
+
+        assert!(matches!(
+            StoragePaths::new_from_dir(KeyMode::Keep, &file),
+            Err(Error::ExpectedDirectory(_))
+        ));
+        assert!(matches!(
+            StoragePaths::new_from_dir(KeyMode::Keep, &dir.path().join("missing")),
+            Err(Error::ExpectedDirectory(_))
+        ));
+    }
+}
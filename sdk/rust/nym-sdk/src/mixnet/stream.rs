@@ -0,0 +1,230 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use client_connections::{ConnectionId, LaneQueueLengths};
+use client_core::client::{
+    base_client::{ClientInput, ClientOutput, ClientState},
+    inbound_messages::{InputMessage, InputMessageSender},
+    received_buffer::{
+        ReceivedBufferMessage, ReceivedBufferRequestSender, ReconstructedMessagesReceiver,
+    },
+    self_address::SelfAddress,
+};
+use futures::{channel::mpsc, StreamExt};
+use nymsphinx::addressing::clients::Recipient;
+use task::TaskManager;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+
+use self::{
+    controller::{ControllerCommand, ControllerSender, StreamController},
+    message::StreamMessage,
+    runner::StreamDataSender,
+};
+
+mod controller;
+mod message;
+mod runner;
+
+// How much of the written data is buffered in each direction before the writes have to wait
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Everything the streams need for sending their data into the mixnet.
+#[derive(Clone)]
+pub(crate) struct StreamContext {
+    input_sender: InputMessageSender,
+    lane_queue_lengths: LaneQueueLengths,
+    controller_sender: ControllerSender,
+}
+
+/// A reliable, ordered, bidirectional byte stream between two mixnet clients.
+///
+/// The data is sent on its own transmission lane, so multiple streams can be used over the same
+/// client at once. Writes are slowed down when the client can't keep up with sending the
+/// data of the stream into the mixnet.
+///
+/// Shutting down the write half informs the other end that no more data is going to be sent,
+/// which it observes as EOF once it has read everything written before. The stream is closed
+/// once both ends are done writing or it's dropped.
+pub struct MixnetStream {
+    connection_id: ConnectionId,
+    peer: Recipient,
+    inner: DuplexStream,
+}
+
+impl MixnetStream {
+    fn start(
+        connection_id: ConnectionId,
+        peer: Recipient,
+        context: StreamContext,
+    ) -> (Self, StreamDataSender) {
+        let (inner, runner_end) = tokio::io::duplex(STREAM_BUFFER_SIZE);
+        let (data_sender, data_receiver) = mpsc::unbounded();
+
+        tokio::spawn(runner::run_stream(
+            connection_id,
+            peer,
+            runner_end,
+            data_receiver,
+            context,
+        ));
+
+        let stream = MixnetStream {
+            connection_id,
+            peer,
+            inner,
+        };
+        (stream, data_sender)
+    }
+
+    /// Get the id of the connection this stream is multiplexed on.
+    pub fn connection_id(&self) -> ConnectionId {
+        self.connection_id
+    }
+
+    /// Get the nym address of the other end of this stream.
+    pub fn peer_address(&self) -> Recipient {
+        self.peer
+    }
+}
+
+impl AsyncRead for MixnetStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MixnetStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Multiplexes [`MixnetStream`]s over a single mixnet client: accepts the streams opened by other
+/// clients and opens new ones.
+///
+/// Once a client is turned into a listener, all the messages it receives are expected to belong
+/// to the streams, anything else is discarded.
+pub struct MixnetListener {
+    self_address: SelfAddress,
+    context: StreamContext,
+    incoming_streams: mpsc::UnboundedReceiver<MixnetStream>,
+    buffer_requester: ReceivedBufferRequestSender,
+    task_manager: TaskManager,
+}
+
+impl Drop for MixnetListener {
+    fn drop(&mut self) {
+        // the received messages get buffered again until another receiver is announced
+        if let Err(err) = self
+            .buffer_requester
+            .unbounded_send(ReceivedBufferMessage::ReceiverDisconnect)
+        {
+            log::debug!("The buffer request failed: {err}");
+        }
+    }
+}
+
+impl MixnetListener {
+    pub(crate) fn new(
+        client_input: ClientInput,
+        client_output: ClientOutput,
+        client_state: ClientState,
+        reconstructed_receiver: ReconstructedMessagesReceiver,
+        task_manager: TaskManager,
+    ) -> Self {
+        let (controller_sender, controller_receiver) = mpsc::unbounded();
+        let (incoming_sender, incoming_streams) = mpsc::unbounded();
+
+        let context = StreamContext {
+            input_sender: client_input.input_sender,
+            lane_queue_lengths: client_state.shared_lane_queue_lengths,
+            controller_sender,
+        };
+
+        StreamController::new(
+            reconstructed_receiver,
+            controller_receiver,
+            incoming_sender,
+            client_input.connection_command_sender,
+            context.clone(),
+        )
+        .start_with_shutdown(task_manager.subscribe());
+
+        MixnetListener {
+            self_address: client_state.self_address,
+            context,
+            incoming_streams,
+            buffer_requester: client_output.received_buffer_request_sender,
+            task_manager,
+        }
+    }
+
+    /// Get the nym address of the underlying client, which other clients use to open streams
+    /// with us.
+    pub fn nym_address(&self) -> Recipient {
+        self.self_address.get()
+    }
+
+    /// Wait for another client to open a stream with us.
+    pub async fn accept(&mut self) -> Option<MixnetStream> {
+        self.incoming_streams.next().await
+    }
+
+    /// Open a new stream with the client at the provided address. Our address is attached to the
+    /// request, so that the other end could send its data back to us.
+    pub async fn connect(&self, recipient: Recipient) -> MixnetStream {
+        let connection_id: ConnectionId = rand::random();
+        let (stream, data_sender) =
+            MixnetStream::start(connection_id, recipient, self.context.clone());
+
+        // register the stream before anything can possibly come back on it
+        self.context
+            .controller_sender
+            .unbounded_send(ControllerCommand::Insert {
+                connection_id,
+                data_sender,
+            })
+            .expect("StreamController has stopped receiving!");
+
+        let open = StreamMessage::Open {
+            connection_id,
+            return_address: Box::new(self.self_address.get()),
+        };
+        self.context
+            .input_sender
+            .send(InputMessage::new_regular(
+                recipient,
+                open.into_bytes(),
+                runner::stream_lane(connection_id),
+            ))
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+
+        stream
+    }
+
+    /// Disconnect from the mixnet, closing all the streams.
+    pub async fn disconnect(&mut self) {
+        self.task_manager.signal_shutdown().ok();
+        self.task_manager.wait_for_shutdown().await;
+    }
+}
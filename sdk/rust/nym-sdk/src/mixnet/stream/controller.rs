@@ -0,0 +1,393 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use client_connections::{ConnectionCommand, ConnectionCommandSender, ConnectionId};
+use client_core::client::received_buffer::ReconstructedMessagesReceiver;
+use futures::{channel::mpsc, StreamExt};
+use nymsphinx::{addressing::clients::Recipient, receiver::ReconstructedMessage};
+use task::TaskClient;
+
+use super::{
+    message::StreamMessage,
+    runner::{StreamData, StreamDataSender},
+    MixnetStream, StreamContext,
+};
+
+// The mixnet might reorder the messages, so a bit of the data might arrive before the stream is
+// opened. Note we don't ever expect to have more than a few messages per stream here.
+const MAX_PENDING_MESSAGES: usize = 64;
+
+// Limit on the data buffered across all the streams that haven't been opened yet, so that the
+// peers couldn't make us buffer an arbitrary amount of it by never opening their streams.
+const MAX_TOTAL_PENDING_MESSAGES: usize = 1024;
+
+// The data of a stream that still hasn't been opened after this long is never going to be used.
+const PENDING_DATA_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Closed streams are remembered for a while, so that any of their data still in flight would not
+// get buffered as if it belonged to a new stream.
+const RECENTLY_CLOSED_RETENTION: Duration = Duration::from_secs(5 * 60);
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(30);
+
+pub(crate) type ControllerSender = mpsc::UnboundedSender<ControllerCommand>;
+pub(crate) type ControllerReceiver = mpsc::UnboundedReceiver<ControllerCommand>;
+
+pub(crate) enum ControllerCommand {
+    /// Registers a stream we have opened ourselves.
+    Insert {
+        connection_id: ConnectionId,
+        data_sender: StreamDataSender,
+    },
+
+    /// Both ends of the stream are done with it.
+    Remove(ConnectionId),
+}
+
+struct PendingData {
+    first_received: Instant,
+    data: Vec<StreamData>,
+}
+
+/// Dispatches the messages received by the client into the streams they belong to and creates
+/// the streams opened by other clients.
+pub(crate) struct StreamController {
+    reconstructed_receiver: ReconstructedMessagesReceiver,
+    command_receiver: ControllerReceiver,
+    incoming_sender: mpsc::UnboundedSender<MixnetStream>,
+
+    // Announce closed streams, so that the `OutQueueControl` could discard their lanes
+    connection_command_sender: ConnectionCommandSender,
+    context: StreamContext,
+
+    active_streams: HashMap<ConnectionId, StreamDataSender>,
+    pending_data: HashMap<ConnectionId, PendingData>,
+    total_pending_messages: usize,
+    recently_closed: HashMap<ConnectionId, Instant>,
+}
+
+impl StreamController {
+    pub(crate) fn new(
+        reconstructed_receiver: ReconstructedMessagesReceiver,
+        command_receiver: ControllerReceiver,
+        incoming_sender: mpsc::UnboundedSender<MixnetStream>,
+        connection_command_sender: ConnectionCommandSender,
+        context: StreamContext,
+    ) -> Self {
+        StreamController {
+            reconstructed_receiver,
+            command_receiver,
+            incoming_sender,
+            connection_command_sender,
+            context,
+            active_streams: HashMap::new(),
+            pending_data: HashMap::new(),
+            total_pending_messages: 0,
+            recently_closed: HashMap::new(),
+        }
+    }
+
+    fn take_pending(&mut self, connection_id: ConnectionId) -> Option<Vec<StreamData>> {
+        let pending = self.pending_data.remove(&connection_id)?;
+        self.total_pending_messages -= pending.data.len();
+        Some(pending.data)
+    }
+
+    fn insert_stream(&mut self, connection_id: ConnectionId, data_sender: StreamDataSender) {
+        if let Some(pending) = self.take_pending(connection_id) {
+            log::debug!("There was some pending data for {connection_id}");
+            for data in pending {
+                data_sender.unbounded_send(data).ok();
+            }
+        }
+        self.active_streams.insert(connection_id, data_sender);
+    }
+
+    fn remove_stream(&mut self, connection_id: ConnectionId) {
+        log::debug!("Removing stream {connection_id}");
+        self.active_streams.remove(&connection_id);
+        self.take_pending(connection_id);
+        self.recently_closed.insert(connection_id, Instant::now());
+
+        if let Err(err) = self
+            .connection_command_sender
+            .unbounded_send(ConnectionCommand::Close(connection_id))
+        {
+            log::debug!("Failed to announce the closed stream {connection_id}: {err}");
+        }
+    }
+
+    fn on_open(&mut self, connection_id: ConnectionId, return_address: Recipient) {
+        if self.active_streams.contains_key(&connection_id)
+            || self.recently_closed.contains_key(&connection_id)
+        {
+            log::warn!("Received a duplicate request to open stream {connection_id}");
+            return;
+        }
+
+        let (stream, data_sender) =
+            MixnetStream::start(connection_id, return_address, self.context.clone());
+        self.insert_stream(connection_id, data_sender);
+
+        if self.incoming_sender.unbounded_send(stream).is_err() {
+            log::debug!("The listener is gone, stream {connection_id} is not going to be accepted");
+        }
+    }
+
+    fn on_data(&mut self, connection_id: ConnectionId, data: StreamData) {
+        if let Some(data_sender) = self.active_streams.get(&connection_id) {
+            if data_sender.unbounded_send(data).is_err() {
+                log::debug!("Stream {connection_id} is no longer receiving any data");
+            }
+        } else if !self.recently_closed.contains_key(&connection_id) {
+            log::debug!(
+                "Received data before stream {connection_id} got opened - going to buffer it"
+            );
+            if self.total_pending_messages >= MAX_TOTAL_PENDING_MESSAGES {
+                log::warn!(
+                    "Too much data got buffered for unopened streams, dropping it for {connection_id}"
+                );
+                return;
+            }
+            let pending = self
+                .pending_data
+                .entry(connection_id)
+                .or_insert_with(|| PendingData {
+                    first_received: Instant::now(),
+                    data: Vec::new(),
+                });
+            if pending.data.len() < MAX_PENDING_MESSAGES {
+                pending.data.push(data);
+                self.total_pending_messages += 1;
+            } else {
+                log::warn!("Too much data got buffered for stream {connection_id}, dropping it");
+            }
+        } else {
+            log::debug!(
+                "Received {} bytes for closed stream {connection_id}",
+                data.message.data.len()
+            );
+        }
+    }
+
+    fn remove_expired(&mut self, now: Instant) {
+        self.recently_closed.retain(|_, closed_at| {
+            now.saturating_duration_since(*closed_at) < RECENTLY_CLOSED_RETENTION
+        });
+
+        let expired = self
+            .pending_data
+            .iter()
+            .filter(|(_, pending)| {
+                now.saturating_duration_since(pending.first_received) >= PENDING_DATA_TIMEOUT
+            })
+            .map(|(connection_id, _)| *connection_id)
+            .collect::<Vec<_>>();
+        for connection_id in expired {
+            log::debug!("Stream {connection_id} never got opened, discarding its data");
+            self.take_pending(connection_id);
+        }
+    }
+
+    fn on_message(&mut self, message: ReconstructedMessage) {
+        let stream_message = match StreamMessage::try_from_bytes(&message.message) {
+            Ok(stream_message) => stream_message,
+            Err(err) => {
+                log::warn!("Discarding a message that doesn't belong to any stream - {err}");
+                return;
+            }
+        };
+
+        let connection_id = stream_message.connection_id();
+        match stream_message {
+            StreamMessage::Open { return_address, .. } => {
+                self.on_open(connection_id, *return_address)
+            }
+            StreamMessage::Data {
+                closed, message, ..
+            } => self.on_data(connection_id, StreamData { closed, message }),
+        }
+    }
+
+    fn on_command(&mut self, command: ControllerCommand) {
+        match command {
+            ControllerCommand::Insert {
+                connection_id,
+                data_sender,
+            } => self.insert_stream(connection_id, data_sender),
+            ControllerCommand::Remove(connection_id) => self.remove_stream(connection_id),
+        }
+    }
+
+    async fn run_with_shutdown(&mut self, mut shutdown: TaskClient) {
+        log::debug!("Started StreamController with graceful shutdown support");
+
+        let mut cleanup_timer = tokio::time::interval(CLEANUP_INTERVAL);
+
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                messages = self.reconstructed_receiver.next() => match messages {
+                    Some(messages) => {
+                        for message in messages {
+                            self.on_message(message)
+                        }
+                    }
+                    None => {
+                        log::trace!("StreamController: Stopping since channel closed");
+                        break;
+                    }
+                },
+                // we're holding a sender ourselves, so the channel is never going to get closed
+                Some(command) = self.command_receiver.next() => self.on_command(command),
+                _ = cleanup_timer.tick() => self.remove_expired(Instant::now()),
+                _ = shutdown.recv() => {
+                    log::trace!("StreamController: Received shutdown");
+                }
+            }
+        }
+
+        log::debug!("StreamController: Exiting");
+    }
+
+    pub(crate) fn start_with_shutdown(mut self, shutdown: TaskClient) {
+        tokio::spawn(async move { self.run_with_shutdown(shutdown).await });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client_connections::LaneQueueLengths;
+    use ordered_buffer::OrderedMessage;
+
+    fn controller() -> StreamController {
+        let (_, reconstructed_receiver) = mpsc::unbounded();
+        let (controller_sender, command_receiver) = mpsc::unbounded();
+        let (incoming_sender, _) = mpsc::unbounded();
+        let (connection_command_sender, _) = mpsc::unbounded();
+        let (input_sender, _) = tokio::sync::mpsc::channel(1);
+
+        StreamController::new(
+            reconstructed_receiver,
+            command_receiver,
+            incoming_sender,
+            connection_command_sender,
+            StreamContext {
+                input_sender,
+                lane_queue_lengths: LaneQueueLengths::new(),
+                controller_sender,
+            },
+        )
+    }
+
+    fn data(index: u64) -> StreamData {
+        StreamData {
+            closed: false,
+            message: OrderedMessage {
+                data: vec![42],
+                index,
+            },
+        }
+    }
+
+    fn delivered(
+        controller: &mut StreamController,
+        connection_id: ConnectionId,
+    ) -> Vec<StreamData> {
+        let (data_sender, mut data_receiver) = mpsc::unbounded();
+        controller.on_command(ControllerCommand::Insert {
+            connection_id,
+            data_sender,
+        });
+        let mut delivered = Vec::new();
+        while let Ok(Some(data)) = data_receiver.try_next() {
+            delivered.push(data);
+        }
+        delivered
+    }
+
+    #[test]
+    fn data_received_before_the_stream_got_opened_is_delivered_once_it_is() {
+        let mut controller = controller();
+        controller.on_data(1, data(1));
+        controller.on_data(1, data(0));
+        controller.on_data(2, data(0));
+
+        let delivered = delivered(&mut controller, 1);
+        assert_eq!(
+            delivered
+                .iter()
+                .map(|data| data.message.index)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        assert_eq!(controller.total_pending_messages, 1);
+    }
+
+    #[test]
+    fn pending_data_is_limited_per_stream() {
+        let mut controller = controller();
+        for index in 0..MAX_PENDING_MESSAGES as u64 + 10 {
+            controller.on_data(1, data(index));
+        }
+        assert_eq!(controller.total_pending_messages, MAX_PENDING_MESSAGES);
+        assert_eq!(delivered(&mut controller, 1).len(), MAX_PENDING_MESSAGES);
+        assert_eq!(controller.total_pending_messages, 0);
+    }
+
+    #[test]
+    fn pending_data_is_limited_across_all_streams() {
+        let mut controller = controller();
+        let full_streams = (MAX_TOTAL_PENDING_MESSAGES / MAX_PENDING_MESSAGES) as ConnectionId;
+        for connection_id in 0..full_streams {
+            for index in 0..MAX_PENDING_MESSAGES as u64 {
+                controller.on_data(connection_id, data(index));
+            }
+        }
+        assert_eq!(
+            controller.total_pending_messages,
+            MAX_TOTAL_PENDING_MESSAGES
+        );
+
+        controller.on_data(full_streams, data(0));
+        assert!(delivered(&mut controller, full_streams).is_empty());
+
+        // once some of the streams get opened, there's space for more
+        assert_eq!(delivered(&mut controller, 0).len(), MAX_PENDING_MESSAGES);
+        controller.on_data(full_streams + 1, data(0));
+        assert_eq!(delivered(&mut controller, full_streams + 1).len(), 1);
+    }
+
+    #[test]
+    fn data_of_closed_streams_is_not_buffered() {
+        let mut controller = controller();
+        assert_eq!(delivered(&mut controller, 1).len(), 0);
+        controller.on_command(ControllerCommand::Remove(1));
+
+        controller.on_data(1, data(0));
+        assert!(controller.pending_data.is_empty());
+        assert_eq!(controller.total_pending_messages, 0);
+    }
+
+    #[test]
+    fn closed_streams_and_unused_pending_data_expire() {
+        let mut controller = controller();
+        controller.on_command(ControllerCommand::Remove(1));
+        controller.on_data(2, data(0));
+
+        let now = Instant::now();
+        controller.remove_expired(now);
+        assert!(controller.recently_closed.contains_key(&1));
+        assert!(controller.pending_data.contains_key(&2));
+
+        controller.remove_expired(now + PENDING_DATA_TIMEOUT);
+        assert!(controller.recently_closed.contains_key(&1));
+        assert!(controller.pending_data.is_empty());
+        assert_eq!(controller.total_pending_messages, 0);
+
+        controller.remove_expired(now + RECENTLY_CLOSED_RETENTION);
+        assert!(controller.recently_closed.is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+use client_connections::ConnectionId;
+use nymsphinx::addressing::clients::{Recipient, RecipientFormattingError};
+use ordered_buffer::{MessageError, OrderedMessage};
+
+const OPEN_MESSAGE: u8 = 0;
+const DATA_MESSAGE: u8 = 1;
+
+// message type + connection id
+const HEADER_LEN: usize = 1 + 8;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StreamMessageError {
+    #[error("the received message is too short to be a stream message ({0} bytes)")]
+    TooShort(usize),
+
+    #[error("the received message has unknown type {0}")]
+    UnknownType(u8),
+
+    #[error("the return address is malformed: {0}")]
+    MalformedReturnAddress(#[from] RecipientFormattingError),
+
+    #[error("the stream data is malformed: {0}")]
+    MalformedData(#[from] MessageError),
+}
+
+/// Messages exchanged between the two ends of a [`MixnetStream`](super::MixnetStream).
+#[derive(Debug, PartialEq)]
+pub(crate) enum StreamMessage {
+    /// Opens a new stream. The return address is where the data of the stream is to be sent back.
+    Open {
+        connection_id: ConnectionId,
+        return_address: Box<Recipient>,
+    },
+
+    /// Data of the stream. If `closed` is set, the sender is not going to write any more data
+    /// after this message.
+    Data {
+        connection_id: ConnectionId,
+        closed: bool,
+        message: OrderedMessage,
+    },
+}
+
+impl StreamMessage {
+    pub(crate) fn connection_id(&self) -> ConnectionId {
+        match self {
+            StreamMessage::Open { connection_id, .. } => *connection_id,
+            StreamMessage::Data { connection_id, .. } => *connection_id,
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        match self {
+            StreamMessage::Open {
+                connection_id,
+                return_address,
+            } => std::iter::once(OPEN_MESSAGE)
+                .chain(connection_id.to_be_bytes())
+                .chain(return_address.to_bytes())
+                .collect(),
+            StreamMessage::Data {
+                connection_id,
+                closed,
+                message,
+            } => std::iter::once(DATA_MESSAGE)
+                .chain(connection_id.to_be_bytes())
+                .chain(std::iter::once(closed as u8))
+                .chain(message.into_bytes())
+                .collect(),
+        }
+    }
+
+    pub(crate) fn try_from_bytes(bytes: &[u8]) -> Result<Self, StreamMessageError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(StreamMessageError::TooShort(bytes.len()));
+        }
+
+        let mut connection_id_bytes = [0u8; 8];
+        connection_id_bytes.copy_from_slice(&bytes[1..HEADER_LEN]);
+        let connection_id = ConnectionId::from_be_bytes(connection_id_bytes);
+        let payload = &bytes[HEADER_LEN..];
+
+        match bytes[0] {
+            OPEN_MESSAGE => {
+                if payload.len() != Recipient::LEN {
+                    return Err(StreamMessageError::TooShort(bytes.len()));
+                }
+                let mut address_bytes = [0u8; Recipient::LEN];
+                address_bytes.copy_from_slice(payload);
+                Ok(StreamMessage::Open {
+                    connection_id,
+                    return_address: Box::new(Recipient::try_from_bytes(address_bytes)?),
+                })
+            }
+            DATA_MESSAGE => {
+                let Some((&closed, message)) = payload.split_first() else {
+                    return Err(StreamMessageError::TooShort(bytes.len()));
+                };
+                Ok(StreamMessage::Data {
+                    connection_id,
+                    closed: closed != 0,
+                    message: OrderedMessage::try_from_bytes(message.to_vec())?,
+                })
+            }
+            other => Err(StreamMessageError::UnknownType(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::asymmetric::{encryption, identity};
+
+    fn recipient() -> Recipient {
+        let mut rng = rand::rngs::OsRng;
+        Recipient::new(
+            *identity::KeyPair::new(&mut rng).public_key(),
+            *encryption::KeyPair::new(&mut rng).public_key(),
+            *identity::KeyPair::new(&mut rng).public_key(),
+        )
+    }
+
+    #[test]
+    fn open_message_survives_serialization() {
+        let return_address = recipient();
+        let message = StreamMessage::Open {
+            connection_id: 1234,
+            return_address: Box::new(return_address),
+        };
+        let bytes = message.into_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + Recipient::LEN);
+
+        let recovered = StreamMessage::try_from_bytes(&bytes).unwrap();
+        assert_eq!(
+            recovered,
+            StreamMessage::Open {
+                connection_id: 1234,
+                return_address: Box::new(return_address),
+            }
+        );
+    }
+
+    #[test]
+    fn data_message_survives_serialization() {
+        for closed in [false, true] {
+            let message = StreamMessage::Data {
+                connection_id: u64::MAX,
+                closed,
+                message: OrderedMessage {
+                    data: b"hello".to_vec(),
+                    index: 42,
+                },
+            };
+            let expected = StreamMessage::Data {
+                connection_id: u64::MAX,
+                closed,
+                message: OrderedMessage {
+                    data: b"hello".to_vec(),
+                    index: 42,
+                },
+            };
+            let recovered = StreamMessage::try_from_bytes(&message.into_bytes()).unwrap();
+            assert_eq!(recovered, expected);
+        }
+    }
+
+    #[test]
+    fn malformed_messages_are_rejected() {
+        assert!(matches!(
+            StreamMessage::try_from_bytes(&[DATA_MESSAGE, 0, 0]),
+            Err(StreamMessageError::TooShort(3))
+        ));
+        assert!(matches!(
+            StreamMessage::try_from_bytes(&[42; HEADER_LEN + 1]),
+            Err(StreamMessageError::UnknownType(42))
+        ));
+
+        // data without the closed flag
+        let mut bytes = vec![DATA_MESSAGE];
+        bytes.extend_from_slice(&7u64.to_be_bytes());
+        assert!(matches!(
+            StreamMessage::try_from_bytes(&bytes),
+            Err(StreamMessageError::TooShort(_))
+        ));
+
+        // truncated return address
+        let open = StreamMessage::Open {
+            connection_id: 7,
+            return_address: Box::new(recipient()),
+        };
+        let bytes = open.into_bytes();
+        assert!(matches!(
+            StreamMessage::try_from_bytes(&bytes[..bytes.len() - 1]),
+            Err(StreamMessageError::TooShort(_))
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use client_connections::{ConnectionId, LaneQueueLengths, TransmissionLane};
+use client_core::client::inbound_messages::InputMessage;
+use futures::{channel::mpsc, StreamExt};
+use nymsphinx::addressing::clients::Recipient;
+use ordered_buffer::{OrderedMessage, OrderedMessageBuffer, OrderedMessageSender};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf};
+
+use super::{controller::ControllerCommand, message::StreamMessage, StreamContext};
+
+// Size of the chunks the written data is read in before being sent into the mixnet
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+
+// We allow a bit of slack to try to keep the pipeline >0
+const LANE_QUEUE_LENGTH_THRESHOLD: usize = 30;
+const LANE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+const LANE_WAIT_TIMEOUT: Duration = Duration::from_secs(4 * 60);
+
+// Give the last message some time to reach the lane before checking whether it's empty
+const CLOSE_DELAY: Duration = Duration::from_secs(2);
+
+/// Data of a stream received from the mixnet, possibly out of order.
+#[derive(Debug)]
+pub(crate) struct StreamData {
+    pub(crate) closed: bool,
+    pub(crate) message: OrderedMessage,
+}
+
+/// Channel for pushing the data received from the mixnet into a particular stream.
+pub(crate) type StreamDataSender = mpsc::UnboundedSender<StreamData>;
+
+/// Receiver part of the [`StreamDataSender`]
+pub(crate) type StreamDataReceiver = mpsc::UnboundedReceiver<StreamData>;
+
+pub(crate) fn stream_lane(connection_id: ConnectionId) -> TransmissionLane {
+    TransmissionLane::ConnectionId(connection_id)
+}
+
+async fn wait_for_lane(
+    lane_queue_lengths: &LaneQueueLengths,
+    lane: TransmissionLane,
+    queue_length_threshold: usize,
+) {
+    let wait = async {
+        while let Some(queue) = lane_queue_lengths.get(&lane) {
+            if queue > queue_length_threshold {
+                tokio::time::sleep(LANE_CHECK_INTERVAL).await;
+            } else {
+                break;
+            }
+        }
+    };
+    if tokio::time::timeout(LANE_WAIT_TIMEOUT, wait).await.is_err() {
+        log::debug!("Waiting for the lane {lane:?} to drain has timed out");
+    }
+}
+
+/// Reads the data written to the stream and sends it into the mixnet until the stream gets
+/// shut down or dropped, after which the other end is informed that the stream is closed.
+async fn run_outbound(
+    mut reader: ReadHalf<DuplexStream>,
+    connection_id: ConnectionId,
+    peer: Recipient,
+    context: &StreamContext,
+) {
+    let lane = stream_lane(connection_id);
+    let mut message_sender = OrderedMessageSender::new();
+    let mut buf = vec![0; READ_CHUNK_SIZE];
+
+    loop {
+        let (data, closed) = match reader.read(&mut buf).await {
+            Ok(0) => (Vec::new(), true),
+            Ok(n) => (buf[..n].to_vec(), false),
+            Err(err) => {
+                log::debug!("({connection_id}) failed to read from the stream - {err}");
+                (Vec::new(), true)
+            }
+        };
+
+        // Before sending the data downstream, wait for the lane to be reasonably close to
+        // finishing. This is how the writer gets paced (backpressure).
+        wait_for_lane(
+            &context.lane_queue_lengths,
+            lane,
+            LANE_QUEUE_LENGTH_THRESHOLD,
+        )
+        .await;
+
+        let message = StreamMessage::Data {
+            connection_id,
+            closed,
+            message: message_sender.wrap_message(data),
+        };
+        if context
+            .input_sender
+            .send(InputMessage::new_regular(peer, message.into_bytes(), lane))
+            .await
+            .is_err()
+        {
+            log::debug!("({connection_id}) the client has stopped, the data won't be sent");
+            return;
+        }
+
+        if closed {
+            // make sure everything got sent out before the lane gets discarded
+            tokio::time::sleep(CLOSE_DELAY).await;
+            wait_for_lane(&context.lane_queue_lengths, lane, 0).await;
+            log::debug!("({connection_id}) the stream got closed locally");
+            return;
+        }
+    }
+}
+
+/// Writes the data received from the mixnet into the stream, in order, until the other end
+/// closes it.
+async fn run_inbound(
+    mut writer: WriteHalf<DuplexStream>,
+    connection_id: ConnectionId,
+    mut data_receiver: StreamDataReceiver,
+) {
+    let mut ordered_buffer = OrderedMessageBuffer::new();
+    let mut closed_at_index = None;
+
+    while let Some(StreamData { closed, message }) = data_receiver.next().await {
+        if closed {
+            closed_at_index = Some(message.index);
+        }
+        ordered_buffer.write(message);
+
+        let Some(contiguous) = ordered_buffer.read() else {
+            continue;
+        };
+        if let Err(err) = writer.write_all(&contiguous.data).await {
+            log::debug!("({connection_id}) the stream is gone - {err}");
+            return;
+        }
+        if matches!(closed_at_index, Some(index) if contiguous.last_index > index) {
+            log::debug!("({connection_id}) the stream got closed by the remote");
+            writer.shutdown().await.ok();
+            return;
+        }
+    }
+}
+
+pub(super) async fn run_stream(
+    connection_id: ConnectionId,
+    peer: Recipient,
+    stream: DuplexStream,
+    data_receiver: StreamDataReceiver,
+    context: StreamContext,
+) {
+    let (reader, writer) = tokio::io::split(stream);
+
+    tokio::join!(
+        run_outbound(reader, connection_id, peer, &context),
+        run_inbound(writer, connection_id, data_receiver),
+    );
+
+    context
+        .controller_sender
+        .unbounded_send(ControllerCommand::Remove(connection_id))
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(index: u64, content: &[u8], closed: bool) -> StreamData {
+        StreamData {
+            closed,
+            message: OrderedMessage {
+                data: content.to_vec(),
+                index,
+            },
+        }
+    }
+
+    fn start_inbound() -> (DuplexStream, StreamDataSender) {
+        let (stream, runner_end) = tokio::io::duplex(1024);
+        let (_, writer) = tokio::io::split(runner_end);
+        let (data_sender, data_receiver) = mpsc::unbounded();
+        tokio::spawn(run_inbound(writer, 1, data_receiver));
+        (stream, data_sender)
+    }
+
+    #[tokio::test]
+    async fn reordered_data_is_written_in_order() {
+        let (mut stream, data_sender) = start_inbound();
+        data_sender.unbounded_send(data(2, b"!", false)).unwrap();
+        data_sender
+            .unbounded_send(data(1, b"world", false))
+            .unwrap();
+        data_sender
+            .unbounded_send(data(0, b"hello ", false))
+            .unwrap();
+
+        let mut buf = [0u8; 12];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello world!");
+    }
+
+    #[tokio::test]
+    async fn stream_is_closed_once_all_the_data_before_the_close_is_written() {
+        let (mut stream, data_sender) = start_inbound();
+        // the close arrives before some of the data sent before it
+        data_sender.unbounded_send(data(2, b"", true)).unwrap();
+        data_sender
+            .unbounded_send(data(1, b"world", false))
+            .unwrap();
+
+        let mut buf = [0u8; 5];
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream.read(&mut buf))
+                .await
+                .is_err()
+        );
+
+        data_sender
+            .unbounded_send(data(0, b"hello ", false))
+            .unwrap();
+        let mut received = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut received))
+            .await
+            .expect("the stream did not get closed")
+            .unwrap();
+        assert_eq!(received, b"hello world");
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    error::Error as StdError,
+    net::{Ipv4Addr, SocketAddr},
+};
+
+use client_core::client::self_address::SelfAddress;
+use network_defaults::DEFAULT_SOCKS5_LISTENING_PORT;
+use nym_socks5::{
+    client::{
+        config::{DEFAULT_CONNECTION_START_SURBS, DEFAULT_PER_REQUEST_SURBS},
+        start_socks5_server,
+    },
+    socks::{
+        authentication::{AuthenticationMethods, Authenticator},
+        client::Config as SocksClientConfig,
+    },
+};
+use nymsphinx::addressing::clients::Recipient;
+use task::TaskManager;
+
+use crate::{error::Result, mixnet::ClientBuilder};
+
+pub use nym_socks5::socks::authentication::User;
+
+pub struct Socks5Config {
+    /// The address the proxy accepts the socks connections on.
+    pub listening_address: SocketAddr,
+
+    /// The address of the service provider (network requester) the requests are forwarded to.
+    pub service_provider: Recipient,
+
+    /// The users allowed to use the proxy. If empty, no authentication is required.
+    pub allowed_users: Vec<User>,
+
+    /// If enabled, the service provider sends its responses back using SURBs, so it never learns
+    /// our address.
+    pub send_anonymously: bool,
+
+    /// The number of SURBs sent along the request opening a new connection.
+    pub connection_start_surbs: u32,
+
+    /// The number of SURBs sent along each subsequent request of a connection.
+    pub per_request_surbs: u32,
+}
+
+impl Socks5Config {
+    pub fn new(service_provider: Recipient) -> Self {
+        Self {
+            listening_address: SocketAddr::from((
+                Ipv4Addr::LOCALHOST,
+                DEFAULT_SOCKS5_LISTENING_PORT,
+            )),
+            service_provider,
+            allowed_users: Vec::new(),
+            send_anonymously: false,
+            connection_start_surbs: DEFAULT_CONNECTION_START_SURBS,
+            per_request_surbs: DEFAULT_PER_REQUEST_SURBS,
+        }
+    }
+
+    fn authenticator(&self) -> Authenticator {
+        let auth_method = if self.allowed_users.is_empty() {
+            AuthenticationMethods::NoAuth
+        } else {
+            AuthenticationMethods::UserPass
+        };
+        Authenticator::new(vec![auth_method as u8], self.allowed_users.clone())
+    }
+}
+
+/// A socks5 proxy running in-process, which forwards all the connections made to it through the
+/// mixnet to the configured service provider.
+///
+/// The proxy owns its own mixnet client, which is stopped together with the proxy.
+pub struct Socks5Proxy {
+    self_address: SelfAddress,
+    listening_address: SocketAddr,
+    task_manager: TaskManager,
+}
+
+impl Socks5Proxy {
+    /// Connects a new client to the mixnet and starts the proxy on top of it. Note that the
+    /// listening socket gets bound in the background, failing to do so is reported via
+    /// [`wait_for_error`](Self::wait_for_error).
+    pub async fn start(client_builder: ClientBuilder, config: Socks5Config) -> Result<Self> {
+        let mut started_client = client_builder.start_base_client().await?;
+        let client_input = started_client.client_input.register_producer();
+        let client_output = started_client.client_output.register_consumer();
+        let client_state = started_client.client_state;
+        let self_address = client_state.self_address.clone();
+
+        start_socks5_server(
+            config.listening_address,
+            config.authenticator(),
+            config.service_provider,
+            SocksClientConfig::new(
+                config.send_anonymously,
+                config.connection_start_surbs,
+                config.per_request_surbs,
+            ),
+            client_input,
+            client_output,
+            client_state,
+            started_client.task_manager.subscribe(),
+        );
+
+        Ok(Socks5Proxy {
+            self_address,
+            listening_address: config.listening_address,
+            task_manager: started_client.task_manager,
+        })
+    }
+
+    /// Get the nym address of the client underlying the proxy.
+    pub fn nym_address(&self) -> Recipient {
+        self.self_address.get()
+    }
+
+    /// Get the address the proxy accepts the socks connections on.
+    pub fn listening_address(&self) -> SocketAddr {
+        self.listening_address
+    }
+
+    /// Wait until either the proxy or the underlying client fails.
+    pub async fn wait_for_error(&mut self) -> Option<Box<dyn StdError + Send + Sync>> {
+        self.task_manager.wait_for_error().await
+    }
+
+    /// Stop the proxy and disconnect its client from the mixnet.
+    pub async fn stop(mut self) {
+        self.task_manager.signal_shutdown().ok();
+        self.task_manager.wait_for_shutdown().await;
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the sdk client against a local mock gateway, with the network topology served by a mock
+//! nym-api, to check how it keeps track of the bandwidth it has at the gateway.
+
+use common::{GatewayEvent, CLAIMED_BANDWIDTH, INITIAL_BANDWIDTH};
+use nym_sdk::mixnet;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+mod common;
+
+async fn connect_client(
+    automatic_bandwidth_top_up: bool,
+) -> (mixnet::Client, mpsc::UnboundedReceiver<GatewayEvent>) {
+    let (client, gateway_events) = common::mock_client_builder(|config| {
+        config.automatic_bandwidth_top_up = automatic_bandwidth_top_up
+    })
+    .await;
+    let client = client.connect_to_mixnet().await.unwrap();
+    (client, gateway_events)
+}
+
+// waits until the gateway has received the specified number of packets, returning the number
+// of the bandwidth claims made in the meantime
+async fn wait_for_packets(
+    gateway_events: &mut mpsc::UnboundedReceiver<GatewayEvent>,
+    packets: usize,
+) -> usize {
+    let mut received = 0;
+    let mut claims = 0;
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while received < packets {
+            match gateway_events.recv().await.unwrap() {
+                GatewayEvent::Packet => received += 1,
+                GatewayEvent::BandwidthClaim => claims += 1,
+            }
+        }
+    })
+    .await
+    .expect("the gateway has not received enough packets");
+    claims
+}
+
+#[tokio::test]
+async fn bandwidth_is_claimed_automatically_once_used_up_if_enabled() {
+    let (mut client, mut gateway_events) = connect_client(true).await;
+
+    // the initial bandwidth is only sufficient for a handful of packets, the rest are only sent
+    // once more of it has been claimed
+    let claims = wait_for_packets(&mut gateway_events, 20).await;
+    assert_eq!(claims, 1);
+
+    let remaining = client.remaining_bandwidth();
+    assert!(remaining > INITIAL_BANDWIDTH);
+    // and the packets sent afterwards are deducted from it
+    assert!(remaining < CLAIMED_BANDWIDTH);
+
+    client.disconnect().await;
+}
+
+#[tokio::test]
+async fn bandwidth_is_only_claimed_on_request_by_default() {
+    let (mut client, mut gateway_events) = connect_client(false).await;
+
+    // the gateway is in charge of the bandwidth, so the client keeps on sending the packets
+    let claims = wait_for_packets(&mut gateway_events, 20).await;
+    assert_eq!(claims, 0);
+    assert_eq!(client.remaining_bandwidth(), INITIAL_BANDWIDTH);
+
+    let remaining = client.top_up_bandwidth().await.unwrap();
+    assert_eq!(remaining, CLAIMED_BANDWIDTH);
+    assert_eq!(client.remaining_bandwidth(), CLAIMED_BANDWIDTH);
+
+    client.disconnect().await;
+}
@@ -0,0 +1,255 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local stand-ins for the gateway and the nym-api, so that the sdk clients could be run without
+//! the actual network.
+
+// not every test makes use of everything in here
+#![allow(dead_code)]
+
+use crypto::asymmetric::{encryption, identity};
+use futures::{SinkExt, StreamExt};
+use gateway_requests::registration::handshake::SharedKeys;
+use gateway_requests::{ClientControlRequest, ServerResponse, PROTOCOL_VERSION};
+use mixnet_contract_common::{
+    Addr, Coin, Decimal, Gateway, GatewayBond, Layer, MixNode, MixNodeBond, MixNodeCostParams,
+    MixNodeDetails, MixNodeRewarding, Percent,
+};
+use nym_api_requests::models::MixNodeBondAnnotated;
+use nym_sdk::mixnet;
+use nymsphinx::acknowledgements::AckKey;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+pub const INITIAL_BANDWIDTH: i64 = 10_000;
+pub const CLAIMED_BANDWIDTH: i64 = 1_000_000;
+
+// the mixnodes have to be compatible with the version of the client
+const NODE_VERSION: &str = "1.1.0";
+
+pub enum GatewayEvent {
+    Packet,
+    BandwidthClaim,
+}
+
+// minimal stand-in for a gateway: it accepts any authentication request, swallows all the mix
+// packets and hands out free bandwidth whenever asked
+async fn start_mock_gateway() -> (u16, mpsc::UnboundedReceiver<GatewayEvent>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (events_tx, events_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let events_tx = events_tx.clone();
+            tokio::spawn(async move {
+                let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(msg)) = socket.next().await {
+                    let response = match msg {
+                        Message::Binary(_) => {
+                            let _ = events_tx.send(GatewayEvent::Packet);
+                            continue;
+                        }
+                        Message::Text(text) => match ClientControlRequest::try_from(text) {
+                            Ok(ClientControlRequest::Authenticate { .. }) => {
+                                ServerResponse::Authenticate {
+                                    protocol_version: Some(PROTOCOL_VERSION),
+                                    status: true,
+                                    bandwidth_remaining: INITIAL_BANDWIDTH,
+                                }
+                            }
+                            Ok(ClientControlRequest::ClaimFreeTestnetBandwidth) => {
+                                let _ = events_tx.send(GatewayEvent::BandwidthClaim);
+                                ServerResponse::Bandwidth {
+                                    available_total: CLAIMED_BANDWIDTH,
+                                }
+                            }
+                            _ => ServerResponse::new_error("unexpected request"),
+                        },
+                        _ => continue,
+                    };
+                    if socket.send(response.into()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    (port, events_rx)
+}
+
+// minimal stand-in for the http api of the nym-api, only serving the network topology
+async fn start_mock_nym_api(mixnodes: Vec<MixNodeDetails>, gateways: Vec<GatewayBond>) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let annotated = mixnodes
+        .iter()
+        .cloned()
+        .map(|mixnode_details| MixNodeBondAnnotated {
+            mixnode_details,
+            stake_saturation: Decimal::zero(),
+            uncapped_stake_saturation: Decimal::zero(),
+            performance: Percent::hundred(),
+            estimated_operator_apy: Decimal::zero(),
+            estimated_delegators_apy: Decimal::zero(),
+            family: None,
+        })
+        .collect::<Vec<_>>();
+    let detailed_body = serde_json::to_string(&annotated).unwrap();
+    let mixnodes_body = serde_json::to_string(&mixnodes).unwrap();
+    let gateways_body = serde_json::to_string(&gateways).unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap_or_default();
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+            let (status, body) = if path.ends_with("/mixnodes/active/detailed") {
+                ("200 OK", detailed_body.as_str())
+            } else if path.ends_with("/mixnodes/active") {
+                ("200 OK", mixnodes_body.as_str())
+            } else if path.ends_with("/gateways") {
+                ("200 OK", gateways_body.as_str())
+            } else {
+                ("404 Not Found", "")
+            };
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    format!("http://127.0.0.1:{port}").parse().unwrap()
+}
+
+fn mixnode(mix_id: u32, layer: Layer) -> MixNodeDetails {
+    let mut rng = rand::rngs::OsRng;
+    let pledge = Coin::new(100_000_000, "unym");
+    let bond = MixNodeBond::new(
+        mix_id,
+        Addr::unchecked(format!("owner{mix_id}")),
+        pledge.clone(),
+        layer,
+        MixNode {
+            host: "127.0.0.1".to_string(),
+            mix_port: 1789,
+            verloc_port: 1790,
+            http_api_port: 8000,
+            sphinx_key: encryption::KeyPair::new(&mut rng)
+                .public_key()
+                .to_base58_string(),
+            identity_key: identity::KeyPair::new(&mut rng)
+                .public_key()
+                .to_base58_string(),
+            version: NODE_VERSION.to_string(),
+        },
+        None,
+        1,
+    );
+    let cost_params = MixNodeCostParams {
+        profit_margin_percent: Percent::from_percentage_value(10).unwrap(),
+        interval_operating_cost: Coin::new(40_000_000, "unym"),
+    };
+    let rewarding = MixNodeRewarding::initialise_new(cost_params, &pledge, 1).unwrap();
+    MixNodeDetails::new(bond, rewarding)
+}
+
+fn gateway(identity_key: &identity::PublicKey, clients_port: u16) -> GatewayBond {
+    let mut rng = rand::rngs::OsRng;
+    GatewayBond::new(
+        Coin::new(100_000_000, "unym"),
+        Addr::unchecked("gateway-owner"),
+        1,
+        Gateway {
+            host: "127.0.0.1".to_string(),
+            mix_port: 1789,
+            clients_port,
+            location: "localhost".to_string(),
+            sphinx_key: encryption::KeyPair::new(&mut rng)
+                .public_key()
+                .to_base58_string(),
+            identity_key: identity_key.to_base58_string(),
+            version: NODE_VERSION.to_string(),
+        },
+        None,
+    )
+}
+
+/// A mock gateway together with the topology of a network that includes it.
+pub struct MockNetwork {
+    pub mixnodes: Vec<MixNodeDetails>,
+    pub gateways: Vec<GatewayBond>,
+    pub gateway_events: mpsc::UnboundedReceiver<GatewayEvent>,
+    gateway_identity: identity::KeyPair,
+    gateway_port: u16,
+}
+
+impl MockNetwork {
+    pub async fn start() -> Self {
+        let mut rng = rand::rngs::OsRng;
+        let (gateway_port, gateway_events) = start_mock_gateway().await;
+        let gateway_identity = identity::KeyPair::new(&mut rng);
+
+        MockNetwork {
+            mixnodes: vec![
+                mixnode(1, Layer::One),
+                mixnode(2, Layer::Two),
+                mixnode(3, Layer::Three),
+            ],
+            gateways: vec![gateway(gateway_identity.public_key(), gateway_port)],
+            gateway_events,
+            gateway_identity,
+            gateway_port,
+        }
+    }
+
+    /// Starts a mock nym-api serving the topology of this network.
+    pub async fn start_nym_api(&self) -> Url {
+        start_mock_nym_api(self.mixnodes.clone(), self.gateways.clone()).await
+    }
+
+    /// Returns a client builder already registered with the gateway of this network.
+    pub fn client_builder(&self, mut config: mixnet::Config) -> mixnet::ClientBuilder {
+        let mut rng = rand::rngs::OsRng;
+        config.debug_config.use_rotated_sphinx_keys = false;
+
+        let mut client = mixnet::ClientBuilder::new(Some(config), None).unwrap();
+        // pretend we have already registered with the gateway
+        client.set_keys(mixnet::Keys {
+            identity_keypair: identity::KeyPair::new(&mut rng),
+            encryption_keypair: encryption::KeyPair::new(&mut rng),
+            ack_key: AckKey::new(&mut rng),
+            gateway_shared_key: SharedKeys::try_from_bytes(&[42; 32]).unwrap(),
+        });
+        client.set_gateway_endpoint(mixnet::GatewayEndpointConfig {
+            gateway_id: self.gateway_identity.public_key().to_base58_string(),
+            gateway_owner: "gateway-owner".to_string(),
+            gateway_listener: format!("ws://127.0.0.1:{}", self.gateway_port),
+        });
+        client
+    }
+}
+
+/// Starts a mock gateway together with a mock nym-api serving a topology that includes it,
+/// and returns a client builder already registered with that gateway.
+pub async fn mock_client_builder(
+    configure: impl FnOnce(&mut mixnet::Config),
+) -> (mixnet::ClientBuilder, mpsc::UnboundedReceiver<GatewayEvent>) {
+    let network = MockNetwork::start().await;
+    let nym_api = network.start_nym_api().await;
+
+    let mut config = mixnet::Config::new(None, vec![nym_api]);
+    configure(&mut config);
+
+    let client = network.client_builder(config);
+    (client, network.gateway_events)
+}
@@ -0,0 +1,124 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the sdk socks5 proxy on top of a client connected to a local mock gateway, to check that
+//! it accepts the socks connections on the configured address with the configured authentication.
+
+use crypto::asymmetric::{encryption, identity};
+use nym_sdk::socks5::{Socks5Config, Socks5Proxy, User};
+use nymsphinx::addressing::clients::Recipient;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+
+const SOCKS5_VERSION: u8 = 0x05;
+const NO_AUTH: u8 = 0x00;
+const USER_PASS: u8 = 0x02;
+const NO_METHODS: u8 = 0xFF;
+
+fn service_provider() -> Recipient {
+    let mut rng = rand::rngs::OsRng;
+    Recipient::new(
+        *identity::KeyPair::new(&mut rng).public_key(),
+        *encryption::KeyPair::new(&mut rng).public_key(),
+        *identity::KeyPair::new(&mut rng).public_key(),
+    )
+}
+
+async fn free_local_address() -> SocketAddr {
+    TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+async fn start_proxy(allowed_users: Vec<User>) -> Socks5Proxy {
+    let (client_builder, _) = common::mock_client_builder(|_| {}).await;
+
+    let mut config = Socks5Config::new(service_provider());
+    config.listening_address = free_local_address().await;
+    config.allowed_users = allowed_users;
+
+    Socks5Proxy::start(client_builder, config).await.unwrap()
+}
+
+// the listening socket is bound in the background, so it might take a moment to show up
+async fn connect(proxy: &Socks5Proxy) -> TcpStream {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match TcpStream::connect(proxy.listening_address()).await {
+                Ok(stream) => return stream,
+                Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+            }
+        }
+    })
+    .await
+    .expect("the proxy is not accepting any connections")
+}
+
+// offers the provided authentication methods, returning the one chosen by the proxy
+async fn negotiate(stream: &mut TcpStream, methods: &[u8]) -> u8 {
+    let mut greeting = vec![SOCKS5_VERSION, methods.len() as u8];
+    greeting.extend_from_slice(methods);
+    stream.write_all(&greeting).await.unwrap();
+
+    let mut response = [0u8; 2];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(response[0], SOCKS5_VERSION);
+    response[1]
+}
+
+// goes through the username/password subnegotiation, returning whether it succeeded
+async fn log_in(stream: &mut TcpStream, username: &str, password: &str) -> bool {
+    let mut request = vec![0x01, username.len() as u8];
+    request.extend_from_slice(username.as_bytes());
+    request.push(password.len() as u8);
+    request.extend_from_slice(password.as_bytes());
+    stream.write_all(&request).await.unwrap();
+
+    let mut response = [0u8; 2];
+    stream.read_exact(&mut response).await.unwrap();
+    response[1] == 0
+}
+
+#[tokio::test]
+async fn proxy_accepts_unauthenticated_connections_without_any_users() {
+    let proxy = start_proxy(Vec::new()).await;
+
+    let mut stream = connect(&proxy).await;
+    assert_eq!(negotiate(&mut stream, &[NO_AUTH]).await, NO_AUTH);
+
+    let mut stream = connect(&proxy).await;
+    assert_eq!(negotiate(&mut stream, &[USER_PASS]).await, NO_METHODS);
+
+    proxy.stop().await;
+}
+
+#[tokio::test]
+async fn proxy_only_lets_the_allowed_users_in() {
+    let proxy = start_proxy(vec![User {
+        username: "alice".to_string(),
+        password: "secret".to_string(),
+    }])
+    .await;
+
+    let mut stream = connect(&proxy).await;
+    assert_eq!(negotiate(&mut stream, &[NO_AUTH]).await, NO_METHODS);
+
+    let mut stream = connect(&proxy).await;
+    assert_eq!(
+        negotiate(&mut stream, &[NO_AUTH, USER_PASS]).await,
+        USER_PASS
+    );
+    assert!(log_in(&mut stream, "alice", "secret").await);
+
+    let mut stream = connect(&proxy).await;
+    assert_eq!(negotiate(&mut stream, &[USER_PASS]).await, USER_PASS);
+    assert!(!log_in(&mut stream, "alice", "wrong").await);
+
+    proxy.stop().await;
+}
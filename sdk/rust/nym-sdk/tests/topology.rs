@@ -0,0 +1,59 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the sdk client against a local mock gateway with a hand-written topology, to check that
+//! it doesn't need a nym-api in that case.
+
+use common::{GatewayEvent, MockNetwork};
+use nym_sdk::mixnet;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+mod common;
+
+#[tokio::test]
+async fn client_can_be_started_with_a_static_topology() {
+    let mut network = MockNetwork::start().await;
+
+    // the client is pointed at a nym-api that's never going to respond, so that we'd notice
+    // any attempt to use it
+    let nym_api = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let nym_api_url = format!("http://{}", nym_api.local_addr().unwrap())
+        .parse()
+        .unwrap();
+    let config = mixnet::Config::new(None, vec![nym_api_url]);
+
+    let topology =
+        mixnet::StaticTopologyProvider::from_description(mixnet::StaticTopologyDescription {
+            mixnodes: network
+                .mixnodes
+                .iter()
+                .map(|details| details.bond_information.clone())
+                .collect(),
+            gateways: network.gateways.clone(),
+        });
+    let mut client_builder = network.client_builder(config);
+    client_builder.set_topology_provider(Box::new(topology));
+    let mut client = client_builder.connect_to_mixnet().await.unwrap();
+
+    // the client can only send the packets if it could construct the routes through the topology
+    tokio::time::timeout(Duration::from_secs(30), async {
+        let mut packets = 0;
+        while packets < 5 {
+            if let GatewayEvent::Packet = network.gateway_events.recv().await.unwrap() {
+                packets += 1
+            }
+        }
+    })
+    .await
+    .expect("the gateway has not received any packets");
+
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), nym_api.accept())
+            .await
+            .is_err(),
+        "the client has queried the nym-api"
+    );
+
+    client.disconnect().await;
+}
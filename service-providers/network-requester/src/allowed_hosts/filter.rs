@@ -1,11 +1,14 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 
+use std::io;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 
-use super::HostsStore;
+use super::rule::{HostRule, Protocol, RequestHost, RequestTarget};
+use super::{HostsStore, RulesStore};
 
-/// Filters outbound requests based on what's in an `allowed_hosts` list.
+/// Filters outbound requests based on the rules in an `allowed_hosts` list.
 ///
 /// Requests to unknown hosts are automatically written to an `unknown_hosts`
 /// list so that they can be copy/pasted into the `allowed_hosts` list if desired.
@@ -13,94 +16,140 @@ use super::HostsStore;
 /// `unknown_hosts` file and allow new hosts (e.g. if a wallet has added a new outbound request
 /// which needs to be allowed).
 ///
-/// We rely on the list of domains at https://publicsuffix.org/ to figure out what the root
-/// domain is for a given request. This allows us to distinguish all the rules for e.g.
-/// .com, .co.uk, .co.jp, uk.com, etc, so that we can distinguish correct root-ish
-/// domains as allowed. That list is loaded once at startup from Mozilla's canonical
-/// publicsuffix list.
+/// Deny rules always take precedence over the allow rules. Unless explicitly disabled, requests
+/// to private, loopback and link-local addresses are denied as well. Both of them apply even if
+/// the network requester runs as an open proxy. Note that at this point the domains are not
+/// resolved, so the addresses they resolve to have to be checked with the [`ResolvedAddressFilter`]
+/// before connecting.
+///
+/// We rely on the list of domains at https://publicsuffix.org/ to figure out whether the requested
+/// domain makes any sense. That list is loaded once at startup from Mozilla's canonical
+/// publicsuffix list, or its cached or bundled copy if it can't be fetched.
 pub(crate) struct OutboundRequestFilter {
-    pub(super) allowed_hosts: HostsStore,
+    pub(super) allowed_hosts: RulesStore,
+    private_networks: Arc<Vec<HostRule>>,
     root_domain_list: publicsuffix::List,
     unknown_hosts: HostsStore,
+    open_proxy: bool,
 }
 
 impl OutboundRequestFilter {
-    /// Create a new `OutboundRequestFilter` with the given `allowed_hosts` and `unknown_hosts` lists.
+    /// Create a new `OutboundRequestFilter` with the given `allowed_hosts` and `unknown_hosts` lists
+    /// using the provided https://publicsuffix.org/ domain list for parsing the requested domains.
     ///
-    /// Automatically fetches the latest https://publicsuffix.org/ domain list from the internet so that
-    /// the requester can properly parse all of the world's top-level domains in an up-to-date fashion.
+    /// If `deny_private_networks` is set, all requests to private, loopback and link-local
+    /// addresses are going to be rejected regardless of the content of the `allowed_hosts` list.
     ///
-    /// Automatcially fetches the latest standard allowed list from the Nym website, so that all
-    /// requesters are able to support the same minimal functionality out of the box.
+    /// If `open_proxy` is set, all requests that are not explicitly denied are allowed.
     pub(crate) fn new(
-        allowed_hosts: HostsStore,
+        allowed_hosts: RulesStore,
         unknown_hosts: HostsStore,
+        root_domain_list: publicsuffix::List,
+        deny_private_networks: bool,
+        open_proxy: bool,
     ) -> OutboundRequestFilter {
-        let domain_list = match publicsuffix::List::fetch() {
-            Ok(list) => list,
-            Err(err) => panic!("Couldn't fetch domain list for request filtering, do you have an internet connection?: {err}"),
+        let private_networks = if deny_private_networks {
+            HostRule::private_networks()
+        } else {
+            Vec::new()
         };
 
         OutboundRequestFilter {
             allowed_hosts,
-            root_domain_list: domain_list,
+            private_networks: Arc::new(private_networks),
+            root_domain_list,
             unknown_hosts,
+            open_proxy,
+        }
+    }
+
+    /// Creates the filter for the addresses the allowed hosts resolve to, sharing the deny rules
+    /// with this filter.
+    pub(crate) fn resolved_address_filter(&self) -> ResolvedAddressFilter {
+        ResolvedAddressFilter {
+            rules: self.allowed_hosts.clone(),
+            private_networks: Arc::clone(&self.private_networks),
         }
     }
 
-    /// Returns `true` if a host's root domain is in the `allowed_hosts` list.
+    /// Returns `true` if the host (and port, if specified) is allowed by the `allowed_hosts` rules
+    /// for the given protocol and none of the deny rules matches it.
     ///
-    /// If it's not in the list, return `false` and write it to the `unknown_hosts` storefile.
-    pub(crate) fn check(&mut self, host: &str) -> bool {
-        // first check if it's a socket address (ip:port)
-        // (this check is performed to not incorrectly strip what we think might be a port
-        // from ipv6 address, as for example ::1 contains colons but has no port
-        let allowed = if let Ok(socketaddr) = host.parse::<SocketAddr>() {
-            if !self.allowed_hosts.contains_ip_address(socketaddr.ip()) {
-                self.unknown_hosts.add_ip(socketaddr.ip());
-                return false;
-            }
-            true
-        } else if let Ok(ipaddr) = host.parse::<IpAddr>() {
-            // then check if it was an ip address
-            if !self.allowed_hosts.contains_ip_address(ipaddr) {
-                self.unknown_hosts.add_ip(ipaddr);
-                return false;
-            }
-            true
-        } else {
-            // finally, then assume it might be a domain
-            let trimmed = Self::trim_port(host);
-            if let Some(domain_root) = self.get_domain_root(&trimmed) {
-                // it's a domain
-                if !self.allowed_hosts.contains_domain(&domain_root) {
-                    self.unknown_hosts.add_domain(&trimmed);
-                    return false;
-                }
-                true
-            } else {
+    /// If it's neither allowed nor denied, return `false` and write it to the `unknown_hosts` storefile.
+    pub(crate) fn check(&mut self, host: &str, protocol: Protocol) -> bool {
+        let target = match self.parse_target(host) {
+            Some(target) => target,
+            None => {
                 // it's something else, no idea what, probably some nonsense
-                false
+                log::warn!("Blocked outbound connection to invalid host {:?}", &host);
+                return false;
             }
         };
 
-        if !allowed {
+        if self
+            .private_networks
+            .iter()
+            .any(|rule| rule.matches(&target, protocol))
+        {
             log::warn!(
-                "Blocked outbound connection to {:?}, add it to allowed.list if needed",
+                "Blocked outbound {protocol} connection to private address {:?}",
                 &host
             );
+            return false;
+        }
+
+        if self.allowed_hosts.is_denied(&target, protocol) {
+            log::warn!(
+                "Blocked outbound {protocol} connection to {:?} as it matches a deny rule",
+                &host
+            );
+            return false;
+        }
+
+        if self.open_proxy || self.allowed_hosts.is_allowed(&target, protocol) {
+            return true;
+        }
+
+        match target.host {
+            RequestHost::Ip(address) => self.unknown_hosts.add_ip(address),
+            RequestHost::Domain(domain) => self.unknown_hosts.add_domain(&domain),
         }
 
-        allowed
+        log::warn!(
+            "Blocked outbound {protocol} connection to {:?}, add it to allowed.list if needed",
+            &host
+        );
+        false
     }
 
-    fn trim_port(host: &str) -> String {
-        let mut tmp: Vec<_> = host.split(':').collect();
-        if tmp.len() > 1 {
-            tmp.pop(); // get rid of last element (port)
-            tmp.join(":") //rejoin
-        } else {
-            host.to_string()
+    fn parse_target(&self, host: &str) -> Option<RequestTarget> {
+        // first check if it's a socket address (ip:port)
+        // (this check is performed to not incorrectly strip what we think might be a port
+        // from ipv6 address, as for example ::1 contains colons but has no port
+        if let Ok(socketaddr) = host.parse::<SocketAddr>() {
+            return Some(RequestTarget::new_ip(
+                socketaddr.ip(),
+                Some(socketaddr.port()),
+            ));
+        }
+
+        // then check if it was an ip address
+        if let Ok(ipaddr) = host.parse::<IpAddr>() {
+            return Some(RequestTarget::new_ip(ipaddr, None));
+        }
+
+        // finally, then assume it might be a domain
+        let (domain, port) = Self::split_port(host)?;
+        self.get_domain_root(domain)?;
+        Some(RequestTarget::new_domain(domain, port))
+    }
+
+    /// Splits the host into the domain and the port, if there's any.
+    /// Returns `None` if whatever follows the last colon isn't a valid port.
+    fn split_port(host: &str) -> Option<(&str, Option<u16>)> {
+        match host.rsplit_once(':') {
+            Some((domain, port)) => Some((domain, Some(port.parse().ok()?))),
+            None => Some((host, None)),
         }
     }
 
@@ -122,6 +171,53 @@ impl OutboundRequestFilter {
     }
 }
 
+/// Checks the addresses the requested hosts resolve to, so that an allowed domain pointing at
+/// a private network, or at a denied address, could not be used for bypassing the filter.
+#[derive(Clone)]
+pub(crate) struct ResolvedAddressFilter {
+    rules: RulesStore,
+    private_networks: Arc<Vec<HostRule>>,
+}
+
+impl ResolvedAddressFilter {
+    pub(crate) fn is_permitted(&self, address: SocketAddr, protocol: Protocol) -> bool {
+        let target = RequestTarget::new_ip(address.ip(), Some(address.port()));
+        !self
+            .private_networks
+            .iter()
+            .any(|rule| rule.matches(&target, protocol))
+            && !self.rules.is_denied(&target, protocol)
+    }
+
+    /// Resolves the host and returns all of its addresses that can be connected to.
+    /// Fails if there are none.
+    pub(crate) async fn resolve(
+        &self,
+        host: &str,
+        protocol: Protocol,
+    ) -> io::Result<Vec<SocketAddr>> {
+        let mut resolved = tokio::net::lookup_host(host).await?.peekable();
+        if resolved.peek().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("could not resolve {host}"),
+            ));
+        }
+
+        let permitted = resolved
+            .filter(|address| self.is_permitted(*address, protocol))
+            .collect::<Vec<_>>();
+        if permitted.is_empty() {
+            log::warn!("Blocked outbound {protocol} connection to {host:?} as it resolves to a denied address");
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{host} does not resolve to any permitted address"),
+            ));
+        }
+        Ok(permitted)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -130,21 +226,34 @@ mod tests {
     };
 
     use super::*;
+    use crate::allowed_hosts::public_suffix;
 
     #[cfg(test)]
-    mod trimming_port_information {
+    mod splitting_port_information {
         use super::*;
 
         #[test]
         fn happens_when_port_exists() {
             let host = "nymtech.net:9999";
-            assert_eq!("nymtech.net", OutboundRequestFilter::trim_port(host));
+            assert_eq!(
+                Some(("nymtech.net", Some(9999))),
+                OutboundRequestFilter::split_port(host)
+            );
         }
 
         #[test]
         fn doesnt_happen_when_no_port_exists() {
             let host = "nymtech.net";
-            assert_eq!("nymtech.net", OutboundRequestFilter::trim_port(host));
+            assert_eq!(
+                Some(("nymtech.net", None)),
+                OutboundRequestFilter::split_port(host)
+            );
+        }
+
+        #[test]
+        fn fails_on_invalid_port() {
+            let host = "nymtech.net:foomp";
+            assert_eq!(None, OutboundRequestFilter::split_port(host));
         }
     }
 
@@ -158,9 +267,9 @@ mod tests {
             let base_dir = test_base_dir();
             let allowed_filename = PathBuf::from(format!("allowed-{}.list", random_string()));
             let unknown_filename = PathBuf::from(&format!("unknown-{}.list", random_string()));
-            let allowed = HostsStore::new(base_dir.clone(), allowed_filename, None);
+            let allowed = RulesStore::new(base_dir.clone(), allowed_filename, None);
             let unknown = HostsStore::new(base_dir, unknown_filename, None);
-            OutboundRequestFilter::new(allowed, unknown)
+            OutboundRequestFilter::new(allowed, unknown, public_suffix::bundled(), true, false)
         }
 
         #[test]
@@ -225,26 +334,26 @@ mod tests {
             let base_dir = test_base_dir();
             let allowed_filename = PathBuf::from(format!("allowed-{}.list", random_string()));
             let unknown_filename = PathBuf::from(&format!("unknown-{}.list", random_string()));
-            let allowed = HostsStore::new(base_dir.clone(), allowed_filename, None);
+            let allowed = RulesStore::new(base_dir.clone(), allowed_filename, None);
             let unknown = HostsStore::new(base_dir, unknown_filename, None);
-            OutboundRequestFilter::new(allowed, unknown)
+            OutboundRequestFilter::new(allowed, unknown, public_suffix::bundled(), true, false)
         }
 
         #[test]
         fn are_not_allowed() {
             let host = "unknown.com";
             let mut filter = setup();
-            assert!(!filter.check(host));
+            assert!(!filter.check(host, Protocol::Tcp));
         }
 
         #[test]
         fn get_appended_once_to_the_unknown_hosts_list() {
             let host = "unknown.com";
             let mut filter = setup();
-            filter.check(host);
+            filter.check(host, Protocol::Tcp);
             assert_eq!(1, filter.unknown_hosts.domains.len());
             assert!(filter.unknown_hosts.domains.contains("unknown.com"));
-            filter.check(host);
+            filter.check(host, Protocol::Tcp);
             assert_eq!(1, filter.unknown_hosts.domains.len());
             assert!(filter.unknown_hosts.domains.contains("unknown.com"));
         }
@@ -255,16 +364,7 @@ mod tests {
         use super::*;
 
         fn setup(allowed: &[&str]) -> OutboundRequestFilter {
-            let (allowed_storefile, base_dir1, allowed_filename) = create_test_storefile();
-            let (_, base_dir2, unknown_filename) = create_test_storefile();
-
-            for allowed_host in allowed {
-                HostsStore::append(&allowed_storefile, allowed_host)
-            }
-
-            let allowed = HostsStore::new(base_dir1, allowed_filename, None);
-            let unknown = HostsStore::new(base_dir2, unknown_filename, None);
-            OutboundRequestFilter::new(allowed, unknown)
+            setup_with_private_networks(allowed, false)
         }
 
         #[test]
@@ -272,15 +372,45 @@ mod tests {
             let host = "nymtech.net";
 
             let mut filter = setup(&["nymtech.net"]);
-            assert!(filter.check(host));
+            assert!(filter.check(host, Protocol::Tcp));
         }
 
         #[test]
-        fn are_allowed_for_subdomains() {
-            let host = "foomp.nymtech.net";
+        fn are_allowed_for_subdomains_of_wildcards() {
+            let mut filter = setup(&["*.nymtech.net"]);
+            assert!(filter.check("nymtech.net", Protocol::Tcp));
+            assert!(filter.check("foomp.nymtech.net", Protocol::Tcp));
+            assert!(filter.check("foomp.nymtech.net:443", Protocol::Tcp));
+            assert!(!filter.check("foompnymtech.net", Protocol::Tcp));
+        }
 
+        #[test]
+        fn are_not_allowed_for_subdomains_of_exact_hosts() {
             let mut filter = setup(&["nymtech.net"]);
-            assert!(filter.check(host));
+            assert!(!filter.check("foomp.nymtech.net", Protocol::Tcp));
+        }
+
+        #[test]
+        fn are_allowed_only_on_listed_ports() {
+            let mut filter = setup(&["nymtech.net:80,443", "1.1.1.1:8000-8100", "[::2]:53 udp"]);
+            assert!(filter.check("nymtech.net:443", Protocol::Tcp));
+            assert!(!filter.check("nymtech.net:22", Protocol::Tcp));
+            assert!(!filter.check("nymtech.net", Protocol::Tcp));
+            assert!(filter.check("1.1.1.1:8050", Protocol::Tcp));
+            assert!(!filter.check("1.1.1.1:9000", Protocol::Tcp));
+            assert!(filter.check("[::2]:53", Protocol::Udp));
+            assert!(!filter.check("[::2]:53", Protocol::Tcp));
+        }
+
+        #[test]
+        fn are_not_allowed_if_denied() {
+            let mut filter = setup(&["*.nymtech.net", "!secret.nymtech.net", "!*.nymtech.net:22"]);
+            assert!(filter.check("nymtech.net:443", Protocol::Tcp));
+            assert!(!filter.check("secret.nymtech.net:443", Protocol::Tcp));
+            assert!(!filter.check("foomp.nymtech.net:22", Protocol::Tcp));
+
+            // denied hosts are not unknown
+            assert!(filter.unknown_hosts.domains.is_empty());
         }
 
         #[test]
@@ -288,13 +418,13 @@ mod tests {
             let mut filter = setup(&["nymtech.net"]);
 
             // test initial state
-            let lines = HostsStore::load_from_storefile(&filter.allowed_hosts.storefile).unwrap();
+            let lines = RulesStore::load_from_storefile(&filter.allowed_hosts.storefile).unwrap();
             assert_eq!(1, lines.len());
 
-            filter.check("nymtech.net");
+            filter.check("nymtech.net", Protocol::Tcp);
 
             // test state after we've checked to make sure no unexpected changes
-            let lines = HostsStore::load_from_storefile(&filter.allowed_hosts.storefile).unwrap();
+            let lines = RulesStore::load_from_storefile(&filter.allowed_hosts.storefile).unwrap();
             assert_eq!(1, lines.len());
         }
 
@@ -305,9 +435,9 @@ mod tests {
             let address_bad = "1.1.1.2";
 
             let mut filter = setup(&["1.1.1.1"]);
-            assert!(filter.check(address_good));
-            assert!(filter.check(address_good_port));
-            assert!(!filter.check(address_bad));
+            assert!(filter.check(address_good, Protocol::Tcp));
+            assert!(filter.check(address_good_port, Protocol::Tcp));
+            assert!(!filter.check(address_bad, Protocol::Tcp));
         }
 
         #[test]
@@ -324,19 +454,19 @@ mod tests {
             let mut filter1 = setup(&[ip_v6_full, ip_v6_semi, "::1"]);
             let mut filter2 = setup(&[ip_v6_full_rendered, ip_v6_semi_rendered, "::1"]);
 
-            assert!(filter1.check(ip_v6_full));
-            assert!(filter1.check(ip_v6_full_rendered));
-            assert!(filter1.check(ip_v6_full_port));
-            assert!(filter1.check(ip_v6_semi));
-            assert!(filter1.check(ip_v6_semi_rendered));
-            assert!(filter1.check(ip_v6_loopback_port));
-
-            assert!(filter2.check(ip_v6_full));
-            assert!(filter2.check(ip_v6_full_rendered));
-            assert!(filter2.check(ip_v6_full_port));
-            assert!(filter2.check(ip_v6_semi));
-            assert!(filter2.check(ip_v6_semi_rendered));
-            assert!(filter2.check(ip_v6_loopback_port));
+            assert!(filter1.check(ip_v6_full, Protocol::Tcp));
+            assert!(filter1.check(ip_v6_full_rendered, Protocol::Tcp));
+            assert!(filter1.check(ip_v6_full_port, Protocol::Tcp));
+            assert!(filter1.check(ip_v6_semi, Protocol::Tcp));
+            assert!(filter1.check(ip_v6_semi_rendered, Protocol::Tcp));
+            assert!(filter1.check(ip_v6_loopback_port, Protocol::Tcp));
+
+            assert!(filter2.check(ip_v6_full, Protocol::Tcp));
+            assert!(filter2.check(ip_v6_full_rendered, Protocol::Tcp));
+            assert!(filter2.check(ip_v6_full_port, Protocol::Tcp));
+            assert!(filter2.check(ip_v6_semi, Protocol::Tcp));
+            assert!(filter2.check(ip_v6_semi_rendered, Protocol::Tcp));
+            assert!(filter2.check(ip_v6_loopback_port, Protocol::Tcp));
         }
 
         #[test]
@@ -350,11 +480,11 @@ mod tests {
             let outside_range2 = "1.2.2.4";
 
             let mut filter = setup(&[range1, range2]);
-            assert!(filter.check("127.0.0.1"));
-            assert!(filter.check("127.0.0.1:1234"));
-            assert!(filter.check(bottom_range2));
-            assert!(filter.check(top_range2));
-            assert!(!filter.check(outside_range2));
+            assert!(filter.check("127.0.0.1", Protocol::Tcp));
+            assert!(filter.check("127.0.0.1:1234", Protocol::Tcp));
+            assert!(filter.check(bottom_range2, Protocol::Tcp));
+            assert!(filter.check(top_range2, Protocol::Tcp));
+            assert!(!filter.check(outside_range2, Protocol::Tcp));
         }
 
         #[test]
@@ -368,11 +498,113 @@ mod tests {
             let mid = "2620:0:42::42";
 
             let mut filter = setup(&[range]);
-            assert!(filter.check(bottom1));
-            assert!(filter.check(bottom2));
-            assert!(filter.check(top));
-            assert!(filter.check(mid));
+            assert!(filter.check(bottom1, Protocol::Tcp));
+            assert!(filter.check(bottom2, Protocol::Tcp));
+            assert!(filter.check(top, Protocol::Tcp));
+            assert!(filter.check(mid, Protocol::Tcp));
+        }
+    }
+
+    #[cfg(test)]
+    mod requests_to_private_networks {
+        use super::*;
+
+        #[test]
+        fn are_denied_by_default() {
+            let mut filter =
+                setup_with_private_networks(&["0.0.0.0/0", "::/0", "*.localhost"], true);
+            assert!(filter.check("1.1.1.1:80", Protocol::Tcp));
+            assert!(!filter.check("127.0.0.1:80", Protocol::Tcp));
+            assert!(!filter.check("192.168.1.1:80", Protocol::Udp));
+            assert!(!filter.check("10.1.2.3", Protocol::Tcp));
+            assert!(!filter.check("172.16.0.1:22", Protocol::Tcp));
+            assert!(!filter.check("169.254.169.254:80", Protocol::Tcp));
+            assert!(!filter.check("[::1]:80", Protocol::Tcp));
+            assert!(!filter.check("[fe80::1]:80", Protocol::Tcp));
+            assert!(!filter.check("[::ffff:127.0.0.1]:80", Protocol::Tcp));
+            assert!(!filter.check("localhost:80", Protocol::Tcp));
         }
+
+        #[test]
+        fn can_be_allowed() {
+            let mut filter = setup_with_private_networks(&["192.168.0.0/16", "::1"], false);
+            assert!(filter.check("192.168.1.1:80", Protocol::Tcp));
+            assert!(filter.check("[::1]:80", Protocol::Tcp));
+        }
+
+        #[test]
+        fn are_denied_for_open_proxies() {
+            let mut filter = setup_filter(&["!1.1.1.1"], true, true);
+            assert!(filter.check("nymtech.net:443", Protocol::Tcp));
+            assert!(filter.check("8.8.8.8:53", Protocol::Udp));
+            assert!(!filter.check("127.0.0.1:80", Protocol::Tcp));
+            assert!(!filter.check("[::1]:80", Protocol::Tcp));
+            assert!(!filter.check("1.1.1.1:80", Protocol::Tcp));
+            assert!(filter.unknown_hosts.domains.is_empty());
+        }
+
+        #[tokio::test]
+        async fn are_denied_after_resolving_the_domains() {
+            let filter = setup_with_private_networks(&["localhost"], true);
+            let resolved_filter = filter.resolved_address_filter();
+
+            assert!(!resolved_filter.is_permitted("127.0.0.1:80".parse().unwrap(), Protocol::Tcp));
+            assert!(resolved_filter.is_permitted("1.1.1.1:80".parse().unwrap(), Protocol::Tcp));
+            let err = resolved_filter
+                .resolve("localhost:80", Protocol::Tcp)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        }
+
+        #[tokio::test]
+        async fn resolved_addresses_can_be_allowed() {
+            let filter = setup_with_private_networks(&["localhost"], false);
+            let resolved = filter
+                .resolved_address_filter()
+                .resolve("localhost:80", Protocol::Tcp)
+                .await
+                .unwrap();
+            assert!(resolved.iter().all(|address| address.ip().is_loopback()));
+        }
+
+        #[test]
+        fn resolved_addresses_are_subject_to_deny_rules() {
+            let filter = setup_with_private_networks(&["*.nymtech.net", "!1.1.1.0/24"], false);
+            let resolved_filter = filter.resolved_address_filter();
+            assert!(!resolved_filter.is_permitted("1.1.1.1:443".parse().unwrap(), Protocol::Tcp));
+            assert!(resolved_filter.is_permitted("1.1.2.1:443".parse().unwrap(), Protocol::Tcp));
+        }
+    }
+
+    fn setup_with_private_networks(
+        allowed: &[&str],
+        deny_private_networks: bool,
+    ) -> OutboundRequestFilter {
+        setup_filter(allowed, deny_private_networks, false)
+    }
+
+    fn setup_filter(
+        allowed: &[&str],
+        deny_private_networks: bool,
+        open_proxy: bool,
+    ) -> OutboundRequestFilter {
+        let (allowed_storefile, base_dir1, allowed_filename) = create_test_storefile();
+        let (_, base_dir2, unknown_filename) = create_test_storefile();
+
+        for allowed_host in allowed {
+            HostsStore::append(&allowed_storefile, allowed_host)
+        }
+
+        let allowed = RulesStore::new(base_dir1, allowed_filename, None);
+        let unknown = HostsStore::new(base_dir2, unknown_filename, None);
+        OutboundRequestFilter::new(
+            allowed,
+            unknown,
+            public_suffix::bundled(),
+            deny_private_networks,
+            open_proxy,
+        )
     }
 
     fn random_string() -> String {
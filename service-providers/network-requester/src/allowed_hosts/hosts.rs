@@ -9,7 +9,7 @@ use std::{
 use super::host::Host;
 use ipnetwork::IpNetwork;
 
-/// A simple file-backed store for information about allowed / unknown hosts.
+/// A simple file-backed store for information about unknown hosts.
 /// It ignores any port information.
 #[derive(Debug)]
 pub(crate) struct HostsStore {
@@ -102,7 +102,7 @@ impl HostsStore {
             .join(".nym")
     }
 
-    fn setup_storefile(base_dir: PathBuf, filename: PathBuf) -> PathBuf {
+    pub(super) fn setup_storefile(base_dir: PathBuf, filename: PathBuf) -> PathBuf {
         let dirpath = base_dir.join("service-providers").join("network-requester");
         fs::create_dir_all(&dirpath)
             .unwrap_or_else(|_| panic!("could not create storage directory at {:?}", dirpath));
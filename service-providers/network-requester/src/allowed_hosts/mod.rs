@@ -1,8 +1,18 @@
 mod filter;
 mod host;
 mod hosts;
+mod public_suffix;
+mod reloader;
+mod rule;
+mod rules;
+mod sources;
 mod standard_list;
 
-pub(crate) use filter::OutboundRequestFilter;
+pub(crate) use filter::{OutboundRequestFilter, ResolvedAddressFilter};
 pub(crate) use hosts::HostsStore;
-pub(crate) use standard_list::fetch as fetch_standard_allowed_list;
+pub(crate) use public_suffix::load as load_public_suffix_list;
+pub(crate) use reloader::AllowedHostsReloader;
+pub(crate) use rule::Protocol;
+pub(crate) use rules::RulesStore;
+pub(crate) use sources::ListSources;
+pub(crate) use standard_list::load as load_standard_allowed_list;
@@ -0,0 +1,53 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::sources::{cache_list, fetch_list, read_cached_list, ListSources};
+
+/// Snapshot of https://publicsuffix.org/list/public_suffix_list.dat used if neither the up-to-date
+/// list nor any previously cached version of it is available.
+const BUNDLED_PUBLIC_SUFFIX_LIST: &str = include_str!("public_suffix_list.dat");
+
+/// Returns the public suffix list bundled with the binary.
+pub(crate) fn bundled() -> publicsuffix::List {
+    publicsuffix::List::from_str(BUNDLED_PUBLIC_SUFFIX_LIST)
+        .expect("the bundled public suffix list is malformed")
+}
+
+/// Loads the public suffix list, so that the requester could properly parse all of the world's
+/// top-level domains. It attempts to fetch the latest version of the list and if that fails,
+/// it falls back to the last successfully fetched version and finally to the bundled snapshot.
+pub(crate) async fn load(sources: &ListSources) -> publicsuffix::List {
+    let cache = sources.public_suffix_list_cache();
+
+    match fetch_list(&sources.public_suffix_list_url).await {
+        Ok(content) => match publicsuffix::List::from_str(&content) {
+            Ok(list) => {
+                cache_list(&cache, &content);
+                return list;
+            }
+            Err(err) => log::warn!(
+                "The public suffix list fetched from {} is malformed: {err}",
+                sources.public_suffix_list_url
+            ),
+        },
+        Err(err) => log::warn!(
+            "Could not fetch the public suffix list from {}: {err}",
+            sources.public_suffix_list_url
+        ),
+    }
+
+    if let Some(content) = read_cached_list(&cache) {
+        match publicsuffix::List::from_str(&content) {
+            Ok(list) => {
+                log::warn!("Using the previously cached public suffix list from {cache:?}");
+                return list;
+            }
+            Err(err) => {
+                log::warn!("The cached public suffix list at {cache:?} is malformed: {err}")
+            }
+        }
+    }
+
+    log::warn!("Using the public suffix list bundled with the binary, it might be outdated");
+    bundled()
+}
@@ -0,0 +1,176 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::rule::HostRule;
+use super::rules::RuleSetChanges;
+use super::{standard_list, ListSources, RulesStore};
+use crate::statistics::ServiceStatisticsCollector;
+use statistics_common::StatsAllowedHostsReloadData;
+use std::fs;
+use std::time::{Duration, SystemTime};
+use task::TaskClient;
+
+/// How often the allowed.list storefile is checked for modifications.
+const STOREFILE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the standard allowed list is fetched again.
+const STANDARD_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Signal requesting an immediate reload, i.e. SIGHUP on unix systems.
+#[cfg(unix)]
+struct ReloadSignal(tokio::signal::unix::Signal);
+
+#[cfg(unix)]
+impl ReloadSignal {
+    fn new() -> Self {
+        use tokio::signal::unix::{signal, SignalKind};
+        ReloadSignal(signal(SignalKind::hangup()).expect("Failed to setup SIGHUP channel"))
+    }
+
+    async fn recv(&mut self) {
+        self.0.recv().await;
+    }
+}
+
+#[cfg(not(unix))]
+struct ReloadSignal;
+
+#[cfg(not(unix))]
+impl ReloadSignal {
+    fn new() -> Self {
+        ReloadSignal
+    }
+
+    async fn recv(&mut self) {
+        futures::future::pending::<()>().await
+    }
+}
+
+/// Keeps the rules of the `allowed_hosts` store up to date without having to restart
+/// the requester: reloads them whenever the storefile gets modified or SIGHUP is received
+/// and periodically fetches the standard allowed list again.
+///
+/// If the standard list can't be fetched, the last successfully fetched version keeps being used.
+/// Whenever the rules change, it's also reported to the statistics service, if enabled.
+pub(crate) struct AllowedHostsReloader {
+    allowed_hosts: RulesStore,
+    sources: ListSources,
+    standard_rules: Vec<HostRule>,
+    storefile_modified: Option<SystemTime>,
+    stats_collector: Option<ServiceStatisticsCollector>,
+}
+
+impl AllowedHostsReloader {
+    pub(crate) fn new(
+        allowed_hosts: RulesStore,
+        sources: ListSources,
+        standard_rules: Vec<HostRule>,
+    ) -> Self {
+        let storefile_modified = Self::modification_time(&allowed_hosts);
+        AllowedHostsReloader {
+            allowed_hosts,
+            sources,
+            standard_rules,
+            storefile_modified,
+            stats_collector: None,
+        }
+    }
+
+    fn modification_time(allowed_hosts: &RulesStore) -> Option<SystemTime> {
+        fs::metadata(&allowed_hosts.storefile)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    async fn report_changes(&self, reason: &str, changes: RuleSetChanges) {
+        if let Some(stats_collector) = &self.stats_collector {
+            let to_strings =
+                |rules: Vec<HostRule>| rules.iter().map(ToString::to_string).collect::<Vec<_>>();
+            stats_collector.allowed_hosts_reloads.write().await.push(
+                StatsAllowedHostsReloadData::new(
+                    reason.to_string(),
+                    to_strings(changes.added),
+                    to_strings(changes.removed),
+                ),
+            );
+        }
+    }
+
+    async fn reload(&mut self, reason: &str) {
+        self.storefile_modified = Self::modification_time(&self.allowed_hosts);
+        match self.allowed_hosts.reload(&self.standard_rules) {
+            Ok(changes) if changes.is_empty() => {
+                log::debug!("Reloaded allowed hosts ({reason}), no rules have changed")
+            }
+            Ok(changes) => {
+                log::info!("Reloaded allowed hosts ({reason}): {changes}");
+                self.report_changes(reason, changes).await
+            }
+            Err(err) => log::error!(
+                "Failed to reload allowed hosts from {:?} ({reason}), the previous rules remain in use: {err}",
+                self.allowed_hosts.storefile
+            ),
+        }
+    }
+
+    async fn check_storefile(&mut self) {
+        let modified = Self::modification_time(&self.allowed_hosts);
+        if modified.is_some() && modified != self.storefile_modified {
+            self.reload("allowed.list got modified").await
+        }
+    }
+
+    async fn refresh_standard_list(&mut self, reason: &str) {
+        match standard_list::fetch(&self.sources).await {
+            Ok(standard_rules) => {
+                self.standard_rules = standard_rules;
+                self.reload(reason).await
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to refresh the standard allowed list, keeping the {} previously fetched rules: {err}",
+                    self.standard_rules.len()
+                );
+                // the storefile might have still changed in the meantime
+                self.reload(reason).await
+            }
+        }
+    }
+
+    pub(crate) async fn run(&mut self, mut shutdown: TaskClient) {
+        log::debug!("Started AllowedHostsReloader with graceful shutdown support");
+
+        let mut reload_signal = ReloadSignal::new();
+        let mut storefile_check = tokio::time::interval(STOREFILE_CHECK_INTERVAL);
+        let start = tokio::time::Instant::now() + STANDARD_LIST_REFRESH_INTERVAL;
+        let mut standard_list_refresh =
+            tokio::time::interval_at(start, STANDARD_LIST_REFRESH_INTERVAL);
+
+        while !shutdown.is_shutdown() {
+            tokio::select! {
+                _ = storefile_check.tick() => self.check_storefile().await,
+                _ = standard_list_refresh.tick() => {
+                    self.refresh_standard_list("periodic standard list refresh").await
+                }
+                _ = reload_signal.recv() => {
+                    log::info!("Received SIGHUP, reloading allowed hosts");
+                    self.refresh_standard_list("SIGHUP").await
+                }
+                _ = shutdown.recv() => {
+                    log::trace!("AllowedHostsReloader: Received shutdown");
+                }
+            }
+        }
+
+        log::trace!("AllowedHostsReloader: Exiting");
+    }
+
+    pub(crate) fn start(
+        mut self,
+        stats_collector: Option<ServiceStatisticsCollector>,
+        shutdown: TaskClient,
+    ) {
+        self.stats_collector = stats_collector;
+        tokio::spawn(async move { self.run(shutdown).await });
+    }
+}
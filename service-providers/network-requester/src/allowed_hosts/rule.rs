@@ -0,0 +1,487 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use ipnetwork::IpNetwork;
+use std::fmt::{self, Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// Networks that are denied by default so that the requester couldn't be used for reaching
+/// the operator's local network: RFC1918, loopback, link-local and their ipv6 equivalents.
+pub(crate) const PRIVATE_NETWORKS: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "::/128",
+    "::1/128",
+    "fc00::/7",
+    "fe80::/10",
+];
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub(crate) enum RuleParseError {
+    #[error("the rule is empty")]
+    Empty,
+
+    #[error("'{0}' is not a valid host")]
+    InvalidHost(String),
+
+    #[error("'{0}' is not a valid port or port range")]
+    InvalidPorts(String),
+
+    #[error("'{0}' is not a supported protocol, expected either 'tcp' or 'udp'")]
+    InvalidProtocol(String),
+
+    #[error("unexpected trailing content: '{0}'")]
+    TrailingContent(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl FromStr for Protocol {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tcp" => Ok(Protocol::Tcp),
+            "udp" => Ok(Protocol::Udp),
+            _ => Err(RuleParseError::InvalidProtocol(s.to_string())),
+        }
+    }
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+/// Inclusive range of ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PortRange {
+    start: u16,
+    end: u16,
+}
+
+impl PortRange {
+    fn contains(&self, port: u16) -> bool {
+        self.start <= port && port <= self.end
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RuleParseError::InvalidPorts(s.to_string());
+        let (start, end) = match s.split_once('-') {
+            Some((start, end)) => (
+                start.trim().parse().map_err(|_| invalid())?,
+                end.trim().parse().map_err(|_| invalid())?,
+            ),
+            None => {
+                let port = s.trim().parse().map_err(|_| invalid())?;
+                (port, port)
+            }
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        Ok(PortRange { start, end })
+    }
+}
+
+impl Display for PortRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HostPattern {
+    /// Matches only the exact domain, e.g. `example.com`.
+    Domain(String),
+
+    /// Matches the domain and all of its subdomains, e.g. `*.example.com`.
+    Subdomains(String),
+
+    /// Matches any address within the network, e.g. `1.2.3.0/24` or `1.2.3.4`.
+    IpNetwork(IpNetwork),
+}
+
+impl HostPattern {
+    fn parse(raw: &str) -> Result<Self, RuleParseError> {
+        if let Ok(ipnet) = raw.parse() {
+            return Ok(HostPattern::IpNetwork(ipnet));
+        }
+
+        let invalid = || RuleParseError::InvalidHost(raw.to_string());
+        let (domain, subdomains) = match raw.strip_prefix("*.") {
+            Some(domain) => (domain, true),
+            None => (raw, false),
+        };
+        let domain = normalise_domain(domain);
+        let is_valid = !domain.is_empty()
+            && domain
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '.' || c == '_');
+        if !is_valid {
+            return Err(invalid());
+        }
+
+        if subdomains {
+            Ok(HostPattern::Subdomains(domain))
+        } else {
+            Ok(HostPattern::Domain(domain))
+        }
+    }
+
+    fn matches(&self, host: &RequestHost) -> bool {
+        match (self, host) {
+            (HostPattern::Domain(domain), RequestHost::Domain(requested)) => domain == requested,
+            (HostPattern::Subdomains(domain), RequestHost::Domain(requested)) => {
+                is_same_or_subdomain(requested, domain)
+            }
+            (HostPattern::IpNetwork(ipnet), RequestHost::Ip(address)) => ipnet.contains(*address),
+            _ => false,
+        }
+    }
+}
+
+impl Display for HostPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HostPattern::Domain(domain) => write!(f, "{domain}"),
+            HostPattern::Subdomains(domain) => write!(f, "*.{domain}"),
+            HostPattern::IpNetwork(ipnet) => write!(f, "{}", display_network(ipnet)),
+        }
+    }
+}
+
+/// Single line of the allowed hosts list. It has the following form:
+///
+/// `[!]<host>[:<ports>] [tcp|udp]`
+///
+/// where
+/// - the leading `!` turns the rule into a deny rule, which takes precedence over all allow rules,
+/// - `<host>` is either an exact domain (`example.com`), a domain with all of its subdomains
+///   (`*.example.com`), an ip address or a network in CIDR notation (`1.2.3.0/24`).
+///   Ipv6 addresses have to be put in square brackets if they're followed by ports (`[::1]:80`),
+/// - `<ports>` is a comma separated list of ports and inclusive port ranges (`80,443,8000-9000`).
+///   If omitted, the rule applies to all ports,
+/// - the protocol restricts the rule to either tcp or udp traffic. If omitted, the rule applies to both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HostRule {
+    pub(crate) deny: bool,
+    pub(crate) host: HostPattern,
+    pub(crate) ports: Vec<PortRange>,
+    pub(crate) protocol: Option<Protocol>,
+}
+
+impl HostRule {
+    fn deny(host: HostPattern) -> Self {
+        HostRule {
+            deny: true,
+            host,
+            ports: Vec::new(),
+            protocol: None,
+        }
+    }
+
+    /// Rules denying all requests to private, loopback and link-local addresses.
+    pub(crate) fn private_networks() -> Vec<HostRule> {
+        let mut rules: Vec<_> = PRIVATE_NETWORKS
+            .iter()
+            .map(|network| HostRule::deny(HostPattern::IpNetwork(network.parse().unwrap())))
+            .collect();
+        rules.push(HostRule::deny(HostPattern::Subdomains(
+            "localhost".to_string(),
+        )));
+        rules
+    }
+
+    /// Extends exact domain rules to also cover all of the subdomains.
+    pub(crate) fn with_subdomains(self) -> Self {
+        match self.host {
+            HostPattern::Domain(domain) => HostRule {
+                host: HostPattern::Subdomains(domain),
+                ..self
+            },
+            _ => self,
+        }
+    }
+
+    pub(crate) fn matches(&self, target: &RequestTarget, protocol: Protocol) -> bool {
+        if let Some(rule_protocol) = self.protocol {
+            if rule_protocol != protocol {
+                return false;
+            }
+        }
+
+        if !self.ports.is_empty() {
+            match target.port {
+                Some(port) if self.ports.iter().any(|range| range.contains(port)) => (),
+                _ => return false,
+            }
+        }
+
+        self.host.matches(&target.host)
+    }
+
+    fn parse_ports(raw: &str) -> Result<Vec<PortRange>, RuleParseError> {
+        if raw.trim().is_empty() {
+            return Err(RuleParseError::InvalidPorts(raw.to_string()));
+        }
+        raw.split(',').map(str::parse).collect()
+    }
+
+    fn parse_destination(raw: &str) -> Result<(HostPattern, Vec<PortRange>), RuleParseError> {
+        // ipv6 with ports, e.g. `[::1]:80` or `[fe80::/10]:80`
+        if let Some(bracketed) = raw.strip_prefix('[') {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .ok_or_else(|| RuleParseError::InvalidHost(raw.to_string()))?;
+            let host: IpNetwork = host
+                .parse()
+                .map_err(|_| RuleParseError::InvalidHost(raw.to_string()))?;
+            let ports = match rest {
+                "" => Vec::new(),
+                rest => match rest.strip_prefix(':') {
+                    Some(ports) => Self::parse_ports(ports)?,
+                    None => return Err(RuleParseError::TrailingContent(rest.to_string())),
+                },
+            };
+            return Ok((HostPattern::IpNetwork(host), ports));
+        }
+
+        // bare ipv6 addresses contain colons, so try to parse it without the ports first
+        if let Ok(ipnet) = raw.parse() {
+            return Ok((HostPattern::IpNetwork(ipnet), Vec::new()));
+        }
+
+        match raw.rsplit_once(':') {
+            Some((host, ports)) => Ok((HostPattern::parse(host)?, Self::parse_ports(ports)?)),
+            None => Ok((HostPattern::parse(raw)?, Vec::new())),
+        }
+    }
+}
+
+impl FromStr for HostRule {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let destination = tokens.next().ok_or(RuleParseError::Empty)?;
+        let protocol = tokens.next().map(str::parse).transpose()?;
+        if let Some(trailing) = tokens.next() {
+            return Err(RuleParseError::TrailingContent(trailing.to_string()));
+        }
+
+        let (deny, destination) = match destination.strip_prefix('!') {
+            Some(destination) => (true, destination),
+            None => (false, destination),
+        };
+        let (host, ports) = Self::parse_destination(destination)?;
+
+        Ok(HostRule {
+            deny,
+            host,
+            ports,
+            protocol,
+        })
+    }
+}
+
+impl Display for HostRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.deny {
+            write!(f, "!")?;
+        }
+        let has_ports = !self.ports.is_empty();
+        match &self.host {
+            HostPattern::IpNetwork(ipnet) if ipnet.is_ipv6() && has_ports => {
+                write!(f, "[{}]", display_network(ipnet))?
+            }
+            host => write!(f, "{host}")?,
+        }
+        if has_ports {
+            let ports: Vec<_> = self.ports.iter().map(ToString::to_string).collect();
+            write!(f, ":{}", ports.join(","))?;
+        }
+        if let Some(protocol) = self.protocol {
+            write!(f, " {protocol}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RequestHost {
+    Domain(String),
+    Ip(IpAddr),
+}
+
+/// Destination of an outbound request, as requested by the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestTarget {
+    pub(crate) host: RequestHost,
+    pub(crate) port: Option<u16>,
+}
+
+impl RequestTarget {
+    pub(crate) fn new_domain(domain: &str, port: Option<u16>) -> Self {
+        RequestTarget {
+            host: RequestHost::Domain(normalise_domain(domain)),
+            port,
+        }
+    }
+
+    pub(crate) fn new_ip(address: IpAddr, port: Option<u16>) -> Self {
+        // treat ipv4-mapped ipv6 addresses as the ipv4 addresses they are, so that they
+        // wouldn't be used for getting around the ipv4 rules
+        let address = match address {
+            IpAddr::V6(v6) => v6
+                .to_ipv4_mapped()
+                .map(IpAddr::V4)
+                .unwrap_or(IpAddr::V6(v6)),
+            v4 => v4,
+        };
+        RequestTarget {
+            host: RequestHost::Ip(address),
+            port,
+        }
+    }
+}
+
+// single addresses are displayed without the prefix, the same way they were most likely written
+fn display_network(ipnet: &IpNetwork) -> String {
+    let is_single_address = match ipnet {
+        IpNetwork::V4(_) => ipnet.prefix() == 32,
+        IpNetwork::V6(_) => ipnet.prefix() == 128,
+    };
+    if is_single_address {
+        ipnet.ip().to_string()
+    } else {
+        ipnet.to_string()
+    }
+}
+
+fn normalise_domain(domain: &str) -> String {
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn is_same_or_subdomain(requested: &str, domain: &str) -> bool {
+    match requested.strip_suffix(domain) {
+        Some("") => true,
+        Some(prefix) => prefix.ends_with('.'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(raw: &str) -> HostRule {
+        raw.parse().unwrap()
+    }
+
+    fn domain(domain: &str, port: u16) -> RequestTarget {
+        RequestTarget::new_domain(domain, Some(port))
+    }
+
+    fn ip(address: &str, port: u16) -> RequestTarget {
+        RequestTarget::new_ip(address.parse().unwrap(), Some(port))
+    }
+
+    #[test]
+    fn parses_all_supported_forms() {
+        let rules = [
+            "example.com",
+            "*.example.com",
+            "example.com:443",
+            "!example.com:80,443,8000-9000 tcp",
+            "1.2.3.4",
+            "1.2.3.0/24:53 udp",
+            "::1",
+            "[2001:db8::/32]:443",
+            "!fe80::/10",
+        ];
+        for raw in rules {
+            assert_eq!(rule(raw).to_string(), raw);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert_eq!("".parse::<HostRule>(), Err(RuleParseError::Empty));
+        assert!("example.com:".parse::<HostRule>().is_err());
+        assert!("example.com:70000".parse::<HostRule>().is_err());
+        assert!("example.com:90-80".parse::<HostRule>().is_err());
+        assert!("example.com sctp".parse::<HostRule>().is_err());
+        assert!("example.com tcp foo".parse::<HostRule>().is_err());
+        assert!("foo.*.example.com".parse::<HostRule>().is_err());
+        assert!("[::1:80".parse::<HostRule>().is_err());
+    }
+
+    #[test]
+    fn exact_domains_do_not_match_subdomains() {
+        let rule = rule("example.com");
+        assert!(rule.matches(&domain("example.com", 80), Protocol::Tcp));
+        assert!(rule.matches(&domain("EXAMPLE.com.", 80), Protocol::Tcp));
+        assert!(!rule.matches(&domain("foo.example.com", 80), Protocol::Tcp));
+    }
+
+    #[test]
+    fn wildcards_match_the_domain_and_its_subdomains() {
+        let rule = rule("*.example.com");
+        assert!(rule.matches(&domain("example.com", 80), Protocol::Tcp));
+        assert!(rule.matches(&domain("foo.bar.example.com", 80), Protocol::Tcp));
+        assert!(!rule.matches(&domain("badexample.com", 80), Protocol::Tcp));
+        assert!(!rule.matches(&domain("example.com.evil.net", 80), Protocol::Tcp));
+    }
+
+    #[test]
+    fn ports_are_respected() {
+        let rule = rule("example.com:80,8000-8100");
+        assert!(rule.matches(&domain("example.com", 80), Protocol::Tcp));
+        assert!(rule.matches(&domain("example.com", 8000), Protocol::Tcp));
+        assert!(rule.matches(&domain("example.com", 8100), Protocol::Tcp));
+        assert!(!rule.matches(&domain("example.com", 443), Protocol::Tcp));
+        assert!(!rule.matches(
+            &RequestTarget::new_domain("example.com", None),
+            Protocol::Tcp
+        ));
+
+        let rule = self::rule("[::1]:53");
+        assert!(rule.matches(&ip("::1", 53), Protocol::Udp));
+        assert!(!rule.matches(&ip("::1", 54), Protocol::Udp));
+    }
+
+    #[test]
+    fn protocols_are_respected() {
+        let rule = rule("1.1.1.1:53 udp");
+        assert!(rule.matches(&ip("1.1.1.1", 53), Protocol::Udp));
+        assert!(!rule.matches(&ip("1.1.1.1", 53), Protocol::Tcp));
+    }
+
+    #[test]
+    fn ipv4_mapped_addresses_are_treated_as_ipv4() {
+        let rule = rule("10.0.0.0/8");
+        assert!(rule.matches(&ip("::ffff:10.1.2.3", 80), Protocol::Tcp));
+    }
+}
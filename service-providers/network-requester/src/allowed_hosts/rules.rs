@@ -0,0 +1,233 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use super::rule::{HostRule, Protocol, RequestTarget};
+use super::HostsStore;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+/// Allow and deny rules that are currently in use.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct RuleSet {
+    pub(super) allow: Vec<HostRule>,
+    pub(super) deny: Vec<HostRule>,
+}
+
+impl RuleSet {
+    fn new(rules: Vec<HostRule>) -> Self {
+        let (deny, allow) = rules.into_iter().partition(|rule| rule.deny);
+        RuleSet { allow, deny }
+    }
+
+    fn rules(&self) -> impl Iterator<Item = &HostRule> {
+        self.deny.iter().chain(self.allow.iter())
+    }
+
+    /// Determines which rules have to be added to and removed from `self` in order to get `other`.
+    fn changes(&self, other: &RuleSet) -> RuleSetChanges {
+        RuleSetChanges {
+            added: other
+                .rules()
+                .filter(|rule| !self.rules().any(|existing| existing == *rule))
+                .cloned()
+                .collect(),
+            removed: self
+                .rules()
+                .filter(|rule| !other.rules().any(|new| new == *rule))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Description of what changed after the rules got reloaded.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct RuleSetChanges {
+    pub(crate) added: Vec<HostRule>,
+    pub(crate) removed: Vec<HostRule>,
+}
+
+impl RuleSetChanges {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl Display for RuleSetChanges {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let join = |rules: &[HostRule]| {
+            rules
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "{} rule(s) added [{}], {} rule(s) removed [{}]",
+            self.added.len(),
+            join(&self.added),
+            self.removed.len(),
+            join(&self.removed)
+        )
+    }
+}
+
+/// A file-backed set of allow and deny rules for outbound requests.
+///
+/// Empty lines and everything following a `#` are ignored. Lines that can't be parsed
+/// are logged and skipped. See [`HostRule`] for the supported syntax.
+///
+/// The rules are shared between all the clones of the store, so that they could be reloaded
+/// in the background without disturbing the requests that are being checked.
+#[derive(Debug, Clone)]
+pub(crate) struct RulesStore {
+    pub(super) storefile: PathBuf,
+
+    rules: Arc<RwLock<RuleSet>>,
+}
+
+impl RulesStore {
+    /// Constructs a new RulesStore. If the storefile does not exist, it will be created.
+    ///
+    /// You can inject a list of standard rules that you want to support, in addition to the ones
+    /// in the user-defined storefile.
+    pub(crate) fn new(
+        base_dir: PathBuf,
+        filename: PathBuf,
+        standard_rules: Option<Vec<HostRule>>,
+    ) -> RulesStore {
+        let storefile = HostsStore::setup_storefile(base_dir, filename);
+        let mut rules = Self::load_from_storefile(&storefile)
+            .unwrap_or_else(|_| panic!("Could not load rules from storefile at {:?}", storefile));
+
+        rules.extend(standard_rules.unwrap_or_default());
+
+        RulesStore {
+            storefile,
+            rules: Arc::new(RwLock::new(RuleSet::new(rules))),
+        }
+    }
+
+    pub(super) fn read(&self) -> RwLockReadGuard<'_, RuleSet> {
+        self.rules.read().expect("allowed hosts lock got poisoned")
+    }
+
+    /// Returns true if any of the deny rules matches the target.
+    pub(super) fn is_denied(&self, target: &RequestTarget, protocol: Protocol) -> bool {
+        self.read()
+            .deny
+            .iter()
+            .any(|rule| rule.matches(target, protocol))
+    }
+
+    /// Returns true if any of the allow rules matches the target.
+    pub(super) fn is_allowed(&self, target: &RequestTarget, protocol: Protocol) -> bool {
+        self.read()
+            .allow
+            .iter()
+            .any(|rule| rule.matches(target, protocol))
+    }
+
+    /// Reads the storefile again and atomically replaces the rules with its content
+    /// and the provided standard rules. If the storefile can't be read, the current rules
+    /// are left untouched.
+    pub(crate) fn reload(&self, standard_rules: &[HostRule]) -> io::Result<RuleSetChanges> {
+        let mut rules = Self::load_from_storefile(&self.storefile)?;
+        rules.extend_from_slice(standard_rules);
+        let new_rules = RuleSet::new(rules);
+
+        let mut guard = self.rules.write().expect("allowed hosts lock got poisoned");
+        let changes = guard.changes(&new_rules);
+        *guard = new_rules;
+        Ok(changes)
+    }
+
+    pub(super) fn parse_rules(content: &str) -> Vec<HostRule> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let line = line.split('#').next().unwrap_or_default().trim();
+                if line.is_empty() {
+                    return None;
+                }
+                match line.parse() {
+                    Ok(rule) => Some(rule),
+                    Err(err) => {
+                        log::warn!("Ignoring invalid allowed hosts rule {line:?}: {err}");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Loads the storefile rules into memory.
+    pub(super) fn load_from_storefile<P>(filename: P) -> io::Result<Vec<HostRule>>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::parse_rules(&fs::read_to_string(filename)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_invalid_lines_are_skipped() {
+        let rules = RulesStore::parse_rules(
+            "# some comment\n\nexample.com:443 # trailing comment\n!*.example.com:22\nfoo bar baz\n",
+        );
+        assert_eq!(rules.len(), 2);
+        assert!(!rules[0].deny);
+        assert!(rules[1].deny);
+    }
+
+    #[test]
+    fn rules_are_split_into_allow_and_deny() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RulesStore::new(
+            dir.path().to_path_buf(),
+            PathBuf::from("foomp-rules.db"),
+            Some(vec![
+                "nymtech.net".parse().unwrap(),
+                "!nymtech.net:22".parse().unwrap(),
+            ]),
+        );
+        assert_eq!(store.read().allow.len(), 1);
+        assert_eq!(store.read().deny.len(), 1);
+    }
+
+    #[test]
+    fn reloading_replaces_the_rules_of_all_clones() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RulesStore::new(
+            dir.path().to_path_buf(),
+            PathBuf::from("reloaded.list"),
+            None,
+        );
+        let clone = store.clone();
+        let standard: Vec<HostRule> = vec!["*.nymtech.net".parse().unwrap()];
+
+        fs::write(&store.storefile, "example.com\n!example.com:22\n").unwrap();
+        let changes = store.reload(&standard).unwrap();
+        assert_eq!(changes.added.len(), 3);
+        assert!(changes.removed.is_empty());
+
+        let target = RequestTarget::new_domain("example.com", Some(443));
+        assert!(clone.is_allowed(&target, Protocol::Tcp));
+
+        fs::write(&store.storefile, "!example.com:22\n").unwrap();
+        let changes = store.reload(&standard).unwrap();
+        assert!(changes.added.is_empty());
+        assert_eq!(changes.removed, vec!["example.com".parse().unwrap()]);
+        assert!(!clone.is_allowed(&target, Protocol::Tcp));
+
+        assert!(store.reload(&standard).unwrap().is_empty());
+    }
+}
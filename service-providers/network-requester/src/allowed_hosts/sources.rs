@@ -0,0 +1,115 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub(crate) const DEFAULT_STANDARD_ALLOWED_LIST_URL: &str =
+    "https://nymtech.net/.wellknown/network-requester/standard-allowed-list.txt";
+
+pub(crate) const DEFAULT_PUBLIC_SUFFIX_LIST_URL: &str =
+    "https://publicsuffix.org/list/public_suffix_list.dat";
+
+const STANDARD_ALLOWED_LIST_CACHE: &str = "standard-allowed-list.txt";
+const PUBLIC_SUFFIX_LIST_CACHE: &str = "public_suffix_list.dat";
+
+// so that a flaky connection wouldn't block the startup indefinitely
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Locations of the remote lists used by the requester alongside the directory where
+/// the last successfully fetched versions are cached.
+#[derive(Debug, Clone)]
+pub(crate) struct ListSources {
+    pub(crate) standard_list_url: String,
+    pub(crate) public_suffix_list_url: String,
+    pub(crate) cache_dir: PathBuf,
+}
+
+impl ListSources {
+    /// Creates new sources using the default urls for the ones that weren't provided.
+    /// The lists are cached within the `base_dir` next to the allowed and unknown hosts storefiles.
+    pub(crate) fn new(
+        base_dir: PathBuf,
+        standard_list_url: Option<String>,
+        public_suffix_list_url: Option<String>,
+    ) -> Self {
+        ListSources {
+            standard_list_url: standard_list_url
+                .unwrap_or_else(|| DEFAULT_STANDARD_ALLOWED_LIST_URL.to_string()),
+            public_suffix_list_url: public_suffix_list_url
+                .unwrap_or_else(|| DEFAULT_PUBLIC_SUFFIX_LIST_URL.to_string()),
+            cache_dir: base_dir
+                .join("service-providers")
+                .join("network-requester")
+                .join("cache"),
+        }
+    }
+
+    pub(super) fn standard_list_cache(&self) -> PathBuf {
+        self.cache_dir.join(STANDARD_ALLOWED_LIST_CACHE)
+    }
+
+    pub(super) fn public_suffix_list_cache(&self) -> PathBuf {
+        self.cache_dir.join(PUBLIC_SUFFIX_LIST_CACHE)
+    }
+}
+
+/// Fetches the content of the remote list.
+pub(super) async fn fetch_list(url: &str) -> Result<String, reqwest::Error> {
+    reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()?
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await
+}
+
+/// Reads the previously cached list, if there is any.
+pub(super) fn read_cached_list(path: &Path) -> Option<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(err) => {
+            log::warn!("Could not read the cached list at {path:?}: {err}");
+            None
+        }
+    }
+}
+
+/// Stores the list so that it could be used if it can't be fetched on the next startup.
+/// Failing to do so is not fatal, it's only logged.
+pub(super) fn cache_list(path: &Path, content: &str) {
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .transpose()
+        .and_then(|_| {
+            // write it in one go so that a crash couldn't leave us with a truncated list
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, content)?;
+            fs::rename(tmp_path, path)
+        });
+
+    if let Err(err) = result {
+        log::warn!("Could not cache the list at {path:?}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_lists_can_be_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = ListSources::new(dir.path().to_path_buf(), None, None);
+        let path = sources.standard_list_cache();
+
+        assert!(read_cached_list(&path).is_none());
+        cache_list(&path, "nymtech.net\n");
+        assert_eq!(read_cached_list(&path).unwrap(), "nymtech.net\n");
+    }
+}
@@ -1,20 +1,119 @@
-use crate::allowed_hosts::host::Host;
+use crate::allowed_hosts::rule::{HostRule, RuleParseError};
+use crate::allowed_hosts::sources::{cache_list, fetch_list, read_cached_list, ListSources};
+use crate::allowed_hosts::RulesStore;
 
-/// Fetch the standard allowed list from nymtech.net
-pub(crate) async fn fetch() -> Vec<Host> {
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum StandardListError {
+    #[error("failed to fetch the list: {0}")]
+    Fetch(#[from] reqwest::Error),
+
+    #[error("the list contains an invalid rule {line:?}: {source}")]
+    InvalidRule {
+        line: String,
+        source: RuleParseError,
+    },
+
+    #[error("the list doesn't contain any rules")]
+    Empty,
+}
+
+/// Fetch the standard allowed list and cache it for the subsequent startups.
+/// The list is only cached if all of its rules are valid, so that a broken response,
+/// such as an error page, wouldn't replace the last good version.
+pub(crate) async fn fetch(sources: &ListSources) -> Result<Vec<HostRule>, StandardListError> {
     log::info!("Refreshing standard allowed hosts");
-    get_standard_allowed_list()
-        .await
-        .split_whitespace()
-        .map(|s| Host::from(s.to_string()))
+    let content = fetch_list(&sources.standard_list_url).await?;
+    validate(&content)?;
+    cache_list(&sources.standard_list_cache(), &content);
+    Ok(parse(&content))
+}
+
+fn validate(list: &str) -> Result<(), StandardListError> {
+    let mut rules = 0;
+    for line in list.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Err(source) = line.parse::<HostRule>() {
+            return Err(StandardListError::InvalidRule {
+                line: line.to_string(),
+                source,
+            });
+        }
+        rules += 1;
+    }
+
+    if rules == 0 {
+        return Err(StandardListError::Empty);
+    }
+    Ok(())
+}
+
+/// Fetch the standard allowed list, falling back to the last successfully fetched version
+/// if it's not available. If there's none, the requester starts with no standard rules.
+pub(crate) async fn load(sources: &ListSources) -> Vec<HostRule> {
+    match fetch(sources).await {
+        Ok(rules) => rules,
+        Err(err) => {
+            log::warn!(
+                "Could not fetch the standard allowed list from {}: {err}",
+                sources.standard_list_url
+            );
+            let cache = sources.standard_list_cache();
+            match read_cached_list(&cache) {
+                Some(content) => {
+                    log::warn!("Using the previously cached standard allowed list from {cache:?}");
+                    parse(&content)
+                }
+                None => {
+                    log::warn!("Starting without the standard allowed list, it will be fetched again later");
+                    Vec::new()
+                }
+            }
+        }
+    }
+}
+
+fn parse(list: &str) -> Vec<HostRule> {
+    // the standard list is shared with older requesters that used to allow all subdomains of
+    // the listed domains, so keep on interpreting its entries that way
+    RulesStore::parse_rules(list)
+        .into_iter()
+        .map(HostRule::with_subdomains)
         .collect()
 }
 
-async fn get_standard_allowed_list() -> String {
-    reqwest::get("https://nymtech.net/.wellknown/network-requester/standard-allowed-list.txt")
-        .await
-        .expect("failed to get allowed hosts")
-        .text()
-        .await
-        .expect("failed to get allowed hosts text")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listed_domains_include_subdomains() {
+        let rules = parse("nymtech.net\n1.2.3.4\n");
+        assert_eq!(rules[0].to_string(), "*.nymtech.net");
+        assert_eq!(rules[1].to_string(), "1.2.3.4");
+    }
+
+    #[test]
+    fn valid_lists_pass_validation() {
+        assert!(validate("# standard list\nnymtech.net\n\n1.2.3.4 # comment\n").is_ok());
+    }
+
+    #[test]
+    fn lists_with_invalid_rules_are_rejected() {
+        assert!(matches!(
+            validate("nymtech.net\n<html><body>Not Found</body></html>\n"),
+            Err(StandardListError::InvalidRule { .. })
+        ));
+    }
+
+    #[test]
+    fn empty_lists_are_rejected() {
+        assert!(matches!(validate(""), Err(StandardListError::Empty)));
+        assert!(matches!(
+            validate("# nothing here\n\n"),
+            Err(StandardListError::Empty)
+        ));
+    }
 }
@@ -1,7 +1,9 @@
 // Copyright 2020 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: Apache-2.0
 use crate::allowed_hosts;
-use crate::allowed_hosts::OutboundRequestFilter;
+use crate::allowed_hosts::{
+    AllowedHostsReloader, OutboundRequestFilter, Protocol, ResolvedAddressFilter,
+};
 use crate::error::NetworkRequesterError;
 use crate::statistics::ServiceStatisticsCollector;
 use crate::websocket;
@@ -21,10 +23,11 @@ use proxy_helpers::connection_controller::{
 };
 use proxy_helpers::proxy_runner::{MixProxyReader, MixProxySender};
 use socks5_requests::{
-    ConnectRequest, ConnectionId, Message as Socks5Message, NetworkRequesterResponse, Request,
-    Response,
+    BindRequest, BindResponse, BindStatus, ConnectRequest, ConnectionId, DatagramRequest,
+    Message as Socks5Message, NetworkRequesterResponse, Request, Response,
 };
 use statistics_common::collector::StatisticsSender;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use task::TaskClient;
@@ -37,27 +40,33 @@ static ACTIVE_PROXIES: AtomicUsize = AtomicUsize::new(0);
 pub struct ServiceProvider {
     websocket_address: String,
     outbound_request_filter: OutboundRequestFilter,
-    open_proxy: bool,
+    allowed_hosts_reloader: Option<AllowedHostsReloader>,
     enable_statistics: bool,
     stats_provider_addr: Option<Recipient>,
+    udp_associations: HashMap<ConnectionId, socks5::udp::AssociationSender>,
+    return_address_updates: reply::ReturnAddressUpdates,
 }
 
 impl ServiceProvider {
     pub async fn new(
         websocket_address: String,
         open_proxy: bool,
+        allow_private_networks: bool,
+        list_sources: allowed_hosts::ListSources,
         enable_statistics: bool,
         stats_provider_addr: Option<Recipient>,
     ) -> ServiceProvider {
-        let standard_hosts = allowed_hosts::fetch_standard_allowed_list().await;
+        let standard_hosts = allowed_hosts::load_standard_allowed_list(&list_sources).await;
 
         log::info!("Standard allowed hosts: {:?}", standard_hosts);
 
-        let allowed_hosts = allowed_hosts::HostsStore::new(
+        let allowed_hosts = allowed_hosts::RulesStore::new(
             allowed_hosts::HostsStore::default_base_dir(),
             PathBuf::from("allowed.list"),
-            Some(standard_hosts),
+            Some(standard_hosts.clone()),
         );
+        let allowed_hosts_reloader =
+            AllowedHostsReloader::new(allowed_hosts.clone(), list_sources.clone(), standard_hosts);
 
         let unknown_hosts = allowed_hosts::HostsStore::new(
             allowed_hosts::HostsStore::default_base_dir(),
@@ -65,13 +74,23 @@ impl ServiceProvider {
             None,
         );
 
-        let outbound_request_filter = OutboundRequestFilter::new(allowed_hosts, unknown_hosts);
+        let root_domain_list = allowed_hosts::load_public_suffix_list(&list_sources).await;
+
+        let outbound_request_filter = OutboundRequestFilter::new(
+            allowed_hosts,
+            unknown_hosts,
+            root_domain_list,
+            !allow_private_networks,
+            open_proxy,
+        );
         ServiceProvider {
             websocket_address,
             outbound_request_filter,
-            open_proxy,
+            allowed_hosts_reloader: Some(allowed_hosts_reloader),
             enable_statistics,
             stats_provider_addr,
+            udp_associations: HashMap::new(),
+            return_address_updates: Default::default(),
         }
     }
 
@@ -82,6 +101,7 @@ impl ServiceProvider {
         mut mix_reader: MixProxyReader<(Socks5Message, reply::ReturnAddress)>,
         stats_collector: Option<ServiceStatisticsCollector>,
         mut client_connection_rx: ConnectionCommandReceiver,
+        return_address_updates: reply::ReturnAddressUpdates,
     ) {
         loop {
             tokio::select! {
@@ -104,6 +124,7 @@ impl ServiceProvider {
 
                         // make 'request' to native-websocket client
                         let conn_id = msg.conn_id();
+                        let return_address = return_address_updates.apply(conn_id, return_address);
                         let response_message = return_address.send_back_to(msg.into_bytes(), conn_id);
 
                         let message = Message::Binary(response_message.serialize());
@@ -116,6 +137,7 @@ impl ServiceProvider {
                 Some(command) = client_connection_rx.next() => {
                     match command {
                         ConnectionCommand::Close(id) => {
+                            return_address_updates.remove(id);
                             let msg = ClientRequest::ClosedConnection(id);
                             let ws_msg = Message::Binary(msg.serialize());
                             websocket_writer.send(ws_msg).await.unwrap();
@@ -198,19 +220,22 @@ impl ServiceProvider {
         None
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn start_proxy(
         conn_id: ConnectionId,
         remote_addr: String,
         return_address: reply::ReturnAddress,
+        address_filter: ResolvedAddressFilter,
         controller_sender: ControllerSender,
         mix_input_sender: MixProxySender<(Socks5Message, reply::ReturnAddress)>,
         lane_queue_lengths: LaneQueueLengths,
         shutdown: TaskClient,
     ) {
-        let mut conn = match socks5::tcp::Connection::new(
+        let conn = match socks5::tcp::Connection::new(
             conn_id,
             remote_addr.clone(),
             return_address.clone(),
+            &address_filter,
         )
         .await
         {
@@ -235,6 +260,26 @@ impl ServiceProvider {
             }
         };
 
+        Self::run_proxy_connection(
+            conn,
+            controller_sender,
+            mix_input_sender,
+            lane_queue_lengths,
+            shutdown,
+        )
+        .await
+    }
+
+    async fn run_proxy_connection(
+        mut conn: socks5::tcp::Connection,
+        controller_sender: ControllerSender,
+        mix_input_sender: MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        lane_queue_lengths: LaneQueueLengths,
+        shutdown: TaskClient,
+    ) {
+        let conn_id = conn.id();
+        let remote_addr = conn.address().clone();
+
         // Connect implies it's a fresh connection - register it with our controller
         let (mix_sender, mix_receiver) = mpsc::unbounded();
         controller_sender
@@ -289,7 +334,10 @@ impl ServiceProvider {
         let remote_addr = connect_req.remote_addr;
         let conn_id = connect_req.conn_id;
 
-        if !self.open_proxy && !self.outbound_request_filter.check(&remote_addr) {
+        if !self
+            .outbound_request_filter
+            .check(&remote_addr, Protocol::Tcp)
+        {
             let log_msg = format!("Domain {remote_addr:?} failed filter check");
             log::info!("{}", log_msg);
             mix_input_sender
@@ -304,6 +352,7 @@ impl ServiceProvider {
             return;
         }
 
+        let address_filter = self.outbound_request_filter.resolved_address_filter();
         let controller_sender_clone = controller_sender.clone();
         let mix_input_sender_clone = mix_input_sender.clone();
 
@@ -313,6 +362,7 @@ impl ServiceProvider {
                 conn_id,
                 remote_addr,
                 return_address,
+                address_filter,
                 controller_sender_clone,
                 mix_input_sender_clone,
                 lane_queue_lengths,
@@ -322,6 +372,276 @@ impl ServiceProvider {
         });
     }
 
+    #[allow(clippy::too_many_arguments)]
+    async fn start_bind_proxy(
+        conn_id: ConnectionId,
+        remote_addr: String,
+        return_address: reply::ReturnAddress,
+        address_filter: ResolvedAddressFilter,
+        controller_sender: ControllerSender,
+        mix_input_sender: MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        lane_queue_lengths: LaneQueueLengths,
+        shutdown: TaskClient,
+    ) {
+        let bind_status = |status| {
+            (
+                Socks5Message::BindResponse(BindResponse::new(conn_id, status)),
+                return_address.clone(),
+            )
+        };
+
+        let listener = match socks5::tcp::Listener::bind(&remote_addr, &address_filter).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::error!("error while binding socket for {remote_addr:?} ! - {err}");
+                mix_input_sender
+                    .send(bind_status(BindStatus::Failed(err.to_string())))
+                    .await
+                    .expect("InputMessageReceiver has stopped receiving!");
+                return;
+            }
+        };
+
+        let advertised_address = listener.advertised_address();
+        log::info!("Waiting for {remote_addr} to connect to {advertised_address}");
+        mix_input_sender
+            .send(bind_status(BindStatus::Listening(
+                advertised_address.to_string(),
+            )))
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+
+        let conn = match listener.accept(conn_id, return_address.clone()).await {
+            Ok(conn) => conn,
+            Err(err) => {
+                log::error!("error while waiting for {remote_addr:?} to connect ! - {err}");
+                mix_input_sender
+                    .send(bind_status(BindStatus::Failed(err.to_string())))
+                    .await
+                    .expect("InputMessageReceiver has stopped receiving!");
+                return;
+            }
+        };
+
+        mix_input_sender
+            .send(bind_status(BindStatus::Accepted(conn.address().clone())))
+            .await
+            .expect("InputMessageReceiver has stopped receiving!");
+
+        Self::run_proxy_connection(
+            conn,
+            controller_sender,
+            mix_input_sender,
+            lane_queue_lengths,
+            shutdown,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_proxy_bind(
+        &mut self,
+        controller_sender: &mut ControllerSender,
+        mix_input_sender: &MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        lane_queue_lengths: LaneQueueLengths,
+        sender_tag: Option<AnonymousSenderTag>,
+        bind_req: Box<BindRequest>,
+        shutdown: TaskClient,
+    ) {
+        let return_address = reply::ReturnAddress::new(bind_req.return_address, sender_tag);
+        let Some(return_address) = return_address else {
+            log::warn!(
+                "attempted to bind a socket with no way of returning data back to the sender"
+            );
+            return;
+        };
+
+        let remote_addr = bind_req.remote_addr;
+        let conn_id = bind_req.conn_id;
+
+        // the expected remote has to be allowed in the same way as if we were connecting to it
+        if !self
+            .outbound_request_filter
+            .check(&remote_addr, Protocol::Tcp)
+        {
+            let log_msg = format!("Domain {remote_addr:?} failed filter check");
+            log::info!("{}", log_msg);
+            mix_input_sender
+                .send((
+                    Socks5Message::BindResponse(BindResponse::new(
+                        conn_id,
+                        BindStatus::Failed(log_msg),
+                    )),
+                    return_address,
+                ))
+                .await
+                .expect("InputMessageReceiver has stopped receiving!");
+            return;
+        }
+
+        let address_filter = self.outbound_request_filter.resolved_address_filter();
+        let controller_sender_clone = controller_sender.clone();
+        let mix_input_sender_clone = mix_input_sender.clone();
+
+        tokio::spawn(async move {
+            Self::start_bind_proxy(
+                conn_id,
+                remote_addr,
+                return_address,
+                address_filter,
+                controller_sender_clone,
+                mix_input_sender_clone,
+                lane_queue_lengths,
+                shutdown,
+            )
+            .await
+        });
+    }
+
+    async fn start_association(
+        conn_id: ConnectionId,
+        remote_addr: String,
+        return_address: reply::ReturnAddress,
+        address_filter: ResolvedAddressFilter,
+        association_receiver: socks5::udp::AssociationReceiver,
+        mix_input_sender: MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        shutdown: TaskClient,
+    ) {
+        let association = match socks5::udp::Association::new(
+            conn_id,
+            return_address,
+            &remote_addr,
+            address_filter,
+        )
+        .await
+        {
+            Ok(association) => association,
+            Err(err) => {
+                log::error!("error while creating UDP association for {remote_addr:?} ! - {err}");
+                return;
+            }
+        };
+
+        log::info!("Starting UDP association {conn_id}");
+        association
+            .run(association_receiver, mix_input_sender, shutdown)
+            .await;
+        log::info!("UDP association {conn_id} is finished");
+    }
+
+    async fn handle_proxy_datagram(
+        &mut self,
+        mix_input_sender: &MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        sender_tag: Option<AnonymousSenderTag>,
+        datagram_req: Box<DatagramRequest>,
+        shutdown: TaskClient,
+    ) {
+        let DatagramRequest {
+            conn_id,
+            remote_addr,
+            return_address,
+            data,
+        } = *datagram_req;
+
+        // every single datagram has to pass the same filter as the tcp connections do
+        if !self
+            .outbound_request_filter
+            .check(&remote_addr, Protocol::Udp)
+        {
+            let log_msg = format!("Domain {remote_addr:?} failed filter check");
+            log::info!("{}", log_msg);
+            if let Some(return_address) = reply::ReturnAddress::new(return_address, sender_tag) {
+                mix_input_sender
+                    .send((
+                        Socks5Message::NetworkRequesterResponse(NetworkRequesterResponse::new(
+                            conn_id, log_msg,
+                        )),
+                        return_address,
+                    ))
+                    .await
+                    .expect("InputMessageReceiver has stopped receiving!");
+            }
+            return;
+        }
+
+        let (remote_addr, data) = match self.udp_associations.get(&conn_id) {
+            Some(association_sender) => {
+                // the association keeps the address it has been created with,
+                // so make sure the responses follow the one attached to the latest datagram
+                if let Some(return_address) = return_address {
+                    self.return_address_updates.update(conn_id, return_address);
+                }
+                match association_sender.unbounded_send((remote_addr, data)) {
+                    Ok(_) => return,
+                    // the association must have expired - we'll try to create a fresh one
+                    Err(err) => err.into_inner(),
+                }
+            }
+            None => (remote_addr, data),
+        };
+
+        self.create_association(
+            mix_input_sender,
+            sender_tag,
+            conn_id,
+            remote_addr,
+            return_address,
+            data,
+            shutdown,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_association(
+        &mut self,
+        mix_input_sender: &MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        sender_tag: Option<AnonymousSenderTag>,
+        conn_id: ConnectionId,
+        remote_addr: String,
+        return_address: Option<Recipient>,
+        data: Vec<u8>,
+        shutdown: TaskClient,
+    ) {
+        let Some(return_address) = reply::ReturnAddress::new(return_address, sender_tag) else {
+            log::warn!(
+                "attempted to start UDP association with no way of returning data back to the sender"
+            );
+            return;
+        };
+
+        // clean up any associations that are no longer running
+        let return_address_updates = &self.return_address_updates;
+        self.udp_associations.retain(|conn_id, sender| {
+            let running = !sender.is_closed();
+            if !running {
+                return_address_updates.remove(*conn_id);
+            }
+            running
+        });
+
+        let (association_sender, association_receiver) = mpsc::unbounded();
+        association_sender
+            .unbounded_send((remote_addr.clone(), data))
+            .expect("the receiver has just been created");
+        self.udp_associations.insert(conn_id, association_sender);
+
+        let address_filter = self.outbound_request_filter.resolved_address_filter();
+        let mix_input_sender_clone = mix_input_sender.clone();
+        tokio::spawn(async move {
+            Self::start_association(
+                conn_id,
+                remote_addr,
+                return_address,
+                address_filter,
+                association_receiver,
+                mix_input_sender_clone,
+                shutdown,
+            )
+            .await
+        });
+    }
+
     fn handle_proxy_send(
         controller_sender: &mut ControllerSender,
         conn_id: ConnectionId,
@@ -388,8 +708,47 @@ impl ServiceProvider {
                     }
                     Self::handle_proxy_send(controller_sender, conn_id, data, closed)
                 }
+
+                Request::Datagram(req) => {
+                    if let Some(stats_collector) = stats_collector {
+                        stats_collector
+                            .request_stats_data
+                            .write()
+                            .await
+                            .processed(&req.remote_addr, req.data.len() as u32);
+                    }
+                    self.handle_proxy_datagram(mix_input_sender, message.sender_tag, req, shutdown)
+                        .await
+                }
+
+                Request::Bind(req) => {
+                    if let Some(stats_collector) = stats_collector {
+                        stats_collector
+                            .connected_services
+                            .write()
+                            .await
+                            .insert(req.conn_id, req.remote_addr.clone());
+                    }
+                    self.handle_proxy_bind(
+                        controller_sender,
+                        mix_input_sender,
+                        lane_queue_lengths,
+                        message.sender_tag,
+                        req,
+                        shutdown,
+                    )
+                    .await
+                }
+
+                Request::ReturnAddressUpdate(conn_id, return_address) => {
+                    log::debug!("Connection {conn_id} is going to use a new return address");
+                    self.return_address_updates.update(conn_id, *return_address)
+                }
             },
-            Socks5Message::Response(_) | Socks5Message::NetworkRequesterResponse(_) => {}
+            Socks5Message::Response(_)
+            | Socks5Message::NetworkRequesterResponse(_)
+            | Socks5Message::DatagramResponse(_)
+            | Socks5Message::BindResponse(_) => {}
         }
     }
 
@@ -445,7 +804,13 @@ impl ServiceProvider {
             None
         };
 
+        // keep the allowed hosts up to date without having to restart the requester
+        if let Some(allowed_hosts_reloader) = self.allowed_hosts_reloader.take() {
+            allowed_hosts_reloader.start(stats_collector.clone(), shutdown.subscribe());
+        }
+
         let stats_collector_clone = stats_collector.clone();
+        let return_address_updates = self.return_address_updates.clone();
         // start the listener for mix messages
         tokio::spawn(async move {
             Self::mixnet_response_listener(
@@ -453,6 +818,7 @@ impl ServiceProvider {
                 mix_input_receiver,
                 stats_collector_clone,
                 client_connection_rx,
+                return_address_updates,
             )
             .await;
         });
@@ -461,10 +827,10 @@ impl ServiceProvider {
         // for each incoming message from the websocket... (which in 99.99% cases is going to be a mix message)
         loop {
             let Some(received) = Self::read_websocket_message(
-                    &mut websocket_reader,
-                    shared_lane_queue_lengths.clone()
-                )
-                .await
+                &mut websocket_reader,
+                shared_lane_queue_lengths.clone(),
+            )
+            .await
             else {
                 log::error!("The websocket stream has finished!");
                 return Err(NetworkRequesterError::ConnectionClosed);
@@ -22,10 +22,24 @@ const ENABLE_STATISTICS: &str = "enable-statistics";
 
 #[derive(Args)]
 struct Run {
-    /// Specifies whether this network requester should run in 'open-proxy' mode
+    /// Specifies whether this network requester should run in 'open-proxy' mode.
+    /// Note that the deny rules and the private network restrictions still apply
     #[clap(long)]
     open_proxy: bool,
 
+    /// Allows requests to private, loopback and link-local addresses, which are otherwise
+    /// always denied, even if they're present in the allowed.list or in 'open-proxy' mode
+    #[clap(long)]
+    allow_private_networks: bool,
+
+    /// Url of the standard allowed list shared by all network requesters
+    #[clap(long)]
+    standard_list_url: Option<String>,
+
+    /// Url of the public suffix list used for parsing the requested domains
+    #[clap(long)]
+    public_suffix_list_url: Option<String>,
+
     /// Websocket port to bind to.
     #[clap(long)]
     websocket_port: Option<String>,
@@ -63,10 +77,18 @@ impl Run {
                 .unwrap_or(&network_defaults::DEFAULT_WEBSOCKET_LISTENING_PORT.to_string())
         );
 
+        let list_sources = allowed_hosts::ListSources::new(
+            allowed_hosts::HostsStore::default_base_dir(),
+            self.standard_list_url.clone(),
+            self.public_suffix_list_url.clone(),
+        );
+
         log::info!("Starting socks5 service provider");
         let mut server = core::ServiceProvider::new(
             websocket_address,
             self.open_proxy,
+            self.allow_private_networks,
+            list_sources,
             self.enable_statistics,
             stats_provider_addr,
         )
@@ -1,5 +1,8 @@
 use nymsphinx::addressing::clients::Recipient;
 use nymsphinx::anonymous_replies::requests::AnonymousSenderTag;
+use socks5_requests::ConnectionId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use websocket_requests::requests::ClientRequest;
 
 /// A return address is a way to send a message back to the original sender. It can be either
@@ -51,3 +54,90 @@ impl From<AnonymousSenderTag> for ReturnAddress {
         ReturnAddress::Anonymous(sender_tag)
     }
 }
+
+/// New return addresses announced by the clients for their ongoing connections, i.e. after they
+/// have switched to a different gateway. They take precedence over the addresses the connections
+/// have been started with.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReturnAddressUpdates {
+    inner: Arc<Mutex<HashMap<ConnectionId, Recipient>>>,
+}
+
+impl ReturnAddressUpdates {
+    pub(crate) fn update(&self, connection_id: ConnectionId, return_address: Recipient) {
+        self.inner
+            .lock()
+            .expect("return address updates lock got poisoned")
+            .insert(connection_id, return_address);
+    }
+
+    pub(crate) fn remove(&self, connection_id: ConnectionId) {
+        self.inner
+            .lock()
+            .expect("return address updates lock got poisoned")
+            .remove(&connection_id);
+    }
+
+    /// Replaces the explicitly known return address of the connection with the most recent one.
+    /// Anonymous return addresses are left untouched since the reply surbs are replaced instead.
+    pub(crate) fn apply(
+        &self,
+        connection_id: ConnectionId,
+        return_address: ReturnAddress,
+    ) -> ReturnAddress {
+        match return_address {
+            ReturnAddress::Known(_) => match self
+                .inner
+                .lock()
+                .expect("return address updates lock got poisoned")
+                .get(&connection_id)
+            {
+                Some(updated) => (*updated).into(),
+                None => return_address,
+            },
+            ReturnAddress::Anonymous(_) => return_address,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updated_return_address_replaces_known_one() {
+        let original = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let updated = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV").unwrap();
+        let updates = ReturnAddressUpdates::default();
+        updates.update(42, updated);
+
+        match updates.apply(42, original.into()) {
+            ReturnAddress::Known(recipient) => assert_eq!(*recipient, updated),
+            ReturnAddress::Anonymous(_) => unreachable!(),
+        }
+        // other connections are not affected
+        match updates.apply(123, original.into()) {
+            ReturnAddress::Known(recipient) => assert_eq!(*recipient, original),
+            ReturnAddress::Anonymous(_) => unreachable!(),
+        }
+
+        updates.remove(42);
+        match updates.apply(42, original.into()) {
+            ReturnAddress::Known(recipient) => assert_eq!(*recipient, original),
+            ReturnAddress::Anonymous(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn anonymous_return_address_is_not_replaced() {
+        let updated = Recipient::try_from_base58_string("CytBseW6yFXUMzz4SGAKdNLGR7q3sJLLYxyBGvutNEQV.4QXYyEVc5fUDjmmi8PrHN9tdUFV4PCvSJE1278cHyvoe@4sBbL1ngf1vtNqykydQKTFh26sQCw888GpUqvPvyNB4f").unwrap();
+        let sender_tag = AnonymousSenderTag::from_bytes([1u8; 16]);
+        let updates = ReturnAddressUpdates::default();
+        updates.update(42, updated);
+
+        match updates.apply(42, sender_tag.into()) {
+            ReturnAddress::Anonymous(tag) => assert_eq!(tag, sender_tag),
+            ReturnAddress::Known(_) => unreachable!(),
+        }
+    }
+}
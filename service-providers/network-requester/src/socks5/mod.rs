@@ -1 +1,2 @@
 pub(super) mod tcp;
+pub(super) mod udp;
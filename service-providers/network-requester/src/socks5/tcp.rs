@@ -6,9 +6,12 @@ use proxy_helpers::connection_controller::ConnectionReceiver;
 use proxy_helpers::proxy_runner::{MixProxySender, ProxyRunner};
 use socks5_requests::{ConnectionId, Message as Socks5Message, RemoteAddress, Response};
 use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
 use task::TaskClient;
-use tokio::net::TcpStream;
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
 
+use crate::allowed_hosts::{Protocol, ResolvedAddressFilter};
 use crate::reply;
 
 /// An outbound TCP connection between the Socks5 service provider, which makes
@@ -27,8 +30,12 @@ impl Connection {
         id: ConnectionId,
         address: RemoteAddress,
         return_address: reply::ReturnAddress,
+        address_filter: &ResolvedAddressFilter,
     ) -> io::Result<Self> {
-        let conn = TcpStream::connect(&address).await?;
+        // connect to the already checked addresses so that the host could not be re-resolved
+        // to something else in the meantime
+        let resolved = address_filter.resolve(&address, Protocol::Tcp).await?;
+        let conn = TcpStream::connect(&resolved[..]).await?;
 
         Ok(Connection {
             id,
@@ -38,6 +45,14 @@ impl Connection {
         })
     }
 
+    pub(crate) fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    pub(crate) fn address(&self) -> &RemoteAddress {
+        &self.address
+    }
+
     pub(crate) async fn run_proxy(
         &mut self,
         mix_receiver: ConnectionReceiver,
@@ -70,3 +85,87 @@ impl Connection {
         self.conn = Some(stream);
     }
 }
+
+/// How long we're willing to wait for the remote to connect to the socket bound via `Bind` request.
+const BIND_ACCEPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A listening TCP socket created as a result of `Bind` request. It accepts a single
+/// inbound connection from the expected remote and turns it into a regular [`Connection`].
+#[derive(Debug)]
+pub(crate) struct Listener {
+    listener: TcpListener,
+    expected_peer: IpAddr,
+    advertised_address: SocketAddr,
+}
+
+impl Listener {
+    pub(crate) async fn bind(
+        expected_remote: &RemoteAddress,
+        address_filter: &ResolvedAddressFilter,
+    ) -> io::Result<Self> {
+        let expected_remote = address_filter
+            .resolve(expected_remote, Protocol::Tcp)
+            .await?[0];
+
+        let bind_ip: IpAddr = if expected_remote.is_ipv4() {
+            Ipv4Addr::UNSPECIFIED.into()
+        } else {
+            Ipv6Addr::UNSPECIFIED.into()
+        };
+        let listener = TcpListener::bind((bind_ip, 0)).await?;
+        let port = listener.local_addr()?.port();
+
+        // rather than advertising an unspecified address, figure out which local address
+        // would have been used for reaching the expected remote
+        let advertised_ip = if expected_remote.ip().is_unspecified() {
+            bind_ip
+        } else {
+            let probe = UdpSocket::bind((bind_ip, 0)).await?;
+            probe.connect(expected_remote).await?;
+            probe.local_addr()?.ip()
+        };
+
+        Ok(Listener {
+            listener,
+            expected_peer: expected_remote.ip(),
+            advertised_address: SocketAddr::new(advertised_ip, port),
+        })
+    }
+
+    pub(crate) fn advertised_address(&self) -> SocketAddr {
+        self.advertised_address
+    }
+
+    /// Waits for the expected remote to connect. Connections from any other hosts are rejected.
+    pub(crate) async fn accept(
+        self,
+        id: ConnectionId,
+        return_address: reply::ReturnAddress,
+    ) -> io::Result<Connection> {
+        let accept_expected = async {
+            loop {
+                let (stream, peer) = self.listener.accept().await?;
+                if self.expected_peer.is_unspecified() || peer.ip() == self.expected_peer {
+                    return Ok::<_, io::Error>((stream, peer));
+                }
+                log::warn!("Rejecting unexpected inbound connection from {peer}");
+            }
+        };
+
+        let (stream, peer) = tokio::time::timeout(BIND_ACCEPT_TIMEOUT, accept_expected)
+            .await
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "no inbound connection arrived in time",
+                )
+            })??;
+
+        Ok(Connection {
+            id,
+            address: peer.to_string(),
+            conn: Some(stream),
+            return_address,
+        })
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use proxy_helpers::proxy_runner::MixProxySender;
+use socks5_requests::{ConnectionId, DatagramResponse, Message as Socks5Message, RemoteAddress};
+use std::collections::HashSet;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use task::TaskClient;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+use crate::allowed_hosts::{Protocol, ResolvedAddressFilter};
+use crate::reply;
+
+/// If no datagram is sent nor received within this duration, the association is torn down.
+const ASSOCIATION_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Maximum size of a datagram we're willing to receive from a remote.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Channel used for passing datagrams received from the mix network to particular association.
+pub(crate) type AssociationSender = mpsc::UnboundedSender<(RemoteAddress, Vec<u8>)>;
+pub(crate) type AssociationReceiver = mpsc::UnboundedReceiver<(RemoteAddress, Vec<u8>)>;
+
+/// An outbound UDP association between the Socks5 service provider and any number of remotes.
+/// Datagrams are relayed in both directions as they come, without any ordering or acknowledgements.
+/// Only datagrams coming from remotes that we have explicitly sent data to are relayed back.
+#[derive(Debug)]
+pub(crate) struct Association {
+    id: ConnectionId,
+
+    // remotes of different address families can't be reached from the same socket,
+    // so we keep a (lazily bound) socket for each of them
+    socket_v4: Option<UdpSocket>,
+    socket_v6: Option<UdpSocket>,
+    return_address: reply::ReturnAddress,
+    known_peers: HashSet<SocketAddr>,
+    address_filter: ResolvedAddressFilter,
+}
+
+impl Association {
+    pub(crate) async fn new(
+        id: ConnectionId,
+        return_address: reply::ReturnAddress,
+        first_remote: &RemoteAddress,
+        address_filter: ResolvedAddressFilter,
+    ) -> io::Result<Self> {
+        let mut association = Association {
+            id,
+            socket_v4: None,
+            socket_v6: None,
+            return_address,
+            known_peers: HashSet::new(),
+            address_filter,
+        };
+
+        // bind the socket for the first remote immediately so that any failure is reported
+        // back straight away
+        let first_remote = association.resolve(first_remote).await?;
+        association.socket_for(&first_remote).await?;
+
+        Ok(association)
+    }
+
+    async fn resolve(&self, remote: &RemoteAddress) -> io::Result<SocketAddr> {
+        Ok(self.address_filter.resolve(remote, Protocol::Udp).await?[0])
+    }
+
+    async fn socket_for(&mut self, remote: &SocketAddr) -> io::Result<&UdpSocket> {
+        let (socket, bind_address) = if remote.is_ipv4() {
+            (&mut self.socket_v4, "0.0.0.0:0")
+        } else {
+            (&mut self.socket_v6, "[::]:0")
+        };
+
+        if socket.is_none() {
+            *socket = Some(UdpSocket::bind(bind_address).await?);
+        }
+        Ok(socket.as_ref().expect("the socket has just been bound"))
+    }
+
+    async fn send_datagram(&mut self, remote: RemoteAddress, data: Vec<u8>) -> io::Result<()> {
+        let remote = self.resolve(&remote).await?;
+        self.socket_for(&remote)
+            .await?
+            .send_to(&data, remote)
+            .await?;
+        self.known_peers.insert(remote);
+        Ok(())
+    }
+
+    async fn relay_inbound(
+        &self,
+        received: io::Result<(usize, SocketAddr)>,
+        buf: &[u8],
+        mix_sender: &MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+    ) -> Result<(), ChannelClosed> {
+        let id = self.id;
+        let (n, source) = match received {
+            Ok(received) => received,
+            Err(err) => {
+                log::debug!("UDP association {id}: failed to receive datagram: {err}");
+                return Ok(());
+            }
+        };
+        if !self.known_peers.contains(&source) {
+            log::debug!("UDP association {id}: dropping datagram from unknown remote {source}");
+            return Ok(());
+        }
+
+        let response = DatagramResponse::new(id, source.to_string(), buf[..n].to_vec());
+        let return_address = self.return_address.clone();
+        mix_sender
+            .send((Socks5Message::DatagramResponse(response), return_address))
+            .await
+            .map_err(|_| ChannelClosed)
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        mut mix_receiver: AssociationReceiver,
+        mix_sender: MixProxySender<(Socks5Message, reply::ReturnAddress)>,
+        mut shutdown: TaskClient,
+    ) {
+        let id = self.id;
+        let mut buf_v4 = vec![0u8; MAX_DATAGRAM_SIZE];
+        let mut buf_v6 = vec![0u8; MAX_DATAGRAM_SIZE];
+        let idle_timeout = tokio::time::sleep(ASSOCIATION_IDLE_TIMEOUT);
+        tokio::pin!(idle_timeout);
+
+        loop {
+            tokio::select! {
+                outbound = mix_receiver.next() => {
+                    let Some((remote, data)) = outbound else {
+                        log::trace!("UDP association {id}: channel closed");
+                        break;
+                    };
+                    if let Err(err) = self.send_datagram(remote.clone(), data).await {
+                        log::debug!("UDP association {id}: failed to send datagram to {remote}: {err}");
+                    }
+                    idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+                }
+                inbound = recv_from(&self.socket_v4, &mut buf_v4) => {
+                    if self.relay_inbound(inbound, &buf_v4, &mix_sender).await.is_err() {
+                        log::error!("InputMessageReceiver has stopped receiving!");
+                        break;
+                    }
+                    idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+                }
+                inbound = recv_from(&self.socket_v6, &mut buf_v6) => {
+                    if self.relay_inbound(inbound, &buf_v6, &mix_sender).await.is_err() {
+                        log::error!("InputMessageReceiver has stopped receiving!");
+                        break;
+                    }
+                    idle_timeout.as_mut().reset(Instant::now() + ASSOCIATION_IDLE_TIMEOUT);
+                }
+                _ = &mut idle_timeout => {
+                    log::debug!("UDP association {id} has been idle for too long");
+                    break;
+                }
+                _ = shutdown.recv() => {
+                    log::trace!("UDP association {id}: received shutdown");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+struct ChannelClosed;
+
+// receives a datagram on the socket, if it has been bound, otherwise waits forever
+async fn recv_from(socket: &Option<UdpSocket>, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+    match socket {
+        Some(socket) => socket.recv_from(buf).await,
+        None => std::future::pending().await,
+    }
+}
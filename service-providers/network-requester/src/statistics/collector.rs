@@ -15,8 +15,8 @@ use statistics_common::api::{
     DEFAULT_STATISTICS_SERVICE_PORT,
 };
 use statistics_common::{
-    collector::StatisticsCollector, error::StatsError as CommonStatsError, StatsMessage,
-    StatsServiceData,
+    collector::StatisticsCollector, error::StatsError as CommonStatsError,
+    StatsAllowedHostsReloadData, StatsMessage, StatsServiceData,
 };
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -77,6 +77,7 @@ pub struct ServiceStatisticsCollector {
     pub(crate) request_stats_data: Arc<RwLock<StatsData>>,
     pub(crate) response_stats_data: Arc<RwLock<StatsData>>,
     pub(crate) connected_services: Arc<RwLock<HashMap<ConnectionId, RemoteAddress>>>,
+    pub(crate) allowed_hosts_reloads: Arc<RwLock<Vec<StatsAllowedHostsReloadData>>>,
     stats_provider_addr: Recipient,
     mix_input_sender: MixProxySender<(Socks5Message, reply::ReturnAddress)>,
 }
@@ -108,6 +109,7 @@ impl ServiceStatisticsCollector {
             request_stats_data: Arc::new(RwLock::new(StatsData::new())),
             response_stats_data: Arc::new(RwLock::new(StatsData::new())),
             connected_services: Arc::new(RwLock::new(HashMap::new())),
+            allowed_hosts_reloads: Arc::new(RwLock::new(Vec::new())),
             stats_provider_addr,
             mix_input_sender,
         })
@@ -121,7 +123,7 @@ impl StatisticsCollector for ServiceStatisticsCollector {
         interval: Duration,
         timestamp: DateTime<Utc>,
     ) -> StatsMessage {
-        let stats_data = {
+        let mut stats_data: Vec<_> = {
             let request_data_bytes = self.request_stats_data.read().await;
             let response_data_bytes = self.response_stats_data.read().await;
             let services: HashSet<String> = request_data_bytes
@@ -151,6 +153,14 @@ impl StatisticsCollector for ServiceStatisticsCollector {
                 })
                 .collect()
         };
+        stats_data.extend(
+            self.allowed_hosts_reloads
+                .read()
+                .await
+                .iter()
+                .cloned()
+                .map(statistics_common::StatsData::AllowedHostsReload),
+        );
 
         StatsMessage {
             stats_data,
@@ -202,5 +212,6 @@ impl StatisticsCollector for ServiceStatisticsCollector {
             .write()
             .await
             .client_processed_bytes = HashMap::new();
+        self.allowed_hosts_reloads.write().await.clear();
     }
 }
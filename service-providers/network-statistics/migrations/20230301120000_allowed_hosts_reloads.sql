@@ -0,0 +1,13 @@
+/*
+ * Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+CREATE TABLE allowed_hosts_reloads
+(
+    id                         INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    reason                     VARCHAR NOT NULL,
+    added_rules                VARCHAR NOT NULL,
+    removed_rules              VARCHAR NOT NULL,
+    timestamp                  DATETIME NOT NULL
+);
@@ -68,6 +68,34 @@ impl StorageManager {
         Ok(())
     }
 
+    /// Adds an entry for a reload of the allowed hosts of a network requester.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason`: What has triggered the reload.
+    /// * `added_rules`: Comma-separated rules that got added.
+    /// * `removed_rules`: Comma-separated rules that got removed.
+    /// * `timestamp`: The moment in time when the data started being collected.
+    pub(super) async fn insert_allowed_hosts_reload(
+        &self,
+        reason: String,
+        added_rules: String,
+        removed_rules: String,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO allowed_hosts_reloads(reason, added_rules, removed_rules, timestamp) VALUES (?, ?, ?, ?)",
+            reason,
+            added_rules,
+            removed_rules,
+            timestamp,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Returns service statistical data submitted within the provided time interval.
     ///
     /// # Arguments
@@ -78,6 +78,16 @@ impl NetworkStatisticsStorage {
                         )
                         .await?
                 }
+                statistics_common::StatsData::AllowedHostsReload(reload_data) => {
+                    self.manager
+                        .insert_allowed_hosts_reload(
+                            reload_data.reason,
+                            reload_data.added_rules.join(", "),
+                            reload_data.removed_rules.join(", "),
+                            timestamp,
+                        )
+                        .await?
+                }
             }
         }
 
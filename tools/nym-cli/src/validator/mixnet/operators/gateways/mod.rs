@@ -4,6 +4,8 @@
 use network_defaults::NymNetworkDetails;
 use nym_cli_commands::context::{create_signing_client, ClientArgs};
 
+pub(crate) mod settings;
+
 pub(crate) async fn execute(
     global_args: ClientArgs,
     gateway: nym_cli_commands::validator::mixnet::operators::gateway::MixnetOperatorsGateway,
@@ -16,6 +18,9 @@ pub(crate) async fn execute(
         nym_cli_commands::validator::mixnet::operators::gateway::MixnetOperatorsGatewayCommands::Unbound(_args) => {
             nym_cli_commands::validator::mixnet::operators::gateway::unbond_gateway::unbond_gateway(create_signing_client(global_args, network_details)?).await
         },
+        nym_cli_commands::validator::mixnet::operators::gateway::MixnetOperatorsGatewayCommands::Settings(settings) => {
+            settings::execute(global_args, settings, network_details).await?
+        },
         _ => unreachable!(),
     }
     Ok(())
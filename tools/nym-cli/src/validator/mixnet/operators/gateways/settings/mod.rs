@@ -0,0 +1,27 @@
+// Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: Apache-2.0
+
+use network_defaults::NymNetworkDetails;
+use nym_cli_commands::context::{create_signing_client, ClientArgs};
+
+pub(crate) async fn execute(
+    global_args: ClientArgs,
+    settings: nym_cli_commands::validator::mixnet::operators::gateway::settings::MixnetOperatorsGatewaySettings,
+    network_details: &NymNetworkDetails,
+) -> anyhow::Result<()> {
+    match settings.command {
+        nym_cli_commands::validator::mixnet::operators::gateway::settings::MixnetOperatorsGatewaySettingsCommands::UpdateConfig(args) => {
+            nym_cli_commands::validator::mixnet::operators::gateway::settings::update_config::update_config(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::operators::gateway::settings::MixnetOperatorsGatewaySettingsCommands::VestingUpdateConfig(args) => {
+            nym_cli_commands::validator::mixnet::operators::gateway::settings::vesting_update_config::vesting_update_config(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::operators::gateway::settings::MixnetOperatorsGatewaySettingsCommands::UpdateKeys(args) => {
+            nym_cli_commands::validator::mixnet::operators::gateway::settings::update_keys::update_keys(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::operators::gateway::settings::MixnetOperatorsGatewaySettingsCommands::VestingUpdateKeys(args) => {
+            nym_cli_commands::validator::mixnet::operators::gateway::settings::vesting_update_keys::vesting_update_keys(args, create_signing_client(global_args, network_details)?).await
+        }
+    }
+    Ok(())
+}
@@ -13,6 +13,12 @@ pub(crate) async fn execute(
         nym_cli_commands::validator::mixnet::operators::mixnode::settings::MixnetOperatorsMixnodeSettingsCommands::UpdateConfig(args) => {
             nym_cli_commands::validator::mixnet::operators::mixnode::settings::update_config::update_config(args, create_signing_client(global_args, network_details)?).await
         }
+        nym_cli_commands::validator::mixnet::operators::mixnode::settings::MixnetOperatorsMixnodeSettingsCommands::UpdateKeys(args) => {
+            nym_cli_commands::validator::mixnet::operators::mixnode::settings::update_keys::update_keys(args, create_signing_client(global_args, network_details)?).await
+        }
+        nym_cli_commands::validator::mixnet::operators::mixnode::settings::MixnetOperatorsMixnodeSettingsCommands::VestingUpdateKeys(args) => {
+            nym_cli_commands::validator::mixnet::operators::mixnode::settings::vesting_update_keys::vesting_update_keys(args, create_signing_client(global_args, network_details)?).await
+        }
         _ => unreachable!(),
     }
     Ok(())